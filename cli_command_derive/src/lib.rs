@@ -16,6 +16,8 @@ struct FieldOpts {
     greedy: Option<bool>,
     nargs: Option<usize>,
     value_source: Option<bool>,
+    raw: Option<bool>,
+    positional: Option<usize>,
     autocomplete: Option<Path>,
 }
 
@@ -61,6 +63,8 @@ pub fn derive_command_args(input: TokenStream) -> TokenStream {
         let greedy = opts.greedy.map(|g| quote! { #g }).unwrap_or(quote! { false });
         let nargs = opts.nargs.map(|n| quote! { Some(#n) }).unwrap_or(quote! { None });
         let value_source = opts.value_source.map(|v| quote! { #v }).unwrap_or(quote! { false });
+        let raw = opts.raw.map(|r| quote! { #r }).unwrap_or(quote! { false });
+        let positional = opts.positional.map(|p| quote! { Some(#p) }).unwrap_or(quote! { None });
 
         let autocomplete_fn = opts.autocomplete.as_ref().map(|fn_path| {
             quote! { Some(#fn_path as fn(&crate::services::CompletionContext, &str, &[String]) -> Vec<String>) }
@@ -80,6 +84,8 @@ pub fn derive_command_args(input: TokenStream) -> TokenStream {
                 nargs: #nargs,
                 repeatable: #repeatable,
                 value_source: #value_source,
+                raw: #raw,
+                positional: #positional,
                 autocomplete: #autocomplete_fn,
             }
         }
@@ -0,0 +1,278 @@
+//! An in-process fake Hubuum server for driving clients end to end in tests.
+//!
+//! [`FakeServer::start`] listens on a free local port and answers the login, token
+//! validation, and CRUD endpoints of collections, classes, objects, groups, and users
+//! from an in-memory store. Seed the store with the `add_*` helpers or a fixture file
+//! through [`FakeServer::seed`], pin a canned response to a route with
+//! [`FakeServer::respond`], and assert on [`FakeServer::requests`] afterwards.
+
+mod http;
+mod routes;
+mod store;
+
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use serde_json::{json, Value};
+
+pub use http::{FakeResponse, RecordedRequest};
+pub use store::Resource;
+
+use store::Store;
+
+/// The username the server accepts unless [`FakeServer::with_credentials`] changes it.
+pub const USERNAME: &str = "admin";
+/// The password the server accepts unless [`FakeServer::with_credentials`] changes it.
+pub const PASSWORD: &str = "secret";
+
+#[derive(Debug)]
+pub(crate) struct State {
+    pub(crate) store: Store,
+    pub(crate) username: String,
+    pub(crate) password: String,
+    pub(crate) tokens: Vec<String>,
+    pub(crate) issued_tokens: usize,
+    pub(crate) fixtures: Vec<(String, String, FakeResponse)>,
+    pub(crate) requests: Vec<RecordedRequest>,
+}
+
+impl State {
+    /// A new token, numbered in the order tokens are issued: `fake-token-1`, ...
+    pub(crate) fn issue_token(&mut self) -> String {
+        self.issued_tokens += 1;
+        let token = format!("fake-token-{}", self.issued_tokens);
+        self.tokens.push(token.clone());
+        token
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            store: Store::default(),
+            username: USERNAME.to_string(),
+            password: PASSWORD.to_string(),
+            tokens: Vec::new(),
+            issued_tokens: 0,
+            fixtures: Vec::new(),
+            requests: Vec::new(),
+        }
+    }
+}
+
+/// A running fake server; it stops when dropped.
+pub struct FakeServer {
+    address: SocketAddr,
+    state: Arc<Mutex<State>>,
+    stopping: Arc<AtomicBool>,
+    listener: Option<JoinHandle<()>>,
+}
+
+impl FakeServer {
+    /// Starts a server on a free port of `127.0.0.1`.
+    pub fn start() -> io::Result<Self> {
+        Self::bind("127.0.0.1:0")
+    }
+
+    /// Starts a server on `address`, e.g. `0.0.0.0:8080`.
+    pub fn bind(address: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State::default()));
+        let stopping = Arc::new(AtomicBool::new(false));
+
+        let listener = {
+            let state = state.clone();
+            let stopping = stopping.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stopping.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let Ok(stream) = stream else {
+                        continue;
+                    };
+                    let state = state.clone();
+                    thread::spawn(move || serve(&stream, &state));
+                }
+            })
+        };
+
+        Ok(Self {
+            address,
+            state,
+            stopping,
+            listener: Some(listener),
+        })
+    }
+
+    pub fn hostname(&self) -> String {
+        self.address.ip().to_string()
+    }
+
+    pub fn port(&self) -> u16 {
+        self.address.port()
+    }
+
+    /// The base URL clients connect to, e.g. `http://127.0.0.1:41234`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Only accepts logins as `username` with `password`.
+    pub fn with_credentials(self, username: &str, password: &str) -> Self {
+        {
+            let mut state = self.state();
+            state.username = username.to_string();
+            state.password = password.to_string();
+        }
+        self
+    }
+
+    /// A token the server accepts without a login, e.g. for a `server.token_file`.
+    pub fn issue_token(&self) -> String {
+        self.state().issue_token()
+    }
+
+    /// Rejects every token issued so far, as when a session expires on the server.
+    pub fn revoke_tokens(&self) {
+        self.state().tokens.clear();
+    }
+
+    /// Answers `method` requests to `path` with `response` from now on, instead of
+    /// the store. A later fixture for the same route replaces the earlier one.
+    pub fn respond(&self, method: &str, path: &str, response: FakeResponse) {
+        let mut state = self.state();
+        state.fixtures.retain(|(fixture_method, fixture_path, _)| {
+            !(fixture_method.eq_ignore_ascii_case(method) && fixture_path == path)
+        });
+        state
+            .fixtures
+            .push((method.to_uppercase(), path.to_string(), response));
+    }
+
+    /// Stores `entity` as if it were created through the API, returning its id.
+    ///
+    /// Panics when the store rejects it, e.g. for a duplicate name.
+    pub fn insert(&self, resource: Resource, entity: Value) -> i64 {
+        let stored = self
+            .state()
+            .store
+            .create(resource, entity, &[])
+            .unwrap_or_else(|response| {
+                panic!(
+                    "fake server rejected a {} fixture: {response:?}",
+                    resource.key()
+                )
+            });
+        stored["id"].as_i64().unwrap_or_default()
+    }
+
+    /// Stores every entity of a fixture document such as
+    /// `{"collections": [...], "classes": [...], "objects": [...]}`.
+    ///
+    /// Collections are stored first, then classes, objects, groups, and users, so
+    /// entities can refer to the ids of the ones listed before them.
+    pub fn seed(&self, fixtures: &Value) {
+        for resource in Resource::ALL {
+            for entity in fixtures[resource.key()].as_array().into_iter().flatten() {
+                self.insert(resource, entity.clone());
+            }
+        }
+    }
+
+    pub fn add_collection(&self, name: &str) -> i64 {
+        self.insert(Resource::Collections, json!({ "name": name }))
+    }
+
+    pub fn add_class(&self, name: &str, collection_id: i64) -> i64 {
+        self.insert(
+            Resource::Classes,
+            json!({ "name": name, "collection_id": collection_id }),
+        )
+    }
+
+    pub fn add_object(&self, class_id: i64, name: &str, data: Value) -> i64 {
+        let collection_id = self
+            .state()
+            .store
+            .get(Resource::Classes, class_id)
+            .map_or(Value::Null, |class| class["collection_id"].clone());
+        self.insert(
+            Resource::Objects,
+            json!({
+                "name": name,
+                "hubuum_class_id": class_id,
+                "collection_id": collection_id,
+                "data": data,
+            }),
+        )
+    }
+
+    pub fn add_group(&self, groupname: &str) -> i64 {
+        self.insert(Resource::Groups, json!({ "groupname": groupname }))
+    }
+
+    pub fn add_user(&self, name: &str) -> i64 {
+        self.insert(Resource::Users, json!({ "name": name }))
+    }
+
+    pub fn add_member(&self, group_id: i64, user_id: i64) {
+        self.state().store.add_member(group_id, user_id);
+    }
+
+    /// Every stored entity of `resource`, in id order.
+    pub fn entities(&self, resource: Resource) -> Vec<Value> {
+        self.state().store.all(resource)
+    }
+
+    /// Every request received so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state().requests.clone()
+    }
+
+    /// The requests received so far with `method` to `path`.
+    pub fn requests_to(&self, method: &str, path: &str) -> Vec<RecordedRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|request| request.method.eq_ignore_ascii_case(method) && request.path == path)
+            .collect()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        lock(&self.state)
+    }
+}
+
+impl Drop for FakeServer {
+    fn drop(&mut self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        // Wakes the listener so it sees the flag.
+        let _ = TcpStream::connect(self.address);
+        if let Some(listener) = self.listener.take() {
+            let _ = listener.join();
+        }
+    }
+}
+
+fn serve(stream: &TcpStream, state: &Mutex<State>) {
+    let Ok(request) = http::read_request(stream) else {
+        return;
+    };
+    let response = {
+        let mut state = lock(state);
+        state.requests.push(request.clone());
+        routes::handle(&mut state, &request)
+    };
+    let _ = http::write_response(stream, &response);
+}
+
+/// Locks the state, even after a panic on another connection poisoned it.
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
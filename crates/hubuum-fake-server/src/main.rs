@@ -0,0 +1,45 @@
+//! Serves a fixture file until stopped, for pointing a client at a fake server by hand.
+//!
+//! `hubuum-fake-server [fixtures.json] [address]` listens on `127.0.0.1:8080` unless an
+//! address is given, and accepts logins as `admin` with password `secret`.
+
+use std::env::args;
+use std::fs::read_to_string;
+use std::process::ExitCode;
+use std::thread;
+
+use hubuum_fake_server::{FakeServer, PASSWORD, USERNAME};
+
+fn main() -> ExitCode {
+    let mut args = args().skip(1);
+    let fixtures = args.next();
+    let address = args.next().unwrap_or_else(|| "127.0.0.1:8080".to_string());
+
+    let server = match FakeServer::bind(&address) {
+        Ok(server) => server,
+        Err(err) => {
+            eprintln!("Cannot listen on {address}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(path) = fixtures {
+        let fixtures = read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| serde_json::from_str(&text).map_err(|err| err.to_string()));
+        match fixtures {
+            Ok(fixtures) => server.seed(&fixtures),
+            Err(err) => {
+                eprintln!("Cannot read fixtures from {path}: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    println!(
+        "Fake hubuum server on {} (login as {USERNAME} / {PASSWORD})",
+        server.url()
+    );
+    loop {
+        thread::park();
+    }
+}
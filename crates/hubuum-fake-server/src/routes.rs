@@ -0,0 +1,289 @@
+use serde_json::{json, Value};
+
+use crate::http::{FakeResponse, RecordedRequest};
+use crate::store::{list, Resource, Store};
+use crate::State;
+
+/// Answers `request` from the fixtures, or else from the store.
+pub(crate) fn handle(state: &mut State, request: &RecordedRequest) -> FakeResponse {
+    if let Some((_, _, response)) = state
+        .fixtures
+        .iter()
+        .find(|(method, path, _)| *method == request.method && *path == request.path)
+    {
+        return response.clone();
+    }
+
+    let segments = request
+        .path
+        .trim_start_matches('/')
+        .split('/')
+        .collect::<Vec<_>>();
+    match segments.as_slice() {
+        ["api", "v0", "auth", "login"] if request.method == "POST" => login(state, request),
+        ["api", "v0", "auth", "validate"] => match authorized(state, request) {
+            true => FakeResponse::json(200, json!({})),
+            false => unauthorized(),
+        },
+        ["api", "v0", "auth", "logout", ..] => FakeResponse::empty(200),
+        ["api", "v1", rest @ ..] => {
+            if !authorized(state, request) {
+                return unauthorized();
+            }
+            api(&mut state.store, request, rest)
+        }
+        _ => not_found(request),
+    }
+}
+
+fn login(state: &mut State, request: &RecordedRequest) -> FakeResponse {
+    let body = request.body.as_ref().unwrap_or(&Value::Null);
+    if body["name"] != state.username.as_str() || body["password"] != state.password.as_str() {
+        return FakeResponse::error(401, "Invalid username or password");
+    }
+    FakeResponse::json(200, json!({ "token": state.issue_token() }))
+}
+
+fn authorized(state: &State, request: &RecordedRequest) -> bool {
+    request
+        .token
+        .as_ref()
+        .is_some_and(|token| state.tokens.contains(token))
+}
+
+fn unauthorized() -> FakeResponse {
+    FakeResponse::error(401, "Invalid or expired token")
+}
+
+fn not_found(request: &RecordedRequest) -> FakeResponse {
+    FakeResponse::error(
+        404,
+        format!("No fake route for {} {}", request.method, request.path),
+    )
+}
+
+fn api(store: &mut Store, request: &RecordedRequest, segments: &[&str]) -> FakeResponse {
+    let segments = match by_id(store, segments) {
+        Ok(segments) => segments,
+        Err(response) => return response,
+    };
+    let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+    let method = request.method.as_str();
+    match segments.as_slice() {
+        ["classes", class_id, "related", "graph"] if method == "GET" => {
+            match id(class_id).and_then(|class_id| store.get(Resource::Classes, class_id)) {
+                Some(_) => FakeResponse::json(200, json!({ "classes": [], "relations": [] })),
+                None => not_found(request),
+            }
+        }
+        ["classes", class_id, "objects", object_id, "related", "graph"] if method == "GET" => {
+            match (
+                id(class_id),
+                id(object_id).and_then(|id| store.get(Resource::Objects, id)),
+            ) {
+                (Some(class_id), Some(object)) if object["hubuum_class_id"] == class_id => {
+                    FakeResponse::json(200, json!({ "objects": [], "relations": [] }))
+                }
+                _ => not_found(request),
+            }
+        }
+        ["classes", class_id, "" | "objects"] => match id(class_id) {
+            Some(class_id) => objects(store, request, class_id, None),
+            None => not_found(request),
+        },
+        ["classes", class_id, object_id] | ["classes", class_id, "objects", object_id] => {
+            match (id(class_id), id(object_id)) {
+                (Some(class_id), Some(object_id)) => {
+                    objects(store, request, class_id, Some(object_id))
+                }
+                _ => not_found(request),
+            }
+        }
+        ["iam", "groups", group_id, "members"] if method == "GET" => match id(group_id) {
+            Some(group_id) => list(store.members(group_id), &request.query),
+            None => not_found(request),
+        },
+        // Relations are not stored, so listing them finds none.
+        ["relations", "classes" | "objects"] if method == "GET" => list(Vec::new(), &request.query),
+        ["iam", "principals", principal_id, "groups"] if method == "GET" => {
+            match id(principal_id) {
+                Some(principal_id) => list(store.groups_of(principal_id), &request.query),
+                None => not_found(request),
+            }
+        }
+        ["iam", "groups", group_id, "members", user_id] => {
+            let (Some(group_id), Some(user_id)) = (id(group_id), id(user_id)) else {
+                return not_found(request);
+            };
+            match method {
+                "POST" => {
+                    store.add_member(group_id, user_id);
+                    FakeResponse::empty(204)
+                }
+                "DELETE" if store.remove_member(group_id, user_id) => FakeResponse::empty(204),
+                "DELETE" => FakeResponse::error(404, "User is not a member of the group"),
+                _ => not_found(request),
+            }
+        }
+        _ => {
+            let (resource, entity_id) = match segments.as_slice() {
+                ["classes", rest @ ..] => (Resource::Classes, rest),
+                ["collections", rest @ ..] => (Resource::Collections, rest),
+                ["iam", "groups", rest @ ..] => (Resource::Groups, rest),
+                ["iam", "users", rest @ ..] => (Resource::Users, rest),
+                _ => return not_found(request),
+            };
+            match entity_id {
+                [] | [""] => collection(store, request, resource, &[]),
+                [entity_id] => match id(entity_id) {
+                    Some(entity_id) => entity(store, request, resource, entity_id, &[]),
+                    None => not_found(request),
+                },
+                _ => not_found(request),
+            }
+        }
+    }
+}
+
+/// Rewrites the `by-name/{name}` routes of classes and their objects to the id routes.
+fn by_id(store: &Store, segments: &[&str]) -> Result<Vec<String>, FakeResponse> {
+    let mut resolved = segments
+        .iter()
+        .map(|segment| (*segment).to_string())
+        .collect::<Vec<_>>();
+    if let ["classes", "by-name", class_name, rest @ ..] = segments {
+        let class = named(store, Resource::Classes, class_name, None)
+            .ok_or_else(|| FakeResponse::error(404, format!("Class '{class_name}' not found")))?;
+        resolved.splice(1..3, [class.to_string()]);
+        if let ["objects", "by-name", object_name, ..] = rest {
+            let object =
+                named(store, Resource::Objects, object_name, Some(class)).ok_or_else(|| {
+                    FakeResponse::error(404, format!("Object '{object_name}' not found"))
+                })?;
+            resolved.splice(3..5, [object.to_string()]);
+        }
+    }
+    Ok(resolved)
+}
+
+/// The id of the entity called `name`, among the objects of `class_id` if given.
+fn named(store: &Store, resource: Resource, name: &str, class_id: Option<i64>) -> Option<i64> {
+    store.all(resource).into_iter().find_map(|entity| {
+        let in_class = class_id.is_none_or(|class_id| entity["hubuum_class_id"] == class_id);
+        (in_class && entity["name"] == name).then(|| entity["id"].as_i64())?
+    })
+}
+
+/// Objects live under their class, which must exist.
+fn objects(
+    store: &mut Store,
+    request: &RecordedRequest,
+    class_id: i64,
+    object_id: Option<i64>,
+) -> FakeResponse {
+    if store.get(Resource::Classes, class_id).is_none() {
+        return FakeResponse::error(404, format!("Class {class_id} not found"));
+    }
+    let scope = [("hubuum_class_id", Value::from(class_id))];
+    match object_id {
+        None => collection(store, request, Resource::Objects, &scope),
+        Some(object_id) => entity(store, request, Resource::Objects, object_id, &scope),
+    }
+}
+
+/// Requests to a resource root: listing and creating.
+fn collection(
+    store: &mut Store,
+    request: &RecordedRequest,
+    resource: Resource,
+    scope: &[(&str, Value)],
+) -> FakeResponse {
+    match request.method.as_str() {
+        "GET" => {
+            let entities = store
+                .all(resource)
+                .into_iter()
+                .filter(|entity| in_scope(entity, scope))
+                .map(|entity| render(store, resource, entity))
+                .collect();
+            list(entities, &request.query)
+        }
+        "POST" => {
+            let mut body = request.body.clone().unwrap_or(Value::Null);
+            if resource == Resource::Objects {
+                // Objects take the collection of their class unless the body names one.
+                if let Some(class) = scope
+                    .first()
+                    .and_then(|(_, class_id)| class_id.as_i64())
+                    .and_then(|class_id| store.get(Resource::Classes, class_id))
+                {
+                    if body.is_object() && body.get("collection_id").is_none_or(Value::is_null) {
+                        body["collection_id"] = class["collection_id"].clone();
+                    }
+                }
+            }
+            match store.create(resource, body, scope) {
+                Ok(created) => FakeResponse::json(201, render(store, resource, created)),
+                Err(response) => response,
+            }
+        }
+        _ => not_found(request),
+    }
+}
+
+/// Requests to one entity: showing, updating, and deleting.
+fn entity(
+    store: &mut Store,
+    request: &RecordedRequest,
+    resource: Resource,
+    entity_id: i64,
+    scope: &[(&str, Value)],
+) -> FakeResponse {
+    let missing = || FakeResponse::error(404, format!("{} {entity_id} not found", resource.key()));
+    if !store
+        .get(resource, entity_id)
+        .is_some_and(|entity| in_scope(&entity, scope))
+    {
+        return missing();
+    }
+    match request.method.as_str() {
+        "GET" => match store.get(resource, entity_id) {
+            Some(found) => FakeResponse::json(200, render(store, resource, found)),
+            None => missing(),
+        },
+        "PATCH" => {
+            let patch = request.body.clone().unwrap_or(Value::Null);
+            match store.update(resource, entity_id, patch) {
+                Some(updated) => FakeResponse::json(200, render(store, resource, updated)),
+                None => missing(),
+            }
+        }
+        "DELETE" if store.delete(resource, entity_id) => FakeResponse::empty(204),
+        "DELETE" => missing(),
+        _ => not_found(request),
+    }
+}
+
+fn in_scope(entity: &Value, scope: &[(&str, Value)]) -> bool {
+    scope.iter().all(|(field, value)| entity[*field] == *value)
+}
+
+/// An entity as the API returns it. Classes embed their collection, which list
+/// requests filter as `collection`.
+fn render(store: &Store, resource: Resource, mut entity: Value) -> Value {
+    if resource == Resource::Classes {
+        let collection = entity["collection_id"]
+            .as_i64()
+            .and_then(|collection_id| store.get(Resource::Collections, collection_id))
+            .unwrap_or(Value::Null);
+        if let Some(fields) = entity.as_object_mut() {
+            fields.remove("collection_id");
+            fields.insert("collection".to_string(), collection);
+        }
+    }
+    entity
+}
+
+fn id(segment: &str) -> Option<i64> {
+    segment.parse().ok()
+}
@@ -0,0 +1,447 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::http::FakeResponse;
+
+/// The timestamp every stored entity is created and updated at.
+pub(crate) const TIMESTAMP: &str = "2026-01-01T00:00:00Z";
+
+/// The kinds of entity the fake server stores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Resource {
+    Collections,
+    Classes,
+    Objects,
+    Groups,
+    Users,
+}
+
+impl Resource {
+    pub const ALL: [Resource; 5] = [
+        Resource::Collections,
+        Resource::Classes,
+        Resource::Objects,
+        Resource::Groups,
+        Resource::Users,
+    ];
+
+    /// The key of the resource in fixture files, e.g. `"classes"`.
+    pub fn key(self) -> &'static str {
+        match self {
+            Resource::Collections => "collections",
+            Resource::Classes => "classes",
+            Resource::Objects => "objects",
+            Resource::Groups => "groups",
+            Resource::Users => "users",
+        }
+    }
+
+    /// The field that names an entity, unique within its scope.
+    fn name_field(self) -> &'static str {
+        match self {
+            Resource::Groups => "groupname",
+            _ => "name",
+        }
+    }
+
+    fn noun(self) -> &'static str {
+        match self {
+            Resource::Collections => "Collection",
+            Resource::Classes => "Class",
+            Resource::Objects => "Object",
+            Resource::Groups => "Group",
+            Resource::Users => "User",
+        }
+    }
+
+    /// Fields the server fills in when a create request leaves them out.
+    fn defaults(self) -> Value {
+        match self {
+            Resource::Collections => json!({
+                "description": "",
+                "group_id": 1,
+                "parent_collection_id": null,
+            }),
+            Resource::Classes => json!({
+                "description": "",
+                "json_schema": null,
+                "validate_schema": false,
+            }),
+            Resource::Objects => json!({ "description": "", "data": {} }),
+            Resource::Groups => json!({
+                "identity_scope": "local",
+                "description": "",
+                "managed_by": "local",
+                "external_key": null,
+                "last_sync_attempted_at": null,
+                "last_sync_success_at": null,
+            }),
+            Resource::Users => json!({
+                "identity_scope": "local",
+                "provider_kind": "local",
+                "provider_managed": false,
+                "email": null,
+                "proper_name": null,
+                "last_sync_attempted_at": null,
+                "last_sync_success_at": null,
+            }),
+        }
+    }
+}
+
+/// Everything the fake server knows, by resource and id.
+#[derive(Debug, Default)]
+pub(crate) struct Store {
+    entities: BTreeMap<Resource, BTreeMap<i64, Map<String, Value>>>,
+    members: BTreeMap<i64, Vec<i64>>,
+    last_ids: BTreeMap<Resource, i64>,
+}
+
+impl Store {
+    /// Stores `entity` as sent in a create request, filling in the id, timestamps, and
+    /// defaults. `scope` fields, such as the class of an object, win over the body.
+    pub(crate) fn create(
+        &mut self,
+        resource: Resource,
+        entity: Value,
+        scope: &[(&str, Value)],
+    ) -> Result<Value, FakeResponse> {
+        let Value::Object(fields) = entity else {
+            return Err(FakeResponse::error(400, "Expected a JSON object"));
+        };
+        let mut stored = match resource.defaults() {
+            Value::Object(defaults) => defaults,
+            _ => Map::new(),
+        };
+        // Create requests send `null` for options that were not given.
+        for (field, value) in fields {
+            if !(value.is_null() && stored.contains_key(&field)) {
+                stored.insert(field, value);
+            }
+        }
+        for (field, value) in scope {
+            stored.insert((*field).to_string(), value.clone());
+        }
+        stored.remove("password");
+
+        let name = stored.get(resource.name_field()).cloned();
+        if name.is_none() {
+            return Err(FakeResponse::error(
+                400,
+                format!("Missing field '{}'", resource.name_field()),
+            ));
+        }
+        if self.find(resource, &stored, name.as_ref()).is_some() {
+            return Err(FakeResponse::error(
+                409,
+                format!(
+                    "{} '{}' already exists",
+                    resource.noun(),
+                    text(name.as_ref().unwrap_or(&Value::Null))
+                ),
+            ));
+        }
+
+        let last_id = self.last_ids.entry(resource).or_default();
+        let id = stored
+            .get("id")
+            .and_then(Value::as_i64)
+            .unwrap_or(*last_id + 1);
+        *last_id = (*last_id).max(id);
+        stored.insert("id".to_string(), Value::from(id));
+        for field in ["created_at", "updated_at"] {
+            stored
+                .entry(field)
+                .or_insert_with(|| Value::from(TIMESTAMP));
+        }
+        self.entities
+            .entry(resource)
+            .or_default()
+            .insert(id, stored.clone());
+        Ok(Value::Object(stored))
+    }
+
+    pub(crate) fn get(&self, resource: Resource, id: i64) -> Option<Value> {
+        self.entities
+            .get(&resource)?
+            .get(&id)
+            .cloned()
+            .map(Value::Object)
+    }
+
+    /// Applies the non-null fields of `patch`, as update requests leave out the rest.
+    pub(crate) fn update(&mut self, resource: Resource, id: i64, patch: Value) -> Option<Value> {
+        let entity = self.entities.get_mut(&resource)?.get_mut(&id)?;
+        if let Value::Object(fields) = patch {
+            for (field, value) in fields {
+                if field != "id" && !value.is_null() {
+                    entity.insert(field, value);
+                }
+            }
+        }
+        Some(Value::Object(entity.clone()))
+    }
+
+    pub(crate) fn delete(&mut self, resource: Resource, id: i64) -> bool {
+        match resource {
+            Resource::Groups => {
+                self.members.remove(&id);
+            }
+            Resource::Users => self
+                .members
+                .values_mut()
+                .for_each(|members| members.retain(|member| *member != id)),
+            Resource::Classes => {
+                if let Some(objects) = self.entities.get_mut(&Resource::Objects) {
+                    objects.retain(|_, object| object.get("hubuum_class_id") != Some(&id.into()));
+                }
+            }
+            _ => {}
+        }
+        self.entities
+            .get_mut(&resource)
+            .is_some_and(|entities| entities.remove(&id).is_some())
+    }
+
+    /// Every stored entity of `resource`, in id order.
+    pub(crate) fn all(&self, resource: Resource) -> Vec<Value> {
+        self.entities
+            .get(&resource)
+            .map(|entities| entities.values().cloned().map(Value::Object).collect())
+            .unwrap_or_default()
+    }
+
+    /// The members of a group, as the principal records the members endpoint returns.
+    pub(crate) fn members(&self, group_id: i64) -> Vec<Value> {
+        self.members
+            .get(&group_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.get(Resource::Users, *id))
+            .map(|user| {
+                json!({
+                    "principal_id": user["id"],
+                    "identity_scope": user["identity_scope"],
+                    "kind": "user",
+                    "name": user["name"],
+                    "created_at": user["created_at"],
+                    "updated_at": user["updated_at"],
+                })
+            })
+            .collect()
+    }
+
+    /// The groups a user is a member of, in id order.
+    pub(crate) fn groups_of(&self, user_id: i64) -> Vec<Value> {
+        self.members
+            .iter()
+            .filter(|(_, members)| members.contains(&user_id))
+            .filter_map(|(group_id, _)| self.get(Resource::Groups, *group_id))
+            .collect()
+    }
+
+    pub(crate) fn add_member(&mut self, group_id: i64, user_id: i64) {
+        let members = self.members.entry(group_id).or_default();
+        if !members.contains(&user_id) {
+            members.push(user_id);
+        }
+    }
+
+    pub(crate) fn remove_member(&mut self, group_id: i64, user_id: i64) -> bool {
+        let Some(members) = self.members.get_mut(&group_id) else {
+            return false;
+        };
+        let before = members.len();
+        members.retain(|member| *member != user_id);
+        members.len() != before
+    }
+
+    /// The stored entity with the same name in the same scope as `entity`, if any.
+    fn find(
+        &self,
+        resource: Resource,
+        entity: &Map<String, Value>,
+        name: Option<&Value>,
+    ) -> Option<i64> {
+        let scope = (resource == Resource::Objects).then(|| entity.get("hubuum_class_id"));
+        self.entities
+            .get(&resource)?
+            .iter()
+            .find_map(|(id, other)| {
+                let same_scope = scope.is_none_or(|class| other.get("hubuum_class_id") == class);
+                (same_scope && other.get(resource.name_field()) == name).then_some(*id)
+            })
+    }
+}
+
+/// Filters, sorts, and pages `entities` by the query parameters of a list request,
+/// setting the cursor and count headers the client reads.
+pub(crate) fn list(mut entities: Vec<Value>, query: &[(String, String)]) -> FakeResponse {
+    let mut limit = None;
+    let mut offset = 0;
+    let mut include_total = false;
+    for (key, value) in query {
+        match key.as_str() {
+            "limit" => limit = value.parse::<usize>().ok(),
+            "cursor" => offset = value.parse::<usize>().unwrap_or(0),
+            "include_total" => include_total = value != "false",
+            "sort" => sort(&mut entities, value),
+            _ => {
+                let (field, operator) = key.split_once("__").unwrap_or((key, "equals"));
+                let Some(matches) = operator_matcher(operator) else {
+                    return FakeResponse::error(400, format!("Unsupported operator '{operator}'"));
+                };
+                entities.retain(|entity| {
+                    entity
+                        .get(field)
+                        .is_some_and(|actual| matches(&text(actual), value))
+                });
+            }
+        }
+    }
+
+    let total = entities.len();
+    let page = entities
+        .into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect::<Vec<_>>();
+    let mut response = FakeResponse::json(200, Value::Array(page.clone()));
+    if let Some(limit) = limit {
+        response = response.with_header("X-Page-Limit", limit.to_string());
+        if offset + page.len() < total {
+            response = response.with_header("X-Next-Cursor", (offset + page.len()).to_string());
+        }
+    }
+    if include_total {
+        response = response.with_header("X-Total-Count", total.to_string());
+    }
+    response
+}
+
+type Matcher = fn(&str, &str) -> bool;
+
+fn operator_matcher(operator: &str) -> Option<Matcher> {
+    let matcher: Matcher = match operator {
+        "equals" => |actual, expected| expected.split(',').any(|value| actual == value),
+        "iequals" => |actual, expected| actual.eq_ignore_ascii_case(expected),
+        "contains" => |actual, expected| actual.contains(expected),
+        "icontains" => |actual, expected| actual.to_lowercase().contains(&expected.to_lowercase()),
+        "startswith" => |actual, expected| actual.starts_with(expected),
+        "istartswith" => {
+            |actual, expected| actual.to_lowercase().starts_with(&expected.to_lowercase())
+        }
+        "endswith" => |actual, expected| actual.ends_with(expected),
+        "iendswith" => |actual, expected| actual.to_lowercase().ends_with(&expected.to_lowercase()),
+        "gt" => |actual, expected| compare(actual, expected) == Ordering::Greater,
+        "gte" => |actual, expected| compare(actual, expected) != Ordering::Less,
+        "lt" => |actual, expected| compare(actual, expected) == Ordering::Less,
+        "lte" => |actual, expected| compare(actual, expected) != Ordering::Greater,
+        _ => return None,
+    };
+    Some(matcher)
+}
+
+fn sort(entities: &mut [Value], clauses: &str) {
+    for clause in clauses.split(',').rev() {
+        let (field, descending) = match clause.rsplit_once('.') {
+            Some((field, "desc")) => (field, true),
+            Some((field, "asc")) => (field, false),
+            _ => (clause, false),
+        };
+        entities.sort_by(|left, right| {
+            let ordering = compare(
+                &left.get(field).map(text).unwrap_or_default(),
+                &right.get(field).map(text).unwrap_or_default(),
+            );
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+}
+
+/// Compares numerically when both sides are numbers, and as text otherwise.
+fn compare(left: &str, right: &str) -> Ordering {
+    match (left.parse::<f64>(), right.parse::<f64>()) {
+        (Ok(left), Ok(right)) => left.partial_cmp(&right).unwrap_or(Ordering::Equal),
+        _ => left.cmp(right),
+    }
+}
+
+/// A field value as the text a query parameter compares against. Embedded entities,
+/// such as the collection of a class, compare by id.
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Object(fields) if fields.contains_key("id") => text(&fields["id"]),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{list, Resource, Store};
+
+    fn query(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn lists_filter_sort_and_page_like_the_server() {
+        let entities = vec![
+            json!({ "id": 1, "name": "web-01" }),
+            json!({ "id": 2, "name": "db-01" }),
+            json!({ "id": 3, "name": "WEB-02" }),
+        ];
+
+        let response = list(
+            entities,
+            &query(&[
+                ("name__icontains", "web"),
+                ("sort", "name.desc"),
+                ("limit", "1"),
+                ("include_total", "true"),
+            ]),
+        );
+
+        assert_eq!(response.body, Some(json!([{ "id": 1, "name": "web-01" }])));
+        assert!(response
+            .headers
+            .contains(&("X-Next-Cursor".to_string(), "1".to_string())));
+        assert!(response
+            .headers
+            .contains(&("X-Total-Count".to_string(), "2".to_string())));
+    }
+
+    #[test]
+    fn names_are_unique_within_their_scope() {
+        let mut store = Store::default();
+        let host = json!({ "name": "web-01", "hubuum_class_id": 1 });
+
+        store
+            .create(Resource::Objects, host.clone(), &[])
+            .expect("first object should be stored");
+        let conflict = store
+            .create(Resource::Objects, host, &[])
+            .expect_err("a second object with the name should conflict");
+        store
+            .create(
+                Resource::Objects,
+                json!({ "name": "web-01", "hubuum_class_id": 2 }),
+                &[],
+            )
+            .expect("another class may reuse the name");
+
+        assert_eq!(conflict.status, 409);
+    }
+}
@@ -0,0 +1,77 @@
+use std::cmp::Ordering;
+use std::iter::from_fn;
+
+use icu_normalizer::DecomposingNormalizerBorrowed;
+
+use crate::model::Collation;
+
+impl Collation {
+    /// Orders two strings; strings that only tie under the collation fall back to
+    /// byte order, so sorting stays deterministic.
+    pub fn compare(self, left: &str, right: &str) -> Ordering {
+        match self {
+            Self::Ascii => left.cmp(right),
+            Self::Natural => compare_natural(left, right),
+            Self::Locale => dictionary_key(left)
+                .cmp(&dictionary_key(right))
+                .then_with(|| left.cmp(right)),
+        }
+    }
+}
+
+fn compare_natural(left: &str, right: &str) -> Ordering {
+    let mut left_runs = runs(left);
+    let mut right_runs = runs(right);
+    loop {
+        let ordering = match (left_runs.next(), right_runs.next()) {
+            (None, None) => return left.cmp(right),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(left_run), Some(right_run)) => {
+                if is_digits(left_run) && is_digits(right_run) {
+                    compare_digits(left_run, right_run)
+                } else {
+                    left_run.cmp(right_run)
+                }
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Splits `text` into alternating runs of ASCII digits and other characters.
+fn runs(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    from_fn(move || {
+        let digits = rest.chars().next()?.is_ascii_digit();
+        let end = rest
+            .find(|character: char| character.is_ascii_digit() != digits)
+            .unwrap_or(rest.len());
+        let (run, tail) = rest.split_at(end);
+        rest = tail;
+        Some(run)
+    })
+}
+
+fn is_digits(run: &str) -> bool {
+    run.starts_with(|character: char| character.is_ascii_digit())
+}
+
+/// Compares digit runs by value without parsing them, so long runs cannot overflow.
+fn compare_digits(left: &str, right: &str) -> Ordering {
+    let left = left.trim_start_matches('0');
+    let right = right.trim_start_matches('0');
+    left.len().cmp(&right.len()).then_with(|| left.cmp(right))
+}
+
+/// `text` lowercased with its combining accents removed.
+fn dictionary_key(text: &str) -> String {
+    DecomposingNormalizerBorrowed::new_nfd()
+        .normalize(text)
+        .chars()
+        .filter(|character| !('\u{0300}'..='\u{036f}').contains(character))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
@@ -1,7 +1,7 @@
 use regex::Regex;
 
 use crate::error::PipelineError;
-use crate::model::{OutputEnvelope, OutputShape, PipeStage, SortCast};
+use crate::model::{Collation, OutputEnvelope, OutputShape, PipeStage, SortCast};
 use crate::verbs::collection::{
     aggregate_envelope, collapse_groups, count_envelope, group_envelope, limit_envelope,
     sort_envelope, unroll_envelope,
@@ -16,14 +16,19 @@ impl PipeStage {
     pub fn apply_all(
         stages: &[Self],
         mut lines: Vec<String>,
+        collation: Collation,
     ) -> Result<Vec<String>, PipelineError> {
         for stage in stages {
-            lines = stage.apply(lines)?;
+            lines = stage.apply(lines, collation)?;
         }
         Ok(lines)
     }
 
-    fn apply(&self, lines: Vec<String>) -> Result<Vec<String>, PipelineError> {
+    fn apply(
+        &self,
+        lines: Vec<String>,
+        collation: Collation,
+    ) -> Result<Vec<String>, PipelineError> {
         match self {
             Self::Grep(pattern) | Self::ValueSearch(pattern) => {
                 let regex = Regex::new(pattern)?;
@@ -49,7 +54,7 @@ impl PipeStage {
             Self::Count => Ok(vec![lines.len().to_string()]),
             Self::SortLines { descending } => {
                 let mut sorted = lines;
-                sorted.sort();
+                sorted.sort_by(|left, right| collation.compare(left, right));
                 if *descending {
                     sorted.reverse();
                 }
@@ -74,10 +79,19 @@ impl PipeStage {
 pub fn apply_pipeline(
     envelope: OutputEnvelope,
     stages: &[PipeStage],
+) -> Result<OutputEnvelope, PipelineError> {
+    apply_pipeline_collated(envelope, stages, Collation::default())
+}
+
+/// Like [`apply_pipeline`], with text sorted and compared by `collation`.
+pub fn apply_pipeline_collated(
+    envelope: OutputEnvelope,
+    stages: &[PipeStage],
+    collation: Collation,
 ) -> Result<OutputEnvelope, PipelineError> {
     let mut envelope = envelope;
     for stage in stages {
-        envelope = apply_semantic_stage(envelope, stage)?;
+        envelope = apply_semantic_stage(envelope, stage, collation)?;
     }
     Ok(envelope)
 }
@@ -85,6 +99,7 @@ pub fn apply_pipeline(
 fn apply_semantic_stage(
     envelope: OutputEnvelope,
     stage: &PipeStage,
+    collation: Collation,
 ) -> Result<OutputEnvelope, PipelineError> {
     if envelope.shape == OutputShape::Lines {
         let lines = envelope
@@ -94,7 +109,7 @@ fn apply_semantic_stage(
             .flatten()
             .filter_map(|value| value.as_str().map(str::to_string))
             .collect::<Vec<_>>();
-        return Ok(OutputEnvelope::lines(stage.apply(lines)?));
+        return Ok(OutputEnvelope::lines(stage.apply(lines, collation)?));
     }
 
     match stage {
@@ -107,16 +122,16 @@ fn apply_semantic_stage(
         PipeStage::Tail(count) => limit_envelope(envelope, *count, 0, true),
         PipeStage::Count => count_envelope(envelope),
         PipeStage::SortLines { descending } => {
-            sort_envelope(envelope, None, *descending, SortCast::Auto)
+            sort_envelope(envelope, None, *descending, SortCast::Auto, collation)
         }
         PipeStage::Columns(columns) => project_envelope(envelope, columns),
         PipeStage::SortColumn {
             column,
             descending,
             cast,
-        } => sort_envelope(envelope, Some(column), *descending, *cast),
+        } => sort_envelope(envelope, Some(column), *descending, *cast, collation),
         PipeStage::Group(keys) => group_envelope(envelope, keys),
-        PipeStage::Aggregate(spec) => aggregate_envelope(envelope, spec),
+        PipeStage::Aggregate(spec) => aggregate_envelope(envelope, spec, collation),
         PipeStage::CollapseGroups => collapse_groups(envelope),
         PipeStage::Unroll(selector) => unroll_envelope(envelope, selector),
         PipeStage::Jq(expression) => jq_envelope(envelope, expression),
@@ -1,3 +1,4 @@
+mod collation;
 mod error;
 mod eval;
 mod model;
@@ -10,9 +11,9 @@ mod verbs;
 mod tests;
 
 pub use error::PipelineError;
-pub use eval::apply_pipeline;
+pub use eval::{apply_pipeline, apply_pipeline_collated};
 pub use model::{
-    AggregateFunction, AggregateSpec, GroupKey, OutputEnvelope, OutputShape, PipeStage,
+    AggregateFunction, AggregateSpec, Collation, GroupKey, OutputEnvelope, OutputShape, PipeStage,
     ProjectTerm, SortCast,
 };
 pub use parse::split_pipeline;
@@ -83,6 +83,19 @@ pub enum SortCast {
     Ip,
 }
 
+/// How text is ordered when sorting, for `sort` stages and min/max aggregates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Collation {
+    /// Byte order, so `web10` sorts before `web2` and `Zeta` before `alpha`.
+    #[default]
+    Ascii,
+    /// Runs of digits compare as numbers, so `web2` sorts before `web10`.
+    Natural,
+    /// Dictionary order: case and accents are ignored first, so `Émile` sorts
+    /// next to `emile`. It is the same on every machine, whatever its locale.
+    Locale,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OutputShape {
     Empty,
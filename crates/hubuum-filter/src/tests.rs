@@ -1,6 +1,7 @@
 use crate::{
-    apply_pipeline, group_summary_rows, split_pipeline, AggregateFunction, AggregateSpec, GroupKey,
-    OutputEnvelope, OutputShape, PipeStage, ProjectTerm, SortCast,
+    apply_pipeline, apply_pipeline_collated, group_summary_rows, split_pipeline, AggregateFunction,
+    AggregateSpec, Collation, GroupKey, OutputEnvelope, OutputShape, PipeStage, ProjectTerm,
+    SortCast,
 };
 use serde_json::json;
 
@@ -225,6 +226,78 @@ fn projection_droppers_and_value_extraction_still_work() {
     assert_eq!(values.value, json!(["10.0.0.10", "129.240.1.11"]));
 }
 
+fn sorted_names(names: &[&str], collation: Collation) -> Vec<String> {
+    let rows = names.iter().map(|name| json!({ "Name": name })).collect();
+    let sorted = apply_pipeline_collated(
+        OutputEnvelope::rows(rows, vec!["Name".to_string()]),
+        &[PipeStage::SortColumn {
+            column: "Name".to_string(),
+            descending: false,
+            cast: SortCast::Auto,
+        }],
+        collation,
+    )
+    .expect("sort");
+    sorted
+        .value
+        .as_array()
+        .expect("rows")
+        .iter()
+        .map(|row| row["Name"].as_str().expect("name").to_string())
+        .collect()
+}
+
+#[test]
+fn collations_order_host_names() {
+    let names = ["web10", "Web3", "web2", "émile", "web02", "zeta"];
+
+    assert_eq!(
+        sorted_names(&names, Collation::Ascii),
+        ["Web3", "web02", "web10", "web2", "zeta", "émile"]
+    );
+    assert_eq!(
+        sorted_names(&names, Collation::Natural),
+        ["Web3", "web02", "web2", "web10", "zeta", "émile"]
+    );
+    assert_eq!(
+        sorted_names(&names, Collation::Locale),
+        ["émile", "web02", "web10", "web2", "Web3", "zeta"]
+    );
+}
+
+#[test]
+fn natural_collation_sorts_lines_and_min_max() {
+    let lines = PipeStage::apply_all(
+        &[PipeStage::SortLines { descending: true }],
+        vec![
+            "host9".to_string(),
+            "host10".to_string(),
+            "host1".to_string(),
+        ],
+        Collation::Natural,
+    )
+    .expect("sorted lines");
+    assert_eq!(lines, ["host10", "host9", "host1"]);
+
+    let rows = vec![json!({"Name": "web9"}), json!({"Name": "web10"})];
+    let grouped = apply_pipeline_collated(
+        OutputEnvelope::rows(rows, vec!["Name".to_string()]),
+        &[
+            PipeStage::Group(Vec::new()),
+            PipeStage::Aggregate(AggregateSpec {
+                function: AggregateFunction::Max("Name".to_string()),
+                alias: "Last".to_string(),
+            }),
+        ],
+        Collation::Natural,
+    )
+    .expect("max");
+    assert_eq!(
+        group_summary_rows(&grouped.value)[0]["Last"],
+        json!("web10")
+    );
+}
+
 #[test]
 fn parsing_rejects_unknown_single_letter_stages() {
     assert!(split_pipeline("object list --class Hosts | X foo").is_err());
@@ -52,7 +52,7 @@ pub fn help_topics() -> &'static [HelpTopic] {
         HelpTopic {
             name: "redirects",
             title: "Redirects",
-            summary: ">, >>, and each:<template>.",
+            summary: ">, >>, each:<template>, and X for external commands.",
         },
     ]
 }
@@ -119,6 +119,11 @@ pub fn verb_summaries() -> &'static [VerbSummary] {
             topic: "jq",
             summary: "Apply a jq-compatible expression.",
         },
+        VerbSummary {
+            names: "X",
+            topic: "redirects",
+            summary: "Pipe the rendered output into an external command.",
+        },
     ]
 }
 
@@ -146,7 +151,7 @@ pub fn topic_help(topic: &str) -> Option<&'static str> {
             "JQ stage:\n  | JQ <expression> - run a jq-compatible transform with the in-process jaq interpreter.\n\nExamples:\n  object list --class Hosts --json | JQ 'map({Name, os_version})'\n  object list --class Hosts --json | JQ '.[] | .Name'\n\nJQ runs against the semantic payload after earlier stages.\nZero outputs become empty output. One output is shaped from its JSON type.\nMultiple outputs become semantic rows or values. Existing column metadata is cleared.",
         ),
         "redirects" => Some(
-            "Redirects:\n  > <file> - write rendered output to a file.\n  >> <file> - append rendered output to a file.\n  > each:<template> - write one file per semantic row or value.\n\nOperators must be standalone, whitespace-delimited tokens.\nParent directories must exist. Compact comparisons such as F age>3 are not redirects.\nFile output follows the configured color mode: auto and never strip ANSI; always preserves it.\n\nExamples (REPL/script syntax):\n  object list --class Hosts | P Name os_version > hosts.txt\n  object list --json --class Hosts | P Name os_version > each:/tmp/host-{Name}.json\n\nIn a POSIX one-shot command, escape or quote |, >, and >>.\nThis lets the shell pass those operators to Hubuum CLI.",
+            "Redirects:\n  > <file> - write rendered output to a file.\n  >> <file> - append rendered output to a file.\n  > each:<template> - write one file per semantic row or value.\n  | X <command> - pipe rendered output into a shell command; the rest of the line, pipes and redirects included, belongs to the shell.\n\nOperators must be standalone, whitespace-delimited tokens.\nParent directories must exist. Compact comparisons such as F age>3 are not redirects.\nFile output follows the configured color mode: auto and never strip ANSI; always preserves it.\n\nExamples (REPL/script syntax):\n  object list --class Hosts | P Name os_version > hosts.txt\n  object list --json --class Hosts | P Name os_version > each:/tmp/host-{Name}.json\n  object list --class Hosts | X grep web | wc -l\n\nIn a POSIX one-shot command, escape or quote |, >, and >>.\nThis lets the shell pass those operators to Hubuum CLI.",
         ),
         _ => None,
     }
@@ -6,7 +6,7 @@ use serde_json::{to_string, Map, Number, Value};
 
 use crate::error::PipelineError;
 use crate::model::{
-    AggregateFunction, AggregateSpec, GroupKey, OutputEnvelope, OutputShape, SortCast,
+    AggregateFunction, AggregateSpec, Collation, GroupKey, OutputEnvelope, OutputShape, SortCast,
 };
 use crate::selector::{scalar_text, select_values};
 use crate::verbs::array_values;
@@ -68,11 +68,14 @@ pub(crate) fn sort_envelope(
     selector: Option<&str>,
     descending: bool,
     cast: SortCast,
+    collation: Collation,
 ) -> Result<OutputEnvelope, PipelineError> {
     match envelope.shape {
         OutputShape::Rows | OutputShape::Values | OutputShape::Groups => {
             let mut values = array_values(&envelope.value)?;
-            values.sort_by(|left, right| compare_selected(left, right, selector, descending, cast));
+            values.sort_by(|left, right| {
+                compare_selected(left, right, selector, descending, cast, collation)
+            });
             Ok(OutputEnvelope {
                 value: Value::Array(values),
                 ..envelope
@@ -124,6 +127,7 @@ pub(crate) fn group_envelope(
 pub(crate) fn aggregate_envelope(
     envelope: OutputEnvelope,
     spec: &AggregateSpec,
+    collation: Collation,
 ) -> Result<OutputEnvelope, PipelineError> {
     if envelope.shape != OutputShape::Groups {
         return Err(PipelineError::Pipe(
@@ -139,7 +143,7 @@ pub(crate) fn aggregate_envelope(
                 .and_then(Value::as_array)
                 .cloned()
                 .unwrap_or_default();
-            let value = aggregate_rows(&rows, &spec.function);
+            let value = aggregate_rows(&rows, &spec.function, collation);
             group
                 .get_mut("aggregates")
                 .and_then(Value::as_object_mut)
@@ -239,7 +243,7 @@ fn group_value_combinations(
     Ok(combinations)
 }
 
-fn aggregate_rows(rows: &[Value], function: &AggregateFunction) -> Value {
+fn aggregate_rows(rows: &[Value], function: &AggregateFunction, collation: Collation) -> Value {
     match function {
         AggregateFunction::Count => Value::Number(rows.len().into()),
         AggregateFunction::Sum(selector) => number_value(numeric_values(rows, selector).sum()),
@@ -251,8 +255,8 @@ fn aggregate_rows(rows: &[Value], function: &AggregateFunction) -> Value {
                 number_value(values.iter().sum::<f64>() / values.len() as f64)
             }
         }
-        AggregateFunction::Min(selector) => selected_min_max(rows, selector, false),
-        AggregateFunction::Max(selector) => selected_min_max(rows, selector, true),
+        AggregateFunction::Min(selector) => selected_min_max(rows, selector, false, collation),
+        AggregateFunction::Max(selector) => selected_min_max(rows, selector, true, collation),
     }
 }
 
@@ -262,13 +266,13 @@ fn numeric_values<'a>(rows: &'a [Value], selector: &'a str) -> impl Iterator<Ite
         .filter_map(Value::as_f64)
 }
 
-fn selected_min_max(rows: &[Value], selector: &str, max: bool) -> Value {
+fn selected_min_max(rows: &[Value], selector: &str, max: bool, collation: Collation) -> Value {
     let mut values = rows
         .iter()
         .flat_map(|row| select_values(row, selector))
         .cloned()
         .collect::<Vec<_>>();
-    values.sort_by(|left, right| compare_values(left, right, SortCast::Auto));
+    values.sort_by(|left, right| compare_values(left, right, SortCast::Auto, collation));
     if max {
         values.pop().unwrap_or(Value::Null)
     } else {
@@ -343,10 +347,11 @@ fn compare_selected(
     selector: Option<&str>,
     descending: bool,
     cast: SortCast,
+    collation: Collation,
 ) -> Ordering {
     let left = selected_sort_value(left, selector);
     let right = selected_sort_value(right, selector);
-    compare_sort_values(left.as_ref(), right.as_ref(), descending, cast)
+    compare_sort_values(left.as_ref(), right.as_ref(), descending, cast, collation)
 }
 
 fn selected_sort_value(value: &Value, selector: Option<&str>) -> Option<Value> {
@@ -370,14 +375,19 @@ fn compare_sort_values(
     right: Option<&Value>,
     descending: bool,
     cast: SortCast,
+    collation: Collation,
 ) -> Ordering {
     match (is_nullish(left), is_nullish(right)) {
         (true, true) => Ordering::Equal,
         (true, false) => Ordering::Greater,
         (false, true) => Ordering::Less,
         (false, false) => {
-            let ordering =
-                compare_values(left.expect("left value"), right.expect("right value"), cast);
+            let ordering = compare_values(
+                left.expect("left value"),
+                right.expect("right value"),
+                cast,
+                collation,
+            );
             if descending {
                 ordering.reverse()
             } else {
@@ -391,9 +401,9 @@ fn is_nullish(value: Option<&Value>) -> bool {
     matches!(value, None | Some(Value::Null))
 }
 
-fn compare_values(left: &Value, right: &Value, cast: SortCast) -> Ordering {
+fn compare_values(left: &Value, right: &Value, cast: SortCast, collation: Collation) -> Ordering {
     match cast {
-        SortCast::String => scalar_text(left).cmp(&scalar_text(right)),
+        SortCast::String => compare_text(left, right, collation),
         SortCast::Number => number_for_sort(left)
             .partial_cmp(&number_for_sort(right))
             .unwrap_or(Ordering::Equal),
@@ -403,13 +413,21 @@ fn compare_values(left: &Value, right: &Value, cast: SortCast) -> Ordering {
                 .as_f64()
                 .partial_cmp(&right.as_f64())
                 .unwrap_or(Ordering::Equal),
-            (Value::String(left), Value::String(right)) => left.cmp(right),
+            (Value::String(left), Value::String(right)) => collation.compare(left, right),
             (Value::Bool(left), Value::Bool(right)) => left.cmp(right),
-            _ => scalar_text(left).cmp(&scalar_text(right)),
+            _ => compare_text(left, right, collation),
         },
     }
 }
 
+/// Compares the text of two scalars; values without text sort first.
+fn compare_text(left: &Value, right: &Value, collation: Collation) -> Ordering {
+    match (scalar_text(left), scalar_text(right)) {
+        (Some(left), Some(right)) => collation.compare(&left, &right),
+        (left, right) => left.cmp(&right),
+    }
+}
+
 fn number_for_sort(value: &Value) -> Option<f64> {
     value
         .as_f64()
@@ -1,29 +1,35 @@
 use std::fs::read_to_string;
 use std::fs::File;
+use std::io::stderr;
 use std::path::{Path, PathBuf};
-use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 
+use chrono::Utc;
 use clap::ArgMatches;
 use hubuum_client::{
-    blocking::Client as BlockingClient, Authenticated, BaseUrl, Credentials, Token, Unauthenticated,
+    blocking::Client as BlockingClient, Authenticated, Credentials, Token, Unauthenticated,
 };
 use log::debug;
 use rpassword::prompt_password;
 use tokio::task::spawn_blocking;
 use tracing_subscriber::fmt as tracing_fmt;
+use tracing_subscriber::fmt::writer::{BoxMakeWriter, MakeWriterExt};
 use tracing_subscriber::EnvFilter;
 
 use crate::catalog::CommandCatalog;
-use crate::cli::{get_cli_config_path, update_config_from_cli};
+use crate::cli::{apply_profile_from_cli, get_cli_config_path, update_config_from_cli};
 use crate::config::{
-    get_config, init_config, init_config_state, inspect_config_state, load_config, AppConfig,
+    get_config, get_config_state, init_config, init_config_state, inspect_config_state,
+    load_config, AppConfig, AuthConfig, ConfigEntry, ConfigSource, ServerConfig,
 };
 use crate::errors::AppError;
-use crate::files::{get_log_file, get_token_from_tokenfile, write_token_to_tokenfile};
-use crate::models::TokenEntry;
-use crate::services::AppServices;
-use crate::theme::{paint, ThemeRole};
+use crate::files::{get_log_file, keyring_problem, token_storage, TokenStore};
+use crate::models::{Protocol, TokenEntry};
+use crate::offline::{is_offline, require_online};
+use crate::secrets::{decrypt_value, is_encrypted, secret_from_command};
+use crate::services::{AppServices, Relogin};
+use crate::theme::{colors_on, paint, Stream, ThemeRole};
+use crate::transaction::QueuedCommand;
 
 #[derive(Clone)]
 pub struct AppRuntime {
@@ -36,6 +42,7 @@ pub struct AppRuntime {
 pub struct AppSession {
     scope: Vec<String>,
     next_page_command: Option<String>,
+    transaction: Option<Vec<QueuedCommand>>,
 }
 
 #[derive(Clone)]
@@ -87,14 +94,69 @@ impl SharedSession {
             .expect("session scope lock should not be poisoned")
             .next_page_command = command;
     }
+
+    pub fn in_transaction(&self) -> bool {
+        self.inner
+            .lock()
+            .expect("session scope lock should not be poisoned")
+            .transaction
+            .is_some()
+    }
+
+    pub fn begin_transaction(&self) -> Result<(), AppError> {
+        let mut guard = self
+            .inner
+            .lock()
+            .expect("session scope lock should not be poisoned");
+        if guard.transaction.is_some() {
+            return Err(AppError::CommandExecutionError(
+                "A transaction is already open; commit or rollback first".to_string(),
+            ));
+        }
+        guard.transaction = Some(Vec::new());
+        Ok(())
+    }
+
+    /// Appends a command to the open transaction and returns the queue length.
+    pub fn queue_command(&self, command: QueuedCommand) -> Option<usize> {
+        let mut guard = self
+            .inner
+            .lock()
+            .expect("session scope lock should not be poisoned");
+        let queue = guard.transaction.as_mut()?;
+        queue.push(command);
+        Some(queue.len())
+    }
+
+    pub fn take_transaction(&self) -> Result<Vec<QueuedCommand>, AppError> {
+        self.inner
+            .lock()
+            .expect("session scope lock should not be poisoned")
+            .transaction
+            .take()
+            .ok_or_else(|| {
+                AppError::CommandExecutionError(
+                    "No open transaction; use 'begin' to start one".to_string(),
+                )
+            })
+    }
 }
 
-pub fn init_logging() -> Result<(), AppError> {
-    let file = get_log_file()?;
-    let file = File::create(file)?;
+/// Logs to the log file, filtered by `RUST_LOG` unless `-v` asked for a `level`.
+///
+/// With a level the log is mirrored to stderr as well.
+pub fn init_logging(level: Option<&str>) -> Result<(), AppError> {
+    let file = File::create(get_log_file()?)?;
+    let (filter, writer) = match level {
+        Some(level) => (EnvFilter::new(level), BoxMakeWriter::new(file.and(stderr))),
+        None => (EnvFilter::from_default_env(), BoxMakeWriter::new(file)),
+    };
+    // The log file never gets colour codes; stderr only when it is coloured.
+    let ansi = level.is_some() && colors_on(Stream::Stderr);
     tracing_fmt()
-        .with_writer(file)
-        .with_env_filter(EnvFilter::from_default_env())
+        .with_writer(writer)
+        .with_env_filter(filter)
+        .with_ansi(ansi)
         .init();
     Ok(())
 }
@@ -102,6 +164,7 @@ pub fn init_logging() -> Result<(), AppError> {
 pub fn load_app_config(matches: &ArgMatches) -> Result<Arc<AppConfig>, AppError> {
     let cli_config_path = get_cli_config_path(matches);
     let mut config = load_config(cli_config_path)?;
+    apply_profile_from_cli(&mut config, matches)?;
     update_config_from_cli(&mut config, matches);
     init_config_state(inspect_config_state(
         &config,
@@ -112,55 +175,171 @@ pub fn load_app_config(matches: &ArgMatches) -> Result<Arc<AppConfig>, AppError>
     Ok(Arc::new(config))
 }
 
+/// Warnings about insecure connection or credential settings, shown before login.
+pub fn startup_warnings(config: &AppConfig) -> Vec<String> {
+    insecure_tls_warning(config)
+        .into_iter()
+        .chain(
+            plaintext_password_warning(get_config_state().entry("server.password"))
+                .filter(|_| !config.server.password.as_deref().is_some_and(is_encrypted)),
+        )
+        .chain(keyring_problem(&config.auth, &config.server))
+        .collect()
+}
+
+fn plaintext_password_warning(entry: Option<&ConfigEntry>) -> Option<String> {
+    let entry = entry?;
+    if !matches!(
+        entry.source,
+        ConfigSource::SystemFile | ConfigSource::UserFile | ConfigSource::CustomFile
+    ) {
+        return None;
+    }
+    Some(format!(
+        "server.password is stored in plain text in {}. Use server.password_command, an encrypted value from `config encrypt`, or HUBUUM_CLI__SERVER__PASSWORD instead.",
+        entry
+            .source_detail
+            .as_deref()
+            .unwrap_or("a configuration file")
+    ))
+}
+
+/// Startup warning for HTTPS connections that skip certificate verification.
+fn insecure_tls_warning(config: &AppConfig) -> Option<String> {
+    if config.server.ssl_validation || config.server.protocol != Protocol::Https {
+        return None;
+    }
+    Some(format!(
+        "TLS CERTIFICATE VALIDATION IS DISABLED for {}://{}:{}. The server's identity is not verified, so credentials and data can be intercepted. Set server.ssl_validation = true to re-enable it.",
+        config.server.protocol, config.server.hostname, config.server.port
+    ))
+}
+
 pub async fn login(config: Arc<AppConfig>) -> Result<Arc<BlockingClient<Authenticated>>, AppError> {
-    spawn_blocking(move || {
-        let baseurl = BaseUrl::from_str(&format!(
-            "{}://{}:{}",
-            config.server.protocol, config.server.hostname, config.server.port
-        ))?;
-
-        let client = BlockingClient::builder(baseurl)
-            .validate_certs(config.server.ssl_validation)
-            .build()?;
-
-        authenticate(
-            client,
-            config.server.hostname.as_str(),
-            config.server.identity_scope.as_deref(),
-            config.server.username.as_str(),
-            config.server.password.clone(),
-            config.server.token_file.as_deref(),
+    spawn_blocking(move || connect(&config.server, &config.auth).map(Arc::new))
+        .await
+        .map_err(|err| AppError::CommandExecutionError(err.to_string()))?
+}
+
+/// Builds a client for `--offline` without reaching the server. It carries the stored
+/// token unchecked, or none; a rejected token is renewed once back online.
+pub async fn offline_login(
+    config: Arc<AppConfig>,
+) -> Result<Arc<BlockingClient<Authenticated>>, AppError> {
+    spawn_blocking(move || attach_stored_token(&config.server, &config.auth).map(Arc::new))
+        .await
+        .map_err(|err| AppError::CommandExecutionError(err.to_string()))?
+}
+
+fn attach_stored_token(
+    server: &ServerConfig,
+    auth: &AuthConfig,
+) -> Result<BlockingClient<Authenticated>, AppError> {
+    let token = stored_token(server, auth)?.unwrap_or_else(|| Token::new(String::new()));
+    Ok(unauthenticated_client(server)?.authenticate(token))
+}
+
+/// Logs in again the way [`login`] did, for renewing a session whose token was rejected.
+pub fn relogin(config: Arc<AppConfig>) -> Relogin {
+    Box::new(move || connect(&config.server, &config.auth))
+}
+
+/// Logs in as `config.server` mid-session and makes `config` the active configuration.
+/// Nothing changes unless the login succeeds; afterwards the prompt, token renewals, and
+/// every command use it.
+pub fn switch_login(services: &AppServices, config: AppConfig) -> Result<(), AppError> {
+    let client = connect(&config.server, &config.auth)?;
+    let config = Arc::new(config);
+    init_config(config.clone())?;
+    services.replace_client(client, relogin(config));
+    Ok(())
+}
+
+fn connect(
+    server: &ServerConfig,
+    auth: &AuthConfig,
+) -> Result<BlockingClient<Authenticated>, AppError> {
+    require_online("logging in")?;
+    authenticate(unauthenticated_client(server)?, server, auth)
+}
+
+fn unauthenticated_client(
+    server: &ServerConfig,
+) -> Result<BlockingClient<Unauthenticated>, AppError> {
+    Ok(BlockingClient::builder(server.api_base_url()?)
+        .validate_certs(server.ssl_validation)
+        .build()?)
+}
+
+/// Whether the server accepts the token that [`login`] would try first for `server`,
+/// without falling back to a password. `None` when no token is stored for the login.
+pub(crate) fn stored_token_accepted(
+    server: &ServerConfig,
+    auth: &AuthConfig,
+) -> Result<Option<bool>, AppError> {
+    require_online("checking the stored token")?;
+    let Some(token) = stored_token(server, auth)? else {
+        return Ok(None);
+    };
+    Ok(Some(
+        unauthenticated_client(server)?
+            .login_with_token(token)
+            .is_ok(),
+    ))
+}
+
+/// The token from `server.token_file`, or else the cached token of the login.
+fn stored_token(server: &ServerConfig, auth: &AuthConfig) -> Result<Option<Token>, AppError> {
+    if let Some(token_file) = server.token_file.as_deref() {
+        return Ok(Some(BearerTokenFile::new(token_file)?.read()?));
+    }
+    Ok(TokenStore::load(token_storage(auth, server)?.as_ref())?
+        .find(
+            &server.hostname,
+            server.identity_scope.as_deref(),
+            &server.username,
         )
-        .map(Arc::new)
-    })
-    .await
-    .map_err(|err| AppError::CommandExecutionError(err.to_string()))?
+        .map(|entry| Token::new(entry.token.clone())))
 }
 
 fn authenticate(
     client: BlockingClient<Unauthenticated>,
-    hostname: &str,
-    identity_scope: Option<&str>,
-    username: &str,
-    password: Option<String>,
-    token_file: Option<&str>,
+    server: &ServerConfig,
+    auth: &AuthConfig,
 ) -> Result<BlockingClient<Authenticated>, AppError> {
-    if let Some(token_file) = token_file {
+    if let Some(token_file) = server.token_file.as_deref() {
         let token = BearerTokenFile::new(token_file)?.read()?;
         return client.login_with_token(token).map_err(AppError::from);
     }
 
-    let token = get_token_from_tokenfile(hostname, identity_scope, username)?;
-    if let Some(token) = token {
+    let hostname = server.hostname.as_str();
+    let identity_scope = server.identity_scope.as_deref();
+    let username = server.username.as_str();
+    let storage = token_storage(auth, server)?;
+    let tokens = TokenStore::load(storage.as_ref())?;
+    if let Some(entry) = tokens.find(hostname, identity_scope, username) {
         debug!("Found existing token, testing validity...");
-        if let Ok(client) = client.clone().login_with_token(Token::new(token)) {
+        if let Ok(client) = client
+            .clone()
+            .login_with_token(Token::new(entry.token.clone()))
+        {
+            TokenStore::update(storage.as_ref(), |tokens| {
+                let now = Utc::now();
+                tokens.touch(hostname, identity_scope, username, now);
+                tokens.prune(server.token_prune_days, now);
+            })?;
             return Ok(client);
         }
     }
 
-    let password = match password {
-        Some(password) => password,
-        None => {
+    let password = match (server.password.clone(), server.password_command.as_deref()) {
+        (Some(password), _) => decrypt_value(
+            &password,
+            server.encryption_key_command.as_deref(),
+            "server.password",
+        )?,
+        (None, Some(command)) => password_from_command(command)?,
+        (None, None) => {
             let scope = identity_scope
                 .map(|scope| format!(" via {scope}"))
                 .unwrap_or_default();
@@ -176,16 +355,26 @@ fn authenticate(
     };
     let client = client.login(credentials)?;
 
-    write_token_to_tokenfile(TokenEntry {
-        hostname: hostname.to_string(),
-        identity_scope: identity_scope.map(str::to_string),
-        username: username.to_string(),
-        token: client.token().to_string(),
+    let entry = TokenEntry::new(
+        hostname.to_string(),
+        identity_scope.map(str::to_string),
+        username.to_string(),
+        client.token().to_string(),
+    );
+    TokenStore::update(storage.as_ref(), |tokens| {
+        let now = Utc::now();
+        tokens.upsert(entry, now);
+        tokens.prune(server.token_prune_days, now);
     })?;
 
     Ok(client)
 }
 
+/// Runs `server.password_command` directly (no shell) and uses the first line it prints.
+fn password_from_command(command: &str) -> Result<String, AppError> {
+    secret_from_command("server.password_command", command)
+}
+
 #[derive(Debug, Clone)]
 struct BearerTokenFile(PathBuf);
 
@@ -259,12 +448,18 @@ impl AppRuntime {
             .map(|badge| format!("{badge} "))
             .unwrap_or_default();
         let pagination = pagination.unwrap_or_default();
+        let transaction = if session.in_transaction() {
+            " [txn]"
+        } else {
+            ""
+        };
+        let offline = if is_offline() { " [offline]" } else { "" };
         let base = paint(ThemeRole::Prompt, base);
         if scope.is_empty() {
-            format!("{status}{background}{base}{pagination} > ")
+            format!("{status}{background}{base}{offline}{transaction}{pagination} > ")
         } else {
             format!(
-                "{status}{background}{base} [{}]{pagination} > ",
+                "{status}{background}{base} [{}]{offline}{transaction}{pagination} > ",
                 scope.join(" ")
             )
         }
@@ -277,7 +472,85 @@ mod tests {
 
     use tempfile::tempdir;
 
-    use super::BearerTokenFile;
+    use super::{
+        insecure_tls_warning, password_from_command, plaintext_password_warning, BearerTokenFile,
+        SharedSession,
+    };
+    use crate::config::{AppConfig, ConfigEntry, ConfigSource};
+    use crate::models::Protocol;
+    use crate::transaction::QueuedCommand;
+
+    #[test]
+    fn insecure_tls_warning_only_for_unverified_https() {
+        let mut config = AppConfig::default();
+        config.server.ssl_validation = true;
+        assert!(insecure_tls_warning(&config).is_none());
+
+        config.server.ssl_validation = false;
+        config.server.protocol = Protocol::Https;
+        let warning = insecure_tls_warning(&config).expect("warning for unverified https");
+        assert!(warning.contains("DISABLED"), "{warning}");
+
+        config.server.protocol = Protocol::Http;
+        assert!(insecure_tls_warning(&config).is_none());
+    }
+
+    #[test]
+    fn plaintext_password_warning_only_for_config_files() {
+        let mut entry = ConfigEntry {
+            key: "server.password".to_string(),
+            value: "********".to_string(),
+            source: ConfigSource::UserFile,
+            source_detail: Some("/home/alice/.config/hubuum-cli/config.toml".to_string()),
+            sensitive: true,
+        };
+        let warning = plaintext_password_warning(Some(&entry)).expect("file passwords warn");
+        assert!(warning.contains("/home/alice/.config/hubuum-cli/config.toml"));
+
+        entry.source = ConfigSource::Environment;
+        assert!(plaintext_password_warning(Some(&entry)).is_none());
+        assert!(plaintext_password_warning(None).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn password_command_uses_the_first_output_line() {
+        assert_eq!(
+            password_from_command("printf 'se cret\\nignored\\n'").expect("command should run"),
+            "se cret"
+        );
+        assert!(password_from_command("false").is_err());
+        assert!(password_from_command("true").is_err());
+    }
+
+    #[test]
+    fn transactions_queue_commands_until_taken() {
+        let session = SharedSession::new();
+        assert!(session
+            .queue_command(QueuedCommand {
+                scope: Vec::new(),
+                line: "class delete Host".to_string(),
+            })
+            .is_none());
+
+        session
+            .begin_transaction()
+            .expect("transaction should open");
+        assert!(session.in_transaction());
+        assert!(session.begin_transaction().is_err());
+        let position = session.queue_command(QueuedCommand {
+            scope: vec!["class".to_string()],
+            line: "delete Host".to_string(),
+        });
+        assert_eq!(position, Some(1));
+
+        let queued = session
+            .take_transaction()
+            .expect("queue should be returned");
+        assert_eq!(queued.len(), 1);
+        assert!(!session.in_transaction());
+        assert!(session.take_transaction().is_err());
+    }
 
     #[test]
     fn bearer_token_file_trims_surrounding_whitespace() {
@@ -0,0 +1,368 @@
+use std::fs::{read_to_string, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use hubuum_filter::{OutputEnvelope, OutputShape};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, to_string, Value};
+use shlex::{split, try_quote};
+
+use crate::config::ServerConfig;
+use crate::errors::AppError;
+use crate::files::get_audit_file;
+
+const REDACTED: &str = "REDACTED";
+
+/// One mutating command as recorded in the local audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub server: String,
+    pub user: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_scope: Option<String>,
+    pub command: String,
+    #[serde(default)]
+    pub entity_ids: Vec<i64>,
+    pub result: AuditResult,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditResult {
+    Success,
+    Failure,
+}
+
+impl AuditResult {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+        }
+    }
+}
+
+impl AuditLogEntry {
+    /// Builds an entry for `command_path`, taking the arguments from the raw command line.
+    ///
+    /// `raw_line` is relative to the REPL scope, so everything up to the command name
+    /// is replaced by the full command path. Secret option values are redacted.
+    pub fn new(
+        server: &ServerConfig,
+        command_path: &[String],
+        raw_line: &str,
+        semantic: &[OutputEnvelope],
+        outcome: &Result<(), AppError>,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            server: server.hostname.clone(),
+            user: server.username.clone(),
+            identity_scope: server.identity_scope.clone(),
+            command: command_line(command_path, raw_line),
+            entity_ids: entity_ids(semantic),
+            result: if outcome.is_ok() {
+                AuditResult::Success
+            } else {
+                AuditResult::Failure
+            },
+            error: outcome.as_ref().err().map(ToString::to_string),
+        }
+    }
+
+    /// Whether the entry was recorded by the login of `server`: its hostname, user, and
+    /// identity scope, which is what a profile selects.
+    pub fn is_from(&self, server: &ServerConfig) -> bool {
+        self.server == server.hostname
+            && self.user == server.username
+            && self.identity_scope == server.identity_scope
+    }
+}
+
+/// Appends `entry` to the audit log of the current data directory.
+pub fn record(entry: &AuditLogEntry) -> Result<(), AppError> {
+    append_to(&get_audit_file()?, entry)
+}
+
+/// Reads audit entries recorded at or after `since` by the login of `server`, oldest first.
+///
+/// Without a server, the entries of every login are returned.
+pub fn read(
+    since: DateTime<Utc>,
+    server: Option<&ServerConfig>,
+) -> Result<Vec<AuditLogEntry>, AppError> {
+    read_from(&get_audit_file()?, since, server)
+}
+
+fn append_to(path: &Path, entry: &AuditLogEntry) -> Result<(), AppError> {
+    let mut file = OpenOptions::new().append(true).open(path)?;
+    writeln!(file, "{}", to_string(entry)?)?;
+    Ok(())
+}
+
+fn read_from(
+    path: &Path,
+    since: DateTime<Utc>,
+    server: Option<&ServerConfig>,
+) -> Result<Vec<AuditLogEntry>, AppError> {
+    Ok(read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match from_str::<AuditLogEntry>(line) {
+            Ok(entry) => Some(entry),
+            Err(error) => {
+                debug!("Skipping unreadable audit log line: {error}");
+                None
+            }
+        })
+        .filter(|entry| entry.timestamp >= since)
+        .filter(|entry| server.is_none_or(|server| entry.is_from(server)))
+        .collect())
+}
+
+/// Parses a relative age such as `30m`, `12h`, `1d`, or `2w` into a point in time before `now`.
+///
+/// Absolute RFC 3339 timestamps are accepted as well.
+pub fn parse_since(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, AppError> {
+    parse_age_option("--since", value, now)
+}
+
+/// Like [`parse_since`], naming `option` in the error message.
+pub fn parse_age_option(
+    option: &str,
+    value: &str,
+    now: DateTime<Utc>,
+) -> Result<DateTime<Utc>, AppError> {
+    let value = value.trim();
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    let invalid = || {
+        AppError::ParseError(format!(
+            "Invalid {option} value '{value}'; use an age such as 30m, 12h, 1d, or 2w, or an RFC 3339 timestamp"
+        ))
+    };
+    let split_at = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(split_at);
+    let amount = amount.parse::<i64>().map_err(|_| invalid())?;
+    let age = match unit {
+        "s" => TimeDelta::try_seconds(amount),
+        "m" => TimeDelta::try_minutes(amount),
+        "h" => TimeDelta::try_hours(amount),
+        "d" => TimeDelta::try_days(amount),
+        "w" => TimeDelta::try_weeks(amount),
+        _ => None,
+    }
+    .ok_or_else(invalid)?;
+    now.checked_sub_signed(age).ok_or_else(invalid)
+}
+
+fn command_line(command_path: &[String], raw_line: &str) -> String {
+    let words = split(raw_line).unwrap_or_default();
+    let args = command_path
+        .last()
+        .and_then(|name| words.iter().position(|word| word == name))
+        .map(|position| &words[position + 1..])
+        .unwrap_or_default();
+
+    let mut redact_next = false;
+    let mut parts = command_path.to_vec();
+    for arg in args {
+        if redact_next {
+            parts.push(REDACTED.to_string());
+            redact_next = false;
+            continue;
+        }
+        match arg.split_once('=') {
+            Some((option, _)) if is_secret_option(option) => {
+                parts.push(format!("{option}={REDACTED}"));
+            }
+            _ => {
+                redact_next = is_secret_option(arg);
+                parts.push(arg.clone());
+            }
+        }
+    }
+
+    parts
+        .iter()
+        .map(|part| {
+            try_quote(part)
+                .map(|quoted| quoted.into_owned())
+                .unwrap_or_else(|_| part.replace('\0', ""))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Options whose value is a secret, such as `--password` or `--auth-secret`.
+///
+/// Options that only name where a secret lives (`--secret-ref`, `--password-file`) are kept.
+fn is_secret_option(arg: &str) -> bool {
+    let Some(name) = arg.strip_prefix("--") else {
+        return false;
+    };
+    (name.contains("password") || name.contains("secret") || name.contains("token"))
+        && !name.ends_with("-ref")
+        && !name.ends_with("-file")
+        && !name.ends_with("-id")
+}
+
+fn entity_ids(semantic: &[OutputEnvelope]) -> Vec<i64> {
+    let mut ids = Vec::new();
+    for envelope in semantic {
+        match envelope.shape {
+            OutputShape::Detail => ids.extend(entity_id(&envelope.value)),
+            OutputShape::Rows => {
+                if let Value::Array(rows) = &envelope.value {
+                    ids.extend(rows.iter().filter_map(entity_id));
+                }
+            }
+            _ => {}
+        }
+    }
+    ids.dedup();
+    ids
+}
+
+fn entity_id(value: &Value) -> Option<i64> {
+    let id = value.get("id").or_else(|| value.get("ID"))?;
+    match id {
+        Value::Number(number) => number.as_i64(),
+        Value::String(text) => text.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::config::AppConfig;
+
+    fn path(parts: &[&str]) -> Vec<String> {
+        parts.iter().map(|part| (*part).to_string()).collect()
+    }
+
+    #[test]
+    fn command_line_uses_full_path_and_redacts_secrets() {
+        assert_eq!(
+            command_line(&path(&["class", "create"]), "create --name 'My Hosts'"),
+            "class create --name 'My Hosts'"
+        );
+        assert_eq!(
+            command_line(
+                &path(&["event", "sink", "create"]),
+                "sink create --name hook --auth-secret hunter2 --secret-ref vault:x"
+            ),
+            "event sink create --name hook --auth-secret REDACTED --secret-ref vault:x"
+        );
+        assert_eq!(
+            command_line(&path(&["user", "create"]), "user create --password=hunter2"),
+            "user create '--password=REDACTED'"
+        );
+    }
+
+    #[test]
+    fn entity_ids_come_from_detail_and_row_output() {
+        let ids = entity_ids(&[
+            OutputEnvelope::detail(json!({ "ID": "7", "Name": "Hosts" }), Vec::new()),
+            OutputEnvelope::rows(
+                vec![
+                    json!({ "id": 8 }),
+                    json!({ "id": 9 }),
+                    json!({ "name": "x" }),
+                ],
+                Vec::new(),
+            ),
+            OutputEnvelope::message(json!({ "message": "done" })),
+        ]);
+        assert_eq!(ids, vec![7, 8, 9]);
+    }
+
+    #[test]
+    fn since_accepts_ages_and_timestamps() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        assert_eq!(
+            parse_since("1d", now).unwrap(),
+            Utc.with_ymd_and_hms(2026, 3, 9, 12, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_since("90m", now).unwrap(),
+            Utc.with_ymd_and_hms(2026, 3, 10, 10, 30, 0).unwrap()
+        );
+        assert_eq!(
+            parse_since("2026-03-01T00:00:00Z", now).unwrap(),
+            Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap()
+        );
+        for value in ["", "d", "1y", "-1d", "1.5h"] {
+            assert!(parse_since(value, now).is_err(), "{value:?} should fail");
+        }
+    }
+
+    #[test]
+    fn entries_are_appended_and_filtered_by_time_and_login() {
+        let directory = tempdir().expect("temporary directory should be created");
+        let file = directory.path().join("audit.jsonl");
+        std::fs::write(&file, "").expect("audit fixture should be written");
+
+        let old = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        let new = Utc.with_ymd_and_hms(2026, 3, 10, 0, 0, 0).unwrap();
+        for (timestamp, server, user, identity_scope) in [
+            (old, "a.example", "admin", None),
+            (new, "a.example", "admin", None),
+            (new, "a.example", "admin", Some("corp")),
+            (new, "a.example", "alice", None),
+            (new, "b.example", "admin", None),
+        ] {
+            let entry = AuditLogEntry {
+                timestamp,
+                server: server.to_string(),
+                user: user.to_string(),
+                identity_scope: identity_scope.map(str::to_string),
+                command: "class delete Hosts".to_string(),
+                entity_ids: Vec::new(),
+                result: AuditResult::Success,
+                error: None,
+            };
+            append_to(&file, &entry).expect("entry should be appended");
+        }
+
+        let mut login = AppConfig::default().server;
+        login.hostname = "a.example".to_string();
+        login.username = "admin".to_string();
+        let since = Utc.with_ymd_and_hms(2026, 3, 5, 0, 0, 0).unwrap();
+        let entries = read_from(&file, since, Some(&login)).expect("log should read");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].timestamp, new);
+        assert_eq!(entries[0].identity_scope, None);
+
+        login.identity_scope = Some("corp".to_string());
+        let entries = read_from(&file, old, Some(&login)).expect("log should read");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].identity_scope.as_deref(), Some("corp"));
+
+        assert_eq!(read_from(&file, old, None).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn entries_written_before_identity_scopes_were_recorded_still_read() {
+        let entry = from_str::<AuditLogEntry>(
+            r#"{"timestamp":"2026-03-10T00:00:00Z","server":"a.example","user":"admin","command":"class delete Hosts","entity_ids":[],"result":"success"}"#,
+        )
+        .expect("entry without an identity scope should parse");
+        assert_eq!(entry.identity_scope, None);
+        assert!(!to_string(&entry).unwrap().contains("identity_scope"));
+    }
+}
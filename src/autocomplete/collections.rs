@@ -1,15 +1,27 @@
 use strum::IntoEnumIterator;
 
+use super::shared::comma_completion_prefix;
 use crate::{domain::CollectionPermission, services::CompletionContext};
 
 pub fn collections(ctx: &CompletionContext, prefix: &str, _parts: &[String]) -> Vec<String> {
     ctx.collections(prefix)
 }
 
-#[allow(dead_code)]
+/// Completes the last entry of a comma-separated list of collection names.
+pub fn collection_list(ctx: &CompletionContext, prefix: &str, _parts: &[String]) -> Vec<String> {
+    let (base, segment_prefix) = comma_completion_prefix(prefix);
+    ctx.collections(segment_prefix)
+        .into_iter()
+        .map(|collection| format!("{base}{collection}"))
+        .collect()
+}
+
+/// Completes the last entry of a comma-separated list of permission names.
 pub fn permissions(_ctx: &CompletionContext, prefix: &str, _parts: &[String]) -> Vec<String> {
+    let (base, segment_prefix) = comma_completion_prefix(prefix);
     CollectionPermission::iter()
-        .filter(|permission| permission.to_string().starts_with(prefix))
         .map(|permission| permission.to_string())
+        .filter(|permission| permission.starts_with(segment_prefix))
+        .map(|permission| format!("{base}{permission}"))
         .collect()
 }
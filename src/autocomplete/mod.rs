@@ -9,7 +9,7 @@ mod shared;
 mod sorts;
 
 pub use classes::classes;
-pub use collections::collections;
+pub use collections::{collection_list, collections, permissions};
 pub use events::{
     audit_event_ids, audit_resource_names, audit_resources, event_actions, event_delivery_ids,
     event_entity_types, event_sink_kinds, event_sinks, event_subscriptions, principal_names,
@@ -29,9 +29,10 @@ pub use objects::{
 };
 pub use shared::{
     bool, computed_operations, computed_result_types, config_keys, config_values,
-    export_content_types, file_paths, object_data_columns, output_formats, principal_kinds,
-    remote_auth_types, remote_http_methods, remote_subject_kinds, remote_subject_types,
-    search_kinds, task_kinds, task_statuses, theme_names,
+    export_content_types, file_paths, object_data_columns, on_off, output_formats, principal_kinds,
+    profile_names, remote_auth_types, remote_http_methods, remote_subject_kinds,
+    remote_subject_types, search_kinds, since_ages, task_kinds, task_statuses, theme_names,
+    timeline_groupings, timeline_periods,
 };
 pub(crate) use sorts::complete_sort_clause;
 pub use sorts::{
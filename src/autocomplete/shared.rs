@@ -2,8 +2,6 @@ use std::env::var_os;
 use std::fs::read_dir;
 use std::path::{Path, PathBuf, MAIN_SEPARATOR};
 
-use shlex::split;
-
 use crate::config::{
     config_key_names, config_value_candidates, get_config, theme_value_candidates,
 };
@@ -14,10 +12,22 @@ pub fn bool(_ctx: &CompletionContext, _prefix: &str, _parts: &[String]) -> Vec<S
     vec!["true".to_string(), "false".to_string()]
 }
 
+pub fn on_off(_ctx: &CompletionContext, prefix: &str, _parts: &[String]) -> Vec<String> {
+    complete_values(&["on", "off"], prefix)
+}
+
 pub fn output_formats(_ctx: &CompletionContext, prefix: &str, _parts: &[String]) -> Vec<String> {
     complete_values(&["text", "json", "jsonl", "csv", "tsv"], prefix)
 }
 
+pub fn profile_names(_ctx: &CompletionContext, prefix: &str, _parts: &[String]) -> Vec<String> {
+    get_config()
+        .profile_names()
+        .into_iter()
+        .filter(|name| name.starts_with(prefix))
+        .collect()
+}
+
 pub fn theme_names(_ctx: &CompletionContext, prefix: &str, _parts: &[String]) -> Vec<String> {
     theme_value_candidates()
         .into_iter()
@@ -25,6 +35,10 @@ pub fn theme_names(_ctx: &CompletionContext, prefix: &str, _parts: &[String]) ->
         .collect()
 }
 
+pub fn since_ages(_ctx: &CompletionContext, prefix: &str, _parts: &[String]) -> Vec<String> {
+    complete_values(&["30m", "1h", "12h", "1d", "7d", "2w"], prefix)
+}
+
 pub fn task_kinds(_ctx: &CompletionContext, prefix: &str, _parts: &[String]) -> Vec<String> {
     complete_values(
         &["import", "export", "backup", "reindex", "remotecall"],
@@ -140,11 +154,26 @@ pub fn search_kinds(_ctx: &CompletionContext, prefix: &str, _parts: &[String]) -
     complete_values(&["collection", "class", "object"], prefix)
 }
 
+pub fn timeline_periods(_ctx: &CompletionContext, prefix: &str, _parts: &[String]) -> Vec<String> {
+    complete_values(&["week", "month"], prefix)
+}
+
+pub fn timeline_groupings(
+    _ctx: &CompletionContext,
+    prefix: &str,
+    _parts: &[String],
+) -> Vec<String> {
+    complete_values(&["collection", "class"], prefix)
+}
+
 pub fn principal_kinds(_ctx: &CompletionContext, prefix: &str, _parts: &[String]) -> Vec<String> {
     complete_values(&["user", "group", "service-account"], prefix)
 }
 
-pub fn file_paths(_ctx: &CompletionContext, prefix: &str, _parts: &[String]) -> Vec<String> {
+pub fn file_paths(ctx: &CompletionContext, prefix: &str, _parts: &[String]) -> Vec<String> {
+    if ctx.offers_fixed_values_only() {
+        return Vec::new();
+    }
     file_path_candidates(prefix)
 }
 
@@ -296,7 +325,7 @@ fn file_path_candidates(prefix: &str) -> Vec<String> {
             if is_dir {
                 value.push(MAIN_SEPARATOR);
             }
-            Some(shell_escape_path(&value))
+            Some(value)
         })
         .collect::<Vec<_>>();
 
@@ -305,11 +334,7 @@ fn file_path_candidates(prefix: &str) -> Vec<String> {
 }
 
 fn normalize_path_prefix(prefix: &str) -> (String, String) {
-    let unescaped = split(prefix)
-        .and_then(|parts| (parts.len() == 1).then(|| parts[0].clone()))
-        .unwrap_or_else(|| prefix.replace("\\ ", " "));
-
-    if let Some(rest) = unescaped.strip_prefix("~/") {
+    if let Some(rest) = prefix.strip_prefix("~/") {
         if let Some(home) = var_os("HOME") {
             return (
                 format!("~/{rest}"),
@@ -318,7 +343,7 @@ fn normalize_path_prefix(prefix: &str) -> (String, String) {
         }
     }
 
-    (unescaped.clone(), unescaped)
+    (prefix.to_string(), prefix.to_string())
 }
 
 fn path_parent_text(path: &str) -> String {
@@ -331,39 +356,6 @@ fn completion_is_file(value: &str) -> bool {
     !value.ends_with(MAIN_SEPARATOR)
 }
 
-fn shell_escape_path(path: &str) -> String {
-    let mut escaped = String::with_capacity(path.len());
-    for ch in path.chars() {
-        if matches!(
-            ch,
-            ' ' | '\t'
-                | '\n'
-                | '\\'
-                | '\''
-                | '"'
-                | '$'
-                | '&'
-                | ';'
-                | '|'
-                | '<'
-                | '>'
-                | '('
-                | ')'
-                | '['
-                | ']'
-                | '{'
-                | '}'
-                | '*'
-                | '?'
-                | '!'
-        ) {
-            escaped.push('\\');
-        }
-        escaped.push(ch);
-    }
-    escaped
-}
-
 fn config_key_from_parts(parts: &[String]) -> Option<&str> {
     parts
         .windows(2)
@@ -372,6 +364,14 @@ fn config_key_from_parts(parts: &[String]) -> Option<&str> {
             _ => None,
         })
         .next_back()
+        .or_else(|| {
+            // `config set <key> <value>` names the key positionally.
+            let set = parts.iter().position(|part| part == "set")?;
+            parts[set + 1..]
+                .iter()
+                .find(|part| !part.starts_with('-'))
+                .map(String::as_str)
+        })
 }
 
 fn object_list_class_column_values(
@@ -441,7 +441,7 @@ fn object_class_computed_field_candidates(fields: &[String], prefix: &str) -> Ve
         .collect()
 }
 
-fn comma_completion_prefix(prefix: &str) -> (&str, &str) {
+pub(super) fn comma_completion_prefix(prefix: &str) -> (&str, &str) {
     prefix
         .rfind(',')
         .map(|index| (&prefix[..=index], &prefix[index + 1..]))
@@ -511,6 +511,18 @@ mod tests {
         assert_eq!(config_key_from_parts(&parts), Some("output.table_style"));
     }
 
+    #[test]
+    fn config_key_from_parts_accepts_positional_key() {
+        let parts = vec![
+            "config".to_string(),
+            "set".to_string(),
+            "output.table_style".to_string(),
+            "--value".to_string(),
+        ];
+
+        assert_eq!(config_key_from_parts(&parts), Some("output.table_style"));
+    }
+
     #[test]
     fn config_values_complete_object_list_data_column_modes() {
         let parts = vec![
@@ -570,7 +582,7 @@ mod tests {
     }
 
     #[test]
-    fn file_path_candidates_escape_spaces() {
+    fn file_path_candidates_keep_spaces_for_the_repl_to_quote() {
         let dir = tempdir().expect("temp dir should be created");
         write(dir.path().join("import payload.json"), "{}").expect("file should be written");
 
@@ -579,7 +591,7 @@ mod tests {
 
         assert!(suggestions
             .iter()
-            .any(|value| value.ends_with("import\\ payload.json")));
+            .any(|value| value.ends_with("import payload.json")));
     }
 
     #[test]
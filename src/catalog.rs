@@ -1,21 +1,26 @@
 use std::any::TypeId;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Debug, Formatter, Result as FmtResult};
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use hubuum_filter::{help_topics, topic_help, verb_summaries, PipeStage};
+use serde::Serialize;
 
-use crate::app::AppRuntime;
+use crate::app::{AppRuntime, SharedSession};
+use crate::build_info::VERSION;
+use crate::command_defaults::{configured_default, CommandDefaults};
 use crate::commands::{AutoCompleter, CliOption};
+use crate::config::{get_config, AppConfig};
 use crate::errors::AppError;
 use crate::list_query::{completion_operators, FilterOperatorProfile};
 use crate::output::OutputSnapshot;
 use crate::redirection::OutputRedirect;
-use crate::services::filter_specs_for_command_path;
+use crate::services::{filter_specs_for_command_path, AppServices};
 use crate::suggestions::did_you_mean_message;
 use crate::terminal::terminal_width;
 use crate::theme::{paint, paint_command, ThemeRole};
+use crate::tokenizer::{CommandTokenizer, RAW_OPTION_SUFFIX};
 
 #[derive(Debug, Clone)]
 pub struct OptionSpec {
@@ -31,6 +36,8 @@ pub struct OptionSpec {
     pub nargs: Option<usize>,
     pub repeatable: bool,
     pub value_source: bool,
+    pub raw: bool,
+    pub positional: Option<usize>,
     pub completion: CompletionSpec,
 }
 
@@ -54,6 +61,12 @@ pub struct CommandSpec {
     pub long_about: Option<String>,
     pub examples: Option<String>,
     pub options: Vec<OptionSpec>,
+    /// Set for commands that authenticate with credentials of their own, where a rejected
+    /// request means those credentials are wrong rather than that the session expired.
+    pub own_credentials: bool,
+    /// Set for commands that change server state. They are queued inside a transaction,
+    /// refused offline, written to the audit log, and not retried after a renewed login.
+    pub mutating: bool,
     pub handler: Arc<dyn AsyncCommandHandler>,
 }
 
@@ -65,6 +78,8 @@ impl Debug for CommandSpec {
             .field("long_about", &self.long_about)
             .field("examples", &self.examples)
             .field("options", &self.options)
+            .field("own_credentials", &self.own_credentials)
+            .field("mutating", &self.mutating)
             .finish()
     }
 }
@@ -76,6 +91,12 @@ pub trait AsyncCommandHandler: Send + Sync {
         ctx: CommandContext,
         invocation: CommandInvocation,
     ) -> Result<CommandOutcome, AppError>;
+
+    /// Checks the options and positionals in `tokens` without running the command, as
+    /// done before a command is queued in a transaction.
+    fn validate(&self, _tokens: &CommandTokenizer) -> Result<(), AppError> {
+        Ok(())
+    }
 }
 
 #[derive(Clone)]
@@ -83,9 +104,33 @@ pub struct CommandCatalog {
     root: ScopeSpec,
 }
 
+/// Everything a command may need while executing: the API services, the active
+/// configuration, the REPL session, and the command catalog it was dispatched from.
+///
+/// Command output is written through the functions in [`crate::output`].
 #[derive(Clone)]
 pub struct CommandContext {
     pub app: Arc<AppRuntime>,
+    pub session: SharedSession,
+}
+
+impl CommandContext {
+    pub fn services(&self) -> &AppServices {
+        self.app.services.as_ref()
+    }
+
+    /// The current configuration, including changes made with `config set` in this session.
+    pub fn config(&self) -> Arc<AppConfig> {
+        get_config()
+    }
+
+    pub fn catalog(&self) -> &CommandCatalog {
+        self.app.catalog.as_ref()
+    }
+
+    pub fn session(&self) -> &SharedSession {
+        &self.session
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -94,6 +139,8 @@ pub struct CommandInvocation {
     pub command_path: Vec<String>,
     pub pipeline: Vec<PipeStage>,
     pub pipeline_suffix: Option<String>,
+    /// Whether the output is redirected to a file once the command finishes.
+    pub redirected: bool,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -293,19 +340,38 @@ impl CommandCatalog {
         lines.join("\n")
     }
 
-    pub fn render_command_help(&self, command_path: &[String]) -> Result<String, AppError> {
-        if command_path.is_empty() {
-            return Err(AppError::CommandNotFound("".to_string()));
+    /// Long names (without dashes) of every option that accepts a `--<name>-raw` value.
+    pub fn raw_option_names(&self) -> BTreeSet<String> {
+        let mut names = BTreeSet::new();
+        collect_raw_option_names(&self.root, &mut names);
+        names
+    }
+
+    /// Describes every registered scope, command, and option for external tooling.
+    pub fn metadata(&self) -> CatalogMetadata {
+        let root = scope_metadata(&self.root, &[]);
+        CatalogMetadata {
+            version: VERSION,
+            commands: root.commands,
+            scopes: root.scopes,
         }
-        let scope = &command_path[..command_path.len() - 1];
-        let name = &command_path[command_path.len() - 1];
+    }
+
+    fn command(&self, command_path: &[String]) -> Result<&CommandSpec, AppError> {
+        let Some((name, scope)) = command_path.split_last() else {
+            return Err(AppError::CommandNotFound("".to_string()));
+        };
         let scope_spec = self
             .scope(scope)
             .ok_or_else(|| AppError::CommandNotFound(scope.join(" ")))?;
-        let command = scope_spec
+        scope_spec
             .commands
             .get(name)
-            .ok_or_else(|| AppError::CommandNotFound(name.clone()))?;
+            .ok_or_else(|| AppError::CommandNotFound(name.clone()))
+    }
+
+    pub fn render_command_help(&self, command_path: &[String]) -> Result<String, AppError> {
+        let command = self.command(command_path)?;
 
         let mut help = String::new();
         help.push_str(&paint(ThemeRole::Heading, command_path.join(" ")));
@@ -356,6 +422,16 @@ impl CommandCatalog {
                 if option.value_source {
                     annotations.push("value-source");
                 }
+                let positional_annotation;
+                if let Some(index) = option.positional {
+                    positional_annotation = format!("positional {}", index + 1);
+                    annotations.push(&positional_annotation);
+                }
+                let raw_annotation;
+                if let (true, Some(long)) = (option.raw, &option.long) {
+                    raw_annotation = format!("raw: {long}{RAW_OPTION_SUFFIX}");
+                    annotations.push(&raw_annotation);
+                }
                 let annotations = if annotations.is_empty() {
                     String::new()
                 } else {
@@ -398,6 +474,131 @@ impl CommandCatalog {
         Ok(help.trim_end().to_string())
     }
 
+    /// Renders the man-page style help of `help <command> --long`: every option with its
+    /// type, configured default, and the fixed values `choices` finds for it.
+    pub fn render_command_manual(
+        &self,
+        command_path: &[String],
+        defaults: &CommandDefaults,
+        choices: &dyn Fn(&OptionSpec) -> Vec<String>,
+    ) -> Result<String, AppError> {
+        let command = self.command(command_path)?;
+        let path = command_path.join(" ");
+        let mut sections = Vec::new();
+
+        let name = match &command.about {
+            Some(about) => format!("{path} - {about}"),
+            None => path.clone(),
+        };
+        sections.push(manual_section("NAME", [name]));
+
+        sections.push(manual_section(
+            "SYNOPSIS",
+            [synopsis(&path, &command.options)],
+        ));
+
+        if let Some(long_about) = &command.long_about {
+            sections.push(manual_section("DESCRIPTION", long_about.lines()));
+        }
+
+        if !command.options.is_empty() {
+            let mut lines = Vec::new();
+            for option in &command.options {
+                let names = [option.short.as_deref(), option.long.as_deref()]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>();
+                let names = if names.is_empty() {
+                    option.name.clone()
+                } else {
+                    names.join(", ")
+                };
+                if option.flag {
+                    lines.push(paint(ThemeRole::Command, names));
+                } else {
+                    lines.push(format!(
+                        "{} <{}>",
+                        paint(ThemeRole::Command, names),
+                        option.field_type_help
+                    ));
+                }
+                lines.push(format!("    {}", option.help));
+                let mut notes = Vec::new();
+                if option.required {
+                    notes.push("required".to_string());
+                }
+                if let Some(index) = option.positional {
+                    notes.push(format!("may be given as positional {}", index + 1));
+                }
+                if option.repeatable {
+                    notes.push("may be repeated".to_string());
+                }
+                if let Some(nargs) = option.nargs {
+                    let plural = if nargs == 1 { "" } else { "s" };
+                    notes.push(format!("takes {nargs} value{plural}"));
+                }
+                if option.value_source {
+                    notes.push("accepts file:// and http(s):// value sources".to_string());
+                }
+                if let (true, Some(long)) = (option.raw, &option.long) {
+                    notes.push(format!("raw form: {long}{RAW_OPTION_SUFFIX}"));
+                }
+                if !notes.is_empty() {
+                    lines.push(format!("    {}.", capitalized(&notes.join("; "))));
+                }
+                if let Some(values) =
+                    configured_default(defaults, command_path, &option.to_cli_option())
+                {
+                    lines.push(format!(
+                        "    Default: {} (from defaults.\"{path}\")",
+                        values.join(" ")
+                    ));
+                }
+                let choices = choices(option);
+                if !choices.is_empty() {
+                    lines.push(format!("    Choices: {}", choices.join(", ")));
+                }
+                lines.push(String::new());
+            }
+            lines.pop();
+            sections.push(manual_section("OPTIONS", lines));
+        }
+
+        if let Some(where_help) = render_where_help(command_path) {
+            sections.push(manual_section(
+                "WHERE",
+                where_help.lines().skip(1).map(unindented),
+            ));
+        }
+
+        if let Some(pagination_help) = render_pagination_help(command) {
+            sections.push(manual_section(
+                "PAGINATION",
+                pagination_help.lines().skip(1).map(unindented),
+            ));
+        }
+
+        if let Some(examples) = &command.examples {
+            sections.push(manual_section(
+                "EXAMPLES",
+                examples
+                    .lines()
+                    .map(|line| paint_command(format!("{path} {line}"))),
+            ));
+        }
+
+        sections.push(manual_section(
+            "SEE ALSO",
+            [format!(
+                "{}, {}",
+                paint_command(format!("help {path}")),
+                paint_command("help pipe")
+            )],
+        ));
+
+        Ok(sections.join("\n\n"))
+    }
+
     pub fn render_pipe_topic_help(&self, topic: Option<&str>) -> Result<String, AppError> {
         render_pipe_topic_help(topic)
     }
@@ -407,6 +608,70 @@ impl CommandCatalog {
     }
 }
 
+/// A manual section: its title, then `lines` indented beneath it.
+fn manual_section<T: AsRef<str>>(title: &str, lines: impl IntoIterator<Item = T>) -> String {
+    let mut section = paint(ThemeRole::Heading, title);
+    for line in lines {
+        let line = line.as_ref().trim_end();
+        section.push('\n');
+        if !line.is_empty() {
+            section.push_str("    ");
+            section.push_str(line);
+        }
+    }
+    section
+}
+
+/// The command line shape of a command: its positionals, then the options it requires,
+/// then `[options]` when it has others.
+fn synopsis(path: &str, options: &[OptionSpec]) -> String {
+    let mut positionals = options
+        .iter()
+        .filter_map(|option| option.positional.map(|index| (index, option)))
+        .collect::<Vec<_>>();
+    positionals.sort_by_key(|(index, _)| *index);
+
+    let mut words = vec![paint_command(path)];
+    for (_, option) in &positionals {
+        if option.required {
+            words.push(format!("<{}>", option.name));
+        } else {
+            words.push(format!("[<{}>]", option.name));
+        }
+    }
+    for option in options
+        .iter()
+        .filter(|option| option.required && option.positional.is_none())
+    {
+        let name = option
+            .long
+            .clone()
+            .or_else(|| option.short.clone())
+            .unwrap_or_else(|| option.name.clone());
+        if option.flag {
+            words.push(name);
+        } else {
+            words.push(format!("{name} <{}>", option.field_type_help));
+        }
+    }
+    if options.iter().any(|option| !option.required) {
+        words.push("[options]".to_string());
+    }
+    words.join(" ")
+}
+
+fn unindented(line: &str) -> &str {
+    line.strip_prefix("  ").unwrap_or(line)
+}
+
+fn capitalized(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
 fn command_not_found_message(part: &str, scope: &ScopeSpec) -> String {
     let candidates = scope
         .scopes
@@ -611,6 +876,10 @@ fn render_shell_topic_help(topic: Option<&str>) -> Result<String, AppError> {
                     "  API-backed completions can be disabled with {}.",
                     paint_command("--completion-api-disable true")
                 ));
+                line!(format!(
+                    "  {} also stops them, along with every other request to the server.",
+                    paint_command("offline on")
+                ));
             }
             "redirects" => {
                 line!(format!(
@@ -625,6 +894,11 @@ fn render_shell_topic_help(topic: Option<&str>) -> Result<String, AppError> {
                 line!(
                     "  each: templates accept field placeholders such as {Name}, {value}, and {n}."
                 );
+                line!(format!(
+                    "  Use {} to pipe rendered output into a shell command such as {}.",
+                    paint_command("| X <command>"),
+                    paint_command("| X grep web | wc -l")
+                ));
                 line!("  Redirect operators must be standalone, whitespace-delimited tokens.");
                 line!("  Parent directories must already exist.");
                 line!("  File redirects honor output.color; auto and never strip ANSI styling.");
@@ -634,6 +908,24 @@ fn render_shell_topic_help(topic: Option<&str>) -> Result<String, AppError> {
                 ));
                 line!("  Redirect paths complete like normal file path arguments.");
             }
+            "transactions" => {
+                line!(format!(
+                    "  Type {} to start queueing commands that change server state.",
+                    paint_command("begin")
+                ));
+                line!(
+                    "  Queued commands are validated when entered and shown with their position."
+                );
+                line!("  Read-only and local commands still run immediately.");
+                line!(format!(
+                    "  Type {} to send the queue in order, or {} to discard it.",
+                    paint_command("commit"),
+                    paint_command("rollback")
+                ));
+                line!("  Commit stops at the first failure; earlier commands stay applied.");
+                line!("  The prompt shows [txn] while a transaction is open.");
+                line!("  Commands can be queued while offline; commit needs the server.");
+            }
             _ => return Err(AppError::CommandNotFound(format!("shell {topic}"))),
         }
         return Ok(lines.join("\n"));
@@ -663,6 +955,10 @@ fn render_shell_topic_help(topic: Option<&str>) -> Result<String, AppError> {
     ));
     line!(format!("  {}", paint_command("help shell completion")));
     line!(format!("  {}", paint_command("help shell redirects")));
+    line!(format!(
+        "  {} Queue changes with begin, commit, and rollback.",
+        paint_command("help shell transactions")
+    ));
     line!("");
     line!("Pipes:");
     line!(format!(
@@ -844,6 +1140,8 @@ impl OptionSpec {
             nargs: self.nargs,
             repeatable: self.repeatable,
             value_source: self.value_source,
+            raw: self.raw,
+            positional: self.positional,
             help: self.help.clone(),
             field_type: self.field_type,
             field_type_help: self.field_type_help.clone(),
@@ -867,6 +1165,131 @@ fn render_tree_scope(scope: &ScopeSpec, prefix: String, lines: &mut Vec<String>)
     }
 }
 
+/// Machine-readable view of the command catalog, as printed by `help --json`.
+#[derive(Debug, Serialize)]
+pub struct CatalogMetadata {
+    pub version: &'static str,
+    pub commands: Vec<CommandMetadata>,
+    pub scopes: Vec<ScopeMetadata>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScopeMetadata {
+    pub name: String,
+    pub path: Vec<String>,
+    pub commands: Vec<CommandMetadata>,
+    pub scopes: Vec<ScopeMetadata>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommandMetadata {
+    pub name: String,
+    pub path: Vec<String>,
+    pub about: Option<String>,
+    pub long_about: Option<String>,
+    /// Complete invocations, including the command path.
+    pub examples: Vec<String>,
+    pub options: Vec<OptionMetadata>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OptionMetadata {
+    pub name: String,
+    pub short: Option<String>,
+    pub long: Option<String>,
+    pub help: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+    pub required: bool,
+    pub flag: bool,
+    pub repeatable: bool,
+    pub nargs: Option<usize>,
+    pub value_source: bool,
+    pub raw: bool,
+    pub positional: Option<usize>,
+    pub dynamic_completion: bool,
+}
+
+fn collect_raw_option_names(scope: &ScopeSpec, names: &mut BTreeSet<String>) {
+    for command in scope.commands.values() {
+        names.extend(
+            command
+                .options
+                .iter()
+                .filter(|option| option.raw)
+                .filter_map(|option| option.long.as_deref())
+                .map(|long| long.trim_start_matches('-').to_string()),
+        );
+    }
+    for child in scope.scopes.values() {
+        collect_raw_option_names(child, names);
+    }
+}
+
+fn scope_metadata(scope: &ScopeSpec, path: &[String]) -> ScopeMetadata {
+    let commands = scope
+        .commands
+        .values()
+        .map(|command| {
+            let mut command_path = path.to_vec();
+            command_path.push(command.name.clone());
+            command_metadata(command, command_path)
+        })
+        .collect();
+    let scopes = scope
+        .scopes
+        .iter()
+        .map(|(name, nested)| {
+            let mut nested_path = path.to_vec();
+            nested_path.push(name.clone());
+            scope_metadata(nested, &nested_path)
+        })
+        .collect();
+    ScopeMetadata {
+        name: scope.name.clone(),
+        path: path.to_vec(),
+        commands,
+        scopes,
+    }
+}
+
+fn command_metadata(command: &CommandSpec, path: Vec<String>) -> CommandMetadata {
+    let examples = command
+        .examples
+        .as_deref()
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| format!("{} {}", path.join(" "), line.trim()))
+        .collect();
+    CommandMetadata {
+        name: command.name.clone(),
+        about: command.about.clone(),
+        long_about: command.long_about.clone(),
+        examples,
+        options: command
+            .options
+            .iter()
+            .map(|option| OptionMetadata {
+                name: option.name.clone(),
+                short: option.short.clone(),
+                long: option.long.clone(),
+                help: option.help.clone(),
+                field_type: option.field_type_help.clone(),
+                required: option.required,
+                flag: option.flag,
+                repeatable: option.repeatable,
+                nargs: option.nargs,
+                value_source: option.value_source,
+                raw: option.raw,
+                positional: option.positional,
+                dynamic_completion: matches!(option.completion, CompletionSpec::Dynamic(_)),
+            })
+            .collect(),
+        path,
+    }
+}
+
 pub struct ResolvedCommand<'a> {
     pub scope_path: Vec<String>,
     pub command_path: Vec<String>,
@@ -877,8 +1300,9 @@ pub struct ResolvedCommand<'a> {
 mod tests {
     use super::{
         command_help_fragment, render_scope_summary_at_width, scope_command_summary,
-        AsyncCommandHandler, CommandCatalogBuilder, CommandContext, CommandInvocation,
-        CommandOutcome, CommandSpec, CompletionSpec, OptionSpec, ScopeAction, ScopeSpec,
+        AsyncCommandHandler, CommandCatalogBuilder, CommandContext, CommandDefaults,
+        CommandInvocation, CommandOutcome, CommandSpec, CompletionSpec, OptionSpec, ScopeAction,
+        ScopeSpec,
     };
     use async_trait::async_trait;
     use regex::Regex;
@@ -916,6 +1340,8 @@ mod tests {
             long_about: None,
             examples: None,
             options: Vec::new(),
+            own_credentials: false,
+            mutating: false,
             handler: Arc::new(NoopHandler),
         }
     }
@@ -943,6 +1369,45 @@ mod tests {
         assert!(catalog.resolve_scope(&[], &["class".to_string()]).is_some());
     }
 
+    #[test]
+    fn metadata_describes_nested_commands_options_and_examples() {
+        let catalog = crate::commands::build_command_catalog();
+        let metadata = serde_json::to_value(catalog.metadata()).expect("metadata serializes");
+
+        assert!(metadata["commands"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|command| command["name"] == "help"));
+        let class = metadata["scopes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|scope| scope["name"] == "class")
+            .expect("class scope should be described");
+        let create = class["commands"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|command| command["name"] == "create")
+            .expect("class create should be described");
+        assert_eq!(create["path"], serde_json::json!(["class", "create"]));
+        let name = create["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|option| option["name"] == "name")
+            .expect("class create should have --name");
+        assert_eq!(name["long"], "--name");
+        assert_eq!(name["type"], "string");
+        assert_eq!(name["required"], true);
+        assert!(create["examples"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|example| example.as_str().unwrap().starts_with("class create ")));
+    }
+
     #[test]
     fn render_command_help_includes_option_metadata() {
         let mut builder = CommandCatalogBuilder::new();
@@ -960,6 +1425,8 @@ mod tests {
             nargs: None,
             repeatable: false,
             value_source: false,
+            raw: false,
+            positional: None,
             completion: CompletionSpec::None,
         });
         spec.options.push(OptionSpec {
@@ -975,6 +1442,8 @@ mod tests {
             nargs: Some(3),
             repeatable: true,
             value_source: false,
+            raw: false,
+            positional: None,
             completion: CompletionSpec::None,
         });
         builder.add_command(&["class"], spec);
@@ -990,6 +1459,95 @@ mod tests {
         assert!(help.contains("[repeatable, nargs=3]"));
     }
 
+    #[test]
+    fn render_command_manual_lists_defaults_and_choices() {
+        let mut builder = CommandCatalogBuilder::new();
+        let mut spec = command("list");
+        spec.long_about = Some("List classes in a collection.".to_string());
+        spec.examples = Some("--format json".to_string());
+        spec.options.push(OptionSpec {
+            name: "collection".to_string(),
+            short: Some("-N".to_string()),
+            long: Some("--collection".to_string()),
+            help: "Collection to list".to_string(),
+            field_type_help: "string".to_string(),
+            field_type: TypeId::of::<String>(),
+            required: true,
+            flag: false,
+            greedy: false,
+            nargs: None,
+            repeatable: false,
+            value_source: false,
+            raw: false,
+            positional: Some(0),
+            completion: CompletionSpec::None,
+        });
+        spec.options.push(OptionSpec {
+            name: "format".to_string(),
+            short: None,
+            long: Some("--format".to_string()),
+            help: "Output format".to_string(),
+            field_type_help: "string".to_string(),
+            field_type: TypeId::of::<String>(),
+            required: false,
+            flag: false,
+            greedy: false,
+            nargs: None,
+            repeatable: false,
+            value_source: false,
+            raw: false,
+            positional: None,
+            completion: CompletionSpec::None,
+        });
+        builder.add_command(&["class"], spec);
+        let catalog = builder.build();
+        let defaults = CommandDefaults::from([(
+            "class  list".to_string(),
+            [("format".to_string(), vec!["csv".to_string()])].into(),
+        )]);
+
+        let manual = strip_ansi(
+            &catalog
+                .render_command_manual(
+                    &["class".to_string(), "list".to_string()],
+                    &defaults,
+                    &|option| match option.name.as_str() {
+                        "format" => vec!["text".to_string(), "csv".to_string()],
+                        _ => Vec::new(),
+                    },
+                )
+                .expect("manual should render"),
+        );
+
+        assert!(manual.starts_with("NAME\n    class list - "), "{manual}");
+        assert!(
+            manual.contains("SYNOPSIS\n    class list <collection> [options]"),
+            "{manual}"
+        );
+        assert!(
+            manual.contains("DESCRIPTION\n    List classes in a collection."),
+            "{manual}"
+        );
+        assert!(
+            manual.contains(
+                "    -N, --collection <string>\n        Collection to list\n        \
+                 Required; may be given as positional 1."
+            ),
+            "{manual}"
+        );
+        assert!(
+            manual.contains(
+                "    --format <string>\n        Output format\n        \
+                 Default: csv (from defaults.\"class list\")\n        Choices: text, csv"
+            ),
+            "{manual}"
+        );
+        assert!(
+            manual.contains("EXAMPLES\n    class list --format json"),
+            "{manual}"
+        );
+    }
+
     #[test]
     #[serial]
     fn render_command_help_colors_example_commands_when_enabled() {
@@ -1051,6 +1609,8 @@ mod tests {
             nargs: Some(3),
             repeatable: true,
             value_source: false,
+            raw: false,
+            positional: None,
             completion: CompletionSpec::None,
         };
 
@@ -1067,9 +1627,9 @@ mod tests {
             .expect("collection scope");
 
         assert!(plain.contains("class"));
-        assert!(plain.contains("create, delete, list, modify, show"));
+        assert!(plain.contains("create, delete, import-schemas, list, modify, schema-drift"));
         assert!(plain.contains("object"));
-        assert!(plain.contains("create, delete, list, modify, show"));
+        assert!(plain.contains("create, delete, fields, grep, import, list, modify"));
         assert!(plain.contains("event"));
         assert!(plain.contains("delivery, sink, subscription"));
         assert!(!plain.contains("event-subscription"));
@@ -1081,7 +1641,7 @@ mod tests {
         assert!(plain.contains("principal-permissions"));
         assert_eq!(
             scope_command_summary(collection_scope),
-            "permissions, create, delete, list, modify, principal-permissions, show"
+            "permissions, create, delete, list, modify, principal-permissions, show, usage"
         );
         assert!(plain.contains("relation"));
         assert!(plain.contains("class, object"));
@@ -1264,6 +1824,26 @@ mod tests {
         assert!(redirects.contains("auto and never strip ANSI"));
     }
 
+    #[test]
+    fn shell_topic_help_explains_transactions() {
+        let catalog = CommandCatalogBuilder::new().build();
+        let help = strip_ansi(
+            &catalog
+                .render_shell_topic_help(None)
+                .expect("shell index should render"),
+        );
+        let transactions = strip_ansi(
+            &catalog
+                .render_shell_topic_help(Some("transactions"))
+                .expect("shell transactions should render"),
+        );
+
+        assert!(help.contains("help shell transactions"));
+        assert!(transactions.contains("Type begin"));
+        assert!(transactions.contains("commit"));
+        assert!(transactions.contains("rollback"));
+    }
+
     #[test]
     fn nested_scope_help_lists_generated_children() {
         let catalog = build_command_catalog();
@@ -1417,6 +1997,10 @@ mod tests {
             "jobs output --id",
             "jobs show --id",
             "jobs watch --task",
+            "object show --id",
+            "relation object create --class-relation-id",
+            "relation object create --from-id",
+            "relation object create --to-id",
             "service-account token revoke --token-id",
             "task events --id",
             "task output --id",
@@ -1717,6 +2301,9 @@ mod tests {
                         | "--sink-id"
                         | "--relation-id"
                         | "--principal-id"
+                        | "--class-relation-id"
+                        | "--from-id"
+                        | "--to-id"
                 ) {
                     exposed.push(format!("{} {long}", command_path.join(" ")));
                 }
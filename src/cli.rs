@@ -1,11 +1,12 @@
 // src/cli.rs
 use crate::config::AppConfig;
+use crate::errors::AppError;
 use crate::models::{
-    EmptyResult, OutputColor, Protocol, TableBands, TableStyle, TableWidth, TableWrap,
+    EmptyResult, OutputColor, Protocol, RenderFormat, TableBands, TableStyle, TableWidth, TableWrap,
 };
 use clap::builder::BoolishValueParser;
 use clap::parser::ValueSource;
-use clap::{value_parser, Arg, ArgMatches, Command};
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
 use shlex::try_quote;
 use std::path::PathBuf;
 
@@ -14,7 +15,7 @@ pub fn build_cli() -> Command {
         .version(crate::build_info::VERSION)
         .disable_version_flag(false)
         .after_help(
-            "Commands:\n  hubuum-cli <command...>        Run one command and exit\n  hubuum-cli script <file>       Run commands from a file and exit\n\nExamples:\n  hubuum-cli object list --limit 5\n  hubuum-cli config show \\| P key value \\| L 5\n  hubuum-cli object list --class Hosts \\| G os_version AS \"OS Version\" \\| A count AS Hosts\n  hubuum-cli object list --json --class Hosts \\| P Name os_version \\> each:/tmp/host-{Name}.json\n  hubuum-cli config show '>>' config.log\n  hubuum-cli theme list\n  hubuum-cli help --tree\n\nIn POSIX shells, escape or quote |, >, and >> so the operators reach Hubuum CLI.",
+            "Commands:\n  hubuum-cli <command...>        Run one command and exit\n  hubuum-cli script <file>       Run commands from a file and exit\n  ... | hubuum-cli               Run commands from standard input and exit\n\nExamples:\n  hubuum-cli object list --limit 5\n  hubuum-cli config show \\| P key value \\| L 5\n  hubuum-cli object list --class Hosts \\| G os_version AS \"OS Version\" \\| A count AS Hosts\n  hubuum-cli object list --json --class Hosts \\| P Name os_version \\> each:/tmp/host-{Name}.json\n  hubuum-cli config show '>>' config.log\n  hubuum-cli theme list\n  hubuum-cli help --tree\n\nIn POSIX shells, escape or quote |, >, and >> so the operators reach Hubuum CLI.",
         )
         .arg(
             Arg::new("config")
@@ -22,6 +23,13 @@ pub fn build_cli() -> Command {
                 .value_name("FILE")
                 .help("Specify a custom configuration file"),
         )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .value_name("NAME")
+                .env("HUBUUM_CLI_PROFILE")
+                .help("Connect with the server settings of a [profiles.<name>] config section"),
+        )
         .arg(
             Arg::new("hostname")
                 .long("hostname")
@@ -156,6 +164,34 @@ pub fn build_cli() -> Command {
                 .env("HUBUUM_CLI__OUTPUT__COLOR")
                 .help("Control colored output (auto, always, never)"),
         )
+        .arg(
+            Arg::new("no_color")
+                .long("no-color")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("color")
+                .help("Disable colored output; same as --color never"),
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .action(ArgAction::Count)
+                .conflicts_with("quiet")
+                .help("Log more detail to the log file and stderr; repeat for debug and trace"),
+        )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .action(ArgAction::SetTrue)
+                .help("Hide warnings; errors are still shown"),
+        )
+        .arg(
+            Arg::new("offline")
+                .long("offline")
+                .action(ArgAction::SetTrue)
+                .help("Start without reaching the server; see 'help offline'"),
+        )
         .arg(
             Arg::new("theme")
                 .long("theme")
@@ -208,6 +244,28 @@ pub fn build_cli() -> Command {
                 .env("HUBUUM_CLI__OUTPUT__EMPTY_RESULT")
                 .help("Set empty table output (message or silent)"),
         )
+        .arg(
+            Arg::new("output_format")
+                .long("output")
+                .value_name("FORMAT")
+                .ignore_case(true)
+                .value_parser(["text", "json", "jsonl", "csv", "tsv"])
+                .env("HUBUUM_CLI__OUTPUT__FORMAT")
+                .help("Set the default output format (text, json, jsonl, csv, tsv)"),
+        )
+        .arg(
+            Arg::new("script")
+                .long("script")
+                .value_name("FILE")
+                .conflicts_with_all(["command", "source"])
+                .help("Run commands from a file, or from standard input with '-', and exit"),
+        )
+        .arg(
+            Arg::new("keep_going")
+                .long("keep-going")
+                .action(ArgAction::SetTrue)
+                .help("Keep running script commands after one fails"),
+        )
         .arg(
             Arg::new("command")
                 .long("command")
@@ -226,6 +284,9 @@ pub fn build_cli() -> Command {
         )
 }
 
+/// Script name that reads the commands from standard input.
+pub const STDIN_SCRIPT: &str = "-";
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StartupMode {
     Repl,
@@ -283,6 +344,12 @@ where
             continue;
         }
 
+        if is_global_flag(arg) {
+            clap_args.push(arg.clone());
+            idx += 1;
+            continue;
+        }
+
         if is_global_bool_option(arg) {
             clap_args.push(arg.clone());
             if !arg.contains('=')
@@ -326,13 +393,24 @@ pub fn execution_mode(matches: &ArgMatches, startup_mode: StartupMode) -> Startu
         return StartupMode::Command(command.clone());
     }
 
-    if let Some(filename) = matches.get_one::<String>("source") {
+    if let Some(filename) = matches
+        .get_one::<String>("script")
+        .or_else(|| matches.get_one::<String>("source"))
+    {
         return StartupMode::Script(filename.clone());
     }
 
     startup_mode
 }
 
+/// Runs commands piped to standard input instead of starting the REPL.
+pub fn piped_startup_mode(mode: StartupMode, stdin_is_terminal: bool) -> StartupMode {
+    match mode {
+        StartupMode::Repl if !stdin_is_terminal => StartupMode::Script(STDIN_SCRIPT.to_string()),
+        mode => mode,
+    }
+}
+
 fn has_legacy_execution_arg(args: &[String]) -> bool {
     args.iter().any(|arg| {
         arg == "--command"
@@ -351,6 +429,7 @@ fn is_global_option_with_value(arg: &str) -> bool {
     matches!(
         key,
         "--config"
+            | "--profile"
             | "--hostname"
             | "--port"
             | "--protocol"
@@ -370,6 +449,8 @@ fn is_global_option_with_value(arg: &str) -> bool {
             | "--table-wrap"
             | "--table-bands"
             | "--empty-result"
+            | "--output"
+            | "--script"
     )
 }
 
@@ -385,6 +466,26 @@ fn is_global_bool_option(arg: &str) -> bool {
     )
 }
 
+/// Global flags that never take a value, including stacked `-vv`.
+fn is_global_flag(arg: &str) -> bool {
+    matches!(
+        arg,
+        "--no-color" | "--quiet" | "-q" | "--verbose" | "--keep-going" | "--offline"
+    ) || arg
+        .strip_prefix('-')
+        .is_some_and(|flags| !flags.is_empty() && flags.chars().all(|flag| flag == 'v'))
+}
+
+/// Log filter requested with `-v` (info), `-vv` (debug), or `-vvv` (trace).
+pub fn verbosity_filter(matches: &ArgMatches) -> Option<&'static str> {
+    match matches.get_count("verbose") {
+        0 => None,
+        1 => Some("info"),
+        2 => Some("debug"),
+        _ => Some("trace"),
+    }
+}
+
 fn parse_boolish(value: &str) -> Option<bool> {
     match value.to_ascii_lowercase().as_str() {
         "true" | "t" | "yes" | "y" | "on" | "1" => Some(true),
@@ -420,6 +521,18 @@ fn get_command_line_value<'a, T: Clone + Send + Sync + 'static>(
         .flatten()
 }
 
+/// Applies the profile chosen with `--profile` or `HUBUUM_CLI_PROFILE`. Server flags given
+/// alongside it still win, as [`update_config_from_cli`] runs afterwards.
+pub fn apply_profile_from_cli(
+    config: &mut AppConfig,
+    matches: &ArgMatches,
+) -> Result<(), AppError> {
+    match matches.get_one::<String>("profile") {
+        Some(profile) => config.use_profile(profile),
+        None => Ok(()),
+    }
+}
+
 pub fn update_config_from_cli(config: &mut AppConfig, matches: &ArgMatches) {
     if let Some(hostname) = get_command_line_value::<String>(matches, "hostname") {
         config.server.hostname = hostname.to_string();
@@ -484,6 +597,9 @@ pub fn update_config_from_cli(config: &mut AppConfig, matches: &ArgMatches) {
     if let Some(color) = get_command_line_value::<String>(matches, "color") {
         config.output.color = color.parse().unwrap_or(OutputColor::Auto);
     }
+    if matches.get_flag("no_color") {
+        config.output.color = OutputColor::Never;
+    }
     if let Some(theme) = get_command_line_value::<String>(matches, "theme") {
         config.output.theme = theme.to_string();
     }
@@ -505,11 +621,16 @@ pub fn update_config_from_cli(config: &mut AppConfig, matches: &ArgMatches) {
     if let Some(empty_result) = get_command_line_value::<String>(matches, "empty_result") {
         config.output.empty_result = empty_result.parse().unwrap_or(EmptyResult::Message);
     }
+    if let Some(format) = get_command_line_value::<String>(matches, "output_format") {
+        config.output.format = format.parse().unwrap_or(RenderFormat::Text);
+        config.output.piped_format = None;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::ProfileConfig;
     use serial_test::serial;
     use std::env::{remove_var, set_var};
 
@@ -540,6 +661,28 @@ mod tests {
         assert_eq!(config.server.hostname, "cli.example.com");
     }
 
+    #[test]
+    fn profile_flag_applies_before_explicit_server_flags() {
+        let matches = build_cli()
+            .try_get_matches_from(["hubuum-cli", "--profile", "staging", "--port", "9000"])
+            .expect("cli should parse");
+        let mut config = AppConfig::default();
+        config.profiles.insert(
+            "staging".to_string(),
+            ProfileConfig {
+                hostname: Some("staging.example.com".to_string()),
+                port: Some(8443),
+                ..ProfileConfig::default()
+            },
+        );
+        apply_profile_from_cli(&mut config, &matches).expect("staging is configured");
+        update_config_from_cli(&mut config, &matches);
+
+        assert_eq!(config.server.hostname, "staging.example.com");
+        assert_eq!(config.server.port, 9000);
+        assert_eq!(config.profile.as_deref(), Some("staging"));
+    }
+
     #[test]
     fn update_config_from_cli_applies_identity_scope() {
         let matches = build_cli()
@@ -688,6 +831,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn color_verbosity_and_quiet_flags_are_global() {
+        let startup =
+            split_startup_args(["hubuum-cli", "--no-color", "-vv", "class", "list", "-v"]);
+        assert_eq!(startup.clap_args, vec!["hubuum-cli", "--no-color", "-vv"]);
+        assert_eq!(
+            startup.mode,
+            StartupMode::Command("class list -v".to_string())
+        );
+
+        let matches = build_cli()
+            .try_get_matches_from(startup.clap_args)
+            .expect("cli should parse");
+        let mut config = AppConfig::default();
+        update_config_from_cli(&mut config, &matches);
+        assert_eq!(config.output.color, OutputColor::Never);
+        assert_eq!(verbosity_filter(&matches), Some("debug"));
+
+        let quiet = build_cli()
+            .try_get_matches_from(["hubuum-cli", "-q"])
+            .expect("cli should parse");
+        assert!(quiet.get_flag("quiet"));
+        assert_eq!(verbosity_filter(&quiet), None);
+        assert!(build_cli()
+            .try_get_matches_from(["hubuum-cli", "-q", "-v"])
+            .is_err());
+    }
+
     #[test]
     fn split_startup_args_preserves_pipe_token_for_direct_command() {
         let startup = split_startup_args([
@@ -732,6 +903,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn script_flag_and_piped_input_run_scripts() {
+        let startup = split_startup_args(["hubuum-cli", "--keep-going", "--script", "-"]);
+        assert_eq!(startup.mode, StartupMode::Repl);
+        let matches = build_cli()
+            .try_get_matches_from(startup.clap_args)
+            .expect("script flags should parse");
+        assert!(matches.get_flag("keep_going"));
+        assert_eq!(
+            execution_mode(&matches, startup.mode),
+            StartupMode::Script(STDIN_SCRIPT.to_string())
+        );
+
+        assert_eq!(
+            piped_startup_mode(StartupMode::Repl, false),
+            StartupMode::Script(STDIN_SCRIPT.to_string())
+        );
+        assert_eq!(
+            piped_startup_mode(StartupMode::Repl, true),
+            StartupMode::Repl
+        );
+        assert_eq!(
+            piped_startup_mode(StartupMode::Command("help".to_string()), false),
+            StartupMode::Command("help".to_string())
+        );
+    }
+
     #[test]
     fn legacy_command_flag_remains_clap_handled() {
         let startup = split_startup_args(["hubuum-cli", "--command", "help"]);
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+use shlex::try_quote;
+
+use crate::commands::CliOption;
+use crate::errors::AppError;
+use crate::output::add_warning;
+use crate::tokenizer::CommandTokenizer;
+
+/// Option values configured per command path, e.g. `[defaults."object list"] class = "Host"`.
+pub type CommandDefaults = HashMap<String, HashMap<String, Vec<String>>>;
+
+/// The configured default options for `command_path` that `tokens` does not set, as words
+/// to append to the command line.
+///
+/// An option counts as set when it is given by its short or long name, or, for options
+/// that may also be given positionally, when that positional is present. Flags are added
+/// for `true` and left out for `false`. Names that are not options of the command are
+/// reported as warnings and skipped.
+pub fn default_arguments(
+    defaults: &CommandDefaults,
+    command_path: &[String],
+    option_defs: &[CliOption],
+    tokens: &CommandTokenizer,
+) -> Result<Vec<String>, AppError> {
+    let command = command_path.join(" ");
+    let Some((configured_path, configured)) = configured_for(defaults, command_path) else {
+        return Ok(Vec::new());
+    };
+
+    let mut names = configured.keys().collect::<Vec<_>>();
+    names.sort();
+    let mut words = Vec::new();
+    for name in names {
+        let Some(option) = option_defs
+            .iter()
+            .find(|option| option_matches(option, name))
+        else {
+            add_warning(format!(
+                "Ignoring defaults.\"{configured_path}\".{name}: '{command}' has no such option"
+            ))?;
+            continue;
+        };
+        if is_given(option, tokens) {
+            continue;
+        }
+        let Some(long) = option.long.clone() else {
+            continue;
+        };
+        let values = &configured[name];
+        if option.flag {
+            if values
+                .iter()
+                .any(|value| value.eq_ignore_ascii_case("true"))
+            {
+                words.push(long);
+            }
+        } else if option.repeatable {
+            for value in values {
+                words.extend([long.clone(), quoted(value)]);
+            }
+        } else if !values.is_empty() {
+            words.push(long);
+            words.extend(values.iter().map(|value| quoted(value)));
+        }
+    }
+    Ok(words)
+}
+
+/// The values configured for `option` of the command at `command_path`, if any.
+pub fn configured_default<'a>(
+    defaults: &'a CommandDefaults,
+    command_path: &[String],
+    option: &CliOption,
+) -> Option<&'a [String]> {
+    let (_, configured) = configured_for(defaults, command_path)?;
+    configured
+        .iter()
+        .find(|(name, _)| option_matches(option, name))
+        .map(|(_, values)| values.as_slice())
+}
+
+fn configured_for<'a>(
+    defaults: &'a CommandDefaults,
+    command_path: &[String],
+) -> Option<(&'a String, &'a HashMap<String, Vec<String>>)> {
+    let command = command_path.join(" ");
+    defaults
+        .iter()
+        .find(|(path, _)| normalized_command(path).eq_ignore_ascii_case(&command))
+}
+
+fn normalized_command(path: &str) -> String {
+    path.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn option_matches(option: &CliOption, name: &str) -> bool {
+    let name = name.trim_start_matches('-').replace('_', "-");
+    option.name.replace('_', "-") == name || option.long_without_dashes().as_deref() == Some(&name)
+}
+
+fn is_given(option: &CliOption, tokens: &CommandTokenizer) -> bool {
+    let options = tokens.get_options();
+    option
+        .long_without_dashes()
+        .into_iter()
+        .chain(option.short_without_dash())
+        .any(|key| options.contains_key(&key))
+        || option
+            .positional
+            .is_some_and(|index| tokens.get_positionals().len() > index)
+}
+
+fn quoted(value: &str) -> String {
+    try_quote(value)
+        .map(|quoted| quoted.into_owned())
+        .unwrap_or_else(|_| value.replace('\0', ""))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::output::{reset_output, take_output};
+
+    fn opt(name: &str, short: &str, flag: bool, positional: Option<usize>) -> CliOption {
+        CliOption {
+            name: name.to_string(),
+            short: Some(format!("-{short}")),
+            long: Some(format!("--{}", name.replace('_', "-"))),
+            flag,
+            greedy: false,
+            nargs: None,
+            repeatable: false,
+            value_source: false,
+            raw: false,
+            positional,
+            help: String::new(),
+            field_type: TypeId::of::<String>(),
+            field_type_help: String::new(),
+            required: false,
+            autocomplete: None,
+        }
+    }
+
+    fn defaults(entries: &[(&str, &[&str])]) -> CommandDefaults {
+        HashMap::from([(
+            "object  List".to_string(),
+            entries
+                .iter()
+                .map(|(name, values)| {
+                    (
+                        (*name).to_string(),
+                        values.iter().map(|value| (*value).to_string()).collect(),
+                    )
+                })
+                .collect(),
+        )])
+    }
+
+    fn arguments(defaults: &CommandDefaults, line: &str) -> Vec<String> {
+        let option_defs = vec![
+            opt("class", "c", false, Some(0)),
+            opt("collection", "N", false, None),
+            opt("show_data", "d", true, None),
+            opt("reveal", "r", true, None),
+        ];
+        let tokens =
+            CommandTokenizer::new_without_value_source_resolution(line, "list", &option_defs)
+                .unwrap();
+        default_arguments(
+            defaults,
+            &["object".to_string(), "list".to_string()],
+            &option_defs,
+            &tokens,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn defaults_fill_in_options_the_line_does_not_set() {
+        let defaults = defaults(&[
+            ("class", &["Host"]),
+            ("collection", &["Web servers"]),
+            ("show-data", &["true"]),
+            ("reveal", &["false"]),
+        ]);
+
+        assert_eq!(
+            arguments(&defaults, "list"),
+            vec![
+                "--class",
+                "Host",
+                "--collection",
+                "'Web servers'",
+                "--show-data"
+            ]
+        );
+        assert_eq!(
+            arguments(&defaults, "list Router -N core --show-data"),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            arguments(&defaults, "list --class Router"),
+            vec!["--collection", "'Web servers'", "--show-data"]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn unknown_default_options_are_skipped_with_a_warning() {
+        reset_output().unwrap();
+        let defaults = defaults(&[("colour", &["red"]), ("class", &["Host"])]);
+
+        assert_eq!(arguments(&defaults, "list"), vec!["--class", "Host"]);
+        assert!(arguments(&HashMap::new(), "list").is_empty());
+        let warnings = take_output().unwrap().warnings;
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("defaults.\"object  List\".colour"));
+    }
+}
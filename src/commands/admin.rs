@@ -5,11 +5,10 @@ use serde_json::{json, Value};
 
 use super::builder::{catalog_command, CommandDocs};
 use super::{desired_format, CliCommand};
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::errors::AppError;
 use crate::models::OutputFormat;
 use crate::output::set_semantic_output;
-use crate::services::AppServices;
 use crate::tokenizer::CommandTokenizer;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -24,6 +23,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                     "Show the authenticated server's effective process configuration. Secrets are redacted by the server. Administrator access is required.",
                 ),
                 examples: Some("--output json"),
+                ..CommandDocs::default()
             },
         ),
     );
@@ -33,9 +33,9 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
 pub struct AdminConfig {}
 
 impl CliCommand for AdminConfig {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let _query = Self::parse_tokens(tokens)?;
-        let config = services.gateway().server_config()?;
+        let config = ctx.services().gateway().server_config()?;
         render_server_config(config, desired_format(tokens))
     }
 }
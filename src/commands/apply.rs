@@ -0,0 +1,367 @@
+use std::fs::read_to_string;
+use std::path::Path;
+
+use cli_command_derive::CommandArgs;
+use hubuum_filter::OutputEnvelope;
+use serde_json::{json, to_value};
+
+use super::builder::{catalog_command, CommandDocs};
+use super::bulk::{
+    checkpoint_path, finish_checkpoint, render_resume_hint, write_retry_file, DEFAULT_RETRY_FILE,
+};
+use super::{desired_format, render_bulk_summary, select_targets, CliCommand};
+use crate::autocomplete::file_paths;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
+use crate::domain::{
+    ApplyPlan, BulkSummary, DesiredState, PlanAction, PlanKind, PlanOperation, RetryFile,
+    RetryItem, NOT_ATTEMPTED,
+};
+use crate::errors::AppError;
+use crate::formatting::{render_value_diff, DiffLayout, OutputFormatter};
+use crate::models::OutputFormat;
+use crate::output::{add_warning, append_line, set_semantic_output};
+use crate::safety::{confirm_protected_collections, confirm_write_ahead, WriteAheadSummary};
+use crate::services::{Checkpoint, RelationTarget};
+use crate::theme::{paint, ThemeRole};
+use crate::tokenizer::CommandTokenizer;
+
+pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
+    builder.add_command(
+        &[],
+        catalog_command(
+            "apply",
+            ApplyCommand::default(),
+            CommandDocs {
+                about: Some("Plan or apply a declarative inventory file"),
+                long_about: Some(
+                    "Read collections, classes, class relations, objects, and object relations from a YAML, TOML, or JSON file and compare them with the server. Without --yes the planned creates, updates, and deletes are shown and nothing is changed. Entities missing from the file are only deleted with --prune, which is limited to objects of declared classes and to class and object relations between declared classes. With --diff (or --side-by-side) each update is followed by a diff of the changed fields; JSON output always carries them as a JSON Patch. With --select the planned changes are listed as a checklist first, so individual changes can be left out. Every change applied is recorded in <file>.checkpoint, so an apply that stopped partway can be run again with --resume to skip them; the checkpoint is removed once the whole plan has been applied.",
+                ),
+                examples: Some("--file desired.yaml\n--file desired.yaml --diff\n--file desired.yaml --yes\n--file desired.yaml --prune --yes\n--file desired.yaml --select --yes\n--file desired.yaml --yes --resume"),
+                mutating: true,
+            },
+        ),
+    );
+}
+
+#[derive(Debug, Clone, CommandArgs, Default)]
+pub struct ApplyCommand {
+    #[option(
+        short = "f",
+        long = "file",
+        help = "Desired state file (.yaml, .yml, .toml, or .json)",
+        autocomplete = "file_paths"
+    )]
+    file: String,
+    #[option(
+        long = "prune",
+        help = "Delete objects and relations of declared classes that are not in the file",
+        flag = true
+    )]
+    prune: bool,
+    #[option(
+        long = "yes",
+        help = "Apply the plan instead of only showing it",
+        flag = true
+    )]
+    yes: bool,
+    #[option(
+        long = "diff",
+        help = "Show a diff of the fields each update changes",
+        flag = true
+    )]
+    diff: bool,
+    #[option(
+        long = "side-by-side",
+        help = "Show update diffs side by side",
+        flag = true
+    )]
+    side_by_side: bool,
+    #[option(
+        long = "select",
+        help = "Pick the changes to keep from a checklist first",
+        flag = true
+    )]
+    select: bool,
+    #[option(
+        long = "retry-file",
+        help = "Where to save changes that could not be applied (default: failed.json)",
+        autocomplete = "file_paths"
+    )]
+    retry_file: Option<String>,
+    #[option(
+        long = "resume",
+        help = "Skip the changes an interrupted apply of the same file made",
+        flag = true
+    )]
+    resume: bool,
+}
+
+impl CliCommand for ApplyCommand {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let path = Path::new(&query.file);
+        let desired = DesiredState::parse(path, &read_to_string(path)?)?;
+        let current = ctx.services().gateway().apply_current_state(&desired)?;
+        let mut plan = ApplyPlan::compute(&desired, &current, query.prune)?;
+        if query.select {
+            plan = select_plan(plan)?;
+        }
+
+        let diff = diff_layout(query.diff, query.side_by_side);
+        if !query.yes || plan.is_empty() {
+            return render_plan(tokens, &plan, None, diff);
+        }
+        let checkpoint = Checkpoint::open(checkpoint_path(&query.file), query.resume)?;
+        execute_plan(
+            ctx,
+            tokens,
+            "apply",
+            &plan,
+            diff,
+            query.retry_file.as_deref(),
+            Some(checkpoint),
+        )
+    }
+}
+
+/// Offers the planned changes as a checklist (`--select`) and keeps the ticked ones.
+pub(super) fn select_plan(plan: ApplyPlan) -> Result<ApplyPlan, AppError> {
+    let (changes, _) = select_targets(plan.changes, |change| change.label())?;
+    Ok(ApplyPlan { changes })
+}
+
+/// Sends every change in `plan` in order, stopping at the first failure.
+///
+/// A summary of the changes is printed first, and a production profile, like any
+/// protected collection a delete reaches into, has to be confirmed by name before
+/// anything is sent. The outcome is summarised either way.
+///
+/// A failed apply saves the failed change and the ones it did not attempt to
+/// `retry_file` (or `failed.json`) for `bulk retry`, reports what was changed before
+/// it stopped, and then fails the command.
+///
+/// With a `checkpoint`, changes it holds from an interrupted run are skipped and every
+/// change made is recorded in it; it is removed once the whole plan has been applied.
+pub(super) fn execute_plan(
+    ctx: &CommandContext,
+    tokens: &CommandTokenizer,
+    action: &str,
+    plan: &ApplyPlan,
+    diff: Option<DiffLayout>,
+    retry_file: Option<&str>,
+    checkpoint: Option<Checkpoint>,
+) -> Result<(), AppError> {
+    confirm_write_ahead(
+        &ctx.config(),
+        desired_format(tokens),
+        action,
+        &write_ahead_summary(plan),
+    )?;
+    confirm_protected_deletes(ctx, action, plan)?;
+    let gateway = ctx.services().gateway();
+    let runner = gateway.bulk_runner();
+    let mut result = BulkSummary::default();
+    let mut retry = RetryFile::default();
+    for change in &plan.changes {
+        if !retry.items.is_empty() {
+            retry.items.push(RetryItem::new(change, NOT_ATTEMPTED));
+            continue;
+        }
+        let label = change.label();
+        if let Some(checkpoint) = &checkpoint {
+            if checkpoint.is_completed(&label) {
+                result.resumed += 1;
+                continue;
+            }
+        }
+        match runner.run_one(&change.operation, |operation| {
+            gateway.apply_plan_operation(operation)
+        }) {
+            Ok(()) => {
+                result.record(change.action);
+                if let Some(checkpoint) = &checkpoint {
+                    checkpoint.record_or_warn(&label);
+                }
+            }
+            Err(error) => {
+                result.record_failure(change.label(), &error);
+                retry.items.push(RetryItem::new(change, error));
+            }
+        }
+    }
+    if !retry.items.is_empty() {
+        let retry_file = retry_file.unwrap_or(DEFAULT_RETRY_FILE);
+        match write_retry_file(Path::new(retry_file), &retry) {
+            Ok(()) => result.retry_file = Some(retry_file.to_string()),
+            Err(error) => add_warning(format!("Could not write {retry_file}: {error}"))?,
+        }
+    }
+    if let Some(checkpoint) = checkpoint {
+        finish_checkpoint(checkpoint, &mut result)?;
+    }
+    render_plan(tokens, plan, Some(&result), diff)?;
+    if result.has_failures() {
+        return Err(AppError::Quiet);
+    }
+    Ok(())
+}
+
+/// Shows `plan`, or with `result` what applying it did.
+pub(super) fn render_plan(
+    tokens: &CommandTokenizer,
+    plan: &ApplyPlan,
+    result: Option<&BulkSummary>,
+    diff: Option<DiffLayout>,
+) -> Result<(), AppError> {
+    let summary = json!({
+        "create": plan.count(PlanAction::Create),
+        "update": plan.count(PlanAction::Update),
+        "delete": plan.count(PlanAction::Delete),
+    });
+    match desired_format(tokens) {
+        OutputFormat::Json => {
+            let mut output = json!({
+                "applied": result.is_some_and(|result| !result.has_failures()),
+                "summary": summary,
+                "changes": plan.changes,
+            });
+            if let Some(result) = result {
+                output["result"] = to_value(result)?;
+            }
+            set_semantic_output(OutputEnvelope::detail(output, Vec::new()))?
+        }
+        OutputFormat::Text => {
+            if plan.is_empty() {
+                append_line("No changes. The server matches the desired state.")?;
+                return Ok(());
+            }
+            plan.changes.format_noreturn()?;
+            if let Some(layout) = diff {
+                render_plan_diffs(plan, layout)?;
+            }
+            let counts = format!(
+                "{} to create, {} to update, {} to delete",
+                summary["create"], summary["update"], summary["delete"]
+            );
+            match result {
+                Some(result) => {
+                    render_bulk_summary(result)?;
+                    if result.resumed > 0 {
+                        append_line(format!(
+                            "Skipped {} change(s) made by the interrupted apply.",
+                            result.resumed
+                        ))?;
+                    }
+                    if result.has_failures() {
+                        let done =
+                            result.created + result.updated + result.deleted + result.resumed;
+                        append_line(format!(
+                            "Apply stopped: {done} of {} change(s) applied.",
+                            plan.changes.len()
+                        ))?;
+                        if let Some(retry_file) = &result.retry_file {
+                            append_line(format!(
+                                "Fix the cause and run `bulk retry --file {retry_file}` to send the remaining changes."
+                            ))?;
+                        }
+                        render_resume_hint(result)?;
+                    } else {
+                        append_line(format!("Applied: {counts}."))?;
+                    }
+                }
+                None => {
+                    append_line(format!("Plan: {counts}."))?;
+                    append_line("Run again with --yes to apply these changes.")?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Asks for every protected collection holding an object, class, or relation end `plan` deletes.
+fn confirm_protected_deletes(
+    ctx: &CommandContext,
+    action: &str,
+    plan: &ApplyPlan,
+) -> Result<(), AppError> {
+    let safety = &ctx.config().safety;
+    if !safety.has_protected_collections() {
+        return Ok(());
+    }
+    let gateway = ctx.services().gateway();
+    let mut collections = Vec::new();
+    for names in gateway
+        .bulk_runner()
+        .run(&plan.changes, |change| match &change.operation {
+            PlanOperation::DeleteObject { class, name } => {
+                Ok(vec![gateway.object_collection_name(class, name)?])
+            }
+            PlanOperation::DeleteClassRelation(relation) => Ok(vec![
+                gateway.class_collection_name(&relation.class_a)?,
+                gateway.class_collection_name(&relation.class_b)?,
+            ]),
+            PlanOperation::DeleteObjectRelation(relation) => {
+                gateway.object_relation_collection_names(&RelationTarget::from(relation))
+            }
+            _ => Ok(Vec::new()),
+        })
+    {
+        collections.extend(names?);
+    }
+    confirm_protected_collections(safety, action, collections)
+}
+
+/// The creates, updates, and deletes of `plan` per entity type.
+fn write_ahead_summary(plan: &ApplyPlan) -> WriteAheadSummary {
+    let mut summary = WriteAheadSummary::new(&["Create", "Update", "Delete"]);
+    for kind in [
+        PlanKind::Collection,
+        PlanKind::Class,
+        PlanKind::ClassRelation,
+        PlanKind::Object,
+        PlanKind::ObjectRelation,
+    ] {
+        let counts = [PlanAction::Create, PlanAction::Update, PlanAction::Delete]
+            .into_iter()
+            .map(|action| {
+                plan.changes
+                    .iter()
+                    .filter(|change| change.kind == kind && change.action == action)
+                    .count()
+            })
+            .collect();
+        summary.add(kind.as_str(), counts);
+    }
+    summary
+}
+
+/// `--side-by-side` implies `--diff`.
+pub(super) fn diff_layout(diff: bool, side_by_side: bool) -> Option<DiffLayout> {
+    if side_by_side {
+        Some(DiffLayout::SideBySide)
+    } else {
+        diff.then_some(DiffLayout::Unified)
+    }
+}
+
+fn render_plan_diffs(plan: &ApplyPlan, layout: DiffLayout) -> Result<(), AppError> {
+    for change in &plan.changes {
+        let Some(diff) = &change.diff else {
+            continue;
+        };
+        append_line("")?;
+        append_line(paint(
+            ThemeRole::Heading,
+            format!(
+                "{} {} {}",
+                change.action.as_str(),
+                change.kind.as_str(),
+                change.name
+            ),
+        ))?;
+        append_line(render_value_diff(diff, ("server", "desired"), layout))?;
+    }
+    append_line("")?;
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use cli_command_derive::CommandArgs;
 use serde::{Deserialize, Serialize};
 use serde_json::to_string_pretty;
@@ -7,15 +8,19 @@ use super::{
     desired_format, normalize_server_page_size, option_or_pos, render_json_record,
     render_list_page, required_i64, CliCommand,
 };
+use crate::audit_log::{parse_since, read as read_audit_log};
 use crate::autocomplete::{
     audit_event_ids, audit_resource_names, audit_resources, classes, collections, event_actions,
+    since_ages,
 };
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
+use crate::config::get_config;
 use crate::errors::AppError;
-use crate::formatting::OutputFormatter;
+use crate::formatting::{append_json, OutputFormatter};
+use crate::masking::masked_json;
 use crate::models::OutputFormat;
 use crate::output::append_line;
-use crate::services::{AppServices, AuditListInput, AuditScope};
+use crate::services::{AuditListInput, AuditScope};
 use crate::tokenizer::CommandTokenizer;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -39,9 +44,10 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 CommandDocs {
                     about: Some("Show a single audit event by id"),
                     long_about: Some(
-                        "Looks for a visible audit event by id. When before and after snapshots are available, the result includes a nested JSON diff. Pass --complete to include the full snapshots. User and collection names are resolved when the referenced resources are still available. The current hubuum_client does not expose a direct event-id endpoint, so this command scans recent visible audit pages until it finds the event.",
+                        "Looks for a visible audit event by id in the server's audit trail; the commands this CLI ran are reviewed with audit local. When before and after snapshots are available, the result includes a nested JSON diff. Pass --complete to include the full snapshots. User and collection names are resolved when the referenced resources are still available. The current hubuum_client does not expose a direct event-id endpoint, so this command scans recent visible audit pages until it finds the event.",
                     ),
                     examples: Some("12345\n--id 12345"),
+                    ..CommandDocs::default()
                 },
             ),
         )
@@ -56,6 +62,22 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                         "Lists audit events scoped to a resource such as a collection, class, object, user, group, template, or remote target.",
                     ),
                     examples: Some("--resource collection --name Math\n--resource object --class Hosts --name host.example.org"),
+                    ..CommandDocs::default()
+                },
+            ),
+        )
+        .add_command(
+            &["audit"],
+            catalog_command(
+                "local",
+                AuditLocal::default(),
+                CommandDocs {
+                    about: Some("Review mutating commands recorded by this CLI"),
+                    long_about: Some(
+                        "Every mutating command run through this CLI is appended to a local audit log in the data directory, with its time, login, command line, the entity IDs it reported, and whether it succeeded. Secret option values are redacted. This command reads the entries of the current profile's login, meaning its server, user, and identity scope; pass --all-logins to include every login. --since takes an age such as 30m, 12h, 1d, or 2w, or an RFC 3339 timestamp. The command is named local rather than show because audit show already looks up a server-side audit event by id.",
+                    ),
+                    examples: Some("\n--since 1d\n--since 2w --all-logins --limit 50"),
+                    ..CommandDocs::default()
                 },
             ),
         );
@@ -92,7 +114,7 @@ pub struct AuditList {
 }
 
 impl CliCommand for AuditList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let input = AuditListInput {
             action: query.action,
@@ -100,12 +122,12 @@ impl CliCommand for AuditList {
             actor_user_id: query
                 .actor_user
                 .as_deref()
-                .map(|name| services.gateway().user_id_by_name(name))
+                .map(|name| ctx.services().gateway().user_id_by_name(name))
                 .transpose()?,
             collection_id: query
                 .collection
                 .as_deref()
-                .map(|name| services.gateway().collection_id_by_name(name))
+                .map(|name| ctx.services().gateway().collection_id_by_name(name))
                 .transpose()?,
             occurred_after: query.occurred_after,
             occurred_before: query.occurred_before,
@@ -113,14 +135,22 @@ impl CliCommand for AuditList {
             sort: query.sort,
             cursor: query.cursor,
         };
-        let events = services.gateway().audit_events(AuditScope::Global, input)?;
+        let events = ctx
+            .services()
+            .gateway()
+            .audit_events(AuditScope::Global, input)?;
         render_list_page(tokens, &events)
     }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct AuditShow {
-    #[option(long = "id", help = "Audit event ID", autocomplete = "audit_event_ids")]
+    #[option(
+        long = "id",
+        help = "Audit event ID",
+        autocomplete = "audit_event_ids",
+        positional = 0
+    )]
     pub id: Option<i64>,
     #[option(
         long = "complete",
@@ -131,10 +161,11 @@ pub struct AuditShow {
 }
 
 impl CliCommand for AuditShow {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.id = option_or_pos(query.id, tokens, 0, "id")?;
-        let event = services
+        let event = ctx
+            .services()
             .gateway()
             .audit_event_by_id(required_i64(query.id, "id")?)?
             .into_audit_detail(query.complete);
@@ -183,21 +214,21 @@ pub struct AuditResource {
 }
 
 impl CliCommand for AuditResource {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let scope = services.gateway().audit_scope_by_name(
+        let scope = ctx.services().gateway().audit_scope_by_name(
             &query.resource,
             query.name.as_deref(),
             query.class.as_deref(),
         )?;
-        let events = services.gateway().audit_events(
+        let events = ctx.services().gateway().audit_events(
             scope,
             AuditListInput {
                 action: query.action,
                 collection_id: query
                     .collection
                     .as_deref()
-                    .map(|name| services.gateway().collection_id_by_name(name))
+                    .map(|name| ctx.services().gateway().collection_id_by_name(name))
                     .transpose()?,
                 limit: normalize_server_page_size(query.limit)?,
                 sort: query.sort,
@@ -207,9 +238,57 @@ impl CliCommand for AuditResource {
         )?;
 
         match desired_format(tokens) {
-            OutputFormat::Json => append_line(to_string_pretty(&events)?)?,
+            OutputFormat::Json => append_line(to_string_pretty(&masked_json(&events)?)?)?,
             OutputFormat::Text => events.items.format_noreturn()?,
         }
         Ok(())
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct AuditLocal {
+    #[option(
+        short = "s",
+        long = "since",
+        help = "Only show commands newer than this age (30m, 12h, 1d, 2w) or timestamp",
+        autocomplete = "since_ages"
+    )]
+    pub since: Option<String>,
+    #[option(long = "limit", help = "Show at most this many of the newest commands")]
+    pub limit: Option<usize>,
+    #[option(
+        long = "all-logins",
+        help = "Include commands run by every server, user, and identity scope",
+        flag = true
+    )]
+    pub all_logins: bool,
+}
+
+impl CliCommand for AuditLocal {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        render_audit_local(tokens)
+    }
+}
+
+pub(crate) fn render_audit_local(tokens: &CommandTokenizer) -> Result<(), AppError> {
+    let query = AuditLocal::parse_tokens(tokens)?;
+    let since = match query.since.as_deref() {
+        Some(since) => parse_since(since, Utc::now())?,
+        None => DateTime::<Utc>::MIN_UTC,
+    };
+    let config = get_config();
+    let server = (!query.all_logins).then_some(&config.server);
+    let mut entries = read_audit_log(since, server)?;
+    if let Some(limit) = query.limit {
+        entries.drain(..entries.len().saturating_sub(limit));
+    }
+
+    match desired_format(tokens) {
+        OutputFormat::Json => append_json(&entries)?,
+        OutputFormat::Text if entries.is_empty() => {
+            append_line("No mutating commands recorded for this selection.")?
+        }
+        OutputFormat::Text => entries.format_noreturn()?,
+    }
+    Ok(())
+}
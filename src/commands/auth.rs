@@ -9,11 +9,11 @@ use serde_json::json;
 use super::builder::{catalog_command, CommandDocs};
 use super::CliCommand;
 use crate::build_info;
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::config::get_config;
 use crate::errors::AppError;
+use crate::offline::require_online;
 use crate::output::set_semantic_output;
-use crate::services::AppServices;
 use crate::tokenizer::CommandTokenizer;
 
 const PROVIDER_DISCOVERY_TIMEOUT: Duration = Duration::from_secs(5);
@@ -30,6 +30,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                     "Discover the configured server's authentication providers without logging in. Use a provider name as the server.identity_scope setting or with --identity-scope.",
                 ),
                 examples: Some("--output json"),
+                ..CommandDocs::default()
             },
         ),
     );
@@ -39,19 +40,16 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
 pub struct AuthProviders {}
 
 impl CliCommand for AuthProviders {
-    fn execute(&self, _services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         render_auth_providers(tokens)
     }
 }
 
 pub(crate) fn render_auth_providers(tokens: &CommandTokenizer) -> Result<(), AppError> {
     let _query = AuthProviders::parse_tokens(tokens)?;
+    require_online("listing authentication providers")?;
     let config = get_config();
-    let base_url = format!(
-        "{}://{}:{}",
-        config.server.protocol, config.server.hostname, config.server.port
-    );
-    let client = BlockingClient::builder_from_url(base_url)?
+    let client = BlockingClient::builder(config.server.api_base_url()?)
         .validate_certs(config.server.ssl_validation)
         .timeout(PROVIDER_DISCOVERY_TIMEOUT)
         .user_agent(format!("hubuum-cli/{}", build_info::VERSION))
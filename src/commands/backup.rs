@@ -11,12 +11,13 @@ use super::builder::{catalog_command, CommandDocs};
 use super::task_submit::{run_task_backed, TaskSubmitOptions};
 use super::{desired_format, option_or_pos, render_task_record, CliCommand};
 use crate::autocomplete::{bool, file_paths};
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::domain::{BackupArtifact, RestoreReceipt};
 use crate::errors::AppError;
+use crate::masking::display_mask_keys;
 use crate::models::OutputFormat;
 use crate::output::{append_key_value, append_line, set_semantic_output};
-use crate::services::{AppServices, BackupInput, RunBackupInput};
+use crate::services::{BackupInput, RunBackupInput};
 use crate::tokenizer::CommandTokenizer;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -29,9 +30,10 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 CommandDocs {
                     about: Some("Create and securely save a full-system backup"),
                     long_about: Some(
-                        "Submit an administrator-only backup, wait for completion, and save the versioned JSON document. Backup files can contain credentials and are created with owner-only permissions on Unix.",
+                        "Submit an administrator-only backup, wait for completion, and save the versioned JSON document. Backup files can contain credentials and are created with owner-only permissions on Unix. While output.mask_keys is set, --reveal is required, as backups are saved unmasked.",
                     ),
                     examples: Some("--file hubuum-backup.json\n--file hubuum-backup.json --include-history false"),
+                    mutating: true,
                 },
             ),
         )
@@ -42,6 +44,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 BackupSubmit::default(),
                 CommandDocs {
                     about: Some("Submit a full-system backup task"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -79,6 +82,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                         "Validate a backup document and save the one-time restore capability in an owner-only receipt file. Staging does not replace server data.",
                     ),
                     examples: Some("--file hubuum-backup.json --receipt restore-receipt.json"),
+                    mutating: true,
                 },
             ),
         )
@@ -104,6 +108,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                         "Destructively replace all Hubuum data with the staged backup. Existing bearer tokens are invalidated. --yes is required.",
                     ),
                     examples: Some("--receipt restore-receipt.json --yes"),
+                    mutating: true,
                 },
             ),
         );
@@ -142,12 +147,13 @@ pub struct BackupCreate {
 }
 
 impl CliCommand for BackupCreate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         ensure_output_available(&query.file, query.force)?;
+        ensure_backup_revealed()?;
         let backup = BackupInput::new(query.include_history.unwrap_or(true))
             .idempotency_key(query.idempotency_key);
-        let artifact = services.gateway().run_backup(
+        let artifact = ctx.services().gateway().run_backup(
             RunBackupInput::new(backup)
                 .timeout_secs(query.timeout.or(Some(300)))
                 .poll_interval_secs(query.poll_interval.or(Some(1))),
@@ -170,14 +176,14 @@ pub struct BackupSubmit {
 }
 
 impl CliCommand for BackupSubmit {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let task = services.gateway().submit_backup(
+        let task = ctx.services().gateway().submit_backup(
             BackupInput::new(query.include_history.unwrap_or(true))
                 .idempotency_key(query.idempotency_key),
         )?;
         run_task_backed(
-            services,
+            ctx.services(),
             tokens,
             format!("backup {}", task.0.id),
             TaskSubmitOptions::default(),
@@ -188,15 +194,15 @@ impl CliCommand for BackupSubmit {
 
 #[derive(Debug, Clone, CommandArgs, Default)]
 pub struct BackupShow {
-    #[option(long = "task", help = "Backup task ID")]
+    #[option(long = "task", help = "Backup task ID", positional = 0)]
     task: Option<i32>,
 }
 
 impl CliCommand for BackupShow {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.task = option_or_pos(query.task, tokens, 0, "task")?;
-        let task = services.gateway().backup_task(
+        let task = ctx.services().gateway().backup_task(
             query
                 .task
                 .ok_or_else(|| AppError::MissingOptions(vec!["task".to_string()]))?,
@@ -207,7 +213,7 @@ impl CliCommand for BackupShow {
 
 #[derive(Debug, Clone, CommandArgs, Default)]
 pub struct BackupDownload {
-    #[option(long = "task", help = "Completed backup task ID")]
+    #[option(long = "task", help = "Completed backup task ID", positional = 0)]
     task: Option<i32>,
     #[option(
         short = "f",
@@ -225,11 +231,12 @@ pub struct BackupDownload {
 }
 
 impl CliCommand for BackupDownload {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.task = option_or_pos(query.task, tokens, 0, "task")?;
         ensure_output_available(&query.file, query.force)?;
-        let artifact = services.gateway().backup_output(
+        ensure_backup_revealed()?;
+        let artifact = ctx.services().gateway().backup_output(
             query
                 .task
                 .ok_or_else(|| AppError::MissingOptions(vec!["task".to_string()]))?,
@@ -260,11 +267,11 @@ pub struct RestoreStage {
 }
 
 impl CliCommand for RestoreStage {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         ensure_output_available(&query.receipt, query.force)?;
         let backup_json = read_to_string(&query.file)?;
-        let (record, receipt) = services.gateway().stage_restore(&backup_json)?;
+        let (record, receipt) = ctx.services().gateway().stage_restore(&backup_json)?;
         write_sensitive_file(&query.receipt, &receipt.json_pretty()?, query.force)?;
         let mut value = to_value(record)?;
         if let Some(object) = value.as_object_mut() {
@@ -286,10 +293,10 @@ pub struct RestoreStatus {
 }
 
 impl CliCommand for RestoreStatus {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let receipt = load_receipt(&query.receipt)?;
-        let status = services.gateway().restore_status(&receipt)?;
+        let status = ctx.services().gateway().restore_status(&receipt)?;
         render_structured_value(to_value(status)?, desired_format(tokens))
     }
 }
@@ -312,7 +319,7 @@ pub struct RestoreConfirm {
 }
 
 impl CliCommand for RestoreConfirm {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         if !query.yes {
             return Err(AppError::InvalidOption(
@@ -321,7 +328,7 @@ impl CliCommand for RestoreConfirm {
             ));
         }
         let receipt = load_receipt(&query.receipt)?;
-        let status = services.gateway().confirm_restore(&receipt)?;
+        let status = ctx.services().gateway().confirm_restore(&receipt)?;
         render_structured_value(to_value(status)?, desired_format(tokens))
     }
 }
@@ -355,6 +362,18 @@ fn load_receipt(path: &str) -> Result<RestoreReceipt, AppError> {
     RestoreReceipt::from_json(&read_to_string(path)?)
 }
 
+/// Backups are saved unmasked because a masked one could not be restored, so with
+/// `output.mask_keys` set they need `--reveal`.
+fn ensure_backup_revealed() -> Result<(), AppError> {
+    if display_mask_keys().is_empty() {
+        return Ok(());
+    }
+    Err(AppError::InvalidOption(
+        "Backups hold object data hidden by output.mask_keys in full, as a masked backup cannot be restored; pass --reveal to save it"
+            .to_string(),
+    ))
+}
+
 fn ensure_output_available(path: &str, force: bool) -> Result<(), AppError> {
     if Path::new(path).exists() && !force {
         return Err(AppError::InvalidOption(format!(
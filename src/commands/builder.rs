@@ -3,14 +3,20 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use tokio::task::spawn_blocking;
 
+use crate::audit_log::{record, AuditLogEntry};
 use crate::catalog::{
     AsyncCommandHandler, CommandCatalog, CommandCatalogBuilder, CommandContext, CommandInvocation,
     CommandOutcome, CommandSpec, CompletionSpec, OptionSpec, ScopeAction,
 };
-use crate::commands::{self, command_options, render_format, CliCommand};
+use crate::commands::{
+    self, command_options, fields_projection, render_format, validate_command_args, CliCommand,
+};
 use crate::errors::AppError;
+use crate::hooks::CommandHooks;
+use crate::offline::forget_saved_listings;
 use crate::output::{
-    reset_output, set_pipeline, set_pipeline_suffix, set_render_format, take_output,
+    add_warning, reset_output, semantic_output, set_pipeline, set_pipeline_suffix, set_redirected,
+    set_render_format, take_output,
 };
 use crate::tokenizer::CommandTokenizer;
 
@@ -19,14 +25,19 @@ pub(crate) struct CommandDocs {
     pub about: Option<&'static str>,
     pub long_about: Option<&'static str>,
     pub examples: Option<&'static str>,
+    /// Whether the command changes server state; see [`CommandSpec::mutating`].
+    pub mutating: bool,
 }
 
 pub fn build_command_catalog() -> CommandCatalog {
     let mut builder = CommandCatalogBuilder::new();
 
     commands::admin::register_commands(&mut builder);
+    commands::apply::register_commands(&mut builder);
     commands::backup::register_commands(&mut builder);
+    commands::bulk::register_commands(&mut builder);
     commands::audit::register_commands(&mut builder);
+    commands::cache::register_commands(&mut builder);
     commands::auth::register_commands(&mut builder);
     commands::jobs::register_commands(&mut builder);
     commands::class::register_commands(&mut builder);
@@ -39,7 +50,10 @@ pub fn build_command_catalog() -> CommandCatalog {
     commands::imports::register_commands(&mut builder);
     commands::task::register_commands(&mut builder);
     commands::theme::register_commands(&mut builder);
+    commands::token::register_commands(&mut builder);
     commands::object::register_commands(&mut builder);
+    commands::offline::register_commands(&mut builder);
+    commands::profile::register_commands(&mut builder);
     commands::relations::register_commands(&mut builder);
     commands::remote_target::register_commands(&mut builder);
     commands::event_sink::register_commands(&mut builder);
@@ -47,6 +61,7 @@ pub fn build_command_catalog() -> CommandCatalog {
     commands::event_delivery::register_commands(&mut builder);
     commands::search::register_commands(&mut builder);
     commands::service_account::register_commands(&mut builder);
+    commands::stats::register_commands(&mut builder);
     commands::me::register_commands(&mut builder);
     commands::metrics::register_commands(&mut builder);
     commands::history::register_commands(&mut builder);
@@ -75,6 +90,8 @@ where
             nargs: option.nargs,
             repeatable: option.repeatable,
             value_source: option.value_source,
+            raw: option.raw,
+            positional: option.positional,
             completion: match option.autocomplete {
                 Some(completion) => CompletionSpec::Dynamic(completion),
                 None => CompletionSpec::None,
@@ -88,9 +105,45 @@ where
         long_about: docs.long_about.map(str::to_string),
         examples: docs.examples.map(str::to_string),
         options,
+        own_credentials: false,
+        mutating: docs.mutating,
         handler: Arc::new(CommandHandler {
             command: Arc::new(command),
-        }) as Arc<dyn AsyncCommandHandler>,
+            mutating: docs.mutating,
+        }),
+    }
+}
+
+/// Marks `spec` as logging in with credentials of its own, such as `login`, so a rejected
+/// request is reported instead of renewing the current session and retrying.
+pub(crate) fn own_credentials(mut spec: CommandSpec) -> CommandSpec {
+    spec.own_credentials = true;
+    spec
+}
+
+/// Makes the REPL exit once `spec` succeeds, for commands such as `logout` that end the
+/// session the REPL was started with.
+pub(crate) fn exits_repl(mut spec: CommandSpec) -> CommandSpec {
+    spec.handler = Arc::new(ExitReplHandler(spec.handler));
+    spec
+}
+
+struct ExitReplHandler(Arc<dyn AsyncCommandHandler>);
+
+#[async_trait]
+impl AsyncCommandHandler for ExitReplHandler {
+    async fn execute(
+        &self,
+        ctx: CommandContext,
+        invocation: CommandInvocation,
+    ) -> Result<CommandOutcome, AppError> {
+        let mut outcome = self.0.execute(ctx, invocation).await?;
+        outcome.scope_action = ScopeAction::ExitRepl;
+        Ok(outcome)
+    }
+
+    fn validate(&self, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        self.0.validate(tokens)
     }
 }
 
@@ -99,6 +152,7 @@ where
     C: CliCommand + Clone + 'static,
 {
     command: Arc<C>,
+    mutating: bool,
 }
 
 #[async_trait]
@@ -112,23 +166,57 @@ where
         invocation: CommandInvocation,
     ) -> Result<CommandOutcome, AppError> {
         let command = self.command.clone();
-        let services = ctx.app.services.clone();
+        let mutating = self.mutating;
         let raw_line = invocation.raw_line.clone();
-        let pipeline = invocation.pipeline.clone();
+        let mut pipeline = invocation.pipeline.clone();
 
         spawn_blocking(move || {
             reset_output()?;
-            set_pipeline(pipeline)?;
-            set_pipeline_suffix(invocation.pipeline_suffix.clone())?;
             let cmd_name = invocation.command_path.last().cloned().ok_or_else(|| {
                 AppError::CommandExecutionError("Missing command name".to_string())
             })?;
 
-            let tokens = CommandTokenizer::new(&raw_line, &cmd_name, &command_options::<C>())?;
-            set_render_format(render_format(&tokens)?)?;
+            let option_defs = command_options::<C>();
+            let tokens = CommandTokenizer::new(&raw_line, &cmd_name, &option_defs)?
+                .with_command_path(&invocation.command_path);
+            let format = render_format(&tokens)?;
+            set_render_format(format)?;
+            // `--fields` trims the output last, after any pipe the user wrote.
+            pipeline.extend(fields_projection(&tokens, format)?);
+            set_pipeline(pipeline)?;
+            set_pipeline_suffix(invocation.pipeline_suffix.clone())?;
+            set_redirected(invocation.redirected)?;
 
-            command.execute(services.as_ref(), &tokens)?;
-            services.invalidate_completion();
+            let hooks = CommandHooks::new(
+                &ctx.config().hooks,
+                &invocation.command_path,
+                tokens.get_options(),
+                &option_defs,
+            );
+            hooks.run_pre()?;
+            let result = command.execute(&ctx, &tokens);
+            hooks.run_post(&result)?;
+            if mutating {
+                ctx.services().gateway().invalidate_caches();
+                forget_saved_listings();
+                let entry = AuditLogEntry::new(
+                    &ctx.config().server,
+                    &invocation.command_path,
+                    &raw_line,
+                    &semantic_output()?,
+                    &result,
+                );
+                if let Err(error) = record(&entry) {
+                    add_warning(format!("Could not write the local audit log: {error}"))?;
+                }
+            }
+            result?;
+            ctx.services().invalidate_completion();
+            ctx.services().completion_store().record_seen_names(
+                &invocation.command_path,
+                mutating,
+                &semantic_output()?,
+            );
 
             Ok(CommandOutcome {
                 output: take_output()?,
@@ -139,4 +227,8 @@ where
         .await
         .map_err(|err| AppError::CommandExecutionError(err.to_string()))?
     }
+
+    fn validate(&self, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        validate_command_args::<C>(tokens)
+    }
 }
@@ -0,0 +1,194 @@
+use std::fs::{read_to_string, remove_file, write};
+use std::path::Path;
+
+use cli_command_derive::CommandArgs;
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, to_string_pretty};
+
+use super::builder::{catalog_command, CommandDocs};
+use super::{desired_format, render_bulk_summary, select_targets, CliCommand};
+use crate::autocomplete::file_paths;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
+use crate::domain::{BulkSummary, RetryFile, RetryItem};
+use crate::errors::AppError;
+use crate::formatting::append_json;
+use crate::models::OutputFormat;
+use crate::output::{add_warning, append_line};
+use crate::services::Checkpoint;
+use crate::tokenizer::CommandTokenizer;
+
+/// Where a failed bulk operation saves its retry file unless told otherwise.
+pub(super) const DEFAULT_RETRY_FILE: &str = "failed.json";
+
+/// Where a bulk operation reading `file` records the items it completed, for `--resume`.
+pub(super) fn checkpoint_path(file: &str) -> String {
+    format!("{file}.checkpoint")
+}
+
+/// Removes `checkpoint` once nothing failed, or names it in `summary` for `--resume`.
+pub(super) fn finish_checkpoint(
+    checkpoint: Checkpoint,
+    summary: &mut BulkSummary,
+) -> Result<(), AppError> {
+    if summary.has_failures() {
+        summary.checkpoint = Some(checkpoint.path().display().to_string());
+        return Ok(());
+    }
+    let path = checkpoint.path().display().to_string();
+    if let Err(error) = checkpoint.remove() {
+        add_warning(format!("Could not remove {path}: {error}"))?;
+    }
+    Ok(())
+}
+
+/// Tells how to continue a run that left a checkpoint behind.
+pub(super) fn render_resume_hint(summary: &BulkSummary) -> Result<(), AppError> {
+    if let Some(checkpoint) = &summary.checkpoint {
+        append_line(format!(
+            "Completed items are recorded in {checkpoint}; run the command again with --resume to skip them."
+        ))?;
+    }
+    Ok(())
+}
+
+pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
+    builder.add_command(
+        &["bulk"],
+        catalog_command(
+            "retry",
+            BulkRetry::default(),
+            CommandDocs {
+                about: Some("Send the failed changes of a bulk operation again"),
+                long_about: Some(
+                    "Read a retry file written by a failed apply, class import-schemas, or object import and send each saved change again, in order. Changes that still fail are written back to the file with their new error; once everything succeeds the file is removed.",
+                ),
+                examples: Some("--file failed.json\n--file failed.json --select"),
+                mutating: true,
+            },
+        ),
+    );
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct BulkRetry {
+    #[option(
+        short = "f",
+        long = "file",
+        help = "Retry file written by a failed bulk operation",
+        autocomplete = "file_paths"
+    )]
+    pub file: String,
+    #[option(
+        long = "select",
+        help = "Pick the changes to send from a checklist; the rest stay in the file",
+        flag = "true"
+    )]
+    pub select: bool,
+}
+
+impl CliCommand for BulkRetry {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let path = Path::new(&query.file);
+        let retry = read_retry_file(path)?;
+        let (items, skipped) = if query.select {
+            select_targets(retry.items, RetryItem::label)?
+        } else {
+            (retry.items, Vec::new())
+        };
+
+        let gateway = ctx.services().gateway();
+        let runner = gateway.bulk_runner();
+        let mut summary = BulkSummary::default();
+        let mut remaining = RetryFile::default();
+        for mut item in items {
+            match runner.run_one(&item.operation, |operation| {
+                gateway.apply_plan_operation(operation)
+            }) {
+                Ok(()) => summary.record(item.action),
+                Err(error) => {
+                    summary.record_failure(item.label(), &error);
+                    item.error = error.to_string();
+                    remaining.items.push(item);
+                }
+            }
+        }
+
+        let still_failing = remaining.items.len();
+        remaining.items.extend(skipped);
+        if remaining.items.is_empty() {
+            remove_file(path)?;
+        } else {
+            write_retry_file(path, &remaining)?;
+            summary.retry_file = Some(query.file.clone());
+        }
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json(&summary)?,
+            OutputFormat::Text => {
+                render_bulk_summary(&summary)?;
+                if remaining.items.is_empty() {
+                    append_line(format!("All changes applied; removed {}.", query.file))?;
+                } else if remaining.items.len() == still_failing {
+                    append_line(format!(
+                        "{still_failing} change(s) still failing were saved back to {}.",
+                        query.file
+                    ))?;
+                } else {
+                    append_line(format!(
+                        "{still_failing} change(s) still failing and {} skipped were saved back to {}.",
+                        remaining.items.len() - still_failing,
+                        query.file
+                    ))?;
+                }
+            }
+        }
+        if summary.has_failures() {
+            return Err(AppError::Quiet);
+        }
+        Ok(())
+    }
+}
+
+fn read_retry_file(path: &Path) -> Result<RetryFile, AppError> {
+    from_str(&read_to_string(path)?).map_err(|error| {
+        AppError::ParseError(format!("{} is not a retry file: {error}", path.display()))
+    })
+}
+
+pub(super) fn write_retry_file(path: &Path, retry: &RetryFile) -> Result<(), AppError> {
+    write(path, to_string_pretty(retry)? + "\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::domain::{PlanAction, PlanKind, PlanOperation, RetryItem};
+
+    #[test]
+    fn retry_files_round_trip_and_reject_other_json() {
+        let directory = tempdir().expect("temporary directory should be created");
+        let path = directory.path().join("failed.json");
+        let retry = RetryFile {
+            items: vec![RetryItem {
+                action: PlanAction::Delete,
+                kind: PlanKind::Object,
+                name: "Host/web01".to_string(),
+                operation: PlanOperation::DeleteObject {
+                    class: "Host".to_string(),
+                    name: "web01".to_string(),
+                },
+                error: "API Error: Status 409".to_string(),
+            }],
+        };
+
+        write_retry_file(&path, &retry).unwrap();
+        assert_eq!(read_retry_file(&path).unwrap(), retry);
+
+        write(&path, "{\"collections\": []}").unwrap();
+        let error = read_retry_file(&path).unwrap_err().to_string();
+        assert!(error.contains("is not a retry file"), "{error}");
+    }
+}
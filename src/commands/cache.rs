@@ -0,0 +1,50 @@
+use cli_command_derive::CommandArgs;
+use serde::{Deserialize, Serialize};
+
+use super::builder::{catalog_command, CommandDocs};
+use super::{desired_format, CliCommand};
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
+use crate::errors::AppError;
+use crate::formatting::append_json_message;
+use crate::models::OutputFormat;
+use crate::offline::forget_saved_entries;
+use crate::output::append_line;
+use crate::tokenizer::CommandTokenizer;
+
+pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
+    builder.add_command(
+        &["cache"],
+        catalog_command(
+            "clear",
+            CacheClear::default(),
+            CommandDocs {
+                about: Some("Drop cached server responses"),
+                long_about: Some(
+                    "Drop the class, collection, and group listings and the entities cached for cache.time seconds, and the names cached for completion, so the next commands read fresh data from the server. The listings and names saved for offline mode are dropped too. Changes made through this CLI already clear the cache; use this after changes made elsewhere.",
+                ),
+                examples: Some("\n--output json"),
+                ..CommandDocs::default()
+            },
+        ),
+    );
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct CacheClear {}
+
+impl CliCommand for CacheClear {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let cleared = ctx.services().gateway().invalidate_caches();
+        ctx.services().completion_store().forget_names();
+        forget_saved_entries();
+        let message = match cleared {
+            1 => "Cleared 1 cached listing".to_string(),
+            count => format!("Cleared {count} cached listings"),
+        };
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json_message(&message)?,
+            OutputFormat::Text => append_line(message)?,
+        }
+        Ok(())
+    }
+}
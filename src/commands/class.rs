@@ -1,23 +1,34 @@
+use std::fs::{read_dir, read_to_string};
+use std::path::Path;
+
 use cli_command_derive::CommandArgs;
 use serde::{Deserialize, Serialize};
-use serde_json::{to_string_pretty, Value};
+use serde_json::{json, to_string_pretty, Value};
 
+use super::apply::{diff_layout, execute_plan, render_plan, select_plan};
 use super::builder::{catalog_command, CommandDocs};
 use super::{
-    build_list_query, contains_clause, desired_format, render_list_page, required_option_or_pos,
-    CliCommand,
+    build_list_query, contains_clause, desired_format, json_option_or_file, render_list_page,
+    required_option_or_pos, CliCommand,
 };
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 
-use crate::autocomplete::{bool, class_sort, class_where, classes, collections};
+use crate::autocomplete::{bool, class_sort, class_where, classes, collections, file_paths};
 use crate::config::get_config;
-use crate::domain::ClassShowRecord;
+use crate::domain::{ApplyPlan, ClassShowRecord, DesiredState, SchemaDrift};
 use crate::errors::AppError;
-use crate::formatting::{append_json_message, render_related_class_tree_with_key, OutputFormatter};
+use crate::formatting::{
+    append_json, append_json_message, render_related_class_tree_with_key, render_value_diff,
+    OutputFormatter,
+};
+use crate::masking::masked_json;
 use crate::models::OutputFormat;
 use crate::output::{append_key_value, append_line};
-use crate::services::{AppServices, ClassUpdateInput, CreateClassInput, RelationTraversalOptions};
+use crate::safety::confirm_protected_collection;
+use crate::services::{ClassUpdateInput, CreateClassInput, RelationTraversalOptions};
+use crate::theme::{paint, ThemeRole};
 use crate::tokenizer::CommandTokenizer;
+use crate::verify::{ensure_found, ensure_visible};
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
     builder
@@ -31,8 +42,10 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                     long_about: Some("Create a new class with the specified properties."),
                     examples: Some(
                         r#"-n MyClass -N collection_1 -d "My class description"
---name MyClass --collection collection_1 --description 'My class' --schema '{\"type\": \"object\"}'"#,
+--name MyClass --collection collection_1 --description 'My class' --schema '{\"type\": \"object\"}'
+-n MyClass -N collection_1 -d "My class" --schema-file ./schema.json"#,
                     ),
+                    mutating: true,
                 },
             ),
         )
@@ -54,6 +67,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ClassDelete::default(),
                 CommandDocs {
                     about: Some("Delete a class"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -69,6 +83,40 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 },
             ),
         )
+        .add_command(
+            &["class"],
+            catalog_command(
+                "import-schemas",
+                ClassImportSchemas::default(),
+                CommandDocs {
+                    about: Some("Create or update classes from a directory of JSON Schema files"),
+                    long_about: Some(
+                        "Read every .json file in --dir as a JSON Schema and plan one class per file in --collection. The class name comes from the last segment of the schema's $id, or else from the file name without .json and .schema. The class description comes from the schema's description or title. Without --yes the planned creates and updates are shown and nothing is changed. --diff shows what each update changes.",
+                    ),
+                    examples: Some(
+                        "--dir ./schemas --collection prod --diff\n--dir ./schemas --collection prod --yes",
+                    ),
+                    mutating: true,
+                },
+            ),
+        )
+        .add_command(
+            &["class"],
+            catalog_command(
+                "schema-drift",
+                ClassSchemaDrift::default(),
+                CommandDocs {
+                    about: Some("Compare class schemas with a directory of JSON Schema files"),
+                    long_about: Some(
+                        "Read every .json file in --dir the same way as class import-schemas and report classes whose server-side schema differs: added (file without a class), removed (class without a file), or changed (with the top-level schema keywords that differ). With --collection, only classes in that collection count as removed. With --check, drift is reported as an error so scripts and CI fail. --diff (or --side-by-side) shows each drifted schema against its file; JSON output carries the difference as a JSON Patch.",
+                    ),
+                    examples: Some(
+                        "--dir ./schemas --diff\n--dir ./schemas --collection prod --check",
+                    ),
+                    ..CommandDocs::default()
+                },
+            ),
+        )
         .add_command(
             &["class"],
             catalog_command(
@@ -81,6 +129,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                         r#"modify my-class --rename new-class
 modify --name my-class --description "Updated description" --collection other-ns"#,
                     ),
+                    mutating: true,
                 },
             ),
         );
@@ -103,9 +152,16 @@ pub struct ClassNew {
         short = "s",
         long = "schema",
         help = "JSON schema for the class",
-        value_source = true
+        value_source = true,
+        raw = true
     )]
     pub json_schema: Option<Value>,
+    #[option(
+        long = "schema-file",
+        help = "Read the JSON schema for the class from a file",
+        autocomplete = "file_paths"
+    )]
+    pub schema_file: Option<String>,
     #[option(
         short = "v",
         long = "validate",
@@ -113,18 +169,36 @@ pub struct ClassNew {
         autocomplete = "bool"
     )]
     pub validate_schema: Option<bool>,
+    #[option(
+        long = "verify",
+        help = "Re-fetch the class afterwards and fail if the change is not visible",
+        flag = "true"
+    )]
+    pub verify: bool,
 }
 
 impl CliCommand for ClassNew {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
-        let new = Self::parse_tokens(tokens)?;
-        let result = services.gateway().create_class(CreateClassInput {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let mut new = Self::parse_tokens(tokens)?;
+        new.json_schema =
+            json_option_or_file(new.json_schema.take(), new.schema_file.take(), "schema")?;
+        let expected = class_expectation(
+            &new.name,
+            Some(&new.collection),
+            Some(&new.description),
+            new.json_schema.as_ref(),
+            new.validate_schema,
+        );
+        let result = ctx.services().gateway().create_class(CreateClassInput {
             name: new.name,
             collection: new.collection,
             description: new.description,
             json_schema: new.json_schema,
             validate_schema: new.validate_schema,
         })?;
+        if new.verify {
+            verify_class(ctx, &expected)?;
+        }
 
         match desired_format(tokens) {
             OutputFormat::Json => result.format_json_noreturn()?,
@@ -135,13 +209,191 @@ impl CliCommand for ClassNew {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct ClassImportSchemas {
+    #[option(
+        short = "D",
+        long = "dir",
+        help = "Directory of JSON Schema files",
+        autocomplete = "file_paths"
+    )]
+    pub dir: String,
+    #[option(
+        short = "N",
+        long = "collection",
+        help = "Collection for the classes",
+        autocomplete = "collections"
+    )]
+    pub collection: String,
+    #[option(
+        long = "yes",
+        help = "Apply the plan instead of only showing it",
+        flag = "true"
+    )]
+    pub yes: bool,
+    #[option(
+        long = "diff",
+        help = "Show a diff of each schema update",
+        flag = "true"
+    )]
+    pub diff: bool,
+    #[option(
+        long = "side-by-side",
+        help = "Show schema diffs side by side",
+        flag = "true"
+    )]
+    pub side_by_side: bool,
+    #[option(
+        long = "select",
+        help = "Pick the changes to keep from a checklist first",
+        flag = "true"
+    )]
+    pub select: bool,
+    #[option(
+        long = "retry-file",
+        help = "Where to save changes that could not be applied (default: failed.json)",
+        autocomplete = "file_paths"
+    )]
+    pub retry_file: Option<String>,
+}
+
+impl CliCommand for ClassImportSchemas {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let files = read_schema_dir(Path::new(&query.dir))?;
+        let desired = DesiredState::from_schema_files(&files, &query.collection)?;
+        let current = ctx.services().gateway().current_class_state()?;
+        let mut plan = ApplyPlan::compute(&desired, &current, false)?;
+        if query.select {
+            plan = select_plan(plan)?;
+        }
+
+        let diff = diff_layout(query.diff, query.side_by_side);
+        if !query.yes || plan.is_empty() {
+            return render_plan(tokens, &plan, None, diff);
+        }
+        execute_plan(
+            ctx,
+            tokens,
+            "class import-schemas",
+            &plan,
+            diff,
+            query.retry_file.as_deref(),
+            None,
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct ClassSchemaDrift {
+    #[option(
+        short = "D",
+        long = "dir",
+        help = "Directory of JSON Schema files",
+        autocomplete = "file_paths"
+    )]
+    pub dir: String,
+    #[option(
+        short = "N",
+        long = "collection",
+        help = "Only report server classes in this collection as removed",
+        autocomplete = "collections"
+    )]
+    pub collection: Option<String>,
+    #[option(long = "check", help = "Fail when any drift is found", flag = "true")]
+    pub check: bool,
+    #[option(
+        long = "diff",
+        help = "Show a diff of each drifted schema",
+        flag = "true"
+    )]
+    pub diff: bool,
+    #[option(
+        long = "side-by-side",
+        help = "Show schema diffs side by side",
+        flag = "true"
+    )]
+    pub side_by_side: bool,
+}
+
+impl CliCommand for ClassSchemaDrift {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let files = read_schema_dir(Path::new(&query.dir))?;
+        let collection = query.collection.as_deref();
+        let desired = DesiredState::from_schema_files(&files, collection.unwrap_or_default())?;
+        let current = ctx.services().gateway().current_class_state()?;
+        let drift = SchemaDrift::compute(&desired, &current, collection);
+
+        if query.check && !drift.is_empty() {
+            return Err(AppError::CommandExecutionError(format!(
+                "Schema drift in {} class(es): {}",
+                drift.len(),
+                drift
+                    .iter()
+                    .map(|entry| format!("{} ({})", entry.class, entry.status.as_str()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json(&drift)?,
+            OutputFormat::Text if drift.is_empty() => {
+                append_line("No schema drift. The server matches the schema files.")?
+            }
+            OutputFormat::Text => {
+                drift.format_noreturn()?;
+                if let Some(layout) = diff_layout(query.diff, query.side_by_side) {
+                    for entry in &drift {
+                        append_line("")?;
+                        append_line(paint(
+                            ThemeRole::Heading,
+                            format!("{} ({})", entry.class, entry.status.as_str()),
+                        ))?;
+                        append_line(render_value_diff(&entry.diff, ("server", "file"), layout))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the `.json` files directly in `dir` as `(file name, contents)`, sorted by name.
+pub(super) fn read_schema_dir(dir: &Path) -> Result<Vec<(String, String)>, AppError> {
+    let mut files = Vec::new();
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+        let is_json = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        if !path.is_file() || !is_json {
+            continue;
+        }
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        files.push((file_name, read_to_string(&path)?));
+    }
+    if files.is_empty() {
+        return Err(AppError::ParseError(format!(
+            "No .json schema files found in {}",
+            dir.display()
+        )));
+    }
+    files.sort();
+    Ok(files)
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct ClassInfo {
     #[option(
         short = "n",
         long = "name",
         help = "Name of the class",
-        autocomplete = "classes"
+        autocomplete = "classes",
+        positional = 0
     )]
     pub name: Option<String>,
     #[option(
@@ -158,11 +410,11 @@ pub struct ClassInfo {
 }
 
 impl CliCommand for ClassInfo {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "name")?;
-        let config = get_config();
-        let details = services.gateway().class_show_details(
+        let config = ctx.config();
+        let details = ctx.services().gateway().class_show_details(
             &name,
             &RelationTraversalOptions {
                 include_self_class: query
@@ -174,7 +426,7 @@ impl CliCommand for ClassInfo {
 
         match desired_format(tokens) {
             OutputFormat::Json => {
-                append_line(to_string_pretty(&details)?)?;
+                append_line(to_string_pretty(&masked_json(&details)?)?)?;
             }
             OutputFormat::Text => {
                 render_class_show_text(&details)?;
@@ -199,17 +451,26 @@ pub struct ClassDelete {
         short = "n",
         long = "name",
         help = "Name of the class",
-        autocomplete = "classes"
+        autocomplete = "classes",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for ClassDelete {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "name")?;
 
-        services.gateway().delete_class(&name)?;
+        let safety = &ctx.config().safety;
+        if safety.has_protected_collections() {
+            let collection = ctx
+                .services()
+                .gateway()
+                .class_collection_name_exact(&name)?;
+            confirm_protected_collection(safety, &format!("class delete {name}"), &collection)?;
+        }
+        ctx.services().gateway().delete_class(&name)?;
 
         let message = format!("Class '{name}' deleted successfully");
 
@@ -228,7 +489,8 @@ pub struct ClassModify {
         short = "n",
         long = "name",
         help = "Name of the class",
-        autocomplete = "classes"
+        autocomplete = "classes",
+        positional = 0
     )]
     pub name: Option<String>,
     #[option(short = "r", long = "rename", help = "Rename the class")]
@@ -250,7 +512,8 @@ pub struct ClassModify {
         short = "s",
         long = "schema",
         help = "JSON schema for the class",
-        value_source = true
+        value_source = true,
+        raw = true
     )]
     pub json_schema: Option<Value>,
     #[option(
@@ -260,14 +523,27 @@ pub struct ClassModify {
         autocomplete = "bool"
     )]
     pub validate_schema: Option<bool>,
+    #[option(
+        long = "verify",
+        help = "Re-fetch the class afterwards and fail if the change is not visible",
+        flag = "true"
+    )]
+    pub verify: bool,
 }
 
 impl CliCommand for ClassModify {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "name")?;
+        let expected = class_expectation(
+            query.rename.as_ref().unwrap_or(&name),
+            query.collection.as_ref(),
+            query.description.as_ref(),
+            query.json_schema.as_ref(),
+            query.validate_schema,
+        );
 
-        let updated = services.gateway().update_class(ClassUpdateInput {
+        let updated = ctx.services().gateway().update_class(ClassUpdateInput {
             name,
             rename: query.rename,
             collection: query.collection,
@@ -275,6 +551,9 @@ impl CliCommand for ClassModify {
             json_schema: query.json_schema,
             validate_schema: query.validate_schema,
         })?;
+        if query.verify {
+            verify_class(ctx, &expected)?;
+        }
 
         match desired_format(tokens) {
             OutputFormat::Json => updated.format_json_noreturn()?,
@@ -285,6 +564,43 @@ impl CliCommand for ClassModify {
     }
 }
 
+/// The values a class create or modify asked for; options left out are not checked.
+fn class_expectation(
+    name: &str,
+    collection: Option<&String>,
+    description: Option<&String>,
+    json_schema: Option<&Value>,
+    validate_schema: Option<bool>,
+) -> Value {
+    let mut expected = json!({ "name": name });
+    for (field, value) in [
+        ("collection", collection.map(|value| json!(value))),
+        ("description", description.map(|value| json!(value))),
+        ("json_schema", json_schema.cloned()),
+        ("validate_schema", validate_schema.map(Value::Bool)),
+    ] {
+        if let Some(value) = value {
+            expected[field] = value;
+        }
+    }
+    expected
+}
+
+/// Re-fetches the class named in `expected` and checks that it shows every expected value.
+fn verify_class(ctx: &CommandContext, expected: &Value) -> Result<(), AppError> {
+    let name = expected["name"].as_str().unwrap_or_default();
+    let entity = format!("class '{name}'");
+    let class = ensure_found(&entity, ctx.services().gateway().get_class(name))?.0;
+    let actual = json!({
+        "name": class.name,
+        "collection": class.collection.name,
+        "description": class.description,
+        "json_schema": class.json_schema,
+        "validate_schema": class.validate_schema,
+    });
+    ensure_visible(&entity, expected, &actual)
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct ClassList {
     #[option(
@@ -320,10 +636,16 @@ pub struct ClassList {
         flag = "true"
     )]
     pub include_total: Option<bool>,
+    #[option(
+        long = "counts",
+        help = "Add the number of objects in each class",
+        flag = "true"
+    )]
+    pub counts: Option<bool>,
 }
 
 impl CliCommand for ClassList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let list_query = build_list_query(
             &query.where_clauses,
@@ -340,7 +662,14 @@ impl CliCommand for ClassList {
             .into_iter()
             .flatten(),
         )?;
-        let classes = services.gateway().list_classes(&list_query)?;
+        if query.counts.unwrap_or(false) {
+            let classes = ctx
+                .services()
+                .gateway()
+                .list_classes_with_counts(&list_query)?;
+            return render_list_page(tokens, &classes);
+        }
+        let classes = ctx.services().gateway().list_classes(&list_query)?;
         render_list_page(tokens, &classes)
     }
 }
@@ -351,16 +680,13 @@ mod tests {
     use serial_test::serial;
 
     use super::render_class_show_text;
-    use crate::domain::{ClassRecord, ClassShowRecord, RelatedClassTreeNode};
+    use crate::domain::{ClassCountRecord, ClassRecord, ClassShowRecord, RelatedClassTreeNode};
+    use crate::formatting::TableRenderable;
     use crate::output::{reset_output, take_output};
 
-    #[test]
-    #[serial]
-    fn class_show_renders_relations_before_object_summary() {
-        reset_output().expect("output should reset");
-        let details = ClassShowRecord {
-            class: ClassRecord(
-                from_value(json!({
+    fn class_fixture() -> ClassRecord {
+        ClassRecord(
+            from_value(json!({
                     "id": 1,
                     "name": "Jacks",
                     "description": "",
@@ -373,11 +699,56 @@ mod tests {
                     },
                     "json_schema": {},
                     "validate_schema": false,
-                    "created_at": "2024-01-01T00:00:00Z",
-                    "updated_at": "2024-01-01T00:00:00Z"
-                }))
-                .expect("class fixture should deserialize"),
-            ),
+                "created_at": "2024-01-01T00:00:00Z",
+                "updated_at": "2024-01-01T00:00:00Z"
+            }))
+            .expect("class fixture should deserialize"),
+        )
+    }
+
+    #[test]
+    fn class_counts_add_an_objects_column_and_json_field() {
+        let record = ClassCountRecord {
+            class: class_fixture(),
+            object_count: Some(42),
+        };
+
+        let headers = ClassCountRecord::headers();
+        let objects = headers
+            .iter()
+            .position(|header| *header == "Objects")
+            .expect("counts should add an Objects column");
+        assert_eq!(headers[objects - 1], "Collection");
+        assert_eq!(record.row()[objects], "42");
+
+        let json = serde_json::to_value(&record).expect("record should serialize");
+        assert_eq!(json["name"], "Jacks");
+        assert_eq!(json["object_count"], 42);
+    }
+
+    #[test]
+    fn class_counts_without_a_server_total_render_as_unknown() {
+        let record = ClassCountRecord {
+            class: class_fixture(),
+            object_count: None,
+        };
+
+        let objects = ClassCountRecord::headers()
+            .iter()
+            .position(|header| *header == "Objects")
+            .expect("counts should add an Objects column");
+        assert_eq!(record.row()[objects], "-");
+
+        let json = serde_json::to_value(&record).expect("record should serialize");
+        assert!(json["object_count"].is_null());
+    }
+
+    #[test]
+    #[serial]
+    fn class_show_renders_relations_before_object_summary() {
+        reset_output().expect("output should reset");
+        let details = ClassShowRecord {
+            class: class_fixture(),
             objects: vec![],
             related_classes: vec![RelatedClassTreeNode {
                 id: 2,
@@ -1,25 +1,29 @@
 use cli_command_derive::CommandArgs;
 use hubuum_client::FilterOperator;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, to_value, Value};
 use strum::IntoEnumIterator;
 
 use super::builder::{catalog_command, CommandDocs};
 use super::{
-    build_list_query, desired_format, render_list_page, required_option_or_pos, CliCommand,
+    build_list_query, desired_format, render_format, render_list_page, required_option_or_pos,
+    CliCommand,
 };
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 
 use crate::autocomplete::{
     collection_sort, collection_where, collections, groups, principal_kinds, principal_names,
 };
-use crate::domain::CollectionPermission;
+use crate::domain::{CollectionPermission, PermissionChanges};
 use crate::errors::AppError;
-use crate::formatting::{append_json_message, OutputFormatter};
+use crate::formatting::{append_json_message, human_bytes, human_count, OutputFormatter};
 use crate::list_query::filter_clause;
 use crate::models::OutputFormat;
-use crate::output::{append_json, append_line};
+use crate::output::{append_json, append_line, has_pipeline, RenderFormat};
+use crate::safety::confirm_protected_collection;
 use crate::services::{AppServices, CollectionUpdateInput, CreateCollectionInput};
 use crate::tokenizer::CommandTokenizer;
+use crate::verify::{ensure_found, ensure_visible};
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
     builder
@@ -30,6 +34,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 CollectionNew::default(),
                 CommandDocs {
                     about: Some("Create a collection"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -52,6 +57,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 CollectionDelete::default(),
                 CommandDocs {
                     about: Some("Delete a collection"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -67,6 +73,21 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 },
             ),
         )
+        .add_command(
+            &["collection"],
+            catalog_command(
+                "usage",
+                CollectionUsageCommand::default(),
+                CommandDocs {
+                    about: Some("Summarize classes, objects, and data size in a collection"),
+                    long_about: Some(
+                        "Count the objects of every class in a collection and sum the size of their JSON data. Every object in the collection's classes is fetched, so this can take a while on large collections.",
+                    ),
+                    examples: Some("usage prod\n--name prod --output csv"),
+                    ..CommandDocs::default()
+                },
+            ),
+        )
         .add_command(
             &["collection"],
             catalog_command(
@@ -79,6 +100,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                         r#"modify my-collection --rename other-ns
 modify --name my-collection --description "Updated description""#,
                     ),
+                    mutating: true,
                 },
             ),
         )
@@ -96,6 +118,7 @@ modify --name my-collection --description "Updated description""#,
                         r#"list my-collection
 list --name my-collection"#,
                     ),
+                    ..CommandDocs::default()
                 },
             ),
         )
@@ -107,12 +130,15 @@ list --name my-collection"#,
                 CommandDocs {
                     about: Some("Grant permissions on a collection"),
                     long_about: Some(
-                        "Grant collection permissions to a group. Pass the collection as the first positional argument or with --name, then select permissions with --all or individual permission flags.",
+                        "Grant collection permissions to a group. Pass the collection as the first positional argument or with --name, then select permissions with --all or individual permission flags. With --exact the selected permissions become the group's complete grant: the permissions to add and to revoke are shown, and nothing changes until the command is run again with --yes.",
                     ),
                     examples: Some(
                         r#"set my-collection --group editors --all
-set --name my-collection --group readers --ReadCollection --ReadClass --ReadObject"#,
+set --name my-collection --group readers --ReadCollection --ReadClass --ReadObject
+set my-collection --group readers --ReadCollection --ReadObject --exact
+set my-collection --group readers --ReadCollection --ReadObject --exact --yes"#,
                     ),
+                    mutating: true,
                 },
             ),
         )
@@ -130,6 +156,7 @@ set --name my-collection --group readers --ReadCollection --ReadClass --ReadObje
                         r#"principal-permissions my-collection --principal-kind group --principal admins
 principal-permissions --name my-collection --principal-kind user --principal alice"#,
                     ),
+                    ..CommandDocs::default()
                 },
             ),
         );
@@ -151,18 +178,29 @@ pub struct CollectionNew {
         help = "Name of the group owning collection"
     )]
     pub owner: String,
+    #[option(
+        long = "verify",
+        help = "Re-fetch the collection afterwards and fail if the change is not visible",
+        flag = "true"
+    )]
+    pub verify: bool,
 }
 
 impl CliCommand for CollectionNew {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let new = Self::parse_tokens(tokens)?;
-        let collection = services
+        let expected = json!({ "name": new.name, "description": new.description });
+        let collection = ctx
+            .services()
             .gateway()
             .create_collection(CreateCollectionInput {
                 name: new.name,
                 description: new.description,
                 owner: new.owner,
             })?;
+        if new.verify {
+            verify_collection(ctx.services(), &expected)?;
+        }
 
         match desired_format(tokens) {
             OutputFormat::Json => collection.format_json_noreturn()?,
@@ -210,7 +248,7 @@ pub struct CollectionList {
 }
 
 impl CliCommand for CollectionList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let list_query = build_list_query(
             &query.where_clauses,
@@ -237,7 +275,7 @@ impl CliCommand for CollectionList {
             .into_iter()
             .flatten(),
         )?;
-        let collections = services.gateway().list_collections(&list_query)?;
+        let collections = ctx.services().gateway().list_collections(&list_query)?;
         render_list_page(tokens, &collections)
     }
 }
@@ -248,16 +286,17 @@ pub struct CollectionInfo {
         short = "n",
         long = "name",
         help = "Name of the collection",
-        autocomplete = "collections"
+        autocomplete = "collections",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for CollectionInfo {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "collection")?;
-        let collection = services.gateway().get_collection(&name)?;
+        let collection = ctx.services().gateway().get_collection(&name)?;
 
         match desired_format(tokens) {
             OutputFormat::Json => collection.format_json_noreturn()?,
@@ -268,22 +307,82 @@ impl CliCommand for CollectionInfo {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct CollectionUsageCommand {
+    #[option(
+        short = "n",
+        long = "name",
+        help = "Name of the collection",
+        autocomplete = "collections",
+        positional = 0
+    )]
+    pub name: Option<String>,
+}
+
+impl CliCommand for CollectionUsageCommand {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let name = required_option_or_pos(query.name, tokens, 0, "collection")?;
+        let usage = ctx.services().gateway().collection_usage(&name)?;
+
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json(json!({
+                "collection": usage.collection,
+                "class_count": usage.classes.len(),
+                "object_count": usage.objects(),
+                "data_bytes": usage.data_bytes(),
+                "classes": usage.classes,
+            }))?,
+            OutputFormat::Text => {
+                usage.classes.format_noreturn()?;
+                if render_format(tokens)? != RenderFormat::Text || has_pipeline()? {
+                    return Ok(());
+                }
+                append_line(format!(
+                    "Collection '{}': {} class(es), {} object(s), {} of data.",
+                    usage.collection,
+                    usage.classes.len(),
+                    human_count(usage.objects()),
+                    human_bytes(usage.data_bytes())
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct CollectionDelete {
     #[option(
         short = "n",
         long = "name",
         help = "Name of the collection",
-        autocomplete = "collections"
+        autocomplete = "collections",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for CollectionDelete {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let collection_name = required_option_or_pos(query.name, tokens, 0, "collection")?;
-        services.gateway().delete_collection(&collection_name)?;
+        let safety = &ctx.config().safety;
+        if safety.has_protected_collections() {
+            let collection = ctx
+                .services()
+                .gateway()
+                .collection_name_exact(&collection_name)?;
+            confirm_protected_collection(
+                safety,
+                &format!("collection delete {collection_name}"),
+                &collection,
+            )?;
+        }
+        ctx.services()
+            .gateway()
+            .delete_collection(&collection_name)?;
 
         let message = format!("Collection '{}' deleted", collection_name);
 
@@ -302,7 +401,8 @@ pub struct CollectionModify {
         short = "n",
         long = "name",
         help = "Name of the collection",
-        autocomplete = "collections"
+        autocomplete = "collections",
+        positional = 0
     )]
     pub name: Option<String>,
     #[option(short = "r", long = "rename", help = "Rename the collection")]
@@ -313,20 +413,34 @@ pub struct CollectionModify {
         help = "Description of the collection"
     )]
     pub description: Option<String>,
+    #[option(
+        long = "verify",
+        help = "Re-fetch the collection afterwards and fail if the change is not visible",
+        flag = "true"
+    )]
+    pub verify: bool,
 }
 
 impl CliCommand for CollectionModify {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "collection")?;
+        let mut expected = json!({ "name": query.rename.as_ref().unwrap_or(&name) });
+        if let Some(description) = &query.description {
+            expected["description"] = json!(description);
+        }
 
-        let collection = services
+        let collection = ctx
+            .services()
             .gateway()
             .update_collection(CollectionUpdateInput {
                 name,
                 rename: query.rename,
                 description: query.description,
             })?;
+        if query.verify {
+            verify_collection(ctx.services(), &expected)?;
+        }
 
         match desired_format(tokens) {
             OutputFormat::Json => collection.format_json_noreturn()?,
@@ -337,23 +451,35 @@ impl CliCommand for CollectionModify {
     }
 }
 
+/// Re-fetches the collection named in `expected` and checks that it shows every expected value.
+fn verify_collection(services: &AppServices, expected: &Value) -> Result<(), AppError> {
+    let name = expected["name"].as_str().unwrap_or_default();
+    let entity = format!("collection '{name}'");
+    let collection = ensure_found(&entity, services.gateway().get_collection(name))?;
+    ensure_visible(&entity, expected, &to_value(collection)?)
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct CollectionPermissions {
     #[option(
         short = "n",
         long = "name",
         help = "Name of the collection",
-        autocomplete = "collections"
+        autocomplete = "collections",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for CollectionPermissions {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "collection")?;
 
-        let permissions = services.gateway().list_collection_permissions(&name)?;
+        let permissions = ctx
+            .services()
+            .gateway()
+            .list_collection_permissions(&name)?;
 
         let empty_message = format!("No permissions found for collection '{name}'");
 
@@ -374,7 +500,8 @@ pub struct CollectionPermissionsSet {
         short = "n",
         long = "name",
         help = "Name of the collection",
-        autocomplete = "collections"
+        autocomplete = "collections",
+        positional = 0
     )]
     pub name: Option<String>,
 
@@ -533,10 +660,24 @@ pub struct CollectionPermissionsSet {
         flag = true
     )]
     pub delete_object_relation: Option<bool>,
+
+    #[option(
+        long = "exact",
+        help = "Make these the group's only permissions on the collection, revoking any others",
+        flag = true
+    )]
+    pub exact: Option<bool>,
+
+    #[option(
+        long = "yes",
+        help = "Apply an --exact change instead of only showing it",
+        flag = true
+    )]
+    pub yes: Option<bool>,
 }
 
 impl CliCommand for CollectionPermissionsSet {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let new = Self::parse_tokens(tokens)?;
         let collection = required_option_or_pos(new.name, tokens, 0, "collection")?;
 
@@ -611,8 +752,18 @@ impl CliCommand for CollectionPermissionsSet {
         if perms.is_empty() {
             return Err(AppError::MissingOptions(vec!["permission".to_string()]));
         }
+        if new.exact.is_some() {
+            return set_exact_permissions(
+                ctx,
+                tokens,
+                &collection,
+                &new.group,
+                &perms,
+                new.yes.is_some(),
+            );
+        }
 
-        services
+        ctx.services()
             .gateway()
             .grant_collection_permissions(&collection, &new.group, &perms)?;
 
@@ -640,13 +791,77 @@ impl CliCommand for CollectionPermissionsSet {
     }
 }
 
+/// Shows what `--exact` adds and revokes, and replaces the grant when `apply` is set.
+fn set_exact_permissions(
+    ctx: &CommandContext,
+    tokens: &CommandTokenizer,
+    collection: &str,
+    group: &str,
+    permissions: &[CollectionPermission],
+    apply: bool,
+) -> Result<(), AppError> {
+    let gateway = ctx.services().gateway();
+    let current = gateway.group_collection_permissions(collection, group)?;
+    let changes = PermissionChanges::between(&current, permissions);
+    let applied = apply && !changes.is_empty();
+    if applied && !changes.removed.is_empty() {
+        confirm_protected_collection(
+            &ctx.config().safety,
+            &format!("collection permissions set {collection} --group {group} --exact"),
+            &gateway.collection_name(collection)?,
+        )?;
+    }
+    if applied {
+        gateway.replace_collection_permissions(collection, group, permissions)?;
+    }
+
+    match desired_format(tokens) {
+        OutputFormat::Json => append_json(json!({
+            "collection": collection,
+            "group": group,
+            "added": changes.added,
+            "removed": changes.removed,
+            "applied": applied,
+        }))?,
+        OutputFormat::Text => {
+            if changes.is_empty() {
+                append_line(format!(
+                    "Group '{group}' already has exactly these permissions on collection '{collection}'."
+                ))?;
+                return Ok(());
+            }
+            for permission in &changes.added {
+                append_line(format!("+ {permission}"))?;
+            }
+            for permission in &changes.removed {
+                append_line(format!("- {permission}"))?;
+            }
+            let counts = format!(
+                "{} to add, {} to revoke",
+                changes.added.len(),
+                changes.removed.len()
+            );
+            if applied {
+                append_line(format!(
+                    "Updated group '{group}' on collection '{collection}': {counts}."
+                ))?;
+            } else {
+                append_line(format!("Plan: {counts}."))?;
+                append_line("Run again with --yes to apply these changes.")?;
+            }
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct CollectionPrincipalPermissions {
     #[option(
         short = "n",
         long = "name",
         help = "Name of the collection",
-        autocomplete = "collections"
+        autocomplete = "collections",
+        positional = 0
     )]
     pub name: Option<String>,
 
@@ -666,12 +881,14 @@ pub struct CollectionPrincipalPermissions {
 }
 
 impl CliCommand for CollectionPrincipalPermissions {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let new = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(new.name, tokens, 0, "collection")?;
 
-        let principal_id = principal_id_by_name(services, &new.principal_kind, &new.principal)?;
-        let permissions = services
+        let principal_id =
+            principal_id_by_name(ctx.services(), &new.principal_kind, &new.principal)?;
+        let permissions = ctx
+            .services()
             .gateway()
             .principal_collection_permissions(&name, principal_id)?;
 
@@ -706,3 +923,27 @@ fn principal_id_by_name(services: &AppServices, kind: &str, name: &str) -> Resul
         other => Err(AppError::InvalidOption(format!("principal-kind={other}"))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::CollectionPrincipalPermissions;
+    use crate::commands::required_option_or_pos;
+    use crate::tokenizer::CommandTokenizer;
+
+    #[test]
+    fn principal_permissions_take_the_collection_as_a_positional() {
+        let tokens = CommandTokenizer::new(
+            "collection principal-permissions prod --principal-kind user -p alice",
+            "principal-permissions",
+            &[],
+        )
+        .expect("tokenization");
+
+        let query = CollectionPrincipalPermissions::parse_tokens(&tokens)
+            .expect("positional should be accepted");
+        let name = required_option_or_pos(query.name, &tokens, 0, "collection").unwrap();
+
+        assert_eq!(name, "prod");
+        assert_eq!(query.principal, "alice");
+    }
+}
@@ -9,7 +9,7 @@ use crate::autocomplete::{
     bool, classes, computed_field_paths, computed_operations, computed_result_types,
     objects_from_class,
 };
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::domain::{
     ClassComputationStateRecord, ComputedFieldMutationRecord, ComputedFieldPreviewRecord,
     ComputedFieldRecord, SharedComputedFieldListRecord,
@@ -19,8 +19,8 @@ use crate::formatting::{append_json, OutputFormatter};
 use crate::models::OutputFormat;
 use crate::output::{append_key_value, append_line};
 use crate::services::{
-    AppServices, ComputedDefinitionInput, ComputedOperationInput, ComputedOperationKind,
-    ComputedPatchInput, ComputedPreviewTarget, ComputedResultKind,
+    ComputedDefinitionInput, ComputedOperationInput, ComputedOperationKind, ComputedPatchInput,
+    ComputedPreviewTarget, ComputedResultKind,
 };
 use crate::tokenizer::CommandTokenizer;
 
@@ -47,6 +47,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                     examples: Some(
                         "--class Hosts --key average_load --label \"Average load\" --operation average --path /load/one --path /load/five --result-type number",
                     ),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -61,6 +62,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                     long_about: Some(
                         "Update a shared computed field by key. The current revision is required for optimistic concurrency.",
                     ),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -72,6 +74,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 SharedComputedDelete::default(),
                 CommandDocs {
                     about: Some("Delete a shared computed field"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -97,6 +100,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 SharedComputedRebuild::default(),
                 CommandDocs {
                     about: Some("Rebuild shared computed values for a class"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -119,6 +123,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 PersonalComputedCreate::default(),
                 CommandDocs {
                     about: Some("Create a personal computed field"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -133,6 +138,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                     long_about: Some(
                         "Update a personal computed field by class and key. The current revision is required for optimistic concurrency.",
                     ),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -144,6 +150,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 PersonalComputedDelete::default(),
                 CommandDocs {
                     about: Some("Delete a personal computed field"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -176,9 +183,10 @@ pub struct SharedComputedList {
 }
 
 impl CliCommand for SharedComputedList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let fields = services
+        let fields = ctx
+            .services()
             .gateway()
             .list_shared_computed_fields(&query.class)?;
         render_shared_list(tokens, &fields)
@@ -279,10 +287,11 @@ definition_args!(SharedComputedCreate);
 definition_args!(PersonalComputedCreate);
 
 impl CliCommand for SharedComputedCreate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let class = query.class().to_string();
-        let result = services
+        let result = ctx
+            .services()
             .gateway()
             .create_shared_computed_field(&class, definition_input(&query)?)?;
         render_mutation(tokens, &result)
@@ -290,10 +299,11 @@ impl CliCommand for SharedComputedCreate {
 }
 
 impl CliCommand for PersonalComputedCreate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let class = query.class().to_string();
-        let result = services
+        let result = ctx
+            .services()
             .gateway()
             .create_personal_computed_field(&class, definition_input(&query)?)?;
         render_definition(tokens, &result)
@@ -353,7 +363,7 @@ update_args!(SharedComputedUpdate);
 update_args!(PersonalComputedUpdate);
 
 impl CliCommand for SharedComputedUpdate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let patch = patch_input(
             query.revision,
@@ -365,16 +375,17 @@ impl CliCommand for SharedComputedUpdate {
             query.result_type,
             query.enabled,
         )?;
-        let result =
-            services
-                .gateway()
-                .update_shared_computed_field(&query.class, &query.key, patch)?;
+        let result = ctx.services().gateway().update_shared_computed_field(
+            &query.class,
+            &query.key,
+            patch,
+        )?;
         render_mutation(tokens, &result)
     }
 }
 
 impl CliCommand for PersonalComputedUpdate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let patch = patch_input(
             query.revision,
@@ -386,10 +397,11 @@ impl CliCommand for PersonalComputedUpdate {
             query.result_type,
             query.enabled,
         )?;
-        let result =
-            services
-                .gateway()
-                .update_personal_computed_field(&query.class, &query.key, patch)?;
+        let result = ctx.services().gateway().update_personal_computed_field(
+            &query.class,
+            &query.key,
+            patch,
+        )?;
         render_definition(tokens, &result)
     }
 }
@@ -417,9 +429,9 @@ delete_args!(SharedComputedDelete);
 delete_args!(PersonalComputedDelete);
 
 impl CliCommand for SharedComputedDelete {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let deleted = services.gateway().delete_shared_computed_field(
+        let deleted = ctx.services().gateway().delete_shared_computed_field(
             &query.class,
             &query.key,
             query.revision,
@@ -436,9 +448,9 @@ impl CliCommand for SharedComputedDelete {
 }
 
 impl CliCommand for PersonalComputedDelete {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let deleted = services.gateway().delete_personal_computed_field(
+        let deleted = ctx.services().gateway().delete_personal_computed_field(
             &query.class,
             &query.key,
             query.revision,
@@ -549,12 +561,13 @@ preview_args!(SharedComputedPreview);
 preview_args!(PersonalComputedPreview);
 
 impl CliCommand for SharedComputedPreview {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let class = query.class().to_string();
         let definition = definition_input(&query)?;
         let target = preview_target(query.object, query.data)?;
-        let preview = services
+        let preview = ctx
+            .services()
             .gateway()
             .preview_shared_computed_field(&class, definition, target)?;
         render_preview(tokens, &preview)
@@ -562,12 +575,13 @@ impl CliCommand for SharedComputedPreview {
 }
 
 impl CliCommand for PersonalComputedPreview {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let class = query.class().to_string();
         let definition = definition_input(&query)?;
         let target = preview_target(query.object, query.data)?;
-        let preview = services
+        let preview = ctx
+            .services()
             .gateway()
             .preview_personal_computed_field(&class, definition, target)?;
         render_preview(tokens, &preview)
@@ -586,9 +600,10 @@ pub struct SharedComputedRebuild {
 }
 
 impl CliCommand for SharedComputedRebuild {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let state = services
+        let state = ctx
+            .services()
             .gateway()
             .rebuild_shared_computed_fields(&query.class)?;
         match desired_format(tokens) {
@@ -621,7 +636,7 @@ pub struct PersonalComputedList {
 }
 
 impl CliCommand for PersonalComputedList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let list_query = build_list_query(
             &[],
@@ -631,7 +646,8 @@ impl CliCommand for PersonalComputedList {
             query.include_total.unwrap_or(false),
             [],
         )?;
-        let fields = services
+        let fields = ctx
+            .services()
             .gateway()
             .list_personal_computed_fields(query.class.as_deref(), &list_query)?;
         render_list_page(tokens, &fields)
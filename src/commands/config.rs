@@ -1,22 +1,25 @@
+use std::io::{stdin, BufRead, IsTerminal};
+
 use cli_command_derive::CommandArgs;
+use rpassword::prompt_password;
 use serde::Serialize;
 use serde_json::{json, to_string_pretty, Map, Value};
 
 use hubuum_filter::OutputEnvelope;
 
 use super::builder::{catalog_command, CommandDocs};
-use super::{desired_format, CliCommand};
-use crate::autocomplete::{config_keys, config_values};
-use crate::catalog::CommandCatalogBuilder;
+use super::{desired_format, option_or_pos, required_option_or_pos, CliCommand};
+use crate::autocomplete::{bool, config_keys, config_values};
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::config::{
-    config_key_names, get_config, get_config_state, is_user_preference_key,
-    persist_user_preferences, reload_runtime_config, set_persisted_value, unset_persisted_value,
-    ConfigEntry, ConfigSource, UserPreferences,
+    config_key_names, discard_session_value, get_config, get_config_state, is_user_preference_key,
+    persist_user_preferences, reload_runtime_config, save_session_values, set_persisted_value,
+    set_session_value, unset_persisted_value, ConfigEntry, ConfigSource, UserPreferences,
 };
 use crate::errors::AppError;
 use crate::models::OutputFormat;
 use crate::output::{append_key_value, append_line, set_semantic_output};
-use crate::services::AppServices;
+use crate::secrets::{encrypt_value, ENCRYPTABLE_KEYS};
 use crate::tokenizer::CommandTokenizer;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -35,6 +38,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                         r#"show
 show --key server.hostname"#,
                     ),
+                    ..CommandDocs::default()
                 },
             ),
         )
@@ -60,13 +64,29 @@ show --key server.hostname"#,
                 CommandDocs {
                     about: Some("Persist a configuration value"),
                     long_about: Some(
-                        "Save a configuration value to the active writable config file and reload it into the current CLI session.",
+                        "Save a configuration value to the active writable config file and reload it into the current CLI session. The key and value can also be given as the first two positional arguments. With --session the value only applies to the running session until `config save` writes it.",
                     ),
                     examples: Some(
                         r#"--key server.hostname --value api.example.com
 --key repl.enter_fetches_next_page --value true
---key output.object_class_computed_fields.Hosts --value S:load,P:note"#,
+--key output.object_class_computed_fields.Hosts --value S:load,P:note
+output.padding 20 --session"#,
                     ),
+                    ..CommandDocs::default()
+                },
+            ),
+        )
+        .add_command(
+            &["config"],
+            catalog_command(
+                "save",
+                ConfigSave::default(),
+                CommandDocs {
+                    about: Some("Persist values set for this session"),
+                    long_about: Some(
+                        "Write every value set with `config set --session` to the active writable config file.",
+                    ),
+                    ..CommandDocs::default()
                 },
             ),
         )
@@ -81,6 +101,7 @@ show --key server.hostname"#,
                         "Remove a configuration value from the active writable config file so lower-precedence sources can take effect again, then reload the current CLI session.",
                     ),
                     examples: Some("--key repl.enter_fetches_next_page"),
+                    ..CommandDocs::default()
                 },
             ),
         )
@@ -94,6 +115,7 @@ show --key server.hostname"#,
                     long_about: Some(
                         "Store portable CLI preferences in the authenticated principal's settings under the 'hubuum-cli' namespace. Connection credentials and machine-specific settings are excluded.",
                     ),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -123,6 +145,22 @@ show --key server.hostname"#,
                         "Enable or disable copying portable preferences to the server after local config set and unset operations. Enabling it also exports the current preferences immediately.",
                     ),
                     examples: Some("--enabled true\n--enabled false"),
+                    mutating: true,
+                },
+            ),
+        )
+        .add_command(
+            &["config"],
+            catalog_command(
+                "encrypt",
+                ConfigEncrypt::default(),
+                CommandDocs {
+                    about: Some("Encrypt a secret configuration value"),
+                    long_about: Some(
+                        "Read a secret without echoing it (or from standard input when it is not a terminal) and print it encrypted, ready to paste into a config file. The key comes from server.encryption_key_command, which can read it from the OS keyring, and the value is decrypted with the same command when it is needed.",
+                    ),
+                    examples: Some("server.password"),
+                    ..CommandDocs::default()
                 },
             ),
         );
@@ -134,13 +172,14 @@ pub struct ConfigShow {
         short = "k",
         long = "key",
         help = "Specific config key to show",
-        autocomplete = "config_keys"
+        autocomplete = "config_keys",
+        positional = 0
     )]
     pub key: Option<String>,
 }
 
 impl CliCommand for ConfigShow {
-    fn execute(&self, _services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         render_config_show(tokens)
     }
 }
@@ -149,7 +188,7 @@ pub(crate) fn render_config_show(tokens: &CommandTokenizer) -> Result<(), AppErr
     let query = ConfigShow::parse_tokens(tokens)?;
     let state = get_config_state();
 
-    if let Some(key) = query.key {
+    if let Some(key) = option_or_pos(query.key, tokens, 0, "key")? {
         let entry = state.entry(&key).ok_or_else(|| {
             AppError::ParseError(format!(
                 "Unknown config key: {key}. Use one of: {}",
@@ -171,7 +210,7 @@ pub(crate) fn render_config_show(tokens: &CommandTokenizer) -> Result<(), AppErr
 pub struct ConfigPaths {}
 
 impl CliCommand for ConfigPaths {
-    fn execute(&self, _services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         render_config_paths(tokens)
     }
 }
@@ -193,35 +232,123 @@ pub(crate) fn render_config_paths(tokens: &CommandTokenizer) -> Result<(), AppEr
     Ok(())
 }
 
+#[derive(Debug, Serialize, Clone, CommandArgs, Default)]
+pub struct ConfigEncrypt {
+    #[option(
+        short = "k",
+        long = "key",
+        help = "Config key the encrypted value is for",
+        autocomplete = "config_keys",
+        positional = 0
+    )]
+    pub key: Option<String>,
+}
+
+impl CliCommand for ConfigEncrypt {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        render_config_encrypt(tokens)
+    }
+}
+
+pub(crate) fn render_config_encrypt(tokens: &CommandTokenizer) -> Result<(), AppError> {
+    let query = ConfigEncrypt::parse_tokens(tokens)?;
+    let key = required_option_or_pos(query.key, tokens, 0, "key")?;
+    if !ENCRYPTABLE_KEYS.contains(&key.as_str()) {
+        return Err(AppError::ParseError(format!(
+            "{key} cannot be encrypted. Use one of: {}",
+            ENCRYPTABLE_KEYS.join(", ")
+        )));
+    }
+    let key_command = get_config()
+        .server
+        .encryption_key_command
+        .clone()
+        .ok_or_else(|| {
+            AppError::GeneralConfigError(
+                "Set server.encryption_key_command to a command that prints the encryption key"
+                    .to_string(),
+            )
+        })?;
+
+    let secret = read_secret(&format!("Value for {key}: "))?;
+    if secret.is_empty() {
+        return Err(AppError::ParseError("Nothing to encrypt".to_string()));
+    }
+    let encrypted = encrypt_value(&secret, &key_command, &key)?;
+    match desired_format(tokens) {
+        OutputFormat::Json => append_line(to_string_pretty(
+            &json!({ "key": key, "value": encrypted }),
+        )?)?,
+        OutputFormat::Text => append_line(format!("{key} = \"{encrypted}\""))?,
+    }
+    Ok(())
+}
+
+/// Prompts for a secret without echo, or reads one line when stdin is not a terminal.
+fn read_secret(prompt: &str) -> Result<String, AppError> {
+    if stdin().is_terminal() {
+        return Ok(prompt_password(prompt)?);
+    }
+    let mut line = String::new();
+    stdin().lock().read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
 #[derive(Debug, Serialize, Clone, CommandArgs, Default)]
 pub struct ConfigSet {
     #[option(
         short = "k",
         long = "key",
         help = "Config key to persist",
-        autocomplete = "config_keys"
+        autocomplete = "config_keys",
+        positional = 0
     )]
-    pub key: String,
+    pub key: Option<String>,
     #[option(
         short = "v",
         long = "value",
         help = "Value to persist",
-        autocomplete = "config_values"
+        autocomplete = "config_values",
+        positional = 1
     )]
-    pub value: String,
+    pub value: Option<String>,
+    #[option(
+        long = "session",
+        help = "Only apply the value to this session until `config save`",
+        flag = "true"
+    )]
+    pub session: bool,
 }
 
 impl CliCommand for ConfigSet {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let path = set_persisted_value(&query.key, &query.value)?;
+        let key = required_option_or_pos(query.key, tokens, 0, "key")?;
+        let value = required_option_or_pos(query.value, tokens, 1, "value")?;
+        if query.session {
+            set_session_value(&key, &value)?;
+            reload_runtime_config()?;
+            ctx.services().invalidate_completion();
+            return match desired_format(tokens) {
+                OutputFormat::Json => append_line(to_string_pretty(&json!({
+                    "key": key,
+                    "note": "Applied to this CLI session only.",
+                }))?),
+                OutputFormat::Text => append_line(format!(
+                    "Set '{key}' for this session; run `config save` to keep it."
+                )),
+            };
+        }
+
+        let path = set_persisted_value(&key, &value)?;
+        discard_session_value(&key)?;
         reload_runtime_config()?;
-        services.invalidate_completion();
-        if is_user_preference_key(&query.key) {
-            services.sync_user_preferences_if_enabled()?;
+        ctx.services().invalidate_completion();
+        if is_user_preference_key(&key) {
+            ctx.services().sync_user_preferences_if_enabled()?;
         }
         let message = PersistMessage {
-            key: query.key,
+            key,
             path: path.display().to_string(),
             note: "Saved and reloaded for this CLI session.".to_string(),
         };
@@ -250,13 +377,14 @@ pub struct ConfigUnset {
 }
 
 impl CliCommand for ConfigUnset {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let path = unset_persisted_value(&query.key)?;
+        discard_session_value(&query.key)?;
         reload_runtime_config()?;
-        services.invalidate_completion();
+        ctx.services().invalidate_completion();
         if is_user_preference_key(&query.key) {
-            services.sync_user_preferences_if_enabled()?;
+            ctx.services().sync_user_preferences_if_enabled()?;
         }
         let message = PersistMessage {
             key: query.key,
@@ -276,14 +404,46 @@ impl CliCommand for ConfigUnset {
     }
 }
 
+#[derive(Debug, Serialize, Clone, CommandArgs, Default)]
+pub struct ConfigSave {}
+
+impl CliCommand for ConfigSave {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let _query = Self::parse_tokens(tokens)?;
+        let (path, keys) = save_session_values()?;
+        if !keys.is_empty() {
+            reload_runtime_config()?;
+            if keys.iter().any(|key| is_user_preference_key(key)) {
+                ctx.services().sync_user_preferences_if_enabled()?;
+            }
+        }
+        match desired_format(tokens) {
+            OutputFormat::Json => append_line(to_string_pretty(&json!({
+                "path": path.display().to_string(),
+                "keys": keys,
+            }))?)?,
+            OutputFormat::Text if keys.is_empty() => {
+                append_line("No session values to save.")?;
+            }
+            OutputFormat::Text => {
+                append_line(format!("Saved {} to {}.", keys.join(", "), path.display()))?
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Clone, CommandArgs, Default)]
 pub struct ConfigExport {}
 
 impl CliCommand for ConfigExport {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let _query = Self::parse_tokens(tokens)?;
-        let preferences = UserPreferences::from_config(&get_config());
-        let stored = services.gateway().store_user_preferences(&preferences)?;
+        let preferences = UserPreferences::from_config(&ctx.config());
+        let stored = ctx
+            .services()
+            .gateway()
+            .store_user_preferences(&preferences)?;
         render_preferences_result(tokens, &stored, "Exported preferences to the server.")
     }
 }
@@ -292,12 +452,12 @@ impl CliCommand for ConfigExport {
 pub struct ConfigImport {}
 
 impl CliCommand for ConfigImport {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let _query = Self::parse_tokens(tokens)?;
-        let preferences = services.gateway().load_user_preferences()?;
+        let preferences = ctx.services().gateway().load_user_preferences()?;
         let path = persist_user_preferences(&preferences)?;
         reload_runtime_config()?;
-        services.invalidate_completion();
+        ctx.services().invalidate_completion();
         render_preferences_result(
             tokens,
             &preferences,
@@ -313,19 +473,20 @@ impl CliCommand for ConfigImport {
 pub struct ConfigStore {
     #[option(
         long = "enabled",
-        help = "Whether local config changes are copied to the server"
+        help = "Whether local config changes are copied to the server",
+        autocomplete = "bool"
     )]
     pub enabled: bool,
 }
 
 impl CliCommand for ConfigStore {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let path = set_persisted_value("settings.store_on_server", &query.enabled.to_string())?;
         reload_runtime_config()?;
-        services.invalidate_completion();
+        ctx.services().invalidate_completion();
         if query.enabled {
-            services.sync_user_preferences_if_enabled()?;
+            ctx.services().sync_user_preferences_if_enabled()?;
         }
         let message = format!(
             "Automatic server storage {} in {}.",
@@ -418,6 +579,7 @@ fn format_source_kind(entry: &ConfigEntry) -> &'static str {
         ConfigSource::CustomFile => "custom file",
         ConfigSource::Environment => "env",
         ConfigSource::CliOption => "cli",
+        ConfigSource::Session => "session",
     }
 }
 
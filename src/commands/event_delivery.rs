@@ -7,9 +7,8 @@ use super::{
     CliCommand,
 };
 use crate::autocomplete::event_delivery_ids;
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::errors::AppError;
-use crate::services::AppServices;
 use crate::tokenizer::CommandTokenizer;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -43,7 +42,10 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
             catalog_command(
                 "retry",
                 EventDeliveryRetry::default(),
-                docs("Retry an event delivery"),
+                CommandDocs {
+                    mutating: true,
+                    ..docs("Retry an event delivery")
+                },
             ),
         )
         .add_command(
@@ -82,7 +84,7 @@ pub struct EventDeliveryList {
 }
 
 impl CliCommand for EventDeliveryList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let list_query = build_list_query(
             &query.where_clauses,
@@ -92,7 +94,10 @@ impl CliCommand for EventDeliveryList {
             query.include_total.unwrap_or(false),
             [],
         )?;
-        render_list_page(tokens, &services.gateway().event_deliveries(&list_query)?)
+        render_list_page(
+            tokens,
+            &ctx.services().gateway().event_deliveries(&list_query)?,
+        )
     }
 }
 
@@ -101,18 +106,19 @@ pub struct EventDeliveryShow {
     #[option(
         long = "id",
         help = "Event delivery ID",
-        autocomplete = "event_delivery_ids"
+        autocomplete = "event_delivery_ids",
+        positional = 0
     )]
     pub id: Option<i64>,
 }
 
 impl CliCommand for EventDeliveryShow {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.id = first_positional_or(query.id, tokens, "id")?;
         render_json_record(
             tokens,
-            &services
+            &ctx.services()
                 .gateway()
                 .event_delivery(required_i64(query.id, "id")?)?,
         )
@@ -123,8 +129,8 @@ impl CliCommand for EventDeliveryShow {
 pub struct EventDeliveryHealth {}
 
 impl CliCommand for EventDeliveryHealth {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
-        render_json_record(tokens, &services.gateway().event_delivery_health()?)
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        render_json_record(tokens, &ctx.services().gateway().event_delivery_health()?)
     }
 }
 
@@ -133,18 +139,19 @@ pub struct EventDeliveryRetry {
     #[option(
         long = "id",
         help = "Event delivery ID",
-        autocomplete = "event_delivery_ids"
+        autocomplete = "event_delivery_ids",
+        positional = 0
     )]
     pub id: Option<i64>,
 }
 
 impl CliCommand for EventDeliveryRetry {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.id = first_positional_or(query.id, tokens, "id")?;
         render_json_record(
             tokens,
-            &services
+            &ctx.services()
                 .gateway()
                 .retry_event_delivery(required_i64(query.id, "id")?)?,
         )
@@ -156,18 +163,19 @@ pub struct EventDeliveryDead {
     #[option(
         long = "id",
         help = "Event delivery ID",
-        autocomplete = "event_delivery_ids"
+        autocomplete = "event_delivery_ids",
+        positional = 0
     )]
     pub id: Option<i64>,
 }
 
 impl CliCommand for EventDeliveryDead {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.id = first_positional_or(query.id, tokens, "id")?;
         render_json_record(
             tokens,
-            &services
+            &ctx.services()
                 .gateway()
                 .dead_event_delivery(required_i64(query.id, "id")?)?,
         )
@@ -9,10 +9,9 @@ use super::{
     CliCommand,
 };
 use crate::autocomplete::{event_sink_kinds, event_sinks};
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::errors::AppError;
 use crate::formatting::append_json_message;
-use crate::services::AppServices;
 use crate::tokenizer::CommandTokenizer;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -34,7 +33,10 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
             catalog_command(
                 "create",
                 EventSinkCreate::default(),
-                docs("Create an event sink"),
+                CommandDocs {
+                    mutating: true,
+                    ..docs("Create an event sink")
+                },
             ),
         )
         .add_command(
@@ -42,7 +44,10 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
             catalog_command(
                 "update",
                 EventSinkUpdate::default(),
-                docs("Update an event sink"),
+                CommandDocs {
+                    mutating: true,
+                    ..docs("Update an event sink")
+                },
             ),
         )
         .add_command(
@@ -50,7 +55,10 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
             catalog_command(
                 "delete",
                 EventSinkDelete::default(),
-                docs("Delete an event sink"),
+                CommandDocs {
+                    mutating: true,
+                    ..docs("Delete an event sink")
+                },
             ),
         );
 }
@@ -81,7 +89,7 @@ pub struct EventSinkList {
 }
 
 impl CliCommand for EventSinkList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let list_query = build_list_query(
             &query.where_clauses,
@@ -91,21 +99,27 @@ impl CliCommand for EventSinkList {
             query.include_total.unwrap_or(false),
             [],
         )?;
-        render_list_page(tokens, &services.gateway().event_sinks(&list_query)?)
+        render_list_page(tokens, &ctx.services().gateway().event_sinks(&list_query)?)
     }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct EventSinkShow {
-    #[option(long = "name", help = "Event sink name", autocomplete = "event_sinks")]
+    #[option(
+        long = "name",
+        help = "Event sink name",
+        autocomplete = "event_sinks",
+        positional = 0
+    )]
     pub name: Option<String>,
 }
 
 impl CliCommand for EventSinkShow {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.name = name_or_first_pos(query.name, tokens);
-        let sink = services
+        let sink = ctx
+            .services()
             .gateway()
             .event_sink_by_name(required_str(query.name.as_deref(), "name")?)?;
         render_json_record(tokens, &sink)
@@ -131,9 +145,9 @@ pub struct EventSinkCreate {
 }
 
 impl CliCommand for EventSinkCreate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let sink = services.gateway().create_event_sink(NewEventSink {
+        let sink = ctx.services().gateway().create_event_sink(NewEventSink {
             name: query.name,
             kind: parse_event_sink_kind(&query.kind)?,
             config: parse_json_object(query.config)?,
@@ -146,7 +160,12 @@ impl CliCommand for EventSinkCreate {
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct EventSinkUpdate {
-    #[option(long = "sink", help = "Event sink name", autocomplete = "event_sinks")]
+    #[option(
+        long = "sink",
+        help = "Event sink name",
+        autocomplete = "event_sinks",
+        positional = 0
+    )]
     pub current_name: Option<String>,
     #[option(long = "name", help = "New name")]
     pub name: Option<String>,
@@ -171,7 +190,7 @@ pub struct EventSinkUpdate {
 }
 
 impl CliCommand for EventSinkUpdate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.current_name = name_or_first_pos(query.current_name, tokens);
         if query.clear_secret_ref.unwrap_or(false) {
@@ -180,7 +199,7 @@ impl CliCommand for EventSinkUpdate {
                     .to_string(),
             ));
         }
-        let sink = services.gateway().update_event_sink(
+        let sink = ctx.services().gateway().update_event_sink(
             required_str(query.current_name.as_deref(), "name")?,
             UpdateEventSink {
                 name: query.name,
@@ -200,15 +219,20 @@ impl CliCommand for EventSinkUpdate {
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct EventSinkDelete {
-    #[option(long = "name", help = "Event sink name", autocomplete = "event_sinks")]
+    #[option(
+        long = "name",
+        help = "Event sink name",
+        autocomplete = "event_sinks",
+        positional = 0
+    )]
     pub name: Option<String>,
 }
 
 impl CliCommand for EventSinkDelete {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.name = name_or_first_pos(query.name, tokens);
-        services
+        ctx.services()
             .gateway()
             .delete_event_sink_by_name(required_str(query.name.as_deref(), "name")?)?;
         append_json_message("event sink deleted")
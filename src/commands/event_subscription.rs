@@ -12,7 +12,7 @@ use super::{
 use crate::autocomplete::{
     collections, event_actions, event_entity_types, event_sinks, event_subscriptions,
 };
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::errors::AppError;
 use crate::formatting::append_json_message;
 use crate::services::AppServices;
@@ -41,7 +41,10 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
             catalog_command(
                 "create",
                 EventSubscriptionCreate::default(),
-                docs("Create an event subscription"),
+                CommandDocs {
+                    mutating: true,
+                    ..docs("Create an event subscription")
+                },
             ),
         )
         .add_command(
@@ -49,7 +52,10 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
             catalog_command(
                 "update",
                 EventSubscriptionUpdate::default(),
-                docs("Update an event subscription"),
+                CommandDocs {
+                    mutating: true,
+                    ..docs("Update an event subscription")
+                },
             ),
         )
         .add_command(
@@ -57,7 +63,10 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
             catalog_command(
                 "delete",
                 EventSubscriptionDelete::default(),
-                docs("Delete an event subscription"),
+                CommandDocs {
+                    mutating: true,
+                    ..docs("Delete an event subscription")
+                },
             ),
         );
 }
@@ -94,9 +103,9 @@ pub struct EventSubscriptionList {
 }
 
 impl CliCommand for EventSubscriptionList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let collection_id = resolve_collection_id(services, query.collection)?;
+        let collection_id = resolve_collection_id(ctx.services(), query.collection)?;
         let list_query = build_list_query(
             &query.where_clauses,
             &query.sort_clauses,
@@ -107,7 +116,7 @@ impl CliCommand for EventSubscriptionList {
         )?;
         render_list_page(
             tokens,
-            &services
+            &ctx.services()
                 .gateway()
                 .event_subscriptions(collection_id, &list_query)?,
         )
@@ -125,17 +134,18 @@ pub struct EventSubscriptionShow {
     #[option(
         long = "name",
         help = "Subscription name",
-        autocomplete = "event_subscriptions"
+        autocomplete = "event_subscriptions",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for EventSubscriptionShow {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.name = name_or_first_pos(query.name, tokens);
-        let collection_id = resolve_collection_id(services, query.collection)?;
-        let record = services.gateway().event_subscription_by_name(
+        let collection_id = resolve_collection_id(ctx.services(), query.collection)?;
+        let record = ctx.services().gateway().event_subscription_by_name(
             collection_id,
             required_str(query.name.as_deref(), "name")?,
         )?;
@@ -178,11 +188,11 @@ pub struct EventSubscriptionCreate {
 }
 
 impl CliCommand for EventSubscriptionCreate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let collection_id = resolve_collection_id(services, query.collection)?;
-        let sink_id = resolve_sink_id(services, query.sink)?;
-        let record = services.gateway().create_event_subscription(
+        let collection_id = resolve_collection_id(ctx.services(), query.collection)?;
+        let sink_id = resolve_sink_id(ctx.services(), query.sink)?;
+        let record = ctx.services().gateway().create_event_subscription(
             collection_id,
             NewEventSubscription {
                 sink_id: sink_id.into(),
@@ -210,7 +220,8 @@ pub struct EventSubscriptionUpdate {
     #[option(
         long = "subscription",
         help = "Subscription name",
-        autocomplete = "event_subscriptions"
+        autocomplete = "event_subscriptions",
+        positional = 0
     )]
     pub subscription: Option<String>,
     #[option(long = "sink", help = "Sink name", autocomplete = "event_sinks")]
@@ -244,15 +255,15 @@ pub struct EventSubscriptionUpdate {
 }
 
 impl CliCommand for EventSubscriptionUpdate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.subscription = name_or_first_pos(query.subscription, tokens);
-        let collection_id = resolve_collection_id(services, query.collection)?;
-        let record = services.gateway().update_event_subscription(
+        let collection_id = resolve_collection_id(ctx.services(), query.collection)?;
+        let record = ctx.services().gateway().update_event_subscription(
             collection_id,
             required_str(query.subscription.as_deref(), "subscription")?,
             UpdateEventSubscription {
-                sink_id: resolve_optional_sink_id(services, query.sink)?.map(Into::into),
+                sink_id: resolve_optional_sink_id(ctx.services(), query.sink)?.map(Into::into),
                 name: query.name,
                 description: query.description,
                 entity_types: query.entity_types.map(|value| split_csv(&value)),
@@ -274,16 +285,16 @@ pub struct EventSubscriptionDelete {
         autocomplete = "collections"
     )]
     pub collection: Option<String>,
-    #[option(long = "name", help = "Subscription name")]
+    #[option(long = "name", help = "Subscription name", positional = 0)]
     pub name: Option<String>,
 }
 
 impl CliCommand for EventSubscriptionDelete {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.name = name_or_first_pos(query.name, tokens);
-        let collection_id = resolve_collection_id(services, query.collection)?;
-        services.gateway().delete_event_subscription_by_name(
+        let collection_id = resolve_collection_id(ctx.services(), query.collection)?;
+        ctx.services().gateway().delete_event_subscription_by_name(
             collection_id,
             required_str(query.name.as_deref(), "name")?,
         )?;
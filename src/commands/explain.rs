@@ -0,0 +1,300 @@
+use hubuum_client::QueryFilter;
+use serde_json::json;
+
+use super::{desired_format, render_format, CliOption};
+use crate::domain::ExplainedCall;
+use crate::errors::AppError;
+use crate::formatting::OutputFormatter;
+use crate::list_query::{
+    parse_sort_clause, parse_where_clause, resolve_sort_field_spec, validate_filter_clauses,
+    validated_clause_to_query_filter, FilterValueResolver, ServerPageSize,
+};
+use crate::models::OutputFormat;
+use crate::output::{add_warning, append_json, append_line, has_pipeline, RenderFormat};
+use crate::services::{
+    api_calls_for_command_path, filter_specs_for_command_path, sort_specs_for_command_path, ApiCall,
+};
+use crate::tokenizer::CommandTokenizer;
+
+const LOOKUP_NOTE: &str = "Classes and collections looked up recently are taken from the cache, and a name that does not match exactly may need more lookups under lookup.name_match.";
+
+/// Lists the API requests the command at `command_path` would send for `tokens`,
+/// without sending any of them.
+pub fn render_explain(
+    command_path: &[String],
+    options: &[CliOption],
+    tokens: &CommandTokenizer,
+) -> Result<(), AppError> {
+    let command = command_path.join(" ");
+    let Some(plan) = api_calls_for_command_path(command_path) else {
+        if let OutputFormat::Json = desired_format(tokens) {
+            append_json(json!({ "command": command, "calls": null }))?;
+        }
+        return add_warning(format!("No API plan is recorded for '{command}'"));
+    };
+    let given = GivenOptions { options, tokens };
+    let calls = given.explain(plan, command_path)?;
+
+    match desired_format(tokens) {
+        OutputFormat::Json => append_json(json!({ "command": command, "calls": calls })),
+        OutputFormat::Text => {
+            if render_format(tokens)? != RenderFormat::Text || has_pipeline()? {
+                return calls.format_noreturn();
+            }
+            if calls.is_empty() {
+                return append_line(format!("'{command}' makes no API requests."));
+            }
+            append_line(format!("'{command}' sends these API requests:"))?;
+            calls.format_noreturn()?;
+            append_line(LOOKUP_NOTE)
+        }
+    }
+}
+
+/// The options of one invocation, for filling in the placeholders of a plan.
+struct GivenOptions<'a> {
+    options: &'a [CliOption],
+    tokens: &'a CommandTokenizer,
+}
+
+impl GivenOptions<'_> {
+    fn explain(
+        &self,
+        plan: &[ApiCall],
+        command_path: &[String],
+    ) -> Result<Vec<ExplainedCall>, AppError> {
+        let mut calls = Vec::new();
+        for call in plan {
+            if call
+                .condition()
+                .is_some_and(|option| self.value(option).is_none())
+            {
+                continue;
+            }
+            let mut query = call
+                .filters()
+                .iter()
+                .map(|filter| self.fill(filter))
+                .collect::<Vec<_>>();
+            if let Some(clauses) = call.listing_clauses() {
+                let (lookups, listing) = self.listing_query(clauses, command_path)?;
+                calls.extend(lookups);
+                query.extend(listing);
+            }
+            calls.push(ExplainedCall {
+                step: 0,
+                method: call.method().to_string(),
+                path: self.fill(call.path()),
+                query,
+                purpose: self.fill(call.purpose()),
+            });
+        }
+        for (index, call) in calls.iter_mut().enumerate() {
+            call.step = index + 1;
+        }
+        Ok(calls)
+    }
+
+    /// The query parameters of a page request, and the lookups its `--where` values need.
+    fn listing_query(
+        &self,
+        clauses: &[&str],
+        command_path: &[String],
+    ) -> Result<(Vec<ExplainedCall>, Vec<String>), AppError> {
+        let filters = clauses
+            .iter()
+            .filter_map(|clause| self.fill_given(clause))
+            .chain(self.values("where_clauses"))
+            .map(|clause| parse_where_clause(&clause))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut lookups = Vec::new();
+        let mut query = Vec::new();
+        match filter_specs_for_command_path(command_path) {
+            Some(specs) => {
+                for mut clause in validate_filter_clauses(&filters, specs)? {
+                    if clause.spec.resolver == FilterValueResolver::CollectionNameToId {
+                        lookups.push(ExplainedCall {
+                            step: 0,
+                            method: "GET".to_string(),
+                            path: "/api/v1/collections".to_string(),
+                            query: vec![format!("name={}", clause.value)],
+                            purpose: format!("Look up collection '{}'", clause.value),
+                        });
+                        clause.value = "{collection_id}".to_string();
+                    }
+                    query.push(validated_clause_to_query_filter(&clause).to_string());
+                }
+            }
+            None => query.extend(filters.into_iter().map(|filter| {
+                QueryFilter::filter(filter.field, filter.operator, filter.value).to_string()
+            })),
+        }
+
+        let sort_specs = sort_specs_for_command_path(command_path).unwrap_or_default();
+        let sorts = self
+            .values("sort_clauses")
+            .iter()
+            .map(|clause| {
+                let sort = parse_sort_clause(clause)?;
+                let field = resolve_sort_field_spec(sort_specs, &sort.field)
+                    .map_or(sort.field, |spec| spec.backend_field.to_string());
+                Ok(format!("{field}.{}", sort.direction.to_api()))
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+        if !sorts.is_empty() {
+            query.push(format!("sort={}", sorts.join(",")));
+        }
+        if let Some(limit) = self.value("limit") {
+            let limit = limit
+                .parse::<usize>()
+                .map_err(|_| AppError::ParseError(format!("Invalid limit '{limit}'")))?;
+            query.push(format!(
+                "limit={}",
+                ServerPageSize::from_requested(limit).effective()
+            ));
+        }
+        if let Some(cursor) = self.value("cursor") {
+            query.push(format!("cursor={cursor}"));
+        }
+        if self.value("include_total").is_some() {
+            query.push("include_total=true".to_string());
+        }
+        Ok((lookups, query))
+    }
+
+    fn option(&self, name: &str) -> Option<&CliOption> {
+        self.options.iter().find(|option| option.name == name)
+    }
+
+    /// Every value given to option `name`, in command line order.
+    fn values(&self, name: &str) -> Vec<String> {
+        let Some(option) = self.option(name) else {
+            return Vec::new();
+        };
+        let keys = [option.long_without_dashes(), option.short_without_dash()];
+        self.tokens
+            .get_option_occurrences()
+            .iter()
+            .filter(|occurrence| keys.iter().flatten().any(|key| *key == occurrence.key))
+            .map(|occurrence| occurrence.value.clone())
+            .collect()
+    }
+
+    /// The value of option `name`, given by flag or in its positional slot.
+    fn value(&self, name: &str) -> Option<String> {
+        self.values(name).pop().or_else(|| {
+            let index = self.option(name)?.positional?;
+            self.tokens.get_positionals().get(index).cloned()
+        })
+    }
+
+    /// Fills in `template`, showing options that were not given as `<option>`.
+    fn fill(&self, template: &str) -> String {
+        self.substitute(template, false)
+            .unwrap_or_else(|| template.to_string())
+    }
+
+    /// Fills in `template`, or `None` when one of its options was not given.
+    fn fill_given(&self, template: &str) -> Option<String> {
+        self.substitute(template, true)
+    }
+
+    fn substitute(&self, template: &str, require_all: bool) -> Option<String> {
+        let mut filled = String::new();
+        let mut rest = template;
+        while let Some((before, after)) = rest.split_once('{') {
+            let Some((name, remainder)) = after.split_once('}') else {
+                break;
+            };
+            filled.push_str(before);
+            match (self.option(name), self.value(name)) {
+                (Some(_), Some(value)) => filled.push_str(&value),
+                (Some(_), None) if require_all => return None,
+                (Some(_), None) => filled.push_str(&format!("<{name}>")),
+                (None, _) => filled.push_str(&format!("{{{name}}}")),
+            }
+            rest = remainder;
+        }
+        filled.push_str(rest);
+        Some(filled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GivenOptions;
+    use crate::commands::class::ClassList;
+    use crate::commands::command_options;
+    use crate::commands::object::ObjectInfo;
+    use crate::services::api_calls_for_command_path;
+    use crate::tokenizer::CommandTokenizer;
+
+    fn path(command: &str) -> Vec<String> {
+        command.split(' ').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn listing_requests_carry_the_filters_sorts_and_limit() {
+        let options = command_options::<ClassList>();
+        let tokens = CommandTokenizer::new_without_value_source_resolution(
+            "class list --name web --where collection = prod --sort name desc --limit 10 --explain",
+            "list",
+            &options,
+        )
+        .expect("class list should tokenize");
+        let given = GivenOptions {
+            options: &options,
+            tokens: &tokens,
+        };
+        let command_path = path("class list");
+        let plan = api_calls_for_command_path(&command_path).expect("class list has a plan");
+
+        let calls = given
+            .explain(plan, &command_path)
+            .expect("plan should explain");
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].query, vec!["name=prod".to_string()]);
+        assert_eq!(calls[1].step, 2);
+        assert_eq!(calls[1].path, "/api/v1/classes");
+        assert_eq!(
+            calls[1].query,
+            vec![
+                "name__icontains=web".to_string(),
+                "collection__equals={collection_id}".to_string(),
+                "sort=name.desc".to_string(),
+                "limit=10".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn placeholders_take_positionals_and_conditional_requests_are_skipped() {
+        let options = command_options::<ObjectInfo>();
+        let tokens = CommandTokenizer::new_without_value_source_resolution(
+            "object show web1 --class Hosts --explain",
+            "show",
+            &options,
+        )
+        .expect("object show should tokenize");
+        let given = GivenOptions {
+            options: &options,
+            tokens: &tokens,
+        };
+        let command_path = path("object show");
+        let plan = api_calls_for_command_path(&command_path).expect("object show has a plan");
+
+        let calls = given
+            .explain(plan, &command_path)
+            .expect("plan should explain");
+
+        assert_eq!(calls[0].path, "/api/v1/classes/by-name/Hosts");
+        assert_eq!(calls[1].query, vec!["name=web1".to_string()]);
+        assert_eq!(calls[1].purpose, "Look up object 'web1'");
+        assert!(calls
+            .iter()
+            .all(|call| call.purpose != "Fetch the computed fields"));
+        assert_eq!(calls[3].query, vec!["depth__lte=<max_depth>".to_string()]);
+    }
+}
@@ -1,8 +1,11 @@
-use std::fs::read_to_string;
+use std::fs::{create_dir_all, read_to_string, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
+use chrono::{SecondsFormat, Utc};
 use cli_command_derive::CommandArgs;
 use serde::{Deserialize, Serialize};
-use serde_json::to_string_pretty;
+use serde_json::{json, to_string_pretty, Map, Value};
 
 use super::builder::{catalog_command, CommandDocs};
 use super::task_submit::{parse_task_submit_options, run_task_backed};
@@ -11,16 +14,19 @@ use super::{
 };
 use crate::autocomplete::{
     classes, collections, export_content_types, export_missing_data_policies, export_scope_kinds,
-    export_sort, export_templates, export_where, objects_from_class,
+    export_sort, export_templates, export_where, file_paths, objects_from_class,
 };
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
+use crate::config::get_config;
+use crate::domain::ClassSchemaRecord;
 use crate::errors::AppError;
-use crate::formatting::{append_json_message, OutputFormatter};
+use crate::formatting::{
+    append_json, append_json_message, render_collection_report_html, OutputFormatter,
+};
 use crate::models::OutputFormat;
 use crate::output::append_line;
-use crate::services::{
-    AppServices, CreateExportTemplateInput, RunExportInput, UpdateExportTemplateInput,
-};
+use crate::redirection::sanitize_path_value;
+use crate::services::{CreateExportTemplateInput, RunExportInput, UpdateExportTemplateInput};
 use crate::tokenizer::CommandTokenizer;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -54,6 +60,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ExportCreate::default(),
                 CommandDocs {
                     about: Some("Create an export template"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -65,6 +72,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ExportModify::default(),
                 CommandDocs {
                     about: Some("Modify an export template"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -76,6 +84,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ExportDelete::default(),
                 CommandDocs {
                     about: Some("Delete an export template"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -90,6 +99,40 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                     long_about: Some(
                         "Run an export for a given scope, optionally using a named export template.",
                     ),
+                    mutating: true,
+                    ..CommandDocs::default()
+                },
+            ),
+        )
+        .add_command(
+            &["export"],
+            catalog_command(
+                "html",
+                ExportHtml::default(),
+                CommandDocs {
+                    about: Some("Write an HTML report of a collection"),
+                    long_about: Some(
+                        "Render the classes, object counts, class relations, and group permissions of a collection into one standalone HTML page for sharing with people without CLI access. The page carries its own styling and no scripts. Without --file the HTML is printed, and an existing file is only replaced with --force.",
+                    ),
+                    examples: Some("prod --file report.html
+--collection prod --file report.html --force"),
+                    ..CommandDocs::default()
+                },
+            ),
+        )
+        .add_command(
+            &["export", "json-schema"],
+            catalog_command(
+                "bundle",
+                ExportSchemaBundle::default(),
+                CommandDocs {
+                    about: Some("Bundle the JSON schemas of all classes"),
+                    long_about: Some(
+                        "Collect the JSON schema of every class, or of the classes in one collection, for documentation generators and validators elsewhere. By default one JSON Schema document is printed with each class schema under $defs and the class collections and descriptions under x-hubuum-classes. --file writes that document to a file, and --dir writes one <collection>/<class>.schema.json file per class instead. Classes without a schema are left out, and existing files are only replaced with --force.",
+                    ),
+                    examples: Some(
+                        "--file schemas.json\n--collection infra --dir schemas/ --force",
+                    ),
                     ..CommandDocs::default()
                 },
             ),
@@ -125,7 +168,7 @@ pub struct ExportList {
 }
 
 impl CliCommand for ExportList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let list_query = build_list_query(
             &query.where_clauses,
@@ -135,7 +178,10 @@ impl CliCommand for ExportList {
             query.include_total.unwrap_or(false),
             [],
         )?;
-        let exports = services.gateway().list_export_templates(&list_query)?;
+        let exports = ctx
+            .services()
+            .gateway()
+            .list_export_templates(&list_query)?;
         render_list_page(tokens, &exports)
     }
 }
@@ -146,16 +192,17 @@ pub struct ExportShow {
         short = "n",
         long = "name",
         help = "Name of the export template",
-        autocomplete = "export_templates"
+        autocomplete = "export_templates",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for ExportShow {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "name")?;
-        let export = services.gateway().export_template(&name)?;
+        let export = ctx.services().gateway().export_template(&name)?;
 
         match desired_format(tokens) {
             OutputFormat::Json => append_line(to_string_pretty(&export)?)?,
@@ -202,18 +249,19 @@ pub struct ExportCreate {
 }
 
 impl CliCommand for ExportCreate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let template = read_template_source(query.template, query.file)?;
-        let export = services
-            .gateway()
-            .create_export_template(CreateExportTemplateInput {
-                name: query.name,
-                collection: query.collection,
-                description: query.description,
-                content_type: query.content_type,
-                template,
-            })?;
+        let export =
+            ctx.services()
+                .gateway()
+                .create_export_template(CreateExportTemplateInput {
+                    name: query.name,
+                    collection: query.collection,
+                    description: query.description,
+                    content_type: query.content_type,
+                    template,
+                })?;
 
         match desired_format(tokens) {
             OutputFormat::Json => append_line(to_string_pretty(&export)?)?,
@@ -230,7 +278,8 @@ pub struct ExportModify {
         short = "n",
         long = "name",
         help = "Name of the export template",
-        autocomplete = "export_templates"
+        autocomplete = "export_templates",
+        positional = 0
     )]
     pub name: Option<String>,
     #[option(short = "r", long = "rename", help = "Rename the export template")]
@@ -260,19 +309,20 @@ pub struct ExportModify {
 }
 
 impl CliCommand for ExportModify {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "name")?;
         let template = read_optional_template_source(query.template, query.file)?;
-        let export = services
-            .gateway()
-            .update_export_template(UpdateExportTemplateInput {
-                name,
-                rename: query.rename,
-                collection: query.collection,
-                description: query.description,
-                template,
-            })?;
+        let export =
+            ctx.services()
+                .gateway()
+                .update_export_template(UpdateExportTemplateInput {
+                    name,
+                    rename: query.rename,
+                    collection: query.collection,
+                    description: query.description,
+                    template,
+                })?;
 
         match desired_format(tokens) {
             OutputFormat::Json => append_line(to_string_pretty(&export)?)?,
@@ -289,16 +339,17 @@ pub struct ExportDelete {
         short = "n",
         long = "name",
         help = "Name of the export template",
-        autocomplete = "export_templates"
+        autocomplete = "export_templates",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for ExportDelete {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "name")?;
-        services.gateway().delete_export_template(&name)?;
+        ctx.services().gateway().delete_export_template(&name)?;
 
         let message = format!("Export template '{name}' deleted");
         match desired_format(tokens) {
@@ -373,7 +424,7 @@ pub struct ExportRun {
 }
 
 impl CliCommand for ExportRun {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let opts = parse_task_submit_options(tokens)?;
         let input = RunExportInput {
@@ -388,9 +439,9 @@ impl CliCommand for ExportRun {
             relation_depth: query.relation_depth,
             include_related: query.include_related,
         };
-        let task = services.gateway().submit_export(input)?;
+        let task = ctx.services().gateway().submit_export(input)?;
         run_task_backed(
-            services,
+            ctx.services(),
             tokens,
             format!("export {}", task.0.id),
             opts,
@@ -399,6 +450,193 @@ impl CliCommand for ExportRun {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct ExportSchemaBundle {
+    #[option(
+        short = "N",
+        long = "collection",
+        help = "Only bundle classes in this collection",
+        autocomplete = "collections"
+    )]
+    pub collection: Option<String>,
+    #[option(
+        short = "f",
+        long = "file",
+        help = "Write the bundle to this file",
+        autocomplete = "file_paths"
+    )]
+    pub file: Option<String>,
+    #[option(
+        long = "dir",
+        help = "Write one schema file per class into this directory",
+        autocomplete = "file_paths"
+    )]
+    pub dir: Option<String>,
+    #[option(long = "force", help = "Replace existing files", flag = "true")]
+    pub force: bool,
+}
+
+impl CliCommand for ExportSchemaBundle {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        if query.file.is_some() && query.dir.is_some() {
+            return Err(AppError::ParseError(
+                "Use either --file or --dir, not both".to_string(),
+            ));
+        }
+        let schemas = ctx
+            .services()
+            .gateway()
+            .class_schemas(query.collection.as_deref())?;
+
+        let (destination, files) = match (&query.file, &query.dir) {
+            (Some(file), None) => (
+                file.clone(),
+                vec![(PathBuf::from(file), schema_bundle(&schemas))],
+            ),
+            (None, Some(dir)) => (
+                dir.clone(),
+                schema_bundle_files(&schemas)
+                    .into_iter()
+                    .map(|(path, schema)| (Path::new(dir).join(path), schema))
+                    .collect(),
+            ),
+            _ => {
+                append_line(to_string_pretty(&schema_bundle(&schemas))?)?;
+                return Ok(());
+            }
+        };
+        if !query.force {
+            if let Some((path, _)) = files.iter().find(|(path, _)| path.exists()) {
+                return Err(AppError::InvalidOption(format!(
+                    "Destination '{}' already exists; use --force to replace it",
+                    path.display()
+                )));
+            }
+        }
+        for (path, schema) in &files {
+            if let Some(parent) = path.parent() {
+                create_dir_all(parent)?;
+            }
+            let mut file = File::create(path)?;
+            file.write_all(to_string_pretty(schema)?.as_bytes())?;
+            file.write_all(b"\n")?;
+        }
+
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json(&json!({
+                "destination": destination,
+                "classes": schemas.iter().map(|schema| &schema.class).collect::<Vec<_>>(),
+            }))?,
+            OutputFormat::Text => append_line(format!(
+                "Exported {} class schema(s) to {destination}",
+                schemas.len()
+            ))?,
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct ExportHtml {
+    #[option(
+        short = "N",
+        long = "collection",
+        help = "Collection to report on",
+        autocomplete = "collections",
+        positional = 0
+    )]
+    pub collection: Option<String>,
+    #[option(
+        short = "f",
+        long = "file",
+        help = "Write the report to this file",
+        autocomplete = "file_paths"
+    )]
+    pub file: Option<String>,
+    #[option(long = "force", help = "Replace an existing file", flag = "true")]
+    pub force: bool,
+}
+
+impl CliCommand for ExportHtml {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let collection = required_option_or_pos(query.collection, tokens, 0, "collection")?;
+        let report = ctx.services().gateway().collection_report(&collection)?;
+        let html = render_collection_report_html(
+            &report,
+            &get_config().server.server_url(),
+            &Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        );
+
+        let Some(destination) = query.file else {
+            append_line(html.trim_end())?;
+            return Ok(());
+        };
+        let path = Path::new(&destination);
+        if !query.force && path.exists() {
+            return Err(AppError::InvalidOption(format!(
+                "Destination '{destination}' already exists; use --force to replace it"
+            )));
+        }
+        File::create(path)?.write_all(html.as_bytes())?;
+
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json(&json!({
+                "destination": destination,
+                "collection": report.collection.0.name,
+                "classes": report.classes.len(),
+                "objects": report.objects(),
+            }))?,
+            OutputFormat::Text => append_line(format!(
+                "Wrote report for collection '{}' to {destination}",
+                report.collection.0.name
+            ))?,
+        }
+        Ok(())
+    }
+}
+
+/// One JSON Schema document holding every class schema under `$defs`.
+fn schema_bundle(schemas: &[ClassSchemaRecord]) -> Value {
+    let defs = schemas
+        .iter()
+        .map(|schema| (schema.class.clone(), schema.json_schema.clone()))
+        .collect::<Map<_, _>>();
+    let classes = schemas
+        .iter()
+        .map(|schema| {
+            json!({
+                "class": schema.class,
+                "collection": schema.collection,
+                "description": schema.description,
+                "ref": format!("#/$defs/{}", schema.class.replace('~', "~0").replace('/', "~1")),
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$defs": defs,
+        "x-hubuum-classes": classes,
+    })
+}
+
+/// Relative `<collection>/<class>.schema.json` paths with the schema each one holds.
+fn schema_bundle_files(schemas: &[ClassSchemaRecord]) -> Vec<(PathBuf, Value)> {
+    schemas
+        .iter()
+        .map(|schema| {
+            (
+                Path::new(&sanitize_path_value(&schema.collection)).join(format!(
+                    "{}.schema.json",
+                    sanitize_path_value(&schema.class)
+                )),
+                schema.json_schema.clone(),
+            )
+        })
+        .collect()
+}
+
 fn read_template_source(
     template: Option<String>,
     file: Option<String>,
@@ -426,3 +664,57 @@ fn read_optional_template_source(
         (None, None) => Ok(None),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(class: &str, collection: &str) -> ClassSchemaRecord {
+        ClassSchemaRecord {
+            class: class.to_string(),
+            collection: collection.to_string(),
+            description: format!("{class} objects"),
+            json_schema: json!({"type": "object", "title": class}),
+        }
+    }
+
+    #[test]
+    fn bundles_hold_every_class_schema_under_defs() {
+        let bundle = schema_bundle(&[schema("Hosts", "infra"), schema("a/b", "misc")]);
+
+        assert_eq!(bundle["$defs"]["Hosts"]["title"], "Hosts");
+        assert_eq!(bundle["$defs"]["a/b"]["title"], "a/b");
+        assert_eq!(
+            bundle["x-hubuum-classes"][0],
+            json!({
+                "class": "Hosts",
+                "collection": "infra",
+                "description": "Hosts objects",
+                "ref": "#/$defs/Hosts",
+            })
+        );
+        assert_eq!(bundle["x-hubuum-classes"][1]["ref"], "#/$defs/a~1b");
+    }
+
+    #[test]
+    fn bundle_files_are_grouped_by_collection_with_safe_names() {
+        let files = schema_bundle_files(&[schema("Hosts", "infra"), schema("a/b", "..")]);
+
+        assert_eq!(files[0].0, Path::new("infra").join("Hosts.schema.json"));
+        assert_eq!(files[1].0, Path::new("_").join("a_b.schema.json"));
+        assert_eq!(files[0].1["title"], "Hosts");
+    }
+
+    #[test]
+    fn html_takes_the_collection_as_a_positional() {
+        let tokens =
+            CommandTokenizer::new("export html prod --file x", "html", &[]).expect("tokenization");
+
+        let query = ExportHtml::parse_tokens(&tokens).expect("positional should be accepted");
+        let collection =
+            required_option_or_pos(query.collection, &tokens, 0, "collection").unwrap();
+
+        assert_eq!(collection, "prod");
+        assert_eq!(query.file.as_deref(), Some("x"));
+    }
+}
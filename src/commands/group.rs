@@ -1,21 +1,23 @@
 use cli_command_derive::CommandArgs;
 use serde::{Deserialize, Serialize};
 use serde_json::to_string_pretty;
+use strum::IntoEnumIterator;
 
 use super::builder::{catalog_command, CommandDocs};
 use super::{
     build_list_query, contains_clause, desired_format, render_list_page, required_option_or_pos,
     CliCommand,
 };
-use crate::autocomplete::{group_sort, group_where, groups, users};
-use crate::catalog::CommandCatalogBuilder;
+use crate::autocomplete::{collection_list, group_sort, group_where, groups, permissions, users};
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 
-use crate::domain::GroupDetails;
+use crate::domain::{CollectionPermission, GroupDetails};
 use crate::errors::AppError;
 use crate::formatting::{append_json_message, OutputFormatter};
 use crate::models::OutputFormat;
 use crate::output::append_line;
-use crate::services::{AppServices, CreateGroupInput, GroupUpdateInput};
+use crate::safety::confirm_protected_collections;
+use crate::services::{CreateGroupInput, GroupUpdateInput};
 use crate::tokenizer::CommandTokenizer;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -27,6 +29,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 GroupNew::default(),
                 CommandDocs {
                     about: Some("Create a group"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -49,6 +52,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 GroupAddUser::default(),
                 CommandDocs {
                     about: Some("Add a user to a group"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -60,6 +64,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 GroupRemoveUser::default(),
                 CommandDocs {
                     about: Some("Remove a user from a group"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -75,6 +80,24 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 },
             ),
         )
+        .add_command(
+            &["group"],
+            catalog_command(
+                "grant",
+                GroupGrant::default(),
+                CommandDocs {
+                    about: Some("Grant a group permissions on collections"),
+                    long_about: Some(
+                        "Grant the same collection permissions to one group on one or more collections. Pass the group as the first positional argument or with --groupname, the collections as a comma-separated list, and either --permissions or --all. Every collection is looked up before anything is granted.",
+                    ),
+                    examples: Some(
+                        r#"grant ops --collections prod,staging --permissions ReadCollection,ReadClass,ReadObject
+grant --groupname admins --collections prod --all"#,
+                    ),
+                    mutating: true,
+                },
+            ),
+        )
         .add_command(
             &["group"],
             catalog_command(
@@ -87,6 +110,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                         r#"modify my-group --rename other-group
 modify --groupname my-group --description "Updated description""#,
                     ),
+                    mutating: true,
                 },
             ),
         );
@@ -101,9 +125,9 @@ pub struct GroupNew {
 }
 
 impl CliCommand for GroupNew {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let new = Self::parse_tokens(tokens)?;
-        let group = services.gateway().create_group(CreateGroupInput {
+        let group = ctx.services().gateway().create_group(CreateGroupInput {
             groupname: new.groupname,
             description: new.description,
         })?;
@@ -135,9 +159,9 @@ pub struct GroupAddUser {
     pub username: String,
 }
 impl CliCommand for GroupAddUser {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let new = Self::parse_tokens(tokens)?;
-        services
+        ctx.services()
             .gateway()
             .add_user_to_group(&new.groupname, &new.username)?;
 
@@ -170,9 +194,19 @@ pub struct GroupRemoveUser {
     pub username: String,
 }
 impl CliCommand for GroupRemoveUser {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let new = Self::parse_tokens(tokens)?;
-        services
+        let safety = &ctx.config().safety;
+        if safety.has_protected_collections() {
+            confirm_protected_collections(
+                safety,
+                &format!("group remove_user {} {}", new.groupname, new.username),
+                ctx.services()
+                    .gateway()
+                    .collections_granting_group(&new.groupname, &safety.protected_collections)?,
+            )?;
+        }
+        ctx.services()
             .gateway()
             .remove_user_from_group(&new.groupname, &new.username)?;
 
@@ -201,9 +235,9 @@ pub struct GroupInfo {
     pub groupname: String,
 }
 impl CliCommand for GroupInfo {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let new = Self::parse_tokens(tokens)?;
-        let details: GroupDetails = services.gateway().group_details(&new.groupname)?;
+        let details: GroupDetails = ctx.services().gateway().group_details(&new.groupname)?;
 
         match desired_format(tokens) {
             OutputFormat::Json => append_line(to_string_pretty(&details)?)?,
@@ -223,7 +257,8 @@ pub struct GroupModify {
         short = "g",
         long = "groupname",
         help = "Name of the group",
-        autocomplete = "groups"
+        autocomplete = "groups",
+        positional = 0
     )]
     pub groupname: Option<String>,
     #[option(short = "r", long = "rename", help = "Rename the group")]
@@ -233,11 +268,11 @@ pub struct GroupModify {
 }
 
 impl CliCommand for GroupModify {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let groupname = required_option_or_pos(query.groupname, tokens, 0, "groupname")?;
 
-        let group = services.gateway().update_group(GroupUpdateInput {
+        let group = ctx.services().gateway().update_group(GroupUpdateInput {
             groupname,
             rename: query.rename,
             description: query.description,
@@ -252,12 +287,117 @@ impl CliCommand for GroupModify {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct GroupGrant {
+    #[option(
+        short = "g",
+        long = "groupname",
+        help = "Name of the group",
+        autocomplete = "groups",
+        positional = 0
+    )]
+    pub groupname: Option<String>,
+    #[option(
+        short = "c",
+        long = "collections",
+        help = "Comma-separated collections to grant permissions on",
+        autocomplete = "collection_list"
+    )]
+    pub collections: String,
+    #[option(
+        short = "p",
+        long = "permissions",
+        help = "Comma-separated permissions, such as ReadClass,ReadObject",
+        autocomplete = "permissions"
+    )]
+    pub permissions: Option<String>,
+    #[option(
+        short = "a",
+        long = "all",
+        help = "Grant all permissions",
+        flag = "true"
+    )]
+    pub all: bool,
+}
+
+impl CliCommand for GroupGrant {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let grant = Self::parse_tokens(tokens)?;
+        let groupname = required_option_or_pos(grant.groupname, tokens, 0, "groupname")?;
+        let collections = comma_separated(&grant.collections);
+        if collections.is_empty() {
+            return Err(AppError::MissingOptions(vec!["collections".to_string()]));
+        }
+        let permissions = match (grant.all, grant.permissions.as_deref()) {
+            (true, Some(_)) => {
+                return Err(AppError::ParseError(
+                    "Use either --permissions or --all, not both".to_string(),
+                ))
+            }
+            (true, None) => CollectionPermission::iter().collect(),
+            (false, Some(permissions)) => comma_separated(permissions)
+                .iter()
+                .map(|permission| permission.parse())
+                .collect::<Result<Vec<CollectionPermission>, _>>()?,
+            (false, None) => Vec::new(),
+        };
+        if permissions.is_empty() {
+            return Err(AppError::MissingOptions(vec!["permissions".to_string()]));
+        }
+
+        ctx.services()
+            .gateway()
+            .grant_group_permissions(&groupname, &collections, &permissions)?;
+
+        let granted = if grant.all {
+            "all permissions".to_string()
+        } else {
+            permissions
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let message = format!(
+            "Granted {granted} to group '{groupname}' on {} '{}'",
+            if collections.len() == 1 {
+                "collection"
+            } else {
+                "collections"
+            },
+            collections.join("', '")
+        );
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json_message(&message)?,
+            OutputFormat::Text => append_line(message)?,
+        }
+
+        Ok(())
+    }
+}
+
+fn comma_separated(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct GroupList {
     #[option(short = "g", long = "groupname", help = "Name of the group")]
     pub name: Option<String>,
     #[option(short = "d", long = "description", help = "Description of the group")]
     pub description: Option<String>,
+    #[option(
+        short = "m",
+        long = "member",
+        help = "Only groups this user is a member of",
+        autocomplete = "users"
+    )]
+    pub member: Option<String>,
     #[option(
         long = "where",
         help = "Filter clause: 'field op value'",
@@ -285,7 +425,7 @@ pub struct GroupList {
 }
 
 impl CliCommand for GroupList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let list_query = build_list_query(
             &query.where_clauses,
@@ -302,7 +442,11 @@ impl CliCommand for GroupList {
             .into_iter()
             .flatten(),
         )?;
-        let groups = services.gateway().list_groups(&list_query)?;
+        let gateway = ctx.services().gateway();
+        let groups = match &query.member {
+            Some(username) => gateway.list_member_groups(username, &list_query)?,
+            None => gateway.list_groups(&list_query)?,
+        };
         render_list_page(tokens, &groups)
     }
 }
@@ -313,7 +457,7 @@ mod tests {
     use crate::errors::AppError;
     use crate::tokenizer::CommandTokenizer;
 
-    use super::GroupList;
+    use super::{GroupGrant, GroupList};
 
     #[test]
     fn simple_group_alias_still_parses() {
@@ -328,6 +472,39 @@ mod tests {
         assert_eq!(parsed.name.as_deref(), Some("admins"));
     }
 
+    #[test]
+    fn group_grant_parses_group_collections_and_permissions() {
+        let tokens = CommandTokenizer::new(
+            "group grant ops --collections prod,staging --permissions readclass,ReadObject",
+            "grant",
+            &command_options::<GroupGrant>(),
+        )
+        .expect("tokenization should succeed");
+        let parsed = GroupGrant::parse_tokens(&tokens).expect("group grant should parse");
+
+        assert_eq!(tokens.get_positionals(), &["ops".to_string()]);
+        assert_eq!(
+            super::comma_separated(&parsed.collections),
+            vec!["prod", "staging"]
+        );
+        let permissions = super::comma_separated(parsed.permissions.as_deref().unwrap())
+            .iter()
+            .map(|permission| permission.parse::<super::CollectionPermission>())
+            .collect::<Result<Vec<_>, _>>()
+            .expect("permissions should parse case-insensitively");
+        assert_eq!(
+            permissions,
+            vec![
+                super::CollectionPermission::ReadClass,
+                super::CollectionPermission::ReadObject
+            ]
+        );
+        assert!(matches!(
+            "ReadEverything".parse::<super::CollectionPermission>(),
+            Err(AppError::ParseError(message)) if message.contains("ReadClass")
+        ));
+    }
+
     #[test]
     fn double_underscore_flags_are_rejected() {
         let tokens = CommandTokenizer::new(
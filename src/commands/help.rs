@@ -1,14 +1,15 @@
 use cli_command_derive::CommandArgs;
 
+use crate::catalog::{CommandCatalog, CommandContext, CompletionSpec, OptionSpec};
+use crate::config::get_config;
 use crate::errors::AppError;
-use crate::output::append_line;
-use crate::services::AppServices;
+use crate::output::{append_json, append_line};
+use crate::services::CompletionContext;
 use crate::tokenizer::CommandTokenizer;
 
 use super::builder::{catalog_command, CommandDocs};
-use super::CliCommand;
+use super::{want_json, CliCommand};
 use crate::catalog::CommandCatalogBuilder;
-use crate::commands::build_command_catalog;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
     builder.add_command(
@@ -18,6 +19,15 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
             Help::default(),
             CommandDocs {
                 about: Some("Show help"),
+                long_about: Some(
+                    "Show help for the current scope, or the whole command tree with --tree. \
+                     With --json, print every scope, command, and option (with types, \
+                     required flags, and examples) as JSON for editor plugins and other tools. \
+                     With --long, show a command's full manual through the pager: its \
+                     description, every option with its type, configured default, and \
+                     accepted values, and examples.",
+                ),
+                examples: Some("--tree\n--json\ncollection permissions set --long"),
                 ..CommandDocs::default()
             },
         ),
@@ -29,17 +39,93 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
 pub struct Help {
     #[option(short = "t", long = "tree", help = "Command tree", flag = "true")]
     pub tree: Option<bool>,
+    #[option(
+        short = "l",
+        long = "long",
+        help = "Full manual of a command, through the pager",
+        flag = "true"
+    )]
+    pub long: Option<bool>,
 }
 
-impl CliCommand for Help {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
-        let options = tokens.get_options();
-        if options.get("tree").is_some() {
-            let _ = services;
-            append_line(build_command_catalog().render_tree())?;
-            return Ok(());
+/// Whether `part` asks `help` for a command's full manual.
+pub(crate) fn is_long_flag(part: &str) -> bool {
+    part == "--long" || part == "-l"
+}
+
+/// Renders `help <command> --long` for the command `parts` names from `scope`.
+///
+/// Options list the fixed values they accept when `completion` is given; without a
+/// server connection there is no completion context to ask.
+pub(crate) fn render_manual(
+    catalog: &CommandCatalog,
+    scope: &[String],
+    parts: &[String],
+    completion: Option<&CompletionContext>,
+) -> Result<String, AppError> {
+    if parts.is_empty() {
+        return Err(AppError::ParseError(
+            "help --long needs a command, such as 'help object create --long'".to_string(),
+        ));
+    }
+    let resolved = catalog.resolve_command(scope, parts)?;
+    let completion = completion.map(CompletionContext::fixed_values_only);
+    catalog.render_command_manual(&resolved.command_path, &get_config().defaults, &|option| {
+        match &completion {
+            Some(completion) => fixed_choices(completion, option),
+            None => Vec::new(),
         }
+    })
+}
 
+/// The values `option`'s completer offers without the server, earlier output, or files.
+fn fixed_choices(completion: &CompletionContext, option: &OptionSpec) -> Vec<String> {
+    match option.completion {
+        CompletionSpec::Dynamic(completer) if !option.flag => completer(completion, "", &[]),
+        _ => Vec::new(),
+    }
+}
+
+impl Help {
+    /// Renders help from the catalog the REPL is actually dispatching against.
+    fn render(&self, catalog: &CommandCatalog, scope: &[String]) -> String {
+        if self.tree.unwrap_or(false) {
+            catalog.render_tree()
+        } else {
+            catalog.render_scope_help(scope)
+        }
+    }
+}
+
+impl CliCommand for Help {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let help = Self::parse_tokens(tokens)?;
+        if want_json(tokens) {
+            return append_json(ctx.catalog().metadata());
+        }
+        append_line(help.render(ctx.catalog(), &ctx.session().scope()))?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Help;
+    use crate::commands::build_command_catalog;
+
+    #[test]
+    fn tree_lists_registered_commands() {
+        let catalog = build_command_catalog();
+        let tree = Help {
+            tree: Some(true),
+            ..Help::default()
+        }
+        .render(&catalog, &[]);
+        assert_eq!(tree, catalog.render_tree());
+        assert!(tree.contains("help"), "{tree}");
+        assert_eq!(
+            Help::default().render(&catalog, &["object".to_string()]),
+            catalog.render_scope_help(&["object".to_string()])
+        );
+    }
+}
@@ -6,9 +6,9 @@ use super::{
     normalize_server_page_size, option_or_pos, render_json_record, render_list_page, CliCommand,
 };
 use crate::autocomplete::{classes, objects_from_class};
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::errors::AppError;
-use crate::services::{AppServices, HistoryInput, HistoryScope};
+use crate::services::{HistoryInput, HistoryScope};
 use crate::tokenizer::CommandTokenizer;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -48,6 +48,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                     examples: Some(
                         "--class Hosts --name host.example.org --id 1498\n--class Hosts --name host.example.org --at 2026-07-21T20:17:03Z\n--class Hosts --id 42",
                     ),
+                    ..CommandDocs::default()
                 },
             ),
         );
@@ -63,7 +64,7 @@ pub struct HistoryShow {
         autocomplete = "objects_from_class"
     )]
     pub name: Option<String>,
-    #[option(long = "id", help = "History record ID")]
+    #[option(long = "id", help = "History record ID", positional = 0)]
     pub id: Option<i64>,
     #[option(long = "at", help = "As-of RFC3339 timestamp")]
     pub at: Option<String>,
@@ -75,7 +76,7 @@ enum HistorySelector {
 }
 
 impl CliCommand for HistoryShow {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.id = option_or_pos(query.id, tokens, 0, "id")?;
         let class_name = query
@@ -90,8 +91,8 @@ impl CliCommand for HistoryShow {
         };
 
         let record = match history_selector(query.id, query.at)? {
-            HistorySelector::Id(id) => services.gateway().history_record_by_id(scope, id)?,
-            HistorySelector::At(at) => services.gateway().history_record_at(scope, &at)?,
+            HistorySelector::Id(id) => ctx.services().gateway().history_record_by_id(scope, id)?,
+            HistorySelector::At(at) => ctx.services().gateway().history_record_at(scope, &at)?,
         };
 
         render_json_record(tokens, &record)
@@ -113,7 +114,12 @@ fn history_selector(id: Option<i64>, at: Option<String>) -> Result<HistorySelect
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct ClassHistory {
-    #[option(long = "class", help = "Class name", autocomplete = "classes")]
+    #[option(
+        long = "class",
+        help = "Class name",
+        autocomplete = "classes",
+        positional = 0
+    )]
     pub class: Option<String>,
     #[option(long = "at", help = "As-of RFC3339 timestamp")]
     pub at: Option<String>,
@@ -132,7 +138,7 @@ pub struct ClassHistory {
 }
 
 impl CliCommand for ClassHistory {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.class = query
             .class
@@ -141,7 +147,7 @@ impl CliCommand for ClassHistory {
             .class
             .as_deref()
             .ok_or_else(|| AppError::MissingOptions(vec!["class".to_string()]))?;
-        let history = services.gateway().history(
+        let history = ctx.services().gateway().history(
             HistoryScope::ClassName(class_name.to_string()),
             HistoryInput {
                 limit: normalize_server_page_size(query.limit)?,
@@ -157,12 +163,18 @@ impl CliCommand for ClassHistory {
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct ObjectHistory {
-    #[option(long = "class", help = "Class name", autocomplete = "classes")]
+    #[option(
+        long = "class",
+        help = "Class name",
+        autocomplete = "classes",
+        positional = 0
+    )]
     pub class: Option<String>,
     #[option(
         long = "name",
         help = "Object name",
-        autocomplete = "objects_from_class"
+        autocomplete = "objects_from_class",
+        positional = 1
     )]
     pub name: Option<String>,
     #[option(long = "at", help = "As-of RFC3339 timestamp")]
@@ -182,7 +194,7 @@ pub struct ObjectHistory {
 }
 
 impl CliCommand for ObjectHistory {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         let positionals = tokens.get_positionals();
         query.class = query.class.or_else(|| positionals.first().cloned());
@@ -195,7 +207,7 @@ impl CliCommand for ObjectHistory {
             .name
             .as_deref()
             .ok_or_else(|| AppError::MissingOptions(vec!["name".to_string()]))?;
-        let history = services.gateway().history(
+        let history = ctx.services().gateway().history(
             HistoryScope::ObjectName {
                 class_name: class_name.to_string(),
                 object_name: object_name.to_string(),
@@ -10,12 +10,16 @@ use serde_json::from_str;
 
 use super::builder::{catalog_command, CommandDocs};
 use super::task_submit::{parse_task_submit_options, run_task_backed};
-use super::{build_list_query, option_or_pos, render_list_page, render_task_record, CliCommand};
+use super::{
+    build_list_query, desired_format, option_or_pos, render_list_page, render_task_record,
+    CliCommand,
+};
 use crate::autocomplete::{collections, file_paths, import_result_sort};
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::errors::AppError;
+use crate::safety::{confirm_write_ahead, WriteAheadSummary};
 use crate::services::CompletionContext;
-use crate::services::{AppServices, SubmitImportInput};
+use crate::services::SubmitImportInput;
 use crate::tokenizer::CommandTokenizer;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -31,6 +35,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                         "Submit an import request from a local JSON file or HTTP(S) URL. CLI policy flags override the request mode. --collection rewrites the import to reuse an existing collection and removes collection creation/permission entries.",
                     ),
                     examples: Some("--file import.json --collection Math --collision-policy overwrite\n--http https://example.com/import.json --atomicity best_effort"),
+                    mutating: true,
                 },
             ),
         )
@@ -113,19 +118,27 @@ pub struct ImportSubmit {
 }
 
 impl CliCommand for ImportSubmit {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let opts = parse_task_submit_options(tokens)?;
         if let Some(collection) = &query.collection {
-            services.gateway().get_collection(collection)?;
+            ctx.services().gateway().get_collection(collection)?;
         }
         let request = import_request(&query)?;
-        let task = services.gateway().submit_import(SubmitImportInput {
+        if request.dry_run != Some(true) {
+            confirm_write_ahead(
+                &ctx.config(),
+                desired_format(tokens),
+                "import submit",
+                &write_ahead_summary(&request),
+            )?;
+        }
+        let task = ctx.services().gateway().submit_import(SubmitImportInput {
             request,
             idempotency_key: query.idempotency_key,
         })?;
         run_task_backed(
-            services,
+            ctx.services(),
             tokens,
             format!("import {}", task.0.id),
             opts,
@@ -154,6 +167,23 @@ fn import_request(query: &ImportSubmit) -> Result<ImportRequest, AppError> {
     Ok(request)
 }
 
+/// The entries of `request` per entity type. Whether an entry creates or overwrites is
+/// only known to the server, through the collision policy.
+fn write_ahead_summary(request: &ImportRequest) -> WriteAheadSummary {
+    let graph = &request.graph;
+    let mut summary = WriteAheadSummary::new(&["Import"]);
+    summary.add("collection", vec![graph.collections.len()]);
+    summary.add("class", vec![graph.classes.len()]);
+    summary.add("class relation", vec![graph.class_relations.len()]);
+    summary.add("object", vec![graph.objects.len()]);
+    summary.add("object relation", vec![graph.object_relations.len()]);
+    summary.add(
+        "collection permission",
+        vec![graph.collection_permissions.len()],
+    );
+    summary
+}
+
 fn apply_mode_overrides(request: &mut ImportRequest, query: &ImportSubmit) {
     if query.atomicity.is_none()
         && query.collision_policy.is_none()
@@ -252,15 +282,15 @@ fn complete_import_policy(prefix: &str, values: &[&str]) -> Vec<String> {
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct ImportShow {
-    #[option(short = "i", long = "id", help = "Import task ID")]
+    #[option(short = "i", long = "id", help = "Import task ID", positional = 0)]
     pub id: Option<i32>,
 }
 
 impl CliCommand for ImportShow {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.id = option_or_pos(query.id, tokens, 0, "id")?;
-        let task = services.gateway().import_task(
+        let task = ctx.services().gateway().import_task(
             query
                 .id
                 .ok_or_else(|| AppError::MissingOptions(vec!["id".to_string()]))?,
@@ -272,7 +302,7 @@ impl CliCommand for ImportShow {
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct ImportResults {
-    #[option(short = "i", long = "id", help = "Import task ID")]
+    #[option(short = "i", long = "id", help = "Import task ID", positional = 0)]
     pub id: Option<i32>,
     #[option(
         long = "sort",
@@ -294,7 +324,7 @@ pub struct ImportResults {
 }
 
 impl CliCommand for ImportResults {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.id = option_or_pos(query.id, tokens, 0, "id")?;
         let list_query = build_list_query(
@@ -305,7 +335,7 @@ impl CliCommand for ImportResults {
             query.include_total.unwrap_or(false),
             [],
         )?;
-        let results = services.gateway().import_results(
+        let results = ctx.services().gateway().import_results(
             query
                 .id
                 .ok_or_else(|| AppError::MissingOptions(vec!["id".to_string()]))?,
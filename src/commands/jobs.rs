@@ -4,12 +4,12 @@ use serde_json::to_string_pretty;
 
 use super::builder::{catalog_command, CommandDocs};
 use super::{desired_format, option_or_pos, CliCommand};
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::errors::AppError;
 use crate::formatting::{append_json_message, OutputFormatter};
 use crate::models::OutputFormat;
 use crate::output::append_line;
-use crate::services::{AppServices, TaskLookupInput};
+use crate::services::TaskLookupInput;
 use crate::tokenizer::CommandTokenizer;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -81,9 +81,9 @@ fn register_group(builder: &mut CommandCatalogBuilder, prefix: &'static str) {
 pub struct JobsList {}
 
 impl CliCommand for JobsList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
-        services.background().require_enabled()?;
-        let jobs = services.background().list_jobs();
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        ctx.services().background().require_enabled()?;
+        let jobs = ctx.services().background().list_jobs();
 
         match desired_format(tokens) {
             OutputFormat::Json => append_line(to_string_pretty(&jobs)?)?,
@@ -103,19 +103,25 @@ impl CliCommand for JobsList {
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct JobsShow {
-    #[option(short = "i", long = "id", help = "Local background job ID")]
+    #[option(
+        short = "i",
+        long = "id",
+        help = "Local background job ID",
+        positional = 0
+    )]
     pub id: Option<u64>,
 }
 
 impl CliCommand for JobsShow {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
-        services.background().require_enabled()?;
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        ctx.services().background().require_enabled()?;
         let mut query = Self::parse_tokens(tokens)?;
         query.id = option_or_pos(query.id, tokens, 0, "id")?;
         let id = query
             .id
             .ok_or_else(|| AppError::MissingOptions(vec!["id".to_string()]))?;
-        let job = services
+        let job = ctx
+            .services()
             .background()
             .job(id)
             .ok_or_else(|| AppError::EntityNotFound(format!("background job {id}")))?;
@@ -140,23 +146,29 @@ impl CliCommand for JobsShow {
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct JobsOutput {
-    #[option(short = "i", long = "id", help = "Local background job ID")]
+    #[option(
+        short = "i",
+        long = "id",
+        help = "Local background job ID",
+        positional = 0
+    )]
     pub id: Option<u64>,
 }
 
 impl CliCommand for JobsOutput {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
-        services.background().require_enabled()?;
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        ctx.services().background().require_enabled()?;
         let mut query = Self::parse_tokens(tokens)?;
         query.id = option_or_pos(query.id, tokens, 0, "id")?;
         let local_id = query
             .id
             .ok_or_else(|| AppError::MissingOptions(vec!["id".to_string()]))?;
-        let job = services
+        let job = ctx
+            .services()
             .background()
             .job(local_id)
             .ok_or_else(|| AppError::EntityNotFound(format!("background job {local_id}")))?;
-        let output = services.gateway().task_output(job.task_id)?;
+        let output = ctx.services().gateway().task_output(job.task_id)?;
         match desired_format(tokens) {
             OutputFormat::Json => append_line(to_string_pretty(&output)?)?,
             OutputFormat::Text => {
@@ -171,20 +183,21 @@ impl CliCommand for JobsOutput {
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct JobsWatch {
-    #[option(short = "t", long = "task", help = "Server task ID")]
+    #[option(short = "t", long = "task", help = "Server task ID", positional = 0)]
     pub task: Option<i32>,
 }
 
 impl CliCommand for JobsWatch {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
-        services.background().require_enabled()?;
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        ctx.services().background().require_enabled()?;
         let mut query = Self::parse_tokens(tokens)?;
         query.task = option_or_pos(query.task, tokens, 0, "task")?;
         let task_id = query
             .task
             .ok_or_else(|| AppError::MissingOptions(vec!["task".to_string()]))?;
-        let task = services.gateway().task(TaskLookupInput { task_id })?;
-        let registration = services
+        let task = ctx.services().gateway().task(TaskLookupInput { task_id })?;
+        let registration = ctx
+            .services()
             .background()
             .watch_task(task, format!("task {task_id}"))
             .ok_or_else(|| {
@@ -222,19 +235,24 @@ impl CliCommand for JobsWatch {
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct JobsForget {
-    #[option(short = "i", long = "id", help = "Local background job ID")]
+    #[option(
+        short = "i",
+        long = "id",
+        help = "Local background job ID",
+        positional = 0
+    )]
     pub id: Option<u64>,
 }
 
 impl CliCommand for JobsForget {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
-        services.background().require_enabled()?;
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        ctx.services().background().require_enabled()?;
         let mut query = Self::parse_tokens(tokens)?;
         query.id = option_or_pos(query.id, tokens, 0, "id")?;
         let id = query
             .id
             .ok_or_else(|| AppError::MissingOptions(vec!["id".to_string()]))?;
-        if !services.background().forget_job(id) {
+        if !ctx.services().background().forget_job(id) {
             return Err(AppError::EntityNotFound(format!("background job {id}")));
         }
 
@@ -1,15 +1,19 @@
+use chrono::Utc;
 use cli_command_derive::CommandArgs;
 use serde::{Deserialize, Serialize};
 use serde_json::to_string_pretty;
 
-use super::builder::{catalog_command, CommandDocs};
-use super::{desired_format, CliCommand};
-use crate::catalog::CommandCatalogBuilder;
+use super::builder::{catalog_command, exits_repl, own_credentials, CommandDocs};
+use super::{desired_format, required_option_or_pos, CliCommand};
+use crate::app::switch_login;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
+use crate::config::{get_config, AppConfig, ServerConfig};
+use crate::domain::SessionRecord;
 use crate::errors::AppError;
-use crate::formatting::OutputFormatter;
+use crate::files::{token_storage, TokenStore};
+use crate::formatting::{append_json_message, OutputFormatter};
 use crate::models::OutputFormat;
 use crate::output::append_line;
-use crate::services::AppServices;
 use crate::tokenizer::CommandTokenizer;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -60,26 +64,61 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
             ),
         );
 
-    // Also register "whoami" as a top-level alias for "me show"
     builder.add_command(
         &[],
         catalog_command(
             "whoami",
-            MeShow::default(),
+            Whoami::default(),
             CommandDocs {
-                about: Some("Show current identity (alias for 'me show')"),
+                about: Some("Show who you are logged in as"),
+                long_about: Some(
+                    "Summarize the current session: the authenticated username, the server, how old the session token is, your group memberships, and whether you have admin rights. Use `me show` for the raw identity and token details.",
+                ),
+                examples: Some("whoami\nwhoami --output json"),
                 ..CommandDocs::default()
             },
         ),
     );
+    builder.add_command(
+        &[],
+        own_credentials(catalog_command(
+            "login",
+            Login::default(),
+            CommandDocs {
+                about: Some("Log in as another user without restarting"),
+                long_about: Some(
+                    "Authenticate as another user, optionally on another server, and switch the running session to that login. The current login stays active if authentication fails. Passwords and token files configured for the current login are not reused: a cached token for the new login is used when it is still valid, otherwise the password is prompted for. Without --identity-scope the new login is a local user.",
+                ),
+                examples: Some(
+                    "login --username bob\nlogin alice --identity-scope ldap\nlogin --username admin --hostname hubuum-staging.example.com",
+                ),
+                ..CommandDocs::default()
+            },
+        )),
+    );
+    builder.add_command(
+        &[],
+        exits_repl(catalog_command(
+            "logout",
+            Logout::default(),
+            CommandDocs {
+                about: Some("Log out and forget the cached token"),
+                long_about: Some(
+                    "Revoke the session token on the server, remove it from the local token cache, and leave the REPL. The next start asks for a password again. Logins that read their token from server.token_file are not logged out, as that token is not managed by the CLI.",
+                ),
+                examples: Some("logout"),
+                mutating: true,
+            },
+        )),
+    );
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct MeShow {}
 
 impl CliCommand for MeShow {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
-        let me = services.gateway().me()?;
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let me = ctx.services().gateway().me()?;
 
         match desired_format(tokens) {
             OutputFormat::Json => append_line(to_string_pretty(&me)?)?,
@@ -90,12 +129,146 @@ impl CliCommand for MeShow {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct Whoami {}
+
+impl CliCommand for Whoami {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let _query = Self::parse_tokens(tokens)?;
+        let gateway = ctx.services().gateway();
+        let groups = gateway
+            .me_groups()?
+            .into_iter()
+            .map(|group| group.0.groupname)
+            .collect();
+        let session = SessionRecord::new(
+            gateway.me()?.0,
+            get_config().server.server_url(),
+            groups,
+            gateway.is_admin()?,
+            Utc::now(),
+        );
+
+        match desired_format(tokens) {
+            OutputFormat::Json => session.format_json_noreturn()?,
+            OutputFormat::Text => session.format_noreturn()?,
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct Login {
+    #[option(
+        short = "u",
+        long = "username",
+        help = "User to log in as",
+        positional = 0
+    )]
+    pub username: Option<String>,
+    #[option(
+        long = "hostname",
+        help = "Server to log in to, defaults to the current one"
+    )]
+    pub hostname: Option<String>,
+    #[option(
+        long = "identity-scope",
+        help = "Identity scope to authenticate through, omit for a local user"
+    )]
+    pub identity_scope: Option<String>,
+}
+
+impl CliCommand for Login {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let username = required_option_or_pos(query.username, tokens, 0, "username")?;
+        let mut config = AppConfig::clone(&get_config());
+        config.server = login_target(
+            &config.server,
+            username,
+            query.hostname,
+            query.identity_scope,
+        );
+        config.profile = None;
+        let message = format!(
+            "Logged in as '{}' on {}",
+            config.server.username,
+            config.server.server_url()
+        );
+        switch_login(ctx.services(), config)?;
+
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json_message(&message)?,
+            OutputFormat::Text => append_line(message)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// The login `login` switches to. Credentials configured for the current login do not
+/// carry over, so the new one uses its cached token or asks for its password.
+fn login_target(
+    current: &ServerConfig,
+    username: String,
+    hostname: Option<String>,
+    identity_scope: Option<String>,
+) -> ServerConfig {
+    ServerConfig {
+        hostname: hostname.unwrap_or_else(|| current.hostname.clone()),
+        identity_scope,
+        username,
+        ..current.clone()
+    }
+    .without_credentials()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct Logout {}
+
+impl CliCommand for Logout {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let _query = Self::parse_tokens(tokens)?;
+        let config = get_config();
+        let server = &config.server;
+        if server.token_file.is_some() {
+            return Err(AppError::CommandExecutionError(
+                "This login uses server.token_file; remove or rotate that token instead of logging out"
+                    .to_string(),
+            ));
+        }
+
+        ctx.services().gateway().logout()?;
+        let storage = token_storage(&config.auth, server)?;
+        TokenStore::update(storage.as_ref(), |store| {
+            store.remove(
+                &server.hostname,
+                server.identity_scope.as_deref(),
+                &server.username,
+            )
+        })?;
+
+        let message = format!(
+            "Logged out '{}' from {}",
+            server.username,
+            server.server_url()
+        );
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json_message(&message)?,
+            OutputFormat::Text => append_line(message)?,
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct MeGroups {}
 
 impl CliCommand for MeGroups {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
-        let groups = services.gateway().me_groups()?;
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let groups = ctx.services().gateway().me_groups()?;
 
         match desired_format(tokens) {
             OutputFormat::Json => append_line(to_string_pretty(&groups)?)?,
@@ -110,8 +283,8 @@ impl CliCommand for MeGroups {
 pub struct MeTokens {}
 
 impl CliCommand for MeTokens {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
-        let token_list = services.gateway().me_tokens()?;
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let token_list = ctx.services().gateway().me_tokens()?;
 
         match desired_format(tokens) {
             OutputFormat::Json => append_line(to_string_pretty(&token_list)?)?,
@@ -126,8 +299,8 @@ impl CliCommand for MeTokens {
 pub struct MePermissions {}
 
 impl CliCommand for MePermissions {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
-        let permissions = services.gateway().me_permissions()?;
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let permissions = ctx.services().gateway().me_permissions()?;
 
         match desired_format(tokens) {
             OutputFormat::Json => append_line(to_string_pretty(&permissions)?)?,
@@ -137,3 +310,36 @@ impl CliCommand for MePermissions {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::login_target;
+    use crate::config::AppConfig;
+
+    #[test]
+    fn switching_login_keeps_the_server_but_not_the_credentials() {
+        let mut current = AppConfig::default().server;
+        current.hostname = "hubuum.example.com".to_string();
+        current.username = "alice".to_string();
+        current.identity_scope = Some("ldap".to_string());
+        current.password = Some("secret".to_string());
+        current.token_file = Some("/run/token".to_string());
+
+        let target = login_target(&current, "bob".to_string(), None, None);
+        assert_eq!(target.hostname, "hubuum.example.com");
+        assert_eq!(target.port, current.port);
+        assert_eq!(target.username, "bob");
+        assert_eq!(target.identity_scope, None);
+        assert_eq!(target.password, None);
+        assert_eq!(target.token_file, None);
+
+        let target = login_target(
+            &current,
+            "carol".to_string(),
+            Some("staging.example.com".to_string()),
+            Some("corp".to_string()),
+        );
+        assert_eq!(target.hostname, "staging.example.com");
+        assert_eq!(target.identity_scope.as_deref(), Some("corp"));
+    }
+}
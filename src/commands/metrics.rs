@@ -8,11 +8,11 @@ use serde::Serialize;
 use super::builder::{catalog_command, CommandDocs};
 use super::CliCommand;
 use crate::build_info;
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::config::get_config;
 use crate::errors::AppError;
+use crate::offline::require_online;
 use crate::output::set_semantic_output;
-use crate::services::AppServices;
 use crate::tokenizer::CommandTokenizer;
 
 const METRICS_TIMEOUT: Duration = Duration::from_secs(10);
@@ -29,6 +29,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                     "Fetch Prometheus exposition text without logging in. The default route is /metrics; use --path when the server exposes a different configured route. The server's metrics client allowlist still applies.",
                 ),
                 examples: Some("--path /internal/metrics\n--output json"),
+                ..CommandDocs::default()
             },
         ),
     );
@@ -44,19 +45,16 @@ pub struct Metrics {
 }
 
 impl CliCommand for Metrics {
-    fn execute(&self, _services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         render_metrics(tokens)
     }
 }
 
 pub(crate) fn render_metrics(tokens: &CommandTokenizer) -> Result<(), AppError> {
     let query = Metrics::parse_tokens(tokens)?;
+    require_online("fetching metrics")?;
     let config = get_config();
-    let base_url = format!(
-        "{}://{}:{}",
-        config.server.protocol, config.server.hostname, config.server.port
-    );
-    let client = BlockingClient::builder_from_url(base_url)?
+    let client = BlockingClient::builder_from_url(config.server.server_url())?
         .validate_certs(config.server.ssl_validation)
         .timeout(METRICS_TIMEOUT)
         .user_agent(format!("hubuum-cli/{}", build_info::VERSION))
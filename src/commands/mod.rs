@@ -1,18 +1,24 @@
 use log::trace;
 use serde::Serialize;
-use serde_json::to_string_pretty;
+use serde_json::{from_str, to_string_pretty, Value};
 use std::any::TypeId;
 use std::collections::HashSet;
 use std::fmt::Display;
+use std::io::{BufRead, Write};
+use std::path::Path;
 use std::str::FromStr;
 
 use hubuum_client::FilterOperator;
+use hubuum_filter::{PipeStage, ProjectTerm};
 
 mod admin;
-mod audit;
+mod apply;
+pub(crate) mod audit;
 pub(crate) mod auth;
 mod backup;
 mod builder;
+mod bulk;
+mod cache;
 mod class;
 mod collection;
 mod computed;
@@ -20,33 +26,40 @@ pub(crate) mod config;
 mod event_delivery;
 mod event_sink;
 mod event_subscription;
+pub(crate) mod explain;
 mod export;
 mod group;
-mod help;
+pub(crate) mod help;
 mod history;
 mod imports;
 mod jobs;
 mod me;
 pub(crate) mod metrics;
 mod object;
+mod offline;
+pub(crate) mod profile;
 mod relations;
 mod remote_target;
 mod search;
 mod service_account;
+mod stats;
 mod task;
 mod task_submit;
 pub(crate) mod theme;
+pub(crate) mod token;
 mod user;
 pub(crate) mod version;
 
 pub use builder::build_command_catalog;
 
 use crate::autocomplete::output_formats;
-use crate::domain::{JsonRecord, TaskRecord};
-use crate::output::RenderFormat;
+use crate::domain::{BulkSummary, JsonRecord, TaskRecord};
+use crate::files::read_text_file;
+use crate::forms::Form;
+use crate::output::{config_render_format, RenderFormat};
 use crate::services::CompletionContext;
 use crate::suggestions::did_you_mean_message;
-use crate::{errors::AppError, services::AppServices, tokenizer::CommandTokenizer};
+use crate::{catalog::CommandContext, errors::AppError, tokenizer::CommandTokenizer};
 use crate::{
     formatting::{OutputFormatter, TableRenderable},
     list_query::{
@@ -70,6 +83,8 @@ pub struct CliOption {
     pub nargs: Option<usize>,
     pub repeatable: bool,
     pub value_source: bool,
+    pub raw: bool,
+    pub positional: Option<usize>,
     pub help: String,
     pub field_type: TypeId,
     pub field_type_help: String,
@@ -94,7 +109,7 @@ pub trait CommandArgs: Sized + Default + Send + Sync + 'static {
 }
 
 pub trait CliCommand: CommandArgs + Send + Sync {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError>;
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError>;
 }
 
 pub fn standard_options() -> Vec<CliOption> {
@@ -108,6 +123,8 @@ pub fn standard_options() -> Vec<CliOption> {
             nargs: None,
             repeatable: false,
             value_source: false,
+            raw: false,
+            positional: None,
             help: "Prints help information".to_string(),
             field_type: TypeId::of::<bool>(),
             field_type_help: "bool".to_string(),
@@ -123,6 +140,8 @@ pub fn standard_options() -> Vec<CliOption> {
             nargs: None,
             repeatable: false,
             value_source: false,
+            raw: false,
+            positional: None,
             help: "Output as JSON".to_string(),
             field_type: TypeId::of::<bool>(),
             field_type_help: "bool".to_string(),
@@ -138,12 +157,65 @@ pub fn standard_options() -> Vec<CliOption> {
             nargs: None,
             repeatable: false,
             value_source: false,
+            raw: false,
+            positional: None,
             help: "Output format: text, json, jsonl, csv, or tsv".to_string(),
             field_type: TypeId::of::<String>(),
             field_type_help: "string".to_string(),
             required: false,
             autocomplete: Some(output_formats),
         },
+        CliOption {
+            name: "fields".to_string(),
+            short: None,
+            long: Some("--fields".to_string()),
+            flag: false,
+            greedy: false,
+            nargs: None,
+            repeatable: false,
+            value_source: false,
+            raw: false,
+            positional: None,
+            help: "Comma-separated fields to keep in JSON output, e.g. id,name,data.os".to_string(),
+            field_type: TypeId::of::<String>(),
+            field_type_help: "string".to_string(),
+            required: false,
+            autocomplete: None,
+        },
+        CliOption {
+            name: "reveal".to_string(),
+            short: None,
+            long: Some("--reveal".to_string()),
+            flag: true,
+            greedy: false,
+            nargs: None,
+            repeatable: false,
+            value_source: false,
+            raw: false,
+            positional: None,
+            help: "Show object data values hidden by output.mask_keys".to_string(),
+            field_type: TypeId::of::<bool>(),
+            field_type_help: "bool".to_string(),
+            required: false,
+            autocomplete: None,
+        },
+        CliOption {
+            name: "explain".to_string(),
+            short: None,
+            long: Some("--explain".to_string()),
+            flag: true,
+            greedy: false,
+            nargs: None,
+            repeatable: false,
+            value_source: false,
+            raw: false,
+            positional: None,
+            help: "List the API requests the command would send, without sending them".to_string(),
+            field_type: TypeId::of::<bool>(),
+            field_type_help: "bool".to_string(),
+            required: false,
+            autocomplete: None,
+        },
     ]
 }
 
@@ -159,9 +231,51 @@ pub fn validate_command_args<C: CommandArgs>(tokens: &CommandTokenizer) -> Resul
     validate_missing_options::<C>(tokens)?;
     validate_flag_options::<C>(tokens)?;
     validate_output_options(tokens)?;
+    validate_positionals::<C>(tokens)?;
     Ok(())
 }
 
+/// Rejects positional arguments at indices not declared with `positional = N`, which
+/// would otherwise be ignored silently (e.g. `object list Hosts` without `--class`).
+pub fn validate_positionals<C: CommandArgs>(tokens: &CommandTokenizer) -> Result<(), AppError> {
+    let mut declared = command_options::<C>()
+        .into_iter()
+        .filter_map(|opt| Some((opt.positional?, opt.name)))
+        .collect::<Vec<_>>();
+    declared.sort();
+
+    let unused = tokens
+        .get_positionals()
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !declared.iter().any(|(declared, _)| declared == index))
+        .map(|(_, value)| format!("'{value}'"))
+        .collect::<Vec<_>>();
+    if unused.is_empty() {
+        return Ok(());
+    }
+
+    let command = tokens.command_path();
+    let command = if command.is_empty() {
+        "command".to_string()
+    } else {
+        command
+    };
+    let usage = declared
+        .iter()
+        .map(|(_, name)| format!(" [{}]", name.to_uppercase()))
+        .collect::<String>();
+    let noun = if unused.len() == 1 {
+        "argument"
+    } else {
+        "arguments"
+    };
+    Err(AppError::ParseError(format!(
+        "Unexpected {noun} {}. Usage: {command}{usage} [OPTIONS]; see '{command} --help'",
+        unused.join(", ")
+    )))
+}
+
 pub fn validate_unknown_options<C: CommandArgs>(tokens: &CommandTokenizer) -> Result<(), AppError> {
     let mut known_options = HashSet::new();
     let mut known_display_options = Vec::new();
@@ -291,8 +405,17 @@ pub fn validate_flag_options<C: CommandArgs>(tokens: &CommandTokenizer) -> Resul
     Ok(())
 }
 
+/// Whether a command should build JSON or text output.
+///
+/// Without `--json` or `--output`, the configured default format decides; csv, tsv,
+/// and jsonl render the text output's tables.
 pub fn desired_format(tokens: &CommandTokenizer) -> OutputFormat {
-    if want_json(tokens) || output_format_name(tokens).as_deref() == Some("json") {
+    let json = match output_format_name(tokens) {
+        _ if want_json(tokens) => true,
+        Some(format) => format == "json",
+        None => config_render_format() == RenderFormat::Json,
+    };
+    if json {
         OutputFormat::Json
     } else {
         OutputFormat::Text
@@ -350,6 +473,47 @@ pub fn render_task_record(tokens: &CommandTokenizer, task: &TaskRecord) -> Resul
     Ok(())
 }
 
+/// Renders the counts of a bulk operation, followed by its failures if there are any.
+pub fn render_bulk_summary(summary: &BulkSummary) -> Result<(), AppError> {
+    vec![summary.clone()].format_noreturn()?;
+    if summary.has_failures() {
+        summary.failed.format_noreturn()?;
+    }
+    Ok(())
+}
+
+/// Offers `items` as a checklist (`--select`) and splits them into the picked and the
+/// skipped ones, both in their original order.
+pub fn select_targets<T>(
+    items: Vec<T>,
+    label: impl Fn(&T) -> String,
+) -> Result<(Vec<T>, Vec<T>), AppError> {
+    if items.is_empty() {
+        return Ok((items, Vec::new()));
+    }
+    let mut form = Form::terminal("--select", "run without --select to act on every target")?;
+    select_targets_with(&mut form, items, label)
+}
+
+fn select_targets_with<R: BufRead, W: Write, T>(
+    form: &mut Form<R, W>,
+    items: Vec<T>,
+    label: impl Fn(&T) -> String,
+) -> Result<(Vec<T>, Vec<T>), AppError> {
+    if items.is_empty() {
+        return Ok((items, Vec::new()));
+    }
+    let selected = form.checklist(&items.iter().map(label).collect::<Vec<_>>())?;
+    let (picked, skipped): (Vec<_>, Vec<_>) = items
+        .into_iter()
+        .zip(selected)
+        .partition(|(_, selected)| *selected);
+    Ok((
+        picked.into_iter().map(|(item, _)| item).collect(),
+        skipped.into_iter().map(|(item, _)| item).collect(),
+    ))
+}
+
 pub fn contains_clause(field: impl Into<String>, value: impl Into<String>) -> FilterClause {
     filter_clause(
         field,
@@ -358,6 +522,29 @@ pub fn contains_clause(field: impl Into<String>, value: impl Into<String>) -> Fi
     )
 }
 
+/// Case-insensitive match for a `*` wildcard pattern.
+///
+/// `*@example.com` ends with, `alice*` starts with, and `*ops*` or a pattern without
+/// wildcards contains the text; wildcards elsewhere fall back to a `like` match.
+pub fn wildcard_clause(field: impl Into<String>, pattern: &str) -> FilterClause {
+    let leading = pattern.starts_with('*');
+    let trailing = pattern.len() > 1 && pattern.ends_with('*');
+    let inner = pattern.trim_matches('*');
+    if inner.contains('*') {
+        return filter_clause(
+            field,
+            FilterOperator::Like { is_negated: false },
+            pattern.replace('*', "%"),
+        );
+    }
+    let operator = match (leading, trailing) {
+        (true, false) => FilterOperator::IEndsWith { is_negated: false },
+        (false, true) => FilterOperator::IStartsWith { is_negated: false },
+        _ => FilterOperator::IContains { is_negated: false },
+    };
+    filter_clause(field, operator, inner)
+}
+
 pub fn equals_clause(field: impl Into<String>, value: impl Into<String>) -> FilterClause {
     filter_clause(field, FilterOperator::Equals { is_negated: false }, value)
 }
@@ -383,6 +570,27 @@ where
         .transpose()
 }
 
+/// Like [`option_or_pos`], but refuses a value given both as an option and positionally,
+/// for leading positionals such as the class in `object delete Hosts web1`, where the
+/// option would otherwise silently drop a misspelt argument.
+pub fn exclusive_option_or_pos<T>(
+    value: Option<T>,
+    tokens: &CommandTokenizer,
+    pos: usize,
+    name: &str,
+) -> Result<Option<T>, AppError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    if let (Some(_), Some(positional)) = (&value, tokens.get_positionals().get(pos)) {
+        return Err(AppError::ParseError(format!(
+            "{name} was given both as --{name} and as the argument '{positional}'"
+        )));
+    }
+    option_or_pos(value, tokens, pos, name)
+}
+
 pub fn required_option_or_pos<T>(
     value: Option<T>,
     tokens: &CommandTokenizer,
@@ -424,6 +632,26 @@ pub fn required_i64(value: Option<i64>, name: &str) -> Result<i64, AppError> {
     required_option(value, name)
 }
 
+/// Picks a JSON document given either inline as `--{option}` or as a path in `--{option}-file`.
+pub fn json_option_or_file(
+    inline: Option<Value>,
+    file: Option<String>,
+    option: &str,
+) -> Result<Option<Value>, AppError> {
+    match (inline, file) {
+        (Some(_), Some(_)) => Err(AppError::InvalidOption(format!(
+            "Use either --{option} or --{option}-file, not both"
+        ))),
+        (inline, None) => Ok(inline),
+        (None, Some(path)) => read_json_file(&path).map(Some),
+    }
+}
+
+fn read_json_file(path: &str) -> Result<Value, AppError> {
+    from_str(&read_text_file(Path::new(path))?)
+        .map_err(|error| AppError::ParseError(format!("{path} is not valid JSON: {error}")))
+}
+
 pub fn render_json_record(tokens: &CommandTokenizer, record: &JsonRecord) -> Result<(), AppError> {
     match desired_format(tokens) {
         OutputFormat::Json => record.format_json_noreturn(),
@@ -450,6 +678,11 @@ pub fn want_json(tokens: &CommandTokenizer) -> bool {
     opts.contains_key("j") || opts.contains_key("json")
 }
 
+/// Whether the command was given `--reveal` to show values hidden by `output.mask_keys`.
+pub fn wants_reveal(tokens: &CommandTokenizer) -> bool {
+    tokens.get_options().contains_key("reveal")
+}
+
 pub fn output_format_name(tokens: &CommandTokenizer) -> Option<String> {
     let opts = tokens.get_options();
     opts.get("o")
@@ -463,16 +696,45 @@ pub fn render_format(tokens: &CommandTokenizer) -> Result<RenderFormat, AppError
         return Ok(RenderFormat::Json);
     }
 
-    match output_format_name(tokens).as_deref() {
-        Some("text") | None => Ok(RenderFormat::Text),
-        Some("json") => Ok(RenderFormat::Json),
-        Some("jsonl") => Ok(RenderFormat::Jsonl),
-        Some("csv") => Ok(RenderFormat::Csv),
-        Some("tsv") => Ok(RenderFormat::Tsv),
-        Some(other) => Err(AppError::ParseError(format!(
-            "Unknown output format: {other}. Use text, json, jsonl, csv, or tsv."
-        ))),
+    match output_format_name(tokens) {
+        Some(format) => format.parse().map_err(AppError::ParseError),
+        None => Ok(config_render_format()),
+    }
+}
+
+/// Turns `--fields id,name,data.os` into the same projection `| columns` applies, so
+/// scripts can trim JSON output without a pipe. Fields prefixed with `!` are dropped.
+pub fn fields_projection(
+    tokens: &CommandTokenizer,
+    format: RenderFormat,
+) -> Result<Option<PipeStage>, AppError> {
+    let Some(fields) = tokens.get_options().get("fields") else {
+        return Ok(None);
+    };
+    if !matches!(format, RenderFormat::Json | RenderFormat::Jsonl) {
+        return Err(AppError::InvalidOption(
+            "--fields only applies to JSON output; add --json or use '| columns' instead"
+                .to_string(),
+        ));
+    }
+
+    let terms = fields
+        .split(',')
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .map(|field| {
+            field
+                .strip_prefix('!')
+                .map(ProjectTerm::drop)
+                .unwrap_or_else(|| ProjectTerm::keep(field))
+        })
+        .collect::<Vec<_>>();
+    if terms.is_empty() {
+        return Err(AppError::InvalidOption(
+            "--fields needs at least one field name".to_string(),
+        ));
     }
+    Ok(Some(PipeStage::Columns(terms)))
 }
 
 fn validate_output_options(tokens: &CommandTokenizer) -> Result<(), AppError> {
@@ -498,14 +760,21 @@ pub fn want_help(tokens: &CommandTokenizer) -> bool {
 mod tests {
     use std::any::TypeId;
 
+    use hubuum_client::FilterOperator;
+    use hubuum_filter::{PipeStage, ProjectTerm};
     use serial_test::serial;
 
+    use serde_json::json;
+    use tempfile::tempdir;
+
     use super::{
-        normalize_server_page_size, option_or_pos, required_option_or_pos,
-        validate_unknown_options, CliOption, CommandArgs,
+        command_options, exclusive_option_or_pos, fields_projection, json_option_or_file,
+        normalize_server_page_size, option_or_pos, required_option_or_pos, select_targets_with,
+        validate_positionals, validate_unknown_options, wildcard_clause, CliOption, CommandArgs,
     };
     use crate::errors::AppError;
-    use crate::output::{reset_output, take_output};
+    use crate::forms::Form;
+    use crate::output::{reset_output, take_output, RenderFormat};
     use crate::tokenizer::CommandTokenizer;
 
     #[derive(Default)]
@@ -522,6 +791,8 @@ mod tests {
                 nargs: None,
                 repeatable: false,
                 value_source: false,
+                raw: false,
+                positional: None,
                 help: "Limit".to_string(),
                 field_type: TypeId::of::<usize>(),
                 field_type_help: "usize".to_string(),
@@ -601,4 +872,175 @@ mod tests {
 
         assert!(err.to_string().contains("id has invalid value 'nope'"));
     }
+
+    #[test]
+    fn unused_positionals_are_rejected_with_usage() {
+        let tokens = CommandTokenizer::new("object list Hosts", "list", &[]).expect("tokenization");
+        let err = validate_positionals::<DummyArgs>(&tokens)
+            .expect_err("a command without positionals should reject one");
+        assert_eq!(
+            err.to_string(),
+            "Error parsing arguments: Unexpected argument 'Hosts'. Usage: object list [OPTIONS]; see 'object list --help'"
+        );
+
+        let tokens =
+            CommandTokenizer::new("class delete Hosts", "delete", &[]).expect("tokenization");
+        validate_positionals::<super::class::ClassDelete>(&tokens)
+            .expect("the declared positional should be accepted");
+
+        let tokens = CommandTokenizer::new("class delete Hosts Devices x", "delete", &[])
+            .expect("tokenization");
+        let err = super::class::ClassDelete::parse_tokens(&tokens)
+            .expect_err("extra positionals should fail parsing");
+        assert!(err
+            .to_string()
+            .contains("Unexpected arguments 'Devices', 'x'. Usage: class delete [NAME] [OPTIONS]"));
+    }
+
+    #[derive(Default)]
+    struct SecondPositionalArgs;
+
+    impl CommandArgs for SecondPositionalArgs {
+        fn options() -> Vec<CliOption> {
+            vec![CliOption {
+                name: "name".to_string(),
+                positional: Some(1),
+                ..DummyArgs::options().remove(0)
+            }]
+        }
+
+        fn parse_tokens(_tokens: &CommandTokenizer) -> Result<Self, AppError> {
+            Ok(Self)
+        }
+    }
+
+    #[test]
+    fn positionals_at_undeclared_indices_are_rejected_with_the_full_command_path() {
+        let tokens = CommandTokenizer::new("delete Hostz web1", "delete", &[])
+            .expect("tokenization")
+            .with_command_path(&["object".to_string(), "delete".to_string()]);
+        let err = validate_positionals::<SecondPositionalArgs>(&tokens)
+            .expect_err("the undeclared first positional should be rejected");
+        assert_eq!(
+            err.to_string(),
+            "Error parsing arguments: Unexpected argument 'Hostz'. Usage: object delete [NAME] [OPTIONS]; see 'object delete --help'"
+        );
+    }
+
+    #[test]
+    fn object_delete_refuses_a_class_given_twice() {
+        let tokens = CommandTokenizer::new(
+            "object delete Hostz web1 -c Hosts",
+            "delete",
+            &command_options::<super::object::ObjectDelete>(),
+        )
+        .expect("tokenization");
+        let query = super::object::ObjectDelete::parse_tokens(&tokens).expect("options parse");
+        let err = exclusive_option_or_pos(query.class, &tokens, 0, "class")
+            .expect_err("a class given twice should be refused");
+        assert!(err.to_string().contains("the argument 'Hostz'"));
+    }
+
+    #[test]
+    fn wildcard_clause_picks_the_matching_operator() {
+        for (pattern, operator, value) in [
+            (
+                "*@example.com",
+                FilterOperator::IEndsWith { is_negated: false },
+                "@example.com",
+            ),
+            (
+                "ops-*",
+                FilterOperator::IStartsWith { is_negated: false },
+                "ops-",
+            ),
+            (
+                "*ops*",
+                FilterOperator::IContains { is_negated: false },
+                "ops",
+            ),
+            (
+                "alice",
+                FilterOperator::IContains { is_negated: false },
+                "alice",
+            ),
+            (
+                "a*@*.org",
+                FilterOperator::Like { is_negated: false },
+                "a%@%.org",
+            ),
+        ] {
+            let clause = wildcard_clause("email", pattern);
+            assert_eq!(clause.operator, operator, "{pattern}");
+            assert_eq!(clause.value, value, "{pattern}");
+        }
+    }
+
+    #[test]
+    fn selected_targets_keep_their_order_and_skipped_ones_are_returned() {
+        let mut form = Form::new(std::io::Cursor::new(b"2\n\n".to_vec()), Vec::new());
+        let (picked, skipped) =
+            select_targets_with(&mut form, vec![10, 20, 30], |item| item.to_string())
+                .expect("selection should succeed");
+        assert_eq!(picked, vec![10, 30]);
+        assert_eq!(skipped, vec![20]);
+    }
+
+    #[test]
+    fn fields_project_json_output_only() {
+        let options = super::standard_options();
+        let tokens = CommandTokenizer::new(
+            "object list --json --fields id,name,!data.secret",
+            "list",
+            &options,
+        )
+        .expect("tokenization should succeed");
+        assert_eq!(
+            fields_projection(&tokens, RenderFormat::Json).unwrap(),
+            Some(PipeStage::Columns(vec![
+                ProjectTerm::keep("id"),
+                ProjectTerm::keep("name"),
+                ProjectTerm::drop("data.secret"),
+            ]))
+        );
+        assert!(matches!(
+            fields_projection(&tokens, RenderFormat::Text),
+            Err(AppError::InvalidOption(_))
+        ));
+
+        let tokens = CommandTokenizer::new("object list --json", "list", &options)
+            .expect("tokenization should succeed");
+        assert!(fields_projection(&tokens, RenderFormat::Json)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn json_documents_come_inline_or_from_a_file_but_not_both() {
+        let directory = tempdir().expect("temporary directory should be created");
+        let path = directory.path().join("data.json");
+        std::fs::write(&path, "{\"rack\": 4}\n").expect("data file should be written");
+        let path = path.display().to_string();
+
+        assert_eq!(
+            json_option_or_file(None, Some(path.clone()), "data").unwrap(),
+            Some(json!({ "rack": 4 }))
+        );
+        assert_eq!(
+            json_option_or_file(Some(json!(1)), None, "data").unwrap(),
+            Some(json!(1))
+        );
+        assert!(json_option_or_file(None, None, "data").unwrap().is_none());
+        assert!(matches!(
+            json_option_or_file(Some(json!(1)), Some(path), "data"),
+            Err(AppError::InvalidOption(_))
+        ));
+
+        let broken = directory.path().join("broken.json");
+        std::fs::write(&broken, "{").expect("broken file should be written");
+        assert!(matches!(
+            json_option_or_file(None, Some(broken.display().to_string()), "schema"),
+            Err(AppError::ParseError(_))
+        ));
+    }
 }
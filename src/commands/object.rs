@@ -1,11 +1,16 @@
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
-use std::fs::read_to_string;
+use std::fs::{read_to_string, File};
+use std::io::{BufRead, Write};
 use std::iter::once;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, SecondsFormat, Utc};
 use cli_command_derive::CommandArgs;
 use hubuum_client::ObjectDataPatchDocument;
 use jqesque::Jqesque;
 use jsonpath_rust::JsonPath;
+use regex::Regex;
 use smooth_json::Flattener;
 
 use serde::{Deserialize, Serialize};
@@ -14,39 +19,55 @@ use serde_json::{from_str, json, to_string_pretty, to_value, Map, Value};
 use hubuum_filter::{scalar_text, select_values, OutputEnvelope};
 
 use super::builder::{catalog_command, CommandDocs};
+use super::bulk::{
+    checkpoint_path, finish_checkpoint, render_resume_hint, write_retry_file, DEFAULT_RETRY_FILE,
+};
 use super::{
-    build_list_query, contains_clause, desired_format, equals_clause, normalize_server_page_size,
-    option_or_pos, want_json, CliCommand,
+    build_list_query, contains_clause, desired_format, equals_clause, exclusive_option_or_pos,
+    json_option_or_file, normalize_server_page_size, option_or_pos, render_bulk_summary,
+    required_option, required_option_or_pos, want_json, CliCommand,
 };
+use crate::audit_log::parse_age_option;
 use crate::autocomplete::{
-    classes, collections, computed_fields, object_data_columns, object_sort, object_where,
-    objects_from_class,
+    classes, collections, computed_fields, file_paths, object_data_columns, object_sort,
+    object_where, objects_from_class, since_ages,
 };
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::config::get_config;
 use crate::domain::{
-    visit_observed_data_fields, ComputedFieldSelector, ComputedFieldSet, ObjectShowRecord,
-    ResolvedObjectRecord, DEFAULT_OBJECT_FIELD_DEPTH, DEFAULT_OBJECT_FIELD_SAMPLE_LIMIT,
+    parse_object_import, visit_observed_data_fields, BulkSummary, ComputedFieldSelector,
+    ComputedFieldSet, ObjectDataMatchRecord, ObjectShowRecord, ObjectValidationRecord, PlanAction,
+    PlanKind, PlanOperation, ResolvedObjectRecord, RetryFile, RetryItem, StaleObjectRecord,
+    DEFAULT_OBJECT_FIELD_DEPTH, DEFAULT_OBJECT_FIELD_SAMPLE_LIMIT,
 };
 use crate::errors::AppError;
 use crate::formatting::{
-    append_json_message, data_preview, render_related_object_tree_with_key, OutputFormatter,
+    append_json, append_json_message, data_preview, render_related_object_tree_with_key,
+    OutputFormatter,
 };
-use crate::list_query::{append_paging_footer, render_paged_result, PagedResult};
+use crate::forms::Form;
+use crate::interrupt::InterruptWatch;
+use crate::json_schema::schema_violations;
+use crate::list_query::{append_paging_footer, render_paged_result, ListQuery, PagedResult};
+use crate::masking::mask_for_display;
 use crate::models::{ObjectListDataColumns, OutputFormat};
 use crate::output::{
-    add_warning, append_key_value, append_line, has_pipeline, set_semantic_output,
+    add_warning, append_key_value, append_line, has_pipeline, is_redirected, join_delimited,
+    print_rendered, set_semantic_output,
 };
+use crate::safety::{confirm_protected_collection, confirm_write_ahead, WriteAheadSummary};
 use crate::services::{
-    AppServices, CreateObjectInput, ObjectDataPatchInput, ObjectUpdateInput,
+    AppServices, Checkpoint, CreateObjectInput, ObjectDataPatchInput, ObjectUpdateInput,
     RelationTraversalOptions,
 };
-use crate::terminal::terminal_width;
+use crate::terminal::{display_width, terminal_width};
 
 const AUTO_OBJECT_DATA_COLUMN_LIMIT: usize = 4;
+const DEFAULT_WATCH_INTERVAL_SECS: u64 = 10;
 const AUTO_OBJECT_DATA_TARGET_WIDTH: usize = 100;
 const AUTO_OBJECT_DATA_MAX_COLUMN_WIDTH: usize = 24;
 use crate::tokenizer::CommandTokenizer;
+use crate::verify::{ensure_found, ensure_visible};
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
     builder
@@ -58,12 +79,33 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 CommandDocs {
                     about: Some("Create an object"),
                     long_about: Some(
-                        "Create a new object in a specific class with the specified properties.",
+                        "Create a new object in a specific class with the specified properties.\n\nWith --interactive, any missing fields are prompted for. The collection defaults to the class's collection, data is prompted property by property from the class JSON schema when it has one, and the final payload is shown for confirmation before anything is created.",
                     ),
                     examples: Some(
                         r#"-n MyObject -c MyClaass -N collection_1 -d "My object description"
---name MyObject --class MyClass --collection collection_1 --description 'My object' --data '{"key": "val"}'"#,
+--name MyObject --class MyClass --collection collection_1 --description 'My object' --data '{"key": "val"}'
+--class MyClass --interactive
+-n MyObject -c MyClass -N collection_1 --data-raw {"note": "it's \"quoted\""}
+-n MyObject -c MyClass -N collection_1 -d "Large object" --data-file ./object.json"#,
+                    ),
+                    mutating: true,
+                },
+            ),
+        )
+        .add_command(
+            &["object"],
+            catalog_command(
+                "import",
+                ObjectImport::default(),
+                CommandDocs {
+                    about: Some("Create objects in bulk from a CSV or JSON file"),
+                    long_about: Some(
+                        "Create one object in --class per row of a CSV file or item of a JSON array, in --collection or else the class's collection. CSV columns name and description fill the object and every other column a data key; dots nest keys and a leading data. is dropped, so files written by object data export import unchanged. Cells are text unless the class schema gives their field a non-string type, such as integer or array, in which case they are read as JSON; empty cells are left out. JSON items have a name, an optional description and data, and any other keys are merged into the data. The whole file is checked before anything is sent, every object is then attempted, and a summary lists the failures. Failed objects are saved to --retry-file (default failed.json) for bulk retry. Every object created is recorded in <file>.checkpoint, so an import that stopped partway can be run again with --resume to skip them; the checkpoint is removed once every object has been created.",
+                    ),
+                    examples: Some(
+                        "--class Host --file hosts.csv\n--class Host --file hosts.json --collection infra --output json\n--class Host --file hosts.csv --resume",
                     ),
+                    mutating: true,
                 },
             ),
         )
@@ -81,6 +123,24 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                         r#"--class Hosts --name srv-01 --patch '[{"op":"add","path":"/facts","value":{"os":"Fedora"}}]'
 --class Hosts --name srv-01 --patch @facts-patch.json --create --description "Managed by Ansible""#,
                     ),
+                    mutating: true,
+                },
+            ),
+        )
+        .add_command(
+            &["object", "data"],
+            catalog_command(
+                "export",
+                ObjectDataExport::default(),
+                CommandDocs {
+                    about: Some("Export selected object fields and data paths as CSV"),
+                    long_about: Some(
+                        "Fetch every object in a class and write one CSV row per object. --paths lists the columns: object fields (id, name, description, collection, class, created_at, updated_at) or data paths such as data.os.name, with [*] and [N] for arrays. Strings are written as-is, other JSON values compactly, and multiple matches are joined with commas. An existing file is only replaced with --force.",
+                    ),
+                    examples: Some(
+                        "--class Host --file hosts.csv --paths name,data.os.name,data.ip\n--class Host --file hosts.csv --paths name,data.network.interfaces[*].ipv4 --force",
+                    ),
+                    ..CommandDocs::default()
                 },
             ),
         )
@@ -91,6 +151,10 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ObjectList::default(),
                 CommandDocs {
                     about: Some("List objects"),
+                    long_about: Some(
+                        "List objects in one class, or with --all-classes in every class (optionally only those in --collection). Combined listings keep the Class column, and filters, sorting, and --limit apply to each class separately.",
+                    ),
+                    examples: Some("--class Host\n--all-classes --collection prod --where name icontains web"),
                     ..CommandDocs::default()
                 },
             ),
@@ -106,6 +170,68 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                         "Sample objects in a class and list observed data paths, value types, counts, and examples. This is useful for classes without schemas.",
                     ),
                     examples: Some("--class Hosts --limit 100"),
+                    ..CommandDocs::default()
+                },
+            ),
+        )
+        .add_command(
+            &["object"],
+            catalog_command(
+                "stale",
+                ObjectStale::default(),
+                CommandDocs {
+                    about: Some("List objects that have not been updated for a while"),
+                    long_about: Some(
+                        "Fetch every object in a class and list those last updated before --older-than, an age such as 90d or 12w or an RFC 3339 timestamp. Objects are grouped by collection, oldest first, to drive data-quality cleanups.",
+                    ),
+                    examples: Some("--class Host --older-than 90d\nHost --older-than 2w --collection infra"),
+                    ..CommandDocs::default()
+                },
+            ),
+        )
+        .add_command(
+            &["object"],
+            catalog_command(
+                "grep",
+                ObjectGrep::default(),
+                CommandDocs {
+                    about: Some("Search object data in a class with a regular expression"),
+                    long_about: Some(
+                        "Fetch every object in a class, flatten its data into key paths, and list each value matching --pattern with the object name and path, e.g. to find where an IP address is referenced. Values hidden by output.mask_keys are not searched unless --reveal is given.",
+                    ),
+                    examples: Some("--class Host --pattern '10\\.1\\.\\d+\\.\\d+'\nHost --pattern '^web' --collection infra"),
+                    ..CommandDocs::default()
+                },
+            ),
+        )
+        .add_command(
+            &["object"],
+            catalog_command(
+                "watch-field",
+                ObjectWatchField::default(),
+                CommandDocs {
+                    about: Some("Print a line whenever a data field of an object changes"),
+                    long_about: Some(
+                        "Poll an object every --interval seconds (default 10) and print a timestamped line with the value at --path, first when the watch starts and then each time it changes. Lines are printed as they happen, as JSON lines with --output json. The watch stops after --changes changes or --timeout seconds, or on Ctrl-C, and cannot be piped or redirected.",
+                    ),
+                    examples: Some("--class Host --name web1 --path status.state --interval 10
+--class Host --name web1 --path status.state --changes 1 --timeout 600"),
+                    ..CommandDocs::default()
+                },
+            ),
+        )
+        .add_command(
+            &["object"],
+            catalog_command(
+                "validate",
+                ObjectValidate::default(),
+                CommandDocs {
+                    about: Some("Validate an object against its class schema"),
+                    long_about: Some(
+                        "Fetch an object and its class JSON schema and validate the object's data locally. Every violation is listed with its JSON pointer, so objects can be checked before schema validation is enforced on the class.",
+                    ),
+                    examples: Some("--class Hosts --name srv-01\nHosts srv-01"),
+                    ..CommandDocs::default()
                 },
             ),
         )
@@ -116,6 +242,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ObjectDelete::default(),
                 CommandDocs {
                     about: Some("Delete an object"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -128,12 +255,14 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 CommandDocs {
                     about: Some("Modify an object"),
                     long_about: Some(
-                        "Modify an object in a specific class with the specified properties.",
+                        "Modify an object in a specific class with the specified properties.\n\n--data applies a single assignment such as foo.bar=4 to the current data; --data-file replaces the data with the JSON document in a file.",
                     ),
                     examples: Some(
                         r#"-n MyObject -c MyClaass -N collection_1 -d "My object description"
---name MyObject --class MyClass --collection collection_1 --description 'My object' --data foo.bar=4"#,
+--name MyObject --class MyClass --collection collection_1 --description 'My object' --data foo.bar=4
+-n MyObject -c MyClass --data-file ./object.json"#,
                     ),
+                    mutating: true,
                 },
             ),
         )
@@ -144,6 +273,10 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ObjectInfo::default(),
                 CommandDocs {
                     about: Some("Show object details"),
+                    long_about: Some(
+                        "Show an object by class and name, or by --id alone. When only an ID is given, every visible class is searched for the owning class.",
+                    ),
+                    examples: Some("--class Host --name web01\n--id 1234"),
                     ..CommandDocs::default()
                 },
             ),
@@ -153,42 +286,88 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct ObjectNew {
     #[option(short = "n", long = "name", help = "Name of the object")]
-    pub name: String,
+    pub name: Option<String>,
     #[option(
         short = "c",
         long = "class",
         help = "Name of the class the object belongs to",
         autocomplete = "classes"
     )]
-    pub class: String,
+    pub class: Option<String>,
     #[option(
         short = "N",
         long = "collection",
         help = "Collection name",
         autocomplete = "collections"
     )]
-    pub collection: String,
+    pub collection: Option<String>,
     #[option(short = "d", long = "description", help = "Description of the class")]
-    pub description: String,
+    pub description: Option<String>,
     #[option(
         short = "D",
         long = "data",
         help = "JSON data for the object the class",
-        value_source = true
+        value_source = true,
+        raw = true
     )]
     pub data: Option<Value>,
+    #[option(
+        long = "data-file",
+        help = "Read the JSON data for the object from a file",
+        autocomplete = "file_paths"
+    )]
+    pub data_file: Option<String>,
+    #[option(
+        short = "I",
+        long = "interactive",
+        help = "Prompt for missing fields and for data from the class schema",
+        flag = "true"
+    )]
+    pub interactive: bool,
+    #[option(
+        long = "verify",
+        help = "Re-fetch the object afterwards and fail if the change is not visible",
+        flag = "true"
+    )]
+    pub verify: bool,
 }
 
 impl CliCommand for ObjectNew {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
-        let new = Self::parse_tokens(tokens)?;
-        let object = services.gateway().create_object(CreateObjectInput {
-            name: new.name,
-            class_name: new.class,
-            collection: new.collection,
-            description: new.description,
-            data: new.data,
-        })?;
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let mut new = Self::parse_tokens(tokens)?;
+        new.data = json_option_or_file(new.data.take(), new.data_file.take(), "data")?;
+        let verify = new.verify;
+        let input = if new.interactive {
+            let mut form = Form::terminal(
+                "--interactive",
+                "pass --name, --class, --collection, and --data instead",
+            )?;
+            match new.prompt_input(ctx.services(), &mut form)? {
+                Some(input) => input,
+                None => {
+                    append_line("Object creation cancelled; nothing was changed.")?;
+                    return Ok(());
+                }
+            }
+        } else {
+            new.into_input()?
+        };
+        let expected = verify.then(|| {
+            let mut expected = json!({
+                "name": input.name,
+                "class": input.class_name,
+                "collection": input.collection,
+                "description": input.description,
+            });
+            if let Some(data) = &input.data {
+                expected["data"] = data.clone();
+            }
+            expected
+        });
+        let object = ctx.services().gateway().create_object(input)?;
+        if let Some(expected) = expected {
+            verify_object(ctx, &expected)?;
+        }
 
         match desired_format(tokens) {
             OutputFormat::Json => object.format_json_noreturn()?,
@@ -199,6 +378,99 @@ impl CliCommand for ObjectNew {
     }
 }
 
+impl ObjectNew {
+    fn into_input(self) -> Result<CreateObjectInput, AppError> {
+        let missing = [
+            ("name", self.name.is_none()),
+            ("class", self.class.is_none()),
+            ("collection", self.collection.is_none()),
+            ("description", self.description.is_none()),
+        ]
+        .into_iter()
+        .filter(|(_, missing)| *missing)
+        .map(|(name, _)| name.to_string())
+        .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(AppError::MissingOptions(missing));
+        }
+
+        Ok(CreateObjectInput {
+            name: self.name.unwrap_or_default(),
+            class_name: self.class.unwrap_or_default(),
+            collection: self.collection.unwrap_or_default(),
+            description: self.description.unwrap_or_default(),
+            data: self.data,
+        })
+    }
+
+    /// Fills in whatever the flags left out, then asks before returning the payload.
+    ///
+    /// The collection defaults to the class's collection, and data is prompted
+    /// property by property when the class has a JSON schema. Returns `None` when
+    /// the final confirmation is declined.
+    fn prompt_input<R: BufRead, W: Write>(
+        self,
+        services: &AppServices,
+        form: &mut Form<R, W>,
+    ) -> Result<Option<CreateObjectInput>, AppError> {
+        let gateway = services.gateway();
+        let class_name = match self.class {
+            Some(class) => class,
+            None => form.text("Class", None)?,
+        };
+        let name = match self.name {
+            Some(name) => name,
+            None => form.text("Name", None)?,
+        };
+        let collection = match self.collection {
+            Some(collection) => collection,
+            None => {
+                let default = gateway.class_collection_name(&class_name)?;
+                form.text("Collection", Some(&default))?
+            }
+        };
+        let description = match self.description {
+            Some(description) => description,
+            None => form.text("Description", Some(""))?,
+        };
+        let schema = gateway.class_schema(&class_name)?;
+        let data = match (self.data, &schema) {
+            (Some(data), _) => Some(data),
+            (None, Some(schema)) => Some(form.schema_object(schema)?),
+            (None, None) => form.json("Data")?,
+        };
+        if let (Some(schema), Some(data)) = (&schema, &data) {
+            let violations = schema_violations(schema, data)?;
+            if !violations.is_empty() {
+                return Err(AppError::CommandExecutionError(format!(
+                    "Data does not match the schema of class '{class_name}': {}",
+                    violations
+                        .iter()
+                        .map(|violation| violation.message.clone())
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                )));
+            }
+        }
+
+        let input = CreateObjectInput {
+            name,
+            class_name,
+            collection,
+            description,
+            data,
+        };
+        form.show(&to_string_pretty(&json!({
+            "name": input.name,
+            "class": input.class_name,
+            "collection": input.collection,
+            "description": input.description,
+            "data": input.data,
+        }))?)?;
+        Ok(form.confirm("Create this object?")?.then_some(input))
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct ObjectDataPatch {
     #[option(
@@ -219,7 +491,8 @@ pub struct ObjectDataPatch {
         short = "p",
         long = "patch",
         help = "RFC 6902 JSON Patch document; use @FILE, file://FILE, or inline JSON",
-        value_source = true
+        value_source = true,
+        raw = true
     )]
     pub patch: String,
     #[option(
@@ -237,7 +510,7 @@ pub struct ObjectDataPatch {
 }
 
 impl CliCommand for ObjectDataPatch {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         if query.description.is_some() && !query.create {
             return Err(AppError::ParseError(
@@ -250,7 +523,7 @@ impl CliCommand for ObjectDataPatch {
         if query.create {
             input = input.create_if_missing(query.description.unwrap_or_default());
         }
-        let result = services.gateway().patch_object_data(input)?;
+        let result = ctx.services().gateway().patch_object_data(input)?;
 
         match desired_format(tokens) {
             OutputFormat::Json => result.format_json_noreturn()?,
@@ -261,6 +534,298 @@ impl CliCommand for ObjectDataPatch {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct ObjectImport {
+    #[option(
+        short = "c",
+        long = "class",
+        help = "Class to create the objects in",
+        autocomplete = "classes"
+    )]
+    pub class: String,
+    #[option(
+        short = "f",
+        long = "file",
+        help = "CSV, TSV, or JSON file with one object per row or item",
+        autocomplete = "file_paths"
+    )]
+    pub file: String,
+    #[option(
+        short = "N",
+        long = "collection",
+        help = "Collection for the objects (default: the class's collection)",
+        autocomplete = "collections"
+    )]
+    pub collection: Option<String>,
+    #[option(
+        long = "retry-file",
+        help = "Where to save objects that could not be created (default: failed.json)",
+        autocomplete = "file_paths"
+    )]
+    pub retry_file: Option<String>,
+    #[option(
+        long = "resume",
+        help = "Skip the objects an interrupted import of the same file created",
+        flag = true
+    )]
+    pub resume: bool,
+}
+
+impl CliCommand for ObjectImport {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let gateway = ctx.services().gateway();
+        let path = Path::new(&query.file);
+        let content = read_to_string(path)?;
+        let collection = match query.collection {
+            Some(collection) => collection,
+            None => gateway.class_collection_name(&query.class)?,
+        };
+        let schema = gateway.class_schema(&query.class)?;
+        let objects =
+            parse_object_import(path, &content, &query.class, &collection, schema.as_ref())?;
+        let checkpoint = Checkpoint::open(checkpoint_path(&query.file), query.resume)?;
+        let pending = objects
+            .iter()
+            .filter(|object| !checkpoint.is_completed(&object.key()))
+            .count();
+        let mut write_ahead = WriteAheadSummary::new(&["Create"]);
+        write_ahead.add("object", vec![pending]);
+        confirm_write_ahead(
+            &ctx.config(),
+            desired_format(tokens),
+            "object import",
+            &write_ahead,
+        )?;
+
+        let mut summary = BulkSummary::default();
+        let mut retry = RetryFile::default();
+        let results = gateway.create_objects(&objects, &checkpoint);
+        for (object, result) in objects.iter().zip(results) {
+            match result {
+                None => summary.resumed += 1,
+                Some(Ok(())) => summary.record(PlanAction::Create),
+                Some(Err(error)) => {
+                    let item = RetryItem {
+                        action: PlanAction::Create,
+                        kind: PlanKind::Object,
+                        name: object.key(),
+                        operation: PlanOperation::CreateObject(object.clone()),
+                        error: error.to_string(),
+                    };
+                    summary.record_failure(item.label(), &error);
+                    retry.items.push(item);
+                }
+            }
+        }
+        if !retry.items.is_empty() {
+            let retry_file = query.retry_file.as_deref().unwrap_or(DEFAULT_RETRY_FILE);
+            match write_retry_file(Path::new(retry_file), &retry) {
+                Ok(()) => summary.retry_file = Some(retry_file.to_string()),
+                Err(error) => add_warning(format!("Could not write {retry_file}: {error}"))?,
+            }
+        }
+        finish_checkpoint(checkpoint, &mut summary)?;
+
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json(&summary)?,
+            OutputFormat::Text => {
+                render_bulk_summary(&summary)?;
+                append_line(format!(
+                    "Imported {} of {} object(s) into class '{}'.",
+                    summary.created + summary.resumed,
+                    objects.len(),
+                    query.class
+                ))?;
+                if summary.resumed > 0 {
+                    append_line(format!(
+                        "Skipped {} object(s) created by the interrupted import.",
+                        summary.resumed
+                    ))?;
+                }
+                if let Some(retry_file) = &summary.retry_file {
+                    append_line(format!(
+                        "Fix the cause and run `bulk retry --file {retry_file}` to create the failed objects."
+                    ))?;
+                }
+                render_resume_hint(&summary)?;
+            }
+        }
+        if summary.has_failures() {
+            return Err(AppError::Quiet);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct ObjectDataExport {
+    #[option(
+        short = "c",
+        long = "class",
+        help = "Class whose objects are exported",
+        autocomplete = "classes"
+    )]
+    pub class: String,
+    #[option(
+        short = "f",
+        long = "file",
+        help = "Destination CSV file",
+        autocomplete = "file_paths"
+    )]
+    pub file: String,
+    #[option(
+        short = "p",
+        long = "paths",
+        help = "Comma-separated columns: object fields such as name, or data paths such as data.os.name"
+    )]
+    pub paths: String,
+    #[option(
+        long = "force",
+        help = "Replace an existing destination file",
+        flag = "true"
+    )]
+    pub force: bool,
+}
+
+impl CliCommand for ObjectDataExport {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let columns = query
+            .paths
+            .split(',')
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(DataExportColumn::parse)
+            .collect::<Vec<_>>();
+        if columns.is_empty() {
+            return Err(AppError::MissingOptions(vec!["paths".to_string()]));
+        }
+        if Path::new(&query.file).exists() && !query.force {
+            return Err(AppError::InvalidOption(format!(
+                "Destination '{}' already exists; use --force to replace it",
+                query.file
+            )));
+        }
+
+        let mut objects = ctx
+            .services()
+            .gateway()
+            .all_objects_in_class(&query.class)?;
+        for data in objects.iter_mut().filter_map(|object| object.data.as_mut()) {
+            mask_for_display(data);
+        }
+        let csv = data_export_csv(&columns, &objects)?;
+        let mut file = if query.force {
+            File::create(&query.file)?
+        } else {
+            File::create_new(&query.file)?
+        };
+        file.write_all(csv.as_bytes())?;
+
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json(&json!({
+                "class": query.class,
+                "file": query.file,
+                "objects": objects.len(),
+                "columns": columns.iter().map(|column| column.header()).collect::<Vec<_>>(),
+            }))?,
+            OutputFormat::Text => append_line(format!(
+                "Exported {} object(s) from class '{}' to {}",
+                objects.len(),
+                query.class,
+                query.file
+            ))?,
+        }
+        Ok(())
+    }
+}
+
+/// One CSV column of `object data export`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DataExportColumn {
+    /// A top-level object field such as `name` or `collection`.
+    Field(String),
+    /// A path into the object's data. Bare paths that are not object fields land here too.
+    Data(String),
+}
+
+impl DataExportColumn {
+    const FIELDS: [&'static str; 7] = [
+        "id",
+        "name",
+        "description",
+        "collection",
+        "class",
+        "created_at",
+        "updated_at",
+    ];
+
+    fn parse(path: &str) -> Self {
+        if Self::FIELDS.contains(&path) {
+            Self::Field(path.to_string())
+        } else {
+            Self::Data(path.strip_prefix("data.").unwrap_or(path).to_string())
+        }
+    }
+
+    fn header(&self) -> String {
+        match self {
+            Self::Field(field) => field.clone(),
+            Self::Data(path) => format!("data.{path}"),
+        }
+    }
+
+    /// The cell text: strings as-is, other JSON values compact, and several matches comma-joined.
+    fn cell(&self, object: &ResolvedObjectRecord) -> String {
+        let values = match self {
+            Self::Field(field) => match field.as_str() {
+                "id" => vec![json!(object.id)],
+                "name" => vec![json!(object.name)],
+                "description" => vec![json!(object.description)],
+                "collection" => vec![json!(object.collection)],
+                "class" => vec![json!(object.class)],
+                "created_at" => vec![json!(object.created_at)],
+                _ => vec![json!(object.updated_at)],
+            },
+            Self::Data(path) => object
+                .data
+                .as_ref()
+                .map(|data| select_values(data, path).into_iter().cloned().collect())
+                .unwrap_or_default(),
+        };
+        values
+            .iter()
+            .map(|value| match value {
+                Value::String(text) => text.clone(),
+                Value::Null => String::new(),
+                other => other.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+fn data_export_csv(
+    columns: &[DataExportColumn],
+    objects: &[ResolvedObjectRecord],
+) -> Result<String, AppError> {
+    let headers = columns
+        .iter()
+        .map(DataExportColumn::header)
+        .collect::<Vec<_>>();
+    once(join_delimited(headers.iter().map(String::as_str), b','))
+        .chain(objects.iter().map(|object| {
+            let cells = columns
+                .iter()
+                .map(|column| column.cell(object))
+                .collect::<Vec<_>>();
+            join_delimited(cells.iter().map(String::as_str), b',')
+        }))
+        .map(|line| line.map(|line| format!("{line}\n")))
+        .collect()
+}
+
 fn parse_object_data_patch(source: &str) -> Result<ObjectDataPatchDocument, AppError> {
     let payload = if let Some(path) = source.strip_prefix('@') {
         if path.is_empty() {
@@ -281,7 +846,8 @@ pub struct ObjectInfo {
         short = "n",
         long = "name",
         help = "Name of the object",
-        autocomplete = "objects_from_class"
+        autocomplete = "objects_from_class",
+        positional = 0
     )]
     pub name: Option<String>,
     #[option(
@@ -290,7 +856,13 @@ pub struct ObjectInfo {
         help = "Class of the object",
         autocomplete = "classes"
     )]
-    pub class: String,
+    pub class: Option<String>,
+    #[option(
+        short = "i",
+        long = "id",
+        help = "ID of the object; the owning class is discovered when --class is omitted"
+    )]
+    pub id: Option<i32>,
     #[option(
         short = "d",
         long = "data",
@@ -325,20 +897,31 @@ pub struct ObjectInfo {
 }
 
 impl CliCommand for ObjectInfo {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
-        query.name = option_or_pos(query.name, tokens, 0, "name")?;
-
-        let object_name = query
-            .name
-            .as_ref()
-            .ok_or_else(|| AppError::MissingOptions(vec!["name".to_string()]))?;
+        let (class_name, object_name) = match query.id {
+            Some(id) => {
+                let location = ctx
+                    .services()
+                    .gateway()
+                    .locate_object_by_id(id, query.class.as_deref())?;
+                (location.class_name, location.object_name)
+            }
+            None => {
+                query.name = option_or_pos(query.name, tokens, 0, "name")?;
+                match (query.class.clone(), query.name.clone()) {
+                    (Some(class), Some(name)) => (class, name),
+                    (None, _) => return Err(AppError::MissingOptions(vec!["class".to_string()])),
+                    (_, None) => return Err(AppError::MissingOptions(vec!["name".to_string()])),
+                }
+            }
+        };
         let computed_selection =
-            ComputedFieldSelection::resolve(&query.computed, Some(&query.class))?;
-        let config = get_config();
-        let object = services.gateway().object_show_details(
-            &query.class,
-            object_name,
+            ComputedFieldSelection::resolve(&query.computed, Some(&class_name))?;
+        let config = ctx.config();
+        let mut object = ctx.services().gateway().object_show_details(
+            &class_name,
+            &object_name,
             &RelationTraversalOptions {
                 include_self_class: query
                     .include_self_class
@@ -347,6 +930,9 @@ impl CliCommand for ObjectInfo {
             },
             computed_selection.requests_values(),
         )?;
+        if let Some(data) = object.object.data.as_mut() {
+            mask_for_display(data);
+        }
 
         if has_pipeline()? {
             let (value, columns) = object_show_pipeline_value(&object, &computed_selection)?;
@@ -413,7 +999,7 @@ fn render_object_data(json_data: Option<&Value>, jsonpath: Option<&str>) -> Resu
 
         let padding = key_values
             .keys()
-            .map(|k| k.len())
+            .map(|k| display_width(k))
             .max()
             .map_or(14, |len| len.max(14));
 
@@ -433,7 +1019,7 @@ fn render_object_data(json_data: Option<&Value>, jsonpath: Option<&str>) -> Resu
         let sorted_map: BTreeMap<_, _> = map.into_iter().collect();
         let padding = sorted_map
             .keys()
-            .map(|k| k.len())
+            .map(|k| display_width(k))
             .max()
             .map_or(15, |len| len.max(15));
 
@@ -464,18 +1050,21 @@ mod tests {
     use std::collections::HashMap;
     use std::fs::write;
 
+    use chrono::{DateTime, Utc};
     use hubuum_client::ObjectDataPatchOperation;
     use hubuum_filter::{apply_pipeline, OutputEnvelope, PipeStage, ProjectTerm, SortCast};
+    use regex::Regex;
     use serde_json::{json, Value};
     use serial_test::serial;
     use tempfile::tempdir;
 
     use super::{
         all_computed_value_columns, bounded_auto_data_columns, data_column_display_value,
-        data_column_value, display_json_value, explicit_data_columns, first_seen_data_keys,
-        object_data_column_label, object_field_summaries, object_list_row,
-        object_show_pipeline_value, parse_object_data_patch, ComputedFieldSelection,
-        ComputedValueColumn, ComputedValueScope, ObjectList, ObjectListColumns,
+        data_column_value, data_export_csv, data_matches, display_json_value,
+        explicit_data_columns, first_seen_data_keys, object_data_column_label,
+        object_field_summaries, object_list_row, object_show_pipeline_value,
+        parse_object_data_patch, stale_objects, watch_line, watched_value, ComputedFieldSelection,
+        ComputedValueColumn, ComputedValueScope, DataExportColumn, ObjectList, ObjectListColumns,
         DEFAULT_OBJECT_FIELD_DEPTH,
     };
     use super::{render_object_data, render_object_show_text, should_render_object_data};
@@ -485,9 +1074,48 @@ mod tests {
         ComputedFieldSet, ObjectShowRecord, RelatedObjectTreeNode, ResolvedObjectRecord,
     };
     use crate::list_query::PagedResult;
+    use crate::models::OutputFormat;
     use crate::output::{append_line, reset_output, take_output};
     use crate::tokenizer::CommandTokenizer;
 
+    #[test]
+    fn watched_values_and_lines() {
+        let data = json!({"status": {"state": "running"}, "dns": ["a", "b"]});
+        assert_eq!(watched_value(Some(&data), "status.state"), json!("running"));
+        assert_eq!(watched_value(Some(&data), "data.dns[*]"), json!(["a", "b"]));
+        assert_eq!(watched_value(Some(&data), "status.missing"), Value::Null);
+        assert_eq!(watched_value(None, "status.state"), Value::Null);
+
+        let at = DateTime::parse_from_rfc3339("2026-03-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            watch_line(
+                &OutputFormat::Text,
+                at,
+                "status.state",
+                None,
+                &json!("pending")
+            ),
+            "2026-03-10T12:00:00Z status.state = \"pending\"\n"
+        );
+        assert_eq!(
+            watch_line(
+                &OutputFormat::Text,
+                at,
+                "status.state",
+                Some(&json!("pending")),
+                &json!("running")
+            ),
+            "2026-03-10T12:00:00Z status.state: \"pending\" -> \"running\"\n"
+        );
+        let line = watch_line(&OutputFormat::Json, at, "status.state", None, &json!(1));
+        assert_eq!(
+            serde_json::from_str::<Value>(&line).unwrap(),
+            json!({"timestamp": "2026-03-10T12:00:00Z", "path": "status.state", "previous": null, "value": 1})
+        );
+    }
+
     #[test]
     fn display_json_value_unquotes_strings() {
         assert_eq!(display_json_value(&json!("Entry")), "Entry");
@@ -605,6 +1233,22 @@ mod tests {
         assert_eq!(query.computed, vec!["S:load", "P:note"]);
     }
 
+    #[test]
+    fn object_list_parses_all_classes_in_a_collection() {
+        let tokens = CommandTokenizer::new(
+            "object list --all-classes --collection prod",
+            "list",
+            &command_options::<ObjectList>(),
+        )
+        .expect("command should tokenize");
+
+        let query = ObjectList::parse_tokens(&tokens).expect("command should parse");
+
+        assert_eq!(query.all_classes, Some(true));
+        assert_eq!(query.collection.as_deref(), Some("prod"));
+        assert_eq!(query.class, None);
+    }
+
     #[test]
     fn computed_field_selection_projects_only_requested_scope_values() {
         let selection =
@@ -847,6 +1491,120 @@ mod tests {
         assert!(columns.contains(&"P:label".to_string()));
     }
 
+    #[test]
+    fn data_export_flattens_fields_and_data_paths_into_csv() {
+        let object = ResolvedObjectRecord {
+            id: 7,
+            name: "web, 01".to_string(),
+            description: String::new(),
+            collection: "prod".to_string(),
+            class: "Host".to_string(),
+            data: Some(json!({
+                "os": {"name": "Fedora"},
+                "ip": ["10.0.0.1", "10.0.0.2"],
+                "cpus": 4
+            })),
+            computed: None,
+            created_at: "2024-01-01 00:00:00".to_string(),
+            updated_at: "2024-01-01 00:00:00".to_string(),
+        };
+        let columns =
+            ["name", "data.os.name", "ip[*]", "cpus", "data.missing"].map(DataExportColumn::parse);
+
+        assert_eq!(
+            data_export_csv(&columns, &[object]).expect("CSV should be written"),
+            "name,data.os.name,data.ip[*],data.cpus,data.missing\n\"web, 01\",Fedora,\"10.0.0.1,10.0.0.2\",4,\n"
+        );
+    }
+
+    #[test]
+    fn stale_objects_are_grouped_by_collection_oldest_first() {
+        let object = |id: i32, collection: &str, updated_at: &str| ResolvedObjectRecord {
+            id,
+            name: format!("host-{id}"),
+            description: String::new(),
+            collection: collection.to_string(),
+            class: "Host".to_string(),
+            data: None,
+            computed: None,
+            created_at: updated_at.to_string(),
+            updated_at: updated_at.to_string(),
+        };
+        let objects = [
+            object(1, "prod", "2026-01-01T00:00:00+00:00"),
+            object(2, "dev", "2026-02-01T00:00:00+00:00"),
+            object(3, "prod", "2025-06-01T00:00:00+00:00"),
+            object(4, "prod", "2026-03-30T00:00:00+00:00"),
+            object(5, "prod", "not a timestamp"),
+        ];
+        let now = "2026-04-01T00:00:00Z".parse().unwrap();
+        let cutoff = "2026-03-01T00:00:00Z".parse().unwrap();
+
+        let stale = stale_objects(&objects, None, cutoff, now);
+        let ids = stale.iter().map(|object| object.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![2, 3, 1]);
+        assert_eq!(stale[0].age_days, 59);
+
+        let prod = stale_objects(&objects, Some("prod"), cutoff, now);
+        assert_eq!(prod.len(), 2);
+    }
+
+    #[test]
+    fn data_matches_report_object_and_flattened_path() {
+        let object = |id: i32, name: &str, collection: &str, data: Value| ResolvedObjectRecord {
+            id,
+            name: name.to_string(),
+            description: String::new(),
+            collection: collection.to_string(),
+            class: "Host".to_string(),
+            data: Some(data),
+            computed: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        let objects = [
+            object(
+                1,
+                "web02",
+                "prod",
+                json!({"ip": "10.1.2.3", "dns": ["10.1.0.1", "8.8.8.8"]}),
+            ),
+            object(
+                2,
+                "web01",
+                "prod",
+                json!({"network": {"gateway": "10.1.0.1"}}),
+            ),
+            object(3, "db01", "dev", json!({"ip": "10.2.0.1", "port": 5432})),
+        ];
+        let pattern = Regex::new(r"10\.1\.\d+\.\d+").unwrap();
+
+        let matches = data_matches(&objects, None, &pattern);
+        let found = matches
+            .iter()
+            .map(|found| {
+                (
+                    found.name.as_str(),
+                    found.path.as_str(),
+                    found.value.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            vec![
+                ("web01", "network.gateway", "10.1.0.1"),
+                ("web02", "dns.0", "10.1.0.1"),
+                ("web02", "ip", "10.1.2.3"),
+            ]
+        );
+
+        let ports = data_matches(&objects, Some("dev"), &Regex::new("^5432$").unwrap());
+        assert_eq!(ports.len(), 1);
+        assert_eq!(ports[0].path, "port");
+        assert!(data_matches(&objects, Some("prod"), &Regex::new("5432").unwrap()).is_empty());
+    }
+
     #[test]
     fn data_column_value_accepts_raw_and_data_prefixed_paths() {
         let data = json!({"name": "host", "hardware": {"cpu": "M2"}});
@@ -1110,21 +1868,24 @@ pub struct ObjectDelete {
         short = "n",
         long = "name",
         help = "Name of the object",
-        autocomplete = "objects_from_class"
+        autocomplete = "objects_from_class",
+        positional = 1
     )]
     pub name: Option<String>,
     #[option(
         short = "c",
         long = "class",
         help = "Class of the object",
-        autocomplete = "classes"
+        autocomplete = "classes",
+        positional = 0
     )]
     pub class: Option<String>,
 }
 
 impl CliCommand for ObjectDelete {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
+        query.class = exclusive_option_or_pos(query.class, tokens, 0, "class")?;
         query.name = option_or_pos(query.name, tokens, 1, "name")?;
 
         let class_name = query
@@ -1135,7 +1896,21 @@ impl CliCommand for ObjectDelete {
             .name
             .as_ref()
             .ok_or_else(|| AppError::MissingOptions(vec!["name".to_string()]))?;
-        services.gateway().delete_object(class_name, object_name)?;
+        let safety = &ctx.config().safety;
+        if safety.has_protected_collections() {
+            let collection = ctx
+                .services()
+                .gateway()
+                .object_collection_name(class_name, object_name)?;
+            confirm_protected_collection(
+                safety,
+                &format!("object delete {class_name} {object_name}"),
+                &collection,
+            )?;
+        }
+        ctx.services()
+            .gateway()
+            .delete_object(class_name, object_name)?;
 
         let message = format!(
             "Object '{}' in class '{}' deleted successfully",
@@ -1167,6 +1942,19 @@ pub struct ObjectList {
         autocomplete = "classes"
     )]
     pub class: Option<String>,
+    #[option(
+        long = "all-classes",
+        help = "List objects from every class instead of one",
+        flag = "true"
+    )]
+    pub all_classes: Option<bool>,
+    #[option(
+        short = "N",
+        long = "collection",
+        help = "With --all-classes, only classes in this collection",
+        autocomplete = "collections"
+    )]
+    pub collection: Option<String>,
     #[option(
         short = "n",
         long = "name",
@@ -1216,8 +2004,26 @@ pub struct ObjectList {
 }
 
 impl CliCommand for ObjectList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query: ObjectList = Self::parse_tokens(tokens)?;
+        let all_classes = query.all_classes.unwrap_or(false);
+        if all_classes && query.class.is_some() {
+            return Err(AppError::ParseError(
+                "--all-classes cannot be combined with --class".to_string(),
+            ));
+        }
+        if all_classes && query.cursor.is_some() {
+            return Err(AppError::ParseError(
+                "--cursor cannot be combined with --all-classes because each class is paged separately"
+                    .to_string(),
+            ));
+        }
+        if query.collection.is_some() && !all_classes {
+            return Err(AppError::ParseError(
+                "--collection requires --all-classes; use --where collection = <name> to filter one class"
+                    .to_string(),
+            ));
+        }
         let computed_selection =
             ComputedFieldSelection::resolve(&query.computed, query.class.as_deref())?;
         let class_filter = query.class.clone();
@@ -1242,11 +2048,27 @@ impl CliCommand for ObjectList {
                 .sorts
                 .iter()
                 .any(|sort| sort.field.starts_with("S:") || sort.field.starts_with("P:"));
-        let objects = services
-            .gateway()
-            .list_objects(&list_query, include_computed)?;
+        let mut objects = if all_classes {
+            list_objects_in_all_classes(
+                ctx.services(),
+                query.collection.as_deref(),
+                &list_query,
+                include_computed,
+            )?
+        } else {
+            ctx.services()
+                .gateway()
+                .list_objects(&list_query, include_computed)?
+        };
+        for data in objects
+            .items
+            .iter_mut()
+            .filter_map(|item| item.data.as_mut())
+        {
+            mask_for_display(data);
+        }
         render_object_list_page(
-            services,
+            ctx.services(),
             tokens,
             &objects,
             class_filter.as_deref(),
@@ -1256,6 +2078,336 @@ impl CliCommand for ObjectList {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct ObjectStale {
+    #[option(
+        short = "c",
+        long = "class",
+        help = "Class whose objects are checked",
+        autocomplete = "classes",
+        positional = 0
+    )]
+    pub class: Option<String>,
+    #[option(
+        long = "older-than",
+        help = "Age (90d, 12w) or timestamp objects must be last updated before",
+        autocomplete = "since_ages"
+    )]
+    pub older_than: String,
+    #[option(
+        short = "N",
+        long = "collection",
+        help = "Only list objects in this collection",
+        autocomplete = "collections"
+    )]
+    pub collection: Option<String>,
+}
+
+impl CliCommand for ObjectStale {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let class = required_option_or_pos(query.class, tokens, 0, "class")?;
+        let now = Utc::now();
+        let cutoff = parse_age_option("--older-than", &query.older_than, now)?;
+        let objects = ctx.services().gateway().all_objects_in_class(&class)?;
+        let stale = stale_objects(&objects, query.collection.as_deref(), cutoff, now);
+
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json(&stale)?,
+            OutputFormat::Text if stale.is_empty() => append_line(format!(
+                "No objects in class '{class}' were last updated before {}",
+                cutoff.to_rfc3339()
+            ))?,
+            OutputFormat::Text => {
+                stale.format_noreturn()?;
+                let collections = stale
+                    .iter()
+                    .map(|object| object.collection.as_str())
+                    .collect::<BTreeSet<_>>();
+                append_line(format!(
+                    "{} of {} object(s) stale across {} collection(s)",
+                    stale.len(),
+                    objects.len(),
+                    collections.len()
+                ))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Objects last updated before `cutoff`, grouped by collection and oldest first.
+///
+/// Objects whose update time cannot be read are left out.
+fn stale_objects(
+    objects: &[ResolvedObjectRecord],
+    collection: Option<&str>,
+    cutoff: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Vec<StaleObjectRecord> {
+    let mut stale = objects
+        .iter()
+        .filter(|object| collection.is_none_or(|name| object.collection == name))
+        .filter_map(|object| {
+            let updated_at = DateTime::parse_from_rfc3339(&object.updated_at)
+                .ok()?
+                .with_timezone(&Utc);
+            (updated_at < cutoff).then(|| StaleObjectRecord {
+                collection: object.collection.clone(),
+                id: object.id,
+                name: object.name.clone(),
+                updated_at: object.updated_at.clone(),
+                age_days: (now - updated_at).num_days(),
+            })
+        })
+        .collect::<Vec<_>>();
+    stale.sort_by(|left, right| {
+        left.collection
+            .cmp(&right.collection)
+            .then(right.age_days.cmp(&left.age_days))
+            .then(left.name.cmp(&right.name))
+    });
+    stale
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct ObjectGrep {
+    #[option(
+        short = "c",
+        long = "class",
+        help = "Class whose objects are searched",
+        autocomplete = "classes",
+        positional = 0
+    )]
+    pub class: Option<String>,
+    #[option(
+        short = "p",
+        long = "pattern",
+        help = "Regular expression matched against each data value"
+    )]
+    pub pattern: String,
+    #[option(
+        short = "N",
+        long = "collection",
+        help = "Only search objects in this collection",
+        autocomplete = "collections"
+    )]
+    pub collection: Option<String>,
+}
+
+impl CliCommand for ObjectGrep {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let class = required_option_or_pos(query.class, tokens, 0, "class")?;
+        let pattern = Regex::new(&query.pattern)?;
+        let mut objects = ctx.services().gateway().all_objects_in_class(&class)?;
+        for data in objects.iter_mut().filter_map(|object| object.data.as_mut()) {
+            mask_for_display(data);
+        }
+        let matches = data_matches(&objects, query.collection.as_deref(), &pattern);
+
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json(&matches)?,
+            OutputFormat::Text if matches.is_empty() => append_line(format!(
+                "No data values in class '{class}' match '{}'",
+                query.pattern
+            ))?,
+            OutputFormat::Text => {
+                matches.format_noreturn()?;
+                let matched_objects = matches
+                    .iter()
+                    .map(|found| found.id)
+                    .collect::<BTreeSet<_>>();
+                append_line(format!(
+                    "{} match(es) in {} of {} object(s)",
+                    matches.len(),
+                    matched_objects.len(),
+                    objects.len()
+                ))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Flattened data values matching `pattern`, ordered by collection, object name, and path.
+///
+/// Array items get their index in the path (`dns.0`). Strings are matched without their
+/// JSON quotes; other scalars by their JSON text.
+fn data_matches(
+    objects: &[ResolvedObjectRecord],
+    collection: Option<&str>,
+    pattern: &Regex,
+) -> Vec<ObjectDataMatchRecord> {
+    let flattener = Flattener {
+        preserve_arrays: true,
+        ..Default::default()
+    };
+    let mut matches = Vec::new();
+    for object in objects
+        .iter()
+        .filter(|object| collection.is_none_or(|name| object.collection == name))
+    {
+        let Some(data) = &object.data else {
+            continue;
+        };
+        let Value::Object(flattened) = flattener.flatten(data) else {
+            continue;
+        };
+        for (path, value) in flattened {
+            let value = display_json_value(&value);
+            if pattern.is_match(&value) {
+                matches.push(ObjectDataMatchRecord {
+                    collection: object.collection.clone(),
+                    id: object.id,
+                    name: object.name.clone(),
+                    path,
+                    value,
+                });
+            }
+        }
+    }
+    matches.sort_by(|left, right| {
+        left.collection
+            .cmp(&right.collection)
+            .then(left.name.cmp(&right.name))
+            .then(left.path.cmp(&right.path))
+    });
+    matches
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct ObjectWatchField {
+    #[option(
+        short = "c",
+        long = "class",
+        help = "Class of the object",
+        autocomplete = "classes"
+    )]
+    pub class: String,
+    #[option(
+        short = "n",
+        long = "name",
+        help = "Name of the object",
+        autocomplete = "objects_from_class"
+    )]
+    pub name: String,
+    #[option(
+        short = "p",
+        long = "path",
+        help = "Data path to watch, such as status.state",
+        autocomplete = "object_data_columns"
+    )]
+    pub path: String,
+    #[option(
+        short = "i",
+        long = "interval",
+        help = "Seconds between polls (default: 10)"
+    )]
+    pub interval: Option<u64>,
+    #[option(long = "changes", help = "Stop after this many changes")]
+    pub changes: Option<usize>,
+    #[option(long = "timeout", help = "Stop after this many seconds")]
+    pub timeout: Option<u64>,
+}
+
+impl CliCommand for ObjectWatchField {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        // Lines are printed as they happen, which a pipeline or redirect would only see at the end.
+        if has_pipeline()? || is_redirected()? {
+            return Err(AppError::InvalidOption(
+                "object watch-field prints as it polls and cannot be piped or redirected"
+                    .to_string(),
+            ));
+        }
+        let format = desired_format(tokens);
+        let interval =
+            Duration::from_secs(query.interval.unwrap_or(DEFAULT_WATCH_INTERVAL_SECS).max(1));
+        let deadline = query
+            .timeout
+            .map(|timeout| Instant::now() + Duration::from_secs(timeout));
+
+        let watch = InterruptWatch::start();
+        let mut previous = None;
+        let mut changes = 0;
+        loop {
+            let mut object = ctx
+                .services()
+                .gateway()
+                .object_details(&query.class, &query.name)?;
+            if let Some(data) = object.data.as_mut() {
+                mask_for_display(data);
+            }
+            let value = watched_value(object.data.as_ref(), &query.path);
+            if previous.as_ref() != Some(&value) {
+                // Lines are printed as they happen rather than buffered until the watch ends.
+                print_rendered(&watch_line(
+                    &format,
+                    Utc::now(),
+                    &query.path,
+                    previous.as_ref(),
+                    &value,
+                ))?;
+                if previous.is_some() {
+                    changes += 1;
+                }
+                previous = Some(value);
+            }
+            if query.changes.is_some_and(|limit| changes >= limit)
+                || deadline.is_some_and(|deadline| Instant::now() + interval > deadline)
+            {
+                break;
+            }
+            if watch.sleep(interval) {
+                break;
+            }
+        }
+
+        if matches!(format, OutputFormat::Text) {
+            append_line(format!("Stopped watching after {changes} change(s)."))?;
+        }
+        Ok(())
+    }
+}
+
+/// The value at `path` in object data; `null` when it is missing, an array when it matches several.
+fn watched_value(data: Option<&Value>, path: &str) -> Value {
+    let path = path.strip_prefix("data.").unwrap_or(path);
+    let mut values = data
+        .map(|data| select_values(data, path))
+        .unwrap_or_default()
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    match values.len() {
+        0 => Value::Null,
+        1 => values.remove(0),
+        _ => Value::Array(values),
+    }
+}
+
+/// One watch line: the starting value when there is no `previous` one, otherwise the change.
+fn watch_line(
+    format: &OutputFormat,
+    at: DateTime<Utc>,
+    path: &str,
+    previous: Option<&Value>,
+    value: &Value,
+) -> String {
+    let timestamp = at.to_rfc3339_opts(SecondsFormat::Secs, true);
+    match (format, previous) {
+        (OutputFormat::Json, _) => format!(
+            "{}\n",
+            json!({ "timestamp": timestamp, "path": path, "previous": previous, "value": value })
+        ),
+        (OutputFormat::Text, None) => format!("{timestamp} {path} = {value}\n"),
+        (OutputFormat::Text, Some(previous)) => {
+            format!("{timestamp} {path}: {previous} -> {value}\n")
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct ObjectFields {
     #[option(
@@ -1284,7 +2436,7 @@ pub struct ObjectFields {
 }
 
 impl CliCommand for ObjectFields {
-    fn execute(&self, services: &AppServices, _tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, _tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(_tokens)?;
         let sample_limit =
             normalize_server_page_size(query.limit)?.unwrap_or(DEFAULT_OBJECT_FIELD_SAMPLE_LIMIT);
@@ -1296,7 +2448,7 @@ impl CliCommand for ObjectFields {
             false,
             [equals_clause("class", query.class.clone())],
         )?;
-        let objects = services.gateway().list_objects(&list_query, false)?;
+        let objects = ctx.services().gateway().list_objects(&list_query, false)?;
         render_object_fields(
             &objects,
             sample_limit,
@@ -1306,6 +2458,76 @@ impl CliCommand for ObjectFields {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct ObjectValidate {
+    #[option(
+        short = "n",
+        long = "name",
+        help = "Name of the object",
+        autocomplete = "objects_from_class",
+        positional = 1
+    )]
+    pub name: Option<String>,
+    #[option(
+        short = "c",
+        long = "class",
+        help = "Class of the object",
+        autocomplete = "classes",
+        positional = 0
+    )]
+    pub class: Option<String>,
+}
+
+impl CliCommand for ObjectValidate {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let class_name = required_option(
+            exclusive_option_or_pos(query.class, tokens, 0, "class")?,
+            "class",
+        )?;
+        let object_name = required_option_or_pos(query.name, tokens, 1, "name")?;
+
+        let Some(schema) = ctx.services().gateway().class_schema(&class_name)? else {
+            let message = format!(
+                "Class '{class_name}' has no JSON schema; there is nothing to validate against"
+            );
+            return match desired_format(tokens) {
+                OutputFormat::Json => append_json_message(&message),
+                OutputFormat::Text => append_line(message),
+            };
+        };
+        let object = ctx
+            .services()
+            .gateway()
+            .object_details(&class_name, &object_name)?;
+        let violations = schema_violations(&schema, object.data.as_ref().unwrap_or(&Value::Null))?;
+        let record = ObjectValidationRecord {
+            class: class_name,
+            object: object_name,
+            valid: violations.is_empty(),
+            violations,
+        };
+
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json(&record)?,
+            OutputFormat::Text if record.valid => append_line(format!(
+                "Object '{}' in class '{}' is valid against the class schema",
+                record.object, record.class
+            ))?,
+            OutputFormat::Text => {
+                record.violations.format_noreturn()?;
+                append_line(format!(
+                    "Object '{}' in class '{}' has {} schema violation(s)",
+                    record.object,
+                    record.class,
+                    record.violations.len()
+                ))?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Default)]
 struct FieldSummary {
     count: usize,
@@ -1387,6 +2609,53 @@ fn json_type_name(value: &Value) -> &'static str {
     }
 }
 
+/// Runs one object list query per class and combines the pages, in class name order.
+///
+/// Each class is paged on its own, so classes with more matches than `--limit` are
+/// reported as truncated rather than offering a cursor.
+fn list_objects_in_all_classes(
+    services: &AppServices,
+    collection: Option<&str>,
+    list_query: &ListQuery,
+    include_computed: bool,
+) -> Result<PagedResult<ResolvedObjectRecord>, AppError> {
+    let mut combined = PagedResult {
+        items: Vec::new(),
+        next_cursor: None,
+        returned_count: 0,
+        total_count: list_query.include_total.then_some(0),
+    };
+    let mut truncated = Vec::new();
+    let gateway = services.gateway();
+    let classes = gateway.class_names_in_collection(collection)?;
+    let pages = gateway.bulk_runner().run(&classes, |class| {
+        let mut class_query = list_query.clone();
+        class_query
+            .filters
+            .push(equals_clause("class", class.clone()));
+        gateway.list_objects(&class_query, include_computed)
+    });
+    for (class, page) in classes.into_iter().zip(pages) {
+        let page = page?;
+        if page.next_cursor.is_some() {
+            truncated.push(class);
+        }
+        combined.total_count = combined
+            .total_count
+            .zip(page.total_count)
+            .map(|(a, b)| a + b);
+        combined.returned_count += page.returned_count;
+        combined.items.extend(page.items);
+    }
+    if !truncated.is_empty() {
+        add_warning(format!(
+            "Results were limited per class; more objects exist in: {}",
+            truncated.join(", ")
+        ))?;
+    }
+    Ok(combined)
+}
+
 fn render_object_list_page(
     services: &AppServices,
     tokens: &CommandTokenizer,
@@ -1926,7 +3195,7 @@ fn render_computed_fields(
 ) -> Result<(), AppError> {
     let padding = columns
         .iter()
-        .map(|column| column.label().len())
+        .map(|column| display_width(&column.label()))
         .max()
         .unwrap_or(15)
         .max(15);
@@ -2062,15 +3331,36 @@ pub struct ObjectModify {
         short = "D",
         long = "data",
         help = "JSON data for the object",
-        value_source = true
+        value_source = true,
+        raw = true
     )]
     pub data: Option<String>,
+    #[option(
+        long = "data-file",
+        help = "Replace the object's data with the JSON document in a file",
+        autocomplete = "file_paths"
+    )]
+    pub data_file: Option<String>,
+    #[option(
+        long = "verify",
+        help = "Re-fetch the object afterwards and fail if the change is not visible",
+        flag = "true"
+    )]
+    pub verify: bool,
 }
 
 impl CliCommand for ObjectModify {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let new = Self::parse_tokens(tokens)?;
-        let object = services.gateway().object_details(&new.class, &new.name)?;
+        if new.data.is_some() && new.data_file.is_some() {
+            return Err(AppError::InvalidOption(
+                "Use either --data or --data-file, not both".to_string(),
+            ));
+        }
+        let object = ctx
+            .services()
+            .gateway()
+            .object_details(&new.class, &new.name)?;
 
         let data = if let Some(data) = &new.data {
             let jqesque = data.parse::<Jqesque>()?;
@@ -2081,9 +3371,22 @@ impl CliCommand for ObjectModify {
             jqesque.apply_to(&mut json_data)?;
             Some(json_data)
         } else {
-            None
+            json_option_or_file(None, new.data_file.clone(), "data")?
         };
-        let object = services.gateway().update_object(ObjectUpdateInput {
+        let mut expected = json!({
+            "name": new.rename.as_ref().unwrap_or(&new.name),
+            "class": new.reclass.as_ref().unwrap_or(&new.class),
+        });
+        for (field, value) in [
+            ("collection", new.collection.clone().map(Value::String)),
+            ("description", new.description.clone().map(Value::String)),
+            ("data", data.clone()),
+        ] {
+            if let Some(value) = value {
+                expected[field] = value;
+            }
+        }
+        let object = ctx.services().gateway().update_object(ObjectUpdateInput {
             name: new.name,
             class_name: new.class,
             rename: new.rename,
@@ -2092,6 +3395,9 @@ impl CliCommand for ObjectModify {
             description: new.description,
             data,
         })?;
+        if new.verify {
+            verify_object(ctx, &expected)?;
+        }
 
         match desired_format(tokens) {
             OutputFormat::Json => object.format_json_noreturn()?,
@@ -2101,3 +3407,15 @@ impl CliCommand for ObjectModify {
         Ok(())
     }
 }
+
+/// Re-fetches the object named in `expected` and checks that it shows every expected value.
+fn verify_object(ctx: &CommandContext, expected: &Value) -> Result<(), AppError> {
+    let class = expected["class"].as_str().unwrap_or_default();
+    let name = expected["name"].as_str().unwrap_or_default();
+    let entity = format!("object '{name}' in class '{class}'");
+    let object = ensure_found(
+        &entity,
+        ctx.services().gateway().object_details(class, name),
+    )?;
+    ensure_visible(&entity, expected, &to_value(object)?)
+}
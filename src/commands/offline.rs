@@ -0,0 +1,71 @@
+use cli_command_derive::CommandArgs;
+use serde::{Deserialize, Serialize};
+
+use super::builder::{catalog_command, CommandDocs};
+use super::{desired_format, CliCommand};
+use crate::autocomplete::on_off;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
+use crate::errors::AppError;
+use crate::formatting::append_json_message;
+use crate::models::OutputFormat;
+use crate::offline::is_offline;
+use crate::output::append_line;
+use crate::tokenizer::CommandTokenizer;
+
+pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
+    builder.add_command(
+        &[],
+        catalog_command(
+            "offline",
+            Offline::default(),
+            CommandDocs {
+                about: Some("Work without the server"),
+                long_about: Some(
+                    "Switch offline mode on or off; without an argument it toggles. While offline no request reaches the server: completion offers only names already seen, listings are answered from this session's cache however old they are, and commands that change the server are refused. Commands can still be queued in a transaction and committed once back online. Start with --offline to begin in offline mode.",
+                ),
+                examples: Some("on\noff\n--output json"),
+                ..CommandDocs::default()
+            },
+        ),
+    );
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct Offline {
+    #[option(
+        short = "s",
+        long = "state",
+        help = "on or off (default: toggle)",
+        positional = 0,
+        autocomplete = "on_off"
+    )]
+    pub state: Option<String>,
+}
+
+impl CliCommand for Offline {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let offline = match query.state.as_deref() {
+            None => !is_offline(),
+            Some("on") => true,
+            Some("off") => false,
+            Some(other) => {
+                return Err(AppError::ParseError(format!(
+                    "Expected 'on' or 'off', got '{other}'"
+                )))
+            }
+        };
+        ctx.services().gateway().set_offline(offline)?;
+        let message = if offline {
+            "Offline: listings come from this session's cache and changes are refused"
+        } else {
+            ctx.services().completion_store().prefetch();
+            "Online: requests go to the server again"
+        };
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json_message(message)?,
+            OutputFormat::Text => append_line(message)?,
+        }
+        Ok(())
+    }
+}
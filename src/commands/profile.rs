@@ -0,0 +1,168 @@
+use cli_command_derive::CommandArgs;
+use hubuum_client::ApiError;
+use serde::{Deserialize, Serialize};
+
+use super::builder::{catalog_command, own_credentials, CommandDocs};
+use super::version::probe_server;
+use super::{desired_format, required_option_or_pos, CliCommand};
+use crate::app::{stored_token_accepted, switch_login};
+use crate::autocomplete::profile_names;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
+use crate::config::{get_config, AppConfig};
+use crate::defaults::Defaults;
+use crate::domain::{ProfileCheckRecord, TokenCheck};
+use crate::errors::AppError;
+use crate::formatting::{append_json, append_json_message, OutputFormatter};
+use crate::models::OutputFormat;
+use crate::offline::require_online;
+use crate::output::append_line;
+use crate::tokenizer::CommandTokenizer;
+
+pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
+    builder.add_command(
+        &["profile"],
+        own_credentials(catalog_command(
+            "use",
+            ProfileUse::default(),
+            CommandDocs {
+                about: Some("Switch the session to another server profile"),
+                long_about: Some(
+                    "Log in to the server of a [profiles.<name>] config section and switch the running session to it. The profile's hostname, port, protocol, and username replace the current ones; settings it leaves out are kept. The current login stays active if authentication fails. Passwords and token files configured for the current login are not reused: a cached token for the new login is used when it is still valid, otherwise the password is prompted for.",
+                ),
+                examples: Some("profile use staging"),
+                ..CommandDocs::default()
+            },
+        )),
+    );
+    builder.add_command(
+        &["profile"],
+        catalog_command(
+            "doctor",
+            ProfileDoctor::default(),
+            CommandDocs {
+                about: Some("Check that every configured profile can connect"),
+                long_about: Some(
+                    "For each [profiles.<name>] section, call the server's health probe and read its version, then try the cached token of the profile's login. The table shows whether the server was reachable, whether its token is valid, rejected, or missing, and the server version; DNS, TLS, and connection failures are listed as the problem. No password is prompted for and the current login is left unchanged.",
+                ),
+                examples: Some("\n--output json"),
+                ..CommandDocs::default()
+            },
+        ),
+    );
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct ProfileUse {
+    #[option(
+        short = "n",
+        long = "name",
+        help = "Profile to switch to",
+        autocomplete = "profile_names",
+        positional = 0
+    )]
+    pub name: Option<String>,
+}
+
+impl CliCommand for ProfileUse {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let name = required_option_or_pos(query.name, tokens, 0, "name")?;
+        let mut config = AppConfig::clone(&get_config());
+        config.use_profile(&name)?;
+        config.server = config.server.without_credentials();
+        let message = format!(
+            "Switched to profile '{name}': logged in as '{}' on {}",
+            config.server.username,
+            config.server.server_url()
+        );
+        switch_login(ctx.services(), config)?;
+
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json_message(&message)?,
+            OutputFormat::Text => append_line(message)?,
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct ProfileDoctor {}
+
+impl CliCommand for ProfileDoctor {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        render_profile_doctor(tokens)
+    }
+}
+
+pub(crate) fn render_profile_doctor(tokens: &CommandTokenizer) -> Result<(), AppError> {
+    ProfileDoctor::parse_tokens(tokens)?;
+    require_online("checking profiles")?;
+    let config = get_config();
+    let names = config.profile_names();
+    if names.is_empty() {
+        return Err(AppError::GeneralConfigError(
+            "No profiles are configured. Add a [profiles.<name>] section to the config file."
+                .to_string(),
+        ));
+    }
+    let checks = names
+        .iter()
+        .map(|name| check_profile(&config, name))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match desired_format(tokens) {
+        OutputFormat::Json => append_json(&checks)?,
+        OutputFormat::Text => checks.format_noreturn()?,
+    }
+    Ok(())
+}
+
+/// Probes the server of profile `name` and tries its cached token, the way `profile use`
+/// would log in before it falls back to a password.
+fn check_profile(config: &AppConfig, name: &str) -> Result<ProfileCheckRecord, AppError> {
+    let mut config = config.clone();
+    config.use_profile(name)?;
+    let server = config.server.without_credentials();
+    let mut check = ProfileCheckRecord {
+        profile: name.to_string(),
+        server: server.server_url(),
+        username: server.username.clone(),
+        reachable: false,
+        token: TokenCheck::Unchecked,
+        server_version: None,
+        problem: None,
+    };
+
+    let info = match probe_server(&server) {
+        Ok(info) => info,
+        Err(error) => {
+            check.problem = Some(connection_problem(&error));
+            return Ok(check);
+        }
+    };
+    check.reachable = true;
+    check.server_version = Some(info.server_version);
+    if !info.compatible {
+        check.problem = Some(format!(
+            "The server does not publish the {} API this CLI uses",
+            Defaults::API_VERSION
+        ));
+    }
+    match stored_token_accepted(&server, &config.auth) {
+        Ok(Some(true)) => check.token = TokenCheck::Valid,
+        Ok(Some(false)) => check.token = TokenCheck::Rejected,
+        Ok(None) => check.token = TokenCheck::Missing,
+        Err(error) => check.problem = Some(connection_problem(&error)),
+    }
+    Ok(check)
+}
+
+/// The failure behind `error`, so DNS and TLS problems are named instead of hidden
+/// behind the client's retry summary.
+fn connection_problem(error: &AppError) -> String {
+    match error.api_error() {
+        Some(ApiError::RetryExhausted { last_error, .. }) => last_error.clone(),
+        _ => error.to_string(),
+    }
+}
@@ -1,29 +1,72 @@
+use std::fs::read_to_string;
+use std::path::Path;
+
 use cli_command_derive::CommandArgs;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, to_value};
 
+use super::apply::{execute_plan, render_plan, select_plan};
 use super::builder::{catalog_command, CommandDocs};
 use super::{build_list_query, desired_format, lte_clause, render_list_page, CliCommand};
 use crate::autocomplete::{
-    classes, objects_from_class_a, objects_from_class_b, objects_from_root_class,
-    relation_class_direct_sort, relation_class_direct_where, relation_class_graph_where,
-    relation_class_list_sort, relation_class_list_where, relation_object_direct_sort,
-    relation_object_direct_where, relation_object_graph_where, relation_object_sort,
-    relation_object_where,
+    classes, collections, file_paths, objects_from_class_a, objects_from_class_b,
+    objects_from_root_class, relation_class_direct_sort, relation_class_direct_where,
+    relation_class_graph_where, relation_class_list_sort, relation_class_list_where,
+    relation_object_direct_sort, relation_object_direct_where, relation_object_graph_where,
+    relation_object_sort, relation_object_where,
+};
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
+use crate::domain::{
+    ApplyPlan, DesiredState, ResolvedRelatedClassGraph, ResolvedRelatedObjectGraph,
 };
-use crate::catalog::CommandCatalogBuilder;
-use crate::domain::{ResolvedRelatedClassGraph, ResolvedRelatedObjectGraph};
 use crate::errors::AppError;
-use crate::formatting::{append_json, append_json_message, OutputFormatter};
+use crate::formatting::{
+    append_json, append_json_message, render_class_relation_matrix, OutputFormatter,
+};
 use crate::models::OutputFormat;
-use crate::output::append_line;
-use crate::services::{AppServices, RelatedObjectOptions, RelationRoot, RelationTarget};
+use crate::output::{add_warning, append_line};
+use crate::safety::confirm_protected_collections;
+use crate::services::{RelatedObjectOptions, RelationRoot, RelationTarget};
 use crate::tokenizer::CommandTokenizer;
+use crate::verify::{ensure_found, ensure_visible};
 
 const DEFAULT_RELATED_OBJECT_MAX_DEPTH: i32 = 2;
 const DEFAULT_RELATED_CLASS_MAX_DEPTH: i32 = 2;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
     builder
+        .add_command(
+            &["relation"],
+            catalog_command(
+                "matrix",
+                RelationMatrix::default(),
+                CommandDocs {
+                    about: Some("Show which classes are related"),
+                    long_about: Some(
+                        "Show a class × class matrix marking every class relation, for a one-screen overview of how the model is connected. With --counts, each cell shows the number of object relations instead.",
+                    ),
+                    examples: Some("\n--collection infra\n--counts"),
+                    ..CommandDocs::default()
+                },
+            ),
+        )
+        .add_command(
+            &["relation"],
+            catalog_command(
+                "apply-model",
+                RelationApplyModel::default(),
+                CommandDocs {
+                    about: Some("Create the class relations listed in a model file"),
+                    long_about: Some(
+                        "Read class pairs from the class_relations list of a YAML, TOML, or JSON file and compare them with the server. Without --yes the relations to create are shown and nothing is changed. Relations between modelled classes that the file does not list are reported, and deleted with --prune --yes, so the relationship model can be kept under version control.",
+                    ),
+                    examples: Some(
+                        "--file model.yaml\n--file model.yaml --yes\n--file model.yaml --prune --yes",
+                    ),
+                    mutating: true,
+                },
+            ),
+        )
         .add_command(
             &["relation", "class"],
             catalog_command(
@@ -46,8 +89,9 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 CommandDocs {
                     about: Some("Show a class relation"),
                     long_about: Some(
-                        "Show a direct class relation by id, or resolve it from an unordered class pair.",
+                        "Show a direct class relation resolved from an unordered class pair. With only one class, list every direct relation touching that class in either direction.",
                     ),
+                    examples: Some("--class-a Host --class-b Room\n--class-a Host"),
                     ..CommandDocs::default()
                 },
             ),
@@ -59,6 +103,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ClassRelationCreate::default(),
                 CommandDocs {
                     about: Some("Create a class relation"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -73,6 +118,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                     long_about: Some(
                         "Delete a class relation by id, or resolve it from an unordered class pair.",
                     ),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -140,7 +186,16 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ObjectRelationCreateV2::default(),
                 CommandDocs {
                     about: Some("Create an object relation"),
-                    ..CommandDocs::default()
+                    long_about: Some(
+                        "Create a relation between two objects, given by class and object names. \
+                         Scripts that already know the IDs can pass --class-relation-id, \
+                         --from-id, and --to-id instead, which skips the name lookups.",
+                    ),
+                    examples: Some(
+                        "--class-a Hosts --object-a web1 --class-b Rooms --object-b B701\n\
+                         --class-relation-id 7 --from-id 101 --to-id 202",
+                    ),
+                    mutating: true,
                 },
             ),
         )
@@ -151,6 +206,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ObjectRelationDeleteV2::default(),
                 CommandDocs {
                     about: Some("Delete an object relation"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -221,7 +277,7 @@ pub struct RelatedClassList {
 }
 
 impl CliCommand for RelatedClassList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let list_query = build_list_query(
             &query.where_clauses,
@@ -237,7 +293,8 @@ impl CliCommand for RelatedClassList {
                     .to_string(),
             )),
         )?;
-        let classes = services
+        let classes = ctx
+            .services()
             .gateway()
             .list_related_classes(&query.root_class, &list_query)?;
         render_list_page(tokens, &classes)
@@ -261,12 +318,31 @@ pub struct ClassRelationShow {
 }
 
 impl CliCommand for ClassRelationShow {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let relation = services.gateway().get_class_relation_by_pair(
-            required_option(query.class_a, "class-a")?.as_str(),
-            required_option(query.class_b, "class-b")?.as_str(),
-        )?;
+        let (class_a, class_b) = match (query.class_a, query.class_b) {
+            (Some(class_a), Some(class_b)) => (class_a, class_b),
+            (Some(class), None) | (None, Some(class)) => {
+                let relations = ctx.services().gateway().class_relations_touching(&class)?;
+                return match desired_format(tokens) {
+                    OutputFormat::Json => relations.format_json_noreturn(),
+                    OutputFormat::Text if relations.is_empty() => {
+                        append_line(format!("Class '{class}' has no direct relations."))
+                    }
+                    OutputFormat::Text => relations.format_noreturn(),
+                };
+            }
+            (None, None) => {
+                return Err(AppError::MissingOptions(vec![
+                    "class-a".to_string(),
+                    "class-b".to_string(),
+                ]))
+            }
+        };
+        let relation = ctx
+            .services()
+            .gateway()
+            .get_class_relation_by_pair(&class_a, &class_b)?;
 
         match desired_format(tokens) {
             OutputFormat::Json => relation.format_json_noreturn()?,
@@ -291,14 +367,31 @@ pub struct ClassRelationCreate {
         autocomplete = "classes"
     )]
     pub class_b: String,
+    #[option(
+        long = "verify",
+        help = "Re-fetch the relation afterwards and fail if it is not visible",
+        flag = "true"
+    )]
+    pub verify: bool,
 }
 
 impl CliCommand for ClassRelationCreate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let relation = services
+        let relation = ctx
+            .services()
             .gateway()
             .create_class_relation_v2(&query.class_a, &query.class_b)?;
+        if query.verify {
+            let entity = format!("class relation {} <-> {}", query.class_a, query.class_b);
+            let fetched = ensure_found(
+                &entity,
+                ctx.services()
+                    .gateway()
+                    .get_class_relation_by_pair(&query.class_a, &query.class_b),
+            )?;
+            ensure_visible(&entity, &json!({ "id": relation.id }), &to_value(fetched)?)?;
+        }
 
         match desired_format(tokens) {
             OutputFormat::Json => relation.format_json_noreturn()?,
@@ -326,11 +419,23 @@ pub struct ClassRelationDelete {
 }
 
 impl CliCommand for ClassRelationDelete {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let class_a = required_option(query.class_a, "class-a")?;
         let class_b = required_option(query.class_b, "class-b")?;
-        services
+        let safety = &ctx.config().safety;
+        if safety.has_protected_collections() {
+            let gateway = ctx.services().gateway();
+            confirm_protected_collections(
+                safety,
+                &format!("relation class delete {class_a} {class_b}"),
+                [
+                    gateway.class_collection_name(&class_a)?,
+                    gateway.class_collection_name(&class_b)?,
+                ],
+            )?;
+        }
+        ctx.services()
             .gateway()
             .delete_class_relation_by_pair(&class_a, &class_b)?;
         let message = format!("Deleted class relation between '{class_a}' and '{class_b}'");
@@ -375,7 +480,7 @@ pub struct RelatedClassRelationList {
 }
 
 impl CliCommand for RelatedClassRelationList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let list_query = build_list_query(
             &query.where_clauses,
@@ -385,13 +490,123 @@ impl CliCommand for RelatedClassRelationList {
             query.include_total.unwrap_or(false),
             [],
         )?;
-        let relations = services
+        let relations = ctx
+            .services()
             .gateway()
             .list_related_class_relations(&query.root_class, &list_query)?;
         render_list_page(tokens, &relations)
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct RelationMatrix {
+    #[option(
+        short = "N",
+        long = "collection",
+        help = "Only include classes in this collection",
+        autocomplete = "collections"
+    )]
+    pub collection: Option<String>,
+    #[option(
+        long = "counts",
+        help = "Show object relation counts instead of marks",
+        flag = "true"
+    )]
+    pub counts: bool,
+}
+
+impl CliCommand for RelationMatrix {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let matrix = ctx
+            .services()
+            .gateway()
+            .class_relation_matrix(query.collection.as_deref(), query.counts)?;
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json(&matrix)?,
+            OutputFormat::Text => render_class_relation_matrix(&matrix)?,
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct RelationApplyModel {
+    #[option(
+        short = "f",
+        long = "file",
+        help = "Relation model file (.yaml, .yml, .toml, or .json)",
+        autocomplete = "file_paths"
+    )]
+    pub file: String,
+    #[option(
+        long = "prune",
+        help = "Delete relations between modelled classes that are not in the file",
+        flag = "true"
+    )]
+    pub prune: bool,
+    #[option(
+        long = "yes",
+        help = "Apply the plan instead of only showing it",
+        flag = "true"
+    )]
+    pub yes: bool,
+    #[option(
+        long = "select",
+        help = "Pick the changes to keep from a checklist first",
+        flag = "true"
+    )]
+    pub select: bool,
+    #[option(
+        long = "retry-file",
+        help = "Where to save changes that could not be applied (default: failed.json)",
+        autocomplete = "file_paths"
+    )]
+    pub retry_file: Option<String>,
+}
+
+impl CliCommand for RelationApplyModel {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let path = Path::new(&query.file);
+        let model = DesiredState::parse_relation_model(path, &read_to_string(path)?)?;
+        let current = ctx.services().gateway().apply_current_state(&model)?;
+        let mut plan = ApplyPlan::compute_relation_model(&model, &current, query.prune)?;
+
+        if !query.prune {
+            let extra = ApplyPlan::extra_class_relations(&model, &current);
+            if !extra.is_empty() {
+                add_warning(format!(
+                    "{} class relation(s) between modelled classes are not in {}: {}. Use --prune to delete them.",
+                    extra.len(),
+                    query.file,
+                    extra
+                        .iter()
+                        .map(|relation| format!("{} <-> {}", relation.class_a, relation.class_b))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))?;
+            }
+        }
+
+        if query.select {
+            plan = select_plan(plan)?;
+        }
+        if !query.yes || plan.is_empty() {
+            return render_plan(tokens, &plan, None, None);
+        }
+        execute_plan(
+            ctx,
+            tokens,
+            "relation apply-model",
+            &plan,
+            None,
+            query.retry_file.as_deref(),
+            None,
+        )
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct RelatedClassGraphCommand {
     #[option(long = "root-class", help = "Root class", autocomplete = "classes")]
@@ -411,9 +626,9 @@ pub struct RelatedClassGraphCommand {
 }
 
 impl CliCommand for RelatedClassGraphCommand {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let graph = services.gateway().related_class_graph(
+        let graph = ctx.services().gateway().related_class_graph(
             &query.root_class,
             &build_list_query(
                 &query.where_clauses,
@@ -464,9 +679,9 @@ pub struct ObjectRelationShowV2 {
 }
 
 impl CliCommand for ObjectRelationShowV2 {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let relation = services.gateway().get_object_relation_v2(
+        let relation = ctx.services().gateway().get_object_relation_v2(
             &exact_object_target(query.class_a, query.object_a, query.class_b, query.object_b)?
                 .ok_or_else(|| {
                     AppError::MissingOptions(vec![
@@ -494,38 +709,125 @@ pub struct ObjectRelationCreateV2 {
         help = "First class endpoint",
         autocomplete = "classes"
     )]
-    pub class_a: String,
+    pub class_a: Option<String>,
     #[option(
         long = "object-a",
         help = "First object endpoint",
         autocomplete = "objects_from_class_a"
     )]
-    pub object_a: String,
+    pub object_a: Option<String>,
     #[option(
         long = "class-b",
         help = "Second class endpoint",
         autocomplete = "classes"
     )]
-    pub class_b: String,
+    pub class_b: Option<String>,
     #[option(
         long = "object-b",
         help = "Second object endpoint",
         autocomplete = "objects_from_class_b"
     )]
-    pub object_b: String,
+    pub object_b: Option<String>,
+    #[option(
+        long = "class-relation-id",
+        help = "ID of the class relation, instead of class and object names"
+    )]
+    pub class_relation_id: Option<i32>,
+    #[option(
+        long = "from-id",
+        help = "ID of the object on the class relation's from side"
+    )]
+    pub from_id: Option<i32>,
+    #[option(
+        long = "to-id",
+        help = "ID of the object on the class relation's to side"
+    )]
+    pub to_id: Option<i32>,
+    #[option(
+        long = "verify",
+        help = "Re-fetch the relation afterwards and fail if it is not visible",
+        flag = "true"
+    )]
+    pub verify: bool,
+}
+
+impl ObjectRelationCreateV2 {
+    /// The three IDs, when the relation is given by IDs rather than names.
+    fn ids(&self) -> Result<Option<(i32, i32, i32)>, AppError> {
+        let by_name = [&self.class_a, &self.object_a, &self.class_b, &self.object_b];
+        match (self.class_relation_id, self.from_id, self.to_id) {
+            (None, None, None) => Ok(None),
+            (Some(class_relation_id), Some(from_id), Some(to_id)) => {
+                if by_name.iter().any(|name| name.is_some()) {
+                    return Err(AppError::ParseError(
+                        "--class-relation-id, --from-id, and --to-id cannot be combined with \
+                         class or object names"
+                            .to_string(),
+                    ));
+                }
+                Ok(Some((class_relation_id, from_id, to_id)))
+            }
+            (class_relation_id, from_id, to_id) => Err(AppError::MissingOptions(
+                [
+                    ("class-relation-id", class_relation_id),
+                    ("from-id", from_id),
+                    ("to-id", to_id),
+                ]
+                .into_iter()
+                .filter(|(_, value)| value.is_none())
+                .map(|(name, _)| name.to_string())
+                .collect(),
+            )),
+        }
+    }
 }
 
 impl CliCommand for ObjectRelationCreateV2 {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let relation = services
-            .gateway()
-            .create_object_relation_v2(&RelationTarget {
-                class_a: query.class_a,
-                class_b: query.class_b,
-                object_a: Some(query.object_a),
-                object_b: Some(query.object_b),
-            })?;
+        let gateway = ctx.services().gateway();
+        let relation = match query.ids()? {
+            Some((class_relation_id, from_id, to_id)) => {
+                gateway.create_object_relation_by_ids(class_relation_id, from_id, to_id)?
+            }
+            None => {
+                let missing = [
+                    ("class-a", &query.class_a),
+                    ("object-a", &query.object_a),
+                    ("class-b", &query.class_b),
+                    ("object-b", &query.object_b),
+                ]
+                .into_iter()
+                .filter(|(_, value)| value.is_none())
+                .map(|(name, _)| name.to_string())
+                .collect::<Vec<_>>();
+                if !missing.is_empty() {
+                    return Err(AppError::MissingOptions(missing));
+                }
+                gateway.create_object_relation_v2(&RelationTarget {
+                    class_a: query.class_a.unwrap_or_default(),
+                    class_b: query.class_b.unwrap_or_default(),
+                    object_a: query.object_a,
+                    object_b: query.object_b,
+                })?
+            }
+        };
+        if query.verify {
+            let entity = format!(
+                "object relation {}/{} <-> {}/{}",
+                relation.class_a, relation.object_a, relation.class_b, relation.object_b
+            );
+            let fetched = ensure_found(
+                &entity,
+                gateway.get_object_relation_v2(&RelationTarget {
+                    class_a: relation.class_a.clone(),
+                    class_b: relation.class_b.clone(),
+                    object_a: Some(relation.object_a.clone()),
+                    object_b: Some(relation.object_b.clone()),
+                }),
+            )?;
+            ensure_visible(&entity, &json!({ "id": relation.id }), &to_value(fetched)?)?;
+        }
 
         match desired_format(tokens) {
             OutputFormat::Json => relation.format_json_noreturn()?,
@@ -565,7 +867,7 @@ pub struct ObjectRelationDeleteV2 {
 }
 
 impl CliCommand for ObjectRelationDeleteV2 {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let target =
             exact_object_target(query.class_a, query.object_a, query.class_b, query.object_b)?
@@ -577,7 +879,25 @@ impl CliCommand for ObjectRelationDeleteV2 {
                         "object-b".to_string(),
                     ])
                 })?;
-        services.gateway().delete_object_relation_v2(&target)?;
+        let safety = &ctx.config().safety;
+        if safety.has_protected_collections() {
+            confirm_protected_collections(
+                safety,
+                &format!(
+                    "relation object delete {}:{} {}:{}",
+                    target.class_a,
+                    target.object_a.as_deref().unwrap_or_default(),
+                    target.class_b,
+                    target.object_b.as_deref().unwrap_or_default()
+                ),
+                ctx.services()
+                    .gateway()
+                    .object_relation_collection_names(&target)?,
+            )?;
+        }
+        ctx.services()
+            .gateway()
+            .delete_object_relation_v2(&target)?;
         let message = format!(
             "Deleted object relation between '{}:{}' and '{}:{}'",
             target.class_a,
@@ -632,7 +952,7 @@ pub struct RelatedRelationList {
 }
 
 impl CliCommand for RelatedRelationList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let list_query = build_list_query(
             &query.where_clauses,
@@ -642,7 +962,7 @@ impl CliCommand for RelatedRelationList {
             query.include_total.unwrap_or(false),
             [],
         )?;
-        let relations = services.gateway().list_related_object_relations(
+        let relations = ctx.services().gateway().list_related_object_relations(
             &RelationRoot {
                 root_class: query.root_class,
                 root_object: query.root_object,
@@ -707,7 +1027,7 @@ pub struct RelatedObjectList {
 }
 
 impl CliCommand for RelatedObjectList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let list_query = build_list_query(
             &query.where_clauses,
@@ -723,7 +1043,7 @@ impl CliCommand for RelatedObjectList {
                     .to_string(),
             )),
         )?;
-        let objects = services.gateway().list_related_objects(
+        let objects = ctx.services().gateway().list_related_objects(
             &RelationRoot {
                 root_class: query.root_class,
                 root_object: query.root_object,
@@ -763,9 +1083,9 @@ pub struct RelatedObjectGraphCommand {
 }
 
 impl CliCommand for RelatedObjectGraphCommand {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let graph = services.gateway().related_object_graph(
+        let graph = ctx.services().gateway().related_object_graph(
             &RelationRoot {
                 root_class: query.root_class,
                 root_object: query.root_object,
@@ -852,3 +1172,35 @@ fn render_related_class_graph(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object_relation_ids_must_be_complete_and_not_mixed_with_names() {
+        let by_ids = ObjectRelationCreateV2 {
+            class_relation_id: Some(7),
+            from_id: Some(101),
+            to_id: Some(202),
+            ..Default::default()
+        };
+        assert_eq!(by_ids.ids().unwrap(), Some((7, 101, 202)));
+        assert_eq!(ObjectRelationCreateV2::default().ids().unwrap(), None);
+
+        let partial = ObjectRelationCreateV2 {
+            class_relation_id: Some(7),
+            ..Default::default()
+        };
+        assert!(matches!(
+            partial.ids(),
+            Err(AppError::MissingOptions(missing)) if missing == ["from-id", "to-id"]
+        ));
+
+        let mixed = ObjectRelationCreateV2 {
+            class_a: Some("Hosts".to_string()),
+            ..by_ids
+        };
+        assert!(mixed.ids().is_err());
+    }
+}
@@ -14,14 +14,14 @@ use crate::autocomplete::{
     remote_auth_types, remote_http_methods, remote_subject_kinds, remote_subject_types,
     remote_targets,
 };
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 
 use crate::errors::AppError;
 use crate::formatting::{append_json_message, OutputFormatter};
 use crate::models::OutputFormat;
 use crate::output::append_line;
 use crate::services::{
-    AppServices, CreateRemoteTargetInput, InvokeRemoteTargetInput, RemoteAuthConfigInput,
+    CreateRemoteTargetInput, InvokeRemoteTargetInput, RemoteAuthConfigInput,
     UpdateRemoteTargetInput,
 };
 use crate::tokenizer::CommandTokenizer;
@@ -35,6 +35,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 RemoteTargetCreate::default(),
                 CommandDocs {
                     about: Some("Create a remote target"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -68,6 +69,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 RemoteTargetUpdate::default(),
                 CommandDocs {
                     about: Some("Update a remote target"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -79,6 +81,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 RemoteTargetDelete::default(),
                 CommandDocs {
                     about: Some("Delete a remote target"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -91,6 +94,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 CommandDocs {
                     about: Some("Invoke a remote target"),
                     long_about: Some("Submit a remote target invocation as a background task. Use --wait to block until completion."),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -157,7 +161,7 @@ pub struct RemoteTargetCreate {
 }
 
 impl CliCommand for RemoteTargetCreate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let new = Self::parse_tokens(tokens)?;
 
         let auth_config = parse_auth_config(
@@ -179,7 +183,8 @@ impl CliCommand for RemoteTargetCreate {
             .map(|s| s.trim().to_string())
             .collect();
 
-        let target = services
+        let target = ctx
+            .services()
             .gateway()
             .create_remote_target(CreateRemoteTargetInput {
                 collection: new.collection,
@@ -224,7 +229,7 @@ pub struct RemoteTargetList {
 }
 
 impl CliCommand for RemoteTargetList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let list_query = build_list_query(
             &query.where_clauses,
@@ -234,7 +239,7 @@ impl CliCommand for RemoteTargetList {
             query.include_total.unwrap_or(false),
             empty(),
         )?;
-        let targets = services.gateway().list_remote_targets(&list_query)?;
+        let targets = ctx.services().gateway().list_remote_targets(&list_query)?;
         render_list_page(tokens, &targets)
     }
 }
@@ -245,17 +250,18 @@ pub struct RemoteTargetShow {
         short = "n",
         long = "name",
         help = "Name of the remote target",
-        autocomplete = "remote_targets"
+        autocomplete = "remote_targets",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for RemoteTargetShow {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let new = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(new.name, tokens, 0, "name")?;
 
-        let target = services.gateway().remote_target(&name)?;
+        let target = ctx.services().gateway().remote_target(&name)?;
 
         match desired_format(tokens) {
             OutputFormat::Json => target.format_json_noreturn()?,
@@ -272,7 +278,8 @@ pub struct RemoteTargetUpdate {
         short = "n",
         long = "name",
         help = "Name of the remote target",
-        autocomplete = "remote_targets"
+        autocomplete = "remote_targets",
+        positional = 0
     )]
     pub name: Option<String>,
     #[option(long = "rename", help = "New name")]
@@ -333,7 +340,7 @@ pub struct RemoteTargetUpdate {
 }
 
 impl CliCommand for RemoteTargetUpdate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "name")?;
 
@@ -362,7 +369,8 @@ impl CliCommand for RemoteTargetUpdate {
             .allowed_subject_types
             .map(|types| types.split(',').map(|s| s.trim().to_string()).collect());
 
-        let target = services
+        let target = ctx
+            .services()
             .gateway()
             .update_remote_target(UpdateRemoteTargetInput {
                 name,
@@ -395,16 +403,19 @@ pub struct RemoteTargetDelete {
         short = "n",
         long = "name",
         help = "Name of the remote target",
-        autocomplete = "remote_targets"
+        autocomplete = "remote_targets",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for RemoteTargetDelete {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let new = Self::parse_tokens(tokens)?;
         let target_name = required_option_or_pos(new.name, tokens, 0, "name")?;
-        services.gateway().delete_remote_target(&target_name)?;
+        ctx.services()
+            .gateway()
+            .delete_remote_target(&target_name)?;
 
         let message = format!("Remote target '{}' deleted", target_name);
 
@@ -423,7 +434,8 @@ pub struct RemoteTargetInvoke {
         short = "n",
         long = "name",
         help = "Name of the remote target",
-        autocomplete = "remote_targets"
+        autocomplete = "remote_targets",
+        positional = 0
     )]
     pub name: Option<String>,
     #[option(
@@ -487,7 +499,7 @@ pub struct RemoteTargetInvoke {
 }
 
 impl CliCommand for RemoteTargetInvoke {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let new = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(new.name, tokens, 0, "name")?;
 
@@ -503,7 +515,7 @@ impl CliCommand for RemoteTargetInvoke {
             .transpose()
             .map_err(|e| AppError::ParseError(format!("Invalid body JSON: {}", e)))?;
 
-        let task = services.gateway().invoke_remote_target(
+        let task = ctx.services().gateway().invoke_remote_target(
             &name,
             InvokeRemoteTargetInput {
                 subject_kind: new.subject_kind,
@@ -521,7 +533,7 @@ impl CliCommand for RemoteTargetInvoke {
 
         let opts = parse_task_submit_options(tokens)?;
         run_task_backed(
-            services,
+            ctx.services(),
             tokens,
             format!("remote-call {}", task.0.id),
             opts,
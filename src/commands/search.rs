@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use super::builder::{catalog_command, CommandDocs};
 use super::{desired_format, option_or_pos, CliCommand};
 use crate::autocomplete::search_kinds;
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::command_line::rebuild_with_replaced_options;
 use crate::config::get_config;
 use crate::domain::{
@@ -15,7 +15,7 @@ use crate::errors::AppError;
 use crate::formatting::{append_json, OutputFormatter, TableRenderable};
 use crate::models::OutputFormat;
 use crate::output::{add_error, append_line, set_next_page_command};
-use crate::services::{AppServices, SearchInput, SearchKind};
+use crate::services::{SearchInput, SearchKind};
 use crate::tokenizer::CommandTokenizer;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -34,6 +34,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
 --query server --kind class --kind object --limit-per-kind 5
 streamneedle --stream --kind class --kind object --search-object-data"#,
                 ),
+                ..CommandDocs::default()
             },
         ),
     );
@@ -41,7 +42,12 @@ streamneedle --stream --kind class --kind object --search-object-data"#,
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, CommandArgs)]
 pub struct SearchCommand {
-    #[option(short = "q", long = "query", help = "Plain-text search query")]
+    #[option(
+        short = "q",
+        long = "query",
+        help = "Plain-text search query",
+        positional = 0
+    )]
     pub query: Option<String>,
     #[option(
         short = "k",
@@ -91,7 +97,7 @@ pub struct SearchCommand {
 }
 
 impl CliCommand for SearchCommand {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.query = option_or_pos(query.query, tokens, 0, "query")?;
 
@@ -112,10 +118,10 @@ impl CliCommand for SearchCommand {
         };
 
         if query.stream.unwrap_or(false) {
-            let events = services.gateway().search_stream(&input)?;
+            let events = ctx.services().gateway().search_stream(&input)?;
             render_search_stream(tokens, &events)
         } else {
-            let response = services.gateway().search(&input)?;
+            let response = ctx.services().gateway().search(&input)?;
             render_search_response(tokens, &response)
         }
     }
@@ -3,12 +3,12 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, to_string_pretty};
 
 use crate::autocomplete::{groups, service_accounts};
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::errors::AppError;
 use crate::formatting::{append_json_message, OutputFormatter};
 use crate::models::OutputFormat;
 use crate::output::append_line;
-use crate::services::{AppServices, CreateServiceAccountInput, NewTokenInput};
+use crate::services::{CreateServiceAccountInput, NewTokenInput};
 use crate::tokenizer::CommandTokenizer;
 
 use super::builder::{catalog_command, CommandDocs};
@@ -26,6 +26,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ServiceAccountCreate::default(),
                 CommandDocs {
                     about: Some("Create a service account"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -59,6 +60,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ServiceAccountDelete::default(),
                 CommandDocs {
                     about: Some("Delete a service account"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -70,6 +72,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ServiceAccountDisable::default(),
                 CommandDocs {
                     about: Some("Disable a service account"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -92,6 +95,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ServiceAccountTokenCreate::default(),
                 CommandDocs {
                     about: Some("Create a token for a service account"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -103,6 +107,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 ServiceAccountTokenRevoke::default(),
                 CommandDocs {
                     about: Some("Revoke a service account token"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -125,15 +130,19 @@ pub struct ServiceAccountCreate {
 }
 
 impl CliCommand for ServiceAccountCreate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
 
-        let sa = services
+        let sa = ctx
+            .services()
             .gateway()
             .create_service_account(CreateServiceAccountInput {
                 name: query.name,
                 description: query.description,
-                owner_group_id: services.gateway().group_id_by_name(&query.owner_group)?,
+                owner_group_id: ctx
+                    .services()
+                    .gateway()
+                    .group_id_by_name(&query.owner_group)?,
             })?;
 
         match desired_format(tokens) {
@@ -168,7 +177,7 @@ pub struct ServiceAccountList {
 }
 
 impl CliCommand for ServiceAccountList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let list_query = build_list_query(
             &query.where_clauses,
@@ -186,7 +195,10 @@ impl CliCommand for ServiceAccountList {
             .flatten(),
         )?;
 
-        let service_accounts = services.gateway().list_service_accounts(&list_query)?;
+        let service_accounts = ctx
+            .services()
+            .gateway()
+            .list_service_accounts(&list_query)?;
         render_list_page(tokens, &service_accounts)
     }
 }
@@ -197,17 +209,18 @@ pub struct ServiceAccountShow {
         short = "n",
         long = "name",
         help = "Name of the service account",
-        autocomplete = "service_accounts"
+        autocomplete = "service_accounts",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for ServiceAccountShow {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "name")?;
 
-        let sa = services.gateway().service_account(&name)?;
+        let sa = ctx.services().gateway().service_account(&name)?;
 
         match desired_format(tokens) {
             OutputFormat::Json => sa.format_json_noreturn()?,
@@ -224,17 +237,18 @@ pub struct ServiceAccountDelete {
         short = "n",
         long = "name",
         help = "Name of the service account",
-        autocomplete = "service_accounts"
+        autocomplete = "service_accounts",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for ServiceAccountDelete {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "name")?;
 
-        services.gateway().delete_service_account(&name)?;
+        ctx.services().gateway().delete_service_account(&name)?;
 
         let message = format!("Service account '{}' deleted", name);
         match desired_format(tokens) {
@@ -252,17 +266,18 @@ pub struct ServiceAccountDisable {
         short = "n",
         long = "name",
         help = "Name of the service account",
-        autocomplete = "service_accounts"
+        autocomplete = "service_accounts",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for ServiceAccountDisable {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "name")?;
 
-        let sa = services.gateway().disable_service_account(&name)?;
+        let sa = ctx.services().gateway().disable_service_account(&name)?;
 
         match desired_format(tokens) {
             OutputFormat::Json => sa.format_json_noreturn()?,
@@ -282,17 +297,18 @@ pub struct ServiceAccountTokenList {
         short = "n",
         long = "name",
         help = "Name of the service account",
-        autocomplete = "service_accounts"
+        autocomplete = "service_accounts",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for ServiceAccountTokenList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "name")?;
 
-        let token_list = services.gateway().service_account_tokens(&name)?;
+        let token_list = ctx.services().gateway().service_account_tokens(&name)?;
 
         match desired_format(tokens) {
             OutputFormat::Json => {
@@ -313,7 +329,8 @@ pub struct ServiceAccountTokenCreate {
         short = "n",
         long = "name",
         help = "Name of the service account",
-        autocomplete = "service_accounts"
+        autocomplete = "service_accounts",
+        positional = 0
     )]
     pub name: Option<String>,
     #[option(long = "token-name", help = "Token name")]
@@ -335,11 +352,11 @@ pub struct ServiceAccountTokenCreate {
 }
 
 impl CliCommand for ServiceAccountTokenCreate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "name")?;
 
-        let raw_token = services.gateway().service_account_token_create(
+        let raw_token = ctx.services().gateway().service_account_token_create(
             &name,
             NewTokenInput {
                 name: query.token_name,
@@ -373,7 +390,8 @@ pub struct ServiceAccountTokenRevoke {
         short = "n",
         long = "name",
         help = "Name of the service account",
-        autocomplete = "service_accounts"
+        autocomplete = "service_accounts",
+        positional = 0
     )]
     pub name: Option<String>,
     #[option(short = "t", long = "token-id", help = "Token ID to revoke")]
@@ -381,11 +399,11 @@ pub struct ServiceAccountTokenRevoke {
 }
 
 impl CliCommand for ServiceAccountTokenRevoke {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let name = required_option_or_pos(query.name, tokens, 0, "name")?;
 
-        services
+        ctx.services()
             .gateway()
             .service_account_token_revoke(&name, query.token_id)?;
 
@@ -401,3 +419,24 @@ impl CliCommand for ServiceAccountTokenRevoke {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ServiceAccountTokenRevoke;
+    use crate::commands::required_option_or_pos;
+    use crate::tokenizer::CommandTokenizer;
+
+    #[test]
+    fn token_revoke_takes_the_account_name_as_a_positional() {
+        let tokens =
+            CommandTokenizer::new("service-account token revoke deploy -t 3", "revoke", &[])
+                .expect("tokenization");
+
+        let query = ServiceAccountTokenRevoke::parse_tokens(&tokens)
+            .expect("positional should be accepted");
+        let name = required_option_or_pos(query.name, &tokens, 0, "name").unwrap();
+
+        assert_eq!(name, "deploy");
+        assert_eq!(query.token_id, 3);
+    }
+}
@@ -0,0 +1,132 @@
+use cli_command_derive::CommandArgs;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::builder::{catalog_command, CommandDocs};
+use super::{desired_format, render_format, CliCommand};
+use crate::autocomplete::{collections, timeline_groupings, timeline_periods};
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
+use crate::domain::{timeline_buckets, TimelineGrouping, TimelinePeriod};
+use crate::errors::AppError;
+use crate::formatting::{render_timeline_chart, OutputFormatter};
+use crate::models::OutputFormat;
+use crate::output::{append_json, append_line, has_pipeline, RenderFormat};
+use crate::tokenizer::CommandTokenizer;
+
+pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
+    builder.add_command(
+        &["stats"],
+        catalog_command(
+            "timeline",
+            StatsTimeline::default(),
+            CommandDocs {
+                about: Some("Chart how many objects or classes were created over time"),
+                long_about: Some(
+                    "Count objects by the week or month they were created in, per collection or per class, and draw an ASCII bar chart with a running total. With --classes, classes are counted per collection instead. Use --output csv for the raw counts. Every object of the counted classes is fetched, so this can take a while on large inventories.",
+                ),
+                examples: Some(
+                    "timeline\ntimeline --period week --collection prod\ntimeline --by class --output csv\ntimeline --classes",
+                ),
+                ..CommandDocs::default()
+            },
+        ),
+    );
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct StatsTimeline {
+    #[option(
+        long = "period",
+        help = "Bucket size: week or month (default: month)",
+        autocomplete = "timeline_periods"
+    )]
+    pub period: Option<TimelinePeriod>,
+    #[option(
+        long = "by",
+        help = "Count per collection or class (default: collection)",
+        autocomplete = "timeline_groupings"
+    )]
+    pub by: Option<TimelineGrouping>,
+    #[option(
+        short = "c",
+        long = "collection",
+        help = "Only count classes in this collection",
+        autocomplete = "collections"
+    )]
+    pub collection: Option<String>,
+    #[option(
+        long = "classes",
+        help = "Count classes instead of objects",
+        flag = "true"
+    )]
+    pub classes: bool,
+}
+
+impl CliCommand for StatsTimeline {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let period = query.period.unwrap_or_default();
+        let grouping = query.by.unwrap_or_default();
+        if query.classes && grouping == TimelineGrouping::Class {
+            return Err(AppError::InvalidOption(
+                "--classes counts classes per collection and cannot be combined with --by class"
+                    .to_string(),
+            ));
+        }
+        let entities = if query.classes { "classes" } else { "objects" };
+
+        let created = ctx.services().gateway().creation_times(
+            query.classes,
+            grouping,
+            query.collection.as_deref(),
+        )?;
+        let buckets = timeline_buckets(created, period);
+
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json(json!({
+                "entities": entities,
+                "period": period,
+                "by": grouping,
+                "buckets": buckets,
+            }))?,
+            OutputFormat::Text => {
+                if render_format(tokens)? != RenderFormat::Text || has_pipeline()? {
+                    return buckets.format_noreturn();
+                }
+                if buckets.is_empty() {
+                    return append_line(format!("No {entities} to chart."));
+                }
+                append_line(format!("New {entities} per {period}, by {grouping}:"))?;
+                for line in render_timeline_chart(&buckets) {
+                    append_line(line)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StatsTimeline;
+    use crate::commands::CommandArgs;
+    use crate::domain::{TimelineGrouping, TimelinePeriod};
+    use crate::tokenizer::CommandTokenizer;
+
+    #[test]
+    fn period_and_grouping_parse_from_their_names() {
+        let tokens = CommandTokenizer::new_without_value_source_resolution(
+            "stats timeline --period week --by class --collection prod",
+            "timeline",
+            &StatsTimeline::options(),
+        )
+        .expect("stats timeline should tokenize");
+        let parsed = StatsTimeline::parse_tokens(&tokens).expect("options should parse");
+
+        assert_eq!(parsed.period, Some(TimelinePeriod::Week));
+        assert_eq!(parsed.by, Some(TimelineGrouping::Class));
+        assert_eq!(parsed.collection.as_deref(), Some("prod"));
+        assert!(!parsed.classes);
+    }
+}
@@ -8,12 +8,12 @@ use super::{
     render_task_record, CliCommand,
 };
 use crate::autocomplete::{task_event_sort, task_kinds, task_statuses};
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::errors::AppError;
 use crate::formatting::OutputFormatter;
 use crate::models::OutputFormat;
 use crate::output::append_line;
-use crate::services::{AppServices, ListTasksInput, TaskLookupInput};
+use crate::services::{ListTasksInput, TaskLookupInput};
 use crate::tokenizer::CommandTokenizer;
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -77,15 +77,15 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct TaskShow {
-    #[option(short = "i", long = "id", help = "Task ID")]
+    #[option(short = "i", long = "id", help = "Task ID", positional = 0)]
     pub id: Option<i32>,
 }
 
 impl CliCommand for TaskShow {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.id = option_or_pos(query.id, tokens, 0, "id")?;
-        let task = services.gateway().task(TaskLookupInput {
+        let task = ctx.services().gateway().task(TaskLookupInput {
             task_id: query
                 .id
                 .ok_or_else(|| AppError::MissingOptions(vec!["id".to_string()]))?,
@@ -97,7 +97,7 @@ impl CliCommand for TaskShow {
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct TaskEvents {
-    #[option(short = "i", long = "id", help = "Task ID")]
+    #[option(short = "i", long = "id", help = "Task ID", positional = 0)]
     pub id: Option<i32>,
     #[option(
         long = "sort",
@@ -119,7 +119,7 @@ pub struct TaskEvents {
 }
 
 impl CliCommand for TaskEvents {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.id = option_or_pos(query.id, tokens, 0, "id")?;
         let list_query = build_list_query(
@@ -130,7 +130,7 @@ impl CliCommand for TaskEvents {
             query.include_total.unwrap_or(false),
             [],
         )?;
-        let events = services.gateway().task_events(
+        let events = ctx.services().gateway().task_events(
             TaskLookupInput {
                 task_id: query
                     .id
@@ -146,8 +146,8 @@ impl CliCommand for TaskEvents {
 pub struct TaskQueue {}
 
 impl CliCommand for TaskQueue {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
-        let state = services.gateway().task_queue_state()?;
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let state = ctx.services().gateway().task_queue_state()?;
 
         match desired_format(tokens) {
             OutputFormat::Json => append_line(to_string_pretty(&state)?)?,
@@ -185,9 +185,9 @@ pub struct TaskList {
 }
 
 impl CliCommand for TaskList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
-        let tasks = services.gateway().list_tasks(ListTasksInput {
+        let tasks = ctx.services().gateway().list_tasks(ListTasksInput {
             kind: query.kind,
             status: query.status,
             limit: normalize_server_page_size(query.limit)?,
@@ -200,18 +200,18 @@ impl CliCommand for TaskList {
 
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct TaskOutputCmd {
-    #[option(short = "i", long = "id", help = "Task ID")]
+    #[option(short = "i", long = "id", help = "Task ID", positional = 0)]
     pub id: Option<i32>,
 }
 
 impl CliCommand for TaskOutputCmd {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.id = option_or_pos(query.id, tokens, 0, "id")?;
         let task_id = query
             .id
             .ok_or_else(|| AppError::MissingOptions(vec!["id".to_string()]))?;
-        let output = services.gateway().task_output(task_id)?;
+        let output = ctx.services().gateway().task_output(task_id)?;
 
         match desired_format(tokens) {
             OutputFormat::Json => append_line(to_string_pretty(&output)?)?,
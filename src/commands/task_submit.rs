@@ -1,7 +1,7 @@
-use serde_json::to_string_pretty;
+use serde_json::{json, to_string_pretty};
 
-use crate::commands::desired_format;
-use crate::domain::TaskRecord;
+use crate::commands::{desired_format, render_bulk_summary};
+use crate::domain::{BulkSummary, TaskOutput, TaskRecord};
 use crate::errors::AppError;
 use crate::formatting::OutputFormatter;
 use crate::models::OutputFormat;
@@ -58,15 +58,35 @@ pub fn run_task_backed(
             poll_interval_secs: opts.poll_interval_secs,
         })?;
         let output = services.gateway().task_output(task_id)?;
+        let TaskOutput::ImportResults(results) = &output else {
+            match desired_format(tokens) {
+                OutputFormat::Json => append_line(to_string_pretty(&output)?)?,
+                OutputFormat::Text => {
+                    final_task.format_noreturn()?;
+                    for line in output.render_lines() {
+                        append_line(line)?;
+                    }
+                }
+            }
+            return Ok(());
+        };
+
+        let summary = BulkSummary::from_import_results(results);
         match desired_format(tokens) {
-            OutputFormat::Json => append_line(to_string_pretty(&output)?)?,
+            OutputFormat::Json => append_line(to_string_pretty(
+                &json!({"summary": summary, "results": results}),
+            )?)?,
             OutputFormat::Text => {
                 final_task.format_noreturn()?;
                 for line in output.render_lines() {
                     append_line(line)?;
                 }
+                render_bulk_summary(&summary)?;
             }
         }
+        if summary.has_failures() {
+            return Err(AppError::Quiet);
+        }
         return Ok(());
     }
 
@@ -99,6 +119,8 @@ mod tests {
             nargs: None,
             repeatable: false,
             value_source: false,
+            raw: false,
+            positional: None,
             help: String::new(),
             field_type: TypeId::of::<String>(),
             field_type_help: "string".to_string(),
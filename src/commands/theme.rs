@@ -7,14 +7,13 @@ use serde_json::{json, to_string_pretty};
 use super::builder::{catalog_command, CommandDocs};
 use super::{desired_format, option_or_pos, CliCommand};
 use crate::autocomplete::theme_names;
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
 use crate::config::{
     get_config, reload_runtime_config, set_persisted_value, theme_value_candidates,
 };
 use crate::errors::AppError;
 use crate::models::OutputFormat;
 use crate::output::{append_line, append_lines, render_dense_theme_preview, set_semantic_output};
-use crate::services::AppServices;
 use crate::theme::{available_themes, paint_command, ThemeRole};
 use crate::tokenizer::CommandTokenizer;
 
@@ -73,7 +72,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
 pub struct ThemeList {}
 
 impl CliCommand for ThemeList {
-    fn execute(&self, _services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         render_theme_list(tokens)
     }
 }
@@ -117,13 +116,14 @@ pub struct ThemeShow {
         short = "n",
         long = "name",
         help = "Theme name",
-        autocomplete = "theme_names"
+        autocomplete = "theme_names",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for ThemeShow {
-    fn execute(&self, _services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         render_theme_show(tokens)
     }
 }
@@ -143,13 +143,14 @@ pub struct ThemePreview {
         short = "n",
         long = "name",
         help = "Theme name",
-        autocomplete = "theme_names"
+        autocomplete = "theme_names",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for ThemePreview {
-    fn execute(&self, _services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         render_theme_preview(tokens)
     }
 }
@@ -218,13 +219,14 @@ pub struct ThemeUse {
         short = "n",
         long = "name",
         help = "Theme name",
-        autocomplete = "theme_names"
+        autocomplete = "theme_names",
+        positional = 0
     )]
     pub name: Option<String>,
 }
 
 impl CliCommand for ThemeUse {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.name = option_or_pos(query.name, tokens, 0, "name")?;
         let name = query
@@ -237,8 +239,8 @@ impl CliCommand for ThemeUse {
 
         let path = set_persisted_value("output.theme", &name)?;
         reload_runtime_config()?;
-        services.invalidate_completion();
-        services.sync_user_preferences_if_enabled()?;
+        ctx.services().invalidate_completion();
+        ctx.services().sync_user_preferences_if_enabled()?;
 
         match desired_format(tokens) {
             OutputFormat::Json => append_line(&to_string_pretty(&json!({
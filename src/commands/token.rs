@@ -0,0 +1,114 @@
+use chrono::Utc;
+use cli_command_derive::CommandArgs;
+use serde::Serialize;
+use serde_json::json;
+
+use super::builder::{catalog_command, CommandDocs};
+use super::{desired_format, CliCommand};
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
+use crate::config::get_config;
+use crate::errors::AppError;
+use crate::files::{token_storage, TokenStore};
+use crate::formatting::{append_json, OutputFormatter};
+use crate::models::{CachedTokenSummary, OutputFormat};
+use crate::output::append_line;
+use crate::tokenizer::CommandTokenizer;
+
+pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
+    builder.add_command(
+        &["token"],
+        catalog_command(
+            "list",
+            TokenList::default(),
+            CommandDocs {
+                about: Some("List cached login tokens and their age"),
+                long_about: Some(
+                    "List the logins in the local token cache with their server, identity scope, username, token age, and when the server last accepted the token. The configured login is marked as current and is listed as missing when it has no cached token, so the next start will ask for a password. Works without contacting the server.",
+                ),
+                examples: Some("\n--json"),
+                ..CommandDocs::default()
+            },
+        ),
+    );
+    builder.add_command(
+        &["token"],
+        catalog_command(
+            "prune",
+            TokenPrune::default(),
+            CommandDocs {
+                about: Some("Remove cached login tokens for servers not used recently"),
+                long_about: Some(
+                    "Remove entries from the local token cache whose server has not accepted the token for more than --days days. Without --days, server.token_prune_days is used. The same pruning runs automatically after each login.",
+                ),
+                examples: Some("\n--days 30"),
+                ..CommandDocs::default()
+            },
+        ),
+    );
+}
+
+#[derive(Debug, Serialize, Clone, CommandArgs, Default)]
+pub struct TokenList {}
+
+impl CliCommand for TokenList {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        render_token_list(tokens)
+    }
+}
+
+pub(crate) fn render_token_list(tokens: &CommandTokenizer) -> Result<(), AppError> {
+    TokenList::parse_tokens(tokens)?;
+    let config = get_config();
+    let store = TokenStore::load(token_storage(&config.auth, &config.server)?.as_ref())?;
+    let summaries = CachedTokenSummary::from_entries(store.entries(), &config.server, Utc::now());
+
+    match desired_format(tokens) {
+        OutputFormat::Json => append_json(&summaries)?,
+        OutputFormat::Text => summaries.format_noreturn()?,
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Clone, CommandArgs, Default)]
+pub struct TokenPrune {
+    #[option(
+        short = "d",
+        long = "days",
+        help = "Remove tokens not used for more than this many days"
+    )]
+    pub days: Option<u64>,
+}
+
+impl CliCommand for TokenPrune {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        render_token_prune(tokens)
+    }
+}
+
+pub(crate) fn render_token_prune(tokens: &CommandTokenizer) -> Result<(), AppError> {
+    let query = TokenPrune::parse_tokens(tokens)?;
+    let config = get_config();
+    let days = query.days.unwrap_or(config.server.token_prune_days);
+    if days == 0 {
+        return Err(AppError::ParseError(
+            "--days must be at least 1".to_string(),
+        ));
+    }
+
+    let storage = token_storage(&config.auth, &config.server)?;
+    let (removed, remaining) = TokenStore::update(storage.as_ref(), |store| {
+        (store.prune(days, Utc::now()), store.count())
+    })?;
+
+    match desired_format(tokens) {
+        OutputFormat::Json => append_json(&json!({
+            "days": days,
+            "removed": removed,
+            "remaining": remaining,
+        }))?,
+        OutputFormat::Text => append_line(format!(
+            "Removed {removed} cached token(s) unused for more than {days} day(s); {remaining} remaining."
+        ))?,
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDateTime, Utc};
 use cli_command_derive::CommandArgs;
 use hubuum_client::FilterOperator;
 use serde::{Deserialize, Serialize};
@@ -12,20 +12,23 @@ use rand::{rng, RngExt};
 use rpassword::prompt_password;
 
 use crate::autocomplete::{file_paths, user_sort, user_where, users};
-use crate::catalog::CommandCatalogBuilder;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
+use crate::config::{get_config, ServerConfig};
 use crate::domain::CreatedUser;
 use crate::errors::AppError;
+use crate::files::{token_storage, TokenStore};
 use crate::formatting::{append_json_message, OutputFormatter};
 use crate::list_query::filter_clause;
-use crate::models::OutputFormat;
-use crate::output::{append_key_value, append_line};
-use crate::services::{AppServices, CreateUserInput, NewTokenInput, UserFilter, UserUpdateInput};
+use crate::models::{OutputFormat, TokenEntry};
+use crate::output::{add_warning, append_key_value, append_line};
+use crate::secrets::secret_to_command;
+use crate::services::{CreateUserInput, NewTokenInput, UserFilter, UserUpdateInput};
 use crate::tokenizer::CommandTokenizer;
 
 use super::builder::{catalog_command, CommandDocs};
 use super::{
     build_list_query, contains_clause, desired_format, render_list_page, required_option_or_pos,
-    CliCommand,
+    wildcard_clause, CliCommand,
 };
 
 pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
@@ -37,7 +40,14 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 UserNew::default(),
                 CommandDocs {
                     about: Some("Create a user"),
-                    ..CommandDocs::default()
+                    long_about: Some(
+                        "Create a user with a generated password. When user.credential_delivery is set, the username, email, and password are sent as a JSON object to that command's standard input instead of being printed.",
+                    ),
+                    examples: Some(
+                        r#"--username alice --email alice@example.com
+--username bob --no-delivery"#,
+                    ),
+                    mutating: true,
                 },
             ),
         )
@@ -52,6 +62,25 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 },
             ),
         )
+        .add_command(
+            &["user"],
+            catalog_command(
+                "find",
+                UserFind::default(),
+                CommandDocs {
+                    about: Some("Find users by email address"),
+                    long_about: Some(
+                        "Look up users whose email address matches a pattern. Matching is case-insensitive; `*` matches any text, so '*@example.com' finds every address in a domain and a pattern without `*` matches anywhere in the address.",
+                    ),
+                    examples: Some(
+                        r#"'*@example.com'
+--email alice
+--email 'ops-*' --limit 50"#,
+                    ),
+                    ..CommandDocs::default()
+                },
+            ),
+        )
         .add_command(
             &["user"],
             catalog_command(
@@ -59,6 +88,7 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 UserDelete::default(),
                 CommandDocs {
                     about: Some("Delete a user"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -81,11 +111,14 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                 UserModify::default(),
                 CommandDocs {
                     about: Some("Modify a user"),
-                    long_about: Some("Update an existing user by username."),
+                    long_about: Some("Update an existing user by username. With --generate-password a new random password is set and printed; with --password-file the password is read from a file."),
                     examples: Some(
                         r#"modify alice --rename alice2
-modify --username alice --email alice@example.com"#,
+modify --username alice --email alice@example.com
+modify alice --generate-password
+modify alice --password-file ./new-password"#,
                     ),
+                    mutating: true,
                 },
             ),
         )
@@ -103,6 +136,40 @@ modify --username alice --email alice@example.com"#,
                         r#"set-password alice
 set-password alice --password-file /run/secrets/alice-password"#,
                     ),
+                    mutating: true,
+                },
+            ),
+        )
+        .add_command(
+            &["user"],
+            catalog_command(
+                "passwd",
+                UserPasswd::default(),
+                CommandDocs {
+                    about: Some("Change your own password"),
+                    long_about: Some(
+                        "Prompt for your current password and a new one, then change the password of the configured server.username. The stored login token is replaced with one issued for the new password. Logins through an identity scope or server.token_file have no local password to change.",
+                    ),
+                    examples: Some("passwd"),
+                    mutating: true,
+                },
+            ),
+        )
+        .add_command(
+            &["user"],
+            catalog_command(
+                "groups",
+                UserGroups::default(),
+                CommandDocs {
+                    about: Some("List the groups a user belongs to"),
+                    long_about: Some(
+                        "List every group the user is a member of, the reverse of the member list in `group show`.",
+                    ),
+                    examples: Some(
+                        r#"groups alice
+groups --username alice --output json"#,
+                    ),
+                    ..CommandDocs::default()
                 },
             ),
         )
@@ -124,6 +191,7 @@ set-password alice --password-file /run/secrets/alice-password"#,
                 UserTokenCreate::default(),
                 CommandDocs {
                     about: Some("Create a token for a user"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -135,6 +203,7 @@ set-password alice --password-file /run/secrets/alice-password"#,
                 UserTokenRevoke::default(),
                 CommandDocs {
                     about: Some("Revoke a user token"),
+                    mutating: true,
                     ..CommandDocs::default()
                 },
             ),
@@ -147,25 +216,46 @@ pub struct UserNew {
     pub username: String,
     #[option(short = "e", long = "email", help = "Email address for the user")]
     pub email: Option<String>,
+    #[option(
+        long = "no-delivery",
+        help = "Print the password even when user.credential_delivery is set",
+        flag = "true"
+    )]
+    pub no_delivery: bool,
 }
 
 impl CliCommand for UserNew {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let new = Self::parse_tokens(tokens)?;
         let password = generate_random_password(20);
-        let created: CreatedUser = services.gateway().create_user(CreateUserInput {
-            username: new.username,
-            email: new.email,
+        let created: CreatedUser = ctx.services().gateway().create_user(CreateUserInput {
+            username: new.username.clone(),
+            email: new.email.clone(),
             password: password.clone(),
         })?;
 
+        let delivery = ctx.config().user.credential_delivery.clone();
+        let delivered = match delivery.filter(|_| !new.no_delivery) {
+            Some(command) => deliver_credentials(&command, &new, &password)?,
+            None => false,
+        };
+
         match desired_format(tokens) {
+            OutputFormat::Json if delivered => {
+                append_line(to_string_pretty(
+                    &json!({ "user": created.user, "password_delivered": true }),
+                )?)?;
+            }
             OutputFormat::Json => {
                 append_line(to_string_pretty(&created)?)?;
             }
             OutputFormat::Text => {
                 created.user.format_noreturn()?;
-                append_key_value("Password", password, 15)?;
+                if delivered {
+                    append_key_value("Password", "sent to user.credential_delivery", 15)?;
+                } else {
+                    append_key_value("Password", password, 15)?;
+                }
             }
         }
 
@@ -173,22 +263,48 @@ impl CliCommand for UserNew {
     }
 }
 
+/// Sends the new credentials to the configured delivery command.
+///
+/// The user already exists at this point, so a failed delivery falls back to printing
+/// the password (with a warning) rather than losing it.
+fn deliver_credentials(command: &str, new: &UserNew, password: &str) -> Result<bool, AppError> {
+    let credentials = json!({
+        "username": new.username,
+        "email": new.email,
+        "password": password,
+    });
+    match secret_to_command(
+        "user.credential_delivery",
+        command,
+        &credentials.to_string(),
+    ) {
+        Ok(()) => Ok(true),
+        Err(error) => {
+            add_warning(format!(
+                "Could not deliver the credentials, showing the password instead: {error}"
+            ))?;
+            Ok(false)
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct UserDelete {
     #[option(
         short = "u",
         long = "username",
         help = "Username of the user",
-        autocomplete = "users"
+        autocomplete = "users",
+        positional = 0
     )]
     pub username: Option<String>,
 }
 
 impl CliCommand for UserDelete {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let username = required_option_or_pos(query.username, tokens, 0, "username")?;
-        services.gateway().delete_user(&username)?;
+        ctx.services().gateway().delete_user(&username)?;
 
         let message = format!("User '{}' deleted", username);
 
@@ -207,7 +323,8 @@ pub struct UserInfo {
         short = "u",
         long = "username",
         help = "Username of the user",
-        autocomplete = "users"
+        autocomplete = "users",
+        positional = 0
     )]
     pub username: Option<String>,
     #[option(short = "e", long = "email", help = "Email address for the user")]
@@ -219,7 +336,7 @@ pub struct UserInfo {
 }
 
 impl CliCommand for UserInfo {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let mut query = Self::parse_tokens(tokens)?;
         query.username = Some(required_option_or_pos(
             query.username,
@@ -228,7 +345,7 @@ impl CliCommand for UserInfo {
             "username",
         )?);
 
-        let user = services.gateway().find_user(UserFilter {
+        let user = ctx.services().gateway().find_user(UserFilter {
             username: query.username,
             email: query.email,
             created_at: query.created_at,
@@ -281,7 +398,7 @@ pub struct UserList {
 }
 
 impl CliCommand for UserList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let list_query = build_list_query(
             &query.where_clauses,
@@ -312,7 +429,45 @@ impl CliCommand for UserList {
             .into_iter()
             .flatten(),
         )?;
-        let users = services.gateway().list_users(&list_query)?;
+        let users = ctx.services().gateway().list_users(&list_query)?;
+        render_list_page(tokens, &users)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct UserFind {
+    #[option(
+        short = "e",
+        long = "email",
+        help = "Email pattern; * matches any text, e.g. '*@example.com'",
+        positional = 0
+    )]
+    pub email: Option<String>,
+    #[option(long = "limit", help = "Page size (server maximum: 250)")]
+    pub limit: Option<usize>,
+    #[option(long = "cursor", help = "Cursor for the next result page")]
+    pub cursor: Option<String>,
+    #[option(
+        long = "include-total",
+        help = "Request the exact matching count",
+        flag = "true"
+    )]
+    pub include_total: Option<bool>,
+}
+
+impl CliCommand for UserFind {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let email = required_option_or_pos(query.email, tokens, 0, "email")?;
+        let list_query = build_list_query(
+            &[],
+            &[],
+            query.limit,
+            query.cursor,
+            query.include_total.unwrap_or(false),
+            [wildcard_clause("email", &email)],
+        )?;
+        let users = ctx.services().gateway().list_users(&list_query)?;
         render_list_page(tokens, &users)
     }
 }
@@ -323,28 +478,68 @@ pub struct UserModify {
         short = "u",
         long = "username",
         help = "Username of the user",
-        autocomplete = "users"
+        autocomplete = "users",
+        positional = 0
     )]
     pub username: Option<String>,
     #[option(short = "r", long = "rename", help = "Rename the user")]
     pub rename: Option<String>,
     #[option(short = "e", long = "email", help = "Email address for the user")]
     pub email: Option<String>,
+    #[option(
+        long = "generate-password",
+        help = "Set a new random password and print it",
+        flag = "true"
+    )]
+    pub generate_password: bool,
+    #[option(
+        long = "password-file",
+        help = "Set the password read from a file",
+        autocomplete = "file_paths"
+    )]
+    pub password_file: Option<String>,
 }
 
 impl CliCommand for UserModify {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let username = required_option_or_pos(query.username, tokens, 0, "username")?;
-        let user = services.gateway().update_user(UserUpdateInput {
-            username,
+        let password = match (query.generate_password, query.password_file) {
+            (true, Some(_)) => {
+                return Err(AppError::InvalidOption(
+                    "Use either --generate-password or --password-file, not both".to_string(),
+                ))
+            }
+            (true, None) => Some(NewPassword::new(generate_random_password(20))?),
+            (false, Some(path)) => Some(NewPassword::from_file(&path)?),
+            (false, None) => None,
+        };
+
+        let gateway = ctx.services().gateway();
+        let user = gateway.update_user(UserUpdateInput {
+            username: username.clone(),
             rename: query.rename,
             email: query.email,
         })?;
+        if let Some(password) = &password {
+            gateway.set_user_password(&username, password.as_str())?;
+        }
 
-        match desired_format(tokens) {
-            OutputFormat::Json => user.format_json_noreturn()?,
-            OutputFormat::Text => user.format_noreturn()?,
+        let password_set = password.is_some();
+        let generated = password.filter(|_| query.generate_password);
+        match (desired_format(tokens), generated) {
+            (OutputFormat::Json, Some(password)) => append_line(to_string_pretty(
+                &json!({ "user": user, "password": password.as_str() }),
+            )?)?,
+            (OutputFormat::Json, None) => user.format_json_noreturn()?,
+            (OutputFormat::Text, generated) => {
+                user.format_noreturn()?;
+                if let Some(password) = generated {
+                    append_key_value("Password", password.as_str(), 15)?;
+                } else if password_set {
+                    append_line(format!("Password updated for user '{username}'"))?;
+                }
+            }
         }
 
         Ok(())
@@ -366,7 +561,8 @@ pub struct UserSetPassword {
         short = "u",
         long = "username",
         help = "Username of the user",
-        autocomplete = "users"
+        autocomplete = "users",
+        positional = 0
     )]
     pub username: Option<String>,
     #[option(
@@ -378,7 +574,7 @@ pub struct UserSetPassword {
 }
 
 impl CliCommand for UserSetPassword {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let username = required_option_or_pos(query.username, tokens, 0, "username")?;
         let password = match query.password_file {
@@ -386,7 +582,7 @@ impl CliCommand for UserSetPassword {
             None => NewPassword::prompt(&username)?,
         };
 
-        services
+        ctx.services()
             .gateway()
             .set_user_password(&username, password.as_str())?;
 
@@ -400,6 +596,64 @@ impl CliCommand for UserSetPassword {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct UserPasswd {}
+
+impl CliCommand for UserPasswd {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let _query = Self::parse_tokens(tokens)?;
+        let config = get_config();
+        let username = password_login_username(&config.server)?;
+        let current = prompt_password(format!("Current password for {username}: "))?;
+        let password = NewPassword::prompt(username)?;
+        if password.as_str() == current {
+            return Err(AppError::InvalidOption(
+                "The new password is the same as the current one".to_string(),
+            ));
+        }
+
+        let token =
+            ctx.services()
+                .gateway()
+                .change_own_password(username, &current, password.as_str())?;
+        let storage = token_storage(&config.auth, &config.server)?;
+        TokenStore::update(storage.as_ref(), |tokens| {
+            tokens.upsert(
+                TokenEntry::new(
+                    config.server.hostname.clone(),
+                    None,
+                    username.to_string(),
+                    token,
+                ),
+                Utc::now(),
+            );
+        })?;
+
+        let message = format!("Password changed for '{username}'; the stored token was refreshed");
+        match desired_format(tokens) {
+            OutputFormat::Json => append_json_message(&message)?,
+            OutputFormat::Text => append_line(message)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// The username whose password `user passwd` changes, when the login used one.
+fn password_login_username(server: &ServerConfig) -> Result<&str, AppError> {
+    if server.token_file.is_some() {
+        return Err(AppError::CommandExecutionError(
+            "user passwd needs a password login; this session uses server.token_file".to_string(),
+        ));
+    }
+    if let Some(scope) = &server.identity_scope {
+        return Err(AppError::CommandExecutionError(format!(
+            "The password for this login is managed by the '{scope}' identity scope"
+        )));
+    }
+    Ok(&server.username)
+}
+
 struct NewPassword(String);
 
 impl NewPassword {
@@ -440,23 +694,54 @@ impl NewPassword {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
+pub struct UserGroups {
+    #[option(
+        short = "u",
+        long = "username",
+        help = "Username of the user",
+        autocomplete = "users",
+        positional = 0
+    )]
+    pub username: Option<String>,
+}
+
+impl CliCommand for UserGroups {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        let query = Self::parse_tokens(tokens)?;
+        let username = required_option_or_pos(query.username, tokens, 0, "username")?;
+
+        let groups = ctx.services().gateway().user_groups(&username)?;
+
+        let empty_message = format!("User '{username}' is not a member of any group");
+        match (desired_format(tokens), groups.is_empty()) {
+            (OutputFormat::Json, _) => append_line(to_string_pretty(&groups)?)?,
+            (OutputFormat::Text, true) => append_line(empty_message)?,
+            (OutputFormat::Text, false) => groups.format_noreturn()?,
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, CommandArgs, Default)]
 pub struct UserTokenList {
     #[option(
         short = "u",
         long = "username",
         help = "Username of the user",
-        autocomplete = "users"
+        autocomplete = "users",
+        positional = 0
     )]
     pub username: Option<String>,
 }
 
 impl CliCommand for UserTokenList {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let username = required_option_or_pos(query.username, tokens, 0, "username")?;
 
-        let token_list = services.gateway().user_tokens(&username)?;
+        let token_list = ctx.services().gateway().user_tokens(&username)?;
 
         match desired_format(tokens) {
             OutputFormat::Json => {
@@ -477,7 +762,8 @@ pub struct UserTokenCreate {
         short = "u",
         long = "username",
         help = "Username of the user",
-        autocomplete = "users"
+        autocomplete = "users",
+        positional = 0
     )]
     pub username: Option<String>,
     #[option(short = "n", long = "name", help = "Token name")]
@@ -499,11 +785,11 @@ pub struct UserTokenCreate {
 }
 
 impl CliCommand for UserTokenCreate {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let username = required_option_or_pos(query.username, tokens, 0, "username")?;
 
-        let raw_token = services.gateway().user_token_create(
+        let raw_token = ctx.services().gateway().user_token_create(
             &username,
             NewTokenInput {
                 name: query.name,
@@ -537,7 +823,8 @@ pub struct UserTokenRevoke {
         short = "u",
         long = "username",
         help = "Username of the user",
-        autocomplete = "users"
+        autocomplete = "users",
+        positional = 0
     )]
     pub username: Option<String>,
     #[option(short = "t", long = "token-id", help = "Token ID to revoke")]
@@ -545,11 +832,11 @@ pub struct UserTokenRevoke {
 }
 
 impl CliCommand for UserTokenRevoke {
-    fn execute(&self, services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         let query = Self::parse_tokens(tokens)?;
         let username = required_option_or_pos(query.username, tokens, 0, "username")?;
 
-        services
+        ctx.services()
             .gateway()
             .user_token_revoke(&username, query.token_id)?;
 
@@ -569,8 +856,12 @@ mod tests {
 
     use tempfile::tempdir;
 
-    use super::{NewPassword, UserSetPassword};
-    use crate::commands::CommandArgs;
+    use super::{
+        password_login_username, NewPassword, UserModify, UserSetPassword, UserTokenRevoke,
+    };
+    use crate::commands::{required_option_or_pos, CommandArgs};
+    use crate::config::AppConfig;
+    use crate::tokenizer::CommandTokenizer;
 
     #[test]
     fn password_file_removes_only_line_endings() {
@@ -609,4 +900,47 @@ mod tests {
             .iter()
             .any(|option| option.long.as_deref() == Some("--password")));
     }
+
+    #[test]
+    fn modify_generates_or_reads_passwords_but_never_takes_them_inline() {
+        let options = UserModify::options();
+        let has = |long: &str| {
+            options
+                .iter()
+                .any(|option| option.long.as_deref() == Some(long))
+        };
+
+        assert!(has("--generate-password"));
+        assert!(has("--password-file"));
+        assert!(!has("--password"));
+    }
+
+    #[test]
+    fn passwd_needs_a_plain_password_login() {
+        let mut server = AppConfig::default().server;
+        server.username = "alice".to_string();
+        assert_eq!(password_login_username(&server).unwrap(), "alice");
+
+        server.identity_scope = Some("ldap".to_string());
+        assert!(password_login_username(&server)
+            .unwrap_err()
+            .to_string()
+            .contains("'ldap' identity scope"));
+
+        server.identity_scope = None;
+        server.token_file = Some("/run/secrets/token".to_string());
+        assert!(password_login_username(&server).is_err());
+    }
+
+    #[test]
+    fn token_revoke_takes_the_username_as_a_positional() {
+        let tokens = CommandTokenizer::new("user token revoke alice -t 3", "revoke", &[])
+            .expect("tokenization");
+
+        let query = UserTokenRevoke::parse_tokens(&tokens).expect("positional should be accepted");
+        let username = required_option_or_pos(query.username, &tokens, 0, "username").unwrap();
+
+        assert_eq!(username, "alice");
+        assert_eq!(query.token_id, 3);
+    }
 }
@@ -1,18 +1,22 @@
-use std::time::Duration;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, Instant};
 
 use cli_command_derive::CommandArgs;
+use hubuum_client::blocking::Client as BlockingClient;
+use serde::de::IgnoredAny;
 use serde::{Deserialize, Serialize};
 use serde_json::to_string_pretty;
 
 use super::builder::{catalog_command, CommandDocs};
 use super::{desired_format, CliCommand};
 use crate::build_info;
-use crate::catalog::CommandCatalogBuilder;
-use crate::config::get_config;
+use crate::catalog::{CommandCatalogBuilder, CommandContext};
+use crate::config::{get_config, ServerConfig};
+use crate::defaults::Defaults;
 use crate::errors::AppError;
 use crate::models::OutputFormat;
-use crate::output::{append_key_value, append_line};
-use crate::services::AppServices;
+use crate::offline::require_online;
+use crate::output::{add_warning, append_key_value, append_line};
 use crate::tokenizer::CommandTokenizer;
 
 const SERVER_VERSION_TIMEOUT: Duration = Duration::from_secs(5);
@@ -29,6 +33,37 @@ pub(crate) fn register_commands(builder: &mut CommandCatalogBuilder) {
                     "Show the CLI version, build target, and commit identity. Use --server to also query the configured Hubuum server's OpenAPI version.",
                 ),
                 examples: Some("--server\n--output json"),
+                ..CommandDocs::default()
+            },
+        ),
+    );
+    builder.add_command(
+        &["server"],
+        catalog_command(
+            "api-version",
+            ServerApiVersion::default(),
+            CommandDocs {
+                about: Some("Compare the CLI's API version with the server's"),
+                long_about: Some(
+                    "Show the API version this CLI speaks, the configured server.api_version, and the API versions the server publishes in its OpenAPI document. A warning is shown when the server does not serve the version the CLI uses.",
+                ),
+                examples: Some("\n--output json"),
+                ..CommandDocs::default()
+            },
+        ),
+    );
+    builder.add_command(
+        &["server"],
+        catalog_command(
+            "info",
+            ServerInfoCommand::default(),
+            CommandDocs {
+                about: Some("Check the server's health, version, and latency"),
+                long_about: Some(
+                    "Call the server's health probe and show its status and round-trip latency, the server version, and the API versions it publishes. A warning is shown when the server does not serve the API version this CLI uses, so compatibility can be checked before running operations.",
+                ),
+                examples: Some("\n--output json"),
+                ..CommandDocs::default()
             },
         ),
     );
@@ -45,11 +80,29 @@ pub struct Version {
 }
 
 impl CliCommand for Version {
-    fn execute(&self, _services: &AppServices, tokens: &CommandTokenizer) -> Result<(), AppError> {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
         render_version(tokens)
     }
 }
 
+#[derive(Debug, Serialize, Clone, CommandArgs, Default)]
+pub struct ServerApiVersion {}
+
+impl CliCommand for ServerApiVersion {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        render_server_api_version(tokens)
+    }
+}
+
+#[derive(Debug, Serialize, Clone, CommandArgs, Default)]
+pub struct ServerInfoCommand {}
+
+impl CliCommand for ServerInfoCommand {
+    fn execute(&self, _ctx: &CommandContext, tokens: &CommandTokenizer) -> Result<(), AppError> {
+        render_server_info(tokens)
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct VersionInfo {
     cli_version: &'static str,
@@ -59,9 +112,31 @@ struct VersionInfo {
     server_version: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct ApiVersionInfo {
+    client_api_version: &'static str,
+    configured_api_version: String,
+    server_version: String,
+    server_api_versions: Vec<String>,
+    compatible: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ServerInfo {
+    pub(crate) server: String,
+    pub(crate) health: String,
+    pub(crate) latency_ms: u128,
+    pub(crate) server_version: String,
+    pub(crate) server_api_versions: Vec<String>,
+    pub(crate) client_api_version: &'static str,
+    pub(crate) compatible: bool,
+}
+
 #[derive(Debug, Deserialize)]
 struct OpenApiDocument {
     info: OpenApiInfo,
+    #[serde(default)]
+    paths: BTreeMap<String, IgnoredAny>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -74,7 +149,7 @@ pub(crate) fn render_version(tokens: &CommandTokenizer) -> Result<(), AppError>
     let server_version = query
         .server
         .unwrap_or(false)
-        .then(fetch_server_version)
+        .then(|| fetch_server_version(&get_config().server))
         .transpose()?;
     let info = VersionInfo {
         cli_version: build_info::VERSION,
@@ -100,14 +175,118 @@ pub(crate) fn render_version(tokens: &CommandTokenizer) -> Result<(), AppError>
     Ok(())
 }
 
-fn fetch_server_version() -> Result<String, AppError> {
-    let config = get_config();
-    let url = format!(
-        "{}://{}:{}/api-doc/openapi.json",
-        config.server.protocol, config.server.hostname, config.server.port
-    );
+pub(crate) fn render_server_api_version(tokens: &CommandTokenizer) -> Result<(), AppError> {
+    let _query = ServerApiVersion::parse_tokens(tokens)?;
+    let document = fetch_openapi_document(&get_config().server)?;
+    let server_api_versions = document.api_versions();
+    let info = ApiVersionInfo {
+        client_api_version: Defaults::API_VERSION,
+        configured_api_version: get_config().server.api_version.clone(),
+        server_version: normalize_version(&document.info.version),
+        compatible: serves_cli_api(&server_api_versions),
+        server_api_versions,
+    };
+
+    match desired_format(tokens) {
+        OutputFormat::Json => append_line(to_string_pretty(&info)?)?,
+        OutputFormat::Text => {
+            append_key_value("CLI API", info.client_api_version, 12)?;
+            append_key_value("Configured", &info.configured_api_version, 12)?;
+            append_key_value("Server", &info.server_version, 12)?;
+            append_key_value("Server APIs", info.server_api_versions.join(", "), 12)?;
+        }
+    }
+    warn_if_incompatible(info.compatible)
+}
+
+pub(crate) fn render_server_info(tokens: &CommandTokenizer) -> Result<(), AppError> {
+    let _query = ServerInfoCommand::parse_tokens(tokens)?;
+    let info = probe_server(&get_config().server)?;
+
+    match desired_format(tokens) {
+        OutputFormat::Json => append_line(to_string_pretty(&info)?)?,
+        OutputFormat::Text => {
+            append_key_value("Server", &info.server, 12)?;
+            append_key_value("Health", &info.health, 12)?;
+            append_key_value("Latency", format!("{} ms", info.latency_ms), 12)?;
+            append_key_value("Version", &info.server_version, 12)?;
+            append_key_value("Server APIs", info.server_api_versions.join(", "), 12)?;
+            append_key_value("CLI API", info.client_api_version, 12)?;
+        }
+    }
+    warn_if_incompatible(info.compatible)
+}
+
+/// Calls the health probe of `server` and reads its OpenAPI document, for `server info`
+/// and `profile doctor`.
+pub(crate) fn probe_server(server: &ServerConfig) -> Result<ServerInfo, AppError> {
+    require_online("checking the server's health")?;
+    let client = BlockingClient::builder(server.api_base_url()?)
+        .validate_certs(server.ssl_validation)
+        .timeout(SERVER_VERSION_TIMEOUT)
+        .user_agent(format!("hubuum-cli/{}", build_info::VERSION))
+        .build()?;
+    let started = Instant::now();
+    let health = client.healthz()?;
+    let latency = started.elapsed();
+    let document = fetch_openapi_document(server)?;
+    let server_api_versions = document.api_versions();
+    Ok(ServerInfo {
+        server: server.server_url(),
+        health: health.status,
+        latency_ms: latency.as_millis(),
+        server_version: normalize_version(&document.info.version),
+        client_api_version: Defaults::API_VERSION,
+        compatible: serves_cli_api(&server_api_versions),
+        server_api_versions,
+    })
+}
+
+fn serves_cli_api(server_api_versions: &[String]) -> bool {
+    server_api_versions
+        .iter()
+        .any(|version| version == Defaults::API_VERSION)
+}
+
+fn warn_if_incompatible(compatible: bool) -> Result<(), AppError> {
+    if !compatible {
+        add_warning(format!(
+            "The server does not publish the {} API this CLI uses; upgrade hubuum-cli to match the server.",
+            Defaults::API_VERSION
+        ))?;
+    }
+    Ok(())
+}
+
+impl OpenApiDocument {
+    /// API versions the server serves, taken from `/api/<version>/...` paths.
+    fn api_versions(&self) -> Vec<String> {
+        self.paths
+            .keys()
+            .filter_map(|path| path.strip_prefix("/api/")?.split('/').next())
+            .filter(|segment| {
+                segment
+                    .strip_prefix('v')
+                    .is_some_and(|number| number.parse::<u32>().is_ok())
+            })
+            .map(str::to_string)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+fn fetch_server_version(server: &ServerConfig) -> Result<String, AppError> {
+    Ok(normalize_version(
+        &fetch_openapi_document(server)?.info.version,
+    ))
+}
+
+fn fetch_openapi_document(server: &ServerConfig) -> Result<OpenApiDocument, AppError> {
+    require_online("asking the server for its version")?;
+    let url = format!("{}/api-doc/openapi.json", server.server_url());
     let client = reqwest::blocking::Client::builder()
-        .danger_accept_invalid_certs(!config.server.ssl_validation)
+        .danger_accept_invalid_certs(!server.ssl_validation)
         .timeout(SERVER_VERSION_TIMEOUT)
         .user_agent(format!("hubuum-cli/{}", build_info::VERSION))
         .build()
@@ -117,11 +296,9 @@ fn fetch_server_version() -> Result<String, AppError> {
         .send()
         .and_then(reqwest::blocking::Response::error_for_status)
         .map_err(|error| server_version_error(&url, error))?;
-    let document = response
+    response
         .json::<OpenApiDocument>()
-        .map_err(|error| server_version_error(&url, error))?;
-
-    Ok(normalize_version(&document.info.version))
+        .map_err(|error| server_version_error(&url, error))
 }
 
 fn server_version_error(url: &str, error: reqwest::Error) -> AppError {
@@ -138,7 +315,28 @@ fn normalize_version(version: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::normalize_version;
+    use serde_json::from_value;
+    use serde_json::json;
+
+    use super::{normalize_version, serves_cli_api, OpenApiDocument};
+
+    #[test]
+    fn api_versions_come_from_openapi_paths() {
+        let document: OpenApiDocument = from_value(json!({
+            "info": {"version": "0.0.3"},
+            "paths": {
+                "/api/v1/classes": {},
+                "/api/v1/iam/me": {},
+                "/api/v2/classes": {},
+                "/api/healthz": {},
+                "/metrics": {}
+            }
+        }))
+        .expect("document should parse");
+        assert_eq!(document.api_versions(), vec!["v1", "v2"]);
+        assert!(serves_cli_api(&document.api_versions()));
+        assert!(!serves_cli_api(&["v2".to_string()]));
+    }
 
     #[test]
     fn version_prefix_is_normalized() {
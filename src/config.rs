@@ -1,31 +1,36 @@
 use clap::{parser::ValueSource, ArgMatches};
-use config::{Config, ConfigError, Environment, File};
+use config::{Config, ConfigError, Environment, File, FileFormat};
+use hubuum_client::BaseUrl;
 use hubuum_theme::{catalog as theme_catalog, theme_names};
 use once_cell::sync::Lazy;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::to_string as to_json_string;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env::var_os;
-use std::fs::{create_dir_all, read_to_string, write};
+use std::fs::{create_dir_all, read_to_string};
 use std::io::ErrorKind;
 use std::mem::take;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock, RwLockWriteGuard};
 use toml::map::Map as TomlMap;
 use toml::{from_str as parse_toml, to_string_pretty as format_toml, Value as TomlValue};
 
+use crate::command_defaults::CommandDefaults;
 use crate::defaults::Defaults;
 use crate::domain::ComputedFieldSet;
 use crate::errors::AppError;
-use crate::files::{get_system_config_path, get_user_config_path};
+use crate::files::{get_system_config_path, get_user_config_path, write_file_atomically};
 use crate::models::{
-    EmptyResult, ObjectListDataColumns, OutputColor, OutputFormat, Protocol, TableBands,
-    TableStyle, TableWidth, TableWrap,
+    DataLimitAction, EmptyResult, NameMatch, ObjectListDataColumns, OutputColor, Protocol,
+    RateLimit, RenderFormat, SortCollation, TableBands, TableStyle, TableWidth, TableWrap,
 };
 
 static CONFIG: Lazy<RwLock<Arc<AppConfig>>> =
     Lazy::new(|| RwLock::new(Arc::new(AppConfig::default())));
 static CONFIG_STATE: Lazy<RwLock<Option<ConfigState>>> = Lazy::new(|| RwLock::new(None));
+/// Values set with `config set --session`, applied over every source until `config save`.
+static SESSION_VALUES: Lazy<RwLock<BTreeMap<String, String>>> = Lazy::new(Default::default);
 
 pub fn init_config(cfg: impl Into<Arc<AppConfig>>) -> Result<(), AppError> {
     *CONFIG
@@ -87,6 +92,7 @@ pub enum ConfigSource {
     CustomFile,
     Environment,
     CliOption,
+    Session,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -97,9 +103,100 @@ pub struct AppConfig {
     pub settings: SettingsConfig,
     pub completion: CompletionConfig,
     pub background: BackgroundConfig,
+    pub bulk: BulkConfig,
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    #[serde(default)]
+    pub safety: SafetyConfig,
+    #[serde(default)]
+    pub user: UserConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub lookup: LookupConfig,
+    #[serde(default)]
+    pub data: DataConfig,
     pub repl: ReplConfig,
     pub relations: RelationsConfig,
     pub output: OutputConfig,
+    /// Options applied to a command when its line does not set them, keyed by command path.
+    #[serde(default, deserialize_with = "deserialize_command_defaults")]
+    pub defaults: CommandDefaults,
+    /// Named server connections, selected with `--profile` or `profile use`.
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
+    /// The profile the server settings were last switched to, if any.
+    #[serde(skip)]
+    pub profile: Option<String>,
+    /// The `[server]` section as loaded, which every profile is applied to.
+    #[serde(skip)]
+    pub base_server: Option<ServerConfig>,
+}
+
+impl AppConfig {
+    /// Points the server settings at the named profile. Settings the profile leaves out
+    /// come from the `[server]` section, not from the profile used before.
+    pub fn use_profile(&mut self, name: &str) -> Result<(), AppError> {
+        let profile = self.profiles.get(name).ok_or_else(|| {
+            AppError::GeneralConfigError(format!(
+                "Unknown profile: {name}. Configured profiles: {}",
+                match self.profile_names().join(", ") {
+                    names if names.is_empty() => "none".to_string(),
+                    names => names,
+                }
+            ))
+        })?;
+        let mut server = self
+            .base_server
+            .get_or_insert_with(|| self.server.clone())
+            .clone();
+        if let Some(hostname) = &profile.hostname {
+            server.hostname = hostname.clone();
+        }
+        if let Some(port) = profile.port {
+            server.port = port;
+        }
+        if let Some(protocol) = &profile.protocol {
+            server.protocol = protocol.clone();
+        }
+        if let Some(username) = &profile.username {
+            server.username = username.clone();
+        }
+        self.server = server;
+        self.profile = Some(name.to_string());
+        Ok(())
+    }
+
+    /// The active profile, when its section marks it `production = true`.
+    pub fn production_profile(&self) -> Option<&str> {
+        self.profile.as_deref().filter(|name| {
+            self.profiles
+                .get(*name)
+                .is_some_and(|profile| profile.production)
+        })
+    }
+
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names = self.profiles.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+}
+
+/// Connection settings of one `[profiles.<name>]` section.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProfileConfig {
+    #[serde(default)]
+    pub hostname: Option<String>,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub protocol: Option<Protocol>,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Whether `apply` and `import submit` ask for the profile name before writing.
+    #[serde(default)]
+    pub production: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -118,7 +215,7 @@ pub struct UserPreferences {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UserOutputPreferences {
-    pub format: OutputFormat,
+    pub format: RenderFormat,
     pub color: OutputColor,
     pub theme: String,
     pub padding: i8,
@@ -134,6 +231,14 @@ pub struct UserOutputPreferences {
     pub object_list_class_aliases: HashMap<String, HashMap<String, Vec<String>>>,
     #[serde(default)]
     pub object_class_computed_fields: HashMap<String, ComputedFieldSet>,
+    #[serde(default)]
+    pub mask_keys: Vec<String>,
+    #[serde(default)]
+    pub locale_numbers: bool,
+    #[serde(default)]
+    pub sort_collation: SortCollation,
+    #[serde(default)]
+    pub piped_format: Option<RenderFormat>,
 }
 
 impl UserPreferences {
@@ -144,7 +249,7 @@ impl UserPreferences {
             repl: config.repl.clone(),
             relations: config.relations.clone(),
             output: UserOutputPreferences {
-                format: config.output.format.clone(),
+                format: config.output.format,
                 color: config.output.color,
                 theme: config.output.theme.clone(),
                 padding: config.output.padding,
@@ -158,6 +263,10 @@ impl UserPreferences {
                 object_list_class_columns: config.output.object_list_class_columns.clone(),
                 object_list_class_aliases: config.output.object_list_class_aliases.clone(),
                 object_class_computed_fields: config.output.object_class_computed_fields.clone(),
+                mask_keys: config.output.mask_keys.clone(),
+                locale_numbers: config.output.locale_numbers,
+                sort_collation: config.output.sort_collation,
+                piped_format: config.output.piped_format,
             },
         }
     }
@@ -175,10 +284,50 @@ pub struct ServerConfig {
     #[serde(default)]
     pub password: Option<String>,
     #[serde(default)]
+    pub password_command: Option<String>,
+    #[serde(default)]
+    pub encryption_key_command: Option<String>,
+    #[serde(default)]
     pub token_file: Option<String>,
+    pub token_prune_days: u64,
     pub protocol: Protocol,
 }
 
+impl ServerConfig {
+    /// The same server and user without the credentials of the current login, so the
+    /// new login uses its cached token or asks for its password.
+    pub fn without_credentials(self) -> Self {
+        Self {
+            password: None,
+            password_command: None,
+            token_file: None,
+            ..self
+        }
+    }
+
+    /// Server root URL, without any API path.
+    pub fn server_url(&self) -> String {
+        format!("{}://{}:{}", self.protocol, self.hostname, self.port)
+    }
+
+    /// Base URL for API clients, after checking that `api_version` is one this CLI speaks.
+    ///
+    /// `hubuum_client` addresses every endpoint below `/api/v1`, so a different configured
+    /// version is rejected instead of silently talking to the wrong API.
+    pub fn api_base_url(&self) -> Result<BaseUrl, AppError> {
+        if self.api_version != Defaults::API_VERSION {
+            return Err(AppError::GeneralConfigError(format!(
+                "server.api_version is '{}', but this CLI only speaks the Hubuum {} API. Set server.api_version to '{}' or use a hubuum-cli release that supports {}",
+                self.api_version,
+                Defaults::API_VERSION,
+                Defaults::API_VERSION,
+                self.api_version
+            )));
+        }
+        Ok(BaseUrl::from_str(&self.server_url())?)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CacheConfig {
     pub time: u64,
@@ -189,6 +338,14 @@ pub struct CacheConfig {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CompletionConfig {
     pub disable_api_related: bool,
+    /// Seconds that class, collection, and group names fetched for completion are
+    /// reused across commands; 0 fetches them again after every command.
+    #[serde(default = "default_completion_cache_time")]
+    pub cache_time: u64,
+}
+
+fn default_completion_cache_time() -> u64 {
+    Defaults::COMPLETION_CACHE_TIME
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -196,6 +353,174 @@ pub struct BackgroundConfig {
     pub poll_interval_seconds: u64,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BulkConfig {
+    pub concurrency: u16,
+    pub rate_limit: RateLimit,
+}
+
+/// Commands run before (`pre`) or after (`post`) a CLI command, keyed by command path.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct HooksConfig {
+    #[serde(default)]
+    pub pre: HashMap<String, String>,
+    #[serde(default)]
+    pub post: HashMap<String, String>,
+}
+
+impl HooksConfig {
+    /// Whether a `pre` or `post` hook runs around `command_path`.
+    pub fn covers(&self, command_path: &[String]) -> bool {
+        let command = command_path.join(" ");
+        self.pre.contains_key(&command) || self.post.contains_key(&command)
+    }
+}
+
+/// Guard rails for destructive commands.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SafetyConfig {
+    /// Collections whose deletions, permission revokes, and group member removals
+    /// require typing the collection name to confirm.
+    #[serde(default, deserialize_with = "deserialize_string_list")]
+    pub protected_collections: Vec<String>,
+}
+
+impl SafetyConfig {
+    pub fn has_protected_collections(&self) -> bool {
+        !self.protected_collections.is_empty()
+    }
+
+    pub fn is_protected(&self, collection: &str) -> bool {
+        self.protected_collections
+            .iter()
+            .any(|protected| protected == collection)
+    }
+}
+
+/// Settings for user management commands.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UserConfig {
+    /// Command that receives the credentials of newly created users instead of the terminal.
+    #[serde(default)]
+    pub credential_delivery: Option<String>,
+}
+
+/// Settings for where login tokens are kept.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AuthConfig {
+    /// Keep cached tokens in the OS keychain instead of `token.json`.
+    #[serde(default)]
+    pub use_keyring: bool,
+    /// Encrypt `token.json` with the key printed by `server.encryption_key_command`.
+    #[serde(default)]
+    pub encrypt_token_file: bool,
+}
+
+/// Settings for resolving the names given to commands.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LookupConfig {
+    /// Policy for names that do not match exactly: `exact`, `ci`, or `contains`.
+    #[serde(default)]
+    pub name_match: NameMatch,
+}
+
+/// Limits on the JSON data of objects sent to the server.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DataConfig {
+    /// Largest serialized data in bytes; 0 turns the check off.
+    #[serde(default = "default_data_max_size")]
+    pub max_size: u64,
+    /// Deepest nesting of objects and arrays; 0 turns the check off.
+    #[serde(default = "default_data_max_depth")]
+    pub max_depth: u64,
+    /// Whether data past a limit is sent with a warning or refused.
+    #[serde(default)]
+    pub limit_action: DataLimitAction,
+}
+
+impl Default for DataConfig {
+    fn default() -> Self {
+        Self {
+            max_size: Defaults::DATA_MAX_SIZE,
+            max_depth: Defaults::DATA_MAX_DEPTH,
+            limit_action: Defaults::DATA_LIMIT_ACTION,
+        }
+    }
+}
+
+fn default_data_max_size() -> u64 {
+    Defaults::DATA_MAX_SIZE
+}
+
+fn default_data_max_depth() -> u64 {
+    Defaults::DATA_MAX_DEPTH
+}
+
+/// Accepts a TOML array or a comma-separated string, as environment variables provide.
+fn deserialize_string_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringList {
+        List(Vec<String>),
+        Joined(String),
+    }
+
+    Ok(match StringList::deserialize(deserializer)? {
+        StringList::List(values) => values,
+        StringList::Joined(joined) => split_string_list(&joined),
+    })
+}
+
+/// Accepts a single scalar or a list for each option, so `class = "Host"` and
+/// `show_data = true` read as naturally as `sort = ["name", "asc"]`.
+fn deserialize_command_defaults<'de, D>(deserializer: D) -> Result<CommandDefaults, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OptionValue {
+        List(Vec<String>),
+        Text(String),
+        Bool(bool),
+        Integer(i64),
+        Float(f64),
+    }
+
+    let defaults = HashMap::<String, HashMap<String, OptionValue>>::deserialize(deserializer)?;
+    Ok(defaults
+        .into_iter()
+        .map(|(command, options)| {
+            let options = options
+                .into_iter()
+                .map(|(name, value)| {
+                    let values = match value {
+                        OptionValue::List(values) => values,
+                        OptionValue::Text(text) => vec![text],
+                        OptionValue::Bool(flag) => vec![flag.to_string()],
+                        OptionValue::Integer(number) => vec![number.to_string()],
+                        OptionValue::Float(number) => vec![number.to_string()],
+                    };
+                    (name, values)
+                })
+                .collect();
+            (command, options)
+        })
+        .collect())
+}
+
+fn split_string_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ReplConfig {
     pub enter_fetches_next_page: bool,
@@ -209,7 +534,7 @@ pub struct RelationsConfig {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OutputConfig {
-    pub format: OutputFormat,
+    pub format: RenderFormat,
     pub color: OutputColor,
     pub theme: String,
     pub theme_file: String,
@@ -227,6 +552,18 @@ pub struct OutputConfig {
     pub object_list_class_aliases: HashMap<String, HashMap<String, Vec<String>>>,
     #[serde(default)]
     pub object_class_computed_fields: HashMap<String, ComputedFieldSet>,
+    /// Object data keys (`password`) or key paths (`db.password`) whose values are masked.
+    #[serde(default, deserialize_with = "deserialize_string_list")]
+    pub mask_keys: Vec<String>,
+    /// Group digits of counts (`1 234 567`) in text output.
+    #[serde(default)]
+    pub locale_numbers: bool,
+    /// How text is ordered when listings and `sort` stages sort it.
+    #[serde(default)]
+    pub sort_collation: SortCollation,
+    /// Format used instead of `format` when stdout is not a terminal and `--output` is not given.
+    #[serde(default)]
+    pub piped_format: Option<RenderFormat>,
     #[serde(default, rename = "object_list_class_meta", skip_serializing)]
     legacy_object_list_class_meta: HashMap<String, HashMap<String, Vec<String>>>,
 }
@@ -239,7 +576,10 @@ enum ConfigValueKind {
     U64,
     I8,
     I32,
+    RateLimit,
     Protocol,
+    StringMap,
+    StringList,
     OutputFormat,
     OutputColor,
     ThemeName,
@@ -248,7 +588,10 @@ enum ConfigValueKind {
     TableWrap,
     TableBands,
     EmptyResult,
+    NameMatch,
+    DataLimitAction,
     ObjectListDataColumns,
+    SortCollation,
     StringListMap,
     StringNestedListMap,
     ComputedFieldSetMap,
@@ -320,6 +663,20 @@ const CONFIG_KEYS: &[ConfigKeyDescriptor] = &[
         value_kind: ConfigValueKind::String,
         sensitive: true,
     },
+    ConfigKeyDescriptor {
+        key: "server.password_command",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__SERVER__PASSWORD_COMMAND",
+        value_kind: ConfigValueKind::String,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "server.encryption_key_command",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__SERVER__ENCRYPTION_KEY_COMMAND",
+        value_kind: ConfigValueKind::String,
+        sensitive: false,
+    },
     ConfigKeyDescriptor {
         key: "server.token_file",
         cli_arg: Some("token_file"),
@@ -327,6 +684,13 @@ const CONFIG_KEYS: &[ConfigKeyDescriptor] = &[
         value_kind: ConfigValueKind::String,
         sensitive: false,
     },
+    ConfigKeyDescriptor {
+        key: "server.token_prune_days",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__SERVER__TOKEN_PRUNE_DAYS",
+        value_kind: ConfigValueKind::U64,
+        sensitive: false,
+    },
     ConfigKeyDescriptor {
         key: "server.protocol",
         cli_arg: Some("protocol"),
@@ -362,6 +726,13 @@ const CONFIG_KEYS: &[ConfigKeyDescriptor] = &[
         value_kind: ConfigValueKind::Bool,
         sensitive: false,
     },
+    ConfigKeyDescriptor {
+        key: "completion.cache_time",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__COMPLETION__CACHE_TIME",
+        value_kind: ConfigValueKind::U64,
+        sensitive: false,
+    },
     ConfigKeyDescriptor {
         key: "background.poll_interval_seconds",
         cli_arg: Some("background_poll_interval"),
@@ -369,6 +740,97 @@ const CONFIG_KEYS: &[ConfigKeyDescriptor] = &[
         value_kind: ConfigValueKind::U64,
         sensitive: false,
     },
+    ConfigKeyDescriptor {
+        key: "bulk.concurrency",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__BULK__CONCURRENCY",
+        value_kind: ConfigValueKind::U16,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "bulk.rate_limit",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__BULK__RATE_LIMIT",
+        value_kind: ConfigValueKind::RateLimit,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "hooks.pre",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__HOOKS__PRE",
+        value_kind: ConfigValueKind::StringMap,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "hooks.post",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__HOOKS__POST",
+        value_kind: ConfigValueKind::StringMap,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "safety.protected_collections",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__SAFETY__PROTECTED_COLLECTIONS",
+        value_kind: ConfigValueKind::StringList,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "defaults",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__DEFAULTS",
+        value_kind: ConfigValueKind::StringNestedListMap,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "user.credential_delivery",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__USER__CREDENTIAL_DELIVERY",
+        value_kind: ConfigValueKind::String,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "auth.use_keyring",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__AUTH__USE_KEYRING",
+        value_kind: ConfigValueKind::Bool,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "auth.encrypt_token_file",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__AUTH__ENCRYPT_TOKEN_FILE",
+        value_kind: ConfigValueKind::Bool,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "lookup.name_match",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__LOOKUP__NAME_MATCH",
+        value_kind: ConfigValueKind::NameMatch,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "data.max_size",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__DATA__MAX_SIZE",
+        value_kind: ConfigValueKind::U64,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "data.max_depth",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__DATA__MAX_DEPTH",
+        value_kind: ConfigValueKind::U64,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "data.limit_action",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__DATA__LIMIT_ACTION",
+        value_kind: ConfigValueKind::DataLimitAction,
+        sensitive: false,
+    },
     ConfigKeyDescriptor {
         key: "repl.enter_fetches_next_page",
         cli_arg: None,
@@ -392,7 +854,7 @@ const CONFIG_KEYS: &[ConfigKeyDescriptor] = &[
     },
     ConfigKeyDescriptor {
         key: "output.format",
-        cli_arg: None,
+        cli_arg: Some("output_format"),
         env_var: "HUBUUM_CLI__OUTPUT__FORMAT",
         value_kind: ConfigValueKind::OutputFormat,
         sensitive: false,
@@ -495,6 +957,34 @@ const CONFIG_KEYS: &[ConfigKeyDescriptor] = &[
         value_kind: ConfigValueKind::ComputedFieldSetMap,
         sensitive: false,
     },
+    ConfigKeyDescriptor {
+        key: "output.mask_keys",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__OUTPUT__MASK_KEYS",
+        value_kind: ConfigValueKind::StringList,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "output.locale_numbers",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__OUTPUT__LOCALE_NUMBERS",
+        value_kind: ConfigValueKind::Bool,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "output.sort_collation",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__OUTPUT__SORT_COLLATION",
+        value_kind: ConfigValueKind::SortCollation,
+        sensitive: false,
+    },
+    ConfigKeyDescriptor {
+        key: "output.piped_format",
+        cli_arg: None,
+        env_var: "HUBUUM_CLI__OUTPUT__PIPED_FORMAT",
+        value_kind: ConfigValueKind::OutputFormat,
+        sensitive: false,
+    },
 ];
 
 impl Default for AppConfig {
@@ -508,7 +998,10 @@ impl Default for AppConfig {
                 identity_scope: None,
                 username: Defaults::USER_USERNAME.to_string(),
                 password: None,
+                password_command: None,
+                encryption_key_command: None,
                 token_file: None,
+                token_prune_days: Defaults::SERVER_TOKEN_PRUNE_DAYS,
                 protocol: Defaults::PROTOCOL,
             },
             cache: CacheConfig {
@@ -519,10 +1012,23 @@ impl Default for AppConfig {
             settings: SettingsConfig::default(),
             completion: CompletionConfig {
                 disable_api_related: Defaults::COMPLETION_DISABLE_API_RELATED,
+                cache_time: Defaults::COMPLETION_CACHE_TIME,
             },
             background: BackgroundConfig {
                 poll_interval_seconds: Defaults::BACKGROUND_POLL_INTERVAL_SECONDS,
             },
+            bulk: BulkConfig {
+                concurrency: Defaults::BULK_CONCURRENCY,
+                rate_limit: Defaults::BULK_RATE_LIMIT,
+            },
+            hooks: HooksConfig::default(),
+            safety: SafetyConfig::default(),
+            user: UserConfig::default(),
+            auth: AuthConfig::default(),
+            lookup: LookupConfig {
+                name_match: Defaults::LOOKUP_NAME_MATCH,
+            },
+            data: DataConfig::default(),
             repl: ReplConfig {
                 enter_fetches_next_page: Defaults::REPL_ENTER_FETCHES_NEXT_PAGE,
             },
@@ -546,8 +1052,16 @@ impl Default for AppConfig {
                 object_list_class_columns: HashMap::new(),
                 object_list_class_aliases: HashMap::new(),
                 object_class_computed_fields: HashMap::new(),
+                mask_keys: Vec::new(),
+                locale_numbers: Defaults::OUTPUT_LOCALE_NUMBERS,
+                sort_collation: Defaults::OUTPUT_SORT_COLLATION,
+                piped_format: None,
                 legacy_object_list_class_meta: HashMap::new(),
             },
+            defaults: CommandDefaults::new(),
+            profiles: HashMap::new(),
+            profile: None,
+            base_server: None,
         }
     }
 }
@@ -582,7 +1096,7 @@ pub fn config_value_candidates(key: &str) -> Vec<String> {
     match descriptor.value_kind {
         ConfigValueKind::Bool => strings(&["true", "false"]),
         ConfigValueKind::Protocol => strings(&["http", "https"]),
-        ConfigValueKind::OutputFormat => strings(&["text", "json"]),
+        ConfigValueKind::OutputFormat => strings(&["text", "json", "jsonl", "csv", "tsv"]),
         ConfigValueKind::OutputColor => strings(&["auto", "always", "never"]),
         ConfigValueKind::ThemeName => theme_value_candidates(),
         ConfigValueKind::TableStyle => {
@@ -592,15 +1106,21 @@ pub fn config_value_candidates(key: &str) -> Vec<String> {
         ConfigValueKind::TableWrap => strings(&["auto", "never"]),
         ConfigValueKind::TableBands => strings(&["auto", "always", "never"]),
         ConfigValueKind::EmptyResult => strings(&["message", "silent"]),
+        ConfigValueKind::NameMatch => strings(&["exact", "ci", "contains"]),
+        ConfigValueKind::DataLimitAction => strings(&["warn", "refuse"]),
         ConfigValueKind::ObjectListDataColumns => strings(&["auto", "preview", "all"]),
-        ConfigValueKind::StringListMap
+        ConfigValueKind::SortCollation => strings(&["ascii", "natural", "locale"]),
+        ConfigValueKind::StringMap
+        | ConfigValueKind::StringList
+        | ConfigValueKind::StringListMap
         | ConfigValueKind::StringNestedListMap
         | ConfigValueKind::ComputedFieldSetMap => Vec::new(),
         ConfigValueKind::String
         | ConfigValueKind::U16
         | ConfigValueKind::U64
         | ConfigValueKind::I8
-        | ConfigValueKind::I32 => Vec::new(),
+        | ConfigValueKind::I32
+        | ConfigValueKind::RateLimit => Vec::new(),
     }
 }
 
@@ -700,6 +1220,80 @@ pub fn set_persisted_value(key: &str, value: &str) -> Result<PathBuf, AppError>
     Ok(path)
 }
 
+/// Writes several plain config keys to the TOML file at `path`, creating it if needed.
+pub fn write_config_values(path: &Path, values: &[(&str, String)]) -> Result<(), AppError> {
+    let mut root = read_toml_file_for_update(path)?;
+    for (key, value) in values {
+        let descriptor = descriptor_for_key(key)?;
+        set_toml_path(
+            &mut root,
+            descriptor.key,
+            parse_config_value(descriptor, value)?,
+        )?;
+    }
+    write_toml_file(path, &root)
+}
+
+/// Sets `key` for the rest of this session without writing it to a config file.
+///
+/// The value is validated like a persisted one and takes effect on the next
+/// [`reload_runtime_config`].
+pub fn set_session_value(key: &str, value: &str) -> Result<(), AppError> {
+    let descriptor = descriptor_for_key(key)?;
+    parse_config_value(descriptor, value)?;
+    session_values()?.insert(descriptor.key.to_string(), value.to_string());
+    Ok(())
+}
+
+/// Forgets an unsaved session value, returning whether there was one.
+pub fn discard_session_value(key: &str) -> Result<bool, AppError> {
+    let key = descriptor_for_key(key).map_or(key, |descriptor| descriptor.key);
+    Ok(session_values()?.remove(key).is_some())
+}
+
+/// Writes every unsaved session value to the active config file and forgets them,
+/// returning the file and the keys written.
+pub fn save_session_values() -> Result<(PathBuf, Vec<String>), AppError> {
+    let mut values = session_values()?;
+    let path = get_config_state().paths.write_target.clone();
+    if !values.is_empty() {
+        let pairs = values
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.clone()))
+            .collect::<Vec<_>>();
+        write_config_values(&path, &pairs)?;
+    }
+    Ok((path, take(&mut *values).into_keys().collect()))
+}
+
+fn session_values() -> Result<RwLockWriteGuard<'static, BTreeMap<String, String>>, AppError> {
+    SESSION_VALUES
+        .write()
+        .map_err(|_| AppError::GeneralConfigError("Failed to update session config".to_string()))
+}
+
+/// The unsaved session values as a TOML document, for layering over the other sources.
+fn session_config_source() -> Result<Option<String>, AppError> {
+    session_config_toml(&*session_values()?)
+}
+
+fn session_config_toml(values: &BTreeMap<String, String>) -> Result<Option<String>, AppError> {
+    if values.is_empty() {
+        return Ok(None);
+    }
+    let mut root = TomlValue::Table(TomlMap::new());
+    for (key, value) in values.iter() {
+        set_toml_path(
+            &mut root,
+            key,
+            parse_config_value(descriptor_for_key(key)?, value)?,
+        )?;
+    }
+    format_toml(&root)
+        .map(Some)
+        .map_err(|err| AppError::ConfigError(err.to_string()))
+}
+
 pub fn unset_persisted_value(key: &str) -> Result<PathBuf, AppError> {
     if object_list_class_columns_key(key).is_some()
         || object_class_computed_fields_key(key).is_some()
@@ -780,16 +1374,25 @@ fn merge_user_preferences(
 pub fn reload_runtime_config() -> Result<(), AppError> {
     let previous_state = get_config_state();
     let custom = previous_state.paths.custom.clone();
+    let session_keys = session_values()?.keys().cloned().collect::<HashSet<_>>();
     let runtime_cli_keys: Vec<String> = previous_state
         .entries
         .iter()
         .filter(|entry| entry.source == ConfigSource::CliOption)
+        .filter(|entry| !session_keys.contains(&entry.key))
         .map(|entry| entry.key.clone())
         .collect();
 
     let mut config = load_config(custom.clone())?;
+    let previous_config = get_config();
+    if let Some(profile) = previous_config
+        .profile
+        .as_deref()
+        .filter(|profile| config.profiles.contains_key(*profile))
+    {
+        config.use_profile(profile)?;
+    }
     if !runtime_cli_keys.is_empty() {
-        let previous_config = get_config();
         apply_runtime_overrides(&mut config, &previous_config, &runtime_cli_keys);
     }
 
@@ -803,11 +1406,17 @@ pub fn reload_runtime_config() -> Result<(), AppError> {
         .map(str::to_string)
         .collect();
 
-    let state = if runtime_cli_args.is_empty() {
+    let mut state = if runtime_cli_args.is_empty() {
         inspect_config_state_without_cli(&config, custom)
     } else {
         inspect_config_state_with_runtime_cli(&config, custom, &runtime_cli_args)
     };
+    for entry in &mut state.entries {
+        if session_keys.contains(&entry.key) {
+            entry.source = ConfigSource::Session;
+            entry.source_detail = Some("unsaved".to_string());
+        }
+    }
     init_config_state(state)?;
     init_config(config)?;
     Ok(())
@@ -824,7 +1433,16 @@ fn apply_runtime_overrides(target: &mut AppConfig, source: &AppConfig, keys: &[S
             }
             "server.username" => target.server.username = source.server.username.clone(),
             "server.password" => target.server.password = source.server.password.clone(),
+            "server.password_command" => {
+                target.server.password_command = source.server.password_command.clone()
+            }
+            "server.encryption_key_command" => {
+                target.server.encryption_key_command = source.server.encryption_key_command.clone()
+            }
             "server.token_file" => target.server.token_file = source.server.token_file.clone(),
+            "server.token_prune_days" => {
+                target.server.token_prune_days = source.server.token_prune_days
+            }
             "server.protocol" => target.server.protocol = source.server.protocol.clone(),
             "cache.time" => target.cache.time = source.cache.time,
             "cache.size" => target.cache.size = source.cache.size,
@@ -832,9 +1450,14 @@ fn apply_runtime_overrides(target: &mut AppConfig, source: &AppConfig, keys: &[S
             "completion.disable_api_related" => {
                 target.completion.disable_api_related = source.completion.disable_api_related;
             }
+            "completion.cache_time" => target.completion.cache_time = source.completion.cache_time,
             "background.poll_interval_seconds" => {
                 target.background.poll_interval_seconds = source.background.poll_interval_seconds;
             }
+            "lookup.name_match" => target.lookup.name_match = source.lookup.name_match,
+            "data.max_size" => target.data.max_size = source.data.max_size,
+            "data.max_depth" => target.data.max_depth = source.data.max_depth,
+            "data.limit_action" => target.data.limit_action = source.data.limit_action,
             "relations.ignore_same_class" => {
                 target.relations.ignore_same_class = source.relations.ignore_same_class;
             }
@@ -857,6 +1480,12 @@ fn apply_runtime_overrides(target: &mut AppConfig, source: &AppConfig, keys: &[S
                 target.output.object_class_computed_fields =
                     source.output.object_class_computed_fields.clone();
             }
+            "output.format" => target.output.format = source.output.format,
+            "output.mask_keys" => target.output.mask_keys = source.output.mask_keys.clone(),
+            "output.locale_numbers" => target.output.locale_numbers = source.output.locale_numbers,
+            "output.sort_collation" => target.output.sort_collation = source.output.sort_collation,
+            "output.piped_format" => target.output.piped_format = source.output.piped_format,
+            "defaults" => target.defaults = source.defaults.clone(),
             "output.color" => target.output.color = source.output.color,
             "output.theme" => target.output.theme = source.output.theme.clone(),
             "output.theme_file" => target.output.theme_file = source.output.theme_file.clone(),
@@ -914,8 +1543,15 @@ pub fn load_config(cli_config_path: Option<PathBuf>) -> Result<AppConfig, Config
             "output.object_class_computed_fields",
             HashMap::<String, Vec<String>>::new(),
         )?
+        .set_default("output.mask_keys", Vec::<String>::new())?
+        .set_default("output.locale_numbers", Defaults::OUTPUT_LOCALE_NUMBERS)?
+        .set_default(
+            "output.sort_collation",
+            Defaults::OUTPUT_SORT_COLLATION.to_string(),
+        )?
         .set_default("server.hostname", Defaults::SERVER_HOSTNAME)?
         .set_default("server.port", Defaults::SERVER_PORT)?
+        .set_default("server.token_prune_days", Defaults::SERVER_TOKEN_PRUNE_DAYS)?
         .set_default("server.ssl_validation", Defaults::SERVER_SSL_VALIDATION)?
         .set_default("server.api_version", Defaults::API_VERSION)?
         .set_default("server.username", Defaults::USER_USERNAME)?
@@ -928,10 +1564,23 @@ pub fn load_config(cli_config_path: Option<PathBuf>) -> Result<AppConfig, Config
             "completion.disable_api_related",
             Defaults::COMPLETION_DISABLE_API_RELATED,
         )?
+        .set_default("completion.cache_time", Defaults::COMPLETION_CACHE_TIME)?
         .set_default(
             "background.poll_interval_seconds",
             Defaults::BACKGROUND_POLL_INTERVAL_SECONDS,
         )?
+        .set_default("bulk.concurrency", Defaults::BULK_CONCURRENCY)?
+        .set_default("bulk.rate_limit", Defaults::BULK_RATE_LIMIT)?
+        .set_default("hooks.pre", HashMap::<String, String>::new())?
+        .set_default("hooks.post", HashMap::<String, String>::new())?
+        .set_default("safety.protected_collections", Vec::<String>::new())?
+        .set_default("defaults", CommandDefaults::new())?
+        .set_default("auth.use_keyring", false)?
+        .set_default("auth.encrypt_token_file", false)?
+        .set_default("lookup.name_match", Defaults::LOOKUP_NAME_MATCH.to_string())?
+        .set_default("data.max_size", Defaults::DATA_MAX_SIZE)?
+        .set_default("data.max_depth", Defaults::DATA_MAX_DEPTH)?
+        .set_default("data.limit_action", Defaults::DATA_LIMIT_ACTION.to_string())?
         .set_default(
             "repl.enter_fetches_next_page",
             Defaults::REPL_ENTER_FETCHES_NEXT_PAGE,
@@ -954,9 +1603,16 @@ pub fn load_config(cli_config_path: Option<PathBuf>) -> Result<AppConfig, Config
         builder = builder.add_source(File::from(config_path).required(true));
     }
 
+    // 5. Values set for this session with `config set --session`
+    let session = session_config_source().map_err(|err| ConfigError::Message(err.to_string()))?;
+    if let Some(session) = session {
+        builder = builder.add_source(File::from_str(&session, FileFormat::Toml));
+    }
+
     let config = builder.build()?;
     let mut config: AppConfig = config.try_deserialize()?;
     merge_legacy_object_list_class_aliases(&mut config.output);
+    config.base_server = Some(config.server.clone());
     Ok(config)
 }
 
@@ -1106,6 +1762,7 @@ fn cli_flag_name(arg: &str) -> Option<&'static str> {
         "table_wrap" => Some("--table-wrap"),
         "table_bands" => Some("--table-bands"),
         "empty_result" => Some("--empty-result"),
+        "output_format" => Some("--output"),
         "output_object_show_data" => Some("--output-object-show-data"),
         _ => None,
     }
@@ -1122,7 +1779,14 @@ fn config_value<'a>(config: &'a AppConfig, key: &str) -> ConfigValueRef<'a> {
         }
         "server.username" => ConfigValueRef::String(&config.server.username),
         "server.password" => ConfigValueRef::OptionalString(config.server.password.as_deref()),
+        "server.password_command" => {
+            ConfigValueRef::OptionalString(config.server.password_command.as_deref())
+        }
+        "server.encryption_key_command" => {
+            ConfigValueRef::OptionalString(config.server.encryption_key_command.as_deref())
+        }
         "server.token_file" => ConfigValueRef::OptionalString(config.server.token_file.as_deref()),
+        "server.token_prune_days" => ConfigValueRef::U64(config.server.token_prune_days),
         "server.protocol" => ConfigValueRef::Protocol(&config.server.protocol),
         "cache.time" => ConfigValueRef::U64(config.cache.time),
         "cache.size" => ConfigValueRef::I32(config.cache.size),
@@ -1131,9 +1795,27 @@ fn config_value<'a>(config: &'a AppConfig, key: &str) -> ConfigValueRef<'a> {
         "completion.disable_api_related" => {
             ConfigValueRef::Bool(config.completion.disable_api_related)
         }
+        "completion.cache_time" => ConfigValueRef::U64(config.completion.cache_time),
         "background.poll_interval_seconds" => {
             ConfigValueRef::U64(config.background.poll_interval_seconds)
         }
+        "bulk.concurrency" => ConfigValueRef::U16(config.bulk.concurrency),
+        "bulk.rate_limit" => ConfigValueRef::RateLimit(&config.bulk.rate_limit),
+        "hooks.pre" => ConfigValueRef::StringMap(&config.hooks.pre),
+        "hooks.post" => ConfigValueRef::StringMap(&config.hooks.post),
+        "safety.protected_collections" => {
+            ConfigValueRef::StringList(&config.safety.protected_collections)
+        }
+        "defaults" => ConfigValueRef::StringNestedListMap(&config.defaults),
+        "user.credential_delivery" => {
+            ConfigValueRef::OptionalString(config.user.credential_delivery.as_deref())
+        }
+        "auth.use_keyring" => ConfigValueRef::Bool(config.auth.use_keyring),
+        "auth.encrypt_token_file" => ConfigValueRef::Bool(config.auth.encrypt_token_file),
+        "lookup.name_match" => ConfigValueRef::NameMatch(&config.lookup.name_match),
+        "data.max_size" => ConfigValueRef::U64(config.data.max_size),
+        "data.max_depth" => ConfigValueRef::U64(config.data.max_depth),
+        "data.limit_action" => ConfigValueRef::DataLimitAction(&config.data.limit_action),
         "repl.enter_fetches_next_page" => ConfigValueRef::Bool(config.repl.enter_fetches_next_page),
         "relations.ignore_same_class" => ConfigValueRef::Bool(config.relations.ignore_same_class),
         "relations.max_depth" => ConfigValueRef::I32(config.relations.max_depth),
@@ -1160,6 +1842,12 @@ fn config_value<'a>(config: &'a AppConfig, key: &str) -> ConfigValueRef<'a> {
         "output.object_class_computed_fields" => {
             ConfigValueRef::ComputedFieldSetMap(&config.output.object_class_computed_fields)
         }
+        "output.mask_keys" => ConfigValueRef::StringList(&config.output.mask_keys),
+        "output.locale_numbers" => ConfigValueRef::Bool(config.output.locale_numbers),
+        "output.sort_collation" => ConfigValueRef::SortCollation(&config.output.sort_collation),
+        "output.piped_format" => {
+            ConfigValueRef::OptionalOutputFormat(config.output.piped_format.as_ref())
+        }
         _ => ConfigValueRef::String(""),
     }
 }
@@ -1172,15 +1860,22 @@ enum ConfigValueRef<'a> {
     U64(u64),
     I8(i8),
     I32(i32),
+    RateLimit(&'a RateLimit),
     Protocol(&'a Protocol),
-    OutputFormat(&'a OutputFormat),
+    OutputFormat(&'a RenderFormat),
+    OptionalOutputFormat(Option<&'a RenderFormat>),
     OutputColor(&'a OutputColor),
     TableStyle(&'a TableStyle),
     TableWidth(&'a TableWidth),
     TableWrap(&'a TableWrap),
     TableBands(&'a TableBands),
     EmptyResult(&'a EmptyResult),
+    NameMatch(&'a NameMatch),
+    DataLimitAction(&'a DataLimitAction),
     ObjectListDataColumns(&'a ObjectListDataColumns),
+    SortCollation(&'a SortCollation),
+    StringMap(&'a HashMap<String, String>),
+    StringList(&'a [String]),
     StringListMap(&'a HashMap<String, Vec<String>>),
     StringNestedListMap(&'a HashMap<String, HashMap<String, Vec<String>>>),
     ComputedFieldSetMap(&'a HashMap<String, ComputedFieldSet>),
@@ -1205,18 +1900,23 @@ fn display_config_value(value: ConfigValueRef<'_>, sensitive: bool) -> String {
         ConfigValueRef::U64(value) => value.to_string(),
         ConfigValueRef::I8(value) => value.to_string(),
         ConfigValueRef::I32(value) => value.to_string(),
+        ConfigValueRef::RateLimit(value) => value.to_string(),
         ConfigValueRef::Protocol(value) => value.to_string(),
-        ConfigValueRef::OutputFormat(value) => match value {
-            OutputFormat::Json => "json".to_string(),
-            OutputFormat::Text => "text".to_string(),
-        },
+        ConfigValueRef::OutputFormat(value) => value.to_string(),
+        ConfigValueRef::OptionalOutputFormat(Some(value)) => value.to_string(),
+        ConfigValueRef::OptionalOutputFormat(None) => "<unset>".to_string(),
         ConfigValueRef::OutputColor(value) => value.to_string(),
         ConfigValueRef::TableStyle(value) => value.to_string(),
         ConfigValueRef::TableWidth(value) => value.to_string(),
         ConfigValueRef::TableWrap(value) => value.to_string(),
         ConfigValueRef::TableBands(value) => value.to_string(),
         ConfigValueRef::EmptyResult(value) => value.to_string(),
+        ConfigValueRef::NameMatch(value) => value.to_string(),
+        ConfigValueRef::DataLimitAction(value) => value.to_string(),
         ConfigValueRef::ObjectListDataColumns(value) => value.to_string(),
+        ConfigValueRef::SortCollation(value) => value.to_string(),
+        ConfigValueRef::StringMap(value) => to_json_string(value).unwrap_or_default(),
+        ConfigValueRef::StringList(value) => value.join(","),
         ConfigValueRef::StringListMap(value) => to_json_string(value).unwrap_or_default(),
         ConfigValueRef::StringNestedListMap(value) => to_json_string(value).unwrap_or_default(),
         ConfigValueRef::ComputedFieldSetMap(value) => to_json_string(value).unwrap_or_default(),
@@ -1278,15 +1978,24 @@ fn parse_config_value(
         ConfigValueKind::U64 => TomlValue::Integer(value.parse::<u64>()? as i64),
         ConfigValueKind::I8 => TomlValue::Integer(i64::from(value.parse::<i8>()?)),
         ConfigValueKind::I32 => TomlValue::Integer(i64::from(value.parse::<i32>()?)),
+        ConfigValueKind::RateLimit => TomlValue::String(
+            value
+                .parse::<RateLimit>()
+                .map_err(AppError::ConfigError)?
+                .to_string(),
+        ),
         ConfigValueKind::Protocol => TomlValue::String(
             value
                 .parse::<Protocol>()
                 .map_err(AppError::ConfigError)?
                 .to_string(),
         ),
-        ConfigValueKind::OutputFormat => {
-            TomlValue::String(parse_output_format(value)?.to_string().to_lowercase())
-        }
+        ConfigValueKind::OutputFormat => TomlValue::String(
+            value
+                .parse::<RenderFormat>()
+                .map_err(AppError::ConfigError)?
+                .to_string(),
+        ),
         ConfigValueKind::OutputColor => TomlValue::String(
             value
                 .parse::<OutputColor>()
@@ -1327,12 +2036,39 @@ fn parse_config_value(
                 .map_err(AppError::ConfigError)?
                 .to_string(),
         ),
+        ConfigValueKind::NameMatch => TomlValue::String(
+            value
+                .parse::<NameMatch>()
+                .map_err(AppError::ConfigError)?
+                .to_string(),
+        ),
+        ConfigValueKind::DataLimitAction => TomlValue::String(
+            value
+                .parse::<DataLimitAction>()
+                .map_err(AppError::ConfigError)?
+                .to_string(),
+        ),
         ConfigValueKind::ObjectListDataColumns => TomlValue::String(
             value
                 .parse::<ObjectListDataColumns>()
                 .map_err(AppError::ConfigError)?
                 .to_string(),
         ),
+        ConfigValueKind::SortCollation => TomlValue::String(
+            value
+                .parse::<SortCollation>()
+                .map_err(AppError::ConfigError)?
+                .to_string(),
+        ),
+        ConfigValueKind::StringMap => {
+            parse_toml(value).map_err(|err| AppError::ConfigError(err.to_string()))?
+        }
+        ConfigValueKind::StringList => TomlValue::Array(
+            split_string_list(value)
+                .into_iter()
+                .map(TomlValue::String)
+                .collect(),
+        ),
         ConfigValueKind::StringListMap => {
             parse_toml(value).map_err(|err| AppError::ConfigError(err.to_string()))?
         }
@@ -1449,16 +2185,6 @@ fn set_persisted_object_list_class_alias(
     Ok(path)
 }
 
-fn parse_output_format(value: &str) -> Result<OutputFormat, AppError> {
-    match value.to_lowercase().as_str() {
-        "json" => Ok(OutputFormat::Json),
-        "text" => Ok(OutputFormat::Text),
-        _ => Err(AppError::ConfigError(format!(
-            "Invalid output format: {value}. Use 'json' or 'text'."
-        ))),
-    }
-}
-
 fn set_toml_path(root: &mut TomlValue, key: &str, value: TomlValue) -> Result<(), AppError> {
     let mut current = root;
     let mut parts = key.split('.').peekable();
@@ -1517,8 +2243,7 @@ fn write_toml_file(path: &Path, root: &TomlValue) -> Result<(), AppError> {
         create_dir_all(parent)?;
     }
     let rendered = format_toml(root).map_err(|err| AppError::ConfigError(err.to_string()))?;
-    write(path, rendered)?;
-    Ok(())
+    write_file_atomically(path, rendered.as_bytes())
 }
 
 #[cfg(test)]
@@ -1526,8 +2251,8 @@ mod tests {
     use super::*;
     use crate::cli::{build_cli, update_config_from_cli};
     use crate::models::{
-        EmptyResult, ObjectListDataColumns, OutputColor, Protocol, TableBands, TableStyle,
-        TableWidth, TableWrap,
+        EmptyResult, NameMatch, ObjectListDataColumns, OutputColor, Protocol, TableBands,
+        TableStyle, TableWidth, TableWrap,
     };
     use serial_test::serial;
     use std::env::{remove_var, set_var, temp_dir};
@@ -1546,16 +2271,28 @@ mod tests {
             "HUBUUM_CLI__SERVER__API_VERSION",
             "HUBUUM_CLI__SERVER__USERNAME",
             "HUBUUM_CLI__SERVER__PASSWORD",
+            "HUBUUM_CLI__SERVER__PASSWORD_COMMAND",
             "HUBUUM_CLI__SERVER__TOKEN_FILE",
+            "HUBUUM_CLI__SERVER__TOKEN_PRUNE_DAYS",
             "HUBUUM_CLI__SERVER__PROTOCOL",
             "HUBUUM_CLI__CACHE__TIME",
             "HUBUUM_CLI__CACHE__SIZE",
             "HUBUUM_CLI__CACHE__DISABLE",
             "HUBUUM_CLI__COMPLETION__DISABLE_API_RELATED",
             "HUBUUM_CLI__BACKGROUND__POLL_INTERVAL_SECONDS",
+            "HUBUUM_CLI__BULK__CONCURRENCY",
+            "HUBUUM_CLI__BULK__RATE_LIMIT",
+            "HUBUUM_CLI__HOOKS__PRE",
+            "HUBUUM_CLI__HOOKS__POST",
+            "HUBUUM_CLI__SAFETY__PROTECTED_COLLECTIONS",
+            "HUBUUM_CLI__LOOKUP__NAME_MATCH",
+            "HUBUUM_CLI__DATA__MAX_SIZE",
+            "HUBUUM_CLI__DATA__MAX_DEPTH",
+            "HUBUUM_CLI__DATA__LIMIT_ACTION",
             "HUBUUM_CLI__REPL__ENTER_FETCHES_NEXT_PAGE",
             "HUBUUM_CLI__RELATIONS__IGNORE_SAME_CLASS",
             "HUBUUM_CLI__RELATIONS__MAX_DEPTH",
+            "HUBUUM_CLI__OUTPUT__FORMAT",
             "HUBUUM_CLI__OUTPUT__COLOR",
             "HUBUUM_CLI__OUTPUT__THEME",
             "HUBUUM_CLI__OUTPUT__THEME_FILE",
@@ -1585,10 +2322,12 @@ mod tests {
         set_var("HUBUUM_CLI__SERVER__API_VERSION", "v9");
         set_var("HUBUUM_CLI__SERVER__USERNAME", "env_user");
         set_var("HUBUUM_CLI__SERVER__PASSWORD", "hunter2");
+        set_var("HUBUUM_CLI__SERVER__PASSWORD_COMMAND", "pass show hubuum");
         set_var(
             "HUBUUM_CLI__SERVER__TOKEN_FILE",
             "/run/secrets/hubuum-token",
         );
+        set_var("HUBUUM_CLI__SERVER__TOKEN_PRUNE_DAYS", "30");
         set_var("HUBUUM_CLI__SERVER__PROTOCOL", "http");
 
         set_var("HUBUUM_CLI__CACHE__TIME", "99");
@@ -1597,12 +2336,20 @@ mod tests {
 
         set_var("HUBUUM_CLI__COMPLETION__DISABLE_API_RELATED", "true");
         set_var("HUBUUM_CLI__BACKGROUND__POLL_INTERVAL_SECONDS", "7");
+        set_var("HUBUUM_CLI__BULK__CONCURRENCY", "8");
+        set_var("HUBUUM_CLI__BULK__RATE_LIMIT", "300/m");
+        set_var("HUBUUM_CLI__SAFETY__PROTECTED_COLLECTIONS", "prod, billing");
+        set_var("HUBUUM_CLI__LOOKUP__NAME_MATCH", "contains");
+        set_var("HUBUUM_CLI__DATA__MAX_SIZE", "4096");
+        set_var("HUBUUM_CLI__DATA__MAX_DEPTH", "0");
+        set_var("HUBUUM_CLI__DATA__LIMIT_ACTION", "warn");
         set_var("HUBUUM_CLI__REPL__ENTER_FETCHES_NEXT_PAGE", "true");
         set_var("HUBUUM_CLI__RELATIONS__IGNORE_SAME_CLASS", "false");
         set_var("HUBUUM_CLI__RELATIONS__MAX_DEPTH", "4");
         set_var("HUBUUM_CLI__OUTPUT__COLOR", "never");
         set_var("HUBUUM_CLI__OUTPUT__THEME", "solarized-dark");
         set_var("HUBUUM_CLI__OUTPUT__THEME_FILE", "/tmp/hubuum-themes.toml");
+        set_var("HUBUUM_CLI__OUTPUT__FORMAT", "csv");
         set_var("HUBUUM_CLI__OUTPUT__TABLE_STYLE", "plain");
         set_var("HUBUUM_CLI__OUTPUT__TABLE_WIDTH", "100");
         set_var("HUBUUM_CLI__OUTPUT__TABLE_WRAP", "never");
@@ -1620,10 +2367,15 @@ mod tests {
         assert_eq!(cfg.server.api_version, "v9");
         assert_eq!(cfg.server.username, "env_user");
         assert_eq!(cfg.server.password, Some("hunter2".into()));
+        assert_eq!(
+            cfg.server.password_command.as_deref(),
+            Some("pass show hubuum")
+        );
         assert_eq!(
             cfg.server.token_file.as_deref(),
             Some("/run/secrets/hubuum-token")
         );
+        assert_eq!(cfg.server.token_prune_days, 30);
         assert_eq!(cfg.server.protocol, Protocol::Http);
 
         assert_eq!(cfg.cache.time, 99);
@@ -1632,12 +2384,20 @@ mod tests {
 
         assert!(cfg.completion.disable_api_related);
         assert_eq!(cfg.background.poll_interval_seconds, 7);
+        assert_eq!(cfg.bulk.concurrency, 8);
+        assert_eq!(cfg.bulk.rate_limit.to_string(), "300/m");
+        assert_eq!(cfg.safety.protected_collections, vec!["prod", "billing"]);
+        assert_eq!(cfg.lookup.name_match, NameMatch::Contains);
+        assert_eq!(cfg.data.max_size, 4096);
+        assert_eq!(cfg.data.max_depth, 0);
+        assert_eq!(cfg.data.limit_action, DataLimitAction::Warn);
         assert!(cfg.repl.enter_fetches_next_page);
         assert!(!cfg.relations.ignore_same_class);
         assert_eq!(cfg.relations.max_depth, 4);
         assert_eq!(cfg.output.color, OutputColor::Never);
         assert_eq!(cfg.output.theme, "solarized-dark");
         assert_eq!(cfg.output.theme_file, "/tmp/hubuum-themes.toml");
+        assert_eq!(cfg.output.format, RenderFormat::Csv);
         assert_eq!(cfg.output.table_style, TableStyle::Plain);
         assert_eq!(cfg.output.table_width, TableWidth::Fixed(100));
         assert_eq!(cfg.output.table_wrap, TableWrap::Never);
@@ -1679,6 +2439,140 @@ Hosts = ["contact", "jack", "data.name"]
         clear_env();
     }
 
+    #[test]
+    #[serial]
+    fn hooks_load_from_toml_with_command_path_keys() {
+        clear_env();
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("config.toml");
+        write(
+            &path,
+            r#"
+[hooks]
+post."object delete" = "./notify.sh {class} {name}"
+"#,
+        )
+        .expect("write config");
+
+        let cfg = load_config(Some(path)).expect("load config");
+
+        assert_eq!(
+            cfg.hooks.post.get("object delete").map(String::as_str),
+            Some("./notify.sh {class} {name}")
+        );
+        assert!(cfg.hooks.pre.is_empty());
+        clear_env();
+    }
+
+    #[test]
+    #[serial]
+    fn session_values_apply_over_config_files_until_discarded() {
+        clear_env();
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("config.toml");
+        write(&path, "[output]\npadding = 4\n").expect("write config");
+
+        assert!(set_session_value("output.padding", "many").is_err());
+        assert!(set_session_value("output.nope", "1").is_err());
+        set_session_value("output.padding", "20").expect("valid session value");
+        set_session_value("cache.disable", "true").expect("valid session value");
+        let cfg = load_config(Some(path.clone())).expect("load config");
+        assert_eq!(cfg.output.padding, 20);
+        assert!(cfg.cache.disable);
+
+        assert!(discard_session_value("output.padding").expect("discard"));
+        assert!(discard_session_value("cache.disable").expect("discard"));
+        assert!(!discard_session_value("cache.disable").expect("discard"));
+        let cfg = load_config(Some(path)).expect("load config");
+        assert_eq!(cfg.output.padding, 4);
+    }
+
+    #[test]
+    #[serial]
+    fn profiles_override_only_the_server_settings_they_set() {
+        clear_env();
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("config.toml");
+        write(
+            &path,
+            r#"
+[server]
+hostname = "prod.example.com"
+port = 443
+username = "admin"
+
+[profiles.staging]
+hostname = "staging.example.com"
+port = 8443
+production = true
+
+[profiles.lab]
+hostname = "lab.example.com"
+protocol = "http"
+username = "tester"
+"#,
+        )
+        .expect("write config");
+
+        let mut cfg = load_config(Some(path)).expect("load config");
+        assert_eq!(cfg.profile_names(), vec!["lab", "staging"]);
+        assert_eq!(cfg.profile, None);
+
+        cfg.use_profile("staging").expect("staging is configured");
+        assert_eq!(cfg.server.hostname, "staging.example.com");
+        assert_eq!(cfg.server.port, 8443);
+        assert_eq!(cfg.server.username, "admin");
+        assert_eq!(cfg.profile.as_deref(), Some("staging"));
+        assert_eq!(cfg.production_profile(), Some("staging"));
+
+        cfg.use_profile("lab").expect("lab is configured");
+        assert_eq!(cfg.production_profile(), None);
+        assert_eq!(cfg.server.server_url(), "http://lab.example.com:443");
+        assert_eq!(cfg.server.username, "tester");
+
+        let error = cfg.use_profile("prod").expect_err("prod is not configured");
+        assert!(error
+            .to_string()
+            .contains("Configured profiles: lab, staging"));
+        assert_eq!(cfg.profile.as_deref(), Some("lab"));
+
+        cfg.use_profile("staging").expect("staging is configured");
+        assert_eq!(cfg.server.server_url(), "https://staging.example.com:8443");
+        assert_eq!(cfg.server.username, "admin");
+        clear_env();
+    }
+
+    #[test]
+    #[serial]
+    fn command_defaults_load_scalars_and_lists_from_toml() {
+        clear_env();
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("config.toml");
+        write(
+            &path,
+            r#"
+[defaults."object list"]
+class = "Host"
+show_data = true
+limit = 50
+sort = ["name", "asc"]
+"#,
+        )
+        .expect("write config");
+
+        let cfg = load_config(Some(path)).expect("load config");
+
+        let object_list = cfg
+            .defaults
+            .get("object list")
+            .expect("object list defaults");
+        assert_eq!(object_list["class"], vec!["Host"]);
+        assert_eq!(object_list["show_data"], vec!["true"]);
+        assert_eq!(object_list["limit"], vec!["50"]);
+        assert_eq!(object_list["sort"], vec!["name", "asc"]);
+        clear_env();
+    }
+
     #[test]
     #[serial]
     fn object_list_class_aliases_load_from_toml() {
@@ -1878,6 +2772,22 @@ os_version = ["data.os.macos.version", "data.os.redhat.version"]
         clear_env();
     }
 
+    #[test]
+    fn api_base_url_rejects_unsupported_api_versions() {
+        let mut server = AppConfig::default().server;
+        server.hostname = "hubuum.example".to_string();
+        server.port = 8443;
+        let base_url = server.api_base_url().expect("v1 should be accepted");
+        assert_eq!(base_url.as_str(), "https://hubuum.example:8443/");
+
+        server.api_version = "v2".to_string();
+        let error = server
+            .api_base_url()
+            .expect_err("v2 should be rejected")
+            .to_string();
+        assert!(error.contains("server.api_version is 'v2'"), "{error}");
+    }
+
     #[test]
     #[serial]
     fn mixing_env_and_defaults() {
@@ -1,6 +1,6 @@
 use crate::models::{
-    EmptyResult, ObjectListDataColumns, OutputColor, OutputFormat, Protocol, TableBands,
-    TableStyle, TableWidth, TableWrap,
+    DataLimitAction, EmptyResult, NameMatch, ObjectListDataColumns, OutputColor, Protocol,
+    RateLimit, RenderFormat, SortCollation, TableBands, TableStyle, TableWidth, TableWrap,
 };
 
 pub struct Defaults;
@@ -9,18 +9,26 @@ impl Defaults {
     pub const SERVER_HOSTNAME: &'static str = "localhost";
     pub const SERVER_PORT: u16 = 8080;
     pub const SERVER_SSL_VALIDATION: bool = true;
+    pub const SERVER_TOKEN_PRUNE_DAYS: u64 = 90;
     pub const USER_USERNAME: &'static str = "default_user";
     pub const CACHE_TIME: u64 = 3600;
     pub const CACHE_SIZE: i32 = 104_857_600; // 100 MB
     pub const CACHE_DISABLE: bool = false;
     pub const COMPLETION_DISABLE_API_RELATED: bool = false;
+    pub const COMPLETION_CACHE_TIME: u64 = 300;
     pub const BACKGROUND_POLL_INTERVAL_SECONDS: u64 = 2;
     pub const REPL_ENTER_FETCHES_NEXT_PAGE: bool = false;
+    pub const BULK_CONCURRENCY: u16 = 4;
+    pub const BULK_RATE_LIMIT: RateLimit = RateLimit::per_second(20);
     pub const API_VERSION: &'static str = "v1";
     pub const PROTOCOL: Protocol = Protocol::Https;
+    pub const LOOKUP_NAME_MATCH: NameMatch = NameMatch::Exact;
+    pub const DATA_MAX_SIZE: u64 = 1_048_576; // 1 MiB
+    pub const DATA_MAX_DEPTH: u64 = 32;
+    pub const DATA_LIMIT_ACTION: DataLimitAction = DataLimitAction::Refuse;
     pub const RELATIONS_IGNORE_SAME_CLASS: bool = true;
     pub const RELATIONS_MAX_DEPTH: i32 = 2;
-    pub const OUTPUT_FORMAT: OutputFormat = OutputFormat::Text;
+    pub const OUTPUT_FORMAT: RenderFormat = RenderFormat::Text;
     pub const OUTPUT_COLOR: OutputColor = OutputColor::Auto;
     pub const OUTPUT_THEME: &'static str = DEFAULT_THEME;
     pub const OUTPUT_THEME_FILE: &'static str = "";
@@ -31,6 +39,8 @@ impl Defaults {
     pub const OUTPUT_TABLE_BANDS: TableBands = TableBands::Auto;
     pub const OUTPUT_EMPTY_RESULT: EmptyResult = EmptyResult::Message;
     pub const OUTPUT_OBJECT_SHOW_DATA: bool = false;
+    pub const OUTPUT_LOCALE_NUMBERS: bool = false;
+    pub const OUTPUT_SORT_COLLATION: SortCollation = SortCollation::Ascii;
     pub const OUTPUT_OBJECT_LIST_DATA_COLUMNS: ObjectListDataColumns = ObjectListDataColumns::Auto;
 }
 use hubuum_theme::DEFAULT_THEME;
@@ -2,33 +2,51 @@ use std::sync::Arc;
 
 use hubuum_client::ApiError;
 use hubuum_filter::{split_pipeline, PipeStage};
+use log::debug;
 use shlex::split;
+use tokio::runtime::Handle;
+use tokio::task::spawn_blocking;
 
 use crate::app::{AppRuntime, SharedSession};
 use crate::catalog::{
-    CommandCatalog, CommandContext, CommandInvocation, CommandOutcome, ResolvedCommand, ScopeAction,
+    CommandCatalog, CommandContext, CommandInvocation, CommandOutcome, CommandSpec,
+    ResolvedCommand, ScopeAction,
 };
+use crate::command_defaults::default_arguments;
+use crate::commands::audit::render_audit_local;
 use crate::commands::auth::render_auth_providers;
-use crate::commands::config::{render_config_paths, render_config_show};
+use crate::commands::config::{render_config_encrypt, render_config_paths, render_config_show};
+use crate::commands::explain::render_explain;
+use crate::commands::help::{is_long_flag, render_manual};
 use crate::commands::metrics::render_metrics;
-use crate::commands::render_format;
+use crate::commands::profile::render_profile_doctor;
 use crate::commands::theme::{render_theme_list, render_theme_preview, render_theme_show};
-use crate::commands::version::render_version;
+use crate::commands::token::{render_token_list, render_token_prune};
+use crate::commands::version::{render_server_api_version, render_server_info, render_version};
+use crate::commands::{render_format, wants_reveal};
+use crate::config::{get_config, HooksConfig};
 use crate::errors::AppError;
+use crate::masking::set_reveal;
+use crate::offline::{is_offline, offline_error_message, refused_change_error};
 use crate::output::{
-    add_error, add_warning, append_line, reset_output, set_pipeline, set_pipeline_suffix,
-    set_render_format, take_output, OutputSnapshot,
+    add_error, add_warning, append_json, append_line, page_output, reset_output, set_pipeline,
+    set_pipeline_suffix, set_render_format, take_output, OutputSnapshot, RenderFormat,
 };
 use crate::redirection::{split_redirect_candidate, OutputRedirect};
-use crate::tokenizer::CommandTokenizer;
+use crate::services::{is_unauthorized, CompletionContext};
+use crate::tokenizer::{join_raw_tail, split_raw_tail, CommandTokenizer};
+use crate::transaction::{is_transaction_command, QueuedCommand, BEGIN, COMMIT, ROLLBACK};
 
 pub async fn execute_line(
     app: Arc<AppRuntime>,
     session: &SharedSession,
     line: &str,
 ) -> Result<CommandOutcome, AppError> {
-    let (line, redirect) = prepare_redirect(&app.catalog, &session.scope(), line)?;
-    let mut outcome = execute_line_inner(app, session, &line).await?;
+    // A `--<name>-raw` tail is taken verbatim, so only the text before it may hold a redirect.
+    let (head, raw_tail) = split_raw_tail(line, &app.catalog.raw_option_names());
+    let (head, redirect) = prepare_redirect(&app.catalog, &session.scope(), head)?;
+    let line = join_raw_tail(&head, raw_tail);
+    let mut outcome = execute_line_inner(app, session, &line, redirect.is_some()).await?;
     outcome.redirect = redirect;
     Ok(outcome)
 }
@@ -37,9 +55,12 @@ async fn execute_line_inner(
     app: Arc<AppRuntime>,
     session: &SharedSession,
     line: &str,
+    redirected: bool,
 ) -> Result<CommandOutcome, AppError> {
     reset_output()?;
-    let (mut line, mut pipeline, mut pipeline_suffix) = process_filter(line)?;
+    let original_line = line;
+    let (head, mut raw_tail) = split_raw_tail(line, &app.catalog.raw_option_names());
+    let (mut line, mut pipeline, mut pipeline_suffix) = process_filter(head)?;
     let mut parts =
         split(&line).ok_or_else(|| AppError::ParseError("Parsing input failed".to_string()))?;
 
@@ -51,6 +72,7 @@ async fn execute_line_inner(
         line = next_line;
         pipeline = next_pipeline;
         pipeline_suffix = next_pipeline_suffix;
+        raw_tail = None;
         parts =
             split(&line).ok_or_else(|| AppError::ParseError("Parsing input failed".to_string()))?;
     }
@@ -84,6 +106,10 @@ async fn execute_line_inner(
         });
     }
 
+    if is_transaction_command(&parts) {
+        return execute_transaction_command(app, session, &parts[0]).await;
+    }
+
     if app.catalog.resolve_scope(&current_scope, &parts).is_some() {
         let mut next_scope = current_scope;
         next_scope.extend(parts);
@@ -106,9 +132,30 @@ async fn execute_line_inner(
         .iter()
         .map(|option| option.to_cli_option())
         .collect::<Vec<_>>();
-    let tokens =
-        CommandTokenizer::new_without_value_source_resolution(&line, &cmd_name, &option_defs)?;
+    let mut tokens = CommandTokenizer::new_without_value_source_resolution(
+        &join_raw_tail(&line, raw_tail),
+        &cmd_name,
+        &option_defs,
+    )?
+    .with_command_path(&resolved.command_path);
+    let defaults = default_arguments(
+        &get_config().defaults,
+        &resolved.command_path,
+        &option_defs,
+        &tokens,
+    )?;
+    if !defaults.is_empty() {
+        line = format!("{line} {}", defaults.join(" "));
+        tokens = CommandTokenizer::new_without_value_source_resolution(
+            &join_raw_tail(&line, raw_tail),
+            &cmd_name,
+            &option_defs,
+        )?
+        .with_command_path(&resolved.command_path);
+    }
+    let line = join_raw_tail(&line, raw_tail);
     set_render_format(render_format(&tokens)?)?;
+    set_reveal(wants_reveal(&tokens));
     let options = tokens.get_options();
     if options.contains_key("help") || options.contains_key("h") {
         return render_help(
@@ -117,20 +164,187 @@ async fn execute_line_inner(
             &resolved.command_path[resolved.scope_path.len()..],
         );
     }
+    if options.contains_key("explain") {
+        render_explain(&resolved.command_path, &option_defs, &tokens)?;
+        return Ok(CommandOutcome {
+            output: take_output()?,
+            ..Default::default()
+        });
+    }
+    if session.in_transaction() && resolved.command.mutating {
+        return queue_transaction_command(
+            session,
+            current_scope,
+            original_line,
+            resolved.command,
+            &tokens,
+        );
+    }
+    if is_offline() && resolved.command.mutating {
+        return Err(refused_change_error(&resolved.command_path.join(" ")));
+    }
     let invocation = CommandInvocation {
         raw_line: line.clone(),
         command_path: resolved.command_path.clone(),
         pipeline,
         pipeline_suffix,
+        redirected,
+    };
+    let ctx = CommandContext {
+        app: app.clone(),
+        session: session.clone(),
     };
-    let ctx = CommandContext { app: app.clone() };
 
-    resolved.command.handler.execute(ctx, invocation).await
+    // An expired token fails the command's first request. After logging in again, only
+    // commands that are safe to repeat are run once more; the rest ask to be rerun.
+    let gateway = app.services.gateway();
+    let generation = gateway.session_generation();
+    let command_path = resolved.command_path.clone();
+    match resolved
+        .command
+        .handler
+        .execute(ctx.clone(), invocation.clone())
+        .await
+    {
+        Err(error) if is_unauthorized(&error) && !resolved.command.own_credentials => {
+            debug!("Session token rejected, logging in again: {error}");
+            spawn_blocking(move || gateway.renew_session(generation))
+                .await
+                .map_err(|err| AppError::CommandExecutionError(err.to_string()))??;
+            if !is_repeatable(resolved.command, &command_path, &ctx.config().hooks) {
+                return Err(AppError::CommandExecutionError(format!(
+                    "The session token was rejected and has been renewed; '{}' was not \
+                     retried in case it already made changes. Run it again.",
+                    command_path.join(" ")
+                )));
+            }
+            reset_output()?;
+            set_render_format(render_format(&tokens)?)?;
+            add_warning("Session token rejected; logged in again and retried the command")?;
+            resolved.command.handler.execute(ctx, invocation).await
+        }
+        result => result,
+    }
+}
+
+/// Whether running `command_path` again after a rejected token cannot repeat a change:
+/// it does not change server state and runs no hooks.
+fn is_repeatable(command: &CommandSpec, command_path: &[String], hooks: &HooksConfig) -> bool {
+    !command.mutating && !hooks.covers(command_path)
+}
+
+async fn execute_transaction_command(
+    app: Arc<AppRuntime>,
+    session: &SharedSession,
+    word: &str,
+) -> Result<CommandOutcome, AppError> {
+    match word {
+        BEGIN => {
+            session.begin_transaction()?;
+            append_line(
+                "Transaction started. Mutating commands are queued until commit or rollback.",
+            )?;
+        }
+        ROLLBACK => {
+            let queued = session.take_transaction()?;
+            append_line(format!("Discarded {} queued command(s).", queued.len()))?;
+        }
+        COMMIT if is_offline() => return Err(refused_change_error(COMMIT)),
+        COMMIT => return commit_transaction(app, session).await,
+        _ => return Err(AppError::CommandNotFound(word.to_string())),
+    }
+    Ok(CommandOutcome {
+        output: take_output()?,
+        ..Default::default()
+    })
+}
+
+/// Queues `line` for the open transaction once its arguments check out, so a typo is
+/// refused now rather than failing at `commit` after earlier commands were sent.
+fn queue_transaction_command(
+    session: &SharedSession,
+    scope: Vec<String>,
+    line: &str,
+    command: &CommandSpec,
+    tokens: &CommandTokenizer,
+) -> Result<CommandOutcome, AppError> {
+    command.handler.validate(tokens)?;
+    let queued = QueuedCommand {
+        scope,
+        line: line.trim().to_string(),
+    };
+    let display = queued.display_line();
+    let position = session.queue_command(queued).ok_or_else(|| {
+        AppError::CommandExecutionError("No open transaction to queue into".to_string())
+    })?;
+    append_line(format!("Queued #{position}: {display}"))?;
+    Ok(CommandOutcome {
+        output: take_output()?,
+        ..Default::default()
+    })
+}
+
+async fn commit_transaction(
+    app: Arc<AppRuntime>,
+    session: &SharedSession,
+) -> Result<CommandOutcome, AppError> {
+    let queued = session.take_transaction()?;
+    let total = queued.len();
+    let original_scope = session.scope();
+    let mut combined = OutputSnapshot::default();
+
+    for (index, command) in queued.iter().enumerate() {
+        combined.lines.push(format!(
+            "[{}/{total}] {}",
+            index + 1,
+            command.display_line()
+        ));
+        session.set_scope(command.scope.clone());
+        let result = Box::pin(execute_line_inner(
+            app.clone(),
+            session,
+            &command.line,
+            false,
+        ))
+        .await;
+        session.set_scope(original_scope.clone());
+        match result {
+            Ok(outcome) => {
+                combined.lines.extend(outcome.output.lines);
+                combined.warnings.extend(outcome.output.warnings);
+                combined.errors.extend(outcome.output.errors);
+            }
+            Err(err) => {
+                let rendered = render_error(err);
+                combined.warnings.extend(rendered.warnings);
+                combined.errors.extend(rendered.errors);
+                combined.errors.push(format!(
+                    "Commit stopped: {index} of {total} command(s) applied, {} not sent.",
+                    total - index - 1
+                ));
+                return Ok(CommandOutcome {
+                    output: combined,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    combined
+        .lines
+        .push(format!("Committed {total} command(s)."));
+    Ok(CommandOutcome {
+        output: combined,
+        ..Default::default()
+    })
 }
 
 fn is_help_alias(parts: &[String]) -> bool {
     matches!(parts.first().map(String::as_str), Some("help" | "?"))
-        && !parts.iter().skip(1).any(|part| part.starts_with('-'))
+        && !parts
+            .iter()
+            .skip(1)
+            .any(|part| part.starts_with('-') && !is_long_flag(part))
 }
 
 fn parent_scope_action(current_scope: &[String]) -> ScopeAction {
@@ -153,14 +367,22 @@ pub fn can_execute_offline(line: &str) -> bool {
     parts
         .first()
         .is_some_and(|part| part == "help" || part == "?")
+        || parts.iter().any(|part| part == "--explain")
         || command_path_is(&parts, &["config", "show"])
         || command_path_is(&parts, &["config", "paths"])
+        || command_path_is(&parts, &["config", "encrypt"])
         || command_path_is(&parts, &["theme", "list"])
         || command_path_is(&parts, &["theme", "show"])
         || command_path_is(&parts, &["theme", "preview"])
         || command_path_is(&parts, &["auth", "providers"])
         || command_path_is(&parts, &["metrics"])
         || command_path_is(&parts, &["version"])
+        || command_path_is(&parts, &["server", "api-version"])
+        || command_path_is(&parts, &["server", "info"])
+        || command_path_is(&parts, &["profile", "doctor"])
+        || command_path_is(&parts, &["token", "list"])
+        || command_path_is(&parts, &["token", "prune"])
+        || command_path_is(&parts, &["audit", "local"])
 }
 
 pub fn execute_offline_line(
@@ -178,6 +400,7 @@ fn execute_offline_line_inner(
     line: &str,
 ) -> Result<CommandOutcome, AppError> {
     reset_output()?;
+    set_reveal(false);
     let (line, _pipeline, _pipeline_suffix) = process_filter(line)?;
     let parts = invocation_parts(&line)?;
     if parts.is_empty() {
@@ -192,6 +415,19 @@ fn execute_offline_line_inner(
         .first()
         .is_some_and(|part| part == "help" || part == "?")
     {
+        if parts
+            .iter()
+            .skip(1)
+            .any(|part| part == "--json" || part == "-j")
+        {
+            set_render_format(RenderFormat::Json)?;
+            append_json(catalog.metadata())?;
+            return Ok(CommandOutcome {
+                output: take_output()?,
+                scope_action: ScopeAction::None,
+                ..Default::default()
+            });
+        }
         if parts
             .iter()
             .skip(1)
@@ -217,6 +453,21 @@ fn execute_offline_line_inner(
                 &resolved.command_path[resolved.scope_path.len()..],
             );
         }
+        if options.contains_key("explain") {
+            set_render_format(render_format(&tokens)?)?;
+            let option_defs = resolved
+                .command
+                .options
+                .iter()
+                .map(|option| option.to_cli_option())
+                .collect::<Vec<_>>();
+            render_explain(&resolved.command_path, &option_defs, &tokens)?;
+            return Ok(CommandOutcome {
+                output: take_output()?,
+                scope_action: ScopeAction::None,
+                ..Default::default()
+            });
+        }
     }
 
     if command_path_is(&parts, &["config", "show"]) {
@@ -229,6 +480,11 @@ fn execute_offline_line_inner(
         let tokens = tokenizer_for_resolved(&line, &resolved)?;
         set_render_format(render_format(&tokens)?)?;
         render_config_paths(&tokens)?;
+    } else if command_path_is(&parts, &["config", "encrypt"]) {
+        let resolved = catalog.resolve_command(&[], &parts)?;
+        let tokens = tokenizer_for_resolved(&line, &resolved)?;
+        set_render_format(render_format(&tokens)?)?;
+        render_config_encrypt(&tokens)?;
     } else if command_path_is(&parts, &["theme", "list"]) {
         let resolved = catalog.resolve_command(&[], &parts)?;
         let tokens = tokenizer_for_resolved(&line, &resolved)?;
@@ -259,6 +515,36 @@ fn execute_offline_line_inner(
         let tokens = tokenizer_for_resolved(&line, &resolved)?;
         set_render_format(render_format(&tokens)?)?;
         render_version(&tokens)?;
+    } else if command_path_is(&parts, &["server", "api-version"]) {
+        let resolved = catalog.resolve_command(&[], &parts)?;
+        let tokens = tokenizer_for_resolved(&line, &resolved)?;
+        set_render_format(render_format(&tokens)?)?;
+        render_server_api_version(&tokens)?;
+    } else if command_path_is(&parts, &["server", "info"]) {
+        let resolved = catalog.resolve_command(&[], &parts)?;
+        let tokens = tokenizer_for_resolved(&line, &resolved)?;
+        set_render_format(render_format(&tokens)?)?;
+        render_server_info(&tokens)?;
+    } else if command_path_is(&parts, &["profile", "doctor"]) {
+        let resolved = catalog.resolve_command(&[], &parts)?;
+        let tokens = tokenizer_for_resolved(&line, &resolved)?;
+        set_render_format(render_format(&tokens)?)?;
+        render_profile_doctor(&tokens)?;
+    } else if command_path_is(&parts, &["token", "list"]) {
+        let resolved = catalog.resolve_command(&[], &parts)?;
+        let tokens = tokenizer_for_resolved(&line, &resolved)?;
+        set_render_format(render_format(&tokens)?)?;
+        render_token_list(&tokens)?;
+    } else if command_path_is(&parts, &["token", "prune"]) {
+        let resolved = catalog.resolve_command(&[], &parts)?;
+        let tokens = tokenizer_for_resolved(&line, &resolved)?;
+        set_render_format(render_format(&tokens)?)?;
+        render_token_prune(&tokens)?;
+    } else if command_path_is(&parts, &["audit", "local"]) {
+        let resolved = catalog.resolve_command(&[], &parts)?;
+        let tokens = tokenizer_for_resolved(&line, &resolved)?;
+        set_render_format(render_format(&tokens)?)?;
+        render_audit_local(&tokens)?;
     } else {
         catalog.resolve_command(&[], &parts)?;
         return Err(AppError::CommandNotFound(parts.join(" ")));
@@ -287,21 +573,26 @@ pub fn apply_output_state(session: &SharedSession, output: &OutputSnapshot) {
 }
 
 pub fn render_error(err: AppError) -> OutputSnapshot {
+    if matches!(err, AppError::Quiet) {
+        return take_output().expect("error snapshot should be captured");
+    }
     reset_output().expect("reset output buffer for errors");
     match err {
-        AppError::Quiet => {}
         AppError::EntityNotFound(entity) => {
             add_warning(entity).expect("warning should be added");
         }
-        AppError::ApiError(ApiError::HttpWithBody {
-            status, message, ..
-        }) => {
-            add_error(format!("API Error: Status {status} - {message}"))
-                .expect("error should be added");
-        }
         AppError::ApiError(api_error) => {
-            add_error(format!("API Error: {api_error}")).expect("error should be added");
+            add_error(api_error_message(&api_error, None)).expect("error should be added");
         }
+        AppError::Context { context, source } => match *source {
+            AppError::ApiError(api_error) => {
+                add_error(api_error_message(&api_error, Some(&context)))
+                    .expect("error should be added");
+            }
+            other => {
+                add_error(format!("Failed {context}: {other}")).expect("error should be added");
+            }
+        },
         other => {
             add_error(other).expect("error should be added");
         }
@@ -309,20 +600,66 @@ pub fn render_error(err: AppError) -> OutputSnapshot {
     take_output().expect("error snapshot should be captured")
 }
 
+/// Formats an API error for display, naming the operation when it is known.
+fn api_error_message(error: &ApiError, context: Option<&str>) -> String {
+    if is_offline() {
+        return offline_error_message(context);
+    }
+    let detail = match error {
+        ApiError::HttpWithBody {
+            status, message, ..
+        } => format!("Status {status} - {message}"),
+        other => other.to_string(),
+    };
+    match context {
+        Some(context) => format!("API Error while {context}: {detail}"),
+        None => format!("API Error: {detail}"),
+    }
+}
+
 fn render_help(
     app: Arc<AppRuntime>,
     scope: Vec<String>,
     parts: &[String],
 ) -> Result<CommandOutcome, AppError> {
     reset_output()?;
+    if parts.iter().any(|part| is_long_flag(part)) {
+        let completion = app
+            .services
+            .completion_context(Handle::current(), app.config.as_ref());
+        return render_manual_outcome(app.catalog.as_ref(), &scope, parts, Some(&completion));
+    }
     render_help_from_catalog(app.catalog.as_ref(), scope, parts)
 }
 
+fn render_manual_outcome(
+    catalog: &CommandCatalog,
+    scope: &[String],
+    parts: &[String],
+    completion: Option<&CompletionContext>,
+) -> Result<CommandOutcome, AppError> {
+    let parts = parts
+        .iter()
+        .filter(|part| !is_long_flag(part))
+        .cloned()
+        .collect::<Vec<_>>();
+    append_line(render_manual(catalog, scope, &parts, completion)?)?;
+    page_output()?;
+    Ok(CommandOutcome {
+        output: take_output()?,
+        scope_action: ScopeAction::None,
+        ..Default::default()
+    })
+}
+
 fn render_help_from_catalog(
     catalog: &CommandCatalog,
     scope: Vec<String>,
     parts: &[String],
 ) -> Result<CommandOutcome, AppError> {
+    if parts.iter().any(|part| is_long_flag(part)) {
+        return render_manual_outcome(catalog, &scope, parts, None);
+    }
     if parts.is_empty() {
         append_line(catalog.render_scope_help(&scope))?;
     } else if parts.first().map(String::as_str) == Some("pipe") {
@@ -439,13 +776,142 @@ mod tests {
 
     use super::{
         apply_output_state, can_execute_offline, execute_offline_line, is_help_alias,
-        parent_scope_action, prepare_redirect, process_filter,
+        is_repeatable, parent_scope_action, prepare_redirect, process_filter,
+        queue_transaction_command, render_error,
     };
     use crate::app::SharedSession;
-    use crate::catalog::ScopeAction;
+    use crate::catalog::{CommandCatalog, ResolvedCommand, ScopeAction};
     use crate::commands::build_command_catalog;
+    use crate::config::HooksConfig;
+    use crate::errors::AppError;
     use crate::output::{append_line, reset_output, take_output, OutputSnapshot};
     use crate::redirection::RedirectTarget;
+    use crate::tokenizer::CommandTokenizer;
+
+    #[test]
+    #[serial]
+    fn quiet_errors_keep_the_output_the_command_rendered() {
+        reset_output().expect("buffer should reset");
+        append_line("Apply stopped: 2 of 3 change(s) applied.").expect("line should append");
+
+        let snapshot = render_error(AppError::Quiet);
+        assert_eq!(
+            snapshot.lines,
+            vec!["Apply stopped: 2 of 3 change(s) applied."]
+        );
+        assert!(snapshot.errors.is_empty());
+
+        append_line("partial output").expect("line should append");
+        let snapshot = render_error(AppError::CommandExecutionError("boom".to_string()));
+        assert!(snapshot.lines.is_empty());
+        assert_eq!(snapshot.errors.len(), 1);
+    }
+
+    fn resolve<'a>(catalog: &'a CommandCatalog, line: &str) -> ResolvedCommand<'a> {
+        let parts = line.split(' ').map(str::to_string).collect::<Vec<_>>();
+        catalog
+            .resolve_command(&[], &parts)
+            .expect("command should resolve")
+    }
+
+    #[test]
+    fn only_reads_without_hooks_are_retried_after_a_rejected_token() {
+        let catalog = build_command_catalog();
+        let repeatable = |line: &str, hooks: &HooksConfig| {
+            let resolved = resolve(&catalog, line);
+            is_repeatable(resolved.command, &resolved.command_path, hooks)
+        };
+        let mut hooks = HooksConfig::default();
+
+        assert!(repeatable("object list", &hooks));
+        assert!(!repeatable("object delete", &hooks));
+        assert!(!repeatable("object import", &hooks));
+        assert!(!repeatable("logout", &hooks));
+        hooks
+            .post
+            .insert("object list".to_string(), "./notify.sh".to_string());
+        assert!(!repeatable("object list", &hooks));
+    }
+
+    #[test]
+    fn commands_that_change_the_server_are_marked_mutating() {
+        let catalog = build_command_catalog();
+        let mutating = |line: &str| resolve(&catalog, line).command.mutating;
+
+        for line in [
+            "class create",
+            "group grant",
+            "group add_user",
+            "relation object delete",
+            "collection permissions set",
+            "object import",
+            "logout",
+            "config store",
+            "config export",
+        ] {
+            assert!(mutating(line), "{line} should be mutating");
+        }
+        for line in [
+            "class list",
+            "object show",
+            "config set",
+            "config import",
+            "jobs forget",
+            "token prune",
+            "login",
+        ] {
+            assert!(!mutating(line), "{line} should not be mutating");
+        }
+    }
+
+    #[test]
+    fn logins_with_their_own_credentials_are_not_renewed_and_retried() {
+        let catalog = build_command_catalog();
+        let own_credentials = |line: &str| resolve(&catalog, line).command.own_credentials;
+
+        assert!(own_credentials("login"));
+        assert!(own_credentials("profile use"));
+        assert!(!own_credentials("whoami"));
+        assert!(!own_credentials("profile doctor"));
+    }
+
+    #[test]
+    #[serial]
+    fn queued_commands_with_bad_arguments_are_refused_at_entry() {
+        let catalog = build_command_catalog();
+        let session = SharedSession::new();
+        session
+            .begin_transaction()
+            .expect("transaction should open");
+        let queue = |line: &str| {
+            let resolved = resolve(&catalog, line);
+            let option_defs = resolved
+                .command
+                .options
+                .iter()
+                .map(|option| option.to_cli_option())
+                .collect::<Vec<_>>();
+            let name = resolved.command_path.last().expect("command name");
+            let tokens =
+                CommandTokenizer::new_without_value_source_resolution(line, name, &option_defs)
+                    .expect("line should tokenize");
+            reset_output().expect("buffer should reset");
+            queue_transaction_command(&session, Vec::new(), line, resolved.command, &tokens)
+        };
+
+        assert!(queue("class create --nmae Host --collection infra").is_err());
+        assert!(queue("class delete Host Rooms").is_err());
+        assert!(queue("class create --name Host --collection infra --description Hosts").is_ok());
+
+        let queued = session
+            .take_transaction()
+            .expect("queue should be returned");
+        assert_eq!(queued.len(), 1);
+        assert_eq!(
+            queued[0].line,
+            "class create --name Host --collection infra --description Hosts"
+        );
+    }
 
     #[test]
     #[serial]
@@ -552,6 +1018,10 @@ mod tests {
         assert!(can_execute_offline("metrics --path /internal/metrics"));
         assert!(can_execute_offline("version"));
         assert!(can_execute_offline("version --server"));
+        assert!(can_execute_offline("audit local --since 1d"));
+        assert!(can_execute_offline("token list --json"));
+        assert!(can_execute_offline("config encrypt server.password"));
+        assert!(can_execute_offline("object list --class Hosts --explain"));
         assert!(!can_execute_offline("theme use hubuum-dark"));
         assert!(!can_execute_offline(
             "config set --key server.hostname --value localhost"
@@ -583,6 +1053,34 @@ mod tests {
             .any(|line| line.contains("Fetch Prometheus server metrics")));
     }
 
+    #[test]
+    #[serial]
+    fn long_help_renders_the_manual_for_the_pager() {
+        let catalog = build_command_catalog();
+        assert!(is_help_alias(&[
+            "help".to_string(),
+            "metrics".to_string(),
+            "--long".to_string()
+        ]));
+
+        let outcome = execute_offline_line(&catalog, "help metrics --long")
+            .expect("long help should render without a server");
+        assert!(outcome.output.paged);
+        assert!(outcome
+            .output
+            .lines
+            .iter()
+            .any(|line| line.contains("SYNOPSIS")));
+
+        let piped = execute_offline_line(&catalog, "help metrics -l | grep SYNOPSIS")
+            .expect("filtered long help should render");
+        assert!(!piped.output.paged);
+
+        let err =
+            execute_offline_line(&catalog, "help --long").expect_err("long help needs a command");
+        assert!(err.to_string().contains("help object create --long"));
+    }
+
     #[test]
     #[serial]
     fn offline_redirect_is_attached_and_removed_from_command() {
@@ -0,0 +1,1256 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use config::{Config, File as ConfigFile, FileFormat};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::errors::AppError;
+
+use super::ValueDiff;
+
+/// Declarative description of the inventory read by `apply`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredState {
+    #[serde(default)]
+    pub collections: Vec<DesiredCollection>,
+    #[serde(default)]
+    pub classes: Vec<DesiredClass>,
+    #[serde(default)]
+    pub class_relations: Vec<DesiredClassRelation>,
+    #[serde(default)]
+    pub objects: Vec<DesiredObject>,
+    #[serde(default)]
+    pub object_relations: Vec<DesiredObjectRelation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredCollection {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Owning group, only needed when the collection has to be created.
+    #[serde(default)]
+    pub owner: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredClass {
+    pub name: String,
+    pub collection: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub json_schema: Option<Value>,
+    #[serde(default)]
+    pub validate_schema: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredClassRelation {
+    pub class_a: String,
+    pub class_b: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredObject {
+    pub name: String,
+    pub class: String,
+    pub collection: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredObjectRelation {
+    pub class_a: String,
+    pub object_a: String,
+    pub class_b: String,
+    pub object_b: String,
+}
+
+/// Server-side view of the entities a [`DesiredState`] refers to.
+#[derive(Debug, Clone, Default)]
+pub struct CurrentState {
+    pub collections: HashMap<String, CurrentCollection>,
+    pub classes: HashMap<String, CurrentClass>,
+    /// Class relations as name pairs, in either order.
+    pub class_relations: HashSet<(String, String)>,
+    /// Objects keyed by `(class, name)`, fetched for declared classes only.
+    pub objects: HashMap<(String, String), CurrentObject>,
+    /// Object relations between objects of declared classes.
+    pub object_relations: HashSet<DesiredObjectRelationKey>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CurrentCollection {
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CurrentClass {
+    pub collection: String,
+    pub description: String,
+    pub json_schema: Option<Value>,
+    pub validate_schema: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CurrentObject {
+    pub collection: String,
+    pub description: String,
+    pub data: Option<Value>,
+}
+
+/// Order-independent identity of an object relation.
+pub type DesiredObjectRelationKey = ((String, String), (String, String));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanAction {
+    Create,
+    Update,
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanKind {
+    Collection,
+    Class,
+    ClassRelation,
+    Object,
+    ObjectRelation,
+}
+
+/// What `apply` has to send to the server for a single plan entry.
+///
+/// Serialized into retry files, so failed entries can be sent again by `bulk retry`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "operation", content = "payload")]
+pub enum PlanOperation {
+    CreateCollection(DesiredCollection),
+    UpdateCollection(DesiredCollection),
+    CreateClass(DesiredClass),
+    UpdateClass(DesiredClass),
+    CreateClassRelation(DesiredClassRelation),
+    DeleteClassRelation(DesiredClassRelation),
+    CreateObject(DesiredObject),
+    UpdateObject(DesiredObject),
+    DeleteObject { class: String, name: String },
+    CreateObjectRelation(DesiredObjectRelation),
+    DeleteObjectRelation(DesiredObjectRelation),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanChange {
+    pub action: PlanAction,
+    pub kind: PlanKind,
+    pub name: String,
+    pub changes: Vec<String>,
+    /// Server and desired values of the changed fields, for updates.
+    #[serde(rename = "patch", skip_serializing_if = "Option::is_none")]
+    pub diff: Option<ValueDiff>,
+    #[serde(skip)]
+    pub operation: PlanOperation,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ApplyPlan {
+    pub changes: Vec<PlanChange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftStatus {
+    /// The schema file has no class on the server.
+    Added,
+    /// The server class has no schema file.
+    Removed,
+    /// Both exist but the schemas differ.
+    Changed,
+}
+
+/// One class whose server-side schema does not match the schema-as-code directory.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SchemaDrift {
+    pub class: String,
+    pub status: DriftStatus,
+    /// Top-level schema keywords that differ, for changed classes.
+    pub keywords: Vec<String>,
+    /// The server schema against the file schema; missing sides are `null`.
+    #[serde(rename = "patch")]
+    pub diff: ValueDiff,
+}
+
+impl PlanAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PlanAction::Create => "create",
+            PlanAction::Update => "update",
+            PlanAction::Delete => "delete",
+        }
+    }
+}
+
+impl PlanKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PlanKind::Collection => "collection",
+            PlanKind::Class => "class",
+            PlanKind::ClassRelation => "class relation",
+            PlanKind::Object => "object",
+            PlanKind::ObjectRelation => "object relation",
+        }
+    }
+}
+
+impl DesiredState {
+    /// Parses a desired-state document, picking YAML, TOML, or JSON from the file extension.
+    pub fn parse(path: &Path, content: &str) -> Result<Self, AppError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        let state: Self = match extension.as_deref() {
+            Some("json") => serde_json::from_str(content)?,
+            Some("toml") => toml::from_str(content).map_err(|error| {
+                AppError::ParseError(format!("Invalid desired state file: {error}"))
+            })?,
+            Some("yaml" | "yml") | None => Config::builder()
+                .add_source(ConfigFile::from_str(content, FileFormat::Yaml))
+                .build()
+                .and_then(Config::try_deserialize)
+                .map_err(|error| {
+                    AppError::ParseError(format!("Invalid desired state file: {error}"))
+                })?,
+            Some(other) => {
+                return Err(AppError::ParseError(format!(
+                    "Unsupported desired state format '.{other}'. Use .yaml, .yml, .toml, or .json."
+                )))
+            }
+        };
+        state.validate()?;
+        Ok(state)
+    }
+
+    fn validate(&self) -> Result<(), AppError> {
+        ensure_unique(
+            "collection",
+            self.collections.iter().map(|entry| entry.name.clone()),
+        )?;
+        ensure_unique("class", self.classes.iter().map(|entry| entry.name.clone()))?;
+        ensure_unique(
+            "object",
+            self.objects
+                .iter()
+                .map(|entry| object_label(&entry.class, &entry.name)),
+        )?;
+        ensure_unique(
+            "class relation",
+            self.class_relations
+                .iter()
+                .map(|entry| class_relation_label(&entry.class_a, &entry.class_b)),
+        )?;
+        ensure_unique(
+            "object relation",
+            self.object_relations.iter().map(object_relation_label),
+        )
+    }
+
+    /// Builds one class per JSON Schema file, all placed in `collection`.
+    ///
+    /// `files` holds file names and contents. The class name comes from the last segment
+    /// of the schema's `$id`, or else from the file name, without `.json` and `.schema`
+    /// suffixes. The description comes from the schema's `description` or `title`.
+    pub fn from_schema_files(
+        files: &[(String, String)],
+        collection: &str,
+    ) -> Result<Self, AppError> {
+        let mut classes = Vec::new();
+        for (file_name, content) in files {
+            let schema: Value = serde_json::from_str(content).map_err(|error| {
+                AppError::ParseError(format!("Invalid JSON Schema in {file_name}: {error}"))
+            })?;
+            if !schema.is_object() {
+                return Err(AppError::ParseError(format!(
+                    "{file_name} does not contain a JSON Schema object"
+                )));
+            }
+            let name = schema
+                .get("$id")
+                .and_then(Value::as_str)
+                .map(|id| id.split('#').next().unwrap_or_default())
+                .and_then(|id| id.rsplit(['/', ':']).next())
+                .filter(|segment| !segment.is_empty())
+                .unwrap_or(file_name);
+            let description = ["description", "title"]
+                .iter()
+                .find_map(|key| schema.get(key).and_then(Value::as_str))
+                .unwrap_or_default()
+                .to_string();
+            classes.push(DesiredClass {
+                name: schema_class_name(name),
+                collection: collection.to_string(),
+                description,
+                json_schema: Some(schema),
+                validate_schema: None,
+            });
+        }
+
+        let state = Self {
+            classes,
+            ..Self::default()
+        };
+        state.validate()?;
+        Ok(state)
+    }
+
+    /// Reads a class relation model: a desired-state document that lists only `class_relations`.
+    pub fn parse_relation_model(path: &Path, content: &str) -> Result<Self, AppError> {
+        let state = Self::parse(path, content)?;
+        if !(state.collections.is_empty()
+            && state.classes.is_empty()
+            && state.objects.is_empty()
+            && state.object_relations.is_empty())
+        {
+            return Err(AppError::ParseError(
+                "A relation model may only list class_relations; use `apply` for other entities"
+                    .to_string(),
+            ));
+        }
+        Ok(state)
+    }
+
+    /// Names of the classes at either end of this document's class relations.
+    pub fn relation_class_names(&self) -> BTreeSet<String> {
+        self.class_relations
+            .iter()
+            .flat_map(|relation| [relation.class_a.clone(), relation.class_b.clone()])
+            .collect()
+    }
+
+    /// Names of the classes whose objects and relations this document manages.
+    pub fn class_names(&self) -> BTreeSet<String> {
+        self.classes
+            .iter()
+            .map(|class| class.name.clone())
+            .chain(self.objects.iter().map(|object| object.class.clone()))
+            .collect()
+    }
+}
+
+fn schema_class_name(file_name: &str) -> String {
+    let name = file_name.strip_suffix(".json").unwrap_or(file_name);
+    name.strip_suffix(".schema").unwrap_or(name).to_string()
+}
+
+impl DesiredObject {
+    /// Identifies the object in checkpoints and retry files as `class/name`.
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.class, self.name)
+    }
+}
+
+impl DesiredObjectRelation {
+    fn from_key(((class_a, object_a), (class_b, object_b)): DesiredObjectRelationKey) -> Self {
+        Self {
+            class_a,
+            object_a,
+            class_b,
+            object_b,
+        }
+    }
+
+    pub fn key(&self) -> DesiredObjectRelationKey {
+        let a = (self.class_a.clone(), self.object_a.clone());
+        let b = (self.class_b.clone(), self.object_b.clone());
+        if a <= b {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+}
+
+impl CurrentState {
+    fn has_class_relation(&self, class_a: &str, class_b: &str) -> bool {
+        self.class_relations
+            .contains(&(class_a.to_string(), class_b.to_string()))
+            || self
+                .class_relations
+                .contains(&(class_b.to_string(), class_a.to_string()))
+    }
+}
+
+impl ApplyPlan {
+    /// Diffs the desired state against the server.
+    ///
+    /// Entities missing from the document are only deleted when `prune` is set, and pruning
+    /// is limited to objects of declared classes and relations between declared classes
+    /// or their objects.
+    pub fn compute(
+        desired: &DesiredState,
+        current: &CurrentState,
+        prune: bool,
+    ) -> Result<Self, AppError> {
+        let mut changes = Vec::new();
+
+        for collection in &desired.collections {
+            match current.collections.get(&collection.name) {
+                None => {
+                    if collection.owner.is_none() {
+                        return Err(AppError::ParseError(format!(
+                            "Collection '{}' does not exist; set 'owner' so it can be created",
+                            collection.name
+                        )));
+                    }
+                    changes.push(PlanChange::new(
+                        PlanOperation::CreateCollection(collection.clone()),
+                        Vec::new(),
+                    ));
+                }
+                Some(existing) if existing.description != collection.description => {
+                    let mut fields = FieldChanges::default();
+                    fields.record(
+                        "description",
+                        &existing.description,
+                        &collection.description,
+                    );
+                    changes.extend(
+                        fields.into_change(PlanOperation::UpdateCollection(collection.clone())),
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+
+        for class in &desired.classes {
+            match current.classes.get(&class.name) {
+                None => changes.push(PlanChange::new(
+                    PlanOperation::CreateClass(class.clone()),
+                    Vec::new(),
+                )),
+                Some(existing) => {
+                    let mut fields = FieldChanges::default();
+                    if existing.collection != class.collection {
+                        fields.record("collection", &existing.collection, &class.collection);
+                    }
+                    if existing.description != class.description {
+                        fields.record("description", &existing.description, &class.description);
+                    }
+                    if let Some(schema) = class
+                        .json_schema
+                        .as_ref()
+                        .filter(|schema| existing.json_schema.as_ref() != Some(*schema))
+                    {
+                        fields.record("json_schema", &existing.json_schema, schema);
+                    }
+                    if let Some(validate) = class
+                        .validate_schema
+                        .filter(|validate| *validate != existing.validate_schema)
+                    {
+                        fields.record("validate_schema", existing.validate_schema, validate);
+                    }
+                    changes.extend(fields.into_change(PlanOperation::UpdateClass(class.clone())));
+                }
+            }
+        }
+
+        for relation in &desired.class_relations {
+            if !current.has_class_relation(&relation.class_a, &relation.class_b) {
+                changes.push(PlanChange::new(
+                    PlanOperation::CreateClassRelation(relation.clone()),
+                    Vec::new(),
+                ));
+            }
+        }
+
+        for object in &desired.objects {
+            match current
+                .objects
+                .get(&(object.class.clone(), object.name.clone()))
+            {
+                None => changes.push(PlanChange::new(
+                    PlanOperation::CreateObject(object.clone()),
+                    Vec::new(),
+                )),
+                Some(existing) => {
+                    let mut fields = FieldChanges::default();
+                    if existing.collection != object.collection {
+                        fields.record("collection", &existing.collection, &object.collection);
+                    }
+                    if existing.description != object.description {
+                        fields.record("description", &existing.description, &object.description);
+                    }
+                    if let Some(data) = object
+                        .data
+                        .as_ref()
+                        .filter(|data| existing.data.as_ref() != Some(*data))
+                    {
+                        fields.record("data", &existing.data, data);
+                    }
+                    changes.extend(fields.into_change(PlanOperation::UpdateObject(object.clone())));
+                }
+            }
+        }
+
+        for relation in &desired.object_relations {
+            if !current.object_relations.contains(&relation.key()) {
+                changes.push(PlanChange::new(
+                    PlanOperation::CreateObjectRelation(relation.clone()),
+                    Vec::new(),
+                ));
+            }
+        }
+
+        if prune {
+            changes.extend(Self::prune_changes(desired, current));
+        }
+
+        Ok(Self { changes })
+    }
+
+    fn prune_changes(desired: &DesiredState, current: &CurrentState) -> Vec<PlanChange> {
+        let classes = desired.class_names();
+        let declared_objects = desired
+            .objects
+            .iter()
+            .map(|object| (object.class.clone(), object.name.clone()))
+            .collect::<HashSet<_>>();
+        let mut stale_objects = current
+            .objects
+            .keys()
+            .filter(|key| classes.contains(&key.0) && !declared_objects.contains(*key))
+            .cloned()
+            .collect::<Vec<_>>();
+        stale_objects.sort();
+        let declared_relations = desired
+            .object_relations
+            .iter()
+            .map(DesiredObjectRelation::key)
+            .collect::<HashSet<_>>();
+        let mut stale_object_relations = current
+            .object_relations
+            .iter()
+            .filter(|key| {
+                classes.contains(&(key.0).0)
+                    && classes.contains(&(key.1).0)
+                    && !declared_relations.contains(*key)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        stale_object_relations.sort();
+
+        // Relations go first, so none is left pointing at an object deleted before it.
+        stale_object_relations
+            .into_iter()
+            .map(|key| {
+                PlanChange::new(
+                    PlanOperation::DeleteObjectRelation(DesiredObjectRelation::from_key(key)),
+                    Vec::new(),
+                )
+            })
+            .chain(stale_objects.into_iter().map(|(class, name)| {
+                PlanChange::new(PlanOperation::DeleteObject { class, name }, Vec::new())
+            }))
+            .chain(
+                Self::stale_class_relations(desired, current, &classes)
+                    .into_iter()
+                    .map(|relation| {
+                        PlanChange::new(PlanOperation::DeleteClassRelation(relation), Vec::new())
+                    }),
+            )
+            .collect()
+    }
+
+    /// Plans a class relation model from [`DesiredState::parse_relation_model`].
+    ///
+    /// Missing relations are created. Relations between modelled classes that the model
+    /// does not list are only deleted when `prune` is set; see
+    /// [`ApplyPlan::extra_class_relations`] for reporting them instead.
+    pub fn compute_relation_model(
+        desired: &DesiredState,
+        current: &CurrentState,
+        prune: bool,
+    ) -> Result<Self, AppError> {
+        let unknown = desired
+            .relation_class_names()
+            .into_iter()
+            .filter(|class| !current.classes.contains_key(class))
+            .collect::<Vec<_>>();
+        if !unknown.is_empty() {
+            return Err(AppError::ParseError(format!(
+                "The relation model names classes that do not exist: {}",
+                unknown.join(", ")
+            )));
+        }
+
+        let mut plan = Self::compute(desired, current, false)?;
+        if prune {
+            plan.changes.extend(
+                Self::extra_class_relations(desired, current)
+                    .into_iter()
+                    .map(|relation| {
+                        PlanChange::new(PlanOperation::DeleteClassRelation(relation), Vec::new())
+                    }),
+            );
+        }
+        Ok(plan)
+    }
+
+    /// Server relations between modelled classes that the relation model does not list.
+    pub fn extra_class_relations(
+        desired: &DesiredState,
+        current: &CurrentState,
+    ) -> Vec<DesiredClassRelation> {
+        Self::stale_class_relations(desired, current, &desired.relation_class_names())
+    }
+
+    /// Current relations between two of `classes` that `desired` does not declare, sorted.
+    fn stale_class_relations(
+        desired: &DesiredState,
+        current: &CurrentState,
+        classes: &BTreeSet<String>,
+    ) -> Vec<DesiredClassRelation> {
+        let declared_relations = desired
+            .class_relations
+            .iter()
+            .map(|relation| sorted_pair(&relation.class_a, &relation.class_b))
+            .collect::<HashSet<_>>();
+        let mut stale_relations = current
+            .class_relations
+            .iter()
+            .map(|(class_a, class_b)| sorted_pair(class_a, class_b))
+            .filter(|pair| {
+                classes.contains(&pair.0)
+                    && classes.contains(&pair.1)
+                    && !declared_relations.contains(pair)
+            })
+            .collect::<Vec<_>>();
+        stale_relations.sort();
+        stale_relations.dedup();
+        stale_relations
+            .into_iter()
+            .map(|(class_a, class_b)| DesiredClassRelation { class_a, class_b })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn count(&self, action: PlanAction) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.action == action)
+            .count()
+    }
+}
+
+impl DriftStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Changed => "changed",
+        }
+    }
+}
+
+impl SchemaDrift {
+    /// Compares the schemas of `desired` classes with the server.
+    ///
+    /// Server classes without a file are reported as removed; with `collection` set, only
+    /// classes in that collection are considered.
+    pub fn compute(
+        desired: &DesiredState,
+        current: &CurrentState,
+        collection: Option<&str>,
+    ) -> Vec<Self> {
+        let mut drift = Vec::new();
+        for class in &desired.classes {
+            match current.classes.get(&class.name) {
+                None => drift.push(Self {
+                    class: class.name.clone(),
+                    status: DriftStatus::Added,
+                    keywords: Vec::new(),
+                    diff: ValueDiff::new(Value::Null, json!(class.json_schema)),
+                }),
+                Some(existing) if existing.json_schema != class.json_schema => {
+                    drift.push(Self {
+                        class: class.name.clone(),
+                        status: DriftStatus::Changed,
+                        keywords: changed_keywords(
+                            existing.json_schema.as_ref(),
+                            class.json_schema.as_ref(),
+                        ),
+                        diff: ValueDiff::new(json!(existing.json_schema), json!(class.json_schema)),
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+
+        let declared = desired.class_names();
+        drift.extend(
+            current
+                .classes
+                .iter()
+                .filter(|(name, class)| {
+                    !declared.contains(*name)
+                        && collection.is_none_or(|collection| class.collection == collection)
+                })
+                .map(|(name, class)| Self {
+                    class: name.clone(),
+                    status: DriftStatus::Removed,
+                    keywords: Vec::new(),
+                    diff: ValueDiff::new(json!(class.json_schema), Value::Null),
+                }),
+        );
+        drift.sort_by(|a, b| (a.status, &a.class).cmp(&(b.status, &b.class)));
+        drift
+    }
+}
+
+fn changed_keywords(server: Option<&Value>, file: Option<&Value>) -> Vec<String> {
+    let (Some(Value::Object(server)), Some(Value::Object(file))) = (server, file) else {
+        return vec!["(entire schema)".to_string()];
+    };
+    server
+        .keys()
+        .chain(file.keys())
+        .filter(|key| server.get(*key) != file.get(*key))
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+impl PlanChange {
+    /// `create class Hosts`, as failures and retry files name the change.
+    pub fn label(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.action.as_str(),
+            self.kind.as_str(),
+            self.name
+        )
+    }
+
+    fn new(operation: PlanOperation, changes: Vec<String>) -> Self {
+        let (action, kind, name) = match &operation {
+            PlanOperation::CreateCollection(collection) => (
+                PlanAction::Create,
+                PlanKind::Collection,
+                collection.name.clone(),
+            ),
+            PlanOperation::UpdateCollection(collection) => (
+                PlanAction::Update,
+                PlanKind::Collection,
+                collection.name.clone(),
+            ),
+            PlanOperation::CreateClass(class) => {
+                (PlanAction::Create, PlanKind::Class, class.name.clone())
+            }
+            PlanOperation::UpdateClass(class) => {
+                (PlanAction::Update, PlanKind::Class, class.name.clone())
+            }
+            PlanOperation::CreateClassRelation(relation) => (
+                PlanAction::Create,
+                PlanKind::ClassRelation,
+                class_relation_label(&relation.class_a, &relation.class_b),
+            ),
+            PlanOperation::DeleteClassRelation(relation) => (
+                PlanAction::Delete,
+                PlanKind::ClassRelation,
+                class_relation_label(&relation.class_a, &relation.class_b),
+            ),
+            PlanOperation::CreateObject(object) => (
+                PlanAction::Create,
+                PlanKind::Object,
+                object_label(&object.class, &object.name),
+            ),
+            PlanOperation::UpdateObject(object) => (
+                PlanAction::Update,
+                PlanKind::Object,
+                object_label(&object.class, &object.name),
+            ),
+            PlanOperation::DeleteObject { class, name } => (
+                PlanAction::Delete,
+                PlanKind::Object,
+                object_label(class, name),
+            ),
+            PlanOperation::CreateObjectRelation(relation) => (
+                PlanAction::Create,
+                PlanKind::ObjectRelation,
+                object_relation_label(relation),
+            ),
+            PlanOperation::DeleteObjectRelation(relation) => (
+                PlanAction::Delete,
+                PlanKind::ObjectRelation,
+                object_relation_label(relation),
+            ),
+        };
+        Self {
+            action,
+            kind,
+            name,
+            changes,
+            diff: None,
+            operation,
+        }
+    }
+}
+
+/// Collects the fields an update changes, with their server and desired values.
+#[derive(Default)]
+struct FieldChanges {
+    names: Vec<String>,
+    before: Map<String, Value>,
+    after: Map<String, Value>,
+}
+
+impl FieldChanges {
+    fn record(&mut self, name: &str, before: impl Serialize, after: impl Serialize) {
+        self.names.push(name.to_string());
+        self.before.insert(name.to_string(), json!(before));
+        self.after.insert(name.to_string(), json!(after));
+    }
+
+    /// The update for `operation`, or `None` when nothing changed.
+    fn into_change(self, operation: PlanOperation) -> Option<PlanChange> {
+        if self.names.is_empty() {
+            return None;
+        }
+        let mut change = PlanChange::new(operation, self.names);
+        change.diff = Some(ValueDiff::new(
+            Value::Object(self.before),
+            Value::Object(self.after),
+        ));
+        Some(change)
+    }
+}
+
+fn ensure_unique(kind: &str, names: impl Iterator<Item = String>) -> Result<(), AppError> {
+    let mut seen = HashSet::new();
+    for name in names {
+        if !seen.insert(name.clone()) {
+            return Err(AppError::ParseError(format!(
+                "Duplicate {kind} '{name}' in desired state file"
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn sorted_pair(a: &str, b: &str) -> (String, String) {
+    if a <= b {
+        (a.to_string(), b.to_string())
+    } else {
+        (b.to_string(), a.to_string())
+    }
+}
+
+fn object_label(class: &str, name: &str) -> String {
+    format!("{class}/{name}")
+}
+
+fn class_relation_label(class_a: &str, class_b: &str) -> String {
+    let (class_a, class_b) = sorted_pair(class_a, class_b);
+    format!("{class_a} <-> {class_b}")
+}
+
+fn object_relation_label(relation: &DesiredObjectRelation) -> String {
+    let ((class_a, object_a), (class_b, object_b)) = relation.key();
+    format!(
+        "{} <-> {}",
+        object_label(&class_a, &object_a),
+        object_label(&class_b, &object_b)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const DESIRED_YAML: &str = r#"
+collections:
+  - name: infra
+    description: Infrastructure
+    owner: admins
+classes:
+  - name: Host
+    collection: infra
+    description: Hosts
+    json_schema:
+      type: object
+    validate_schema: true
+  - name: Room
+    collection: infra
+class_relations:
+  - class_a: Room
+    class_b: Host
+objects:
+  - name: web1
+    class: Host
+    collection: infra
+    data:
+      ipAddress: 10.0.0.1
+"#;
+
+    fn desired() -> DesiredState {
+        DesiredState::parse(Path::new("desired.yaml"), DESIRED_YAML).expect("yaml should parse")
+    }
+
+    fn current() -> CurrentState {
+        let mut current = CurrentState::default();
+        current.collections.insert(
+            "infra".to_string(),
+            CurrentCollection {
+                description: "Infrastructure".to_string(),
+            },
+        );
+        current.classes.insert(
+            "Host".to_string(),
+            CurrentClass {
+                collection: "infra".to_string(),
+                description: "Hosts".to_string(),
+                json_schema: Some(json!({"type": "object"})),
+                validate_schema: true,
+            },
+        );
+        current.classes.insert(
+            "Room".to_string(),
+            CurrentClass {
+                collection: "infra".to_string(),
+                ..CurrentClass::default()
+            },
+        );
+        current
+            .class_relations
+            .insert(("Host".to_string(), "Room".to_string()));
+        current.objects.insert(
+            ("Host".to_string(), "web1".to_string()),
+            CurrentObject {
+                collection: "infra".to_string(),
+                description: String::new(),
+                data: Some(json!({"ipAddress": "10.0.0.1"})),
+            },
+        );
+        current
+    }
+
+    #[test]
+    fn parses_yaml_and_preserves_data_keys() {
+        let desired = desired();
+        assert_eq!(desired.classes.len(), 2);
+        assert_eq!(
+            desired.objects[0].data,
+            Some(json!({"ipAddress": "10.0.0.1"}))
+        );
+    }
+
+    #[test]
+    fn parses_json_and_rejects_duplicates() {
+        let state = DesiredState::parse(
+            Path::new("desired.json"),
+            r#"{"classes": [{"name": "Host", "collection": "infra"}]}"#,
+        )
+        .expect("json should parse");
+        assert_eq!(state.classes[0].name, "Host");
+
+        let duplicate = DesiredState::parse(
+            Path::new("desired.json"),
+            r#"{"classes": [{"name": "Host", "collection": "a"}, {"name": "Host", "collection": "b"}]}"#,
+        );
+        assert!(duplicate.is_err());
+    }
+
+    #[test]
+    fn matching_state_produces_empty_plan() {
+        let plan = ApplyPlan::compute(&desired(), &current(), true).expect("plan");
+        assert!(plan.is_empty(), "unexpected changes: {:?}", plan.changes);
+    }
+
+    #[test]
+    fn missing_entities_are_created_in_dependency_order() {
+        let plan = ApplyPlan::compute(&desired(), &CurrentState::default(), false).expect("plan");
+        let kinds = plan
+            .changes
+            .iter()
+            .map(|change| (change.action, change.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (PlanAction::Create, PlanKind::Collection),
+                (PlanAction::Create, PlanKind::Class),
+                (PlanAction::Create, PlanKind::Class),
+                (PlanAction::Create, PlanKind::ClassRelation),
+                (PlanAction::Create, PlanKind::Object),
+            ]
+        );
+        assert_eq!(plan.changes[3].name, "Host <-> Room");
+    }
+
+    #[test]
+    fn changed_fields_are_listed_on_updates() {
+        let mut current = current();
+        current
+            .classes
+            .get_mut("Host")
+            .expect("class")
+            .validate_schema = false;
+        current
+            .objects
+            .get_mut(&("Host".to_string(), "web1".to_string()))
+            .expect("object")
+            .data = Some(json!({"ipAddress": "10.0.0.2"}));
+
+        let plan = ApplyPlan::compute(&desired(), &current, false).expect("plan");
+        assert_eq!(plan.count(PlanAction::Update), 2);
+        assert_eq!(plan.changes[0].changes, vec!["validate_schema"]);
+        assert_eq!(plan.changes[1].changes, vec!["data"]);
+        assert_eq!(
+            serde_json::to_value(&plan.changes[1]).expect("change")["patch"],
+            json!([{"op": "replace", "path": "/data/ipAddress", "value": "10.0.0.1"}])
+        );
+    }
+
+    #[test]
+    fn deletes_require_prune_and_stay_within_declared_classes() {
+        let mut current = current();
+        current.objects.insert(
+            ("Host".to_string(), "old".to_string()),
+            CurrentObject::default(),
+        );
+        current.objects.insert(
+            ("Switch".to_string(), "sw1".to_string()),
+            CurrentObject::default(),
+        );
+
+        let plan = ApplyPlan::compute(&desired(), &current, false).expect("plan");
+        assert!(plan.is_empty());
+
+        let plan = ApplyPlan::compute(&desired(), &current, true).expect("plan");
+        assert_eq!(plan.count(PlanAction::Delete), 1);
+        assert_eq!(plan.changes[0].name, "Host/old");
+    }
+
+    #[test]
+    fn pruning_deletes_object_relations_dropped_from_the_file_before_their_objects() {
+        let mut desired = desired();
+        desired.objects.push(DesiredObject {
+            name: "room1".to_string(),
+            class: "Room".to_string(),
+            collection: "infra".to_string(),
+            description: String::new(),
+            data: None,
+        });
+        let kept = DesiredObjectRelation {
+            class_a: "Room".to_string(),
+            object_a: "room1".to_string(),
+            class_b: "Host".to_string(),
+            object_b: "web1".to_string(),
+        };
+        desired.object_relations.push(kept.clone());
+
+        let mut current = current();
+        for (class, name) in [("Room", "room1"), ("Room", "room2"), ("Switch", "sw1")] {
+            current.objects.insert(
+                (class.to_string(), name.to_string()),
+                CurrentObject {
+                    collection: "infra".to_string(),
+                    ..CurrentObject::default()
+                },
+            );
+        }
+        let dropped = DesiredObjectRelation {
+            class_a: "Host".to_string(),
+            object_a: "web1".to_string(),
+            class_b: "Room".to_string(),
+            object_b: "room2".to_string(),
+        };
+        let undeclared_class = DesiredObjectRelation {
+            class_a: "Host".to_string(),
+            object_a: "web1".to_string(),
+            class_b: "Switch".to_string(),
+            object_b: "sw1".to_string(),
+        };
+        for relation in [&kept, &dropped, &undeclared_class] {
+            current.object_relations.insert(relation.key());
+        }
+
+        let plan = ApplyPlan::compute(&desired, &current, false).expect("plan");
+        assert!(plan.is_empty(), "unexpected changes: {:?}", plan.changes);
+
+        let plan = ApplyPlan::compute(&desired, &current, true).expect("plan");
+        let labels = plan
+            .changes
+            .iter()
+            .map(PlanChange::label)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                "delete object relation Host/web1 <-> Room/room2",
+                "delete object Room/room2",
+            ]
+        );
+        assert_eq!(
+            plan.changes[0].operation,
+            PlanOperation::DeleteObjectRelation(dropped)
+        );
+    }
+
+    #[test]
+    fn relation_models_create_missing_and_prune_extra_relations() {
+        let model = DesiredState::parse_relation_model(
+            Path::new("model.yaml"),
+            "class_relations:\n  - class_a: Host\n    class_b: Room\n  - class_a: Host\n    class_b: Rack\n",
+        )
+        .expect("model should parse");
+        let mut current = CurrentState::default();
+        for class in ["Host", "Room", "Rack", "Switch"] {
+            current
+                .classes
+                .insert(class.to_string(), CurrentClass::default());
+        }
+        for (class_a, class_b) in [("Room", "Host"), ("Rack", "Room"), ("Host", "Switch")] {
+            current
+                .class_relations
+                .insert((class_a.to_string(), class_b.to_string()));
+        }
+
+        let plan = ApplyPlan::compute_relation_model(&model, &current, false).expect("plan");
+        assert_eq!(plan.count(PlanAction::Create), 1);
+        assert_eq!(plan.changes[0].name, "Host <-> Rack");
+        assert_eq!(
+            ApplyPlan::extra_class_relations(&model, &current),
+            vec![DesiredClassRelation {
+                class_a: "Rack".to_string(),
+                class_b: "Room".to_string(),
+            }]
+        );
+
+        let plan = ApplyPlan::compute_relation_model(&model, &current, true).expect("plan");
+        assert_eq!(plan.count(PlanAction::Delete), 1);
+
+        current.classes.remove("Rack");
+        assert!(ApplyPlan::compute_relation_model(&model, &current, false).is_err());
+        assert!(DesiredState::parse_relation_model(
+            Path::new("model.yaml"),
+            "classes:\n  - name: Host\n    collection: infra\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn new_collections_need_an_owner() {
+        let mut desired = desired();
+        desired.collections[0].owner = None;
+        assert!(ApplyPlan::compute(&desired, &CurrentState::default(), false).is_err());
+    }
+
+    #[test]
+    fn schema_files_become_classes_named_by_id_or_file_name() {
+        let files = vec![
+            (
+                "host.schema.json".to_string(),
+                r#"{"$id": "https://example.com/schemas/Host.schema.json", "title": "Hosts", "type": "object"}"#
+                    .to_string(),
+            ),
+            (
+                "Room.json".to_string(),
+                r#"{"type": "object", "description": "Rooms"}"#.to_string(),
+            ),
+        ];
+
+        let state = DesiredState::from_schema_files(&files, "infra").expect("schemas parse");
+
+        let names = state
+            .classes
+            .iter()
+            .map(|class| (class.name.as_str(), class.description.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![("Host", "Hosts"), ("Room", "Rooms")]);
+        assert!(state
+            .classes
+            .iter()
+            .all(|class| class.collection == "infra"));
+        assert_eq!(
+            state.classes[1].json_schema,
+            Some(json!({"type": "object", "description": "Rooms"}))
+        );
+
+        let duplicate = vec![
+            ("Host.json".to_string(), "{}".to_string()),
+            (
+                "other.json".to_string(),
+                r#"{"$id": "urn:schemas:Host"}"#.to_string(),
+            ),
+        ];
+        assert!(DesiredState::from_schema_files(&duplicate, "infra").is_err());
+        assert!(DesiredState::from_schema_files(
+            &[("x.json".to_string(), "[]".to_string())],
+            "infra"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn schema_drift_reports_added_removed_and_changed_classes() {
+        let files = vec![
+            (
+                "Host.json".to_string(),
+                r#"{"type": "object", "required": ["ip"]}"#.to_string(),
+            ),
+            ("Room.json".to_string(), r#"{"type": "object"}"#.to_string()),
+            ("Rack.json".to_string(), r#"{"type": "object"}"#.to_string()),
+        ];
+        let desired = DesiredState::from_schema_files(&files, "infra").expect("schemas parse");
+        let mut current = CurrentState::default();
+        for (name, collection, schema) in [
+            ("Host", "infra", Some(json!({"type": "object"}))),
+            ("Room", "infra", Some(json!({"type": "object"}))),
+            ("Switch", "infra", None),
+            ("User", "people", None),
+        ] {
+            current.classes.insert(
+                name.to_string(),
+                CurrentClass {
+                    collection: collection.to_string(),
+                    json_schema: schema,
+                    ..CurrentClass::default()
+                },
+            );
+        }
+
+        let drift = SchemaDrift::compute(&desired, &current, Some("infra"));
+
+        let summary = drift
+            .iter()
+            .map(|entry| (entry.class.as_str(), entry.status, entry.keywords.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("Rack", DriftStatus::Added, vec![]),
+                ("Switch", DriftStatus::Removed, vec![]),
+                ("Host", DriftStatus::Changed, vec!["required".to_string()]),
+            ]
+        );
+        assert_eq!(SchemaDrift::compute(&desired, &current, None).len(), 4);
+    }
+}
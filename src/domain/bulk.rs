@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ImportResultRecord, PlanAction, PlanChange, PlanKind, PlanOperation};
+
+/// Error recorded for changes a bulk operation stopped before sending.
+pub const NOT_ATTEMPTED: &str = "Not attempted because an earlier change failed";
+
+/// What a bulk operation (apply, import) changed, and which items failed.
+///
+/// JSON output carries it as one object so pipelines can assert on the result.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BulkSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub failed: Vec<BulkFailure>,
+    /// Items an earlier, interrupted run completed, skipped by `--resume`.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub resumed: usize,
+    /// Where the failed items were saved for `bulk retry`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_file: Option<String>,
+    /// Where the completed items were recorded for `--resume`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<String>,
+}
+
+fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BulkFailure {
+    pub name: String,
+    pub error: String,
+}
+
+impl BulkSummary {
+    /// Counts one successful item under `action`.
+    pub fn record(&mut self, action: PlanAction) {
+        match action {
+            PlanAction::Create => self.created += 1,
+            PlanAction::Update => self.updated += 1,
+            PlanAction::Delete => self.deleted += 1,
+        }
+    }
+
+    pub fn record_failure(&mut self, name: impl Into<String>, error: impl ToString) {
+        self.failed.push(BulkFailure {
+            name: name.into(),
+            error: error.to_string(),
+        });
+    }
+
+    pub fn has_failures(&self) -> bool {
+        !self.failed.is_empty()
+    }
+
+    /// Summarises the per-item results of a finished import task.
+    ///
+    /// Items the server skipped, or whose action is neither a create, update, nor
+    /// delete, are not counted.
+    pub fn from_import_results(results: &[ImportResultRecord]) -> Self {
+        let mut summary = Self::default();
+        for result in results.iter().map(|record| &record.0) {
+            let name = format!(
+                "{} {}",
+                result.entity_kind,
+                result.identifier.as_deref().unwrap_or("<unknown>")
+            );
+            if result.outcome.eq_ignore_ascii_case("failed") {
+                let error = result.error.as_deref().unwrap_or("failed");
+                summary.record_failure(name, error);
+                continue;
+            }
+            if !result.outcome.eq_ignore_ascii_case("succeeded") {
+                continue;
+            }
+            match result.action.to_ascii_lowercase().as_str() {
+                "create" => summary.record(PlanAction::Create),
+                "update" => summary.record(PlanAction::Update),
+                "delete" => summary.record(PlanAction::Delete),
+                _ => {}
+            }
+        }
+        summary
+    }
+}
+
+/// Changes a bulk operation could not make, with their payloads, for `bulk retry`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetryFile {
+    pub items: Vec<RetryItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryItem {
+    pub action: PlanAction,
+    pub kind: PlanKind,
+    pub name: String,
+    #[serde(flatten)]
+    pub operation: PlanOperation,
+    pub error: String,
+}
+
+impl RetryItem {
+    pub fn new(change: &PlanChange, error: impl ToString) -> Self {
+        Self {
+            action: change.action,
+            kind: change.kind,
+            name: change.name.clone(),
+            operation: change.operation.clone(),
+            error: error.to_string(),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.action.as_str(),
+            self.kind.as_str(),
+            self.name
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_value, json, to_value};
+
+    use crate::domain::apply::DesiredObject;
+
+    use super::*;
+
+    fn result(
+        action: &str,
+        identifier: &str,
+        outcome: &str,
+        error: Option<&str>,
+    ) -> ImportResultRecord {
+        from_value(json!({
+            "id": 1,
+            "task_id": 7,
+            "item_ref": null,
+            "entity_kind": "object",
+            "action": action,
+            "identifier": identifier,
+            "outcome": outcome,
+            "error": error,
+            "details": null,
+            "created_at": "2026-03-01T00:00:00Z"
+        }))
+        .expect("import result fixture should deserialize")
+    }
+
+    #[test]
+    fn import_results_are_counted_by_action_and_outcome() {
+        let summary = BulkSummary::from_import_results(&[
+            result("create", "web01", "succeeded", None),
+            result("create", "web02", "succeeded", None),
+            result("update", "db01", "succeeded", None),
+            result("create", "web03", "failed", Some("name taken")),
+            result("create", "web04", "skipped", None),
+        ]);
+
+        assert_eq!(summary.created, 2);
+        assert_eq!(summary.updated, 1);
+        assert_eq!(summary.deleted, 0);
+        assert_eq!(
+            summary.failed,
+            vec![BulkFailure {
+                name: "object web03".to_string(),
+                error: "name taken".to_string(),
+            }]
+        );
+        assert_eq!(
+            to_value(&summary).unwrap(),
+            json!({
+                "created": 2,
+                "updated": 1,
+                "deleted": 0,
+                "failed": [{"name": "object web03", "error": "name taken"}]
+            })
+        );
+    }
+
+    #[test]
+    fn retry_items_keep_the_payload_to_send_again() {
+        let item = RetryItem {
+            action: PlanAction::Update,
+            kind: PlanKind::Object,
+            name: "web01".to_string(),
+            operation: PlanOperation::UpdateObject(DesiredObject {
+                name: "web01".to_string(),
+                class: "Host".to_string(),
+                collection: "infra".to_string(),
+                description: String::new(),
+                data: Some(json!({"ip": "10.0.0.1"})),
+            }),
+            error: "API Error: Status 500".to_string(),
+        };
+        let file = RetryFile {
+            items: vec![item.clone()],
+        };
+
+        let value = to_value(&file).unwrap();
+        assert_eq!(value["items"][0]["operation"], "update_object");
+        assert_eq!(value["items"][0]["payload"]["data"]["ip"], "10.0.0.1");
+        assert_eq!(from_value::<RetryFile>(value).unwrap(), file);
+        assert_eq!(item.label(), "update object web01");
+    }
+}
@@ -1,13 +1,32 @@
 use hubuum_client::Class;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use super::{ObjectRecord, RelatedClassTreeNode};
 
 transparent_record!(ClassRecord, Class);
 
+/// A class with the number of objects it holds, for `class list --counts`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassCountRecord {
+    #[serde(flatten)]
+    pub class: ClassRecord,
+    /// `None` when the server did not report a total for the class.
+    pub object_count: Option<u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClassShowRecord {
     pub class: ClassRecord,
     pub objects: Vec<ObjectRecord>,
     pub related_classes: Vec<RelatedClassTreeNode>,
 }
+
+/// The JSON schema of one class, for `export json-schema bundle`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassSchemaRecord {
+    pub class: String,
+    pub collection: String,
+    pub description: String,
+    pub json_schema: Value,
+}
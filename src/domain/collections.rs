@@ -1,6 +1,11 @@
-use hubuum_client::{Collection, GroupPermissionsResult};
+use std::str::FromStr;
+
+use hubuum_client::{Collection, GroupPermissionsResult, PermissionResult};
 use serde::{Deserialize, Serialize};
-use strum::{Display, EnumIter};
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use super::{ClassCountRecord, ResolvedClassRelationRecord};
+use crate::errors::AppError;
 
 transparent_record!(CollectionRecord, Collection);
 transparent_record!(GroupPermissionsRecord, GroupPermissionsResult);
@@ -11,6 +16,48 @@ pub struct CollectionPermissionsView {
     pub summary: Vec<GroupPermissionsSummary>,
 }
 
+/// Class and object totals for one collection, with a row per class.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionUsage {
+    pub collection: String,
+    pub classes: Vec<ClassUsage>,
+}
+
+/// Object count and serialized data size of one class.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassUsage {
+    pub class: String,
+    pub objects: u64,
+    pub data_bytes: u64,
+}
+
+impl CollectionUsage {
+    pub fn objects(&self) -> u64 {
+        self.classes.iter().map(|class| class.objects).sum()
+    }
+
+    pub fn data_bytes(&self) -> u64 {
+        self.classes.iter().map(|class| class.data_bytes).sum()
+    }
+}
+
+/// The classes, object counts, class relations, and group permissions of one collection,
+/// as shown by `export html`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionReport {
+    pub collection: CollectionRecord,
+    pub classes: Vec<ClassCountRecord>,
+    pub relations: Vec<ResolvedClassRelationRecord>,
+    pub permissions: Vec<GroupPermissionsSummary>,
+}
+
+impl CollectionReport {
+    /// Total objects across the classes, or `None` if any class count is unknown.
+    pub fn objects(&self) -> Option<u64> {
+        self.classes.iter().map(|class| class.object_count).sum()
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, EnumIter, Display)]
 pub enum CollectionPermission {
     ReadCollection,
@@ -48,6 +95,90 @@ impl CollectionPermission {
     pub fn api_name(self) -> String {
         self.to_string()
     }
+
+    /// The permissions a server-side grant has enabled, in declaration order.
+    pub fn granted(permission: &PermissionResult) -> Vec<Self> {
+        Self::iter()
+            .filter(|candidate| candidate.is_granted(permission))
+            .collect()
+    }
+
+    fn is_granted(self, permission: &PermissionResult) -> bool {
+        match self {
+            Self::ReadCollection => permission.has_read_collection,
+            Self::UpdateCollection => permission.has_update_collection,
+            Self::DeleteCollection => permission.has_delete_collection,
+            Self::DelegateCollection => permission.has_delegate_collection,
+            Self::CreateClass => permission.has_create_class,
+            Self::ReadClass => permission.has_read_class,
+            Self::UpdateClass => permission.has_update_class,
+            Self::DeleteClass => permission.has_delete_class,
+            Self::CreateObject => permission.has_create_object,
+            Self::ReadObject => permission.has_read_object,
+            Self::UpdateObject => permission.has_update_object,
+            Self::DeleteObject => permission.has_delete_object,
+            Self::CreateClassRelation => permission.has_create_class_relation,
+            Self::ReadClassRelation => permission.has_read_class_relation,
+            Self::UpdateClassRelation => permission.has_update_class_relation,
+            Self::DeleteClassRelation => permission.has_delete_class_relation,
+            Self::CreateObjectRelation => permission.has_create_object_relation,
+            Self::ReadObjectRelation => permission.has_read_object_relation,
+            Self::UpdateObjectRelation => permission.has_update_object_relation,
+            Self::DeleteObjectRelation => permission.has_delete_object_relation,
+            Self::ReadTemplate => permission.has_read_template,
+            Self::CreateTemplate => permission.has_create_template,
+            Self::UpdateTemplate => permission.has_update_template,
+            Self::DeleteTemplate => permission.has_delete_template,
+            Self::ReadRemoteTarget => permission.has_read_remote_target,
+            Self::CreateRemoteTarget => permission.has_create_remote_target,
+            Self::UpdateRemoteTarget => permission.has_update_remote_target,
+            Self::DeleteRemoteTarget => permission.has_delete_remote_target,
+            Self::ExecuteRemoteTarget => permission.has_execute_remote_target,
+        }
+    }
+}
+
+/// What `collection permissions set --exact` adds to and removes from a group's grant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionChanges {
+    pub added: Vec<CollectionPermission>,
+    pub removed: Vec<CollectionPermission>,
+}
+
+impl PermissionChanges {
+    pub fn between(current: &[CollectionPermission], desired: &[CollectionPermission]) -> Self {
+        Self {
+            added: CollectionPermission::iter()
+                .filter(|permission| desired.contains(permission) && !current.contains(permission))
+                .collect(),
+            removed: CollectionPermission::iter()
+                .filter(|permission| current.contains(permission) && !desired.contains(permission))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl FromStr for CollectionPermission {
+    type Err = AppError;
+
+    /// Parses a permission name such as `ReadClass`, ignoring case.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::iter()
+            .find(|permission| permission.to_string().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| {
+                AppError::ParseError(format!(
+                    "Unknown permission '{value}'. Use one of: {}",
+                    Self::iter()
+                        .map(|permission| permission.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -121,3 +252,22 @@ impl From<GroupPermissionsResult> for GroupPermissionsSummary {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CollectionPermission, PermissionChanges};
+
+    #[test]
+    fn permission_changes_list_additions_and_removals_in_declaration_order() {
+        use CollectionPermission::*;
+
+        let changes = PermissionChanges::between(
+            &[ReadObject, ReadClass, DeleteObject],
+            &[ReadObject, ReadCollection, ReadClass],
+        );
+
+        assert_eq!(changes.added, vec![ReadCollection]);
+        assert_eq!(changes.removed, vec![DeleteObject]);
+        assert!(PermissionChanges::between(&[ReadClass], &[ReadClass]).is_empty());
+    }
+}
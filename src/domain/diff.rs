@@ -0,0 +1,55 @@
+use json_patch::diff as json_patch_diff;
+use serde::{Serialize, Serializer};
+use serde_json::{to_value, Value};
+
+/// Two versions of the same value, such as the server state and a local file.
+///
+/// Machine output serializes the difference as an RFC 6902 JSON Patch that turns
+/// `before` into `after`; terminals render it with `formatting::render_value_diff`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueDiff {
+    pub before: Value,
+    pub after: Value,
+}
+
+impl ValueDiff {
+    pub fn new(before: Value, after: Value) -> Self {
+        Self { before, after }
+    }
+
+    /// The JSON Patch operations that turn `before` into `after`.
+    pub fn patch(&self) -> Value {
+        to_value(json_patch_diff(&self.before, &self.after)).unwrap_or(Value::Null)
+    }
+}
+
+impl Serialize for ValueDiff {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.patch().serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn serializes_as_a_json_patch() {
+        let diff = ValueDiff::new(
+            json!({"os": "linux", "cpus": 2, "tags": ["a"]}),
+            json!({"os": "linux", "cpus": 4, "owner": "ops"}),
+        );
+        let patch = serde_json::to_value(&diff).unwrap();
+        let operations = patch.as_array().unwrap();
+        assert!(operations.contains(&json!({"op": "replace", "path": "/cpus", "value": 4})));
+        assert!(operations.contains(&json!({"op": "remove", "path": "/tags"})));
+        assert!(operations.contains(&json!({"op": "add", "path": "/owner", "value": "ops"})));
+
+        let mut patched = diff.before.clone();
+        let patch: json_patch::Patch = serde_json::from_value(patch).unwrap();
+        json_patch::patch(&mut patched, &patch).unwrap();
+        assert_eq!(patched, diff.after);
+    }
+}
@@ -5,6 +5,7 @@ use hubuum_filter::OutputEnvelope;
 
 use crate::errors::AppError;
 use crate::formatting::{OutputFormatter, TableRenderable};
+use crate::masking::mask_object_output;
 use crate::output::set_semantic_output;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -272,7 +273,10 @@ fn write_pretty_json(value: &Value, indent: usize, output: &mut String) {
 
 impl OutputFormatter for JsonRecord {
     fn format(&self) -> Result<Self, AppError> {
-        let (value, columns) = match &self.value {
+        // Masked before the fields are summarised into text, which the output layer cannot see into.
+        let mut record = self.value.clone();
+        mask_object_output(&mut record);
+        let (value, columns) = match &record {
             Value::Object(map) => {
                 let mut object = Map::new();
                 let mut columns = Vec::with_capacity(map.len());
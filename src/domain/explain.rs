@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// An API request as `--explain` lists it, with the values from the command line filled in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExplainedCall {
+    pub step: usize,
+    pub method: String,
+    pub path: String,
+    /// Query parameters, as `field__operator=value`.
+    pub query: Vec<String>,
+    pub purpose: String,
+}
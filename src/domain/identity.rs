@@ -1,8 +1,49 @@
+use chrono::{DateTime, Utc};
 use hubuum_client::{
     MeResponse, PrincipalCollectionPermissions, PrincipalTokenMetadata, ServiceAccount,
 };
+use serde::{Deserialize, Serialize};
 
 transparent_record!(MeRecord, MeResponse);
 transparent_record!(PrincipalTokenRecord, PrincipalTokenMetadata);
 transparent_record!(PrincipalPermissionsRecord, PrincipalCollectionPermissions);
 transparent_record!(ServiceAccountRecord, ServiceAccount);
+
+/// Who the CLI is logged in as, on which server, and with what standing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub username: String,
+    pub kind: String,
+    pub identity_scope: String,
+    pub server: String,
+    pub token_id: i32,
+    pub token_issued: DateTime<Utc>,
+    pub token_age_seconds: i64,
+    pub token_expires_at: Option<DateTime<Utc>>,
+    pub groups: Vec<String>,
+    pub admin: bool,
+}
+
+impl SessionRecord {
+    pub fn new(
+        me: MeResponse,
+        server: String,
+        mut groups: Vec<String>,
+        admin: bool,
+        now: DateTime<Utc>,
+    ) -> Self {
+        groups.sort();
+        Self {
+            username: me.principal.name,
+            kind: me.principal.kind,
+            identity_scope: me.principal.identity_scope,
+            server,
+            token_id: me.token.id.get(),
+            token_issued: me.token.issued.0,
+            token_age_seconds: (now - me.token.issued.0).num_seconds().max(0),
+            token_expires_at: me.token.expires_at.map(|expires_at| expires_at.0),
+            groups,
+            admin,
+        }
+    }
+}
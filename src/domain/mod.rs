@@ -18,61 +18,81 @@ macro_rules! transparent_record {
     };
 }
 
+mod apply;
 mod backups;
+mod bulk;
 mod classes;
 mod collections;
 mod computed;
+mod diff;
 mod events;
+mod explain;
 mod exports;
 mod groups;
 mod identity;
 mod imports;
 mod object_fields;
+mod object_import;
 mod objects;
+mod profiles;
 mod relations;
 mod remote_targets;
 mod search;
+mod stats;
 mod task_output;
 mod tasks;
 mod users;
 
+pub use apply::{
+    ApplyPlan, CurrentClass, CurrentCollection, CurrentObject, CurrentState, DesiredObject,
+    DesiredObjectRelation, DesiredState, PlanAction, PlanChange, PlanKind, PlanOperation,
+    SchemaDrift,
+};
 pub use backups::{BackupArtifact, RestoreReceipt, RestoreRecord};
-pub use classes::{ClassRecord, ClassShowRecord};
+pub use bulk::{BulkFailure, BulkSummary, RetryFile, RetryItem, NOT_ATTEMPTED};
+pub use classes::{ClassCountRecord, ClassRecord, ClassSchemaRecord, ClassShowRecord};
 pub use collections::{
-    CollectionPermission, CollectionPermissionsView, CollectionRecord, GroupPermissionsRecord,
-    GroupPermissionsSummary,
+    ClassUsage, CollectionPermission, CollectionPermissionsView, CollectionRecord,
+    CollectionReport, CollectionUsage, GroupPermissionsRecord, GroupPermissionsSummary,
+    PermissionChanges,
 };
 pub use computed::{
     ClassComputationStateRecord, ComputedFieldDeleteRecord, ComputedFieldMutationRecord,
     ComputedFieldPreviewRecord, ComputedFieldRecord, ComputedFieldSelector, ComputedFieldSet,
     SharedComputedFieldListRecord,
 };
+pub use diff::ValueDiff;
 pub use events::JsonRecord;
+pub use explain::ExplainedCall;
 pub use exports::{ExportOutput, ExportTemplateRecord};
 pub use groups::{GroupDetails, GroupRecord, PrincipalMemberRecord};
 pub use identity::{
-    MeRecord, PrincipalPermissionsRecord, PrincipalTokenRecord, ServiceAccountRecord,
+    MeRecord, PrincipalPermissionsRecord, PrincipalTokenRecord, ServiceAccountRecord, SessionRecord,
 };
 pub use imports::ImportResultRecord;
 pub use object_fields::{
     observed_json_pointers, visit_observed_data_fields, DEFAULT_OBJECT_FIELD_DEPTH,
     DEFAULT_OBJECT_FIELD_SAMPLE_LIMIT,
 };
+pub use object_import::parse_object_import;
 pub use objects::{
-    ObjectDataMutationOutcome, ObjectDataMutationRecord, ObjectRecord, ObjectShowRecord,
-    ResolvedObjectRecord,
+    ObjectDataMatchRecord, ObjectDataMutationOutcome, ObjectDataMutationRecord, ObjectRecord,
+    ObjectShowRecord, ObjectValidationRecord, ResolvedObjectRecord, SchemaViolation,
+    StaleObjectRecord,
 };
+pub use profiles::{ProfileCheckRecord, TokenCheck};
 pub use relations::{
-    build_related_class_tree, build_related_object_tree, RelatedClassTreeNode,
-    RelatedObjectTreeNode, ResolvedClassRelationRecord, ResolvedObjectRelationRecord,
-    ResolvedRelatedClassGraph, ResolvedRelatedClassRecord, ResolvedRelatedObjectGraph,
-    ResolvedRelatedObjectRecord,
+    build_related_class_tree, build_related_object_tree, from_to, ClassRelationMatrix,
+    RelatedClassTreeNode, RelatedObjectTreeNode, RelationDirection, ResolvedClassRelationRecord,
+    ResolvedObjectRelationRecord, ResolvedRelatedClassGraph, ResolvedRelatedClassRecord,
+    ResolvedRelatedObjectGraph, ResolvedRelatedObjectRecord,
 };
 pub use remote_targets::RemoteTargetRecord;
 pub use search::{
     SearchBatchRecord, SearchCursorSet, SearchErrorEvent, SearchQueryEvent, SearchResponseRecord,
     SearchResultsRecord, SearchStreamEvent,
 };
+pub use stats::{timeline_buckets, TimelineBucket, TimelineGrouping, TimelinePeriod};
 pub use task_output::TaskOutput;
 pub use tasks::{TaskEventRecord, TaskQueueStateRecord, TaskRecord};
 pub use users::{CreatedUser, UserRecord};
@@ -0,0 +1,332 @@
+use std::path::Path;
+
+use csv::ReaderBuilder;
+use serde_json::{from_str, Map, Value};
+
+use super::apply::DesiredObject;
+use crate::errors::AppError;
+use crate::json_schema::schema_property_types;
+
+/// Columns and keys that fill the object itself rather than its data.
+const NAME: &str = "name";
+const DESCRIPTION: &str = "description";
+const DATA: &str = "data";
+
+/// Reads the objects of an `object import` file into `class` and `collection`.
+///
+/// A `.json` file (or one starting with `[`) holds an array of objects with a `name`, an
+/// optional `description` and `data`, and any other keys merged into the data. Anything
+/// else is CSV, or TSV for `.tsv` files, with a header row: `name` and `description` fill
+/// the object and every other column a data key, where dots nest and a leading `data.`
+/// is dropped, so the columns of `object data export` import as they were exported.
+/// Empty cells are left out. Cells are text unless the class `schema` gives their field
+/// only non-string types, such as `integer` or `array`, in which case a cell holding JSON
+/// is read as that JSON. The whole file is checked before anything is sent.
+pub fn parse_object_import(
+    path: &Path,
+    content: &str,
+    class: &str,
+    collection: &str,
+    schema: Option<&Value>,
+) -> Result<Vec<DesiredObject>, AppError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+    let rows = match extension.as_deref() {
+        Some("json") => json_rows(content)?,
+        Some("tsv") => delimited_rows(content, b'\t', schema)?,
+        _ if content.trim_start().starts_with('[') => json_rows(content)?,
+        _ => delimited_rows(content, b',', schema)?,
+    };
+    if rows.is_empty() {
+        return Err(AppError::ParseError(format!(
+            "{} holds no objects to import",
+            path.display()
+        )));
+    }
+    Ok(rows
+        .into_iter()
+        .map(|row| DesiredObject {
+            name: row.name,
+            class: class.to_string(),
+            collection: collection.to_string(),
+            description: row.description,
+            data: (!row.data.is_empty()).then_some(Value::Object(row.data)),
+        })
+        .collect())
+}
+
+#[derive(Debug, Default)]
+struct ImportRow {
+    name: String,
+    description: String,
+    data: Map<String, Value>,
+}
+
+fn json_rows(content: &str) -> Result<Vec<ImportRow>, AppError> {
+    let items = match from_str::<Value>(content)? {
+        Value::Array(items) => items,
+        _ => {
+            return Err(AppError::ParseError(
+                "A JSON import file must hold an array of objects".to_string(),
+            ))
+        }
+    };
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| json_row(index + 1, item))
+        .collect()
+}
+
+fn json_row(number: usize, item: Value) -> Result<ImportRow, AppError> {
+    let Value::Object(fields) = item else {
+        return Err(AppError::ParseError(format!(
+            "Item {number} is not a JSON object"
+        )));
+    };
+    let mut row = ImportRow::default();
+    for (key, value) in fields {
+        match (key.as_str(), value) {
+            (NAME, Value::String(name)) => row.name = name,
+            (DESCRIPTION, Value::String(description)) => row.description = description,
+            (NAME | DESCRIPTION, _) => {
+                return Err(AppError::ParseError(format!(
+                    "Item {number} has a {key} that is not a string"
+                )))
+            }
+            (DATA, Value::Object(data)) => row.data.extend(data),
+            (DATA, Value::Null) => {}
+            (DATA, _) => {
+                return Err(AppError::ParseError(format!(
+                    "Item {number} has data that is not a JSON object"
+                )))
+            }
+            (_, value) => {
+                row.data.insert(key, value);
+            }
+        }
+    }
+    if row.name.is_empty() {
+        return Err(AppError::ParseError(format!("Item {number} has no name")));
+    }
+    Ok(row)
+}
+
+fn delimited_rows(
+    content: &str,
+    delimiter: u8,
+    schema: Option<&Value>,
+) -> Result<Vec<ImportRow>, AppError> {
+    let mut records = parse_delimited(content, delimiter)?.into_iter();
+    let Some(header) = records.next() else {
+        return Ok(Vec::new());
+    };
+    let columns = header
+        .iter()
+        .map(|column| Column::parse(column.trim()))
+        .collect::<Vec<_>>();
+    if !columns.contains(&Column::Name) {
+        return Err(AppError::ParseError(
+            "The import file has no 'name' column".to_string(),
+        ));
+    }
+
+    records
+        .enumerate()
+        .map(|(index, cells)| {
+            let number = index + 1;
+            if cells.len() != columns.len() {
+                return Err(AppError::ParseError(format!(
+                    "Row {number} has {} cell(s) but the header has {}",
+                    cells.len(),
+                    columns.len()
+                )));
+            }
+            let mut row = ImportRow::default();
+            for ((column, cell), heading) in columns.iter().zip(cells).zip(&header) {
+                match column {
+                    Column::Name => row.name = cell,
+                    Column::Description => row.description = cell,
+                    Column::Data(_) if cell.is_empty() => {}
+                    Column::Data(path) => {
+                        insert_path(&mut row.data, path, cell_value(cell, schema, path)).map_err(|()| {
+                            AppError::ParseError(format!(
+                                "Row {number}: column '{heading}' nests under a value that is not an object"
+                            ))
+                        })?
+                    }
+                }
+            }
+            if row.name.is_empty() {
+                return Err(AppError::ParseError(format!("Row {number} has no name")));
+            }
+            Ok(row)
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+enum Column {
+    Name,
+    Description,
+    Data(Vec<String>),
+}
+
+impl Column {
+    fn parse(heading: &str) -> Self {
+        match heading {
+            NAME => Self::Name,
+            DESCRIPTION => Self::Description,
+            _ => {
+                let path = heading.strip_prefix("data.").unwrap_or(heading);
+                Self::Data(path.split('.').map(str::to_string).collect())
+            }
+        }
+    }
+}
+
+/// Reads a cell as JSON where `schema` gives its field only non-string types; every
+/// other cell, such as `12345` in a field without a schema, is kept as text.
+fn cell_value(cell: String, schema: Option<&Value>, path: &[String]) -> Value {
+    let types = schema
+        .map(|schema| schema_property_types(schema, path))
+        .unwrap_or_default();
+    if types.is_empty() || types.contains(&"string") {
+        return Value::String(cell);
+    }
+    from_str(&cell).unwrap_or(Value::String(cell))
+}
+
+fn insert_path(data: &mut Map<String, Value>, path: &[String], value: Value) -> Result<(), ()> {
+    let Some((last, parents)) = path.split_last() else {
+        return Ok(());
+    };
+    let mut current = data;
+    for key in parents {
+        let entry = current
+            .entry(key.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+        current = entry.as_object_mut().ok_or(())?;
+    }
+    if current.get(last).is_some_and(Value::is_object) {
+        return Err(());
+    }
+    current.insert(last.clone(), value);
+    Ok(())
+}
+
+/// Splits CSV-style text into records. Quoted cells may hold the delimiter, doubled
+/// quotes, and line breaks; blank lines are skipped.
+fn parse_delimited(content: &str, delimiter: u8) -> Result<Vec<Vec<String>>, AppError> {
+    ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(content.as_bytes())
+        .records()
+        .map(|record| Ok(record?.iter().map(str::to_string).collect()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn parse(file: &str, content: &str) -> Result<Vec<DesiredObject>, AppError> {
+        parse_object_import(Path::new(file), content, "Host", "infra", None)
+    }
+
+    #[test]
+    fn csv_columns_fill_name_description_and_nested_data() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "os": {"type": "object", "properties": {"name": {"type": "string"}}},
+                "ip": {"type": "string"},
+                "rack": {"type": "integer"},
+                "tags": {"type": "array"}
+            }
+        });
+        let objects = parse_object_import(
+            Path::new("hosts.csv"),
+            "name,description,data.os.name,ip,rack,tags\r\n\
+             web01,\"Web, front\",Fedora,10.0.0.1,4,\"[\"\"a\"\"]\"\r\n\
+             \r\n\
+             db01,,Debian,10.0.0.2,,\n",
+            "Host",
+            "infra",
+            Some(&schema),
+        )
+        .unwrap();
+
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[0].name, "web01");
+        assert_eq!(objects[0].class, "Host");
+        assert_eq!(objects[0].collection, "infra");
+        assert_eq!(objects[0].description, "Web, front");
+        assert_eq!(
+            objects[0].data,
+            Some(json!({"os": {"name": "Fedora"}, "ip": "10.0.0.1", "rack": 4, "tags": ["a"]}))
+        );
+        assert_eq!(objects[1].description, "");
+        assert_eq!(
+            objects[1].data,
+            Some(json!({"os": {"name": "Debian"}, "ip": "10.0.0.2"}))
+        );
+    }
+
+    #[test]
+    fn cells_stay_text_unless_the_schema_types_their_field() {
+        let schema = json!({"properties": {"port": {"type": "integer"}, "serial": {"type": ["string", "null"]}}});
+        let import = |schema| {
+            parse_object_import(
+                Path::new("hosts.csv"),
+                "name,serial,port,enabled\nweb01,\"12345\",8080,true\n",
+                "Host",
+                "infra",
+                schema,
+            )
+            .unwrap()
+            .remove(0)
+            .data
+        };
+
+        assert_eq!(
+            import(None),
+            Some(json!({"serial": "12345", "port": "8080", "enabled": "true"}))
+        );
+        assert_eq!(
+            import(Some(&schema)),
+            Some(json!({"serial": "12345", "port": 8080, "enabled": "true"}))
+        );
+    }
+
+    #[test]
+    fn json_arrays_merge_extra_keys_into_data() {
+        let objects = parse(
+            "hosts.json",
+            r#"[{"name": "web01", "data": {"ip": "10.0.0.1"}, "rack": 4}, {"name": "db01"}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(objects[0].data, Some(json!({"ip": "10.0.0.1", "rack": 4})));
+        assert_eq!(objects[1].data, None);
+    }
+
+    #[test]
+    fn malformed_files_are_rejected_before_anything_is_sent() {
+        let error = |file: &str, content: &str| parse(file, content).unwrap_err().to_string();
+
+        assert!(error("hosts.csv", "host,ip\nweb01,10.0.0.1\n").contains("no 'name' column"));
+        assert!(error("hosts.csv", "name,ip\nweb01\n").contains("Row 1 has 1 cell(s)"));
+        assert!(error("hosts.csv", "name,ip\n,10.0.0.1\n").contains("Row 1 has no name"));
+        assert!(error("hosts.csv", "name,os,os.name\nweb01,x,y\n").contains("column 'os.name'"));
+        assert!(error("hosts.csv", "name\n").contains("holds no objects"));
+        assert!(error("hosts.json", r#"{"name": "web01"}"#).contains("array of objects"));
+        assert!(error("hosts.json", r#"[{"name": 1}]"#).contains("Item 1 has a name"));
+    }
+}
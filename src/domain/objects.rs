@@ -24,6 +24,21 @@ impl ObjectDataMutationOutcome {
     }
 }
 
+/// A single place where object data does not satisfy its class schema.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SchemaViolation {
+    pub pointer: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectValidationRecord {
+    pub class: String,
+    pub object: String,
+    pub valid: bool,
+    pub violations: Vec<SchemaViolation>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ObjectDataMutationRecord {
     pub outcome: ObjectDataMutationOutcome,
@@ -45,6 +60,26 @@ impl ObjectDataMutationRecord {
     }
 }
 
+/// An object that has not been updated since the `object stale` threshold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleObjectRecord {
+    pub collection: String,
+    pub id: i32,
+    pub name: String,
+    pub updated_at: String,
+    pub age_days: i64,
+}
+
+/// A flattened object data value matched by `object grep`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectDataMatchRecord {
+    pub collection: String,
+    pub id: i32,
+    pub name: String,
+    pub path: String,
+    pub value: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResolvedObjectRecord {
     pub id: i32,
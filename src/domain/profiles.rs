@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// What `profile doctor` found for one `[profiles.<name>]` section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileCheckRecord {
+    pub profile: String,
+    pub server: String,
+    pub username: String,
+    pub reachable: bool,
+    pub token: TokenCheck,
+    /// `None` when the server could not be reached.
+    pub server_version: Option<String>,
+    /// Why the server was unreachable or the check is incomplete.
+    pub problem: Option<String>,
+}
+
+/// Whether the server accepts the stored token of a profile's login.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenCheck {
+    Valid,
+    Rejected,
+    /// No token is cached for the login, so using the profile asks for a password.
+    Missing,
+    /// The server was not reached, so the token was not tried.
+    Unchecked,
+}
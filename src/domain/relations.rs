@@ -6,11 +6,61 @@ use hubuum_client::{
 };
 use serde::{Deserialize, Serialize};
 
+/// Which way a relation runs as seen from the class or object a command was asked about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RelationDirection {
+    /// The anchor is the relation's `from` end.
+    Outgoing,
+    /// The anchor is the relation's `to` end.
+    Incoming,
+}
+
+impl RelationDirection {
+    fn between(anchor_id: i32, from_id: i32, to_id: i32) -> Option<Self> {
+        if anchor_id == from_id {
+            Some(Self::Outgoing)
+        } else if anchor_id == to_id {
+            Some(Self::Incoming)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Outgoing => "outgoing",
+            Self::Incoming => "incoming",
+        }
+    }
+}
+
+/// Orders the endpoints `a` and `b` the way the server stores their relation, `from` first.
+///
+/// Class relations are created from the lower class id to the higher one, and object
+/// relations follow the class relation they belong to, so callers pass whether `a` is that
+/// `from` end.
+pub fn from_to<T>(a: T, b: T, a_is_from: bool) -> (T, T) {
+    if a_is_from {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResolvedClassRelationRecord {
     pub id: i32,
     pub class_a: String,
+    pub class_a_id: i32,
     pub class_b: String,
+    pub class_b_id: i32,
+    /// Number of object relations using this class relation, when it was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object_relations: Option<u64>,
+    /// How the relation runs from the class the command was asked about, if it touches it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<RelationDirection>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -29,20 +79,48 @@ impl ResolvedClassRelationRecord {
         Self {
             id: class_relation.id.into(),
             class_a,
+            class_a_id: class_relation.from_hubuum_class_id.into(),
             class_b,
+            class_b_id: class_relation.to_hubuum_class_id.into(),
+            object_relations: None,
+            direction: None,
             created_at: class_relation.created_at.to_string(),
             updated_at: class_relation.updated_at.to_string(),
         }
     }
+
+    pub fn with_object_relations(mut self, count: Option<u64>) -> Self {
+        self.object_relations = count;
+        self
+    }
+
+    /// Records how the relation runs from `class_id`, the class the command was asked about.
+    pub fn seen_from(mut self, class_id: i32) -> Self {
+        self.direction = RelationDirection::between(class_id, self.class_a_id, self.class_b_id);
+        self
+    }
+
+    /// The relation as stored by the server, e.g. `Hosts → Rooms`.
+    pub fn arrow(&self) -> String {
+        format!("{} → {}", self.class_a, self.class_b)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResolvedObjectRelationRecord {
     pub id: i32,
+    pub class_relation_id: i32,
     pub class_a: String,
+    pub class_a_id: i32,
     pub class_b: String,
+    pub class_b_id: i32,
     pub object_a: String,
+    pub object_a_id: i32,
     pub object_b: String,
+    pub object_b_id: i32,
+    /// How the relation runs from the object the command was asked about, if it touches it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<RelationDirection>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -73,14 +151,34 @@ impl ResolvedObjectRelationRecord {
 
         Self {
             id: object_relation.id.into(),
+            class_relation_id: class_relation.id.into(),
             class_a,
+            class_a_id: class_relation.from_hubuum_class_id.into(),
             class_b,
+            class_b_id: class_relation.to_hubuum_class_id.into(),
             object_a,
+            object_a_id: object_relation.from_hubuum_object_id.into(),
             object_b,
+            object_b_id: object_relation.to_hubuum_object_id.into(),
+            direction: None,
             created_at: object_relation.created_at.to_string(),
             updated_at: object_relation.updated_at.to_string(),
         }
     }
+
+    /// Records how the relation runs from `object_id`, the object the command was asked about.
+    pub fn seen_from(mut self, object_id: i32) -> Self {
+        self.direction = RelationDirection::between(object_id, self.object_a_id, self.object_b_id);
+        self
+    }
+
+    /// The relation as stored by the server, e.g. `Hosts/web1 → Rooms/B701`.
+    pub fn arrow(&self) -> String {
+        format!(
+            "{}/{} → {}/{}",
+            self.class_a, self.object_a, self.class_b, self.object_b
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -174,6 +272,48 @@ pub struct ResolvedRelatedClassGraph {
     pub relations: Vec<ResolvedClassRelationRecord>,
 }
 
+/// Class × class overview of which classes are related.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassRelationMatrix {
+    pub classes: Vec<String>,
+    pub relations: Vec<ResolvedClassRelationRecord>,
+}
+
+impl ClassRelationMatrix {
+    /// Builds the matrix over `classes`, keeping only relations between two of them.
+    pub fn new(mut classes: Vec<String>, relations: Vec<ResolvedClassRelationRecord>) -> Self {
+        classes.sort();
+        classes.dedup();
+        let mut relations = relations
+            .into_iter()
+            .filter(|relation| {
+                classes.contains(&relation.class_a) && classes.contains(&relation.class_b)
+            })
+            .collect::<Vec<_>>();
+        relations.sort_by(|left, right| {
+            (&left.class_a, &left.class_b).cmp(&(&right.class_a, &right.class_b))
+        });
+        Self { classes, relations }
+    }
+
+    /// The cell for `row` and `column`: `·` when unrelated, otherwise the object relation
+    /// count when it was requested and `x` when it was not.
+    ///
+    /// Class relations are unordered, so the matrix is symmetric.
+    pub fn cell(&self, row: &str, column: &str) -> String {
+        let relation = self.relations.iter().find(|relation| {
+            (relation.class_a == row && relation.class_b == column)
+                || (relation.class_a == column && relation.class_b == row)
+        });
+        match relation {
+            None => "·".to_string(),
+            Some(relation) => relation
+                .object_relations
+                .map_or_else(|| "x".to_string(), |count| count.to_string()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct RelatedObjectTreeNode {
     pub id: i32,
@@ -509,4 +649,64 @@ mod tests {
         assert_eq!(tree[0].children.len(), 1);
         assert_eq!(tree[0].children[0].label(), "Rooms");
     }
+
+    fn relation_record(
+        class_a: &str,
+        class_b: &str,
+        count: Option<u64>,
+    ) -> ResolvedClassRelationRecord {
+        ResolvedClassRelationRecord {
+            id: 1,
+            class_a: class_a.to_string(),
+            class_a_id: 1,
+            class_b: class_b.to_string(),
+            class_b_id: 2,
+            object_relations: count,
+            direction: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn endpoints_are_ordered_from_to_and_directions_follow_the_anchor() {
+        assert_eq!(from_to("Hosts", "Rooms", true), ("Hosts", "Rooms"));
+        assert_eq!(from_to("Rooms", "Hosts", false), ("Hosts", "Rooms"));
+
+        let relation = relation_record("Hosts", "Rooms", None);
+        assert_eq!(
+            relation.clone().seen_from(2).direction,
+            Some(RelationDirection::Incoming)
+        );
+        assert_eq!(relation.seen_from(3).direction, None);
+    }
+
+    #[test]
+    fn relation_matrix_is_symmetric_and_limited_to_its_classes() {
+        let matrix = ClassRelationMatrix::new(
+            vec![
+                "Rooms".to_string(),
+                "Hosts".to_string(),
+                "Jacks".to_string(),
+            ],
+            vec![
+                relation_record("Hosts", "Rooms", None),
+                relation_record("Jacks", "Jacks", None),
+                relation_record("Hosts", "Vendors", None),
+            ],
+        );
+
+        assert_eq!(matrix.classes, vec!["Hosts", "Jacks", "Rooms"]);
+        assert_eq!(matrix.relations.len(), 2);
+        assert_eq!(matrix.cell("Hosts", "Rooms"), "x");
+        assert_eq!(matrix.cell("Rooms", "Hosts"), "x");
+        assert_eq!(matrix.cell("Jacks", "Jacks"), "x");
+        assert_eq!(matrix.cell("Hosts", "Jacks"), "·");
+
+        let counted = ClassRelationMatrix::new(
+            vec!["Hosts".to_string(), "Rooms".to_string()],
+            vec![relation_record("Rooms", "Hosts", Some(12))],
+        );
+        assert_eq!(counted.cell("Hosts", "Rooms"), "12");
+    }
 }
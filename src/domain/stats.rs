@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// Calendar period that `stats timeline` groups creation times into.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, EnumString, Display,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum TimelinePeriod {
+    Week,
+    #[default]
+    Month,
+}
+
+impl TimelinePeriod {
+    /// First day of the period containing `date`: the Monday of its ISO week or the first of
+    /// its month.
+    fn start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Week => date - TimeDelta::days(date.weekday().num_days_from_monday().into()),
+            Self::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    fn next(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Week => start + TimeDelta::days(7),
+            Self::Month => start + Months::new(1),
+        }
+    }
+
+    fn label(self, start: NaiveDate) -> String {
+        match self {
+            Self::Week => start.format("%G-W%V").to_string(),
+            Self::Month => start.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// What `stats timeline` keeps separate counts for.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, EnumString, Display,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum TimelineGrouping {
+    #[default]
+    Collection,
+    Class,
+}
+
+/// Entities created in one period of one group, with the group's running total.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimelineBucket {
+    pub group: String,
+    pub period: String,
+    pub created: u64,
+    pub total: u64,
+}
+
+/// Counts creation times per group and period.
+///
+/// Every group runs from its first period to the latest period of any group, so quiet
+/// periods show up as zero instead of disappearing from the chart.
+pub fn timeline_buckets(
+    created: impl IntoIterator<Item = (String, DateTime<Utc>)>,
+    period: TimelinePeriod,
+) -> Vec<TimelineBucket> {
+    let mut groups: BTreeMap<String, BTreeMap<NaiveDate, u64>> = BTreeMap::new();
+    for (group, at) in created {
+        *groups
+            .entry(group)
+            .or_default()
+            .entry(period.start(at.date_naive()))
+            .or_default() += 1;
+    }
+    let Some(last) = groups
+        .values()
+        .filter_map(|counts| counts.keys().next_back())
+        .max()
+        .copied()
+    else {
+        return Vec::new();
+    };
+
+    let mut buckets = Vec::new();
+    for (group, counts) in groups {
+        let Some(mut start) = counts.keys().next().copied() else {
+            continue;
+        };
+        let mut total = 0;
+        while start <= last {
+            let created = counts.get(&start).copied().unwrap_or_default();
+            total += created;
+            buckets.push(TimelineBucket {
+                group: group.clone(),
+                period: period.label(start),
+                created,
+                total,
+            });
+            start = period.next(start);
+        }
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::{timeline_buckets, TimelineBucket, TimelinePeriod};
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        timestamp.parse().expect("timestamp should parse")
+    }
+
+    fn bucket(group: &str, period: &str, created: u64, total: u64) -> TimelineBucket {
+        TimelineBucket {
+            group: group.to_string(),
+            period: period.to_string(),
+            created,
+            total,
+        }
+    }
+
+    #[test]
+    fn months_without_new_entities_are_kept_as_zero() {
+        let created = [
+            ("prod", "2026-01-05T10:00:00Z"),
+            ("prod", "2026-01-31T23:59:59Z"),
+            ("prod", "2026-03-01T00:00:00Z"),
+            ("lab", "2026-02-14T12:00:00Z"),
+        ]
+        .map(|(group, timestamp)| (group.to_string(), at(timestamp)));
+
+        assert_eq!(
+            timeline_buckets(created, TimelinePeriod::Month),
+            vec![
+                bucket("lab", "2026-02", 1, 1),
+                bucket("lab", "2026-03", 0, 1),
+                bucket("prod", "2026-01", 2, 2),
+                bucket("prod", "2026-02", 0, 2),
+                bucket("prod", "2026-03", 1, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn weeks_follow_iso_numbering_across_years() {
+        let created = [
+            "2025-12-29T08:00:00Z",
+            "2026-01-04T20:00:00Z",
+            "2026-01-05T08:00:00Z",
+        ]
+        .map(|timestamp| ("prod".to_string(), at(timestamp)));
+
+        assert_eq!(
+            timeline_buckets(created, TimelinePeriod::Week),
+            vec![
+                bucket("prod", "2026-W01", 2, 2),
+                bucket("prod", "2026-W02", 1, 3),
+            ]
+        );
+        assert!(timeline_buckets(Vec::new(), TimelinePeriod::Week).is_empty());
+    }
+}
@@ -1,8 +1,10 @@
+use std::error::Error as StdError;
 use std::io::Error as StdIoError;
 use std::num::ParseIntError;
 use std::str::ParseBoolError;
 
 use config::ConfigError;
+use csv::Error as CsvError;
 use hubuum_client::ApiError;
 use hubuum_filter::PipelineError as FilterPipelineError;
 use jqesque::JqesqueError;
@@ -36,6 +38,9 @@ pub enum AppError {
     #[error("JSON parse error: {0}")]
     ParseJsonError(#[from] JsonError),
 
+    #[error("CSV error: {0}")]
+    CsvError(#[from] CsvError),
+
     #[error("Boolean parse error: {0}")]
     ParseBoolError(#[from] ParseBoolError),
 
@@ -77,7 +82,10 @@ pub enum AppError {
     DataDirError(String),
 
     #[error("API error: {0}")]
-    ApiError(#[from] ApiError),
+    ApiError(ApiError),
+
+    #[error("TLS verification failed: {0}. Check that the server certificate is valid for the configured hostname and trusted by this system, or use --ssl-validation false to connect without verification (insecure)")]
+    TlsError(String),
 
     #[allow(dead_code)]
     #[error("Multiple entities found: {0}")]
@@ -87,7 +95,7 @@ pub enum AppError {
     #[error("Entity not found: {0}")]
     EntityNotFound(String),
 
-    #[allow(dead_code)]
+    /// The command failed after reporting the failure in its own output, which is kept.
     #[error("Quiet error")]
     Quiet,
 
@@ -99,4 +107,125 @@ pub enum AppError {
 
     #[error("Configuration error: {0}")]
     GeneralConfigError(String),
+
+    #[error("OS keychain error: {0}")]
+    KeyringError(String),
+
+    /// An API error together with what the CLI was doing when it happened.
+    #[error("Failed {context}: {source}")]
+    Context {
+        context: String,
+        source: Box<AppError>,
+    },
+}
+
+impl AppError {
+    /// The client error behind this error, looking through any added context.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            AppError::ApiError(error) => Some(error),
+            AppError::Context { source, .. } => source.api_error(),
+            _ => None,
+        }
+    }
+}
+
+/// Adds what the CLI was doing to API errors, such as
+/// "creating object 'web1' in class 'Hosts'". Other errors pass through unchanged.
+pub trait ApiContext<T> {
+    fn context(self, context: impl FnOnce() -> String) -> Result<T, AppError>;
+}
+
+impl<T, E: Into<AppError>> ApiContext<T> for Result<T, E> {
+    fn context(self, context: impl FnOnce() -> String) -> Result<T, AppError> {
+        self.map_err(|error| match error.into() {
+            error @ AppError::ApiError(_) => AppError::Context {
+                context: context(),
+                source: Box::new(error),
+            },
+            error => error,
+        })
+    }
+}
+
+/// Fragments that rustls and reqwest use when a TLS handshake or certificate check fails.
+const TLS_FAILURE_MARKERS: &[&str] = &[
+    "certificate",
+    "tls handshake",
+    "unknownissuer",
+    "notvalidforname",
+    "received corrupt message",
+    "peer is incompatible",
+];
+
+impl From<ApiError> for AppError {
+    fn from(error: ApiError) -> Self {
+        match tls_failure(&error) {
+            Some(detail) => AppError::TlsError(detail),
+            None => AppError::ApiError(error),
+        }
+    }
+}
+
+/// Returns the most specific message in the error chain if it describes a TLS failure.
+fn tls_failure(error: &ApiError) -> Option<String> {
+    if !matches!(error, ApiError::Http(_) | ApiError::Transport(_)) {
+        return None;
+    }
+    let mut messages = vec![error.to_string()];
+    let mut source = error.source();
+    while let Some(inner) = source {
+        messages.push(inner.to_string());
+        source = inner.source();
+    }
+    messages.into_iter().rev().find(|message| {
+        let message = message.to_ascii_lowercase();
+        TLS_FAILURE_MARKERS
+            .iter()
+            .any(|marker| message.contains(marker))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use hubuum_client::ApiError;
+
+    use super::{ApiContext, AppError};
+
+    #[test]
+    fn certificate_failures_become_tls_errors() {
+        let error = AppError::from(ApiError::Transport(
+            "invalid peer certificate: UnknownIssuer".to_string(),
+        ));
+        let AppError::TlsError(detail) = &error else {
+            panic!("expected a TLS error, got {error:?}");
+        };
+        assert!(detail.contains("UnknownIssuer"), "{detail}");
+        assert!(error.to_string().contains("--ssl-validation false"));
+    }
+
+    #[test]
+    fn other_api_errors_are_kept() {
+        assert!(matches!(
+            AppError::from(ApiError::Api("certificate object not found".to_string())),
+            AppError::ApiError(ApiError::Api(_))
+        ));
+    }
+
+    #[test]
+    fn context_is_only_added_to_api_errors() {
+        let error = Err::<(), _>(ApiError::Api("conflict".to_string()))
+            .context(|| "creating object 'web1' in class 'Hosts'".to_string())
+            .expect_err("the error should be kept");
+        assert_eq!(
+            error.to_string(),
+            "Failed creating object 'web1' in class 'Hosts': API error: API error: conflict"
+        );
+        assert!(matches!(error.api_error(), Some(ApiError::Api(_))));
+
+        let error = Err::<(), _>(AppError::EntityNotFound("class 'Hosts'".to_string()))
+            .context(|| "creating object 'web1' in class 'Hosts'".to_string())
+            .expect_err("the error should be kept");
+        assert!(matches!(error, AppError::EntityNotFound(_)));
+    }
 }
@@ -1,41 +1,218 @@
-use std::fs::{create_dir_all, read_to_string, File, OpenOptions};
+use std::collections::{BTreeMap, HashMap};
+use std::env::var_os;
+use std::ffi::OsString;
+use std::fs::{
+    create_dir_all, metadata, read_to_string, remove_file, rename, set_permissions, File,
+    OpenOptions,
+};
 use std::io::{Error, ErrorKind, Write};
 use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::{Arc, Mutex};
 
+use chrono::{DateTime, TimeDelta, Utc};
 use dirs::{config_dir, data_dir};
+use keyring::Entry;
 use log::{debug, trace};
-use serde_json::{from_str, to_string};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, to_string, Value};
 
+use crate::config::{AuthConfig, ServerConfig};
+use crate::secrets::{decrypt_with_context, encrypt_with, encryption_key, is_encrypted};
 use crate::{errors::AppError, models::TokenEntry};
 
 #[derive(Clone, Copy)]
 enum DataFile {
+    Audit,
     History,
     Log,
+    OfflineCache,
     Token,
 }
 
 impl DataFile {
     fn name(self) -> &'static str {
         match self {
+            Self::Audit => "audit.jsonl",
             Self::History => "history.txt",
             Self::Log => "log.txt",
+            Self::OfflineCache => "offline-cache.json",
             Self::Token => "token.json",
         }
     }
 
     fn initial_contents(self) -> &'static str {
         match self {
-            Self::Token => "[]",
-            Self::History | Self::Log => "",
+            Self::Token => r#"{"version":1,"entries":[]}"#,
+            Self::OfflineCache => "{}",
+            Self::Audit | Self::History | Self::Log => "",
         }
     }
 }
 
+const APP_DIR: &str = "hubuum_cli";
+const CONFIG_FILE: &str = "config.toml";
+
+/// Operating-system conventions for configuration, data, and `file://` paths.
+///
+/// The conventions are plain data so every platform's layout is tested on every CI runner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Platform {
+    Windows,
+    MacOs,
+    Unix,
+}
+
+#[derive(Debug, Clone)]
+struct PlatformPaths {
+    platform: Platform,
+    config_dir: Option<PathBuf>,
+    data_dir: Option<PathBuf>,
+    program_data: Option<PathBuf>,
+}
+
+impl Platform {
+    fn current() -> Self {
+        if cfg!(windows) {
+            Self::Windows
+        } else if cfg!(target_os = "macos") {
+            Self::MacOs
+        } else {
+            Self::Unix
+        }
+    }
+
+    /// Converts the part of a `file://` URL after the scheme to a path. Windows URLs carry
+    /// the drive after a slash (`file:///C:/data.json`), which must be dropped.
+    fn file_url_path(self, rest: &str) -> PathBuf {
+        let rest = rest
+            .strip_prefix("localhost")
+            .filter(|path| path.starts_with('/'))
+            .unwrap_or(rest);
+        if self == Self::Windows {
+            if let Some(path) = rest.strip_prefix('/') {
+                let bytes = path.as_bytes();
+                if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+                    return PathBuf::from(path);
+                }
+            }
+        }
+        PathBuf::from(rest)
+    }
+}
+
+impl PlatformPaths {
+    fn current() -> Self {
+        Self {
+            platform: Platform::current(),
+            config_dir: config_dir(),
+            data_dir: data_dir(),
+            program_data: var_os("ProgramData").map(PathBuf::from),
+        }
+    }
+
+    fn system_config(&self) -> PathBuf {
+        match self.platform {
+            Platform::Windows => self
+                .program_data
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"))
+                .join(APP_DIR)
+                .join(CONFIG_FILE),
+            Platform::MacOs => Path::new("/Library/Application Support")
+                .join(APP_DIR)
+                .join(CONFIG_FILE),
+            Platform::Unix => Path::new("/etc").join(APP_DIR).join(CONFIG_FILE),
+        }
+    }
+
+    fn user_config(&self) -> PathBuf {
+        self.config_dir
+            .as_ref()
+            .map(|dir| dir.join(".hubuum_cli").join(CONFIG_FILE))
+            .unwrap_or_else(|| PathBuf::from(CONFIG_FILE))
+    }
+
+    fn data_root(&self) -> Result<PathBuf, AppError> {
+        Ok(self
+            .data_dir
+            .as_ref()
+            .ok_or_else(|| {
+                AppError::DataDirError("Could not determine data directory".to_string())
+            })?
+            .join(APP_DIR))
+    }
+}
+
 fn data_root_dir() -> Result<PathBuf, AppError> {
-    Ok(data_dir()
-        .ok_or_else(|| AppError::DataDirError("Could not determine data directory".to_string()))?
-        .join("hubuum_cli"))
+    PlatformPaths::current().data_root()
+}
+
+/// Resolves a `file://` value source to a local path for this platform.
+pub fn file_url_path(rest: &str) -> PathBuf {
+    Platform::current().file_url_path(rest)
+}
+
+/// Reads a text file with Windows line endings normalized to `\n`.
+pub fn read_text_file(path: &Path) -> Result<String, AppError> {
+    Ok(read_to_string(path)?.replace("\r\n", "\n"))
+}
+
+/// Replaces `path` with `contents` through a temporary file in the same directory.
+///
+/// The temporary file is renamed over `path`, so readers see either the old or the new
+/// contents, never a partial write. An existing file keeps its permissions.
+pub fn write_file_atomically(path: &Path, contents: &[u8]) -> Result<(), AppError> {
+    let file_name = path.file_name().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("Not a file path: {}", path.display()),
+        )
+    })?;
+    let mut temp_name = OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(".{}.tmp", process::id()));
+    let temp_path = path.with_file_name(temp_name);
+
+    let result = write_temp_file(&temp_path, path, contents)
+        .and_then(|()| rename(&temp_path, path).map_err(AppError::from));
+    if result.is_err() {
+        let _ = remove_file(&temp_path);
+    }
+    result
+}
+
+fn write_temp_file(temp_path: &Path, target: &Path, contents: &[u8]) -> Result<(), AppError> {
+    let mut file = File::create(temp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    if let Ok(existing) = metadata(target) {
+        set_permissions(temp_path, existing.permissions())?;
+    }
+    Ok(())
+}
+
+/// An exclusive lock on `<path>.lock`, released when dropped.
+///
+/// Held across a load-modify-save cycle so concurrent sessions take turns instead of
+/// overwriting each other's changes.
+struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    fn acquire(path: &Path) -> Result<Self, AppError> {
+        let mut lock_path = path.as_os_str().to_owned();
+        lock_path.push(".lock");
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(PathBuf::from(lock_path))?;
+        file.lock()?;
+        Ok(Self { _file: file })
+    }
 }
 
 fn ensure_root_dir_at(root_dir: &Path) -> Result<(), AppError> {
@@ -71,22 +248,11 @@ fn set_owner_only_file_permissions(_path: &Path) -> Result<(), AppError> {
 }
 
 pub fn get_system_config_path() -> PathBuf {
-    if cfg!(target_os = "windows") {
-        PathBuf::from(r"C:\ProgramData\hubuum_cli\config.toml")
-    } else if cfg!(target_os = "macos") {
-        PathBuf::from("/Library/Application Support/hubuum_cli/config.toml")
-    } else {
-        PathBuf::from("/etc/hubuum_cli/config.toml")
-    }
+    PlatformPaths::current().system_config()
 }
 
 pub fn get_user_config_path() -> PathBuf {
-    config_dir()
-        .map(|mut path| {
-            path.push(".hubuum_cli/config.toml");
-            path
-        })
-        .unwrap_or_else(|| PathBuf::from("config.toml"))
+    PlatformPaths::current().user_config()
 }
 
 fn ensure_file_exists(file: DataFile) -> Result<PathBuf, AppError> {
@@ -143,47 +309,542 @@ pub fn get_log_file() -> Result<PathBuf, AppError> {
     ensure_file_exists(DataFile::Log)
 }
 
-pub fn get_token_from_tokenfile(
-    hostname: &str,
-    identity_scope: Option<&str>,
-    username: &str,
-) -> Result<Option<String>, AppError> {
-    let token_file_path = get_token_file()?;
-    let token_file_content = read_to_string(token_file_path)?;
-    let token_entries: Vec<TokenEntry> = from_str(&token_file_content)?;
+pub fn get_audit_file() -> Result<PathBuf, AppError> {
+    ensure_file_exists(DataFile::Audit)
+}
+
+pub fn get_offline_cache_file() -> Result<PathBuf, AppError> {
+    ensure_file_exists(DataFile::OfflineCache)
+}
+
+/// Where the serialized [`TokenStore`] is kept.
+pub trait TokenStorage {
+    /// Returns the saved document, or `None` when nothing has been saved yet.
+    fn read(&self) -> Result<Option<String>, AppError>;
+
+    fn write(&self, contents: &str) -> Result<(), AppError>;
 
-    for token_entry in &token_entries {
-        if token_entry.hostname == hostname
-            && token_entry.identity_scope.as_deref() == identity_scope
-            && token_entry.username == username
+    /// Names the storage in error messages.
+    fn describe(&self) -> String;
+
+    /// File whose `.lock` sibling serializes load-modify-save cycles.
+    fn lock_path(&self) -> &Path;
+}
+
+/// Tokens in the owner-only `token.json` in the data directory.
+///
+/// With `auth.encrypt_token_file` the file holds a single `enc:` value, encrypted like
+/// configuration secrets with the key printed by `server.encryption_key_command`. An
+/// encrypted file is read whenever a key command is configured, so turning encryption
+/// off rewrites the file in plain text at the next save.
+pub struct FileTokenStorage {
+    path: PathBuf,
+    encrypt: bool,
+    key_command: Option<String>,
+}
+
+/// Associated data binding encrypted token files to their purpose.
+const TOKEN_FILE_SETTING: &str = "token.json";
+
+/// The last key printed for `token.json`, so each key command runs once per process.
+static TOKEN_FILE_KEY: Lazy<Mutex<Option<(String, String)>>> = Lazy::new(|| Mutex::new(None));
+
+impl FileTokenStorage {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            encrypt: false,
+            key_command: None,
+        }
+    }
+
+    fn configured(path: PathBuf, auth: &AuthConfig, server: &ServerConfig) -> Self {
+        Self {
+            encrypt: auth.encrypt_token_file,
+            key_command: server.encryption_key_command.clone(),
+            ..Self::new(path)
+        }
+    }
+
+    fn key(&self, purpose: &str) -> Result<String, AppError> {
+        let command = self.key_command.as_deref().ok_or_else(|| {
+            AppError::GeneralConfigError(format!(
+                "{purpose} {} needs server.encryption_key_command",
+                self.path.display()
+            ))
+        })?;
+        let mut cached = TOKEN_FILE_KEY.lock().map_err(|_| AppError::LockError)?;
+        if let Some((cached_command, key)) = cached.as_ref() {
+            if cached_command == command {
+                return Ok(key.clone());
+            }
+        }
+        let key = encryption_key(command)?;
+        *cached = Some((command.to_string(), key.clone()));
+        Ok(key)
+    }
+}
+
+impl TokenStorage for FileTokenStorage {
+    fn read(&self) -> Result<Option<String>, AppError> {
+        let contents = read_to_string(&self.path)?;
+        let contents = contents.trim();
+        if !is_encrypted(contents) {
+            return Ok(Some(contents.to_string()));
+        }
+        let key = self.key("Decrypting")?;
+        decrypt_with_context(
+            contents,
+            &key,
+            TOKEN_FILE_SETTING,
+            &format!("token file {}", self.path.display()),
+        )
+        .map(Some)
+    }
+
+    fn write(&self, contents: &str) -> Result<(), AppError> {
+        let contents = if self.encrypt {
+            encrypt_with(contents, &self.key("Encrypting")?, TOKEN_FILE_SETTING)?
+        } else {
+            contents.to_string()
+        };
+        write_file_atomically(&self.path, contents.as_bytes())?;
+        set_owner_only_file_permissions(&self.path)
+    }
+
+    fn describe(&self) -> String {
+        format!("Token file {}", self.path.display())
+    }
+
+    fn lock_path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Tokens in the OS keychain, enabled with `auth.use_keyring`.
+///
+/// Each login is kept in an entry of its own, as keychains limit the size of an entry
+/// (Windows to 2560 bytes). Keychains cannot be searched portably, so the `logins` entry
+/// lists the names of the login entries.
+///
+/// Until the first save, tokens are read from the single `tokens` entry of earlier
+/// releases or from an existing `token.json`, so current logins carry over. The first
+/// save moves them into login entries and removes the old copies.
+pub struct KeyringTokenStorage {
+    file: FileTokenStorage,
+    /// Entries opened so far by name, so each is looked up in the keychain once.
+    entries: Mutex<HashMap<String, Arc<Entry>>>,
+}
+
+const KEYRING_SERVICE: &str = "hubuum_cli";
+/// The entry listing the login entries.
+const KEYRING_INDEX: &str = "logins";
+/// The entry that held every token before logins had entries of their own.
+const KEYRING_LEGACY: &str = "tokens";
+
+impl KeyringTokenStorage {
+    /// Opens the keychain, failing when the platform has no usable keychain.
+    fn open(file: FileTokenStorage) -> Result<Self, AppError> {
+        let storage = Self {
+            file,
+            entries: Mutex::new(HashMap::new()),
+        };
+        match storage.entry(KEYRING_INDEX)?.get_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => Ok(storage),
+            Err(error) => Err(keyring_error(error)),
+        }
+    }
+
+    fn entry(&self, name: &str) -> Result<Arc<Entry>, AppError> {
+        let mut entries = self.entries.lock().map_err(|_| AppError::LockError)?;
+        if let Some(entry) = entries.get(name) {
+            return Ok(entry.clone());
+        }
+        let entry = Arc::new(Entry::new(KEYRING_SERVICE, name).map_err(keyring_error)?);
+        entries.insert(name.to_string(), entry.clone());
+        Ok(entry)
+    }
+
+    /// The contents of entry `name`, or `None` when it does not exist.
+    fn get(&self, name: &str) -> Result<Option<String>, AppError> {
+        match self.entry(name)?.get_password() {
+            Ok(contents) => Ok(Some(contents)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(error) => Err(keyring_error(error)),
+        }
+    }
+
+    fn delete(&self, name: &str) -> Result<(), AppError> {
+        match self.entry(name)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(error) => Err(keyring_error(error)),
+        }
+    }
+
+    /// Names of the login entries listed in the index.
+    fn login_names(&self) -> Result<Option<Vec<String>>, AppError> {
+        self.get(KEYRING_INDEX)?
+            .map(|index| from_str(&index).map_err(AppError::from))
+            .transpose()
+    }
+}
+
+/// Name of the keychain entry holding the token of `entry`'s login.
+fn keyring_login_name(entry: &TokenEntry) -> String {
+    match entry.identity_scope.as_deref() {
+        Some(scope) => format!("{scope}:{}@{}", entry.username, entry.hostname),
+        None => format!("{}@{}", entry.username, entry.hostname),
+    }
+}
+
+impl TokenStorage for KeyringTokenStorage {
+    fn read(&self) -> Result<Option<String>, AppError> {
+        let Some(names) = self.login_names()? else {
+            return match self.get(KEYRING_LEGACY)? {
+                Some(contents) => Ok(Some(contents)),
+                None if self.file.path.is_file() => self.file.read(),
+                None => Ok(None),
+            };
+        };
+        // A login entry removed outside the CLI leaves only its name in the index.
+        let mut entries = Vec::new();
+        for name in names {
+            if let Some(contents) = self.get(&name)? {
+                entries.push(from_str::<TokenEntry>(&contents)?);
+            }
+        }
+        Ok(Some(to_string(&TokenStore {
+            version: TOKEN_FILE_VERSION,
+            entries,
+        })?))
+    }
+
+    fn write(&self, contents: &str) -> Result<(), AppError> {
+        let store: TokenStore = from_str(contents)?;
+        let previous = self.login_names()?.unwrap_or_default();
+        let mut names = Vec::with_capacity(store.entries.len());
+        for entry in &store.entries {
+            let name = keyring_login_name(entry);
+            let contents = to_string(entry)?;
+            if self.get(&name)?.as_deref() != Some(contents.as_str()) {
+                self.entry(&name)?
+                    .set_password(&contents)
+                    .map_err(keyring_error)?;
+            }
+            names.push(name);
+        }
+        self.entry(KEYRING_INDEX)?
+            .set_password(&to_string(&names)?)
+            .map_err(keyring_error)?;
+        for name in previous.iter().filter(|name| !names.contains(name)) {
+            self.delete(name)?;
+        }
+        self.delete(KEYRING_LEGACY)?;
+        if self.file.path.is_file() {
+            debug!(
+                "Removing {} after moving its tokens to the OS keychain",
+                self.file.path.display()
+            );
+            remove_file(&self.file.path)?;
+        }
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        "The token store in the OS keychain".to_string()
+    }
+
+    fn lock_path(&self) -> &Path {
+        &self.file.path
+    }
+}
+
+fn keyring_error(error: keyring::Error) -> AppError {
+    AppError::KeyringError(error.to_string())
+}
+
+/// The token storage selected by `auth.use_keyring`, falling back to `token.json` when the
+/// OS keychain cannot be used. Tokens are encrypted with `server`'s key command.
+pub fn token_storage(
+    auth: &AuthConfig,
+    server: &ServerConfig,
+) -> Result<Box<dyn TokenStorage>, AppError> {
+    if auth.use_keyring {
+        match open_keyring(auth, server) {
+            Ok(storage) => return Ok(Box::new(storage)),
+            Err(error) => debug!("The OS keychain is unavailable, using token.json: {error}"),
+        }
+    }
+    Ok(Box::new(FileTokenStorage::configured(
+        get_token_file()?,
+        auth,
+        server,
+    )))
+}
+
+fn open_keyring(auth: &AuthConfig, server: &ServerConfig) -> Result<KeyringTokenStorage, AppError> {
+    let root_dir = data_root_dir()?;
+    ensure_root_dir_at(&root_dir)?;
+    let file = FileTokenStorage::configured(root_dir.join(DataFile::Token.name()), auth, server);
+    KeyringTokenStorage::open(file)
+}
+
+/// Why tokens are kept in `token.json` although `auth.use_keyring` is set, if they are.
+pub fn keyring_problem(auth: &AuthConfig, server: &ServerConfig) -> Option<String> {
+    if !auth.use_keyring {
+        return None;
+    }
+    open_keyring(auth, server).err().map(|error| {
+        format!("auth.use_keyring is set, but the OS keychain cannot be used ({error}); tokens are kept in token.json instead.")
+    })
+}
+
+/// Current `token.json` layout. Releases before versioning wrote a bare array of entries.
+const TOKEN_FILE_VERSION: u32 = 1;
+
+/// Cached login tokens, one per server, identity scope, and username.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenStore {
+    version: u32,
+    entries: Vec<TokenEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredTokens {
+    Versioned(TokenStore),
+    Legacy(Vec<TokenEntry>),
+}
+
+impl TokenStore {
+    pub fn load(storage: &dyn TokenStorage) -> Result<Self, AppError> {
+        Self::load_from(storage, Utc::now())
+    }
+
+    /// Applies `change` to the stored tokens and saves them.
+    ///
+    /// The file is locked and read again first, so tokens saved by another session since
+    /// [`TokenStore::load`] are kept rather than overwritten.
+    pub fn update<T>(
+        storage: &dyn TokenStorage,
+        change: impl FnOnce(&mut Self) -> T,
+    ) -> Result<T, AppError> {
+        Self::update_at(storage, Utc::now(), change)
+    }
+
+    fn update_at<T>(
+        storage: &dyn TokenStorage,
+        now: DateTime<Utc>,
+        change: impl FnOnce(&mut Self) -> T,
+    ) -> Result<T, AppError> {
+        let _lock = FileLock::acquire(storage.lock_path())?;
+        let mut store = Self::load_from(storage, now)?;
+        let result = change(&mut store);
+        store.save_to(storage)?;
+        Ok(result)
+    }
+
+    fn load_from(storage: &dyn TokenStorage, now: DateTime<Utc>) -> Result<Self, AppError> {
+        let Some(contents) = storage.read()? else {
+            return Ok(Self {
+                version: TOKEN_FILE_VERSION,
+                entries: Vec::new(),
+            });
+        };
+        let mut store = match from_str(&contents)? {
+            StoredTokens::Versioned(store) if store.version > TOKEN_FILE_VERSION => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "{} has format version {}, but this CLI supports up to {TOKEN_FILE_VERSION}",
+                        storage.describe(),
+                        store.version
+                    ),
+                )
+                .into());
+            }
+            StoredTokens::Versioned(store) => store,
+            StoredTokens::Legacy(entries) => Self {
+                version: TOKEN_FILE_VERSION,
+                entries,
+            },
+        };
+        store.version = TOKEN_FILE_VERSION;
+        // Entries from older releases start their age at the first load that sees them.
+        for entry in &mut store.entries {
+            entry.created_at.get_or_insert(now);
+        }
+        Ok(store)
+    }
+
+    fn save_to(&self, storage: &dyn TokenStorage) -> Result<(), AppError> {
+        storage.write(&to_string(self)?)
+    }
+
+    pub fn count(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn entries(&self) -> &[TokenEntry] {
+        &self.entries
+    }
+
+    pub fn find(
+        &self,
+        hostname: &str,
+        identity_scope: Option<&str>,
+        username: &str,
+    ) -> Option<&TokenEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.matches(hostname, identity_scope, username))
+    }
+
+    /// Records that the cached token for this login was just accepted by its server.
+    pub fn touch(
+        &mut self,
+        hostname: &str,
+        identity_scope: Option<&str>,
+        username: &str,
+        now: DateTime<Utc>,
+    ) {
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.matches(hostname, identity_scope, username))
         {
-            return Ok(Some(token_entry.token.clone()));
+            entry.last_used_at = Some(now);
+        }
+    }
+
+    /// Stores a freshly issued token, replacing any previous token for the same login.
+    pub fn upsert(&mut self, mut entry: TokenEntry, now: DateTime<Utc>) {
+        self.entries.retain(|existing| {
+            !existing.matches(
+                &entry.hostname,
+                entry.identity_scope.as_deref(),
+                &entry.username,
+            )
+        });
+        entry.created_at = Some(now);
+        entry.last_used_at = Some(now);
+        self.entries.push(entry);
+    }
+
+    /// Forgets the cached token for this login. Returns whether there was one.
+    pub fn remove(&mut self, hostname: &str, identity_scope: Option<&str>, username: &str) -> bool {
+        let before = self.entries.len();
+        self.entries
+            .retain(|entry| !entry.matches(hostname, identity_scope, username));
+        before != self.entries.len()
+    }
+
+    /// Removes tokens whose server has not been contacted for more than `days` days.
+    /// Returns the number of removed entries; `days == 0` keeps everything.
+    pub fn prune(&mut self, days: u64, now: DateTime<Utc>) -> usize {
+        if days == 0 {
+            return 0;
         }
+        let cutoff = i64::try_from(days)
+            .ok()
+            .and_then(TimeDelta::try_days)
+            .and_then(|age| now.checked_sub_signed(age));
+        let Some(cutoff) = cutoff else {
+            return 0;
+        };
+        let before = self.entries.len();
+        self.entries
+            .retain(|entry| entry.last_contact().is_none_or(|contact| contact >= cutoff));
+        before - self.entries.len()
     }
-    Ok(None)
 }
 
-pub fn write_token_to_tokenfile(token_entry: TokenEntry) -> Result<(), AppError> {
-    let token_file_path = get_token_file()?;
-    let token_file_content = read_to_string(&token_file_path)?;
-    let mut token_entries: Vec<TokenEntry> = from_str(&token_file_content)?;
+/// Listings and completion names saved per login in `offline-cache.json`, so offline
+/// mode has something to answer from after a restart.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OfflineCache {
+    #[serde(default)]
+    logins: BTreeMap<String, OfflineEntries>,
+}
+
+/// What one login saved for offline mode.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OfflineEntries {
+    /// List responses by the key the response cache used for them.
+    #[serde(default)]
+    pub listings: BTreeMap<String, SavedListing>,
+    /// Completion names by source, such as `classes`.
+    #[serde(default)]
+    pub names: BTreeMap<String, Vec<String>>,
+}
 
-    token_entries.retain(|entry| {
-        entry.hostname != token_entry.hostname
-            || entry.identity_scope != token_entry.identity_scope
-            || entry.username != token_entry.username
-    });
-    token_entries.push(token_entry);
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedListing {
+    pub fetched_at: DateTime<Utc>,
+    pub value: Value,
+}
 
-    let token_file_content = to_string(&token_entries)?;
-    let mut token_file = File::options()
-        .write(true)
-        .truncate(true)
-        .open(token_file_path)?;
-    token_file.write_all(token_file_content.as_bytes())?;
-    token_file.sync_all()?;
+impl OfflineCache {
+    pub fn load() -> Result<Self, AppError> {
+        Ok(Self::parse(&read_text_file(&get_offline_cache_file()?)?))
+    }
 
-    Ok(())
+    /// Applies `change` to the saved entries and saves them, under the same kind of
+    /// lock as [`TokenStore::update`].
+    pub fn update<T>(change: impl FnOnce(&mut Self) -> T) -> Result<T, AppError> {
+        let path = get_offline_cache_file()?;
+        let _lock = FileLock::acquire(&path)?;
+        let mut cache = Self::parse(&read_text_file(&path)?);
+        let result = change(&mut cache);
+        write_file_atomically(&path, to_string(&cache)?.as_bytes())?;
+        Ok(result)
+    }
+
+    /// A damaged file only loses what offline mode could have shown, so it reads as empty.
+    fn parse(contents: &str) -> Self {
+        from_str(contents).unwrap_or_else(|error| {
+            debug!("Ignoring unreadable offline cache: {error}");
+            Self::default()
+        })
+    }
+
+    pub fn login(&self, login: &str) -> Option<&OfflineEntries> {
+        self.logins.get(login)
+    }
+
+    pub fn login_mut(&mut self, login: &str) -> &mut OfflineEntries {
+        self.logins.entry(login.to_string()).or_default()
+    }
+
+    pub fn forget_login(&mut self, login: &str) {
+        self.logins.remove(login);
+    }
+}
+
+impl OfflineEntries {
+    /// Saves `listing` under `key`, dropping the oldest listings until their JSON fits
+    /// in `budget` bytes. A listing larger than the budget is not kept.
+    pub fn save_listing(&mut self, key: &str, listing: SavedListing, budget: usize) {
+        self.listings.remove(key);
+        let size = |listing: &SavedListing| {
+            to_string(&listing.value).map_or(usize::MAX, |json| json.len())
+        };
+        if size(&listing) > budget {
+            return;
+        }
+        self.listings.insert(key.to_string(), listing);
+        while self.listings.values().map(size).sum::<usize>() > budget {
+            let Some(oldest) = self
+                .listings
+                .iter()
+                .min_by_key(|(_, listing)| listing.fetched_at)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.listings.remove(&oldest);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -192,19 +853,405 @@ mod tests {
 
     use tempfile::tempdir;
 
-    use super::{ensure_file_exists_at, DataFile};
+    use chrono::{TimeDelta, Utc};
+    use serde_json::{from_str, Value};
+
+    use std::path::{Path, PathBuf};
+
+    use keyring::{mock, set_default_credential_builder};
+
+    use super::{
+        ensure_file_exists_at, read_text_file, write_file_atomically, DataFile, FileTokenStorage,
+        KeyringTokenStorage, OfflineCache, Platform, PlatformPaths, SavedListing, TokenStore,
+        KEYRING_INDEX, KEYRING_LEGACY, TOKEN_FILE_VERSION,
+    };
+    use crate::models::TokenEntry;
+
+    fn paths(platform: Platform) -> PlatformPaths {
+        PlatformPaths {
+            platform,
+            config_dir: Some(PathBuf::from("config-home")),
+            data_dir: Some(PathBuf::from("data-home")),
+            program_data: None,
+        }
+    }
 
     #[test]
-    fn token_file_starts_with_an_empty_json_array() {
+    fn system_config_follows_platform_conventions() {
+        assert_eq!(
+            paths(Platform::Unix).system_config(),
+            Path::new("/etc/hubuum_cli/config.toml")
+        );
+        assert_eq!(
+            paths(Platform::MacOs).system_config(),
+            Path::new("/Library/Application Support/hubuum_cli/config.toml")
+        );
+        assert_eq!(
+            paths(Platform::Windows).system_config(),
+            Path::new(r"C:\ProgramData")
+                .join("hubuum_cli")
+                .join("config.toml")
+        );
+
+        let mut windows = paths(Platform::Windows);
+        windows.program_data = Some(PathBuf::from(r"D:\ProgramData"));
+        assert_eq!(
+            windows.system_config(),
+            Path::new(r"D:\ProgramData")
+                .join("hubuum_cli")
+                .join("config.toml")
+        );
+    }
+
+    #[test]
+    fn user_config_and_data_root_use_native_separators() {
+        let paths = paths(Platform::Windows);
+        assert_eq!(
+            paths.user_config(),
+            Path::new("config-home")
+                .join(".hubuum_cli")
+                .join("config.toml")
+        );
+        assert_eq!(
+            paths.data_root().expect("data root should resolve"),
+            Path::new("data-home").join("hubuum_cli")
+        );
+
+        let mut missing = paths;
+        missing.data_dir = None;
+        assert!(missing.data_root().is_err());
+    }
+
+    #[test]
+    fn file_urls_with_windows_drives_drop_the_leading_slash() {
+        assert_eq!(
+            Platform::Windows.file_url_path("/C:/data/object.json"),
+            PathBuf::from("C:/data/object.json")
+        );
+        assert_eq!(
+            Platform::Windows.file_url_path("localhost/C:/data/object.json"),
+            PathBuf::from("C:/data/object.json")
+        );
+        assert_eq!(
+            Platform::Windows.file_url_path("relative.json"),
+            PathBuf::from("relative.json")
+        );
+        assert_eq!(
+            Platform::Unix.file_url_path("/C:/data/object.json"),
+            PathBuf::from("/C:/data/object.json")
+        );
+        assert_eq!(
+            Platform::Unix.file_url_path("localhost/tmp/object.json"),
+            PathBuf::from("/tmp/object.json")
+        );
+    }
+
+    #[test]
+    fn text_files_with_crlf_line_endings_are_normalized() {
+        let directory = tempdir().expect("temporary directory should be created");
+        let path = directory.path().join("notes.txt");
+        write(&path, "first\r\nsecond\r\n").expect("fixture should be written");
+
+        assert_eq!(
+            read_text_file(&path).expect("text should be readable"),
+            "first\nsecond\n"
+        );
+    }
+
+    #[test]
+    fn token_file_starts_as_an_empty_versioned_store() {
         let directory = tempdir().expect("temporary directory should be created");
 
         let path = ensure_file_exists_at(directory.path(), DataFile::Token)
             .expect("token file should be created");
 
         assert_eq!(
-            read_to_string(path).expect("token file should be readable"),
-            "[]"
+            read_to_string(&path).expect("token file should be readable"),
+            r#"{"version":1,"entries":[]}"#
         );
+        let store = TokenStore::load_from(&FileTokenStorage::new(&path), Utc::now())
+            .expect("empty store should load");
+        assert_eq!(store.count(), 0);
+    }
+
+    #[test]
+    fn legacy_token_arrays_are_migrated_on_save() {
+        let directory = tempdir().expect("temporary directory should be created");
+        let path = directory.path().join(DataFile::Token.name());
+        write(
+            &path,
+            r#"[{"hostname":"api.example.com","username":"alice","token":"secret"}]"#,
+        )
+        .expect("legacy token file should be written");
+        let now = Utc::now();
+
+        let store = TokenStore::load_from(&FileTokenStorage::new(&path), now)
+            .expect("legacy store should load");
+        assert_eq!(
+            store
+                .find("api.example.com", None, "alice")
+                .and_then(|entry| entry.created_at),
+            Some(now)
+        );
+        store
+            .save_to(&FileTokenStorage::new(&path))
+            .expect("store should save");
+        let saved: Value = from_str(&read_to_string(&path).expect("token file should be readable"))
+            .expect("saved store should be JSON");
+        assert_eq!(saved["version"], 1);
+        assert_eq!(saved["entries"][0]["token"], "secret");
+    }
+
+    #[test]
+    fn newer_token_file_versions_are_rejected() {
+        let directory = tempdir().expect("temporary directory should be created");
+        let path = directory.path().join(DataFile::Token.name());
+        write(&path, r#"{"version":99,"entries":[]}"#).expect("token file should be written");
+
+        let error = TokenStore::load_from(&FileTokenStorage::new(&path), Utc::now())
+            .expect_err("future versions should be rejected")
+            .to_string();
+        assert!(error.contains("format version 99"), "{error}");
+    }
+
+    #[test]
+    fn prune_drops_tokens_for_servers_not_contacted_recently() {
+        let now = Utc::now();
+        let mut store = TokenStore {
+            version: TOKEN_FILE_VERSION,
+            entries: Vec::new(),
+        };
+        for (hostname, days_ago) in [("fresh.example.com", 1), ("stale.example.com", 40)] {
+            store.upsert(
+                TokenEntry::new(
+                    hostname.to_string(),
+                    None,
+                    "alice".to_string(),
+                    "secret".to_string(),
+                ),
+                now - TimeDelta::days(days_ago),
+            );
+        }
+        store.touch("stale.example.com", None, "bob", now);
+
+        assert_eq!(store.prune(0, now), 0);
+        assert_eq!(store.prune(30, now), 1);
+        assert!(store.find("fresh.example.com", None, "alice").is_some());
+        assert!(store.find("stale.example.com", None, "alice").is_none());
+    }
+
+    #[test]
+    fn remove_forgets_only_the_matching_login() {
+        let now = Utc::now();
+        let mut store = TokenStore {
+            version: TOKEN_FILE_VERSION,
+            entries: Vec::new(),
+        };
+        for scope in [None, Some("corp")] {
+            store.upsert(
+                TokenEntry::new(
+                    "hubuum.example.com".to_string(),
+                    scope.map(str::to_string),
+                    "alice".to_string(),
+                    "secret".to_string(),
+                ),
+                now,
+            );
+        }
+
+        assert!(store.remove("hubuum.example.com", Some("corp"), "alice"));
+        assert!(!store.remove("hubuum.example.com", Some("corp"), "alice"));
+        assert!(store.find("hubuum.example.com", None, "alice").is_some());
+        assert_eq!(store.count(), 1);
+    }
+
+    #[test]
+    fn token_updates_keep_tokens_saved_by_other_sessions() {
+        let directory = tempdir().expect("temporary directory should be created");
+        let path = ensure_file_exists_at(directory.path(), DataFile::Token)
+            .expect("token file should be created");
+        let now = Utc::now();
+        let entry = |hostname: &str| {
+            TokenEntry::new(
+                hostname.to_string(),
+                None,
+                "alice".to_string(),
+                "secret".to_string(),
+            )
+        };
+
+        // Both sessions loaded the empty store before either logged in.
+        let stale =
+            TokenStore::load_from(&FileTokenStorage::new(&path), now).expect("store should load");
+        TokenStore::update_at(&FileTokenStorage::new(&path), now, |store| {
+            store.upsert(entry("a.example.com"), now)
+        })
+        .expect("first session should save");
+        TokenStore::update_at(&FileTokenStorage::new(&path), now, |store| {
+            store.upsert(entry("b.example.com"), now)
+        })
+        .expect("second session should save");
+
+        assert_eq!(stale.count(), 0);
+        let store =
+            TokenStore::load_from(&FileTokenStorage::new(&path), now).expect("store should load");
+        assert!(store.find("a.example.com", None, "alice").is_some());
+        assert!(store.find("b.example.com", None, "alice").is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn encrypted_token_files_need_the_key_command_to_load() {
+        let directory = tempdir().expect("temporary directory should be created");
+        let path = directory.path().join(DataFile::Token.name());
+        let encrypted = FileTokenStorage {
+            encrypt: true,
+            key_command: Some("echo token-k3y".to_string()),
+            ..FileTokenStorage::new(&path)
+        };
+        let now = Utc::now();
+        let mut store = TokenStore {
+            version: TOKEN_FILE_VERSION,
+            entries: Vec::new(),
+        };
+        store.upsert(
+            TokenEntry::new(
+                "api.example.com".to_string(),
+                None,
+                "alice".to_string(),
+                "secret".to_string(),
+            ),
+            now,
+        );
+
+        store
+            .save_to(&encrypted)
+            .expect("store should save encrypted");
+        let contents = read_to_string(&path).expect("token file should be readable");
+        assert!(contents.starts_with("enc:"), "{contents}");
+        assert!(!contents.contains("secret"));
+
+        let loaded = TokenStore::load_from(&encrypted, now).expect("store should decrypt");
+        assert!(loaded.find("api.example.com", None, "alice").is_some());
+        assert!(TokenStore::load_from(&FileTokenStorage::new(&path), now).is_err());
+
+        let wrong_key = FileTokenStorage {
+            key_command: Some("echo other-k3y".to_string()),
+            ..FileTokenStorage::new(&path)
+        };
+        let error = TokenStore::load_from(&wrong_key, now)
+            .expect_err("another key should not decrypt the file")
+            .to_string();
+        assert!(
+            error.contains(&format!("token file {}", path.display())),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn keyring_storage_takes_over_tokens_from_the_token_file() {
+        set_default_credential_builder(mock::default_credential_builder());
+        let directory = tempdir().expect("temporary directory should be created");
+        let path = directory.path().join(DataFile::Token.name());
+        write(
+            &path,
+            r#"{"version":1,"entries":[{"hostname":"api.example.com","username":"alice","token":"secret"}]}"#,
+        )
+        .expect("token file should be written");
+        let storage = KeyringTokenStorage::open(FileTokenStorage::new(&path))
+            .expect("mock keychain should open");
+        let now = Utc::now();
+
+        TokenStore::update_at(&storage, now, |store| {
+            store.touch("api.example.com", None, "alice", now)
+        })
+        .expect("tokens should move to the keychain");
+
+        assert!(!path.exists());
+        assert!(storage
+            .get("alice@api.example.com")
+            .expect("login entry should be readable")
+            .is_some());
+        let store = TokenStore::load_from(&storage, now).expect("keychain store should load");
+        assert_eq!(
+            store
+                .find("api.example.com", None, "alice")
+                .and_then(|entry| entry.last_used_at),
+            Some(now)
+        );
+    }
+
+    #[test]
+    fn keyring_storage_keeps_each_login_in_an_entry_of_its_own() {
+        set_default_credential_builder(mock::default_credential_builder());
+        let directory = tempdir().expect("temporary directory should be created");
+        let storage =
+            KeyringTokenStorage::open(FileTokenStorage::new(directory.path().join("token.json")))
+                .expect("mock keychain should open");
+        storage
+            .entry(KEYRING_LEGACY)
+            .expect("legacy entry should open")
+            .set_password(
+                r#"{"version":1,"entries":[
+                    {"hostname":"api.example.com","username":"alice","token":"first"},
+                    {"hostname":"api.example.com","identity_scope":"ldap","username":"bob","token":"second"}
+                ]}"#,
+            )
+            .expect("legacy entry should be written");
+        let now = Utc::now();
+
+        TokenStore::update_at(&storage, now, |store| {
+            store.remove("api.example.com", None, "alice");
+            store.upsert(
+                TokenEntry::new(
+                    "staging.example.com".to_string(),
+                    None,
+                    "carol".to_string(),
+                    "third".to_string(),
+                ),
+                now,
+            );
+        })
+        .expect("tokens should move to login entries");
+
+        let entry = |name: &str| storage.get(name).expect("entry should be readable");
+        assert_eq!(entry(KEYRING_LEGACY), None);
+        assert_eq!(entry("alice@api.example.com"), None);
+        assert!(entry("ldap:bob@api.example.com").is_some_and(|entry| entry.contains("second")));
+        assert!(entry("carol@staging.example.com").is_some_and(|entry| entry.contains("third")));
+        assert_eq!(
+            from_str::<Vec<String>>(&entry(KEYRING_INDEX).expect("index should be written"))
+                .expect("index should parse"),
+            vec!["ldap:bob@api.example.com", "carol@staging.example.com"]
+        );
+
+        TokenStore::update_at(&storage, now, |store| {
+            store.remove("api.example.com", Some("ldap"), "bob")
+        })
+        .expect("login should be removed");
+        assert_eq!(entry("ldap:bob@api.example.com"), None);
+        let store = TokenStore::load_from(&storage, now).expect("keychain store should load");
+        assert_eq!(store.count(), 1);
+        assert!(store.find("staging.example.com", None, "carol").is_some());
+    }
+
+    #[test]
+    fn atomic_writes_replace_the_file_without_leaving_temporary_files() {
+        let directory = tempdir().expect("temporary directory should be created");
+        let path = directory.path().join("config.toml");
+        write(&path, "old").expect("fixture should be written");
+
+        write_file_atomically(&path, b"new").expect("file should be replaced");
+
+        assert_eq!(
+            read_to_string(&path).expect("file should be readable"),
+            "new"
+        );
+        let names = std::fs::read_dir(directory.path())
+            .expect("directory should be listed")
+            .map(|entry| entry.expect("entry should be readable").file_name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["config.toml"]);
     }
 
     #[test]
@@ -231,7 +1278,13 @@ mod tests {
         std::fs::set_permissions(directory.path(), std::fs::Permissions::from_mode(0o755))
             .expect("directory permissions should be widened for the fixture");
 
-        let paths = [DataFile::History, DataFile::Log, DataFile::Token].map(|file| {
+        let paths = [
+            DataFile::Audit,
+            DataFile::History,
+            DataFile::Log,
+            DataFile::Token,
+        ]
+        .map(|file| {
             let path = directory.path().join(file.name());
             write(&path, file.initial_contents()).expect("fixture should be written");
             std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644))
@@ -258,4 +1311,25 @@ mod tests {
             assert_eq!(file_mode, 0o600);
         }
     }
+
+    #[test]
+    fn saved_listings_drop_the_oldest_to_fit_the_budget() {
+        let now = Utc::now();
+        let listing = |minutes: i64, value: &str| SavedListing {
+            fetched_at: now - TimeDelta::minutes(minutes),
+            value: Value::String(value.to_string()),
+        };
+        let mut cache = OfflineCache::parse("not json");
+        let entries = cache.login_mut("admin@http://localhost:8080");
+
+        entries.save_listing("classes", listing(2, "aaaa"), 16);
+        entries.save_listing("groups", listing(1, "bbbb"), 16);
+        entries.save_listing("collections", listing(0, "cccc"), 16);
+        entries.save_listing("huge", listing(0, "far too large to keep"), 16);
+
+        assert_eq!(
+            entries.listings.keys().collect::<Vec<_>>(),
+            vec!["collections", "groups"]
+        );
+    }
 }
@@ -0,0 +1,57 @@
+use crate::domain::{BulkFailure, BulkSummary, PlanChange, SchemaDrift};
+
+use super::TableRenderable;
+
+impl TableRenderable for PlanChange {
+    fn headers() -> Vec<&'static str> {
+        vec!["Action", "Kind", "Name", "Changes"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.action.as_str().to_string(),
+            self.kind.as_str().to_string(),
+            self.name.clone(),
+            self.changes.join(", "),
+        ]
+    }
+}
+
+impl TableRenderable for SchemaDrift {
+    fn headers() -> Vec<&'static str> {
+        vec!["Class", "Drift", "Keywords"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.class.clone(),
+            self.status.as_str().to_string(),
+            self.keywords.join(", "),
+        ]
+    }
+}
+
+impl TableRenderable for BulkSummary {
+    fn headers() -> Vec<&'static str> {
+        vec!["Created", "Updated", "Deleted", "Failed"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.created.to_string(),
+            self.updated.to_string(),
+            self.deleted.to_string(),
+            self.failed.len().to_string(),
+        ]
+    }
+}
+
+impl TableRenderable for BulkFailure {
+    fn headers() -> Vec<&'static str> {
+        vec!["Failed", "Error"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![self.name.clone(), self.error.clone()]
+    }
+}
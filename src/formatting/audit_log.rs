@@ -0,0 +1,26 @@
+use crate::audit_log::AuditLogEntry;
+
+use super::TableRenderable;
+
+impl TableRenderable for AuditLogEntry {
+    fn headers() -> Vec<&'static str> {
+        vec!["Time", "User", "Command", "Entity IDs", "Result"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+            self.user.clone(),
+            self.command.clone(),
+            self.entity_ids
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+            match &self.error {
+                Some(error) => format!("{}: {error}", self.result.as_str()),
+                None => self.result.as_str().to_string(),
+            },
+        ]
+    }
+}
@@ -1,6 +1,6 @@
-use crate::domain::ClassRecord;
+use crate::domain::{ClassCountRecord, ClassRecord};
 
-use super::{DetailRenderable, TableRenderable};
+use super::{optional_count, DetailRenderable, TableRenderable};
 
 impl DetailRenderable for ClassRecord {
     fn detail_rows(&self) -> Vec<(&'static str, String)> {
@@ -55,6 +55,20 @@ impl TableRenderable for ClassRecord {
     }
 }
 
+impl TableRenderable for ClassCountRecord {
+    fn headers() -> Vec<&'static str> {
+        let mut headers = ClassRecord::headers();
+        headers.insert(4, "Objects");
+        headers
+    }
+
+    fn row(&self) -> Vec<String> {
+        let mut row = self.class.row();
+        row.insert(4, optional_count(self.object_count));
+        row
+    }
+}
+
 fn schema_label(schema: Option<&Value>) -> String {
     let schema_id = schema
         .and_then(|value| value.as_object())
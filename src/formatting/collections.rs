@@ -1,4 +1,5 @@
-use crate::domain::{CollectionRecord, GroupPermissionsSummary};
+use crate::config::get_config;
+use crate::domain::{ClassUsage, CollectionRecord, GroupPermissionsSummary};
 
 use super::{DetailRenderable, TableRenderable};
 
@@ -31,6 +32,62 @@ impl TableRenderable for CollectionRecord {
     }
 }
 
+impl TableRenderable for ClassUsage {
+    fn headers() -> Vec<&'static str> {
+        vec!["Class", "Objects", "Data"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.class.clone(),
+            human_count(self.objects),
+            human_bytes(self.data_bytes),
+        ]
+    }
+}
+
+/// Formats a byte count with binary units, e.g. `1.5 KiB`.
+pub(crate) fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// Formats a count for text output, grouping digits (`1 234 567`) when
+/// `output.locale_numbers` is enabled.
+pub(crate) fn human_count(count: u64) -> String {
+    if get_config().output.locale_numbers {
+        group_digits(count)
+    } else {
+        count.to_string()
+    }
+}
+
+/// Renders a count the server may not have reported, using `-` when unknown.
+pub(crate) fn optional_count(count: Option<u64>) -> String {
+    count.map_or_else(|| "-".to_string(), human_count)
+}
+
+fn group_digits(count: u64) -> String {
+    let digits = count.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(' ');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
 impl TableRenderable for GroupPermissionsSummary {
     fn headers() -> Vec<&'static str> {
         vec![
@@ -54,3 +111,23 @@ impl TableRenderable for GroupPermissionsSummary {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{group_digits, human_bytes};
+
+    #[test]
+    fn byte_counts_use_binary_units() {
+        assert_eq!(human_bytes(512), "512 B");
+        assert_eq!(human_bytes(1536), "1.5 KiB");
+        assert_eq!(human_bytes(5 * 1024 * 1024), "5.0 MiB");
+    }
+
+    #[test]
+    fn grouped_counts_split_thousands() {
+        assert_eq!(group_digits(0), "0");
+        assert_eq!(group_digits(999), "999");
+        assert_eq!(group_digits(1000), "1 000");
+        assert_eq!(group_digits(1_234_567), "1 234 567");
+    }
+}
@@ -0,0 +1,236 @@
+use difflib::sequencematcher::{Opcode, SequenceMatcher};
+use serde_json::{to_string_pretty, Value};
+
+use crate::domain::ValueDiff;
+use crate::terminal::{pad_to_width, terminal_width, truncate_to_width};
+use crate::theme::{paint, ThemeRole};
+
+/// Unchanged lines shown around each change.
+const CONTEXT_LINES: usize = 3;
+const DEFAULT_SIDE_BY_SIDE_WIDTH: usize = 120;
+const GUTTER: &str = " | ";
+
+/// How a diff is laid out on the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiffLayout {
+    #[default]
+    Unified,
+    SideBySide,
+}
+
+/// Renders both sides of `diff` as pretty-printed JSON and diffs them line by line.
+///
+/// `labels` name the before and after sides, e.g. `("server", "schemas/Host.json")`.
+/// Identical values render as an empty string.
+pub fn render_value_diff(diff: &ValueDiff, labels: (&str, &str), layout: DiffLayout) -> String {
+    render_text_diff(
+        &pretty_json(&diff.before),
+        &pretty_json(&diff.after),
+        labels,
+        layout,
+    )
+}
+
+/// Diffs two texts line by line; identical texts render as an empty string.
+pub fn render_text_diff(
+    before: &str,
+    after: &str,
+    labels: (&str, &str),
+    layout: DiffLayout,
+) -> String {
+    let before = before.lines().collect::<Vec<_>>();
+    let after = after.lines().collect::<Vec<_>>();
+    let hunks = SequenceMatcher::new(&before, &after).get_grouped_opcodes(CONTEXT_LINES);
+    if before == after || hunks.is_empty() {
+        return String::new();
+    }
+    match layout {
+        DiffLayout::Unified => render_unified(&before, &after, labels, &hunks),
+        DiffLayout::SideBySide => render_side_by_side(
+            &before,
+            &after,
+            labels,
+            &hunks,
+            terminal_width().unwrap_or(DEFAULT_SIDE_BY_SIDE_WIDTH),
+        ),
+    }
+}
+
+fn render_unified(
+    before: &[&str],
+    after: &[&str],
+    (from, to): (&str, &str),
+    hunks: &[Vec<Opcode>],
+) -> String {
+    let mut lines = vec![
+        paint(ThemeRole::Error, format!("--- {from}")),
+        paint(ThemeRole::Command, format!("+++ {to}")),
+    ];
+    for hunk in hunks {
+        let (first, last) = (&hunk[0], &hunk[hunk.len() - 1]);
+        lines.push(paint(
+            ThemeRole::Prompt,
+            format!(
+                "@@ -{} +{} @@",
+                unified_range(first.first_start, last.first_end),
+                unified_range(first.second_start, last.second_end)
+            ),
+        ));
+        for opcode in hunk {
+            if opcode.tag == "equal" {
+                lines.extend(
+                    before[opcode.first_start..opcode.first_end]
+                        .iter()
+                        .map(|line| format!(" {line}")),
+                );
+                continue;
+            }
+            lines.extend(
+                before[opcode.first_start..opcode.first_end]
+                    .iter()
+                    .map(|line| paint(ThemeRole::Error, format!("-{line}"))),
+            );
+            lines.extend(
+                after[opcode.second_start..opcode.second_end]
+                    .iter()
+                    .map(|line| paint(ThemeRole::Command, format!("+{line}"))),
+            );
+        }
+    }
+    lines.join("\n")
+}
+
+/// `start,count` with one-based line numbers, as in `diff -u`.
+fn unified_range(start: usize, end: usize) -> String {
+    match end - start {
+        1 => format!("{}", start + 1),
+        0 => format!("{start},0"),
+        count => format!("{},{count}", start + 1),
+    }
+}
+
+fn render_side_by_side(
+    before: &[&str],
+    after: &[&str],
+    (from, to): (&str, &str),
+    hunks: &[Vec<Opcode>],
+    width: usize,
+) -> String {
+    let column = (width.saturating_sub(GUTTER.len()) / 2).max(10);
+    // Only the left side is padded, so lines carry no trailing blanks.
+    let left = |role: Option<ThemeRole>, text: &str| {
+        let cell = pad_to_width(&truncate_to_width(text, column), column);
+        role.map_or_else(|| cell.clone(), |role| paint(role, &cell))
+    };
+    let right = |role: Option<ThemeRole>, text: &str| {
+        let cell = truncate_to_width(text, column);
+        role.map_or_else(|| cell.clone(), |role| paint(role, &cell))
+    };
+
+    let mut lines = vec![format!(
+        "{}{GUTTER}{}",
+        left(Some(ThemeRole::Heading), from),
+        right(Some(ThemeRole::Heading), to)
+    )];
+    for (index, hunk) in hunks.iter().enumerate() {
+        if index > 0 {
+            lines.push(paint(ThemeRole::Muted, "⋯"));
+        }
+        for opcode in hunk {
+            let removed = &before[opcode.first_start..opcode.first_end];
+            let added = &after[opcode.second_start..opcode.second_end];
+            let (removed_role, added_role) = if opcode.tag == "equal" {
+                (None, None)
+            } else {
+                (Some(ThemeRole::Error), Some(ThemeRole::Command))
+            };
+            for offset in 0..removed.len().max(added.len()) {
+                let line = format!(
+                    "{}{GUTTER}{}",
+                    left(
+                        removed_role,
+                        removed.get(offset).copied().unwrap_or_default()
+                    ),
+                    right(added_role, added.get(offset).copied().unwrap_or_default())
+                );
+                lines.push(line.trim_end().to_string());
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+fn pretty_json(value: &Value) -> String {
+    to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+    use serde_json::json;
+
+    use super::*;
+
+    fn strip_ansi(text: &str) -> String {
+        Regex::new(r"\x1b\[[0-9;]*m")
+            .unwrap()
+            .replace_all(text, "")
+            .to_string()
+    }
+
+    #[test]
+    fn unified_diff_shows_hunks_with_context() {
+        let before = (1..=10)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let after = before
+            .replace('2', "two")
+            .replace("9", "nine\nnine-and-a-half");
+
+        let rendered = strip_ansi(&render_text_diff(
+            &before,
+            &after,
+            ("a", "b"),
+            DiffLayout::Unified,
+        ));
+
+        assert_eq!(
+            rendered,
+            "--- a\n+++ b\n@@ -1,10 +1,11 @@\n 1\n-2\n+two\n 3\n 4\n 5\n 6\n 7\n 8\n\
+             -9\n+nine\n+nine-and-a-half\n 10"
+        );
+        assert_eq!(
+            render_text_diff(&before, &before, ("a", "b"), DiffLayout::Unified),
+            ""
+        );
+    }
+
+    #[test]
+    fn value_diffs_render_pretty_json_side_by_side() {
+        let diff = ValueDiff::new(
+            json!({"cpus": 2, "os": "linux"}),
+            json!({"cpus": 4, "os": "linux"}),
+        );
+
+        let before = pretty_json(&diff.before);
+        let after = pretty_json(&diff.after);
+        let before = before.lines().collect::<Vec<_>>();
+        let after = after.lines().collect::<Vec<_>>();
+        let hunks = SequenceMatcher::new(&before, &after).get_grouped_opcodes(CONTEXT_LINES);
+
+        let rendered = strip_ansi(&render_side_by_side(
+            &before,
+            &after,
+            ("server", "file"),
+            &hunks,
+            43,
+        ));
+
+        let lines = rendered.lines().collect::<Vec<_>>();
+        assert!(lines[0].starts_with("server"));
+        assert!(lines[0].ends_with("| file"));
+        assert!(lines.contains(&r#"  "cpus": 2,         |   "cpus": 4,"#));
+        assert!(lines.contains(&r#"  "os": "linux"      |   "os": "linux""#));
+    }
+}
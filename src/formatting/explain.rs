@@ -0,0 +1,19 @@
+use crate::domain::ExplainedCall;
+
+use super::TableRenderable;
+
+impl TableRenderable for ExplainedCall {
+    fn headers() -> Vec<&'static str> {
+        vec!["Step", "Method", "Path", "Query", "Purpose"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.step.to_string(),
+            self.method.clone(),
+            self.path.clone(),
+            self.query.join("&"),
+            self.purpose.clone(),
+        ]
+    }
+}
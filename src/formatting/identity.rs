@@ -1,4 +1,5 @@
-use crate::domain::{MeRecord, PrincipalPermissionsRecord, PrincipalTokenRecord};
+use crate::domain::{MeRecord, PrincipalPermissionsRecord, PrincipalTokenRecord, SessionRecord};
+use crate::models::CachedTokenSummary;
 
 use super::{DetailRenderable, TableRenderable};
 
@@ -46,6 +47,39 @@ impl DetailRenderable for MeRecord {
     }
 }
 
+impl DetailRenderable for SessionRecord {
+    fn detail_rows(&self) -> Vec<(&'static str, String)> {
+        let mut rows = vec![
+            ("Username", self.username.clone()),
+            ("Kind", self.kind.clone()),
+            ("Identity Scope", self.identity_scope.clone()),
+            ("Server", self.server.clone()),
+            (
+                "Token Age",
+                format!(
+                    "{} (token {}, issued {})",
+                    elapsed(self.token_age_seconds),
+                    self.token_id,
+                    self.token_issued
+                ),
+            ),
+        ];
+        if let Some(expires_at) = &self.token_expires_at {
+            rows.push(("Token Expires", expires_at.to_string()));
+        }
+        rows.push((
+            "Groups",
+            if self.groups.is_empty() {
+                "<none>".to_string()
+            } else {
+                self.groups.join(", ")
+            },
+        ));
+        rows.push(("Admin", if self.admin { "yes" } else { "no" }.to_string()));
+        rows
+    }
+}
+
 impl TableRenderable for PrincipalTokenRecord {
     fn headers() -> Vec<&'static str> {
         vec![
@@ -160,20 +194,60 @@ impl TableRenderable for PrincipalPermissionsRecord {
 }
 use std::collections::HashSet;
 
+impl TableRenderable for CachedTokenSummary {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "Server",
+            "Scope",
+            "Username",
+            "Token",
+            "Age",
+            "Last Used",
+            "Current",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.server.clone(),
+            self.identity_scope.clone().unwrap_or_default(),
+            self.username.clone(),
+            if self.cached { "cached" } else { "missing" }.to_string(),
+            self.age_seconds.map(elapsed).unwrap_or_default(),
+            self.idle_seconds
+                .map(|seconds| format!("{} ago", elapsed(seconds)))
+                .unwrap_or_default(),
+            if self.current { "*" } else { "" }.to_string(),
+        ]
+    }
+}
+
+/// Compact elapsed time in the largest whole unit, such as `45s`, `12m`, `3h`, or `5d`.
+fn elapsed(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    match seconds {
+        0..60 => format!("{seconds}s"),
+        60..3_600 => format!("{}m", seconds / 60),
+        3_600..86_400 => format!("{}h", seconds / 3_600),
+        _ => format!("{}d", seconds / 86_400),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use hubuum_client::MeResponse;
     use serde_json::json;
 
+    use chrono::{TimeZone, Utc};
+
     use super::DetailRenderable;
-    use crate::domain::MeRecord;
+    use crate::domain::{MeRecord, SessionRecord};
 
-    #[test]
-    fn me_details_show_identity_scope() {
-        let response: MeResponse = serde_json::from_value(json!({
+    fn me_response(identity_scope: &str) -> MeResponse {
+        serde_json::from_value(json!({
             "principal": {
                 "principal_id": 1,
-                "identity_scope": "example-directory",
+                "identity_scope": identity_scope,
                 "kind": "human",
                 "name": "admin",
                 "created_at": null,
@@ -190,9 +264,35 @@ mod tests {
                 "last_used_at": null
             }
         }))
-        .expect("me response should deserialize");
+        .expect("me response should deserialize")
+    }
+
+    #[test]
+    fn me_details_show_identity_scope() {
+        let response = me_response("example-directory");
 
         let rows = MeRecord(response).detail_rows();
         assert!(rows.contains(&("Identity Scope", "example-directory".to_string())));
     }
+
+    #[test]
+    fn session_details_show_token_age_groups_and_admin_status() {
+        let now = Utc.with_ymd_and_hms(2026, 7, 13, 9, 0, 0).unwrap();
+        let session = SessionRecord::new(
+            me_response("local"),
+            "https://hubuum.example:443".to_string(),
+            vec!["operators".to_string(), "admins".to_string()],
+            true,
+            now,
+        );
+
+        assert_eq!(session.token_age_seconds, 2 * 86_400 + 12 * 60 + 9);
+        let rows = session.detail_rows();
+        assert!(rows.contains(&("Username", "admin".to_string())));
+        assert!(rows.contains(&("Groups", "admins, operators".to_string())));
+        assert!(rows.contains(&("Admin", "yes".to_string())));
+        assert!(rows
+            .iter()
+            .any(|(key, value)| *key == "Token Age" && value.starts_with("2d (token 9")));
+    }
 }
@@ -1,20 +1,34 @@
+mod apply;
+mod audit_log;
 mod background;
 mod classes;
 mod collections;
 mod computed;
 mod core;
+mod diff;
+mod explain;
 mod exports;
 mod groups;
 mod identity;
 mod imports;
 mod objects;
+mod profiles;
 mod relations;
+mod report;
 mod service_accounts;
 mod tasks;
+mod timeline;
 mod users;
 
+pub(crate) use collections::{human_bytes, human_count, optional_count};
 pub use core::{
     append_json, append_json_message, DetailRenderable, OutputFormatter, TableRenderable,
 };
+pub use diff::{render_value_diff, DiffLayout};
 pub(crate) use objects::data_preview;
-pub use relations::{render_related_class_tree_with_key, render_related_object_tree_with_key};
+pub use relations::{
+    render_class_relation_matrix, render_related_class_tree_with_key,
+    render_related_object_tree_with_key,
+};
+pub use report::render_collection_report_html;
+pub use timeline::render_timeline_chart;
@@ -1,6 +1,11 @@
 use serde_json::Value;
 
-use crate::domain::{ObjectDataMutationRecord, ResolvedObjectRecord};
+use crate::domain::{
+    ObjectDataMatchRecord, ObjectDataMutationRecord, ResolvedObjectRecord, SchemaViolation,
+    StaleObjectRecord,
+};
+use crate::masking::mask_for_display;
+use crate::terminal::truncate_to_width;
 
 use super::{DetailRenderable, TableRenderable};
 
@@ -33,6 +38,52 @@ impl DetailRenderable for ResolvedObjectRecord {
     }
 }
 
+impl TableRenderable for SchemaViolation {
+    fn headers() -> Vec<&'static str> {
+        vec!["Pointer", "Message"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        let pointer = if self.pointer.is_empty() {
+            "/".to_string()
+        } else {
+            self.pointer.clone()
+        };
+        vec![pointer, self.message.clone()]
+    }
+}
+
+impl TableRenderable for ObjectDataMatchRecord {
+    fn headers() -> Vec<&'static str> {
+        vec!["Collection", "Name", "Path", "Value"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.collection.clone(),
+            self.name.clone(),
+            self.path.clone(),
+            self.value.clone(),
+        ]
+    }
+}
+
+impl TableRenderable for StaleObjectRecord {
+    fn headers() -> Vec<&'static str> {
+        vec!["Collection", "id", "Name", "Updated", "Age (days)"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.collection.clone(),
+            self.id.to_string(),
+            self.name.clone(),
+            self.updated_at.clone(),
+            self.age_days.to_string(),
+        ]
+    }
+}
+
 impl TableRenderable for ResolvedObjectRecord {
     fn headers() -> Vec<&'static str> {
         vec![
@@ -94,7 +145,11 @@ fn human_readable_bytes(size: usize) -> String {
 }
 
 pub(crate) fn data_preview(data: Option<&Value>) -> String {
-    match data {
+    let data = data.cloned().map(|mut data| {
+        mask_for_display(&mut data);
+        data
+    });
+    match data.as_ref() {
         Some(Value::Object(object)) => truncate_preview(
             &object
                 .iter()
@@ -118,13 +173,7 @@ fn preview_value(value: &Value) -> String {
 }
 
 fn truncate_preview(value: &str) -> String {
-    if value.chars().count() <= DATA_PREVIEW_WIDTH {
-        return value.to_string();
-    }
-
-    let keep = DATA_PREVIEW_WIDTH.saturating_sub(3);
-    let truncated = value.chars().take(keep).collect::<String>();
-    format!("{truncated}...")
+    truncate_to_width(value, DATA_PREVIEW_WIDTH)
 }
 
 #[cfg(test)]
@@ -0,0 +1,37 @@
+use crate::domain::{ProfileCheckRecord, TokenCheck};
+
+use super::TableRenderable;
+
+impl TableRenderable for ProfileCheckRecord {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "Profile",
+            "Server",
+            "Username",
+            "Reachable",
+            "Token",
+            "Version",
+            "Problem",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.profile.clone(),
+            self.server.clone(),
+            self.username.clone(),
+            if self.reachable { "yes" } else { "no" }.to_string(),
+            match self.token {
+                TokenCheck::Valid => "valid",
+                TokenCheck::Rejected => "rejected",
+                TokenCheck::Missing => "missing",
+                TokenCheck::Unchecked => "-",
+            }
+            .to_string(),
+            self.server_version
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+            self.problem.clone().unwrap_or_default(),
+        ]
+    }
+}
@@ -1,31 +1,52 @@
+use std::iter::once;
+
 use crate::domain::{
-    RelatedClassTreeNode, RelatedObjectTreeNode, ResolvedClassRelationRecord,
-    ResolvedObjectRelationRecord, ResolvedRelatedClassRecord, ResolvedRelatedObjectRecord,
+    ClassRelationMatrix, RelatedClassTreeNode, RelatedObjectTreeNode, RelationDirection,
+    ResolvedClassRelationRecord, ResolvedObjectRelationRecord, ResolvedRelatedClassRecord,
+    ResolvedRelatedObjectRecord,
 };
 use crate::errors::AppError;
 use crate::output::{append_key_value, append_line};
+use crate::terminal::{display_width, pad_to_width};
 
 use super::{DetailRenderable, TableRenderable};
 
 impl DetailRenderable for ResolvedClassRelationRecord {
     fn detail_rows(&self) -> Vec<(&'static str, String)> {
-        vec![
-            ("ClassA", self.class_a.clone()),
-            ("ClassB", self.class_b.clone()),
-            ("Created", self.created_at.to_string()),
-            ("Updated", self.updated_at.to_string()),
-        ]
+        let mut rows = vec![
+            ("ID", self.id.to_string()),
+            ("Relation", self.arrow()),
+            ("Direction", direction_label(self.direction)),
+            ("From", with_id(&self.class_a, self.class_a_id)),
+            ("To", with_id(&self.class_b, self.class_b_id)),
+        ];
+        if let Some(count) = self.object_relations {
+            rows.push(("ObjectRelations", count.to_string()));
+        }
+        rows.push(("Created", self.created_at.to_string()));
+        rows.push(("Updated", self.updated_at.to_string()));
+        rows
     }
 }
 
 impl TableRenderable for ResolvedClassRelationRecord {
     fn headers() -> Vec<&'static str> {
-        vec!["id", "ClassA", "ClassB", "Created", "Updated"]
+        vec![
+            "id",
+            "Relation",
+            "Direction",
+            "From",
+            "To",
+            "Created",
+            "Updated",
+        ]
     }
 
     fn row(&self) -> Vec<String> {
         vec![
             self.id.to_string(),
+            self.arrow(),
+            direction_label(self.direction),
             self.class_a.clone(),
             self.class_b.clone(),
             self.created_at.to_string(),
@@ -37,10 +58,14 @@ impl TableRenderable for ResolvedClassRelationRecord {
 impl DetailRenderable for ResolvedObjectRelationRecord {
     fn detail_rows(&self) -> Vec<(&'static str, String)> {
         vec![
-            ("ClassA", self.class_a.clone()),
-            ("ClassB", self.class_b.clone()),
-            ("ObjectA", self.object_a.clone()),
-            ("ObjectB", self.object_b.clone()),
+            ("ID", self.id.to_string()),
+            ("Relation", self.arrow()),
+            ("Direction", direction_label(self.direction)),
+            ("ClassRelation", self.class_relation_id.to_string()),
+            ("FromClass", with_id(&self.class_a, self.class_a_id)),
+            ("ToClass", with_id(&self.class_b, self.class_b_id)),
+            ("FromObject", with_id(&self.object_a, self.object_a_id)),
+            ("ToObject", with_id(&self.object_b, self.object_b_id)),
             ("Created", self.created_at.to_string()),
             ("Updated", self.updated_at.to_string()),
         ]
@@ -50,13 +75,23 @@ impl DetailRenderable for ResolvedObjectRelationRecord {
 impl TableRenderable for ResolvedObjectRelationRecord {
     fn headers() -> Vec<&'static str> {
         vec![
-            "id", "ClassA", "ClassB", "ObjectA", "ObjectB", "Created", "Updated",
+            "id",
+            "Relation",
+            "Direction",
+            "FromClass",
+            "ToClass",
+            "FromObject",
+            "ToObject",
+            "Created",
+            "Updated",
         ]
     }
 
     fn row(&self) -> Vec<String> {
         vec![
             self.id.to_string(),
+            self.arrow(),
+            direction_label(self.direction),
             self.class_a.clone(),
             self.class_b.clone(),
             self.object_a.clone(),
@@ -67,6 +102,16 @@ impl TableRenderable for ResolvedObjectRelationRecord {
     }
 }
 
+/// `outgoing` or `incoming` from the class or object the command was asked about, or `-`
+/// when the relation was not reached through one of its ends.
+fn direction_label(direction: Option<RelationDirection>) -> String {
+    direction.map_or("-", RelationDirection::as_str).to_string()
+}
+
+fn with_id(name: &str, id: i32) -> String {
+    format!("{name} (#{id})")
+}
+
 impl DetailRenderable for ResolvedRelatedClassRecord {
     fn detail_rows(&self) -> Vec<(&'static str, String)> {
         vec![
@@ -166,6 +211,67 @@ pub fn render_related_class_tree_with_key(
     render_keyed_relation_entries(key, &class_relation_entries(nodes), padding)
 }
 
+/// Renders `matrix` as a grid with numbered columns, so wide models still fit one screen.
+pub fn render_class_relation_matrix(matrix: &ClassRelationMatrix) -> Result<(), AppError> {
+    if matrix.classes.is_empty() {
+        return append_line("No classes.");
+    }
+
+    let rows = matrix
+        .classes
+        .iter()
+        .map(|row| {
+            matrix
+                .classes
+                .iter()
+                .map(|column| matrix.cell(row, column))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let index_width = matrix.classes.len().to_string().len();
+    let name_width = matrix
+        .classes
+        .iter()
+        .map(|class| display_width(class))
+        .chain(once(display_width("Class")))
+        .max()
+        .unwrap_or_default();
+    let column_widths = (0..matrix.classes.len())
+        .map(|column| {
+            rows.iter()
+                .map(|cells| display_width(&cells[column]))
+                .chain(once((column + 1).to_string().len()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    let mut header = format!(
+        "{}  {}",
+        " ".repeat(index_width),
+        pad_to_width("Class", name_width)
+    );
+    for (column, width) in column_widths.iter().enumerate() {
+        header.push_str(&format!("  {:>width$}", column + 1));
+    }
+    append_line(header.trim_end())?;
+    for (index, (class, cells)) in matrix.classes.iter().zip(&rows).enumerate() {
+        let mut line = format!(
+            "{:>index_width$}  {}",
+            index + 1,
+            pad_to_width(class, name_width)
+        );
+        for (cell, width) in cells.iter().zip(&column_widths) {
+            line.push_str(&format!(
+                "  {}{cell}",
+                " ".repeat(width.saturating_sub(display_width(cell)))
+            ));
+        }
+        append_line(line)?;
+    }
+    Ok(())
+}
+
 fn render_keyed_relation_entries(
     key: &str,
     entries: &[String],
@@ -238,6 +344,62 @@ mod tests {
     use super::*;
     use crate::output::{reset_output, take_output};
 
+    #[test]
+    #[serial]
+    fn class_relation_matrix_numbers_columns() {
+        reset_output().expect("output should reset");
+        let relation = ResolvedClassRelationRecord {
+            id: 7,
+            class_a: "Hosts".to_string(),
+            class_a_id: 1,
+            class_b: "Rooms".to_string(),
+            class_b_id: 4,
+            object_relations: Some(12),
+            direction: None,
+            created_at: "created".to_string(),
+            updated_at: "updated".to_string(),
+        };
+        let matrix = ClassRelationMatrix::new(
+            vec!["Rooms".to_string(), "Hosts".to_string()],
+            vec![relation],
+        );
+
+        render_class_relation_matrix(&matrix).expect("matrix should render");
+
+        let output = take_output().expect("output should be readable");
+        assert_eq!(
+            output.lines,
+            vec!["   Class   1   2", "1  Hosts   ·  12", "2  Rooms  12   ·",]
+        );
+    }
+
+    #[test]
+    fn class_relation_details_include_ids_and_direction() {
+        let relation = ResolvedClassRelationRecord {
+            id: 7,
+            class_a: "Hosts".to_string(),
+            class_a_id: 1,
+            class_b: "Rooms".to_string(),
+            class_b_id: 4,
+            object_relations: Some(12),
+            direction: None,
+            created_at: "created".to_string(),
+            updated_at: "updated".to_string(),
+        };
+
+        let rows = relation.clone().seen_from(4).detail_rows();
+
+        assert_eq!(rows[0], ("ID", "7".to_string()));
+        assert_eq!(rows[1], ("Relation", "Hosts → Rooms".to_string()));
+        assert_eq!(rows[2], ("Direction", "incoming".to_string()));
+        assert_eq!(rows[3], ("From", "Hosts (#1)".to_string()));
+        assert_eq!(rows[4], ("To", "Rooms (#4)".to_string()));
+        assert_eq!(rows[5], ("ObjectRelations", "12".to_string()));
+
+        assert_eq!(relation.clone().seen_from(1).row()[2], "outgoing");
+        assert_eq!(relation.seen_from(9).row()[2], "-");
+    }
+
     #[test]
     #[serial]
     fn render_related_object_tree_uses_hierarchy() {
@@ -0,0 +1,238 @@
+//! The standalone HTML report written by `export html`.
+//!
+//! The page carries its own stylesheet and no scripts, so it can be mailed or
+//! attached to a ticket and opened anywhere.
+
+use std::fmt::Write;
+
+use crate::domain::{CollectionReport, GroupPermissionsSummary};
+
+use super::collections::optional_count;
+use super::TableRenderable;
+
+const STYLE: &str = "
+body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 60rem; color: #1f2933; }
+h1 { margin-bottom: 0.25rem; }
+h2 { margin-top: 2rem; border-bottom: 1px solid #d9e2ec; padding-bottom: 0.25rem; }
+.meta, .empty { color: #627d98; }
+.cards { display: flex; gap: 1rem; margin: 1.5rem 0; }
+.card { flex: 1; border: 1px solid #d9e2ec; border-radius: 6px; padding: 0.75rem 1rem; }
+.card strong { display: block; font-size: 1.75rem; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: 0.4rem 0.6rem; border-bottom: 1px solid #e4e7eb; }
+th { background: #f0f4f8; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+";
+
+/// Renders `report` as a self-contained HTML page.
+pub fn render_collection_report_html(
+    report: &CollectionReport,
+    server_url: &str,
+    generated_at: &str,
+) -> String {
+    let collection = &report.collection.0;
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Collection {name}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>Collection {name}</h1>\n<p class=\"meta\">{description}</p>\n\
+         <p class=\"meta\">Generated {generated} from {server}</p>\n",
+        name = escape(&collection.name),
+        description = escape(&collection.description),
+        generated = escape(generated_at),
+        server = escape(server_url),
+    );
+
+    html.push_str("<div class=\"cards\">\n");
+    for (label, count) in [
+        ("Classes", Some(report.classes.len() as u64)),
+        ("Objects", report.objects()),
+        ("Relations", Some(report.relations.len() as u64)),
+        ("Groups", Some(report.permissions.len() as u64)),
+    ] {
+        let _ = writeln!(
+            html,
+            "<div class=\"card\"><strong>{}</strong>{label}</div>",
+            escape(&optional_count(count))
+        );
+    }
+    html.push_str("</div>\n");
+
+    section(
+        &mut html,
+        "Classes",
+        &["Class", "Description", "Objects"],
+        report.classes.iter().map(|class| {
+            vec![
+                Cell::Text(class.class.0.name.clone()),
+                Cell::Text(class.class.0.description.clone()),
+                Cell::Number(class.object_count),
+            ]
+        }),
+    );
+    section(
+        &mut html,
+        "Relations",
+        &["From", "To"],
+        report.relations.iter().map(|relation| {
+            vec![
+                Cell::Text(relation.class_a.clone()),
+                Cell::Text(relation.class_b.clone()),
+            ]
+        }),
+    );
+    section(
+        &mut html,
+        "Permissions",
+        &GroupPermissionsSummary::headers(),
+        report
+            .permissions
+            .iter()
+            .map(|summary| summary.row().into_iter().map(Cell::Text).collect()),
+    );
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+enum Cell {
+    Text(String),
+    Number(Option<u64>),
+}
+
+fn section(
+    html: &mut String,
+    title: &str,
+    headers: &[&str],
+    rows: impl Iterator<Item = Vec<Cell>>,
+) {
+    let _ = writeln!(html, "<h2>{}</h2>", escape(title));
+    let mut rows = rows.peekable();
+    if rows.peek().is_none() {
+        let _ = writeln!(
+            html,
+            "<p class=\"empty\">No {} in this collection.</p>",
+            title.to_lowercase()
+        );
+        return;
+    }
+
+    html.push_str("<table>\n<thead><tr>");
+    for header in headers {
+        let _ = write!(html, "<th>{}</th>", escape(header));
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+    for row in rows {
+        html.push_str("<tr>");
+        for cell in row {
+            match cell {
+                Cell::Text(text) => {
+                    let _ = write!(html, "<td>{}</td>", escape(&text));
+                }
+                Cell::Number(count) => {
+                    let _ = write!(
+                        html,
+                        "<td class=\"num\">{}</td>",
+                        escape(&optional_count(count))
+                    );
+                }
+            }
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>\n");
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_value, json};
+
+    use super::*;
+    use crate::domain::{ClassCountRecord, CollectionRecord};
+
+    fn class(name: &str, object_count: Option<u64>) -> ClassCountRecord {
+        ClassCountRecord {
+            class: from_value(json!({
+                "id": 1,
+                "name": name,
+                "description": "",
+                "collection": {
+                    "id": 1,
+                    "name": "prod",
+                    "description": "",
+                    "created_at": "2024-01-01T00:00:00Z",
+                    "updated_at": "2024-01-01T00:00:00Z"
+                },
+                "json_schema": {},
+                "validate_schema": false,
+                "created_at": "2024-01-01T00:00:00Z",
+                "updated_at": "2024-01-01T00:00:00Z"
+            }))
+            .expect("class fixture should deserialize"),
+            object_count,
+        }
+    }
+
+    fn report() -> CollectionReport {
+        let collection: CollectionRecord = from_value(json!({
+            "id": 1,
+            "name": "prod",
+            "description": "Servers & <routers>",
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z"
+        }))
+        .expect("collection fixture should deserialize");
+        CollectionReport {
+            collection,
+            classes: vec![class("hosts", Some(12)), class("rooms", Some(3))],
+            relations: Vec::new(),
+            permissions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn escapes_markup_in_names() {
+        assert_eq!(
+            escape(r#"<a href="x">'&'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;"
+        );
+        let html = render_collection_report_html(&report(), "https://hubuum", "now");
+        assert!(html.contains("Servers &amp; &lt;routers&gt;"));
+        assert!(!html.contains("<routers>"));
+    }
+
+    #[test]
+    fn renders_every_section_without_scripts() {
+        let html = render_collection_report_html(&report(), "https://hubuum", "now");
+        assert!(html.contains("<strong>15</strong>Objects"));
+        assert!(html.contains("<td>hosts</td><td></td><td class=\"num\">12</td>"));
+        assert!(html.contains("No relations in this collection."));
+        assert!(html.contains("No permissions in this collection."));
+        assert!(!html.contains("<script"));
+    }
+
+    #[test]
+    fn unknown_class_counts_render_as_a_dash() {
+        let mut report = report();
+        report.classes[1].object_count = None;
+        let html = render_collection_report_html(&report, "https://hubuum", "now");
+        assert!(html.contains("<strong>-</strong>Objects"));
+        assert!(html.contains("<td>rooms</td><td></td><td class=\"num\">-</td>"));
+    }
+}
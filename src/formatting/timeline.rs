@@ -0,0 +1,101 @@
+use crate::domain::TimelineBucket;
+
+use super::{human_count, TableRenderable};
+
+/// Width of the longest bar in a timeline chart.
+const BAR_WIDTH: u64 = 40;
+
+impl TableRenderable for TimelineBucket {
+    fn headers() -> Vec<&'static str> {
+        vec!["Group", "Period", "Created", "Total"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.group.clone(),
+            self.period.clone(),
+            self.created.to_string(),
+            self.total.to_string(),
+        ]
+    }
+}
+
+/// Renders `buckets` as one ASCII bar chart per group.
+///
+/// Bars share one scale, so groups can be compared, and any period with new entities gets
+/// at least one `#`.
+pub fn render_timeline_chart(buckets: &[TimelineBucket]) -> Vec<String> {
+    let busiest = buckets
+        .iter()
+        .map(|bucket| bucket.created)
+        .max()
+        .unwrap_or(0);
+    let period_width = buckets
+        .iter()
+        .map(|bucket| bucket.period.len())
+        .max()
+        .unwrap_or(0);
+    let count_width = buckets
+        .iter()
+        .map(|bucket| human_count(bucket.created).len())
+        .max()
+        .unwrap_or(0);
+
+    let mut lines = Vec::new();
+    let mut current_group: Option<&str> = None;
+    for bucket in buckets {
+        if current_group != Some(bucket.group.as_str()) {
+            if current_group.is_some() {
+                lines.push(String::new());
+            }
+            lines.push(bucket.group.clone());
+            current_group = Some(&bucket.group);
+        }
+        let bar = match bucket.created {
+            0 => 0,
+            created => (created * BAR_WIDTH / busiest).max(1),
+        };
+        lines.push(format!(
+            "  {:<period_width$}  {:<bar_width$}  {:>count_width$}  (total {})",
+            bucket.period,
+            "#".repeat(bar as usize),
+            human_count(bucket.created),
+            human_count(bucket.total),
+            bar_width = BAR_WIDTH as usize,
+        ));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render_timeline_chart;
+    use crate::domain::TimelineBucket;
+
+    fn bucket(group: &str, period: &str, created: u64, total: u64) -> TimelineBucket {
+        TimelineBucket {
+            group: group.to_string(),
+            period: period.to_string(),
+            created,
+            total,
+        }
+    }
+
+    #[test]
+    fn bars_share_one_scale_across_groups() {
+        let lines = render_timeline_chart(&[
+            bucket("lab", "2026-02", 1, 1),
+            bucket("prod", "2026-01", 40, 40),
+            bucket("prod", "2026-02", 0, 40),
+        ]);
+
+        assert_eq!(lines[0], "lab");
+        assert!(lines[1].starts_with("  2026-02  # "), "{}", lines[1]);
+        assert!(lines[1].ends_with(" 1  (total 1)"), "{}", lines[1]);
+        assert_eq!(lines[2], "");
+        assert_eq!(lines[3], "prod");
+        assert!(lines[4].contains(&"#".repeat(40)), "{}", lines[4]);
+        assert!(!lines[5].contains('#'), "{}", lines[5]);
+        assert!(lines[5].ends_with(" 0  (total 40)"), "{}", lines[5]);
+    }
+}
@@ -0,0 +1,383 @@
+use std::io::{stderr, stdin, BufRead, Stderr, StdinLock, Write};
+
+use serde_json::{from_str, Map, Value};
+
+use crate::errors::AppError;
+use crate::json_schema::schema_violations;
+use crate::terminal::prompts_are_interactive;
+
+/// Line-based prompts for interactive commands, written to stderr so stdout stays clean.
+pub struct Form<R, W> {
+    input: R,
+    prompt: W,
+}
+
+impl Form<StdinLock<'static>, Stderr> {
+    pub fn stdio() -> Self {
+        Self::new(stdin().lock(), stderr())
+    }
+
+    /// Like [`Form::stdio`], but refuses to prompt when stdin or stderr is not a terminal.
+    ///
+    /// `feature` names what asked for the prompts and `alternative` says how to do
+    /// without them, e.g. in a script.
+    pub fn terminal(feature: &str, alternative: &str) -> Result<Self, AppError> {
+        if !prompts_are_interactive() {
+            return Err(AppError::CommandExecutionError(format!(
+                "{feature} needs an interactive terminal; {alternative}"
+            )));
+        }
+        Ok(Self::stdio())
+    }
+}
+
+impl<R: BufRead, W: Write> Form<R, W> {
+    pub fn new(input: R, prompt: W) -> Self {
+        Self { input, prompt }
+    }
+
+    /// Asks for a text value until one is given; an empty answer takes `default` if there is one.
+    pub fn text(&mut self, label: &str, default: Option<&str>) -> Result<String, AppError> {
+        loop {
+            let answer = match default {
+                Some(default) => self.ask(&format!("{label} [{default}]: "))?,
+                None => self.ask(&format!("{label}: "))?,
+            };
+            match (answer.is_empty(), default) {
+                (false, _) => return Ok(answer),
+                (true, Some(default)) => return Ok(default.to_string()),
+                (true, None) => writeln!(self.prompt, "  {label} is required.")?,
+            }
+        }
+    }
+
+    /// Asks for one of `choices` (case-insensitive), re-prompting until one is given.
+    pub fn choice(
+        &mut self,
+        label: &str,
+        choices: &[&str],
+        default: &str,
+    ) -> Result<String, AppError> {
+        let label = format!("{label} ({})", choices.join("/"));
+        loop {
+            let answer = self.text(&label, Some(default))?.to_lowercase();
+            if choices.contains(&answer.as_str()) {
+                return Ok(answer);
+            }
+            writeln!(self.prompt, "  Use one of {}.", choices.join(", "))?;
+        }
+    }
+
+    /// Prints `text` above the next prompt, e.g. a summary to confirm.
+    pub fn show(&mut self, text: &str) -> Result<(), AppError> {
+        writeln!(self.prompt, "{text}")?;
+        Ok(())
+    }
+
+    /// Asks a yes/no question; only `y` or `yes` count as yes.
+    pub fn confirm(&mut self, question: &str) -> Result<bool, AppError> {
+        let answer = self.ask(&format!("{question} [y/N]: "))?;
+        Ok(matches!(answer.to_lowercase().as_str(), "y" | "yes"))
+    }
+
+    /// Asks for `expected` to be typed exactly, as the safety prompts do; surrounding
+    /// whitespace or a different case does not count.
+    pub fn typed_confirmation(&mut self, question: &str, expected: &str) -> Result<bool, AppError> {
+        let answer = self.read_answer(question)?;
+        Ok(answer.trim_end_matches(['\r', '\n']) == expected)
+    }
+
+    /// Shows `items` as a checklist and toggles entries until an empty answer confirms it.
+    ///
+    /// Every item starts selected. Answers are item numbers and ranges such as `2 4-6`,
+    /// or `all` / `none`. Returns one flag per item.
+    pub fn checklist(&mut self, items: &[String]) -> Result<Vec<bool>, AppError> {
+        let mut selected = vec![true; items.len()];
+        let width = items.len().to_string().len();
+        loop {
+            for (index, item) in items.iter().enumerate() {
+                let mark = if selected[index] { "x" } else { " " };
+                writeln!(self.prompt, "  [{mark}] {:>width$}. {item}", index + 1)?;
+            }
+            let count = selected.iter().filter(|selected| **selected).count();
+            let answer = self.ask(&format!(
+                "Toggle items (numbers, ranges, all, none), or press Enter to keep {count} of {}: ",
+                items.len()
+            ))?;
+            match answer.to_lowercase().as_str() {
+                "" => return Ok(selected),
+                "all" => selected.fill(true),
+                "none" => selected.fill(false),
+                _ => match parse_toggles(&answer, items.len()) {
+                    Ok(indexes) => {
+                        for index in indexes {
+                            selected[index] = !selected[index];
+                        }
+                    }
+                    Err(message) => writeln!(self.prompt, "  {message}")?,
+                },
+            }
+        }
+    }
+
+    /// Walks the properties of an object `schema`, prompting for each and validating the answers.
+    ///
+    /// Required properties come first. Optional properties may be skipped with an empty
+    /// answer, nested objects are prompted property by property, and anything else is
+    /// read as a JSON literal.
+    pub fn schema_object(&mut self, schema: &Value) -> Result<Value, AppError> {
+        self.schema_object_at(schema, "")
+    }
+
+    /// Reads an optional JSON value; an empty answer yields `None`.
+    pub fn json(&mut self, label: &str) -> Result<Option<Value>, AppError> {
+        loop {
+            let answer = self.ask(&format!("{label} (JSON, empty to skip): "))?;
+            if answer.is_empty() {
+                return Ok(None);
+            }
+            match from_str(&answer) {
+                Ok(value) => return Ok(Some(value)),
+                Err(error) => writeln!(self.prompt, "  Invalid JSON: {error}")?,
+            }
+        }
+    }
+
+    fn schema_object_at(&mut self, schema: &Value, prefix: &str) -> Result<Value, AppError> {
+        let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+            return Ok(Value::Object(Map::new()));
+        };
+        let required = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|names| names.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let mut ordered = properties.iter().collect::<Vec<_>>();
+        ordered.sort_by_key(|(name, _)| !required.contains(&name.as_str()));
+
+        let mut data = Map::new();
+        for (name, property) in ordered {
+            let path = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{prefix}.{name}")
+            };
+            let is_required = required.contains(&name.as_str());
+            if let Some(value) = self.schema_value(property, &path, is_required)? {
+                data.insert(name.clone(), value);
+            }
+        }
+        Ok(Value::Object(data))
+    }
+
+    fn schema_value(
+        &mut self,
+        schema: &Value,
+        path: &str,
+        required: bool,
+    ) -> Result<Option<Value>, AppError> {
+        let kind = schema.get("type").and_then(Value::as_str).unwrap_or("json");
+        if kind == "object" && schema.get("properties").is_some() {
+            if !required && !self.confirm(&format!("Fill in {path}?"))? {
+                return Ok(None);
+            }
+            return self.schema_object_at(schema, path).map(Some);
+        }
+
+        let mut label = format!("{path} ({kind}");
+        if required {
+            label.push_str(", required");
+        }
+        label.push(')');
+        if let Some(choices) = schema.get("enum").and_then(Value::as_array) {
+            let choices = choices.iter().map(scalar_label).collect::<Vec<_>>();
+            label.push_str(&format!(" one of {}", choices.join(", ")));
+        }
+        if let Some(description) = schema.get("description").and_then(Value::as_str) {
+            label.push_str(&format!(" - {description}"));
+        }
+        let default = schema.get("default");
+        if let Some(default) = default {
+            label.push_str(&format!(" [{}]", scalar_label(default)));
+        }
+
+        loop {
+            let answer = self.ask(&format!("{label}: "))?;
+            if answer.is_empty() {
+                match default {
+                    Some(default) => return Ok(Some(default.clone())),
+                    None if !required => return Ok(None),
+                    None => {
+                        writeln!(self.prompt, "  {path} is required.")?;
+                        continue;
+                    }
+                }
+            }
+
+            let value = match parse_typed(kind, &answer) {
+                Ok(value) => value,
+                Err(message) => {
+                    writeln!(self.prompt, "  {message}")?;
+                    continue;
+                }
+            };
+            // Property schemas may reference definitions elsewhere in the class schema; the
+            // whole payload is validated again before anything is sent.
+            let violations = schema_violations(schema, &value).unwrap_or_default();
+            if violations.is_empty() {
+                return Ok(Some(value));
+            }
+            for violation in violations {
+                writeln!(self.prompt, "  {}", violation.message)?;
+            }
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn into_prompt(self) -> W {
+        self.prompt
+    }
+
+    fn ask(&mut self, prompt: &str) -> Result<String, AppError> {
+        Ok(self.read_answer(prompt)?.trim().to_string())
+    }
+
+    fn read_answer(&mut self, prompt: &str) -> Result<String, AppError> {
+        write!(self.prompt, "{prompt}")?;
+        self.prompt.flush()?;
+
+        let mut answer = String::new();
+        if self.input.read_line(&mut answer)? == 0 {
+            return Err(AppError::CommandExecutionError(
+                "Input ended before the form was complete; nothing was changed".to_string(),
+            ));
+        }
+        Ok(answer)
+    }
+}
+
+/// Zero-based indexes named by a checklist answer such as `1 3-5`.
+fn parse_toggles(answer: &str, len: usize) -> Result<Vec<usize>, String> {
+    let mut indexes = Vec::new();
+    for part in answer.split([' ', ',']).filter(|part| !part.is_empty()) {
+        let invalid = || format!("'{part}' is not an item number or range between 1 and {len}");
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (start, end),
+            None => (part, part),
+        };
+        let start = start.parse::<usize>().map_err(|_| invalid())?;
+        let end = end.parse::<usize>().map_err(|_| invalid())?;
+        if start == 0 || start > end || end > len {
+            return Err(invalid());
+        }
+        indexes.extend(start - 1..end);
+    }
+    Ok(indexes)
+}
+
+fn parse_typed(kind: &str, answer: &str) -> Result<Value, String> {
+    match kind {
+        "string" => Ok(Value::String(answer.to_string())),
+        "integer" => answer
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| format!("'{answer}' is not an integer")),
+        "number" => answer
+            .parse::<f64>()
+            .map(Value::from)
+            .map_err(|_| format!("'{answer}' is not a number")),
+        "boolean" => match answer.to_lowercase().as_str() {
+            "true" | "yes" | "y" => Ok(Value::Bool(true)),
+            "false" | "no" | "n" => Ok(Value::Bool(false)),
+            _ => Err(format!("'{answer}' is not true or false")),
+        },
+        _ => from_str(answer).map_err(|error| format!("Invalid JSON: {error}")),
+    }
+}
+
+fn scalar_label(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn form(answers: &str) -> Form<Cursor<Vec<u8>>, Vec<u8>> {
+        Form::new(Cursor::new(answers.as_bytes().to_vec()), Vec::new())
+    }
+
+    #[test]
+    fn text_reprompts_until_required_value_and_uses_defaults() {
+        let mut prompts = form("\nweb01\n\n");
+        assert_eq!(prompts.text("Name", None).unwrap(), "web01");
+        assert_eq!(prompts.text("Collection", Some("prod")).unwrap(), "prod");
+        assert!(String::from_utf8(prompts.prompt)
+            .unwrap()
+            .contains("Name is required."));
+    }
+
+    #[test]
+    fn schema_object_prompts_required_first_and_validates_answers() {
+        let schema = json!({
+            "type": "object",
+            "required": ["os"],
+            "properties": {
+                "cpus": {"type": "integer", "minimum": 1},
+                "managed": {"type": "boolean", "default": true},
+                "notes": {"type": "string"},
+                "os": {"type": "string", "enum": ["linux", "bsd"]}
+            }
+        });
+        // os: invalid enum value, then valid; cpus: not a number, below minimum, then 4;
+        // managed: default; notes: skipped.
+        let mut prompts = form("windows\nlinux\nmany\n0\n4\n\n\n");
+
+        let data = prompts.schema_object(&schema).unwrap();
+
+        assert_eq!(data, json!({"os": "linux", "cpus": 4, "managed": true}));
+        let transcript = String::from_utf8(prompts.prompt).unwrap();
+        assert!(transcript.starts_with("os (string, required) one of linux, bsd: "));
+        assert!(transcript.contains("'many' is not an integer"));
+    }
+
+    #[test]
+    fn checklists_toggle_numbers_and_ranges_until_confirmed() {
+        let items = ["a", "b", "c", "d"].map(String::from);
+        // Deselect 2-4, reject an out-of-range answer, reselect 3, then confirm.
+        let mut prompts = form("2-4\n9\n3\n\n");
+
+        let selected = prompts.checklist(&items).unwrap();
+
+        assert_eq!(selected, vec![true, false, true, false]);
+        let transcript = String::from_utf8(prompts.prompt).unwrap();
+        assert!(transcript.contains("  [x] 1. a"));
+        assert!(transcript.contains("'9' is not an item number or range between 1 and 4"));
+        assert!(transcript.contains("press Enter to keep 1 of 4"));
+        assert_eq!(form("none\n\n").checklist(&items).unwrap(), vec![false; 4]);
+    }
+
+    #[test]
+    fn nested_objects_and_end_of_input() {
+        let schema = json!({
+            "properties": {
+                "network": {
+                    "type": "object",
+                    "properties": {"ip": {"type": "string"}}
+                }
+            }
+        });
+        let data = form("y\n10.0.0.1\n").schema_object(&schema).unwrap();
+        assert_eq!(data, json!({"network": {"ip": "10.0.0.1"}}));
+
+        assert!(form("").text("Name", None).is_err());
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+
+use log::debug;
+use shlex::split;
+
+use crate::commands::CliOption;
+use crate::config::HooksConfig;
+use crate::errors::AppError;
+use crate::output::add_warning;
+
+const ENV_PREFIX: &str = "HUBUUM_HOOK_";
+
+/// Hooks configured for one command invocation, with the entity context they receive.
+///
+/// Hook commands are split into words like a shell would, but are executed directly,
+/// so option values substituted into `{placeholders}` are never re-parsed by a shell.
+pub struct CommandHooks {
+    command: String,
+    pre: Option<String>,
+    post: Option<String>,
+    context: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum HookStage {
+    Pre,
+    Post,
+}
+
+impl CommandHooks {
+    pub fn new(
+        config: &HooksConfig,
+        command_path: &[String],
+        options: &HashMap<String, String>,
+        option_defs: &[CliOption],
+    ) -> Self {
+        let command = command_path.join(" ");
+        let context = option_defs
+            .iter()
+            .filter_map(|option| {
+                let long = option
+                    .long
+                    .as_deref()
+                    .map(|long| long.trim_start_matches('-'));
+                let short = option
+                    .short
+                    .as_deref()
+                    .map(|short| short.trim_start_matches('-'));
+                let value = long
+                    .and_then(|key| options.get(key))
+                    .or_else(|| short.and_then(|key| options.get(key)))?;
+                let value = if option.flag && value.is_empty() {
+                    "true".to_string()
+                } else {
+                    value.clone()
+                };
+                Some((long.unwrap_or(&option.name).to_string(), value))
+            })
+            .collect();
+
+        Self {
+            pre: config.pre.get(&command).cloned(),
+            post: config.post.get(&command).cloned(),
+            command,
+            context,
+        }
+    }
+
+    /// Runs the `pre` hook; a failing hook aborts the command.
+    pub fn run_pre(&self) -> Result<(), AppError> {
+        let Some(template) = &self.pre else {
+            return Ok(());
+        };
+        self.run(HookStage::Pre, template, &[])
+    }
+
+    /// Runs the `post` hook with the command's outcome; failures are reported as warnings.
+    pub fn run_post(&self, outcome: &Result<(), AppError>) -> Result<(), AppError> {
+        let Some(template) = &self.post else {
+            return Ok(());
+        };
+        let status = match outcome {
+            Ok(()) => vec![("STATUS".to_string(), "success".to_string())],
+            Err(error) => vec![
+                ("STATUS".to_string(), "failure".to_string()),
+                ("ERROR".to_string(), error.to_string()),
+            ],
+        };
+        if let Err(error) = self.run(HookStage::Post, template, &status) {
+            add_warning(error)?;
+        }
+        Ok(())
+    }
+
+    fn run(
+        &self,
+        stage: HookStage,
+        template: &str,
+        extra_env: &[(String, String)],
+    ) -> Result<(), AppError> {
+        let argv = self.expand(template)?;
+        let (program, args) = argv.split_first().ok_or_else(|| {
+            AppError::ConfigError(format!(
+                "Empty {} hook for '{}'",
+                stage.as_str(),
+                self.command
+            ))
+        })?;
+        debug!(
+            "Running {} hook for '{}': {argv:?}",
+            stage.as_str(),
+            self.command
+        );
+
+        let output = Command::new(program)
+            .args(args)
+            .envs(self.environment(stage, extra_env))
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|error| {
+                AppError::CommandExecutionError(format!(
+                    "{} hook for '{}' could not start '{program}': {error}",
+                    stage.label(),
+                    self.command
+                ))
+            })?;
+        if output.status.success() {
+            return Ok(());
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr.trim();
+        Err(AppError::CommandExecutionError(format!(
+            "{} hook for '{}' failed ({}){}",
+            stage.label(),
+            self.command,
+            output.status,
+            if detail.is_empty() {
+                String::new()
+            } else {
+                format!(": {detail}")
+            }
+        )))
+    }
+
+    fn expand(&self, template: &str) -> Result<Vec<String>, AppError> {
+        let words = split(template).ok_or_else(|| {
+            AppError::ConfigError(format!(
+                "Hook for '{}' has unbalanced quotes: {template}",
+                self.command
+            ))
+        })?;
+        Ok(words
+            .into_iter()
+            .map(|word| {
+                self.context.iter().fold(
+                    word.replace("{command}", &self.command),
+                    |word, (key, value)| word.replace(&format!("{{{key}}}"), value),
+                )
+            })
+            .collect())
+    }
+
+    fn environment(
+        &self,
+        stage: HookStage,
+        extra_env: &[(String, String)],
+    ) -> Vec<(String, String)> {
+        [
+            ("STAGE".to_string(), stage.as_str().to_string()),
+            ("COMMAND".to_string(), self.command.clone()),
+        ]
+        .into_iter()
+        .chain(self.context.iter().map(|(key, value)| {
+            (
+                format!("OPT_{}", key.to_ascii_uppercase().replace('-', "_")),
+                value.clone(),
+            )
+        }))
+        .chain(extra_env.iter().cloned())
+        .map(|(key, value)| (format!("{ENV_PREFIX}{key}"), value))
+        .collect()
+    }
+}
+
+impl HookStage {
+    fn as_str(self) -> &'static str {
+        match self {
+            HookStage::Pre => "pre",
+            HookStage::Post => "post",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            HookStage::Pre => "Pre",
+            HookStage::Post => "Post",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+
+    use super::*;
+
+    fn option(name: &str, short: Option<&str>, flag: bool) -> CliOption {
+        CliOption {
+            name: name.to_string(),
+            short: short.map(str::to_string),
+            long: Some(format!("--{name}")),
+            flag,
+            greedy: false,
+            nargs: None,
+            repeatable: false,
+            value_source: false,
+            raw: false,
+            positional: None,
+            help: String::new(),
+            field_type: TypeId::of::<String>(),
+            field_type_help: String::new(),
+            required: false,
+            autocomplete: None,
+        }
+    }
+
+    fn hooks(pre: &str, post: &str) -> CommandHooks {
+        let config = HooksConfig {
+            pre: HashMap::from([("object delete".to_string(), pre.to_string())]),
+            post: HashMap::from([("object delete".to_string(), post.to_string())]),
+        };
+        let options = HashMap::from([
+            ("c".to_string(), "Host".to_string()),
+            ("name".to_string(), "web 1".to_string()),
+            ("force".to_string(), String::new()),
+        ]);
+        CommandHooks::new(
+            &config,
+            &["object".to_string(), "delete".to_string()],
+            &options,
+            &[
+                option("class", Some("-c"), false),
+                option("name", Some("-n"), false),
+                option("force", None, true),
+            ],
+        )
+    }
+
+    #[test]
+    fn placeholders_expand_to_single_arguments() {
+        let hooks = hooks("", "");
+        assert_eq!(
+            hooks
+                .expand("./notify.sh {command} {class} '{name}' {missing}")
+                .expect("template should expand"),
+            vec!["./notify.sh", "object delete", "Host", "web 1", "{missing}"]
+        );
+    }
+
+    #[test]
+    fn environment_exposes_entity_context() {
+        let env = hooks("", "").environment(
+            HookStage::Post,
+            &[("STATUS".to_string(), "success".to_string())],
+        );
+        for expected in [
+            ("HUBUUM_HOOK_STAGE", "post"),
+            ("HUBUUM_HOOK_COMMAND", "object delete"),
+            ("HUBUUM_HOOK_OPT_CLASS", "Host"),
+            ("HUBUUM_HOOK_OPT_NAME", "web 1"),
+            ("HUBUUM_HOOK_OPT_FORCE", "true"),
+            ("HUBUUM_HOOK_STATUS", "success"),
+        ] {
+            assert!(
+                env.contains(&(expected.0.to_string(), expected.1.to_string())),
+                "missing {expected:?} in {env:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn unmatched_commands_have_no_hooks() {
+        let hooks = CommandHooks::new(
+            &HooksConfig::default(),
+            &["class".to_string(), "list".to_string()],
+            &HashMap::new(),
+            &[],
+        );
+        assert!(hooks.run_pre().is_ok());
+        assert!(hooks.run_post(&Ok(())).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failing_pre_hook_aborts_the_command() {
+        assert!(hooks("true", "true").run_pre().is_ok());
+        let error = hooks("sh -c 'echo denied >&2; exit 3'", "true")
+            .run_pre()
+            .expect_err("failing hook should abort");
+        assert!(error.to_string().contains("denied"), "{error}");
+    }
+}
@@ -0,0 +1,111 @@
+//! Ctrl-C handling for long-running commands that should stop rather than end the session.
+//!
+//! While an [`InterruptWatch`] is alive, SIGINT only sets a flag the command polls. Outside of
+//! one the default action applies, so Ctrl-C still terminates any other command.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(unix)]
+use std::sync::Once;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+static WATCHING: AtomicBool = AtomicBool::new(false);
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// How often [`InterruptWatch::sleep`] checks for an interrupt.
+const POLL_SLICE: Duration = Duration::from_millis(100);
+
+/// Catches Ctrl-C for as long as it is alive.
+pub(crate) struct InterruptWatch {
+    _private: (),
+}
+
+impl InterruptWatch {
+    pub(crate) fn start() -> Self {
+        install_handler();
+        INTERRUPTED.store(false, Ordering::SeqCst);
+        WATCHING.store(true, Ordering::SeqCst);
+        Self { _private: () }
+    }
+
+    /// Whether Ctrl-C was pressed since the watch started.
+    pub(crate) fn interrupted(&self) -> bool {
+        INTERRUPTED.load(Ordering::SeqCst)
+    }
+
+    /// Sleeps for `duration`, waking early when interrupted; returns whether it was.
+    pub(crate) fn sleep(&self, duration: Duration) -> bool {
+        let until = Instant::now() + duration;
+        while !self.interrupted() {
+            let now = Instant::now();
+            if now >= until {
+                return false;
+            }
+            sleep(POLL_SLICE.min(until - now));
+        }
+        true
+    }
+
+    #[cfg(test)]
+    fn interrupt(&self) {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for InterruptWatch {
+    fn drop(&mut self) {
+        WATCHING.store(false, Ordering::SeqCst);
+        INTERRUPTED.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(unix)]
+fn install_handler() {
+    use log::warn;
+    use signal_hook::consts::SIGINT;
+    use signal_hook::low_level::{emulate_default_handler, register};
+
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        // SAFETY: the handler only touches atomics and `emulate_default_handler`, which are
+        // async-signal-safe.
+        let registered = unsafe {
+            register(SIGINT, || {
+                if WATCHING.load(Ordering::SeqCst) {
+                    INTERRUPTED.store(true, Ordering::SeqCst);
+                } else {
+                    let _ = emulate_default_handler(SIGINT);
+                }
+            })
+        };
+        if let Err(err) = registered {
+            warn!("Unable to catch Ctrl-C: {err}");
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn install_handler() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn an_interrupt_wakes_the_sleep_and_is_cleared_when_the_watch_ends() {
+        let watch = InterruptWatch::start();
+        assert!(!watch.interrupted());
+        assert!(!watch.sleep(Duration::from_millis(1)));
+
+        watch.interrupt();
+        let started = Instant::now();
+        assert!(watch.sleep(Duration::from_secs(60)));
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        drop(watch);
+        assert!(!WATCHING.load(Ordering::SeqCst));
+        assert!(!INTERRUPTED.load(Ordering::SeqCst));
+    }
+}
@@ -1,5 +1,27 @@
+use jsonschema::validator_for;
 use serde_json::Value;
 
+use crate::domain::SchemaViolation;
+use crate::errors::AppError;
+
+/// Validates `instance` against `schema`, returning every violation with its JSON pointer.
+pub(crate) fn schema_violations(
+    schema: &Value,
+    instance: &Value,
+) -> Result<Vec<SchemaViolation>, AppError> {
+    let validator = validator_for(schema)
+        .map_err(|error| AppError::ParseError(format!("Invalid JSON schema: {error}")))?;
+    let mut violations = validator
+        .iter_errors(instance)
+        .map(|error| SchemaViolation {
+            pointer: error.instance_path().to_string(),
+            message: error.to_string(),
+        })
+        .collect::<Vec<_>>();
+    violations.sort_by(|a, b| a.pointer.cmp(&b.pointer));
+    Ok(violations)
+}
+
 pub(crate) fn schema_paths(schema: &Value, include_array_items: bool) -> Vec<String> {
     let mut paths = Vec::new();
     collect_schema_paths(schema, "", include_array_items, &mut paths);
@@ -8,6 +30,26 @@ pub(crate) fn schema_paths(schema: &Value, include_array_items: bool) -> Vec<Str
     paths
 }
 
+/// The JSON types `schema` declares for the property at `path`, empty when it does not
+/// describe that property or its type.
+pub(crate) fn schema_property_types<'a>(schema: &'a Value, path: &[String]) -> Vec<&'a str> {
+    let mut current = schema;
+    for key in path {
+        let Some(property) = current
+            .get("properties")
+            .and_then(|properties| properties.get(key))
+        else {
+            return Vec::new();
+        };
+        current = property;
+    }
+    match current.get("type") {
+        Some(Value::String(kind)) => vec![kind.as_str()],
+        Some(Value::Array(kinds)) => kinds.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
 pub(crate) fn schema_json_pointers(schema: &Value) -> Vec<String> {
     let mut pointers = Vec::new();
     collect_schema_json_pointers(schema, "", &mut pointers);
@@ -66,9 +108,34 @@ fn collect_schema_paths(
 
 #[cfg(test)]
 mod tests {
-    use super::{schema_json_pointers, schema_paths};
+    use super::{schema_json_pointers, schema_paths, schema_violations};
     use serde_json::json;
 
+    #[test]
+    fn schema_violations_report_json_pointers() {
+        let schema = json!({
+            "type": "object",
+            "required": ["hostname"],
+            "properties": {
+                "hostname": {"type": "string"},
+                "ports": {"type": "array", "items": {"type": "integer"}}
+            }
+        });
+
+        let violations = schema_violations(&schema, &json!({"ports": [22, "http"]}))
+            .expect("schema should compile");
+
+        let pointers = violations
+            .iter()
+            .map(|violation| violation.pointer.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(pointers, vec!["", "/ports/1"]);
+        assert!(violations[0].message.contains("hostname"));
+        assert!(schema_violations(&schema, &json!({"hostname": "web1"}))
+            .expect("schema should compile")
+            .is_empty());
+    }
+
     #[test]
     fn schema_paths_can_include_array_item_paths() {
         let schema = json!({
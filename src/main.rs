@@ -1,31 +1,46 @@
+// User-visible text goes through the output buffer so pipes, redirection, and JSON mode see it.
+#![deny(clippy::print_stdout, clippy::print_stderr)]
+
 use std::env::args;
+use std::io::{stdin, IsTerminal};
 use std::process::exit;
 use std::sync::Arc;
 use std::time::Duration;
 
-use app::{init_logging, load_app_config, login, AppRuntime, SharedSession};
+use app::{
+    init_logging, load_app_config, login, offline_login, relogin, startup_warnings, AppRuntime,
+    SharedSession,
+};
 use catalog::{CommandCatalog, CommandOutcome};
-use cli::{build_cli, execution_mode, split_startup_args, StartupMode};
+use clap::ArgMatches;
+use cli::{
+    build_cli, execution_mode, get_cli_config_path, piped_startup_mode, split_startup_args,
+    verbosity_filter, StartupMode, STDIN_SCRIPT,
+};
 use commands::build_command_catalog;
 use dispatch::{
     apply_output_state, apply_scope_action, can_execute_offline, execute_line,
     execute_offline_line, render_error,
 };
 use errors::AppError;
-use output::{print_rendered, OutputSnapshot};
-use redirection::write_output;
+use files::{get_system_config_path, get_user_config_path};
+use output::{set_quiet, OutputSnapshot};
+use redirection::{print_output, write_output};
 use repl::run;
 use services::AppServices;
+use setup::{run_setup_wizard, should_offer_setup};
 use tokio::fs::read_to_string;
 use tokio::runtime::Handle;
 use tokio::task::spawn_blocking;
 
 mod app;
+mod audit_log;
 mod autocomplete;
 mod background;
 mod build_info;
 mod catalog;
 mod cli;
+mod command_defaults;
 mod command_line;
 mod commands;
 mod config;
@@ -35,25 +50,47 @@ mod domain;
 mod errors;
 mod files;
 mod formatting;
+mod forms;
+mod hooks;
+mod interrupt;
 mod json_schema;
 mod list_query;
+mod masking;
 mod models;
+mod offline;
 mod output;
 mod redirection;
 mod repl;
+mod safety;
+mod secrets;
 mod services;
+mod setup;
 mod suggestions;
 mod terminal;
 mod theme;
 mod tokenizer;
+mod transaction;
+mod verify;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> Result<(), AppError> {
     let startup_args = split_startup_args(args());
     let matches = build_cli().get_matches_from(startup_args.clap_args);
+    set_quiet(matches.get_flag("quiet"));
+    let mode = piped_startup_mode(
+        execution_mode(&matches, startup_args.mode),
+        stdin().is_terminal(),
+    );
+    let keep_going = matches.get_flag("keep_going");
+    if matches!(mode, StartupMode::Repl) {
+        offer_first_run_setup(&matches).await?;
+    }
     let config = load_app_config(&matches)?;
     let catalog = Arc::new(build_command_catalog());
-    let mode = execution_mode(&matches, startup_args.mode);
+    let script = match &mode {
+        StartupMode::Script(filename) => read_script(filename).await?,
+        StartupMode::Repl | StartupMode::Command(_) => String::new(),
+    };
 
     match &mode {
         StartupMode::Command(command) if can_execute_offline(command) => {
@@ -67,9 +104,9 @@ async fn main() -> Result<(), AppError> {
             }
             return Ok(());
         }
-        StartupMode::Script(filename) if can_execute_script_offline(filename).await? => {
+        StartupMode::Script(_) if can_execute_script_offline(&script) => {
             let session = SharedSession::new();
-            if !execute_offline_script(catalog.clone(), &session, filename).await? {
+            if !execute_offline_script(catalog.clone(), &session, &script, keep_going).await? {
                 exit(1);
             }
             return Ok(());
@@ -77,14 +114,30 @@ async fn main() -> Result<(), AppError> {
         StartupMode::Repl | StartupMode::Command(_) | StartupMode::Script(_) => {}
     }
 
-    init_logging()?;
-    let client = login(config.clone()).await?;
+    init_logging(verbosity_filter(&matches))?;
+    let warnings = startup_warnings(&config);
+    if !warnings.is_empty() {
+        render_snapshot(OutputSnapshot {
+            warnings,
+            ..Default::default()
+        });
+    }
+    let offline = matches.get_flag("offline");
+    let client = if offline {
+        offline_login(config.clone()).await?
+    } else {
+        login(config.clone()).await?
+    };
 
     let services = Arc::new(AppServices::new(
         client,
         Handle::current(),
         Duration::from_secs(config.background.poll_interval_seconds),
     ));
+    services.gateway().set_relogin(relogin(config.clone()));
+    if offline {
+        services.gateway().set_offline(true)?;
+    }
     let runtime = Arc::new(AppRuntime::new(config, services, catalog));
     let session = SharedSession::new();
 
@@ -96,8 +149,8 @@ async fn main() -> Result<(), AppError> {
         return Ok(());
     }
 
-    if let StartupMode::Script(filename) = mode {
-        if !execute_script(runtime.clone(), &session, &filename).await? {
+    if let StartupMode::Script(_) = mode {
+        if !execute_script(runtime.clone(), &session, &script, keep_going).await {
             exit(1);
         }
         return Ok(());
@@ -106,6 +159,20 @@ async fn main() -> Result<(), AppError> {
     run(runtime, session).await
 }
 
+/// Runs the setup wizard when the REPL starts without any configuration to use.
+async fn offer_first_run_setup(matches: &ArgMatches) -> Result<(), AppError> {
+    let user_config = get_user_config_path();
+    let server_given =
+        get_cli_config_path(matches).is_some() || matches.value_source("hostname").is_some();
+    if !should_offer_setup(&[&get_system_config_path(), &user_config], server_given) {
+        return Ok(());
+    }
+    spawn_blocking(move || run_setup_wizard(&user_config))
+        .await
+        .map_err(|err| AppError::CommandExecutionError(err.to_string()))??;
+    Ok(())
+}
+
 fn sessionless() -> SharedSession {
     SharedSession::new()
 }
@@ -123,46 +190,110 @@ fn render_dispatch_result(
     }
 }
 
+/// Reads a script file, or standard input for [`STDIN_SCRIPT`].
+async fn read_script(filename: &str) -> Result<String, AppError> {
+    if filename == STDIN_SCRIPT {
+        return spawn_blocking(|| std::io::read_to_string(stdin()))
+            .await
+            .map_err(|err| AppError::CommandExecutionError(err.to_string()))?
+            .map_err(AppError::from);
+    }
+    Ok(read_to_string(filename).await?)
+}
+
+/// Script commands with their line numbers; blank lines and `#` comments are skipped.
+fn script_commands(script: &str) -> impl Iterator<Item = (usize, &str)> {
+    script
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| {
+            let line = line.trim();
+            !line.is_empty() && !line.starts_with('#')
+        })
+}
+
+/// Failed script lines; without `--keep-going` a script stops at the first one.
+struct ScriptRun {
+    keep_going: bool,
+    failed_lines: Vec<usize>,
+}
+
+impl ScriptRun {
+    fn new(keep_going: bool) -> Self {
+        Self {
+            keep_going,
+            failed_lines: Vec::new(),
+        }
+    }
+
+    /// Records whether the command on `line` succeeded and returns whether to go on.
+    fn record(&mut self, line: usize, succeeded: bool) -> bool {
+        if !succeeded {
+            self.failed_lines.push(line);
+        }
+        succeeded || self.keep_going
+    }
+
+    /// Reports the failed lines of a `--keep-going` run and returns whether all succeeded.
+    fn finish(self) -> bool {
+        if self.keep_going && !self.failed_lines.is_empty() {
+            let lines = self
+                .failed_lines
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            render_snapshot(OutputSnapshot {
+                warnings: vec![format!(
+                    "{} script command(s) failed, on line(s) {}",
+                    lines.len(),
+                    lines.join(", ")
+                )],
+                ..Default::default()
+            });
+        }
+        self.failed_lines.is_empty()
+    }
+}
+
 async fn execute_script(
     runtime: Arc<AppRuntime>,
     session: &SharedSession,
-    filename: &str,
-) -> Result<bool, AppError> {
-    let content = read_to_string(filename).await?;
-    for line in content.lines() {
+    script: &str,
+    keep_going: bool,
+) -> bool {
+    let mut run = ScriptRun::new(keep_going);
+    for (number, line) in script_commands(script) {
         let outcome = execute_line(runtime.clone(), session, line).await;
-        if !render_dispatch_result(session, outcome) {
-            return Ok(false);
+        if !run.record(number, render_dispatch_result(session, outcome)) {
+            break;
         }
     }
-    Ok(true)
+    run.finish()
 }
 
-async fn can_execute_script_offline(filename: &str) -> Result<bool, AppError> {
-    let content = read_to_string(filename).await?;
-    Ok(content
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .all(can_execute_offline))
+fn can_execute_script_offline(script: &str) -> bool {
+    script_commands(script).all(|(_, line)| can_execute_offline(line))
 }
 
 async fn execute_offline_script(
     catalog: Arc<CommandCatalog>,
     session: &SharedSession,
-    filename: &str,
+    script: &str,
+    keep_going: bool,
 ) -> Result<bool, AppError> {
-    let content = read_to_string(filename).await?;
-    for line in content.lines() {
+    let mut run = ScriptRun::new(keep_going);
+    for (number, line) in script_commands(script) {
         let catalog = catalog.clone();
         let line = line.to_string();
         let outcome = spawn_blocking(move || execute_offline_line(catalog.as_ref(), &line))
             .await
             .map_err(|err| AppError::CommandExecutionError(err.to_string()))?;
-        if !render_dispatch_result(session, outcome) {
-            return Ok(false);
+        if !run.record(number, render_dispatch_result(session, outcome)) {
+            break;
         }
     }
-    Ok(true)
+    Ok(run.finish())
 }
 
 fn render_outcome(session: &SharedSession, outcome: CommandOutcome) -> bool {
@@ -185,6 +316,6 @@ fn render_outcome(session: &SharedSession, outcome: CommandOutcome) -> bool {
 
 fn render_snapshot(snapshot: OutputSnapshot) {
     if !snapshot.is_empty() {
-        let _ = print_rendered(&snapshot.render());
+        let _ = print_output(&snapshot);
     }
 }
@@ -0,0 +1,174 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::Serialize;
+use serde_json::{to_value, Value};
+
+use crate::config::get_config;
+
+/// Shown in place of a masked value.
+pub const MASKED_VALUE: &str = "***";
+
+static REVEAL: AtomicBool = AtomicBool::new(false);
+
+/// Shows values hidden by `output.mask_keys` in the output of the command being run, as
+/// requested with `--reveal`. Set before each command.
+pub fn set_reveal(reveal: bool) {
+    REVEAL.store(reveal, Ordering::Relaxed);
+}
+
+/// The keys to hide from displayed object data: `output.mask_keys`, or none under `--reveal`.
+pub fn display_mask_keys() -> Vec<String> {
+    if REVEAL.load(Ordering::Relaxed) {
+        Vec::new()
+    } else {
+        get_config().output.mask_keys.clone()
+    }
+}
+
+/// Masks object data about to be displayed, unless the command was given `--reveal`.
+pub fn mask_for_display(data: &mut Value) {
+    mask_data(data, &display_mask_keys());
+}
+
+/// Masks the object data in rendered output: every `data` member of a JSON object, at any
+/// depth, is treated as object data with [`mask_for_display`].
+///
+/// Object records, search hits, and audit snapshots all carry their object's data under
+/// `data`, so this covers them wherever they are nested.
+pub fn mask_object_output(value: &mut Value) {
+    let mask_keys = display_mask_keys();
+    if !mask_keys.is_empty() {
+        mask_data_members(value, &mask_keys);
+    }
+}
+
+/// `value` as JSON with its object data masked by [`mask_object_output`], for output that
+/// is printed as a JSON document rather than through the output buffer's semantic result.
+pub fn masked_json<T: Serialize>(value: &T) -> Result<Value, serde_json::Error> {
+    let mut value = to_value(value)?;
+    mask_object_output(&mut value);
+    Ok(value)
+}
+
+fn mask_data_members(value: &mut Value, mask_keys: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if key == "data" {
+                    mask_data(child, mask_keys);
+                } else {
+                    mask_data_members(child, mask_keys);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                mask_data_members(item, mask_keys);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replaces values in object data whose key matches one of `mask_keys` with [`MASKED_VALUE`].
+///
+/// An entry such as `password` matches that key at any depth; an entry with dots, such as
+/// `db.password`, matches that path from the top of the data. Keys compare
+/// case-insensitively.
+pub fn mask_data(data: &mut Value, mask_keys: &[String]) {
+    if mask_keys.is_empty() {
+        return;
+    }
+    let mask_keys = mask_keys
+        .iter()
+        .map(|key| key.to_lowercase())
+        .collect::<Vec<_>>();
+    mask_at(data, &mut Vec::new(), &mask_keys);
+}
+
+fn mask_at(value: &mut Value, path: &mut Vec<String>, mask_keys: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                path.push(key.to_lowercase());
+                if is_masked(path, mask_keys) {
+                    *child = Value::String(MASKED_VALUE.to_string());
+                } else {
+                    mask_at(child, path, mask_keys);
+                }
+                path.pop();
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                mask_at(item, path, mask_keys);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_masked(path: &[String], mask_keys: &[String]) -> bool {
+    mask_keys.iter().any(|mask| {
+        if mask.contains('.') {
+            path.join(".") == *mask
+        } else {
+            path.last() == Some(mask)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn keys_match_at_any_depth_and_paths_from_the_top() {
+        let mut data = json!({
+            "Password": "hunter2",
+            "db": {"password": {"nested": true}, "user": "app"},
+            "accounts": [{"token": "abc"}, {"name": "x"}],
+            "api": {"secret": "s", "ui": {"secret": "kept"}}
+        });
+        let keys = ["password", "TOKEN", "api.secret"].map(str::to_string);
+
+        mask_data(&mut data, &keys);
+
+        assert_eq!(
+            data,
+            json!({
+                "Password": "***",
+                "db": {"password": "***", "user": "app"},
+                "accounts": [{"token": "***"}, {"name": "x"}],
+                "api": {"secret": "***", "ui": {"secret": "kept"}}
+            })
+        );
+    }
+
+    #[test]
+    fn output_masks_data_members_at_any_depth() {
+        let mut output = json!({
+            "name": "web-01",
+            "password": "not object data",
+            "data": {"password": "hunter2", "db": {"password": "x"}},
+            "diff": {"data": {"password": {"before": "a", "after": "b"}}},
+            "results": [{"data": {"token": "abc", "os": "linux"}}]
+        });
+        let keys = ["password", "token", "db.password"].map(str::to_string);
+
+        mask_data_members(&mut output, &keys);
+
+        assert_eq!(
+            output,
+            json!({
+                "name": "web-01",
+                "password": "not object data",
+                "data": {"password": "***", "db": {"password": "***"}},
+                "diff": {"data": {"password": "***"}},
+                "results": [{"data": {"token": "***", "os": "linux"}}]
+            })
+        );
+    }
+}
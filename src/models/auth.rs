@@ -1,19 +1,118 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::config::ServerConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenEntry {
     pub hostname: String,
     #[serde(default)]
     pub identity_scope: Option<String>,
     pub username: String,
     pub token: String,
+    /// When the token was first cached. Missing for entries written by older releases.
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// When the token last authenticated successfully against its server.
+    #[serde(default)]
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl TokenEntry {
+    pub fn new(
+        hostname: String,
+        identity_scope: Option<String>,
+        username: String,
+        token: String,
+    ) -> Self {
+        Self {
+            hostname,
+            identity_scope,
+            username,
+            token,
+            created_at: None,
+            last_used_at: None,
+        }
+    }
+
+    pub fn matches(&self, hostname: &str, identity_scope: Option<&str>, username: &str) -> bool {
+        self.hostname == hostname
+            && self.identity_scope.as_deref() == identity_scope
+            && self.username == username
+    }
+
+    /// Last time the server was contacted with this token, falling back to its creation time.
+    pub fn last_contact(&self) -> Option<DateTime<Utc>> {
+        self.last_used_at.or(self.created_at)
+    }
+}
+
+/// One login in the local token cache, as shown by `token list`.
+///
+/// The configured login is always listed, with `cached` set to false when it has
+/// no token yet, so missing credentials show up before a login is attempted.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CachedTokenSummary {
+    pub server: String,
+    pub identity_scope: Option<String>,
+    pub username: String,
+    pub cached: bool,
+    pub current: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub age_seconds: Option<i64>,
+    pub idle_seconds: Option<i64>,
+}
+
+impl CachedTokenSummary {
+    /// Summarises `entries`, marking the one for the configured `server` login as current.
+    pub fn from_entries(
+        entries: &[TokenEntry],
+        server: &ServerConfig,
+        now: DateTime<Utc>,
+    ) -> Vec<Self> {
+        let identity_scope = server.identity_scope.as_deref();
+        let mut summaries = entries
+            .iter()
+            .map(|entry| Self {
+                server: entry.hostname.clone(),
+                identity_scope: entry.identity_scope.clone(),
+                username: entry.username.clone(),
+                cached: true,
+                current: entry.matches(&server.hostname, identity_scope, &server.username),
+                created_at: entry.created_at,
+                last_used_at: entry.last_used_at,
+                age_seconds: entry.created_at.map(|at| (now - at).num_seconds()),
+                idle_seconds: entry.last_contact().map(|at| (now - at).num_seconds()),
+            })
+            .collect::<Vec<_>>();
+        if !summaries.iter().any(|summary| summary.current) {
+            summaries.push(Self {
+                server: server.hostname.clone(),
+                identity_scope: server.identity_scope.clone(),
+                username: server.username.clone(),
+                cached: false,
+                current: true,
+                created_at: None,
+                last_used_at: None,
+                age_seconds: None,
+                idle_seconds: None,
+            });
+        }
+        summaries.sort_by(|a, b| {
+            (b.current, &a.server, &a.username).cmp(&(a.current, &b.server, &b.username))
+        });
+        summaries
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::{TimeDelta, TimeZone, Utc};
     use serde_json::from_str;
 
-    use super::TokenEntry;
+    use super::{CachedTokenSummary, TokenEntry};
+    use crate::config::AppConfig;
 
     #[test]
     fn legacy_token_entries_default_to_the_local_identity_scope() {
@@ -23,4 +122,33 @@ mod tests {
 
         assert_eq!(entry.identity_scope, None);
     }
+
+    #[test]
+    fn token_summaries_mark_the_configured_login_and_report_missing_tokens() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let mut server = AppConfig::default().server;
+        server.hostname = "hub.example".to_string();
+        server.username = "admin".to_string();
+        let mut entry = TokenEntry::new(
+            "other.example".to_string(),
+            None,
+            "alice".to_string(),
+            "secret".to_string(),
+        );
+        entry.created_at = Some(now - TimeDelta::days(3));
+        entry.last_used_at = Some(now - TimeDelta::hours(2));
+
+        let summaries = CachedTokenSummary::from_entries(&[entry.clone()], &server, now);
+        assert_eq!(summaries.len(), 2);
+        assert!(summaries[0].current && !summaries[0].cached);
+        assert_eq!(summaries[0].server, "hub.example");
+        assert_eq!(summaries[1].age_seconds, Some(3 * 86_400));
+        assert_eq!(summaries[1].idle_seconds, Some(2 * 3_600));
+
+        entry.hostname = "hub.example".to_string();
+        entry.username = "admin".to_string();
+        let summaries = CachedTokenSummary::from_entries(&[entry], &server, now);
+        assert_eq!(summaries.len(), 1);
+        assert!(summaries[0].current && summaries[0].cached);
+    }
 }
@@ -0,0 +1,139 @@
+use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
+use std::str::FromStr;
+use std::time::Duration;
+
+use config::Value;
+use serde::{Deserialize, Serialize};
+
+/// Request budget for bulk operations, written as `<count>/<unit>` (`20/s`, `600/m`).
+///
+/// A count of zero disables throttling.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub struct RateLimit {
+    count: u32,
+    unit: RateUnit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RateUnit {
+    Second,
+    Minute,
+    Hour,
+}
+
+impl RateLimit {
+    pub const fn per_second(count: u32) -> Self {
+        Self {
+            count,
+            unit: RateUnit::Second,
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Minimum spacing between two requests, or `None` when unlimited.
+    pub fn interval(&self) -> Option<Duration> {
+        if self.is_unlimited() {
+            return None;
+        }
+        Some(self.unit.duration() / self.count)
+    }
+}
+
+impl RateUnit {
+    fn duration(self) -> Duration {
+        match self {
+            RateUnit::Second => Duration::from_secs(1),
+            RateUnit::Minute => Duration::from_secs(60),
+            RateUnit::Hour => Duration::from_secs(3600),
+        }
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            RateUnit::Second => "s",
+            RateUnit::Minute => "m",
+            RateUnit::Hour => "h",
+        }
+    }
+}
+
+impl FromStr for RateLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid rate limit: {s}. Use <count>/s, <count>/m or <count>/h.");
+        let trimmed = s.trim();
+        let (count, unit) = trimmed.split_once('/').unwrap_or((trimmed, "s"));
+        let count = count.trim().parse::<u32>().map_err(|_| invalid())?;
+        let unit = match unit.trim().to_lowercase().as_str() {
+            "s" | "sec" | "second" => RateUnit::Second,
+            "m" | "min" | "minute" => RateUnit::Minute,
+            "h" | "hour" => RateUnit::Hour,
+            _ => return Err(invalid()),
+        };
+        Ok(Self { count, unit })
+    }
+}
+
+impl TryFrom<String> for RateLimit {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<RateLimit> for String {
+    fn from(value: RateLimit) -> Self {
+        value.to_string()
+    }
+}
+
+impl From<RateLimit> for Value {
+    fn from(val: RateLimit) -> Self {
+        Value::new(None, val.to_string())
+    }
+}
+
+impl FmtDisplay for RateLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}/{}", self.count, self.unit.suffix())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rate_limits_with_units() {
+        let limit: RateLimit = "20/s".parse().expect("rate limit should parse");
+        assert_eq!(limit, RateLimit::per_second(20));
+        assert_eq!(limit.interval(), Some(Duration::from_millis(50)));
+
+        let limit: RateLimit = "120/m".parse().expect("rate limit should parse");
+        assert_eq!(limit.to_string(), "120/m");
+        assert_eq!(limit.interval(), Some(Duration::from_millis(500)));
+
+        let limit: RateLimit = "5".parse().expect("bare count should default to seconds");
+        assert_eq!(limit, RateLimit::per_second(5));
+    }
+
+    #[test]
+    fn zero_rate_limit_disables_throttling() {
+        let limit: RateLimit = "0/s".parse().expect("rate limit should parse");
+        assert!(limit.is_unlimited());
+        assert_eq!(limit.interval(), None);
+    }
+
+    #[test]
+    fn rejects_malformed_rate_limits() {
+        assert!("fast".parse::<RateLimit>().is_err());
+        assert!("20/week".parse::<RateLimit>().is_err());
+        assert!("-1/s".parse::<RateLimit>().is_err());
+    }
+}
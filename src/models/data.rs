@@ -0,0 +1,35 @@
+use std::str::FromStr;
+
+use config::Value;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+/// What happens when object data passes `data.max_size` or `data.max_depth`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Display, Default)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum DataLimitAction {
+    Warn,
+    #[default]
+    Refuse,
+}
+
+impl FromStr for DataLimitAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "warn" => Ok(DataLimitAction::Warn),
+            "refuse" => Ok(DataLimitAction::Refuse),
+            _ => Err(format!(
+                "Invalid data limit action: {s}. Use warn or refuse."
+            )),
+        }
+    }
+}
+
+impl From<DataLimitAction> for Value {
+    fn from(val: DataLimitAction) -> Self {
+        Value::new(None, val.to_string())
+    }
+}
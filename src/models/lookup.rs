@@ -0,0 +1,40 @@
+use std::str::FromStr;
+
+use config::Value;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+/// How a name given on the command line is matched against names on the server.
+///
+/// An exact match always wins, so the looser policies only change what happens
+/// when the name does not exist as written.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Display, Default)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum NameMatch {
+    #[default]
+    Exact,
+    Ci,
+    Contains,
+}
+
+impl FromStr for NameMatch {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "exact" => Ok(NameMatch::Exact),
+            "ci" => Ok(NameMatch::Ci),
+            "contains" => Ok(NameMatch::Contains),
+            _ => Err(format!(
+                "Invalid name match policy: {s}. Use exact, ci, or contains."
+            )),
+        }
+    }
+}
+
+impl From<NameMatch> for Value {
+    fn from(val: NameMatch) -> Self {
+        Value::new(None, val.to_string())
+    }
+}
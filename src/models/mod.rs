@@ -1,9 +1,15 @@
 pub mod auth;
+pub mod bulk;
+pub mod data;
+pub mod lookup;
 pub mod output;
 pub mod responses;
 
-pub use auth::TokenEntry;
+pub use auth::{CachedTokenSummary, TokenEntry};
+pub use bulk::RateLimit;
+pub use data::DataLimitAction;
+pub use lookup::NameMatch;
 pub use output::{
-    EmptyResult, ObjectListDataColumns, OutputColor, OutputFormat, Protocol, TableBands,
-    TableStyle, TableWidth, TableWrap,
+    EmptyResult, ObjectListDataColumns, OutputColor, OutputFormat, Protocol, RenderFormat,
+    SortCollation, TableBands, TableStyle, TableWidth, TableWrap,
 };
@@ -2,6 +2,7 @@ use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
 use std::str::FromStr;
 
 use config::Value;
+use hubuum_filter::Collation;
 use serde::de::Error as DeserializeError;
 use serde::{Deserialize, Deserializer, Serialize};
 use strum::{Display, EnumString};
@@ -79,6 +80,42 @@ impl From<OutputColor> for Value {
     }
 }
 
+/// How command output is rendered: as tables and text, or as a data format.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Display, Default)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum RenderFormat {
+    #[default]
+    Text,
+    Json,
+    Jsonl,
+    Csv,
+    Tsv,
+}
+
+impl FromStr for RenderFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(RenderFormat::Text),
+            "json" => Ok(RenderFormat::Json),
+            "jsonl" => Ok(RenderFormat::Jsonl),
+            "csv" => Ok(RenderFormat::Csv),
+            "tsv" => Ok(RenderFormat::Tsv),
+            _ => Err(format!(
+                "Unknown output format: {s}. Use text, json, jsonl, csv, or tsv."
+            )),
+        }
+    }
+}
+
+impl From<RenderFormat> for Value {
+    fn from(val: RenderFormat) -> Self {
+        Value::new(None, val.to_string())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Display, Default)]
 #[strum(serialize_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
@@ -245,6 +282,48 @@ impl From<EmptyResult> for Value {
     }
 }
 
+/// How listings order text: byte order, numbers by value, or dictionary order.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Display, Default)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum SortCollation {
+    #[default]
+    Ascii,
+    Natural,
+    Locale,
+}
+
+impl FromStr for SortCollation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ascii" => Ok(SortCollation::Ascii),
+            "natural" => Ok(SortCollation::Natural),
+            "locale" => Ok(SortCollation::Locale),
+            _ => Err(format!(
+                "Invalid sort collation: {s}. Use ascii, natural, or locale."
+            )),
+        }
+    }
+}
+
+impl From<SortCollation> for Value {
+    fn from(val: SortCollation) -> Self {
+        Value::new(None, val.to_string())
+    }
+}
+
+impl From<SortCollation> for Collation {
+    fn from(val: SortCollation) -> Self {
+        match val {
+            SortCollation::Ascii => Collation::Ascii,
+            SortCollation::Natural => Collation::Natural,
+            SortCollation::Locale => Collation::Locale,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Display, Default)]
 #[strum(serialize_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
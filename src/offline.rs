@@ -0,0 +1,192 @@
+//! Offline mode, entered with `--offline` or the `offline` command.
+//!
+//! While offline no request reaches the server. Completion offers only names it already
+//! knows, listings are answered from the response cache however old they are, and
+//! commands that change the server are refused. Listings and completion names fetched
+//! online are also saved per login in `offline-cache.json`, so a session started with
+//! `--offline` can show them too.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use hubuum_client::blocking::Client as BlockingClient;
+use hubuum_client::{
+    ApiError, Authenticated, BlockingTransport, RequestPlan, RetryPolicy, Token, TransportResponse,
+};
+use log::debug;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{from_value, to_value};
+
+use crate::config::get_config;
+use crate::errors::AppError;
+use crate::files::{OfflineCache, SavedListing};
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Whether requests are kept from the server.
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Switched by [`crate::services::HubuumGateway::set_offline`], which first makes sure
+/// it has a client that sends nothing.
+pub(crate) fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// How long cached responses and entities are reused: `seconds`, or for as long as
+/// they are kept while offline.
+pub(crate) fn cache_ttl(seconds: u64) -> Duration {
+    if is_offline() {
+        Duration::MAX
+    } else {
+        Duration::from_secs(seconds)
+    }
+}
+
+/// A client for the server of `online` whose requests all fail without being sent.
+pub(crate) fn offline_client(
+    online: &BlockingClient<Authenticated>,
+) -> Result<BlockingClient<Authenticated>, AppError> {
+    let client = BlockingClient::builder(online.base_url().clone())
+        .with_http_client(online.http_client().clone())
+        .with_transport(Arc::new(OfflineTransport))
+        .retry_policy(RetryPolicy::disabled())
+        .build()?;
+    Ok(client.authenticate(Token::new(String::new())))
+}
+
+/// Shown for a request that offline mode kept from the server.
+pub(crate) fn offline_error_message(context: Option<&str>) -> String {
+    let needed = match context {
+        Some(context) => format!("{context} needs the server"),
+        None => "this needs the server".to_string(),
+    };
+    format!("Offline: {needed} and is not in the cache. Run 'offline off' to reconnect.")
+}
+
+/// Fails while offline, for commands that reach the server without the session's client.
+pub(crate) fn require_online(context: &str) -> Result<(), AppError> {
+    if is_offline() {
+        return Err(AppError::CommandExecutionError(format!(
+            "Offline: {context} needs the server. Run 'offline off' to reconnect."
+        )));
+    }
+    Ok(())
+}
+
+/// Why a command that changes the server was not run.
+pub(crate) fn refused_change_error(command: &str) -> AppError {
+    AppError::CommandExecutionError(format!(
+        "Offline: '{command}' changes the server and was not run. Run 'offline off' first."
+    ))
+}
+
+/// Warns that a listing came from the cache rather than the server.
+pub(crate) fn cached_listing_warning(age: Duration) -> String {
+    let seconds = age.as_secs();
+    let age = match seconds {
+        0..=59 => format!("{seconds}s"),
+        60..=3599 => format!("{}m", seconds / 60),
+        _ => format!("{}h", seconds / 3600),
+    };
+    format!("Offline: showing the listing cached {age} ago; it may be out of date")
+}
+
+/// The login whose saved entries offline mode uses.
+fn login_key() -> String {
+    let server = &get_config().server;
+    format!("{}@{}", server.username, server.server_url())
+}
+
+/// Saving is best effort: a failure only leaves offline mode with less to show.
+fn update_saved(change: impl FnOnce(&mut OfflineCache)) {
+    if let Err(error) = OfflineCache::update(change) {
+        debug!("Could not update the offline cache: {error}");
+    }
+}
+
+/// Saves a listing fetched from the server, within `budget` bytes per login.
+pub(crate) fn save_listing<T: Serialize>(key: &str, value: &T, budget: usize) {
+    let Ok(value) = to_value(value) else {
+        return;
+    };
+    let listing = SavedListing {
+        fetched_at: Utc::now(),
+        value,
+    };
+    let login = login_key();
+    update_saved(|cache| cache.login_mut(&login).save_listing(key, listing, budget));
+}
+
+/// The listing saved for `key`, with its age.
+pub(crate) fn saved_listing<T: DeserializeOwned>(key: &str) -> Option<(T, Duration)> {
+    let cache = OfflineCache::load().ok()?;
+    let listing = cache.login(&login_key())?.listings.get(key)?.clone();
+    let age = (Utc::now() - listing.fetched_at)
+        .to_std()
+        .unwrap_or_default();
+    from_value(listing.value).ok().map(|value| (value, age))
+}
+
+/// Drops the saved listings after a change on the server made them out of date.
+pub(crate) fn forget_saved_listings() {
+    let login = login_key();
+    update_saved(|cache| cache.login_mut(&login).listings.clear());
+}
+
+/// Drops everything saved for the current login.
+pub(crate) fn forget_saved_entries() {
+    let login = login_key();
+    update_saved(|cache| cache.forget_login(&login));
+}
+
+/// Saves the completion names of `source`, such as `classes`.
+pub(crate) fn save_names(source: &str, names: &[String]) {
+    let login = login_key();
+    update_saved(|cache| {
+        cache
+            .login_mut(&login)
+            .names
+            .insert(source.to_string(), names.to_vec());
+    });
+}
+
+/// The completion names saved for `source`.
+pub(crate) fn saved_names(source: &str) -> Vec<String> {
+    OfflineCache::load()
+        .ok()
+        .and_then(|cache| cache.login(&login_key())?.names.get(source).cloned())
+        .unwrap_or_default()
+}
+
+#[derive(Debug)]
+struct OfflineTransport;
+
+impl BlockingTransport for OfflineTransport {
+    fn execute(&self, request: RequestPlan) -> Result<TransportResponse, ApiError> {
+        Err(ApiError::Transport(format!(
+            "offline, {} {} was not sent",
+            request.method,
+            request.url.path()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_listings_name_their_age() {
+        assert_eq!(
+            cached_listing_warning(Duration::from_secs(42)),
+            "Offline: showing the listing cached 42s ago; it may be out of date"
+        );
+        assert!(cached_listing_warning(Duration::from_secs(600)).contains("cached 10m ago"));
+        assert!(cached_listing_warning(Duration::from_secs(7300)).contains("cached 2h ago"));
+    }
+}
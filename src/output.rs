@@ -8,22 +8,34 @@ use comfy_table::{
     presets::{ASCII_FULL, ASCII_MARKDOWN, NOTHING, UTF8_FULL, UTF8_HORIZONTAL_ONLY},
     ColumnConstraint, ContentArrangement, Table, Width,
 };
-use hubuum_filter::{apply_pipeline, group_summary_rows, OutputEnvelope, OutputShape, PipeStage};
+use csv::{QuoteStyle, Terminator, WriterBuilder};
+use hubuum_filter::{
+    apply_pipeline_collated, group_summary_rows, OutputEnvelope, OutputShape, PipeStage,
+};
 use hubuum_theme::{paint as paint_theme, Theme as HubuumTheme};
 use once_cell::sync::Lazy;
 use serde::Serialize;
 use serde_json::{json, to_string, to_string_pretty, to_value, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 
 use log::debug;
 
-use crate::config::get_config;
+use crate::config::{get_config, OutputConfig};
 use crate::errors::AppError;
-use crate::models::{EmptyResult, OutputFormat, TableBands, TableStyle, TableWidth, TableWrap};
-use crate::terminal::terminal_width;
+use crate::masking::mask_object_output;
+pub use crate::models::RenderFormat;
+use crate::models::{EmptyResult, TableBands, TableStyle, TableWidth, TableWrap};
+use crate::terminal::{display_width, pad_to_width, stdout_is_terminal, terminal_width};
 use crate::theme::{color_choice, paint, ThemeRole};
 
 static OUTPUT_BUFFER: Lazy<Mutex<OutputBuffer>> = Lazy::new(|| Mutex::new(OutputBuffer::new()));
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Hides warnings for the rest of the process, as requested with `--quiet`.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
 
 #[derive(Debug)]
 enum OutputEvent {
@@ -39,16 +51,8 @@ pub struct OutputSnapshot {
     pub warnings: Vec<String>,
     pub errors: Vec<String>,
     pub next_page_command: Option<String>,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum RenderFormat {
-    #[default]
-    Text,
-    Json,
-    Jsonl,
-    Csv,
-    Tsv,
+    /// Shown through the pager when printed to a terminal, as `help <command> --long` is.
+    pub paged: bool,
 }
 
 impl OutputSnapshot {
@@ -59,11 +63,13 @@ impl OutputSnapshot {
     pub fn render(&self) -> String {
         let mut rendered = Vec::new();
 
-        rendered.extend(
-            self.warnings
-                .iter()
-                .map(|warning| paint(ThemeRole::Warning, format!("Warning: {warning}"))),
-        );
+        if !QUIET.load(Ordering::Relaxed) {
+            rendered.extend(
+                self.warnings
+                    .iter()
+                    .map(|warning| paint(ThemeRole::Warning, format!("Warning: {warning}"))),
+            );
+        }
         rendered.extend(
             self.errors
                 .iter()
@@ -92,10 +98,12 @@ pub struct OutputBuffer {
     events: Vec<OutputEvent>,
     pipeline: Vec<PipeStage>,
     pipeline_suffix: Option<String>,
+    redirected: bool,
     render_format: RenderFormat,
     warnings: Vec<String>,
     errors: Vec<String>,
     next_page_command: Option<String>,
+    paged: bool,
 }
 
 impl OutputBuffer {
@@ -172,11 +180,14 @@ impl OutputBuffer {
         self.errors.clear();
         self.pipeline.clear();
         self.pipeline_suffix = None;
+        self.redirected = false;
         self.render_format = config_render_format();
         self.next_page_command = None;
+        self.paged = false;
     }
 
     fn snapshot(&self) -> Result<OutputSnapshot, AppError> {
+        let collation = get_config().output.sort_collation.into();
         let mut semantic = Vec::new();
         let has_semantic = self
             .events
@@ -188,7 +199,8 @@ impl OutputBuffer {
                 match event {
                     OutputEvent::Line(line) => rendered.push(line.clone()),
                     OutputEvent::Semantic(envelope) => {
-                        let envelope = apply_pipeline(envelope.clone(), &self.pipeline)?;
+                        let envelope =
+                            apply_pipeline_collated(envelope.clone(), &self.pipeline, collation)?;
                         rendered.extend(render_semantic(&envelope, self.render_format)?);
                         semantic.push(envelope);
                     }
@@ -204,7 +216,7 @@ impl OutputBuffer {
                     OutputEvent::Semantic(_) => None,
                 })
                 .collect();
-            PipeStage::apply_all(&self.pipeline, lines)?
+            PipeStage::apply_all(&self.pipeline, lines, collation)?
         };
 
         Ok(OutputSnapshot {
@@ -214,6 +226,7 @@ impl OutputBuffer {
             warnings: self.warnings.clone(),
             errors: self.errors.clone(),
             next_page_command: self.next_page_command.clone(),
+            paged: self.paged && self.pipeline.is_empty(),
         })
     }
 
@@ -240,6 +253,12 @@ pub fn add_error<T: Display>(message: T) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Asks for the current command's output to be shown through the pager.
+pub fn page_output() -> Result<(), AppError> {
+    OUTPUT_BUFFER.lock().map_err(|_| AppError::LockError)?.paged = true;
+    Ok(())
+}
+
 pub fn append_line<T: Display>(line: T) -> Result<(), AppError> {
     OUTPUT_BUFFER
         .lock()
@@ -248,7 +267,9 @@ pub fn append_line<T: Display>(line: T) -> Result<(), AppError> {
     Ok(())
 }
 
-pub fn set_semantic_output(envelope: OutputEnvelope) -> Result<(), AppError> {
+/// Makes `envelope` the command's result. Object data in it is masked for display.
+pub fn set_semantic_output(mut envelope: OutputEnvelope) -> Result<(), AppError> {
+    mask_object_output(&mut envelope.value);
     OUTPUT_BUFFER
         .lock()
         .map_err(|_| AppError::LockError)?
@@ -289,7 +310,7 @@ pub fn append_key_value<K: Display, V: Display>(
     value: V,
     padding: usize,
 ) -> Result<(), AppError> {
-    let line = format!("{key:<padding$} : {value}");
+    let line = format!("{} : {value}", pad_to_width(&key.to_string(), padding));
     append_line(line)
 }
 
@@ -301,6 +322,20 @@ pub fn reset_output() -> Result<(), AppError> {
     Ok(())
 }
 
+/// Semantic output recorded so far, before any pipeline is applied.
+pub fn semantic_output() -> Result<Vec<OutputEnvelope>, AppError> {
+    Ok(OUTPUT_BUFFER
+        .lock()
+        .map_err(|_| AppError::LockError)?
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            OutputEvent::Semantic(envelope) => Some(envelope.clone()),
+            OutputEvent::Line(_) => None,
+        })
+        .collect())
+}
+
 pub fn take_output() -> Result<OutputSnapshot, AppError> {
     OUTPUT_BUFFER
         .lock()
@@ -338,6 +373,22 @@ pub fn has_pipeline() -> Result<bool, AppError> {
         .has_pipeline())
 }
 
+/// Records whether the command's output goes to a file rather than the terminal.
+pub fn set_redirected(redirected: bool) -> Result<(), AppError> {
+    OUTPUT_BUFFER
+        .lock()
+        .map_err(|_| AppError::LockError)?
+        .redirected = redirected;
+    Ok(())
+}
+
+pub fn is_redirected() -> Result<bool, AppError> {
+    Ok(OUTPUT_BUFFER
+        .lock()
+        .map_err(|_| AppError::LockError)?
+        .redirected)
+}
+
 pub fn set_render_format(format: RenderFormat) -> Result<(), AppError> {
     OUTPUT_BUFFER
         .lock()
@@ -372,8 +423,8 @@ pub(crate) fn render_semantic(
             .map(str::to_string)
             .collect()),
         RenderFormat::Jsonl => Ok(render_jsonl(&envelope.value)?),
-        RenderFormat::Csv => render_delimited(envelope, ','),
-        RenderFormat::Tsv => render_delimited(envelope, '\t'),
+        RenderFormat::Csv => render_delimited(envelope, b','),
+        RenderFormat::Tsv => render_delimited(envelope, b'\t'),
     }
 }
 
@@ -399,8 +450,8 @@ pub(crate) fn render_semantic_item(
             .map(str::to_string)
             .collect(),
         RenderFormat::Jsonl => vec![to_string(value)?],
-        RenderFormat::Csv => render_item_delimited(value, source_shape, columns, ',')?,
-        RenderFormat::Tsv => render_item_delimited(value, source_shape, columns, '\t')?,
+        RenderFormat::Csv => render_item_delimited(value, source_shape, columns, b',')?,
+        RenderFormat::Tsv => render_item_delimited(value, source_shape, columns, b'\t')?,
     };
 
     Ok(if lines.is_empty() {
@@ -410,10 +461,15 @@ pub(crate) fn render_semantic_item(
     })
 }
 
-fn config_render_format() -> RenderFormat {
-    match get_config().output.format {
-        OutputFormat::Json => RenderFormat::Json,
-        OutputFormat::Text => RenderFormat::Text,
+/// The configured render format, or `output.piped_format` when stdout is not a terminal.
+pub fn config_render_format() -> RenderFormat {
+    render_format_for(&get_config().output, stdout_is_terminal())
+}
+
+fn render_format_for(output: &OutputConfig, stdout_is_terminal: bool) -> RenderFormat {
+    match output.piped_format {
+        Some(format) if !stdout_is_terminal => format,
+        _ => output.format,
     }
 }
 
@@ -489,7 +545,7 @@ fn render_detail_text(envelope: &OutputEnvelope) -> Result<Vec<String>, AppError
     let configured_padding = usize::try_from(get_config().output.padding).unwrap_or_default();
     let padding = columns
         .iter()
-        .map(String::len)
+        .map(|column| display_width(column))
         .max()
         .unwrap_or_default()
         .max(configured_padding);
@@ -502,7 +558,7 @@ fn render_detail_text(envelope: &OutputEnvelope) -> Result<Vec<String>, AppError
 fn render_detail_field(column: &str, value: &str, padding: usize) -> String {
     let mut lines = value.split('\n');
     let first = lines.next().unwrap_or_default();
-    let mut rendered = format!("{column:<padding$}: {first}");
+    let mut rendered = format!("{}: {first}", pad_to_width(column, padding));
     let continuation_indent = " ".repeat(padding + 2);
     for line in lines {
         rendered.push('\n');
@@ -523,7 +579,7 @@ fn render_jsonl(value: &Value) -> Result<Vec<String>, AppError> {
     }
 }
 
-fn render_delimited(envelope: &OutputEnvelope, delimiter: char) -> Result<Vec<String>, AppError> {
+fn render_delimited(envelope: &OutputEnvelope, delimiter: u8) -> Result<Vec<String>, AppError> {
     let rows = match envelope.shape {
         OutputShape::Rows => value_array(&envelope.value),
         OutputShape::Detail | OutputShape::Message => vec![envelope.value.clone()],
@@ -543,18 +599,14 @@ fn render_delimited(envelope: &OutputEnvelope, delimiter: char) -> Result<Vec<St
     let mut lines = vec![join_delimited(
         columns.iter().map(String::as_str),
         delimiter,
-    )];
-    lines.extend(rows.iter().map(|row| {
-        join_delimited(
-            columns
-                .iter()
-                .map(|column| cell_text(row.get(column)))
-                .collect::<Vec<_>>()
-                .iter()
-                .map(String::as_str),
-            delimiter,
-        )
-    }));
+    )?];
+    for row in &rows {
+        let cells = columns
+            .iter()
+            .map(|column| cell_text(row.get(column)))
+            .collect::<Vec<_>>();
+        lines.push(join_delimited(cells.iter().map(String::as_str), delimiter)?);
+    }
     Ok(lines)
 }
 
@@ -562,7 +614,7 @@ fn render_item_delimited(
     value: &Value,
     source_shape: OutputShape,
     columns: &[String],
-    delimiter: char,
+    delimiter: u8,
 ) -> Result<Vec<String>, AppError> {
     let envelope = match source_shape {
         OutputShape::Rows | OutputShape::Detail | OutputShape::Message => {
@@ -577,24 +629,37 @@ fn render_item_delimited(
     render_delimited(&envelope, delimiter)
 }
 
-fn join_delimited<'a>(values: impl IntoIterator<Item = &'a str>, delimiter: char) -> String {
-    values
-        .into_iter()
-        .map(|value| escape_delimited(value, delimiter))
-        .collect::<Vec<_>>()
-        .join(&delimiter.to_string())
-}
-
-fn escape_delimited(value: &str, delimiter: char) -> String {
-    if delimiter == '\t' {
-        return value.replace(['\t', '\n', '\r'], " ");
-    }
-
-    if value.contains([delimiter, '"', '\n', '\r']) {
-        format!("\"{}\"", value.replace('"', "\"\""))
+/// Writes `values` as one CSV record, or as one TSV line for a tab `delimiter`, where
+/// tabs and line breaks in values become spaces instead of being quoted.
+pub(crate) fn join_delimited<'a>(
+    values: impl IntoIterator<Item = &'a str>,
+    delimiter: u8,
+) -> Result<String, AppError> {
+    let tsv = delimiter == b'\t';
+    let mut writer = WriterBuilder::new()
+        .delimiter(delimiter)
+        .quote_style(if tsv {
+            QuoteStyle::Never
+        } else {
+            QuoteStyle::Necessary
+        })
+        .terminator(Terminator::Any(b'\n'))
+        .from_writer(Vec::new());
+    if tsv {
+        writer.write_record(
+            values
+                .into_iter()
+                .map(|value| value.replace(['\t', '\n', '\r'], " ")),
+        )?;
     } else {
-        value.to_string()
+        writer.write_record(values)?;
     }
+    let record = writer
+        .into_inner()
+        .map_err(|error| AppError::IoError(error.into_error()))?;
+    let record =
+        String::from_utf8(record).map_err(|error| AppError::ParseError(error.to_string()))?;
+    Ok(record.trim_end_matches('\n').to_string())
 }
 
 fn display_columns(envelope: &OutputEnvelope, rows: &[Value]) -> Vec<String> {
@@ -667,10 +732,10 @@ fn dense_widths(rows: &[Value], columns: &[String], headers: &[String]) -> Vec<u
         .map(|column| {
             let (column, header) = column;
             rows.iter()
-                .map(|row| cell_text(row.get(column)).len())
-                .chain(once(header.len()))
+                .map(|row| display_width(&cell_text(row.get(column))))
+                .chain(once(display_width(header)))
                 .max()
-                .unwrap_or(header.len())
+                .unwrap_or_default()
         })
         .collect()
 }
@@ -687,7 +752,7 @@ fn render_dense_line<'a>(values: impl IntoIterator<Item = &'a str>, widths: &[us
     values
         .into_iter()
         .zip(widths.iter())
-        .map(|(value, width)| format!("{value:<width$}"))
+        .map(|(value, width)| pad_to_width(value, *width))
         .collect::<Vec<_>>()
         .join(" | ")
 }
@@ -783,8 +848,8 @@ mod tests {
     use serial_test::serial;
 
     use super::{
-        append_line, render_dense_theme_preview, reset_output, set_pipeline, set_render_format,
-        set_semantic_output, take_output, OutputSnapshot, RenderFormat,
+        append_line, render_dense_theme_preview, render_format_for, reset_output, set_pipeline,
+        set_render_format, set_semantic_output, take_output, OutputSnapshot, RenderFormat,
     };
     use crate::config::{init_config, AppConfig};
     use crate::models::{OutputColor, TableBands, TableStyle};
@@ -1062,4 +1127,14 @@ mod tests {
         assert!(rendered.contains("alpha"));
         assert!(!rendered.contains("null"));
     }
+
+    #[test]
+    fn piped_format_only_applies_when_stdout_is_not_a_terminal() {
+        let mut output = AppConfig::default().output;
+        assert_eq!(render_format_for(&output, false), RenderFormat::Text);
+
+        output.piped_format = Some(RenderFormat::Json);
+        assert_eq!(render_format_for(&output, true), RenderFormat::Text);
+        assert_eq!(render_format_for(&output, false), RenderFormat::Json);
+    }
 }
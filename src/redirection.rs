@@ -1,8 +1,11 @@
 use std::collections::HashSet;
+use std::env::var;
 use std::fs::OpenOptions;
-use std::io::Write;
+use std::io::{ErrorKind, Write};
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
+use anstream::adapter::strip_str;
 use anstream::{AutoStream, ColorChoice};
 use dirs::home_dir;
 use hubuum_filter::{group_summary_rows, scalar_text, select_values, OutputShape};
@@ -10,9 +13,15 @@ use serde_json::Value;
 use shlex::split;
 
 use crate::errors::AppError;
-use crate::output::{render_semantic_item, OutputSnapshot};
+use crate::output::{print_rendered, render_semantic_item, OutputSnapshot};
+use crate::terminal::stdout_is_terminal;
 use crate::theme::color_choice;
 
+#[cfg(unix)]
+const DEFAULT_PAGER: &str = "less -R";
+#[cfg(windows)]
+const DEFAULT_PAGER: &str = "more";
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OutputRedirect {
     pub target: RedirectTarget,
@@ -23,8 +32,13 @@ pub struct OutputRedirect {
 pub enum RedirectTarget {
     File(PathBuf),
     Each(EachTemplate),
+    /// A shell command line that reads the rendered output on its standard input.
+    Command(String),
 }
 
+/// Pipe stage that hands the rest of the line to the system shell, e.g. `| X grep web | wc -l`.
+const EXTERNAL_STAGE: &str = "X";
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EachTemplate {
     template: String,
@@ -37,6 +51,9 @@ pub(crate) struct RedirectCandidate {
 }
 
 pub(crate) fn split_redirect_candidate(line: &str) -> Result<Option<RedirectCandidate>, AppError> {
+    if let Some(candidate) = split_external_pipe(line)? {
+        return Ok(Some(candidate));
+    }
     let Some((operator_start, operator_len)) = final_redirect_operator(line) else {
         return Ok(None);
     };
@@ -69,6 +86,59 @@ pub(crate) fn split_redirect_candidate(line: &str) -> Result<Option<RedirectCand
     }))
 }
 
+/// Splits `command | X shell pipeline` at the first unquoted `X` stage.
+///
+/// Everything after the stage, including further pipes and redirects, is left to the shell.
+fn split_external_pipe(line: &str) -> Result<Option<RedirectCandidate>, AppError> {
+    for pipe in unquoted_pipes(line) {
+        let stage = line[pipe + 1..].trim_start();
+        let Some(external) = stage.strip_prefix(EXTERNAL_STAGE) else {
+            continue;
+        };
+        if !external.is_empty() && !external.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let command = line[..pipe].trim_end();
+        if command.is_empty() {
+            return Ok(None);
+        }
+        let external = external.trim();
+        if external.is_empty() {
+            return Err(AppError::ParseError(format!(
+                "Pipe stage '{EXTERNAL_STAGE}' requires a command to run"
+            )));
+        }
+        return Ok(Some(RedirectCandidate {
+            line: command.to_string(),
+            redirect: OutputRedirect {
+                target: RedirectTarget::Command(external.to_string()),
+                append: false,
+            },
+        }));
+    }
+    Ok(None)
+}
+
+fn unquoted_pipes(line: &str) -> Vec<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut pipes = Vec::new();
+    for (index, ch) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' if quote != Some('\'') => escaped = true,
+            '\'' | '"' if quote == Some(ch) => quote = None,
+            '\'' | '"' if quote.is_none() => quote = Some(ch),
+            '|' if quote.is_none() => pipes.push(index),
+            _ => {}
+        }
+    }
+    pipes
+}
+
 pub(crate) fn redirect_completion_context(line: &str, pos: usize) -> Option<(&str, usize)> {
     let prefix = line.get(..pos)?;
     let (operator_start, operator_len) = final_redirect_operator(prefix)?;
@@ -93,7 +163,82 @@ pub fn write_output(snapshot: &OutputSnapshot, redirect: &OutputRedirect) -> Res
     match &redirect.target {
         RedirectTarget::File(path) => write_file(&snapshot.render(), path, redirect.append),
         RedirectTarget::Each(template) => write_each_output(snapshot, template, redirect.append),
+        RedirectTarget::Command(command) => {
+            pipe_to_command(&snapshot.render(), command, color_choice())
+        }
+    }
+}
+
+/// Prints `snapshot` to stdout, through the pager when it asks for one and stdout is a
+/// terminal. The pager is `$PAGER`, or `less -R`; if it cannot run, the text is printed.
+pub fn print_output(snapshot: &OutputSnapshot) -> Result<(), AppError> {
+    let rendered = snapshot.render();
+    if snapshot.paged && stdout_is_terminal() {
+        let color = match color_choice() {
+            ColorChoice::Never => ColorChoice::Never,
+            _ => ColorChoice::Always,
+        };
+        if pipe_to_command(&rendered, &pager_command(), color).is_ok() {
+            return Ok(());
+        }
+    }
+    print_rendered(&rendered)
+}
+
+fn pager_command() -> String {
+    var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string())
+}
+
+/// Runs `command` through the system shell with `content` on its standard input.
+///
+/// The command writes straight to the terminal. Colors are kept only when they are
+/// forced on, and a command that stops reading early (such as `head`) is not an error.
+fn pipe_to_command(
+    content: &str,
+    command: &str,
+    color_choice: ColorChoice,
+) -> Result<(), AppError> {
+    let content = if color_choice == ColorChoice::Always {
+        content.to_string()
+    } else {
+        strip_str(content).to_string()
+    };
+    let mut child = shell(command)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|error| {
+            AppError::CommandExecutionError(format!("Could not run '{command}': {error}"))
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        match stdin.write_all(content.as_bytes()) {
+            Err(error) if error.kind() != ErrorKind::BrokenPipe => return Err(error.into()),
+            _ => {}
+        }
     }
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(AppError::CommandExecutionError(format!(
+            "'{command}' failed ({status})"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut process = Command::new("sh");
+    process.arg("-c").arg(command);
+    process
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut process = Command::new("cmd");
+    process.arg("/C").arg(command);
+    process
 }
 
 fn write_file(content: &str, path: &Path, append: bool) -> Result<(), AppError> {
@@ -316,7 +461,7 @@ fn select_placeholder_values<'a>(value: &'a Value, selector: &str) -> Vec<&'a Va
     select_values(value, selector)
 }
 
-fn sanitize_path_value(value: &str) -> String {
+pub(crate) fn sanitize_path_value(value: &str) -> String {
     let sanitized = value
         .chars()
         .map(|ch| match ch {
@@ -406,8 +551,8 @@ mod tests {
     use std::path::PathBuf;
 
     use super::{
-        redirect_completion_context, split_redirect_candidate, write_file_with_color_choice,
-        write_output, RedirectTarget,
+        pipe_to_command, redirect_completion_context, split_redirect_candidate,
+        write_file_with_color_choice, write_output, RedirectTarget,
     };
     use crate::output::{OutputSnapshot, RenderFormat};
     use anstream::ColorChoice;
@@ -415,6 +560,52 @@ mod tests {
     use serde_json::json;
     use tempfile::tempdir;
 
+    #[test]
+    fn x_stages_hand_the_rest_of_the_line_to_the_shell() {
+        let candidate = split_redirect_candidate(
+            "object list --where name equals 'a | X b' | P Name | X grep web | wc -l > n.txt",
+        )
+        .expect("external pipe should parse")
+        .expect("external pipe should exist");
+        assert_eq!(
+            candidate.line,
+            "object list --where name equals 'a | X b' | P Name"
+        );
+        assert_eq!(
+            candidate.redirect.target,
+            RedirectTarget::Command("grep web | wc -l > n.txt".to_string())
+        );
+
+        assert!(split_redirect_candidate("object list | Xenon")
+            .expect("grep stage should parse")
+            .is_none());
+        assert!(split_redirect_candidate("object list | X").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shell_commands_receive_plain_rendered_output() {
+        let directory = tempdir().expect("temporary directory should be created");
+        let target = directory.path().join("piped.txt");
+        let command = format!("grep web > '{}'", target.display());
+
+        pipe_to_command(
+            "\u{1b}[1mweb01\u{1b}[0m\ndb01\nweb02\n",
+            &command,
+            ColorChoice::Auto,
+        )
+        .expect("command should run");
+
+        assert_eq!(read_to_string(&target).unwrap(), "web01\nweb02\n");
+        assert!(pipe_to_command("x\n", "exit 3", ColorChoice::Auto).is_err());
+        pipe_to_command(
+            &"line\n".repeat(100_000),
+            "head -n 1 > /dev/null",
+            ColorChoice::Auto,
+        )
+        .expect("an early exit is not an error");
+    }
+
     #[test]
     fn splits_trailing_redirects() {
         let candidate = split_redirect_candidate("object list | P Name > out.json")
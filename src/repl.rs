@@ -18,14 +18,16 @@ use crate::app::{AppRuntime, SharedSession};
 use crate::autocomplete::{complete_sort_clause, complete_where_clause, file_paths};
 use crate::background::BackgroundManager;
 use crate::catalog::{CommandOutcome, CompletionSpec, OptionSpec, ScopeAction};
+use crate::command_line::shell_escape;
 use crate::config::get_config;
 use crate::dispatch::{apply_output_state, apply_scope_action, execute_line, render_error};
 use crate::errors::AppError;
 use crate::files::get_history_file;
 use crate::json_schema::schema_paths;
 use crate::output::print_rendered;
-use crate::redirection::{redirect_completion_context, write_output};
+use crate::redirection::{print_output, redirect_completion_context, write_output};
 use crate::services::CompletionContext;
+use crate::transaction::{BEGIN, COMMIT, ROLLBACK};
 
 const CANCEL_PAGINATION_HOST_COMMAND: &str = "__hubuum_cancel_pagination__";
 
@@ -50,6 +52,7 @@ fn run_thread(
     let completion = app
         .services
         .completion_context(runtime.clone(), app.config.as_ref());
+    completion.prefetch_names();
     let completer = Box::new(ReplCompleter {
         app: app.clone(),
         session: session.clone(),
@@ -202,7 +205,7 @@ fn apply_outcome(session: &SharedSession, outcome: CommandOutcome) -> Result<(),
     if let Some(redirect) = outcome.redirect {
         write_output(&outcome.output, &redirect)?;
     } else if !outcome.output.is_empty() {
-        print_rendered(&outcome.output.render())?;
+        print_output(&outcome.output)?;
     }
     Ok(())
 }
@@ -259,28 +262,35 @@ impl Completer for ReplCompleter {
             return suggestions;
         }
         if let Some((prefix, replacement_start)) = redirect_completion_context(prefix_line, pos) {
-            return file_paths(&self.completion, prefix, &[])
+            let target = completion_word(prefix);
+            return file_paths(&self.completion, &target.text, &[])
                 .into_iter()
-                .map(|value| dynamic_value_suggestion(value, replacement_start, pos))
+                .map(|value| {
+                    dynamic_value_suggestion(
+                        value,
+                        replacement_start + target.start,
+                        pos,
+                        target.quote,
+                    )
+                })
                 .collect();
         }
-        let ends_with_space = prefix_line.ends_with(' ');
-        let (start, word) = prefix_line
-            .rsplit_once(char::is_whitespace)
-            .map_or((0, prefix_line), |(left, right)| (left.len() + 1, right));
+        let current = completion_word(prefix_line);
+        let ends_with_space = prefix_line.ends_with(' ') && current.start == prefix_line.len();
+        let (start, word) = (current.start, current.text.as_str());
 
-        let Some(parts) = split(prefix_line) else {
+        let Some(parts) = split_completion_line(prefix_line, current.quote) else {
             return Vec::new();
         };
 
         if parts.is_empty() {
-            return self.scope_suggestions(start, word, &[], ends_with_space);
+            return self.scope_suggestions(start, word, &[], ends_with_space, true);
         }
 
         let scope = self.session.scope();
 
         if parts[0] == "help" || parts[0] == "?" {
-            return self.scope_suggestions(start, word, &parts[1..], ends_with_space);
+            return self.scope_suggestions(start, word, &parts[1..], ends_with_space, false);
         }
 
         if let Ok(resolved) = self.app.catalog.resolve_command(&scope, &parts) {
@@ -340,6 +350,7 @@ impl Completer for ReplCompleter {
                                         value,
                                         context.replacement_start,
                                         context.replacement_end,
+                                        current.quote,
                                     )
                                 })
                                 .collect();
@@ -347,7 +358,7 @@ impl Completer for ReplCompleter {
                     }
                 }
 
-                if last.starts_with('-') || prefix_line.ends_with(' ') {
+                if last.starts_with('-') || ends_with_space {
                     return options
                         .iter()
                         .filter(|option| {
@@ -365,7 +376,7 @@ impl Completer for ReplCompleter {
             }
         }
 
-        self.scope_suggestions(start, word, &parts, ends_with_space)
+        self.scope_suggestions(start, word, &parts, ends_with_space, true)
     }
 }
 
@@ -623,12 +634,14 @@ impl ReplCompleter {
             .collect()
     }
 
+    /// Suggests commands and scopes; `with_meta` adds REPL words such as `exit` and `begin`.
     fn scope_suggestions(
         &self,
         start: usize,
         word: &str,
         parts: &[String],
         ends_with_space: bool,
+        with_meta: bool,
     ) -> Vec<Suggestion> {
         let scope = self.session.scope();
         let context_parts = completion_context_parts(parts, ends_with_space);
@@ -650,6 +663,10 @@ impl ReplCompleter {
         if !scope.is_empty() {
             scope_words.push("..".to_string());
         }
+        if with_meta && context_parts.is_empty() {
+            let has_next_page = self.session.next_page_command().is_some();
+            scope_words.extend(meta_command_words(has_next_page).map(str::to_string));
+        }
 
         scope_words
             .into_iter()
@@ -659,6 +676,13 @@ impl ReplCompleter {
     }
 }
 
+/// Words the REPL handles itself before consulting the command catalog.
+fn meta_command_words(has_next_page: bool) -> impl Iterator<Item = &'static str> {
+    ["exit", "quit", BEGIN, COMMIT, ROLLBACK]
+        .into_iter()
+        .chain(has_next_page.then_some("next"))
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum IdCompletionKind {
     LocalJob,
@@ -1143,9 +1167,93 @@ fn suggestion(value: String, start: usize, end: usize, description: Option<Strin
     suggestion_with_whitespace(value, start, end, description, true)
 }
 
-fn dynamic_value_suggestion(value: String, start: usize, end: usize) -> Suggestion {
+fn dynamic_value_suggestion(
+    value: String,
+    start: usize,
+    end: usize,
+    quote: Option<char>,
+) -> Suggestion {
     let append_whitespace = !value.ends_with(MAIN_SEPARATOR) && !value.ends_with('/');
-    suggestion_with_whitespace(value, start, end, None, append_whitespace)
+    suggestion_with_whitespace(
+        quote_completion(&value, quote),
+        start,
+        end,
+        None,
+        append_whitespace,
+    )
+}
+
+/// The word under the cursor, read the way the line is split when it runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CompletionWord {
+    /// Byte offset of the word, including any opening quote.
+    start: usize,
+    /// The word with its quotes and escapes removed.
+    text: String,
+    /// The quote still open at the cursor.
+    quote: Option<char>,
+}
+
+fn completion_word(line: &str) -> CompletionWord {
+    let mut word = CompletionWord {
+        start: 0,
+        text: String::new(),
+        quote: None,
+    };
+    let mut escaped = false;
+    for (index, ch) in line.char_indices() {
+        if escaped {
+            // Inside double quotes a backslash only escapes the characters the shell
+            // gives meaning to there.
+            if word.quote == Some('"') && !matches!(ch, '"' | '\\' | '$' | '`' | '\n') {
+                word.text.push('\\');
+            }
+            word.text.push(ch);
+            escaped = false;
+            continue;
+        }
+        match (word.quote, ch) {
+            (Some(quote), _) if ch == quote => word.quote = None,
+            (Some('"'), '\\') | (None, '\\') => escaped = true,
+            (Some(_), _) => word.text.push(ch),
+            (None, '\'' | '"') => word.quote = Some(ch),
+            (None, _) if ch.is_whitespace() => {
+                word.start = index + ch.len_utf8();
+                word.text.clear();
+            }
+            (None, _) => word.text.push(ch),
+        }
+    }
+    word
+}
+
+/// Splits a line being completed, closing the quote the cursor is still inside.
+fn split_completion_line(line: &str, quote: Option<char>) -> Option<Vec<String>> {
+    match quote {
+        Some(quote) => split(&format!("{line}{quote}")),
+        None => split(line),
+    }
+}
+
+/// Quotes a completed value when it would not split back into itself, keeping the
+/// quote style the user already opened.
+fn quote_completion(value: &str, quote: Option<char>) -> String {
+    match quote {
+        Some('"') => {
+            let mut quoted = String::from('"');
+            for ch in value.chars() {
+                if matches!(ch, '"' | '\\' | '$' | '`') {
+                    quoted.push('\\');
+                }
+                quoted.push(ch);
+            }
+            quoted.push('"');
+            quoted
+        }
+        Some(_) => format!("'{}'", value.replace('\'', "'\\''")),
+        None if split(value).is_some_and(|words| words == [value]) => value.to_string(),
+        None => shell_escape(value),
+    }
 }
 
 fn suggestion_with_whitespace(
@@ -1345,9 +1453,10 @@ mod tests {
 
     use super::{
         clause_active_token_offset, clause_option_context, completion_context_parts,
-        dynamic_value_suggestion, id_completion_context, is_completing_option_value,
-        option_suggestion, option_value_context, pipe_completion_context, quoted_where_context,
-        safe_prefix_end, where_suggestion, IdCompletionKind, PaginationEditMode,
+        completion_word, dynamic_value_suggestion, id_completion_context,
+        is_completing_option_value, meta_command_words, option_suggestion, option_value_context,
+        pipe_completion_context, quote_completion, quoted_where_context, safe_prefix_end,
+        split_completion_line, where_suggestion, IdCompletionKind, PaginationEditMode,
         PipeCompletionKind, CANCEL_PAGINATION_HOST_COMMAND,
     };
     use crate::json_schema::schema_paths;
@@ -1369,6 +1478,13 @@ mod tests {
         );
     }
 
+    #[test]
+    fn meta_commands_are_completed_and_next_only_with_a_pending_page() {
+        let words = meta_command_words(false).collect::<Vec<_>>();
+        assert_eq!(words, ["exit", "quit", "begin", "commit", "rollback"]);
+        assert!(meta_command_words(true).any(|word| word == "next"));
+    }
+
     #[test]
     fn completion_context_uses_parent_path_for_partial_word() {
         let parts = vec!["collection".to_string(), "mod".to_string()];
@@ -1407,8 +1523,44 @@ mod tests {
 
     #[test]
     fn nested_json_pointer_completion_does_not_append_whitespace() {
-        let suggestion = dynamic_value_suggestion("/load/".to_string(), 0, 0);
+        let suggestion = dynamic_value_suggestion("/load/".to_string(), 0, 0, None);
+
+        assert!(!suggestion.append_whitespace);
+    }
+
+    #[test]
+    fn completion_words_follow_shell_quoting() {
+        let word = completion_word(r#"object show --class Hosts --name "web se"#);
+        assert_eq!(word.text, "web se");
+        assert_eq!(word.quote, Some('"'));
+        assert_eq!(word.start, "object show --class Hosts --name ".len());
 
+        let word = completion_word(r"object show --class 'Web Servers' db\ prim");
+        assert_eq!(word.text, "db prim");
+        assert_eq!(word.quote, None);
+        assert_eq!(word.start, "object show --class 'Web Servers' ".len());
+
+        assert_eq!(
+            split_completion_line(r#"object show --class "Web Ser"#, Some('"')),
+            Some(vec![
+                "object".to_string(),
+                "show".to_string(),
+                "--class".to_string(),
+                "Web Ser".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn completed_values_are_quoted_only_when_needed() {
+        assert_eq!(quote_completion("web-01", None), "web-01");
+        assert_eq!(quote_completion("web server", None), "'web server'");
+        assert_eq!(quote_completion("#42", None), "'#42'");
+        assert_eq!(quote_completion("it's", Some('\'')), r"'it'\''s'");
+        assert_eq!(quote_completion(r#"a"b"#, Some('"')), r#""a\"b""#);
+
+        let suggestion = dynamic_value_suggestion("my dir/".to_string(), 0, 0, None);
+        assert_eq!(suggestion.value, "'my dir/'");
         assert!(!suggestion.append_whitespace);
     }
 
@@ -1722,6 +1874,8 @@ mod tests {
             nargs: None,
             repeatable: false,
             value_source: false,
+            raw: false,
+            positional: None,
             completion: CompletionSpec::None,
         }
     }
@@ -0,0 +1,367 @@
+use std::collections::BTreeSet;
+use std::io::{BufRead, Write};
+use std::iter::once;
+
+use hubuum_client::{ObjectDataPatchDocument, ObjectDataPatchOperation};
+use hubuum_filter::OutputEnvelope;
+use serde_json::{Map, Value};
+
+use crate::config::{AppConfig, DataConfig, SafetyConfig};
+use crate::errors::AppError;
+use crate::formatting::human_bytes;
+use crate::forms::Form;
+use crate::models::{DataLimitAction, OutputFormat};
+use crate::output::{add_warning, append_line, set_semantic_output};
+use crate::theme::{paint_on, Stream, ThemeRole};
+
+/// Counts of the changes a command is about to send, per entity type.
+pub struct WriteAheadSummary {
+    columns: &'static [&'static str],
+    rows: Vec<(&'static str, Vec<usize>)>,
+}
+
+impl WriteAheadSummary {
+    pub fn new(columns: &'static [&'static str]) -> Self {
+        Self {
+            columns,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Adds a row for `kind`, unless all of its counts are zero.
+    pub fn add(&mut self, kind: &'static str, counts: Vec<usize>) {
+        if counts.iter().any(|count| *count > 0) {
+            self.rows.push((kind, counts));
+        }
+    }
+
+    /// One JSON row per entity type, keyed by `type` and the lowercased column names.
+    fn json_rows(&self) -> Vec<Value> {
+        self.rows
+            .iter()
+            .map(|(kind, counts)| {
+                let mut row = Map::new();
+                row.insert("type".to_string(), Value::from(*kind));
+                for (name, count) in self.columns.iter().zip(counts) {
+                    row.insert(name.to_lowercase(), Value::from(*count));
+                }
+                Value::Object(row)
+            })
+            .collect()
+    }
+
+    fn json_columns(&self) -> Vec<String> {
+        once("type".to_string())
+            .chain(self.columns.iter().map(|name| name.to_lowercase()))
+            .collect()
+    }
+
+    /// A table of the rows with a closing total per column.
+    fn render(&self) -> String {
+        let totals = (0..self.columns.len())
+            .map(|column| self.rows.iter().map(|(_, counts)| counts[column]).sum())
+            .collect::<Vec<usize>>();
+        let rows = self
+            .rows
+            .iter()
+            .map(|(kind, counts)| (*kind, counts))
+            .chain([("Total", &totals)])
+            .collect::<Vec<_>>();
+        let kind_width = rows
+            .iter()
+            .map(|(kind, _)| kind.len())
+            .chain(["Type".len()])
+            .max()
+            .unwrap_or_default();
+        let widths = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(column, name)| name.len().max(totals[column].to_string().len()))
+            .collect::<Vec<_>>();
+
+        let mut table = format!("{:kind_width$}", "Type");
+        for (name, width) in self.columns.iter().zip(&widths) {
+            table.push_str(&format!("  {name:>width$}"));
+        }
+        for (kind, counts) in rows {
+            table.push_str(&format!("\n{kind:kind_width$}"));
+            for (count, width) in counts.iter().zip(&widths) {
+                table.push_str(&format!("  {count:>width$}"));
+            }
+        }
+        table
+    }
+}
+
+/// Adds `summary` to the command output before `action` sends its changes, and asks
+/// for the profile name when the active profile is marked `production`.
+///
+/// As with protected collections, `--yes` does not skip the prompt, and a production
+/// profile cannot be confirmed without a terminal.
+pub fn confirm_write_ahead(
+    config: &AppConfig,
+    format: OutputFormat,
+    action: &str,
+    summary: &WriteAheadSummary,
+) -> Result<(), AppError> {
+    let text = format!("'{action}' will send:\n{}", summary.render());
+    match format {
+        OutputFormat::Json => set_semantic_output(OutputEnvelope::rows(
+            summary.json_rows(),
+            summary.json_columns(),
+        ))?,
+        OutputFormat::Text => append_line(&text)?,
+    }
+    let Some(profile) = config.production_profile() else {
+        return Ok(());
+    };
+    let mut form = Form::terminal(
+        &format!("Confirming '{action}' on production profile '{profile}'"),
+        "run it from a terminal, or switch to a profile that is not marked production",
+    )?;
+    form.show(&text)?;
+    confirm_with(&mut form, action, "production profile", profile)
+}
+
+/// Asks for the collection name before `action` touches a protected collection.
+///
+/// Unprotected collections pass straight through. Flags such as `--yes` do not skip
+/// this prompt; the only way past it is to type the collection name at a terminal.
+pub fn confirm_protected_collection(
+    safety: &SafetyConfig,
+    action: &str,
+    collection: &str,
+) -> Result<(), AppError> {
+    if !safety.is_protected(collection) {
+        return Ok(());
+    }
+    let mut form = Form::terminal(
+        &format!("'{action}' on protected collection '{collection}'"),
+        &format!(
+            "run it from a terminal, or remove '{collection}' from safety.protected_collections"
+        ),
+    )?;
+    confirm_with(&mut form, action, "protected collection", collection)
+}
+
+/// Like [`confirm_protected_collection`] for an action that touches several
+/// collections; each protected one is asked for once.
+pub fn confirm_protected_collections(
+    safety: &SafetyConfig,
+    action: &str,
+    collections: impl IntoIterator<Item = String>,
+) -> Result<(), AppError> {
+    let protected = collections
+        .into_iter()
+        .filter(|collection| safety.is_protected(collection))
+        .collect::<BTreeSet<_>>();
+    for collection in protected {
+        confirm_protected_collection(safety, action, &collection)?;
+    }
+    Ok(())
+}
+
+/// Checks object data against `data.max_size` and `data.max_depth` before `action`
+/// sends it, then warns or refuses as `data.limit_action` says.
+pub fn guard_object_data(limits: &DataConfig, action: &str, data: &Value) -> Result<(), AppError> {
+    guard_data_shape(limits, action, json_size(data), json_depth(data))
+}
+
+/// Like [`guard_object_data`] for the values a data patch writes, each counted as
+/// nested as deep as its path.
+pub fn guard_object_data_patch(
+    limits: &DataConfig,
+    action: &str,
+    patch: &ObjectDataPatchDocument,
+) -> Result<(), AppError> {
+    let (size, depth) = patch
+        .iter()
+        .filter_map(|operation| match operation {
+            ObjectDataPatchOperation::Add { path, value }
+            | ObjectDataPatchOperation::Replace { path, value }
+            | ObjectDataPatchOperation::Test { path, value } => Some((path, value)),
+            _ => None,
+        })
+        .fold((0, 0), |(size, depth), (path, value)| {
+            let levels = path.split('/').filter(|token| !token.is_empty()).count();
+            (
+                size + json_size(value),
+                depth.max(levels as u64 + json_depth(value)),
+            )
+        });
+    guard_data_shape(limits, action, size, depth)
+}
+
+fn guard_data_shape(
+    limits: &DataConfig,
+    action: &str,
+    size: u64,
+    depth: u64,
+) -> Result<(), AppError> {
+    let problem = if limits.max_size > 0 && size > limits.max_size {
+        format!(
+            "object data is {}, above data.max_size of {}",
+            human_bytes(size),
+            human_bytes(limits.max_size)
+        )
+    } else if limits.max_depth > 0 && depth > limits.max_depth {
+        format!(
+            "object data nests {depth} levels deep, above data.max_depth of {}",
+            limits.max_depth
+        )
+    } else {
+        return Ok(());
+    };
+
+    match limits.limit_action {
+        DataLimitAction::Warn => add_warning(format!("{action}: {problem}")),
+        DataLimitAction::Refuse => Err(AppError::CommandExecutionError(format!(
+            "Refused to {action}: {problem}. Raise the limit, or set data.limit_action = \"warn\" to send it anyway"
+        ))),
+    }
+}
+
+fn json_size(value: &Value) -> u64 {
+    serde_json::to_vec(value).map_or(0, |json| json.len() as u64)
+}
+
+/// Levels of objects and arrays in `value`; scalars have none.
+fn json_depth(value: &Value) -> u64 {
+    match value {
+        Value::Object(fields) => 1 + fields.values().map(json_depth).max().unwrap_or(0),
+        Value::Array(items) => 1 + items.iter().map(json_depth).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// Asks for `name` to be typed before `action` touches the `scope` (such as a
+/// "protected collection") of that name.
+fn confirm_with<R: BufRead, W: Write>(
+    form: &mut Form<R, W>,
+    action: &str,
+    scope: &str,
+    name: &str,
+) -> Result<(), AppError> {
+    let noun = scope.rsplit(' ').next().unwrap_or(scope);
+    let question = paint_on(
+        Stream::Stderr,
+        ThemeRole::Warning,
+        format!("'{action}' affects {scope} '{name}'. Type the {noun} name to continue: "),
+    );
+    if form.typed_confirmation(&question, name)? {
+        return Ok(());
+    }
+    Err(AppError::CommandExecutionError(format!(
+        "'{action}' was not confirmed for {scope} '{name}'; nothing was changed"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn confirm(answer: &str) -> Result<(), AppError> {
+        confirm_with(
+            &mut Form::new(Cursor::new(answer.as_bytes()), Vec::new()),
+            "collection delete prod",
+            "protected collection",
+            "prod",
+        )
+    }
+
+    #[test]
+    fn only_the_exact_collection_name_confirms() {
+        assert!(confirm("prod\n").is_ok());
+        assert!(confirm("prod\r\n").is_ok());
+        for answer in ["", "\n", "yes\n", "Prod\n", " prod\n"] {
+            assert!(confirm(answer).is_err(), "{answer:?} should not confirm");
+        }
+    }
+
+    #[test]
+    fn unprotected_collections_do_not_prompt() {
+        let safety = SafetyConfig {
+            protected_collections: vec!["prod".to_string()],
+        };
+        assert!(confirm_protected_collection(&safety, "class delete Hosts", "staging").is_ok());
+        assert!(confirm_protected_collections(
+            &safety,
+            "relation delete",
+            ["lab".to_string(), "staging".to_string()]
+        )
+        .is_ok());
+        assert!(safety.is_protected("prod"));
+        assert!(!SafetyConfig::default().has_protected_collections());
+    }
+
+    #[test]
+    fn write_ahead_summaries_skip_empty_rows_and_total_the_columns() {
+        let mut summary = WriteAheadSummary::new(&["Create", "Update", "Delete"]);
+        summary.add("collection", vec![1, 0, 0]);
+        summary.add("class", vec![0, 0, 0]);
+        summary.add("object relation", vec![12, 3, 0]);
+
+        assert_eq!(
+            summary.render(),
+            "Type             Create  Update  Delete\n\
+             collection            1       0       0\n\
+             object relation      12       3       0\n\
+             Total                13       3       0"
+        );
+        assert_eq!(
+            summary.json_rows()[1],
+            json!({ "type": "object relation", "create": 12, "update": 3, "delete": 0 })
+        );
+        assert_eq!(
+            summary.json_columns(),
+            vec!["type", "create", "update", "delete"]
+        );
+    }
+
+    #[test]
+    fn object_data_past_its_limits_is_refused_or_sent_with_a_warning() {
+        let mut limits = DataConfig {
+            max_size: 32,
+            max_depth: 2,
+            limit_action: DataLimitAction::Refuse,
+        };
+        let nested = json!({ "a": { "b": { "c": 1 } } });
+
+        assert!(guard_object_data(&limits, "create object", &json!({ "a": [1] })).is_ok());
+        let error = guard_object_data(&limits, "create object", &nested)
+            .expect_err("three levels are above the limit");
+        assert!(error.to_string().contains("nests 3 levels deep"));
+        let error = guard_object_data(&limits, "create object", &json!({ "x": "y".repeat(40) }))
+            .expect_err("48 bytes are above the limit");
+        assert!(error.to_string().contains("object data is 48 B"));
+
+        let patch = ObjectDataPatchDocument::new([ObjectDataPatchOperation::Add {
+            path: "/a/b".to_string(),
+            value: json!({ "c": 1 }),
+        }]);
+        assert!(guard_object_data_patch(&limits, "patch object", &patch).is_err());
+
+        limits.limit_action = DataLimitAction::Warn;
+        assert!(guard_object_data(&limits, "create object", &nested).is_ok());
+        limits.max_depth = 0;
+        limits.limit_action = DataLimitAction::Refuse;
+        assert!(guard_object_data(&limits, "create object", &nested).is_ok());
+    }
+
+    #[test]
+    fn the_prompt_names_what_has_to_be_typed() {
+        let mut form = Form::new(Cursor::new(b"prod\n".as_slice()), Vec::new());
+        let result = confirm_with(&mut form, "apply", "production profile", "live");
+
+        assert!(result.is_err());
+        assert_eq!(
+            String::from_utf8(form.into_prompt()).expect("UTF-8 prompt"),
+            "'apply' affects production profile 'live'. Type the profile name to continue: "
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use std::io::{Error as IoError, Write};
+use std::num::NonZeroU32;
+use std::process::{Command, Stdio};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::pbkdf2::{derive, PBKDF2_HMAC_SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use shlex::split;
+
+use crate::errors::AppError;
+
+/// Prefix marking an encrypted configuration value, e.g. `password = "enc:..."`.
+const ENCRYPTED_PREFIX: &str = "enc:";
+
+/// Configuration keys whose values may be stored encrypted.
+pub const ENCRYPTABLE_KEYS: &[&str] = &["server.password"];
+
+/// Leading byte of an encrypted payload, followed by the salt, the nonce, and the sealed
+/// value. Values from before the salted format have no version byte.
+const PAYLOAD_VERSION: u8 = 2;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+/// PBKDF2-HMAC-SHA256 rounds turning key material into a cipher key.
+const KDF_ITERATIONS: NonZeroU32 = match NonZeroU32::new(600_000) {
+    Some(iterations) => iterations,
+    None => panic!("KDF iterations must not be zero"),
+};
+
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+/// Encrypts `plaintext` for the configuration key `setting` with the key printed by
+/// `key_command`.
+pub fn encrypt_value(
+    plaintext: &str,
+    key_command: &str,
+    setting: &str,
+) -> Result<String, AppError> {
+    encrypt_with(plaintext, &encryption_key(key_command)?, setting)
+}
+
+/// Decrypts an `enc:` value of the configuration key `setting` with the key printed by
+/// `key_command`; other values pass through.
+pub fn decrypt_value(
+    value: &str,
+    key_command: Option<&str>,
+    setting: &str,
+) -> Result<String, AppError> {
+    if !is_encrypted(value) {
+        return Ok(value.to_string());
+    }
+    let key_command = key_command.ok_or_else(|| {
+        AppError::GeneralConfigError(
+            "An encrypted configuration value needs server.encryption_key_command to decrypt it"
+                .to_string(),
+        )
+    })?;
+    decrypt_with(value, &encryption_key(key_command)?, setting)
+}
+
+/// Runs `command` directly (no shell) and returns the first line it prints.
+///
+/// `setting` names the configuration key in error messages.
+pub fn secret_from_command(setting: &str, command: &str) -> Result<String, AppError> {
+    let (mut process, program) = configured_command(setting, command)?;
+    let output = process
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|error| start_error(setting, &program, error))?;
+    if !output.status.success() {
+        return Err(AppError::GeneralConfigError(format!(
+            "{setting} failed ({})",
+            output.status
+        )));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let secret = stdout.lines().next().unwrap_or_default();
+    if secret.is_empty() {
+        return Err(AppError::GeneralConfigError(format!(
+            "{setting} printed nothing"
+        )));
+    }
+    Ok(secret.to_string())
+}
+
+/// Runs `command` directly (no shell) with `secret` on its standard input.
+///
+/// The command's own output goes to the terminal. `setting` names the configuration
+/// key in error messages.
+pub fn secret_to_command(setting: &str, command: &str, secret: &str) -> Result<(), AppError> {
+    let (mut process, program) = configured_command(setting, command)?;
+    let mut child = process
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|error| start_error(setting, &program, error))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(secret.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(AppError::GeneralConfigError(format!(
+            "{setting} failed ({status})"
+        )));
+    }
+    Ok(())
+}
+
+/// Splits a configured command line into a process to spawn and its program name.
+fn configured_command(setting: &str, command: &str) -> Result<(Command, String), AppError> {
+    let argv = split(command).ok_or_else(|| {
+        AppError::GeneralConfigError(format!("{setting} has unbalanced quotes: {command}"))
+    })?;
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| AppError::GeneralConfigError(format!("{setting} is empty")))?;
+    let mut process = Command::new(program);
+    process.args(args);
+    Ok((process, program.clone()))
+}
+
+fn start_error(setting: &str, program: &str, error: IoError) -> AppError {
+    AppError::GeneralConfigError(format!("{setting} could not start '{program}': {error}"))
+}
+
+/// Runs `server.encryption_key_command` and returns the key material it prints.
+pub fn encryption_key(key_command: &str) -> Result<String, AppError> {
+    secret_from_command("server.encryption_key_command", key_command)
+}
+
+/// ChaCha20-Poly1305 keyed by stretching the key material with PBKDF2 over `salt`.
+fn cipher(key_material: &str, salt: &[u8]) -> Result<LessSafeKey, AppError> {
+    let mut key = [0u8; KEY_LEN];
+    derive(
+        PBKDF2_HMAC_SHA256,
+        KDF_ITERATIONS,
+        salt,
+        key_material.as_bytes(),
+        &mut key,
+    );
+    let key = UnboundKey::new(&CHACHA20_POLY1305, &key).map_err(|_| {
+        AppError::CommandExecutionError("Could not build the cipher key".to_string())
+    })?;
+    Ok(LessSafeKey::new(key))
+}
+
+/// Encrypts `plaintext` into an `enc:` value with key material already fetched.
+///
+/// `setting` names what the value is for and is bound to it as associated data, so an
+/// encrypted value only decrypts for the setting it was made for.
+pub fn encrypt_with(
+    plaintext: &str,
+    key_material: &str,
+    setting: &str,
+) -> Result<String, AppError> {
+    let random = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    random
+        .fill(&mut salt)
+        .and_then(|()| random.fill(&mut nonce))
+        .map_err(|_| {
+            AppError::CommandExecutionError(
+                "Could not generate a random salt and nonce".to_string(),
+            )
+        })?;
+    let mut sealed = plaintext.as_bytes().to_vec();
+    cipher(key_material, &salt)?
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(setting.as_bytes()),
+            &mut sealed,
+        )
+        .map_err(|_| AppError::CommandExecutionError("Encryption failed".to_string()))?;
+
+    let mut payload = vec![PAYLOAD_VERSION];
+    payload.extend(salt);
+    payload.extend(nonce);
+    payload.extend(sealed);
+    Ok(format!("{ENCRYPTED_PREFIX}{}", STANDARD.encode(payload)))
+}
+
+/// Decrypts an `enc:` value made for `setting` with key material already fetched.
+pub fn decrypt_with(value: &str, key_material: &str, setting: &str) -> Result<String, AppError> {
+    decrypt_with_context(value, key_material, setting, setting)
+}
+
+/// Decrypts like [`decrypt_with`], naming `context` rather than the setting in errors,
+/// e.g. the file the value was read from.
+pub fn decrypt_with_context(
+    value: &str,
+    key_material: &str,
+    setting: &str,
+    context: &str,
+) -> Result<String, AppError> {
+    let invalid = |reason: &str| {
+        AppError::GeneralConfigError(format!("Could not decrypt {context}: {reason}"))
+    };
+    let payload = STANDARD
+        .decode(value.trim_start_matches(ENCRYPTED_PREFIX))
+        .map_err(|_| invalid("not valid base64"))?;
+    let Some((&version, payload)) = payload.split_first() else {
+        return Err(invalid("too short"));
+    };
+    if version != PAYLOAD_VERSION {
+        return Err(invalid(
+            "it was encrypted by an older hubuum-cli; encrypt it again with 'config encrypt'",
+        ));
+    }
+    if payload.len() < SALT_LEN + NONCE_LEN {
+        return Err(invalid("too short"));
+    }
+    let (salt, payload) = payload.split_at(SALT_LEN);
+    let (nonce, sealed) = payload.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| invalid("bad nonce"))?;
+    let mut sealed = sealed.to_vec();
+    let plaintext = cipher(key_material, salt)?
+        .open_in_place(nonce, Aad::from(setting.as_bytes()), &mut sealed)
+        .map_err(|_| invalid("wrong key or corrupted value"))?;
+    String::from_utf8(plaintext.to_vec()).map_err(|_| invalid("not UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_round_trip_only_with_the_same_key_and_setting() {
+        let encrypted = encrypt_with("hunter2", "key-one", "server.password").unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.contains("hunter2"));
+        assert_ne!(
+            encrypted,
+            encrypt_with("hunter2", "key-one", "server.password").unwrap()
+        );
+
+        assert_eq!(
+            decrypt_with(&encrypted, "key-one", "server.password").unwrap(),
+            "hunter2"
+        );
+        assert!(decrypt_with(&encrypted, "key-two", "server.password").is_err());
+        assert!(decrypt_with(&encrypted, "key-one", "token.json").is_err());
+        assert!(decrypt_with("enc:not base64!", "key-one", "server.password").is_err());
+    }
+
+    #[test]
+    fn payloads_carry_a_version_and_a_random_salt() {
+        let payload = |value: &str| {
+            STANDARD
+                .decode(value.trim_start_matches(ENCRYPTED_PREFIX))
+                .unwrap()
+        };
+        let first = payload(&encrypt_with("hunter2", "key", "server.password").unwrap());
+        let second = payload(&encrypt_with("hunter2", "key", "server.password").unwrap());
+        assert_eq!(first[0], PAYLOAD_VERSION);
+        assert_ne!(first[1..=SALT_LEN], second[1..=SALT_LEN]);
+
+        let unsalted = format!("{ENCRYPTED_PREFIX}{}", STANDARD.encode([7u8; 40]));
+        let error = decrypt_with(&unsalted, "key", "server.password").unwrap_err();
+        assert!(error.to_string().contains("older hubuum-cli"));
+    }
+
+    #[test]
+    fn plain_values_pass_through_and_encrypted_ones_need_a_key_command() {
+        assert_eq!(
+            decrypt_value("hunter2", None, "server.password").unwrap(),
+            "hunter2"
+        );
+        let encrypted = encrypt_with("hunter2", "key", "server.password").unwrap();
+        assert!(decrypt_value(&encrypted, None, "server.password").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn key_commands_supply_the_key() {
+        let encrypted =
+            encrypt_value("hunter2", "printf 'k3y\\nignored'", "server.password").unwrap();
+        assert_eq!(
+            decrypt_with(&encrypted, "k3y", "server.password").unwrap(),
+            "hunter2"
+        );
+        assert_eq!(
+            decrypt_value(&encrypted, Some("echo k3y"), "server.password").unwrap(),
+            "hunter2"
+        );
+        assert!(secret_from_command("server.encryption_key_command", "true").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn secrets_can_be_piped_to_a_command() {
+        let directory = tempfile::tempdir().expect("temporary directory should be created");
+        let target = directory.path().join("delivered");
+        let command = format!("sh -c 'cat > \"{}\"'", target.display());
+
+        secret_to_command("user.credential_delivery", &command, "hunter2").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "hunter2");
+        assert!(secret_to_command("user.credential_delivery", "false", "x").is_err());
+        assert!(secret_to_command("user.credential_delivery", "'unbalanced", "x").is_err());
+    }
+}
@@ -0,0 +1,547 @@
+use std::collections::HashSet;
+use std::fs::{read_to_string, remove_file, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{scope, sleep};
+use std::time::{Duration, Instant};
+
+use hubuum_client::ApiError as ClientApiError;
+use log::warn;
+use serde_json::{from_str, to_string};
+
+use crate::config::BulkConfig;
+use crate::errors::AppError;
+use crate::models::RateLimit;
+
+use super::gateway::{is_unauthorized, Session};
+
+/// Extra attempts granted to a single item after the server answers 429.
+const RATE_LIMITED_RETRIES: usize = 5;
+/// Spacing used as the starting point when an unthrottled runner is told to slow down.
+const SLOWDOWN_FLOOR: Duration = Duration::from_millis(50);
+/// Upper bound for the spacing between requests after repeated slowdowns.
+const SLOWDOWN_CEILING: Duration = Duration::from_secs(5);
+/// Logins a single item may trigger after the server rejects the session token.
+const UNAUTHORIZED_RETRIES: u32 = 3;
+/// First wait before renewing a rejected login; it doubles with every further rejection.
+const RENEWAL_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Runs independent API operations with a bounded worker pool and a shared request budget.
+///
+/// Results are returned in item order. When an operation fails with HTTP 429 the shared
+/// request spacing is doubled and the item is retried, so all workers back off together.
+/// With a session attached, an HTTP 401 renews the login once for all workers and the
+/// item is retried with the new token.
+pub(crate) struct BulkRunner {
+    concurrency: usize,
+    throttle: Throttle,
+    session: Option<Arc<Session>>,
+}
+
+struct Throttle {
+    state: Mutex<ThrottleState>,
+}
+
+struct ThrottleState {
+    interval: Option<Duration>,
+    next_slot: Instant,
+}
+
+impl BulkRunner {
+    pub(crate) fn new(concurrency: u16, rate_limit: RateLimit) -> Self {
+        Self {
+            concurrency: usize::from(concurrency.max(1)),
+            throttle: Throttle::new(rate_limit.interval()),
+            session: None,
+        }
+    }
+
+    pub(crate) fn with_session(mut self, session: Arc<Session>) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    pub(crate) fn from_config(config: &BulkConfig) -> Self {
+        Self::new(config.concurrency, config.rate_limit)
+    }
+
+    pub(crate) fn run<T, R, F>(&self, items: &[T], operation: F) -> Vec<Result<R, AppError>>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> Result<R, AppError> + Sync,
+    {
+        let workers = self.concurrency.min(items.len());
+        if workers <= 1 {
+            return items
+                .iter()
+                .map(|item| self.run_item(item, &operation))
+                .collect();
+        }
+
+        let next = AtomicUsize::new(0);
+        let mut slots: Vec<Option<Result<R, AppError>>> = items.iter().map(|_| None).collect();
+        scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(item) = items.get(index) else {
+                                break;
+                            };
+                            done.push((index, self.run_item(item, &operation)));
+                        }
+                        done
+                    })
+                })
+                .collect();
+            for handle in handles {
+                for (index, result) in handle.join().expect("bulk worker should not panic") {
+                    slots[index] = Some(result);
+                }
+            }
+        });
+
+        slots
+            .into_iter()
+            .map(|slot| slot.expect("every bulk item should produce a result"))
+            .collect()
+    }
+
+    /// Like [`BulkRunner::run`], but skips the items whose `key` `checkpoint` holds from
+    /// an earlier run and records the key of every item that succeeds. Skipped items have
+    /// no result.
+    pub(crate) fn run_resumable<T, R, F, K>(
+        &self,
+        items: &[T],
+        checkpoint: &Checkpoint,
+        key: K,
+        operation: F,
+    ) -> Vec<Option<Result<R, AppError>>>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> Result<R, AppError> + Sync,
+        K: Fn(&T) -> String + Sync,
+    {
+        let pending = items
+            .iter()
+            .filter(|item| !checkpoint.is_completed(&key(item)))
+            .collect::<Vec<_>>();
+        let mut results = self
+            .run(&pending, |item| {
+                let result = operation(item)?;
+                checkpoint.record_or_warn(&key(item));
+                Ok(result)
+            })
+            .into_iter();
+        items
+            .iter()
+            .map(|item| {
+                if checkpoint.is_completed(&key(item)) {
+                    None
+                } else {
+                    results.next()
+                }
+            })
+            .collect()
+    }
+
+    /// Runs one item under the same request budget as `run`, for callers that must look
+    /// at each result before sending the next item.
+    pub(crate) fn run_one<T, R, F>(&self, item: &T, operation: F) -> Result<R, AppError>
+    where
+        F: Fn(&T) -> Result<R, AppError>,
+    {
+        self.run_item(item, &operation)
+    }
+
+    fn run_item<T, R, F>(&self, item: &T, operation: &F) -> Result<R, AppError>
+    where
+        F: Fn(&T) -> Result<R, AppError>,
+    {
+        let mut retries = 0;
+        let mut renewals = 0;
+        loop {
+            self.throttle.acquire();
+            let generation = self.session.as_ref().map(|session| session.generation());
+            match operation(item) {
+                Err(error) if is_rate_limited(&error) && retries < RATE_LIMITED_RETRIES => {
+                    retries += 1;
+                    let interval = self.throttle.slow_down();
+                    warn!("Server rate limit reached; spacing bulk requests {interval:?} apart");
+                }
+                Err(error) if is_unauthorized(&error) && renewals < UNAUTHORIZED_RETRIES => {
+                    let (Some(session), Some(generation)) = (&self.session, generation) else {
+                        return Err(error);
+                    };
+                    sleep(RENEWAL_BACKOFF * 2u32.pow(renewals));
+                    renewals += 1;
+                    session.renew(generation)?;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Keys of the bulk items that completed, appended to a file as each one finishes, so an
+/// interrupted run can be resumed without sending them again.
+pub(crate) struct Checkpoint {
+    path: PathBuf,
+    /// Keys completed by earlier runs; keys recorded by this run are only written out.
+    completed: HashSet<String>,
+    file: Mutex<File>,
+}
+
+impl Checkpoint {
+    /// Opens the checkpoint at `path`. With `resume` the keys it holds count as completed
+    /// and new ones are added; otherwise it starts out empty.
+    pub(crate) fn open(path: impl Into<PathBuf>, resume: bool) -> Result<Self, AppError> {
+        let path = path.into();
+        let mut completed = HashSet::new();
+        if resume {
+            let contents = read_to_string(&path).map_err(|error| match error.kind() {
+                ErrorKind::NotFound => AppError::InvalidOption(format!(
+                    "--resume needs the checkpoint {} of an earlier run",
+                    path.display()
+                )),
+                _ => error.into(),
+            })?;
+            for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+                completed.insert(from_str(line)?);
+            }
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(resume)
+            .write(!resume)
+            .truncate(!resume)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            completed,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether an earlier run completed the item with `key`.
+    pub(crate) fn is_completed(&self, key: &str) -> bool {
+        self.completed.contains(key)
+    }
+
+    pub(crate) fn record(&self, key: &str) -> Result<(), AppError> {
+        let mut file = self.file.lock().map_err(|_| AppError::LockError)?;
+        writeln!(file, "{}", to_string(key)?)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    /// Records `key`, only warning when that fails, as the item itself did complete.
+    pub(crate) fn record_or_warn(&self, key: &str) {
+        if let Err(error) = self.record(key) {
+            warn!(
+                "Could not record '{key}' in checkpoint {}: {error}",
+                self.path.display()
+            );
+        }
+    }
+
+    /// Deletes the checkpoint once every item has completed.
+    pub(crate) fn remove(self) -> Result<(), AppError> {
+        drop(self.file);
+        match remove_file(&self.path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Throttle {
+    fn new(interval: Option<Duration>) -> Self {
+        Self {
+            state: Mutex::new(ThrottleState {
+                interval,
+                next_slot: Instant::now(),
+            }),
+        }
+    }
+
+    fn acquire(&self) {
+        let wait = {
+            let mut state = self
+                .state
+                .lock()
+                .expect("throttle lock should not be poisoned");
+            let Some(interval) = state.interval else {
+                return;
+            };
+            let now = Instant::now();
+            let slot = state.next_slot.max(now);
+            state.next_slot = slot + interval;
+            slot - now
+        };
+        if !wait.is_zero() {
+            sleep(wait);
+        }
+    }
+
+    fn slow_down(&self) -> Duration {
+        let mut state = self
+            .state
+            .lock()
+            .expect("throttle lock should not be poisoned");
+        let interval = state
+            .interval
+            .map_or(SLOWDOWN_FLOOR, |interval| interval.max(SLOWDOWN_FLOOR) * 2)
+            .min(SLOWDOWN_CEILING);
+        state.interval = Some(interval);
+        state.next_slot = state.next_slot.max(Instant::now() + interval);
+        interval
+    }
+}
+
+fn is_rate_limited(error: &AppError) -> bool {
+    match error.api_error() {
+        Some(ClientApiError::HttpWithBody { status, .. }) => status.as_u16() == 429,
+        Some(ClientApiError::RetryExhausted { last_error, .. }) => last_error.contains("429"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use hubuum_client::{blocking::Client as BlockingClient, Token};
+    use reqwest::{Method, StatusCode};
+
+    use super::*;
+
+    fn too_many_requests() -> AppError {
+        AppError::ApiError(ClientApiError::HttpWithBody {
+            method: Method::GET,
+            url: "http://localhost/api/v1/classes".to_string(),
+            status: StatusCode::TOO_MANY_REQUESTS,
+            message: "Too Many Requests".to_string(),
+            body: String::new(),
+        })
+    }
+
+    fn unauthorized() -> AppError {
+        AppError::ApiError(ClientApiError::HttpWithBody {
+            method: Method::GET,
+            url: "http://localhost/api/v1/classes".to_string(),
+            status: StatusCode::UNAUTHORIZED,
+            message: "Unauthorized".to_string(),
+            body: String::new(),
+        })
+    }
+
+    fn client(token: &str) -> BlockingClient<hubuum_client::Authenticated> {
+        BlockingClient::from_url("http://localhost")
+            .expect("client should build")
+            .authenticate(Token::new(token))
+    }
+
+    #[test]
+    fn run_preserves_item_order_across_workers() {
+        let runner = BulkRunner::new(4, "0/s".parse().expect("rate limit"));
+        let items: Vec<u32> = (0..32).collect();
+
+        let results = runner.run(&items, |item| Ok(item * 2));
+
+        let values: Vec<u32> = results
+            .into_iter()
+            .map(|result| result.expect("operation should succeed"))
+            .collect();
+        assert_eq!(
+            values,
+            items.iter().map(|item| item * 2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn run_never_exceeds_configured_concurrency() {
+        let runner = BulkRunner::new(3, "0/s".parse().expect("rate limit"));
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let items: Vec<u32> = (0..12).collect();
+
+        runner.run(&items, |_| {
+            let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            sleep(Duration::from_millis(5));
+            active.fetch_sub(1, Ordering::SeqCst);
+            Ok(())
+        });
+
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[test]
+    fn run_spaces_requests_according_to_rate_limit() {
+        let runner = BulkRunner::new(4, "100/s".parse().expect("rate limit"));
+        let items: Vec<u32> = (0..5).collect();
+        let started = Instant::now();
+
+        runner.run(&items, |_| Ok(()));
+
+        assert!(started.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[test]
+    fn rate_limited_items_are_retried_after_slowing_down() {
+        let runner = BulkRunner::new(1, "0/s".parse().expect("rate limit"));
+        let attempts = AtomicUsize::new(0);
+
+        let results = runner.run(&[1], |_| {
+            if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(too_many_requests())
+            } else {
+                Ok("done")
+            }
+        });
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(results[0].as_ref().ok(), Some(&"done"));
+        let interval = runner.throttle.state.lock().expect("lock").interval;
+        assert_eq!(interval, Some(SLOWDOWN_FLOOR * 2));
+    }
+
+    #[test]
+    fn items_run_one_at_a_time_share_the_request_budget() {
+        let runner = BulkRunner::new(4, "100/s".parse().expect("rate limit"));
+        let started = Instant::now();
+
+        for item in 0..5 {
+            runner
+                .run_one(&item, |_| Ok(()))
+                .expect("operation should succeed");
+        }
+
+        assert!(started.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[test]
+    fn other_errors_are_not_retried() {
+        let runner = BulkRunner::new(2, "0/s".parse().expect("rate limit"));
+        let attempts = AtomicUsize::new(0);
+
+        let results = runner.run(&[1], |_| -> Result<(), AppError> {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(AppError::EntityNotFound("missing".to_string()))
+        });
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        assert!(results[0].is_err());
+    }
+
+    #[test]
+    fn rejected_workers_share_a_single_login() {
+        let session = Arc::new(Session::new(Arc::new(client("expired"))));
+        let logins = Arc::new(AtomicUsize::new(0));
+        let counter = logins.clone();
+        session.set_relogin(Box::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            sleep(Duration::from_millis(20));
+            Ok(client("renewed"))
+        }));
+        let runner =
+            BulkRunner::new(4, "0/s".parse().expect("rate limit")).with_session(session.clone());
+        let items: Vec<u32> = (0..8).collect();
+
+        let results = runner.run(&items, |item| {
+            if session.client().token() == "renewed" {
+                Ok(*item)
+            } else {
+                Err(unauthorized())
+            }
+        });
+
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(logins.load(Ordering::SeqCst), 1);
+        assert_eq!(session.generation(), 1);
+    }
+
+    #[test]
+    fn switched_sessions_renew_as_the_new_login() {
+        let session = Session::new(Arc::new(client("alice")));
+        session.set_relogin(Box::new(|| Ok(client("alice-renewed"))));
+
+        session.replace(client("bob"), Box::new(|| Ok(client("bob-renewed"))));
+        assert_eq!(session.client().token(), "bob");
+        assert_eq!(session.generation(), 1);
+
+        session.renew(1).expect("renewal should succeed");
+        assert_eq!(session.client().token(), "bob-renewed");
+    }
+
+    #[test]
+    fn resumed_runs_skip_the_items_an_interrupted_run_completed() {
+        let directory = tempfile::tempdir().expect("temporary directory should be created");
+        let path = directory.path().join("hosts.csv.checkpoint");
+        let runner = BulkRunner::new(2, "0/s".parse().expect("rate limit"));
+        let items: Vec<u32> = (0..6).collect();
+        let key = |item: &u32| format!("item {item}");
+
+        let checkpoint = Checkpoint::open(&path, false).expect("checkpoint should open");
+        let results = runner.run_resumable(&items, &checkpoint, key, |item| {
+            if *item >= 4 {
+                Err(AppError::CommandExecutionError("interrupted".to_string()))
+            } else {
+                Ok(*item)
+            }
+        });
+        assert!(results.iter().all(Option::is_some));
+        assert_eq!(
+            results
+                .iter()
+                .flatten()
+                .filter(|result| result.is_ok())
+                .count(),
+            4
+        );
+        drop(checkpoint);
+
+        let attempted = Mutex::new(Vec::new());
+        let checkpoint = Checkpoint::open(&path, true).expect("checkpoint should reopen");
+        let results = runner.run_resumable(&items, &checkpoint, key, |item| {
+            attempted.lock().expect("lock").push(*item);
+            Ok(*item)
+        });
+        let mut attempted = attempted.into_inner().expect("lock");
+        attempted.sort_unstable();
+        assert_eq!(attempted, vec![4, 5]);
+        assert!(results[..4].iter().all(Option::is_none));
+        assert!(results[4..]
+            .iter()
+            .all(|result| matches!(result, Some(Ok(_)))));
+
+        checkpoint.remove().expect("checkpoint should be removed");
+        assert!(!path.exists());
+        assert!(Checkpoint::open(&path, true).is_err());
+    }
+
+    #[test]
+    fn unauthorized_errors_without_a_session_are_returned() {
+        let runner = BulkRunner::new(1, "0/s".parse().expect("rate limit"));
+        let attempts = AtomicUsize::new(0);
+
+        let results = runner.run(&[1], |_| -> Result<(), AppError> {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(unauthorized())
+        });
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        assert!(results[0].is_err());
+    }
+}
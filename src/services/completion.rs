@@ -1,6 +1,10 @@
 use std::collections::{BTreeSet, HashMap};
-use std::sync::{Arc, RwLock};
+use std::mem::take;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
 
+use hubuum_filter::OutputEnvelope;
 use serde_json::Value;
 use tokio::runtime::Handle;
 use tokio::task::spawn_blocking;
@@ -12,11 +16,23 @@ use crate::domain::{
 use crate::errors::AppError;
 use crate::json_schema::schema_json_pointers;
 use crate::list_query::{ListQuery, SortClause, SortDirectionArg};
+use crate::offline::{is_offline, save_names, saved_names};
 use crate::services::{AuditListInput, AuditScope, ListTasksInput};
 
 use super::gateway::HubuumGateway;
 use super::AppServices;
 
+/// Most names remembered per completion source from earlier command output.
+const SEEN_NAME_LIMIT: usize = 1000;
+
+/// Name lists fetched in the background once the REPL starts and after every
+/// command that changes the server.
+const PREFETCHED_KINDS: [CompletionKind; 3] = [
+    CompletionKind::Classes,
+    CompletionKind::Collections,
+    CompletionKind::Groups,
+];
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CompletionItem {
     pub value: String,
@@ -27,11 +43,25 @@ pub struct CompletionItem {
 pub struct CompletionContext {
     services: Arc<AppServices>,
     runtime: Handle,
+    fixed_only: bool,
+}
+
+/// Names of one completion source as the server listed them.
+#[derive(Clone)]
+struct FetchedNames {
+    fetched_at: Instant,
+    names: Vec<String>,
+}
+
+impl FetchedNames {
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        self.fetched_at.elapsed() < ttl
+    }
 }
 
 #[derive(Clone, Default)]
 struct CompletionSnapshot {
-    simple_sources: HashMap<CompletionKind, Vec<String>>,
+    simple_sources: HashMap<CompletionKind, FetchedNames>,
     objects_by_class: HashMap<String, Vec<String>>,
     event_subscriptions_by_collection: HashMap<String, Vec<String>>,
     class_schemas: HashMap<String, Option<Value>>,
@@ -42,9 +72,30 @@ struct CompletionSnapshot {
     event_delivery_ids: Option<Vec<String>>,
 }
 
+/// Entity names shown by earlier commands, offered before asking the server.
+///
+/// Unlike the snapshot these survive the invalidation after every command, so a
+/// `class list` keeps paying off; commands that change the server forget them.
+#[derive(Default)]
+struct SeenNames {
+    simple_sources: HashMap<CompletionKind, Vec<String>>,
+    objects_by_class: HashMap<String, Vec<String>>,
+}
+
+/// What the store needs to fetch name lists without a completion request.
+struct Prefetcher {
+    gateway: Arc<HubuumGateway>,
+    runtime: Handle,
+}
+
 #[derive(Clone, Default)]
 pub(crate) struct CompletionStore {
     snapshot: Arc<RwLock<CompletionSnapshot>>,
+    seen: Arc<RwLock<SeenNames>>,
+    /// Bumped whenever name lists are forgotten, so fetches that started earlier
+    /// do not store names the server may no longer have.
+    generation: Arc<AtomicU64>,
+    prefetcher: Arc<OnceLock<Prefetcher>>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -59,9 +110,50 @@ enum CompletionKind {
     RemoteTargets,
 }
 
+impl CompletionKind {
+    /// Names the source in the offline cache.
+    fn source(self) -> &'static str {
+        match self {
+            Self::Groups => "groups",
+            Self::Classes => "classes",
+            Self::Collections => "collections",
+            Self::EventSinks => "event_sinks",
+            Self::ExportTemplates => "export_templates",
+            Self::Users => "users",
+            Self::ServiceAccounts => "service_accounts",
+            Self::RemoteTargets => "remote_targets",
+        }
+    }
+}
+
 impl CompletionContext {
     pub(crate) fn new(services: Arc<AppServices>, runtime: Handle) -> Self {
-        Self { services, runtime }
+        Self {
+            services,
+            runtime,
+            fixed_only: false,
+        }
+    }
+
+    /// This context limited to fixed values: completers offer nothing from the server,
+    /// from earlier output, or from the file system.
+    pub fn fixed_values_only(&self) -> Self {
+        Self {
+            fixed_only: true,
+            ..self.clone()
+        }
+    }
+
+    pub fn offers_fixed_values_only(&self) -> bool {
+        self.fixed_only
+    }
+
+    /// Fetches class, collection, and group names on a worker thread now and after
+    /// every command that changes the server, so completing them does not wait.
+    pub fn prefetch_names(&self) {
+        self.services
+            .completion_store()
+            .start_prefetching(self.services.gateway(), self.runtime.clone());
     }
 
     pub fn groups(&self, prefix: &str) -> Vec<String> {
@@ -97,14 +189,21 @@ impl CompletionContext {
     }
 
     pub fn objects_from_class(&self, prefix: &str, parts: &[String], source: &str) -> Vec<String> {
-        if get_config().completion.disable_api_related {
-            return Vec::new();
-        }
-
         let Some(class_name) = option_value(parts, source) else {
             return Vec::new();
         };
 
+        if self.fixed_only {
+            return Vec::new();
+        }
+        let seen = self
+            .services
+            .completion_store()
+            .seen_objects(&class_name, prefix);
+        if !seen.is_empty() || api_completion_disabled() {
+            return seen;
+        }
+
         if prefix.is_empty() {
             let fetched = self
                 .runtime
@@ -135,7 +234,7 @@ impl CompletionContext {
         prefix: &str,
         parts: &[String],
     ) -> Vec<String> {
-        if get_config().completion.disable_api_related {
+        if self.server_disabled() {
             return Vec::new();
         }
 
@@ -154,7 +253,7 @@ impl CompletionContext {
     }
 
     pub fn task_ids(&self, prefix: &str) -> Vec<CompletionItem> {
-        if get_config().completion.disable_api_related {
+        if self.server_disabled() {
             return Vec::new();
         }
 
@@ -180,7 +279,7 @@ impl CompletionContext {
     }
 
     pub fn audit_event_ids(&self, prefix: &str) -> Vec<String> {
-        if get_config().completion.disable_api_related {
+        if self.server_disabled() {
             return Vec::new();
         }
 
@@ -195,7 +294,7 @@ impl CompletionContext {
     }
 
     pub fn event_delivery_ids(&self, prefix: &str) -> Vec<String> {
-        if get_config().completion.disable_api_related {
+        if self.server_disabled() {
             return Vec::new();
         }
 
@@ -210,7 +309,7 @@ impl CompletionContext {
     }
 
     pub fn class_schema(&self, class_name: &str) -> Option<Option<Value>> {
-        if get_config().completion.disable_api_related {
+        if self.server_disabled() {
             return None;
         }
 
@@ -224,7 +323,7 @@ impl CompletionContext {
     }
 
     pub fn computed_field_paths(&self, prefix: &str, parts: &[String]) -> Vec<String> {
-        if get_config().completion.disable_api_related {
+        if self.server_disabled() {
             return Vec::new();
         }
 
@@ -249,7 +348,7 @@ impl CompletionContext {
     }
 
     pub fn computed_sort_fields(&self, parts: &[String]) -> Vec<String> {
-        if get_config().completion.disable_api_related {
+        if self.server_disabled() {
             return Vec::new();
         }
         let Some(class_name) = class_name_from_parts(parts) else {
@@ -264,10 +363,21 @@ impl CompletionContext {
             .unwrap_or_default()
     }
 
+    fn server_disabled(&self) -> bool {
+        self.fixed_only || api_completion_disabled()
+    }
+
     fn complete(&self, prefix: &str, kind: CompletionKind) -> Vec<String> {
-        if get_config().completion.disable_api_related {
+        if self.fixed_only {
             return Vec::new();
         }
+        let seen = self.services.completion_store().seen(kind, prefix);
+        if seen.is_empty() && is_offline() {
+            return filter_prefix(&saved_names(kind.source()), prefix);
+        }
+        if !seen.is_empty() || api_completion_disabled() {
+            return seen;
+        }
 
         let fetched = self
             .runtime
@@ -282,10 +392,107 @@ impl CompletionContext {
 }
 
 impl CompletionStore {
+    /// Drops what completion fetched for earlier command lines, except name lists
+    /// younger than `completion.cache_time` seconds.
     pub(crate) fn invalidate_all(&self) {
+        let ttl = completion_cache_ttl();
+        if let Ok(mut snapshot) = self.snapshot.write() {
+            let mut names = take(&mut snapshot.simple_sources);
+            names.retain(|_, fetched| fetched.is_fresh(ttl));
+            *snapshot = CompletionSnapshot {
+                simple_sources: names,
+                ..CompletionSnapshot::default()
+            };
+        }
+    }
+
+    /// Drops every name list, such as after a command created or deleted entities,
+    /// and fetches the prefetched ones again.
+    pub(crate) fn forget_names(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        if let Ok(mut snapshot) = self.snapshot.write() {
+            snapshot.simple_sources.clear();
+        }
+        if let Ok(mut seen) = self.seen.write() {
+            *seen = SeenNames::default();
+        }
+        self.prefetch();
+    }
+
+    /// Drops everything fetched or seen, as another login may see other entities,
+    /// and fetches the prefetched name lists again.
+    pub(crate) fn forget_login(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
         if let Ok(mut snapshot) = self.snapshot.write() {
             *snapshot = CompletionSnapshot::default();
         }
+        if let Ok(mut seen) = self.seen.write() {
+            *seen = SeenNames::default();
+        }
+        self.prefetch();
+    }
+
+    fn start_prefetching(&self, gateway: Arc<HubuumGateway>, runtime: Handle) {
+        if self.prefetcher.set(Prefetcher { gateway, runtime }).is_ok() {
+            self.prefetch();
+        }
+    }
+
+    /// Loads the prefetched name lists in the background. Failures are left for a
+    /// completion request to retry against the server.
+    pub(crate) fn prefetch(&self) {
+        let Some(prefetcher) = self.prefetcher.get() else {
+            return;
+        };
+        if api_completion_disabled() {
+            return;
+        }
+        for kind in PREFETCHED_KINDS {
+            let store = self.clone();
+            let gateway = prefetcher.gateway.clone();
+            prefetcher.runtime.spawn(async move {
+                let _ = store.load(gateway, kind).await;
+            });
+        }
+    }
+
+    /// Remembers the names shown by a successful command, or forgets every remembered
+    /// name when the command changed the server.
+    pub(crate) fn record_seen_names(
+        &self,
+        command_path: &[String],
+        mutating: bool,
+        semantic: &[OutputEnvelope],
+    ) {
+        if mutating {
+            self.forget_names();
+        } else if let Ok(mut seen) = self.seen.write() {
+            seen.record(command_path, semantic);
+        }
+    }
+
+    fn seen(&self, kind: CompletionKind, prefix: &str) -> Vec<String> {
+        self.seen
+            .read()
+            .ok()
+            .and_then(|seen| {
+                seen.simple_sources
+                    .get(&kind)
+                    .map(|names| filter_prefix(names, prefix))
+            })
+            .unwrap_or_default()
+    }
+
+    fn seen_objects(&self, class_name: &str, prefix: &str) -> Vec<String> {
+        self.seen
+            .read()
+            .ok()
+            .and_then(|seen| {
+                seen.objects_by_class
+                    .get(class_name)
+                    .map(|names| filter_prefix(names, prefix))
+            })
+            .unwrap_or_default()
     }
 
     async fn load(
@@ -297,8 +504,9 @@ impl CompletionStore {
             return Ok(cached);
         }
 
+        let generation = self.generation.load(Ordering::SeqCst);
         let fetched = spawn_blocking(move || -> Result<Vec<String>, AppError> {
-            match kind {
+            let names = match kind {
                 CompletionKind::Groups => gateway.list_group_names(),
                 CompletionKind::Classes => gateway.list_class_names(),
                 CompletionKind::Collections => gateway.list_collection_names(),
@@ -307,18 +515,34 @@ impl CompletionStore {
                 CompletionKind::Users => gateway.list_user_names(),
                 CompletionKind::ServiceAccounts => gateway.list_service_account_names(),
                 CompletionKind::RemoteTargets => gateway.list_remote_target_names(),
-            }
+            }?;
+            save_names(kind.source(), &names);
+            Ok(names)
         })
         .await
         .map_err(|err| AppError::CommandExecutionError(err.to_string()))??;
 
-        if let Ok(mut snapshot) = self.snapshot.write() {
-            snapshot.simple_sources.insert(kind, fetched.clone());
-        }
-
+        self.store_names(kind, generation, fetched.clone());
         Ok(fetched)
     }
 
+    /// Keeps names fetched while the store was at `generation`, unless they have
+    /// been forgotten since.
+    fn store_names(&self, kind: CompletionKind, generation: u64, names: Vec<String>) {
+        let Ok(mut snapshot) = self.snapshot.write() else {
+            return;
+        };
+        if self.generation.load(Ordering::SeqCst) == generation {
+            snapshot.simple_sources.insert(
+                kind,
+                FetchedNames {
+                    fetched_at: Instant::now(),
+                    names,
+                },
+            );
+        }
+    }
+
     async fn load_objects_for_class(
         &self,
         gateway: Arc<HubuumGateway>,
@@ -601,10 +825,80 @@ impl CompletionStore {
             return None;
         };
 
-        snapshot.simple_sources.get(&kind).cloned()
+        // Without a TTL the names last until the next command, as other sources do.
+        let ttl = completion_cache_ttl();
+        snapshot
+            .simple_sources
+            .get(&kind)
+            .filter(|fetched| ttl.is_zero() || fetched.is_fresh(ttl))
+            .map(|fetched| fetched.names.clone())
+    }
+}
+
+/// Whether `completion.disable_api_related` limits completion to names already known,
+/// without asking the server.
+fn api_completion_disabled() -> bool {
+    get_config().completion.disable_api_related || is_offline()
+}
+
+fn completion_cache_ttl() -> Duration {
+    Duration::from_secs(get_config().completion.cache_time)
+}
+
+impl SeenNames {
+    /// Records names from the primary output of `list` and `show` commands in the
+    /// class, collection, group, user, and object scopes.
+    ///
+    /// Only the first envelope counts: later ones hold related entities, such as the
+    /// members listed by `group show`.
+    fn record(&mut self, command_path: &[String], semantic: &[OutputEnvelope]) {
+        if !matches!(
+            command_path.last().map(String::as_str),
+            Some("list" | "show")
+        ) {
+            return;
+        }
+        let kind = match command_path.first().map(String::as_str) {
+            Some("class") => Some(CompletionKind::Classes),
+            Some("collection") => Some(CompletionKind::Collections),
+            Some("group") => Some(CompletionKind::Groups),
+            Some("user") => Some(CompletionKind::Users),
+            Some("object") => None,
+            _ => return,
+        };
+        let Some(envelope) = semantic.first() else {
+            return;
+        };
+
+        let records = match &envelope.value {
+            Value::Array(records) => records.iter().collect::<Vec<_>>(),
+            record => vec![record],
+        };
+        for record in records {
+            let Some(name) = record_text(record, &["Name", "name", "groupname", "username"]) else {
+                continue;
+            };
+            let names = match kind {
+                Some(kind) => self.simple_sources.entry(kind).or_default(),
+                None => match record_text(record, &["Class", "class"]) {
+                    Some(class) => self.objects_by_class.entry(class).or_default(),
+                    None => continue,
+                },
+            };
+            names.retain(|seen| *seen != name);
+            names.insert(0, name);
+            names.truncate(SEEN_NAME_LIMIT);
+        }
     }
 }
 
+fn record_text(record: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| record.get(*key).and_then(Value::as_str))
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
 fn filter_prefix(values: &[String], prefix: &str) -> Vec<String> {
     values
         .iter()
@@ -692,10 +986,171 @@ fn json_record_i64(record: &JsonRecord, keys: &[&str]) -> Option<i64> {
 #[cfg(test)]
 mod tests {
     use std::cell::Cell;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
 
+    use hubuum_client::{blocking::Client as BlockingClient, Authenticated, Token};
     use serde_json::json;
+    use tokio::runtime::Runtime;
+
+    use hubuum_filter::OutputEnvelope;
+
+    use super::{
+        filter_prefix, json_pointer_completion_candidates, pointers_from_schema_or_else,
+        CompletionKind, CompletionStore, FetchedNames,
+    };
+    use crate::errors::AppError;
+    use crate::services::AppServices;
+
+    fn path(parts: &[&str]) -> Vec<String> {
+        parts.iter().map(|part| (*part).to_string()).collect()
+    }
 
-    use super::{filter_prefix, json_pointer_completion_candidates, pointers_from_schema_or_else};
+    #[test]
+    fn names_from_listings_are_offered_until_the_server_changes() {
+        let store = CompletionStore::default();
+        store.record_seen_names(
+            &path(&["object", "list"]),
+            false,
+            &[OutputEnvelope::rows(
+                vec![
+                    json!({"Name": "web01", "Class": "Hosts"}),
+                    json!({"Name": "db01", "Class": "Hosts"}),
+                    json!({"Name": "B701", "Class": "Rooms"}),
+                ],
+                Vec::new(),
+            )],
+        );
+        store.record_seen_names(
+            &path(&["group", "show"]),
+            false,
+            &[
+                OutputEnvelope::detail(json!({"Name": "admins"}), Vec::new()),
+                OutputEnvelope::rows(vec![json!({"Name": "alice"})], Vec::new()),
+            ],
+        );
+        store.record_seen_names(
+            &path(&["class", "list"]),
+            false,
+            &[OutputEnvelope::detail(
+                json!([{"name": "Hosts"}, {"name": "Rooms"}]),
+                Vec::new(),
+            )],
+        );
+
+        assert_eq!(store.seen_objects("Hosts", "w"), vec!["web01".to_string()]);
+        assert_eq!(store.seen_objects("Hosts", "").len(), 2);
+        assert_eq!(store.seen(CompletionKind::Groups, ""), vec!["admins"]);
+        assert_eq!(store.seen(CompletionKind::Classes, "R"), vec!["Rooms"]);
+
+        store.invalidate_all();
+        assert_eq!(store.seen(CompletionKind::Classes, "").len(), 2);
+
+        store.record_seen_names(&path(&["object", "delete"]), true, &[]);
+        assert!(store.seen_objects("Hosts", "").is_empty());
+        assert!(store.seen(CompletionKind::Classes, "").is_empty());
+    }
+
+    #[test]
+    fn fetched_names_outlive_commands_until_they_expire_or_the_server_changes() {
+        let store = CompletionStore::default();
+        let fetched = |age: Duration| FetchedNames {
+            fetched_at: Instant::now() - age,
+            names: vec!["Hosts".to_string()],
+        };
+        {
+            let mut snapshot = store.snapshot.write().expect("snapshot lock");
+            snapshot
+                .simple_sources
+                .insert(CompletionKind::Classes, fetched(Duration::ZERO));
+            snapshot
+                .simple_sources
+                .insert(CompletionKind::Groups, fetched(Duration::from_secs(3600)));
+        }
+
+        store.invalidate_all();
+        assert_eq!(
+            store.cached(CompletionKind::Classes),
+            Some(vec!["Hosts".to_string()])
+        );
+        assert_eq!(store.cached(CompletionKind::Groups), None);
+
+        store.record_seen_names(&path(&["class", "create"]), true, &[]);
+        assert_eq!(store.cached(CompletionKind::Classes), None);
+    }
+
+    #[test]
+    fn names_fetched_before_the_server_changed_are_not_kept() {
+        let store = CompletionStore::default();
+        let generation = store.generation.load(Ordering::SeqCst);
+        let names = vec!["Hosts".to_string()];
+
+        store.forget_names();
+        store.store_names(CompletionKind::Classes, generation, names.clone());
+        assert_eq!(store.cached(CompletionKind::Classes), None);
+
+        let generation = store.generation.load(Ordering::SeqCst);
+        store.store_names(CompletionKind::Classes, generation, names.clone());
+        assert_eq!(store.cached(CompletionKind::Classes), Some(names));
+    }
+
+    #[test]
+    fn names_are_fetched_again_after_switching_login() {
+        let client = |token: &str| -> BlockingClient<Authenticated> {
+            BlockingClient::from_url("http://localhost")
+                .expect("client should build")
+                .authenticate(Token::new(token))
+        };
+        let runtime = Runtime::new().expect("runtime should start");
+        let services = AppServices::new(
+            Arc::new(client("alice")),
+            runtime.handle().clone(),
+            Duration::from_secs(1),
+        );
+        let store = services.completion_store();
+        let generation = store.generation.load(Ordering::SeqCst);
+        store.store_names(
+            CompletionKind::Classes,
+            generation,
+            vec!["Hosts".to_string()],
+        );
+        store.record_seen_names(
+            &path(&["object", "list"]),
+            false,
+            &[OutputEnvelope::rows(
+                vec![json!({"Name": "web01", "Class": "Hosts"})],
+                Vec::new(),
+            )],
+        );
+        store
+            .snapshot
+            .write()
+            .expect("snapshot lock")
+            .objects_by_class
+            .insert("Hosts".to_string(), vec!["web01".to_string()]);
+
+        services.replace_client(
+            client("bob"),
+            Box::new(|| Err(AppError::CommandExecutionError("no relogin".to_string()))),
+        );
+
+        assert_eq!(store.cached(CompletionKind::Classes), None);
+        assert!(store.seen(CompletionKind::Classes, "").is_empty());
+        assert!(store.seen_objects("Hosts", "").is_empty());
+        assert!(store
+            .snapshot
+            .read()
+            .expect("snapshot lock")
+            .objects_by_class
+            .is_empty());
+        store.store_names(
+            CompletionKind::Classes,
+            generation,
+            vec!["Hosts".to_string()],
+        );
+        assert_eq!(store.cached(CompletionKind::Classes), None);
+    }
 
     #[test]
     fn filter_prefix_matches_start_of_value() {
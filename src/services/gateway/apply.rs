@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use hubuum_client::{Class, FilterOperator};
+
+use crate::domain::{
+    CurrentClass, CurrentCollection, CurrentObject, CurrentState, DesiredObjectRelation,
+    DesiredState, PlanOperation,
+};
+use crate::errors::AppError;
+
+use super::{
+    ClassUpdateInput, CollectionUpdateInput, CreateClassInput, CreateCollectionInput,
+    CreateObjectInput, HubuumGateway, ObjectUpdateInput, RelationTarget,
+};
+
+/// Current collections and classes, plus the raw classes and collection names by ID.
+struct CurrentModel {
+    state: CurrentState,
+    classes: Vec<Class>,
+    collection_names: HashMap<i32, String>,
+}
+
+impl HubuumGateway {
+    /// Fetches the server state needed to plan `desired`.
+    pub fn apply_current_state(&self, desired: &DesiredState) -> Result<CurrentState, AppError> {
+        let CurrentModel {
+            state: mut current,
+            classes,
+            collection_names,
+        } = self.current_model()?;
+        let class_names = classes
+            .iter()
+            .map(|class| (i32::from(class.id), class.name.clone()))
+            .collect::<HashMap<_, _>>();
+
+        let declared_classes = desired.class_names();
+        let mut declared_class_relations = Vec::new();
+        for relation in self.client().class_relation().query().all()? {
+            let from = class_names.get(&i32::from(relation.from_hubuum_class_id));
+            let to = class_names.get(&i32::from(relation.to_hubuum_class_id));
+            if let (Some(from), Some(to)) = (from, to) {
+                if declared_classes.contains(from) && declared_classes.contains(to) {
+                    declared_class_relations.push(i32::from(relation.id));
+                }
+                current.class_relations.insert((from.clone(), to.clone()));
+            }
+        }
+
+        let declared = classes
+            .iter()
+            .filter(|class| declared_classes.contains(&class.name))
+            .collect::<Vec<_>>();
+        let runner = self.bulk_runner();
+        let objects = runner.run(&declared, |class| {
+            Ok(self.client().objects(class.id).query().all()?)
+        });
+        let mut object_names = HashMap::new();
+        for (class, objects) in declared.iter().zip(objects) {
+            for object in objects? {
+                object_names.insert(
+                    i32::from(object.id),
+                    (class.name.clone(), object.name.clone()),
+                );
+                current.objects.insert(
+                    (class.name.clone(), object.name.clone()),
+                    CurrentObject {
+                        collection: collection_names
+                            .get(&i32::from(object.collection_id))
+                            .cloned()
+                            .unwrap_or_default(),
+                        description: object.description,
+                        data: object.data,
+                    },
+                );
+            }
+        }
+
+        // Every object relation between declared classes, so `--prune` can drop the stale ones.
+        let relations = runner.run(&declared_class_relations, |class_relation| {
+            Ok(self
+                .client()
+                .object_relation()
+                .query()
+                .filter(
+                    "class_relation",
+                    FilterOperator::Equals { is_negated: false },
+                    *class_relation,
+                )
+                .all()?)
+        });
+        for relations in relations {
+            for relation in relations? {
+                let from = object_names.get(&i32::from(relation.from_hubuum_object_id));
+                let to = object_names.get(&i32::from(relation.to_hubuum_object_id));
+                if let (Some((class_a, object_a)), Some((class_b, object_b))) = (from, to) {
+                    let relation = DesiredObjectRelation {
+                        class_a: class_a.clone(),
+                        object_a: object_a.clone(),
+                        class_b: class_b.clone(),
+                        object_b: object_b.clone(),
+                    };
+                    current.object_relations.insert(relation.key());
+                }
+            }
+        }
+
+        Ok(current)
+    }
+
+    /// Fetches collections and classes only, for plans that do not touch objects or relations.
+    pub fn current_class_state(&self) -> Result<CurrentState, AppError> {
+        Ok(self.current_model()?.state)
+    }
+
+    fn current_model(&self) -> Result<CurrentModel, AppError> {
+        let collections = self.client().collections().query().all()?;
+        let classes = self.client().classes().query().all()?;
+        let collection_names = collections
+            .iter()
+            .map(|collection| (i32::from(collection.id), collection.name.clone()))
+            .collect::<HashMap<_, _>>();
+
+        let mut current = CurrentState::default();
+        for collection in &collections {
+            current.collections.insert(
+                collection.name.clone(),
+                CurrentCollection {
+                    description: collection.description.clone(),
+                },
+            );
+        }
+        for class in &classes {
+            current.classes.insert(
+                class.name.clone(),
+                CurrentClass {
+                    collection: collection_names
+                        .get(&i32::from(class.collection.id))
+                        .cloned()
+                        .unwrap_or_default(),
+                    description: class.description.clone(),
+                    json_schema: class.json_schema.clone(),
+                    validate_schema: class.validate_schema.unwrap_or_default(),
+                },
+            );
+        }
+        Ok(CurrentModel {
+            state: current,
+            classes,
+            collection_names,
+        })
+    }
+
+    /// Sends a single planned change to the server.
+    pub fn apply_plan_operation(&self, operation: &PlanOperation) -> Result<(), AppError> {
+        match operation {
+            PlanOperation::CreateCollection(collection) => {
+                self.create_collection(CreateCollectionInput {
+                    name: collection.name.clone(),
+                    description: collection.description.clone(),
+                    owner: collection.owner.clone().unwrap_or_default(),
+                })?;
+            }
+            PlanOperation::UpdateCollection(collection) => {
+                self.update_collection(CollectionUpdateInput {
+                    name: collection.name.clone(),
+                    rename: None,
+                    description: Some(collection.description.clone()),
+                })?;
+            }
+            PlanOperation::CreateClass(class) => {
+                self.create_class(CreateClassInput {
+                    name: class.name.clone(),
+                    collection: class.collection.clone(),
+                    description: class.description.clone(),
+                    json_schema: class.json_schema.clone(),
+                    validate_schema: class.validate_schema,
+                })?;
+            }
+            PlanOperation::UpdateClass(class) => {
+                self.update_class(ClassUpdateInput {
+                    name: class.name.clone(),
+                    rename: None,
+                    collection: Some(class.collection.clone()),
+                    description: Some(class.description.clone()),
+                    json_schema: class.json_schema.clone(),
+                    validate_schema: class.validate_schema,
+                })?;
+            }
+            PlanOperation::CreateClassRelation(relation) => {
+                self.create_class_relation_v2(&relation.class_a, &relation.class_b)?;
+            }
+            PlanOperation::DeleteClassRelation(relation) => {
+                self.delete_class_relation_by_pair(&relation.class_a, &relation.class_b)?;
+            }
+            PlanOperation::CreateObject(object) => {
+                self.create_object(CreateObjectInput {
+                    name: object.name.clone(),
+                    class_name: object.class.clone(),
+                    collection: object.collection.clone(),
+                    description: object.description.clone(),
+                    data: object.data.clone(),
+                })?;
+            }
+            PlanOperation::UpdateObject(object) => {
+                self.update_object(ObjectUpdateInput {
+                    name: object.name.clone(),
+                    class_name: object.class.clone(),
+                    rename: None,
+                    collection: Some(object.collection.clone()),
+                    reclass: None,
+                    description: Some(object.description.clone()),
+                    data: object.data.clone(),
+                })?;
+            }
+            PlanOperation::DeleteObject { class, name } => {
+                self.delete_object(class, name)?;
+            }
+            PlanOperation::CreateObjectRelation(relation) => {
+                self.create_object_relation_v2(&RelationTarget::from(relation))?;
+            }
+            PlanOperation::DeleteObjectRelation(relation) => {
+                self.delete_object_relation_v2(&RelationTarget::from(relation))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&DesiredObjectRelation> for RelationTarget {
+    fn from(relation: &DesiredObjectRelation) -> Self {
+        Self {
+            class_a: relation.class_a.clone(),
+            class_b: relation.class_b.clone(),
+            object_a: Some(relation.object_a.clone()),
+            object_b: Some(relation.object_b.clone()),
+        }
+    }
+}
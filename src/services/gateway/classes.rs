@@ -1,15 +1,20 @@
+use std::collections::HashMap;
+
 use hubuum_client::{ClassPatch, ClassPost, FilterOperator};
 use serde_json::Value;
 
-use crate::domain::{build_related_class_tree, ClassRecord, ClassShowRecord, ObjectRecord};
-use crate::errors::AppError;
+use crate::domain::{
+    build_related_class_tree, ClassCountRecord, ClassRecord, ClassSchemaRecord, ClassShowRecord,
+    ObjectRecord,
+};
+use crate::errors::{ApiContext, AppError};
 use crate::list_query::{
     apply_query_paging, validate_filter_clauses, validate_sort_clauses, FilterFieldSpec,
     FilterOperatorProfile, FilterValueProfile, FilterValueResolver, ListQuery, PagedResult,
     SortFieldSpec,
 };
 
-use super::{HubuumGateway, RelationTraversalOptions};
+use super::{ApiCall, HubuumGateway, RelationTraversalOptions};
 
 #[derive(Debug, Clone)]
 pub struct CreateClassInput {
@@ -33,7 +38,7 @@ pub struct ClassUpdateInput {
 impl HubuumGateway {
     pub fn list_class_names(&self) -> Result<Vec<String>, AppError> {
         Ok(self
-            .client
+            .client()
             .classes()
             .query()
             .list()?
@@ -42,25 +47,92 @@ impl HubuumGateway {
             .collect())
     }
 
+    /// Names of every visible class, optionally limited to one collection.
+    pub fn class_names_in_collection(
+        &self,
+        collection: Option<&str>,
+    ) -> Result<Vec<String>, AppError> {
+        let collection_id = collection
+            .map(|name| self.resolve_collection(name))
+            .transpose()?
+            .map(|collection| collection.id());
+        let mut names = self
+            .client()
+            .classes()
+            .query()
+            .all()?
+            .into_iter()
+            .filter(|class| collection_id.is_none_or(|id| class.collection.id == id))
+            .map(|class| class.name)
+            .collect::<Vec<_>>();
+        names.sort();
+        Ok(names)
+    }
+
     pub fn class_schema(&self, name: &str) -> Result<Option<Value>, AppError> {
-        Ok(self
-            .client
+        Ok(self.resolve_class(name)?.resource().json_schema.clone())
+    }
+
+    /// Every class that has a JSON schema, optionally limited to one collection, by name.
+    pub fn class_schemas(
+        &self,
+        collection: Option<&str>,
+    ) -> Result<Vec<ClassSchemaRecord>, AppError> {
+        let collection_names = self
+            .client()
+            .collections()
+            .query()
+            .all()?
+            .into_iter()
+            .map(|collection| (i32::from(collection.id), collection.name))
+            .collect::<HashMap<_, _>>();
+        if let Some(name) = collection {
+            if !collection_names.values().any(|known| known == name) {
+                return Err(AppError::EntityNotFound(format!("collection '{name}'")));
+            }
+        }
+        let mut schemas = self
+            .client()
             .classes()
-            .get_by_name(name)?
-            .resource()
-            .json_schema
-            .clone())
+            .query()
+            .all()?
+            .into_iter()
+            .filter_map(|class| {
+                Some(ClassSchemaRecord {
+                    collection: collection_names
+                        .get(&i32::from(class.collection.id))
+                        .cloned()
+                        .unwrap_or_default(),
+                    json_schema: class.json_schema?,
+                    class: class.name,
+                    description: class.description,
+                })
+            })
+            .filter(|schema| collection.is_none_or(|name| schema.collection == name))
+            .collect::<Vec<_>>();
+        schemas.sort_by(|left, right| left.class.cmp(&right.class));
+        Ok(schemas)
     }
 
     pub fn create_class(&self, input: CreateClassInput) -> Result<ClassRecord, AppError> {
-        let collection = self.client.collections().get_by_name(&input.collection)?;
-        let class = self.client.classes().create_raw(ClassPost {
-            name: input.name,
-            collection_id: collection.id(),
-            description: input.description,
-            json_schema: input.json_schema,
-            validate_schema: input.validate_schema,
-        })?;
+        let collection = self.resolve_collection(&input.collection)?;
+        let name = input.name.clone();
+        let class = self
+            .client()
+            .classes()
+            .create_raw(ClassPost {
+                name: input.name,
+                collection_id: collection.id(),
+                description: input.description,
+                json_schema: input.json_schema,
+                validate_schema: input.validate_schema,
+            })
+            .context(|| {
+                format!(
+                    "creating class '{name}' in collection '{}'",
+                    input.collection
+                )
+            })?;
         Ok(ClassRecord::from(class))
     }
 
@@ -69,7 +141,7 @@ impl HubuumGateway {
         name: &str,
         options: &RelationTraversalOptions,
     ) -> Result<ClassShowRecord, AppError> {
-        let class = self.client.classes().get_by_name(name)?;
+        let class = self.resolve_class(name)?;
         let objects = class
             .objects()?
             .into_iter()
@@ -103,48 +175,128 @@ impl HubuumGateway {
         })
     }
 
+    pub fn get_class(&self, name: &str) -> Result<ClassRecord, AppError> {
+        let class = self.resolve_class(name)?;
+        Ok(ClassRecord::from(class.resource()))
+    }
+
+    pub fn class_collection_name(&self, name: &str) -> Result<String, AppError> {
+        Ok(self.resolve_class(name)?.resource().collection.name.clone())
+    }
+
+    /// The collection of the class `delete_class` would delete for `name`.
+    pub fn class_collection_name_exact(&self, name: &str) -> Result<String, AppError> {
+        Ok(self
+            .resolve_class_exact(name)?
+            .resource()
+            .collection
+            .name
+            .clone())
+    }
+
     pub fn delete_class(&self, name: &str) -> Result<(), AppError> {
-        self.client.classes().get_by_name(name)?.delete()?;
+        self.resolve_class_exact(name)?
+            .delete()
+            .context(|| format!("deleting class '{name}'"))?;
         Ok(())
     }
 
     pub fn update_class(&self, input: ClassUpdateInput) -> Result<ClassRecord, AppError> {
-        let class = self.client.classes().get_by_name(&input.name)?;
+        let class = self.resolve_class(&input.name)?;
 
         let collection_id = match input.collection {
-            Some(collection) => self.client.collections().get_by_name(&collection)?.id(),
+            Some(collection) => self.resolve_collection(&collection)?.id(),
             None => class.resource().collection.id,
         };
 
-        let updated = self.client.classes().update_raw(
-            class.id(),
-            ClassPatch {
-                name: input.rename,
-                collection_id,
-                description: input.description,
-                json_schema: input.json_schema,
-                validate_schema: input.validate_schema,
-            },
-        )?;
+        let updated = self
+            .client()
+            .classes()
+            .update_raw(
+                class.id(),
+                ClassPatch {
+                    name: input.rename,
+                    collection_id,
+                    description: input.description,
+                    json_schema: input.json_schema,
+                    validate_schema: input.validate_schema,
+                },
+            )
+            .context(|| format!("updating class '{}'", input.name))?;
 
         Ok(ClassRecord::from(updated))
     }
 
     pub fn list_classes(&self, query: &ListQuery) -> Result<PagedResult<ClassRecord>, AppError> {
-        let validated = validate_filter_clauses(&query.filters, CLASS_FILTER_SPECS)?;
-        let validated_sorts = validate_sort_clauses(&query.sorts, CLASS_SORT_SPECS)?;
-        let filters = validated
+        self.cached_list("classes", query, || {
+            let validated = validate_filter_clauses(&query.filters, CLASS_FILTER_SPECS)?;
+            let validated_sorts = validate_sort_clauses(&query.sorts, CLASS_SORT_SPECS)?;
+            let filters = validated
+                .iter()
+                .map(|clause| self.resolve_validated_filter(clause))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let page = apply_query_paging(
+                self.client().classes().query().filters(filters),
+                query,
+                &validated_sorts,
+            )
+            .page()?;
+            Ok(PagedResult::from_page(page, ClassRecord::from))
+        })
+    }
+
+    /// Lists classes like [`Self::list_classes`], adding the object count of each class.
+    pub fn list_classes_with_counts(
+        &self,
+        query: &ListQuery,
+    ) -> Result<PagedResult<ClassCountRecord>, AppError> {
+        let classes = self.list_classes(query)?;
+        let class_ids = classes
+            .items
+            .iter()
+            .map(|class| class.0.id.into())
+            .collect::<Vec<_>>();
+        let mut counts = self.class_object_counts(&class_ids)?;
+        Ok(PagedResult {
+            items: classes
+                .items
+                .into_iter()
+                .map(|class| ClassCountRecord {
+                    object_count: counts.remove(&class.0.id.into()).flatten(),
+                    class,
+                })
+                .collect(),
+            next_cursor: classes.next_cursor,
+            returned_count: classes.returned_count,
+            total_count: classes.total_count,
+        })
+    }
+
+    /// Counts the objects of each class with one single-item page request per class.
+    ///
+    /// The requests only ask the server for its total, and run in parallel under the
+    /// `bulk` concurrency and rate limits. A class maps to `None` when the server
+    /// leaves the total out of its reply.
+    pub fn class_object_counts(
+        &self,
+        class_ids: &[i32],
+    ) -> Result<HashMap<i32, Option<u64>>, AppError> {
+        let runner = self.bulk_runner();
+        class_ids
             .iter()
-            .map(|clause| self.resolve_validated_filter(clause))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let page = apply_query_paging(
-            self.client.classes().query().filters(filters),
-            query,
-            &validated_sorts,
-        )
-        .page()?;
-        Ok(PagedResult::from_page(page, ClassRecord::from))
+            .zip(runner.run(class_ids, |class_id| {
+                Ok(self
+                    .client()
+                    .objects(*class_id)
+                    .query()
+                    .include_total(true)
+                    .limit(1)
+                    .page()?
+                    .total_count)
+            }))
+            .map(|(class_id, count)| Ok((*class_id, count?)))
+            .collect()
     }
 }
 
@@ -210,3 +362,53 @@ pub(crate) const CLASS_SORT_SPECS: &[SortFieldSpec] = &[
     SortFieldSpec::new("created_at", "created_at"),
     SortFieldSpec::new("updated_at", "updated_at"),
 ];
+
+pub(crate) const CLASS_CREATE_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/collections", "Look up collection '{collection}'")
+        .with_filters(&["name={collection}"]),
+    ApiCall::post("/api/v1/classes", "Create class '{name}'"),
+];
+
+pub(crate) const CLASS_DELETE_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/classes/by-name/{name}", "Look up class '{name}'"),
+    ApiCall::delete("/api/v1/classes/{class_id}", "Delete the class"),
+];
+
+pub(crate) const CLASS_MODIFY_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/classes/by-name/{name}", "Look up class '{name}'"),
+    ApiCall::get("/api/v1/collections", "Look up collection '{collection}'")
+        .with_filters(&["name={collection}"])
+        .when("collection"),
+    ApiCall::patch("/api/v1/classes/{class_id}", "Update the class"),
+];
+
+pub(crate) const CLASS_SHOW_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/classes/by-name/{name}", "Look up class '{name}'"),
+    ApiCall::get(
+        "/api/v1/classes/{class_id}/",
+        "List the objects of the class",
+    ),
+    ApiCall::get(
+        "/api/v1/classes/{class_id}/related/graph",
+        "Fetch the related classes",
+    )
+    .with_filters(&["depth__lte={max_depth}"]),
+    ApiCall::get(
+        "/api/v1/collections",
+        "Name the collections of the related classes",
+    )
+    .with_filters(&["id__equals={collection_ids}"]),
+];
+
+pub(crate) const CLASS_LIST_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/classes", "List classes").listing(&[
+        "name icontains {name}",
+        "description icontains {description}",
+    ]),
+    ApiCall::get(
+        "/api/v1/classes/{class_id}/",
+        "Count the objects of each listed class",
+    )
+    .with_filters(&["include_total=true", "limit=1"])
+    .when("counts"),
+];
@@ -1,16 +1,21 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
 use hubuum_client::{CollectionPatch, CollectionPost};
+use serde_json::to_vec;
 
 use crate::domain::{
-    CollectionPermission, CollectionPermissionsView, CollectionRecord, GroupPermissionsRecord,
-    GroupPermissionsSummary,
+    ClassCountRecord, ClassRecord, ClassUsage, CollectionPermission, CollectionPermissionsView,
+    CollectionRecord, CollectionReport, CollectionUsage, GroupPermissionsRecord,
+    GroupPermissionsSummary, ResolvedClassRelationRecord, TimelineGrouping,
 };
-use crate::errors::AppError;
+use crate::errors::{ApiContext, AppError};
 use crate::list_query::{
     apply_query_paging, validate_filter_clauses, validate_sort_clauses, FilterFieldSpec,
     FilterOperatorProfile, FilterValueProfile, ListQuery, PagedResult, SortFieldSpec,
 };
 
-use super::HubuumGateway;
+use super::{ApiCall, HubuumGateway};
 
 #[derive(Debug, Clone)]
 pub struct CreateCollectionInput {
@@ -29,7 +34,7 @@ pub struct CollectionUpdateInput {
 impl HubuumGateway {
     pub fn list_collection_names(&self) -> Result<Vec<String>, AppError> {
         Ok(self
-            .client
+            .client()
             .collections()
             .query()
             .list()?
@@ -42,13 +47,18 @@ impl HubuumGateway {
         &self,
         input: CreateCollectionInput,
     ) -> Result<CollectionRecord, AppError> {
-        let group = self.client.groups().get_by_name(&input.owner)?;
-        let collection = self.client.collections().create_raw(CollectionPost {
-            name: input.name,
-            description: input.description,
-            group_id: group.id(),
-            parent_collection_id: None,
-        })?;
+        let group = self.resolve_group(&input.owner)?;
+        let name = input.name.clone();
+        let collection = self
+            .client()
+            .collections()
+            .create_raw(CollectionPost {
+                name: input.name,
+                description: input.description,
+                group_id: group.id(),
+                parent_collection_id: None,
+            })
+            .context(|| format!("creating collection '{name}'"))?;
         Ok(CollectionRecord::from(collection))
     }
 
@@ -56,30 +66,192 @@ impl HubuumGateway {
         &self,
         query: &ListQuery,
     ) -> Result<PagedResult<CollectionRecord>, AppError> {
-        let validated = validate_filter_clauses(&query.filters, COLLECTION_FILTER_SPECS)?;
-        let validated_sorts = validate_sort_clauses(&query.sorts, COLLECTION_SORT_SPECS)?;
-        let filters = validated
-            .iter()
-            .map(|clause| self.resolve_validated_filter(clause))
-            .collect::<Result<Vec<_>, _>>()?;
+        self.cached_list("collections", query, || {
+            let validated = validate_filter_clauses(&query.filters, COLLECTION_FILTER_SPECS)?;
+            let validated_sorts = validate_sort_clauses(&query.sorts, COLLECTION_SORT_SPECS)?;
+            let filters = validated
+                .iter()
+                .map(|clause| self.resolve_validated_filter(clause))
+                .collect::<Result<Vec<_>, _>>()?;
 
-        let page = apply_query_paging(
-            self.client.collections().query().filters(filters),
-            query,
-            &validated_sorts,
-        )
-        .page()?;
-        Ok(PagedResult::from_page(page, CollectionRecord::from))
+            let page = apply_query_paging(
+                self.client().collections().query().filters(filters),
+                query,
+                &validated_sorts,
+            )
+            .page()?;
+            Ok(PagedResult::from_page(page, CollectionRecord::from))
+        })
     }
 
     pub fn get_collection(&self, name: &str) -> Result<CollectionRecord, AppError> {
-        let collection = self.client.collections().get_by_name(name)?;
+        let collection = self.resolve_collection(name)?;
         Ok(CollectionRecord::from(collection.resource()))
     }
 
+    /// Counts the objects of every class in `name` and sums their serialized data size.
+    pub fn collection_usage(&self, name: &str) -> Result<CollectionUsage, AppError> {
+        let collection = self.resolve_collection(name)?;
+        let mut classes = self
+            .client()
+            .classes()
+            .query()
+            .all()?
+            .into_iter()
+            .filter(|class| class.collection.id == collection.id())
+            .map(|class| {
+                let objects = self.client().objects(class.id).query().all()?;
+                let data_bytes = objects
+                    .iter()
+                    .map(|object| to_vec(&object.data).map(|data| data.len() as u64))
+                    .sum::<Result<u64, _>>()?;
+                Ok(ClassUsage {
+                    class: class.name,
+                    objects: objects.len() as u64,
+                    data_bytes,
+                })
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+        classes.sort_by(|a, b| a.class.cmp(&b.class));
+        Ok(CollectionUsage {
+            collection: collection.resource().name.clone(),
+            classes,
+        })
+    }
+
+    /// Creation times for `stats timeline`, each with the collection or class it counts
+    /// towards. With `count_classes` the classes themselves are counted, per collection;
+    /// otherwise every object of the selected classes is fetched.
+    pub fn creation_times(
+        &self,
+        count_classes: bool,
+        grouping: TimelineGrouping,
+        collection: Option<&str>,
+    ) -> Result<Vec<(String, DateTime<Utc>)>, AppError> {
+        let collection_id = collection
+            .map(|name| self.resolve_collection(name))
+            .transpose()?
+            .map(|collection| collection.id());
+        let classes = self
+            .client()
+            .classes()
+            .query()
+            .all()?
+            .into_iter()
+            .filter(|class| collection_id.is_none_or(|id| class.collection.id == id))
+            .collect::<Vec<_>>();
+        if count_classes {
+            return Ok(classes
+                .into_iter()
+                .map(|class| (class.collection.name, class.created_at.0))
+                .collect());
+        }
+
+        let collection_names = self
+            .client()
+            .collections()
+            .query()
+            .all()?
+            .into_iter()
+            .map(|collection| (i32::from(collection.id), collection.name))
+            .collect::<HashMap<_, _>>();
+        let class_ids = classes
+            .iter()
+            .map(|class| class.id.into())
+            .collect::<Vec<i32>>();
+        let objects = self.bulk_runner().run(&class_ids, |class_id| {
+            Ok(self.client().objects(*class_id).query().all()?)
+        });
+
+        let mut created = Vec::new();
+        for (class, objects) in classes.iter().zip(objects) {
+            for object in objects? {
+                let group = match grouping {
+                    TimelineGrouping::Class => class.name.clone(),
+                    TimelineGrouping::Collection => collection_names
+                        .get(&object.collection_id.into())
+                        .cloned()
+                        .unwrap_or_else(|| object.collection_id.to_string()),
+                };
+                created.push((group, object.created_at.0));
+            }
+        }
+        Ok(created)
+    }
+
+    /// Gathers what `export html` reports about `name`: its classes with object counts,
+    /// the class relations touching them, and the group permissions on the collection.
+    pub fn collection_report(&self, name: &str) -> Result<CollectionReport, AppError> {
+        let collection = self.resolve_collection(name)?;
+        let mut classes = self
+            .client()
+            .classes()
+            .query()
+            .all()?
+            .into_iter()
+            .filter(|class| class.collection.id == collection.id())
+            .collect::<Vec<_>>();
+        classes.sort_by(|a, b| a.name.cmp(&b.name));
+        let class_ids = classes
+            .iter()
+            .map(|class| class.id.into())
+            .collect::<Vec<i32>>();
+        let mut counts = self.class_object_counts(&class_ids)?;
+
+        let relations = self
+            .client()
+            .class_relation()
+            .query()
+            .all()?
+            .into_iter()
+            .filter(|relation| {
+                class_ids.contains(&relation.from_hubuum_class_id.into())
+                    || class_ids.contains(&relation.to_hubuum_class_id.into())
+            })
+            .collect::<Vec<_>>();
+        let class_map = self.class_map_from_relation_ids(&relations)?;
+        let mut relations = relations
+            .iter()
+            .map(|relation| ResolvedClassRelationRecord::new(relation, &class_map))
+            .collect::<Vec<_>>();
+        relations.sort_by(|a, b| (&a.class_a, &a.class_b).cmp(&(&b.class_a, &b.class_b)));
+
+        let permissions = collection
+            .permissions()?
+            .into_iter()
+            .map(GroupPermissionsSummary::from)
+            .collect();
+
+        Ok(CollectionReport {
+            collection: CollectionRecord::from(collection.resource()),
+            classes: classes
+                .into_iter()
+                .map(|class| ClassCountRecord {
+                    object_count: counts.remove(&class.id.into()).flatten(),
+                    class: ClassRecord::from(class),
+                })
+                .collect(),
+            relations,
+            permissions,
+        })
+    }
+
+    /// The stored name of the collection `name` resolves to.
+    pub fn collection_name(&self, name: &str) -> Result<String, AppError> {
+        Ok(self.resolve_collection(name)?.resource().name.clone())
+    }
+
+    /// The stored name of the collection `delete_collection` would delete for `name`.
+    pub fn collection_name_exact(&self, name: &str) -> Result<String, AppError> {
+        Ok(self.resolve_collection_exact(name)?.resource().name.clone())
+    }
+
     pub fn delete_collection(&self, name: &str) -> Result<(), AppError> {
-        let collection = self.client.collections().get_by_name(name)?;
-        self.client.collections().delete(collection.id())?;
+        let collection = self.resolve_collection_exact(name)?;
+        self.client()
+            .collections()
+            .delete(collection.id())
+            .context(|| format!("deleting collection '{name}'"))?;
         Ok(())
     }
 
@@ -87,14 +259,18 @@ impl HubuumGateway {
         &self,
         input: CollectionUpdateInput,
     ) -> Result<CollectionRecord, AppError> {
-        let collection = self.client.collections().get_by_name(&input.name)?;
-        let updated = self.client.collections().update_raw(
-            collection.id(),
-            CollectionPatch {
-                name: input.rename,
-                description: input.description,
-            },
-        )?;
+        let collection = self.resolve_collection(&input.name)?;
+        let updated = self
+            .client()
+            .collections()
+            .update_raw(
+                collection.id(),
+                CollectionPatch {
+                    name: input.rename,
+                    description: input.description,
+                },
+            )
+            .context(|| format!("updating collection '{}'", input.name))?;
 
         Ok(CollectionRecord::from(updated))
     }
@@ -103,7 +279,7 @@ impl HubuumGateway {
         &self,
         name: &str,
     ) -> Result<CollectionPermissionsView, AppError> {
-        let permissions = self.client.collections().get_by_name(name)?.permissions()?;
+        let permissions = self.resolve_collection(name)?.permissions()?;
         let entries = permissions
             .iter()
             .cloned()
@@ -123,8 +299,8 @@ impl HubuumGateway {
         group_name: &str,
         permissions: &[CollectionPermission],
     ) -> Result<(), AppError> {
-        let collection = self.client.collections().get_by_name(collection_name)?;
-        let group = self.client.groups().get_by_name(group_name)?;
+        let collection = self.resolve_collection(collection_name)?;
+        let group = self.resolve_group(group_name)?;
         collection.grant_permissions(
             group.id(),
             permissions
@@ -135,12 +311,98 @@ impl HubuumGateway {
         Ok(())
     }
 
+    /// The permissions `group_name` currently holds on `collection_name`; empty without a grant.
+    pub fn group_collection_permissions(
+        &self,
+        collection_name: &str,
+        group_name: &str,
+    ) -> Result<Vec<CollectionPermission>, AppError> {
+        let permissions = self.resolve_collection(collection_name)?.permissions()?;
+        Ok(permissions
+            .iter()
+            .find(|entry| entry.group.groupname == group_name)
+            .map(|entry| CollectionPermission::granted(&entry.permission))
+            .unwrap_or_default())
+    }
+
+    /// Those of `collection_names` on which `group_name` holds any permission. Names
+    /// that match no collection are skipped.
+    pub fn collections_granting_group(
+        &self,
+        group_name: &str,
+        collection_names: &[String],
+    ) -> Result<Vec<String>, AppError> {
+        let runner = self.bulk_runner();
+        let mut granting = Vec::new();
+        for found in runner.run(collection_names, |name| {
+            let collection = match self.resolve_collection_exact(name) {
+                Ok(collection) => collection,
+                Err(AppError::EntityNotFound(_)) => return Ok(None),
+                Err(error) => return Err(error),
+            };
+            let grants = collection
+                .permissions()?
+                .iter()
+                .any(|entry| entry.group.groupname == group_name);
+            Ok(grants.then(|| collection.resource().name.clone()))
+        }) {
+            granting.extend(found?);
+        }
+        Ok(granting)
+    }
+
+    /// Makes `permissions` the complete grant of one group on a collection.
+    pub fn replace_collection_permissions(
+        &self,
+        collection_name: &str,
+        group_name: &str,
+        permissions: &[CollectionPermission],
+    ) -> Result<(), AppError> {
+        let collection = self.resolve_collection(collection_name)?;
+        let group = self.resolve_group(group_name)?;
+        collection.replace_permissions(
+            group.id(),
+            permissions
+                .iter()
+                .map(|permission| permission.api_name())
+                .collect(),
+        )?;
+        Ok(())
+    }
+
+    /// Grants `permissions` to one group on each of `collection_names`.
+    ///
+    /// Every collection is resolved before the first grant, so a misspelt name
+    /// changes nothing.
+    pub fn grant_group_permissions(
+        &self,
+        group_name: &str,
+        collection_names: &[String],
+        permissions: &[CollectionPermission],
+    ) -> Result<(), AppError> {
+        let group = self.resolve_group(group_name)?;
+        let collections = collection_names
+            .iter()
+            .map(|name| self.resolve_collection(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let permissions = permissions
+            .iter()
+            .map(|permission| permission.api_name())
+            .collect::<Vec<_>>();
+        self.bulk_runner()
+            .run(&collections, |collection| {
+                Ok(collection.grant_permissions(group.id(), permissions.clone())?)
+            })
+            .into_iter()
+            .collect()
+    }
+
     pub fn principal_collection_permissions(
         &self,
         collection: &str,
         principal_id: i32,
     ) -> Result<Vec<GroupPermissionsRecord>, AppError> {
-        let collection = self.client.collections().get_by_name(collection)?;
+        let collection = self.resolve_collection(collection)?;
         Ok(collection
             .principal_permissions(principal_id)?
             .into_iter()
@@ -189,3 +451,37 @@ pub(crate) const COLLECTION_SORT_SPECS: &[SortFieldSpec] = &[
     SortFieldSpec::new("created_at", "created_at"),
     SortFieldSpec::new("updated_at", "updated_at"),
 ];
+
+pub(crate) const COLLECTION_CREATE_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/iam/groups", "Look up owner group '{owner}'")
+        .with_filters(&["groupname={owner}"]),
+    ApiCall::post("/api/v1/collections", "Create collection '{name}'"),
+];
+
+pub(crate) const COLLECTION_DELETE_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/collections", "Look up collection '{name}'")
+        .with_filters(&["name={name}"]),
+    ApiCall::delete(
+        "/api/v1/collections/{collection_id}",
+        "Delete the collection",
+    ),
+];
+
+pub(crate) const COLLECTION_MODIFY_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/collections", "Look up collection '{name}'")
+        .with_filters(&["name={name}"]),
+    ApiCall::patch(
+        "/api/v1/collections/{collection_id}",
+        "Update the collection",
+    ),
+];
+
+pub(crate) const COLLECTION_SHOW_CALLS: &[ApiCall] =
+    &[
+        ApiCall::get("/api/v1/collections", "Look up collection '{name}'")
+            .with_filters(&["name={name}"]),
+    ];
+
+pub(crate) const COLLECTION_LIST_CALLS: &[ApiCall] =
+    &[ApiCall::get("/api/v1/collections", "List collections")
+        .listing(&["name contains {name}", "description contains {description}"])];
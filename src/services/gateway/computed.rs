@@ -278,8 +278,8 @@ impl HubuumGateway {
         &self,
         class_name: &str,
     ) -> Result<SharedComputedFieldListRecord, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
-        let response = self.client.computed_fields(class.id()).list()?;
+        let class = self.resolve_class(class_name)?;
+        let response = self.client().computed_fields(class.id()).list()?;
         Ok(SharedComputedFieldListRecord {
             definitions: response
                 .definitions
@@ -295,9 +295,9 @@ impl HubuumGateway {
         class_name: &str,
         input: ComputedDefinitionInput,
     ) -> Result<ComputedFieldMutationRecord, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
+        let class = self.resolve_class(class_name)?;
         Ok(self
-            .client
+            .client()
             .computed_fields(class.id())
             .create(input.into_api())?
             .into())
@@ -309,8 +309,8 @@ impl HubuumGateway {
         field_key: &str,
         input: ComputedPatchInput,
     ) -> Result<ComputedFieldMutationRecord, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
-        let fields = self.client.computed_fields(class.id());
+        let class = self.resolve_class(class_name)?;
+        let fields = self.client().computed_fields(class.id());
         let definition = fields
             .list()?
             .definitions
@@ -326,8 +326,8 @@ impl HubuumGateway {
         field_key: &str,
         expected_revision: i64,
     ) -> Result<ComputedFieldDeleteRecord, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
-        let fields = self.client.computed_fields(class.id());
+        let class = self.resolve_class(class_name)?;
+        let fields = self.client().computed_fields(class.id());
         let definition = fields
             .list()?
             .definitions
@@ -343,10 +343,10 @@ impl HubuumGateway {
         definition: ComputedDefinitionInput,
         target: ComputedPreviewTarget,
     ) -> Result<ComputedFieldPreviewRecord, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
+        let class = self.resolve_class(class_name)?;
         let request = self.computed_preview_request(&class, definition, target, false)?;
         Ok(self
-            .client
+            .client()
             .computed_fields(class.id())
             .preview(request)?
             .into())
@@ -356,8 +356,8 @@ impl HubuumGateway {
         &self,
         class_name: &str,
     ) -> Result<ClassComputationStateRecord, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
-        Ok(self.client.computed_fields(class.id()).rebuild()?.into())
+        let class = self.resolve_class(class_name)?;
+        Ok(self.client().computed_fields(class.id()).rebuild()?.into())
     }
 
     pub fn list_personal_computed_fields(
@@ -367,10 +367,12 @@ impl HubuumGateway {
     ) -> Result<PagedResult<ComputedFieldRecord>, AppError> {
         let request = match class_name {
             Some(class_name) => {
-                let class = self.client.classes().get_by_name(class_name)?;
-                self.client.personal_computed_fields().for_class(class.id())
+                let class = self.resolve_class(class_name)?;
+                self.client()
+                    .personal_computed_fields()
+                    .for_class(class.id())
             }
-            None => self.client.personal_computed_fields().query(),
+            None => self.client().personal_computed_fields().query(),
         };
         let page = apply_cursor_request_paging(request, query, &[]).page()?;
         Ok(PagedResult::from_page(page, Into::into))
@@ -381,9 +383,9 @@ impl HubuumGateway {
         class_name: &str,
         input: ComputedDefinitionInput,
     ) -> Result<ComputedFieldRecord, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
+        let class = self.resolve_class(class_name)?;
         Ok(self
-            .client
+            .client()
             .personal_computed_fields()
             .create(PersonalComputedFieldDefinitionRequest::new(
                 class.id(),
@@ -398,8 +400,8 @@ impl HubuumGateway {
         field_key: &str,
         input: ComputedPatchInput,
     ) -> Result<ComputedFieldRecord, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
-        let fields = self.client.personal_computed_fields();
+        let class = self.resolve_class(class_name)?;
+        let fields = self.client().personal_computed_fields();
         let definition = fields
             .for_class(class.id())
             .all()?
@@ -415,8 +417,8 @@ impl HubuumGateway {
         field_key: &str,
         expected_revision: i64,
     ) -> Result<ComputedFieldRecord, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
-        let fields = self.client.personal_computed_fields();
+        let class = self.resolve_class(class_name)?;
+        let fields = self.client().personal_computed_fields();
         let definition = fields
             .for_class(class.id())
             .all()?
@@ -433,10 +435,10 @@ impl HubuumGateway {
         definition: ComputedDefinitionInput,
         target: ComputedPreviewTarget,
     ) -> Result<ComputedFieldPreviewRecord, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
+        let class = self.resolve_class(class_name)?;
         let request = self.computed_preview_request(&class, definition, target, true)?;
         Ok(self
-            .client
+            .client()
             .personal_computed_fields()
             .preview(request)?
             .into())
@@ -452,7 +454,7 @@ impl HubuumGateway {
         let definition = definition.into_api();
         let request = match target {
             ComputedPreviewTarget::Object(object_name) => {
-                let object = class.object_by_name(&object_name)?;
+                let object = self.resolve_object_in(class, &object_name)?;
                 ComputedFieldPreviewRequest::for_object(definition, object.id())
             }
             ComputedPreviewTarget::Data(data) => {
@@ -0,0 +1,179 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use hubuum_client::{Class, ClassRelation, Collection};
+
+/// Entities that rarely change and are safe to reuse across commands for a while.
+///
+/// Objects are deliberately not cacheable: their data changes far more often than
+/// the class, collection, and relation metadata used to decorate object listings.
+pub(super) trait CacheableEntity: Any + Clone + Send {}
+
+impl CacheableEntity for Class {}
+impl CacheableEntity for Collection {}
+impl CacheableEntity for ClassRelation {}
+
+struct CachedEntity {
+    fetched_at: Instant,
+    value: Box<dyn Any + Send>,
+}
+
+/// In-memory cache of entities fetched by ID, keyed by entity type and ID.
+///
+/// Names resolved to an entity are remembered alongside, and stay valid for as
+/// long as the entity they point at is fresh.
+#[derive(Default)]
+pub(super) struct EntityCache {
+    entries: HashMap<(TypeId, i32), CachedEntity>,
+    names: HashMap<(TypeId, String), i32>,
+}
+
+impl EntityCache {
+    /// Splits `ids` into entities fresher than `ttl` and the IDs that still need fetching.
+    pub(super) fn lookup<T: CacheableEntity>(
+        &mut self,
+        ids: &[i32],
+        ttl: Duration,
+        now: Instant,
+    ) -> (HashMap<i32, T>, Vec<i32>) {
+        let mut hits = HashMap::new();
+        let mut missing = Vec::new();
+        for &id in ids {
+            let key = (TypeId::of::<T>(), id);
+            let cached = self
+                .entries
+                .get(&key)
+                .filter(|entry| now.duration_since(entry.fetched_at) < ttl)
+                .and_then(|entry| entry.value.downcast_ref::<T>());
+            match cached {
+                Some(entity) => {
+                    hits.insert(id, entity.clone());
+                }
+                None => {
+                    self.entries.remove(&key);
+                    missing.push(id);
+                }
+            }
+        }
+        (hits, missing)
+    }
+
+    pub(super) fn store<T: CacheableEntity>(&mut self, entities: &HashMap<i32, T>, now: Instant) {
+        for (&id, entity) in entities {
+            self.entries.insert(
+                (TypeId::of::<T>(), id),
+                CachedEntity {
+                    fetched_at: now,
+                    value: Box::new(entity.clone()),
+                },
+            );
+        }
+    }
+
+    /// The entity `key` resolved to earlier, if that entity is still fresher than `ttl`.
+    pub(super) fn lookup_named<T: CacheableEntity>(
+        &mut self,
+        key: &str,
+        ttl: Duration,
+        now: Instant,
+    ) -> Option<T> {
+        let name_key = (TypeId::of::<T>(), key.to_string());
+        let id = *self.names.get(&name_key)?;
+        let (mut hits, _) = self.lookup::<T>(&[id], ttl, now);
+        let hit = hits.remove(&id);
+        if hit.is_none() {
+            self.names.remove(&name_key);
+        }
+        hit
+    }
+
+    pub(super) fn store_named<T: CacheableEntity>(
+        &mut self,
+        key: &str,
+        id: i32,
+        entity: &T,
+        now: Instant,
+    ) {
+        self.names.insert((TypeId::of::<T>(), key.to_string()), id);
+        self.store(&HashMap::from([(id, entity.clone())]), now);
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.entries.clear();
+        self.names.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Widget(&'static str);
+
+    impl CacheableEntity for Widget {}
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Gadget(&'static str);
+
+    impl CacheableEntity for Gadget {}
+
+    #[test]
+    fn lookup_returns_fresh_hits_and_reports_missing_ids() {
+        let mut cache = EntityCache::default();
+        let start = Instant::now();
+        let ttl = Duration::from_secs(60);
+        cache.store(
+            &HashMap::from([(1, Widget("one")), (2, Widget("two"))]),
+            start,
+        );
+
+        let (hits, missing) = cache.lookup::<Widget>(&[1, 2, 3], ttl, start);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[&1], Widget("one"));
+        assert_eq!(missing, vec![3]);
+
+        let (hits, missing) = cache.lookup::<Gadget>(&[1], ttl, start);
+        assert!(hits.is_empty(), "entity types must not share cache entries");
+        assert_eq!(missing, vec![1]);
+    }
+
+    #[test]
+    fn expired_and_cleared_entries_are_fetched_again() {
+        let mut cache = EntityCache::default();
+        let start = Instant::now();
+        let ttl = Duration::from_secs(60);
+        cache.store(&HashMap::from([(1, Widget("one"))]), start);
+
+        let (_, missing) = cache.lookup::<Widget>(&[1], ttl, start + ttl);
+        assert_eq!(missing, vec![1]);
+
+        cache.store(&HashMap::from([(1, Widget("one"))]), start);
+        cache.clear();
+        let (_, missing) = cache.lookup::<Widget>(&[1], ttl, start);
+        assert_eq!(missing, vec![1]);
+    }
+
+    #[test]
+    fn names_resolve_while_their_entity_is_fresh() {
+        let mut cache = EntityCache::default();
+        let start = Instant::now();
+        let ttl = Duration::from_secs(60);
+        cache.store_named("exact:one", 1, &Widget("one"), start);
+
+        assert_eq!(
+            cache.lookup_named::<Widget>("exact:one", ttl, start),
+            Some(Widget("one"))
+        );
+        assert_eq!(cache.lookup_named::<Gadget>("exact:one", ttl, start), None);
+        assert_eq!(
+            cache.lookup_named::<Widget>("exact:one", ttl, start + ttl),
+            None
+        );
+
+        cache.store_named("exact:one", 1, &Widget("one"), start);
+        cache.clear();
+        assert_eq!(cache.lookup_named::<Widget>("exact:one", ttl, start), None);
+    }
+}
@@ -10,7 +10,7 @@ use serde::Serialize;
 use serde_json::{from_value, Value};
 
 use crate::domain::JsonRecord;
-use crate::errors::AppError;
+use crate::errors::{ApiContext, AppError};
 use crate::list_query::{
     apply_cursor_request_paging, apply_query_paging, validate_filter_clauses,
     validate_sort_clauses, FilterFieldSpec, FilterOperatorProfile, FilterValueProfile, ListQuery,
@@ -70,7 +70,7 @@ pub struct HistoryInput {
 impl HubuumGateway {
     pub fn list_event_sink_names(&self) -> Result<Vec<String>, AppError> {
         Ok(self
-            .client
+            .client()
             .event_sinks()
             .query()
             .list()?
@@ -80,7 +80,7 @@ impl HubuumGateway {
     }
 
     pub fn event_sink_id_by_name(&self, name: &str) -> Result<i32, AppError> {
-        Ok(self.client.event_sinks().get_by_name(name)?.id().get())
+        Ok(self.client().event_sinks().get_by_name(name)?.id().get())
     }
 
     pub fn list_event_subscription_names_for_collection(
@@ -89,7 +89,7 @@ impl HubuumGateway {
     ) -> Result<Vec<String>, AppError> {
         let collection_id = self.collection_id(collection)?;
         Ok(self
-            .client
+            .client()
             .event_subscriptions(collection_id)
             .query()
             .limit(200)
@@ -105,7 +105,7 @@ impl HubuumGateway {
     }
 
     pub fn user_id_by_name(&self, name: &str) -> Result<i32, AppError> {
-        Ok(self.client.users().get_by_name(name)?.id().into())
+        Ok(self.resolve_user(name)?.id().into())
     }
 
     pub fn audit_scope_by_name(
@@ -117,33 +117,27 @@ impl HubuumGateway {
         let name = name.ok_or_else(|| AppError::MissingOptions(vec!["name".to_string()]))?;
         match resource {
             "collection" => Ok(AuditScope::Collection(self.collection_id(name)?)),
-            "class" => Ok(AuditScope::Class(
-                self.class_handle_by_name(name)?.id().into(),
-            )),
+            "class" => Ok(AuditScope::Class(self.resolve_class(name)?.id().into())),
             "object" => {
                 let class_name = class_name
                     .ok_or_else(|| AppError::MissingOptions(vec!["class".to_string()]))?;
-                let object = self.object_handle_by_name(class_name, name)?;
+                let object = self.resolve_object(class_name, name)?;
                 Ok(AuditScope::Object {
                     class_id: object.resource().hubuum_class_id.into(),
                     object_id: object.id().into(),
                 })
             }
-            "user" => Ok(AuditScope::User(
-                self.client.users().get_by_name(name)?.id().into(),
-            )),
-            "group" => Ok(AuditScope::Group(
-                self.client.groups().get_by_name(name)?.id().into(),
-            )),
+            "user" => Ok(AuditScope::User(self.resolve_user(name)?.id().into())),
+            "group" => Ok(AuditScope::Group(self.resolve_group(name)?.id().into())),
             "template" => Ok(AuditScope::Template(
-                self.client
+                self.client()
                     .export_templates()
                     .get_by_name(name)?
                     .id()
                     .into(),
             )),
             "remote-target" => Ok(AuditScope::RemoteTarget(
-                self.client.remote_targets().get_by_name(name)?.id().get(),
+                self.client().remote_targets().get_by_name(name)?.id().get(),
             )),
             other => Err(AppError::InvalidOption(format!("resource={other}"))),
         }
@@ -155,17 +149,17 @@ impl HubuumGateway {
         input: AuditListInput,
     ) -> Result<PagedResult<JsonRecord>, AppError> {
         let request = match scope {
-            AuditScope::Global => self.client.events(),
-            AuditScope::Collection(id) => self.client.collection_events(id),
-            AuditScope::Class(id) => self.client.class_events(id),
+            AuditScope::Global => self.client().events(),
+            AuditScope::Collection(id) => self.client().collection_events(id),
+            AuditScope::Class(id) => self.client().class_events(id),
             AuditScope::Object {
                 class_id,
                 object_id,
-            } => self.client.object_events(class_id, object_id),
-            AuditScope::Template(id) => self.client.template_events(id),
-            AuditScope::RemoteTarget(id) => self.client.remote_target_events(id),
-            AuditScope::User(id) => self.client.user_events(id),
-            AuditScope::Group(id) => self.client.group_events(id),
+            } => self.client().object_events(class_id, object_id),
+            AuditScope::Template(id) => self.client().template_events(id),
+            AuditScope::RemoteTarget(id) => self.client().remote_target_events(id),
+            AuditScope::User(id) => self.client().user_events(id),
+            AuditScope::Group(id) => self.client().group_events(id),
         };
 
         let request = apply_audit_input(request, &input)?;
@@ -210,7 +204,7 @@ impl HubuumGateway {
 
     fn resolve_audit_resource_names(&self, record: JsonRecord) -> JsonRecord {
         let actor_user = record.audit_actor_user_id().and_then(|id| {
-            self.client
+            self.client()
                 .users()
                 .get(id)
                 .map(|user| user.resource().name.clone())
@@ -220,7 +214,7 @@ impl HubuumGateway {
                 .ok()
         });
         let collection = record.audit_collection_id().and_then(|id| {
-            self.client
+            self.client()
                 .collections()
                 .get(id)
                 .map(|collection| collection.resource().name.clone())
@@ -251,7 +245,7 @@ impl HubuumGateway {
 
         match scope {
             HistoryScope::Class(id) => {
-                let request = apply_history_input(self.client.class_history(id), &input)?;
+                let request = apply_history_input(self.client().class_history(id), &input)?;
                 page_to_json(request.page()?)
             }
             HistoryScope::Object {
@@ -259,7 +253,7 @@ impl HubuumGateway {
                 object_id,
             } => {
                 let request =
-                    apply_history_input(self.client.object_history(class_id, object_id), &input)?;
+                    apply_history_input(self.client().object_history(class_id, object_id), &input)?;
                 page_to_json(request.page()?)
             }
             HistoryScope::ClassName(_) | HistoryScope::ObjectName { .. } => {
@@ -321,14 +315,15 @@ impl HubuumGateway {
     ) -> Result<JsonRecord, AppError> {
         match scope {
             HistoryScope::Class(id) => {
-                JsonRecord::from_serializable(self.client.class_history_as_of(id, at)?)
+                JsonRecord::from_serializable(self.client().class_history_as_of(id, at)?)
                     .map_err(AppError::from)
             }
             HistoryScope::Object {
                 class_id,
                 object_id,
             } => JsonRecord::from_serializable(
-                self.client.object_history_as_of(class_id, object_id, at)?,
+                self.client()
+                    .object_history_as_of(class_id, object_id, at)?,
             )
             .map_err(AppError::from),
             HistoryScope::ClassName(_) | HistoryScope::ObjectName { .. } => {
@@ -340,13 +335,13 @@ impl HubuumGateway {
     fn resolve_history_scope(&self, scope: HistoryScope) -> Result<HistoryScope, AppError> {
         match scope {
             HistoryScope::ClassName(class_name) => Ok(HistoryScope::Class(
-                self.class_handle_by_name(&class_name)?.id().into(),
+                self.resolve_class(&class_name)?.id().into(),
             )),
             HistoryScope::ObjectName {
                 class_name,
                 object_name,
             } => {
-                let object = self.object_handle_by_name(&class_name, &object_name)?;
+                let object = self.resolve_object(&class_name, &object_name)?;
                 Ok(HistoryScope::Object {
                     class_id: object.resource().hubuum_class_id.into(),
                     object_id: object.id().into(),
@@ -364,7 +359,7 @@ impl HubuumGateway {
             .map(|clause| self.resolve_validated_filter(clause))
             .collect::<Result<Vec<_>, _>>()?;
         let page = apply_query_paging(
-            self.client.event_sinks().query().filters(filters),
+            self.client().event_sinks().query().filters(filters),
             query,
             &validated_sorts,
         )
@@ -374,7 +369,7 @@ impl HubuumGateway {
 
     pub fn event_sink_by_name(&self, name: &str) -> Result<JsonRecord, AppError> {
         JsonRecord::from_serializable(
-            self.client
+            self.client()
                 .event_sinks()
                 .get_by_name(name)?
                 .resource()
@@ -384,7 +379,7 @@ impl HubuumGateway {
     }
 
     pub fn create_event_sink(&self, input: NewEventSink) -> Result<JsonRecord, AppError> {
-        JsonRecord::from_serializable(self.client.event_sinks().create_raw(input)?)
+        JsonRecord::from_serializable(self.client().event_sinks().create_raw(input)?)
             .map_err(AppError::from)
     }
 
@@ -393,14 +388,21 @@ impl HubuumGateway {
         name: &str,
         input: UpdateEventSink,
     ) -> Result<JsonRecord, AppError> {
-        let sink = self.client.event_sinks().get_by_name(name)?;
-        JsonRecord::from_serializable(self.client.event_sinks().update_raw(sink.id(), input)?)
-            .map_err(AppError::from)
+        let sink = self.client().event_sinks().get_by_name(name)?;
+        let updated = self
+            .client()
+            .event_sinks()
+            .update_raw(sink.id(), input)
+            .context(|| format!("updating event sink '{name}'"))?;
+        JsonRecord::from_serializable(updated).map_err(AppError::from)
     }
 
     pub fn delete_event_sink_by_name(&self, name: &str) -> Result<(), AppError> {
-        let sink = self.client.event_sinks().get_by_name(name)?;
-        self.client.event_sinks().delete(sink.id())?;
+        let sink = self.client().event_sinks().get_by_name(name)?;
+        self.client()
+            .event_sinks()
+            .delete(sink.id())
+            .context(|| format!("deleting event sink '{name}'"))?;
         Ok(())
     }
 
@@ -413,7 +415,7 @@ impl HubuumGateway {
         let validated_sorts = validate_sort_clauses(&query.sorts, EVENT_SUBSCRIPTION_SORT_SPECS)?;
         let filters = self.resolve_event_filters(&validated)?;
         let page = apply_cursor_request_paging(
-            self.client
+            self.client()
                 .event_subscriptions(collection_id)
                 .query()
                 .filters(filters),
@@ -430,7 +432,7 @@ impl HubuumGateway {
         subscription_id: i32,
     ) -> Result<JsonRecord, AppError> {
         JsonRecord::from_serializable(
-            self.client
+            self.client()
                 .event_subscriptions(collection_id)
                 .get(subscription_id)?,
         )
@@ -452,7 +454,7 @@ impl HubuumGateway {
         input: NewEventSubscription,
     ) -> Result<JsonRecord, AppError> {
         JsonRecord::from_serializable(
-            self.client
+            self.client()
                 .event_subscriptions(collection_id)
                 .create(input)?,
         )
@@ -468,7 +470,7 @@ impl HubuumGateway {
         let subscription_id =
             self.event_subscription_id_by_name(collection_id, subscription_name)?;
         JsonRecord::from_serializable(
-            self.client
+            self.client()
                 .event_subscriptions(collection_id)
                 .update(subscription_id, input)?,
         )
@@ -480,7 +482,7 @@ impl HubuumGateway {
         collection_id: i32,
         subscription_id: i32,
     ) -> Result<(), AppError> {
-        self.client
+        self.client()
             .event_subscriptions(collection_id)
             .delete(subscription_id)?;
         Ok(())
@@ -502,7 +504,7 @@ impl HubuumGateway {
         name: &str,
     ) -> Result<i32, AppError> {
         let page = self
-            .client
+            .client()
             .event_subscriptions(collection_id)
             .query()
             .filter("name", FilterOperator::Equals { is_negated: false }, name)
@@ -524,7 +526,7 @@ impl HubuumGateway {
         let validated_sorts = validate_sort_clauses(&query.sorts, EVENT_DELIVERY_SORT_SPECS)?;
         let filters = self.resolve_event_filters(&validated)?;
         let page = apply_cursor_request_paging(
-            self.client.event_deliveries().query().filters(filters),
+            self.client().event_deliveries().query().filters(filters),
             query,
             &validated_sorts,
         )
@@ -533,22 +535,22 @@ impl HubuumGateway {
     }
 
     pub fn event_delivery(&self, id: i64) -> Result<JsonRecord, AppError> {
-        JsonRecord::from_serializable(self.client.event_deliveries().get(id)?)
+        JsonRecord::from_serializable(self.client().event_deliveries().get(id)?)
             .map_err(AppError::from)
     }
 
     pub fn event_delivery_health(&self) -> Result<JsonRecord, AppError> {
-        JsonRecord::from_serializable(self.client.event_deliveries().health()?)
+        JsonRecord::from_serializable(self.client().event_deliveries().health()?)
             .map_err(AppError::from)
     }
 
     pub fn retry_event_delivery(&self, id: i64) -> Result<JsonRecord, AppError> {
-        JsonRecord::from_serializable(self.client.event_deliveries().retry(id)?)
+        JsonRecord::from_serializable(self.client().event_deliveries().retry(id)?)
             .map_err(AppError::from)
     }
 
     pub fn dead_event_delivery(&self, id: i64) -> Result<JsonRecord, AppError> {
-        JsonRecord::from_serializable(self.client.event_deliveries().mark_dead(id)?)
+        JsonRecord::from_serializable(self.client().event_deliveries().mark_dead(id)?)
             .map_err(AppError::from)
     }
 
@@ -8,14 +8,14 @@ use hubuum_client::{
 };
 
 use crate::domain::{ExportTemplateRecord, TaskRecord};
-use crate::errors::AppError;
+use crate::errors::{ApiContext, AppError};
 use crate::list_query::{
     apply_query_paging, validate_filter_clauses, validate_sort_clauses, FilterFieldSpec,
     FilterOperatorProfile, FilterValueProfile, FilterValueResolver, ListQuery, PagedResult,
     SortFieldSpec,
 };
 
-use super::{shared::find_entities_by_ids, HubuumGateway};
+use super::HubuumGateway;
 
 #[derive(Debug, Clone)]
 pub struct CreateExportTemplateInput {
@@ -64,7 +64,7 @@ fn parse_include_related_spec(
     spec: &str,
 ) -> Result<(String, ExportIncludeRelatedObject), AppError> {
     let (key, class_name, max_depth) = parse_include_related_spec_parts(spec)?;
-    let class_id = gateway.class_handle_by_name(&class_name)?.id();
+    let class_id = gateway.resolve_class(&class_name)?.id();
 
     Ok((
         key,
@@ -121,7 +121,7 @@ fn parse_include_related_spec_parts(spec: &str) -> Result<(String, String, Optio
 impl HubuumGateway {
     pub fn list_export_template_names(&self) -> Result<Vec<String>, AppError> {
         Ok(self
-            .client
+            .client()
             .export_templates()
             .query()
             .list()?
@@ -141,7 +141,7 @@ impl HubuumGateway {
             .map(|clause| self.resolve_validated_filter(clause))
             .collect::<Result<Vec<_>, _>>()?;
         let page = apply_query_paging(
-            self.client.export_templates().query().filters(filters),
+            self.client().export_templates().query().filters(filters),
             query,
             &validated_sorts,
         )
@@ -155,10 +155,11 @@ impl HubuumGateway {
             });
         }
 
-        let collectionmap =
-            find_entities_by_ids(&self.client.collections(), page.items.iter(), |template| {
-                template.collection_id
-            })?;
+        let collectionmap = self.find_entities_by_ids(
+            |client| client.collections(),
+            page.items.iter(),
+            |template| template.collection_id,
+        )?;
 
         Ok(PagedResult::from_page(page, |template| {
             ExportTemplateRecord::new(&template, &collectionmap)
@@ -166,9 +167,9 @@ impl HubuumGateway {
     }
 
     pub fn export_template(&self, name: &str) -> Result<ExportTemplateRecord, AppError> {
-        let template = self.client.export_templates().get_by_name(name)?;
+        let template = self.client().export_templates().get_by_name(name)?;
         let collection = self
-            .client
+            .client()
             .collections()
             .get(template.resource().collection_id)?;
         let collectionmap =
@@ -184,13 +185,13 @@ impl HubuumGateway {
         &self,
         input: CreateExportTemplateInput,
     ) -> Result<ExportTemplateRecord, AppError> {
-        let collection = self.client.collections().get_by_name(&input.collection)?;
+        let collection = self.resolve_collection(&input.collection)?;
         let content_type = ExportContentType::from_str(&input.content_type).map_err(|_| {
             AppError::ParseError(format!("Invalid content type: {}", input.content_type))
         })?;
 
         let template = self
-            .client
+            .client()
             .export_templates()
             .create_checked()
             .collection_id(collection.id())
@@ -210,14 +211,14 @@ impl HubuumGateway {
         &self,
         input: UpdateExportTemplateInput,
     ) -> Result<ExportTemplateRecord, AppError> {
-        let template = self.client.export_templates().get_by_name(&input.name)?;
+        let template = self.client().export_templates().get_by_name(&input.name)?;
         let collection_id = match input.collection {
-            Some(collection) => Some(self.client.collections().get_by_name(&collection)?.id()),
+            Some(collection) => Some(self.resolve_collection(&collection)?.id()),
             None => None,
         };
 
         let updated = self
-            .client
+            .client()
             .export_templates()
             .update(template.id())
             .params(ExportTemplatePatch {
@@ -236,15 +237,18 @@ impl HubuumGateway {
             })
             .send()?;
 
-        let collection = self.client.collections().get(updated.collection_id)?;
+        let collection = self.client().collections().get(updated.collection_id)?;
         let collectionmap =
             HashMap::from([(collection.id().into(), collection.resource().clone())]);
         Ok(ExportTemplateRecord::new(&updated, &collectionmap))
     }
 
     pub fn delete_export_template(&self, name: &str) -> Result<(), AppError> {
-        let template = self.client.export_templates().get_by_name(name)?;
-        self.client.export_templates().delete(template.id())?;
+        let template = self.client().export_templates().get_by_name(name)?;
+        self.client()
+            .export_templates()
+            .delete(template.id())
+            .context(|| format!("deleting export template '{name}'"))?;
         Ok(())
     }
 
@@ -254,14 +258,14 @@ impl HubuumGateway {
         })?;
 
         let class_id = match &input.class_name {
-            Some(name) => Some(self.client.classes().get_by_name(name)?.id()),
+            Some(name) => Some(self.resolve_class(name)?.id()),
             None => None,
         };
 
         let object_id = match (&input.class_name, &input.object_name) {
             (Some(class_name), Some(object_name)) => {
-                let class = self.client.classes().get_by_name(class_name)?;
-                Some(class.object_by_name(object_name)?.id())
+                let class = self.resolve_class(class_name)?;
+                Some(self.resolve_object_in(&class, object_name)?.id())
             }
             (None, Some(_)) => {
                 return Err(AppError::MissingOptions(vec!["class".to_string()]));
@@ -318,13 +322,18 @@ impl HubuumGateway {
 
     pub fn submit_export(&self, input: RunExportInput) -> Result<TaskRecord, AppError> {
         if let Some(template_name) = &input.template {
-            let template = self.client.export_templates().get_by_name(template_name)?;
+            let template = self
+                .client()
+                .export_templates()
+                .get_by_name(template_name)?;
             let class = match &input.class_name {
-                Some(class_name) => Some(self.client.classes().get_by_name(class_name)?),
+                Some(class_name) => Some(self.resolve_class(class_name)?),
                 None => None,
             };
             let object_id = match (&class, &input.object_name) {
-                (Some(class), Some(object_name)) => Some(class.object_by_name(object_name)?.id()),
+                (Some(class), Some(object_name)) => {
+                    Some(self.resolve_object_in(class, object_name)?.id())
+                }
                 (None, Some(_)) => return Err(AppError::MissingOptions(vec!["class".to_string()])),
                 _ => None,
             };
@@ -349,7 +358,7 @@ impl HubuumGateway {
                 limits,
             };
             return Ok(TaskRecord(
-                self.client
+                self.client()
                     .export_templates()
                     .submit_export(template.id(), request)
                     .send()?,
@@ -357,7 +366,7 @@ impl HubuumGateway {
         }
 
         let request = self.build_export_request(&input)?;
-        Ok(TaskRecord(self.client.exports().submit(request).send()?))
+        Ok(TaskRecord(self.client().exports().submit(request).send()?))
     }
 }
 
@@ -1,11 +1,13 @@
+use hubuum_client::FilterOperator;
+
 use crate::domain::{GroupDetails, GroupRecord, PrincipalMemberRecord};
-use crate::errors::AppError;
+use crate::errors::{ApiContext, AppError};
 use crate::list_query::{
     apply_query_paging, validate_filter_clauses, validate_sort_clauses, FilterFieldSpec,
     FilterOperatorProfile, FilterValueProfile, ListQuery, PagedResult, SortFieldSpec,
 };
 
-use super::HubuumGateway;
+use super::{ApiCall, HubuumGateway};
 
 #[derive(Debug, Clone)]
 pub struct CreateGroupInput {
@@ -23,7 +25,7 @@ pub struct GroupUpdateInput {
 impl HubuumGateway {
     pub fn list_group_names(&self) -> Result<Vec<String>, AppError> {
         Ok(self
-            .client
+            .client()
             .groups()
             .query()
             .list()?
@@ -33,36 +35,41 @@ impl HubuumGateway {
     }
 
     pub fn group_id_by_name(&self, group_name: &str) -> Result<i32, AppError> {
-        Ok(self.client.groups().get_by_name(group_name)?.id().into())
+        Ok(self.resolve_group(group_name)?.id().into())
     }
 
     pub fn create_group(&self, input: CreateGroupInput) -> Result<GroupRecord, AppError> {
         let group = self
-            .client
+            .client()
             .groups()
             .create_checked()
-            .groupname(input.groupname)
+            .groupname(input.groupname.clone())
             .description(input.description)
-            .send()?;
+            .send()
+            .context(|| format!("creating group '{}'", input.groupname))?;
         Ok(GroupRecord::from(group))
     }
 
     pub fn add_user_to_group(&self, group_name: &str, username: &str) -> Result<(), AppError> {
-        let group = self.client.groups().get_by_name(group_name)?;
-        let principal_id = self.client.users().get_by_name(username)?.id();
-        group.add_member(principal_id)?;
+        let group = self.resolve_group(group_name)?;
+        let principal_id = self.resolve_user(username)?.id();
+        group
+            .add_member(principal_id)
+            .context(|| format!("adding user '{username}' to group '{group_name}'"))?;
         Ok(())
     }
 
     pub fn remove_user_from_group(&self, group_name: &str, username: &str) -> Result<(), AppError> {
-        let group = self.client.groups().get_by_name(group_name)?;
-        let principal_id = self.client.users().get_by_name(username)?.id();
-        group.remove_member(principal_id)?;
+        let group = self.resolve_group(group_name)?;
+        let principal_id = self.resolve_user(username)?.id();
+        group
+            .remove_member(principal_id)
+            .context(|| format!("removing user '{username}' from group '{group_name}'"))?;
         Ok(())
     }
 
     pub fn group_details(&self, group_name: &str) -> Result<GroupDetails, AppError> {
-        let handle = self.client.groups().get_by_name(group_name)?;
+        let handle = self.resolve_group(group_name)?;
         let members = handle
             .members()?
             .into_iter()
@@ -76,9 +83,9 @@ impl HubuumGateway {
     }
 
     pub fn update_group(&self, input: GroupUpdateInput) -> Result<GroupRecord, AppError> {
-        let handle = self.client.groups().get_by_name(&input.groupname)?;
+        let handle = self.resolve_group(&input.groupname)?;
         let updated = self
-            .client
+            .client()
             .groups()
             .update(handle.id())
             .params(GroupPatch {
@@ -91,20 +98,59 @@ impl HubuumGateway {
     }
 
     pub fn list_groups(&self, query: &ListQuery) -> Result<PagedResult<GroupRecord>, AppError> {
-        let validated = validate_filter_clauses(&query.filters, GROUP_FILTER_SPECS)?;
-        let validated_sorts = validate_sort_clauses(&query.sorts, GROUP_SORT_SPECS)?;
-        let filters = validated
-            .iter()
-            .map(|clause| self.resolve_validated_filter(clause))
-            .collect::<Result<Vec<_>, _>>()?;
+        self.fetch_groups("groups", query, None)
+    }
 
-        let mut query_op = self.client.groups().query();
-        for filter in filters {
-            query_op = query_op.filter(&filter.key, filter.operator, &filter.value);
+    /// The groups matching `query` that `username` is a member of.
+    pub fn list_member_groups(
+        &self,
+        username: &str,
+        query: &ListQuery,
+    ) -> Result<PagedResult<GroupRecord>, AppError> {
+        let ids = self
+            .user_groups(username)?
+            .iter()
+            .map(|group| group.0.id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        if ids.is_empty() {
+            return Ok(PagedResult {
+                items: Vec::new(),
+                next_cursor: None,
+                returned_count: 0,
+                total_count: query.include_total.then_some(0),
+            });
         }
 
-        let page = apply_query_paging(query_op, query, &validated_sorts).page()?;
-        Ok(PagedResult::from_page(page, GroupRecord::from))
+        self.fetch_groups(&format!("groups {ids}"), query, Some(&ids))
+    }
+
+    /// Lists groups, limited to the comma-separated group `ids` when given.
+    fn fetch_groups(
+        &self,
+        listing: &str,
+        query: &ListQuery,
+        ids: Option<&str>,
+    ) -> Result<PagedResult<GroupRecord>, AppError> {
+        self.cached_list(listing, query, || {
+            let validated = validate_filter_clauses(&query.filters, GROUP_FILTER_SPECS)?;
+            let validated_sorts = validate_sort_clauses(&query.sorts, GROUP_SORT_SPECS)?;
+            let filters = validated
+                .iter()
+                .map(|clause| self.resolve_validated_filter(clause))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut query_op = self.client().groups().query();
+            for filter in filters {
+                query_op = query_op.filter(&filter.key, filter.operator, &filter.value);
+            }
+            if let Some(ids) = ids {
+                query_op = query_op.filter("id", FilterOperator::Equals { is_negated: false }, ids);
+            }
+
+            let page = apply_query_paging(query_op, query, &validated_sorts).page()?;
+            Ok(PagedResult::from_page(page, GroupRecord::from))
+        })
     }
 }
 
@@ -149,3 +195,54 @@ pub(crate) const GROUP_SORT_SPECS: &[SortFieldSpec] = &[
     SortFieldSpec::new("updated_at", "updated_at"),
 ];
 use hubuum_client::GroupPatch;
+
+pub(crate) const GROUP_CREATE_CALLS: &[ApiCall] = &[ApiCall::post(
+    "/api/v1/iam/groups",
+    "Create group '{groupname}'",
+)];
+
+pub(crate) const GROUP_ADD_USER_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/iam/groups", "Look up group '{groupname}'")
+        .with_filters(&["groupname={groupname}"]),
+    ApiCall::get("/api/v1/iam/users", "Look up user '{username}'")
+        .with_filters(&["name={username}"]),
+    ApiCall::post(
+        "/api/v1/iam/groups/{group_id}/members/{principal_id}",
+        "Add the user to the group",
+    ),
+];
+
+pub(crate) const GROUP_REMOVE_USER_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/iam/groups", "Look up group '{groupname}'")
+        .with_filters(&["groupname={groupname}"]),
+    ApiCall::get("/api/v1/iam/users", "Look up user '{username}'")
+        .with_filters(&["name={username}"]),
+    ApiCall::delete(
+        "/api/v1/iam/groups/{group_id}/members/{principal_id}",
+        "Remove the user from the group",
+    ),
+];
+
+pub(crate) const GROUP_SHOW_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/iam/groups", "Look up group '{groupname}'")
+        .with_filters(&["groupname={groupname}"]),
+    ApiCall::get(
+        "/api/v1/iam/groups/{group_id}/members",
+        "List the members of the group",
+    ),
+];
+
+pub(crate) const GROUP_LIST_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/iam/users", "Look up user '{member}'")
+        .with_filters(&["name={member}"])
+        .when("member"),
+    ApiCall::get(
+        "/api/v1/iam/principals/{principal_id}/groups",
+        "List the groups of the user",
+    )
+    .when("member"),
+    ApiCall::get("/api/v1/iam/groups", "List groups").listing(&[
+        "groupname icontains {name}",
+        "description icontains {description}",
+    ]),
+];
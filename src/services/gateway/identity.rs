@@ -1,16 +1,75 @@
+use hubuum_client::{blocking::Client as BlockingClient, ApiError as ClientApiError, Credentials};
+use log::debug;
+
 use crate::domain::{GroupRecord, MeRecord, PrincipalPermissionsRecord, PrincipalTokenRecord};
 use crate::errors::AppError;
+use crate::offline::require_online;
 
-use super::HubuumGateway;
+use super::{is_unauthorized, HubuumGateway};
 
 impl HubuumGateway {
+    /// Changes `username`'s own password once `current` is confirmed by a login.
+    ///
+    /// Returns the token from logging in again with the new password.
+    pub fn change_own_password(
+        &self,
+        username: &str,
+        current: &str,
+        new: &str,
+    ) -> Result<String, AppError> {
+        require_online("changing your password")?;
+        let client = BlockingClient::builder(self.client().base_url().clone())
+            .with_http_client(self.client().http_client().clone())
+            .build()?;
+        let verified = client
+            .login(Credentials::new(username.to_string(), current.to_string()))
+            .map_err(|_| {
+                AppError::CommandExecutionError(format!(
+                    "The current password for '{username}' was not accepted"
+                ))
+            })?;
+        self.set_user_password(username, new)?;
+        let refreshed = client.login(Credentials::new(username.to_string(), new.to_string()))?;
+        if let Err(error) = verified.logout() {
+            debug!("Could not log out the password check session: {error}");
+        }
+        Ok(refreshed.token().to_string())
+    }
+
+    /// Revokes the session's token on the server. A token the server already rejects
+    /// is treated as logged out.
+    pub fn logout(&self) -> Result<(), AppError> {
+        match self.client().as_ref().clone().logout() {
+            Ok(_) => Ok(()),
+            Err(error) => {
+                let error = AppError::from(error);
+                if is_unauthorized(&error) {
+                    debug!("The session token was already rejected: {error}");
+                    Ok(())
+                } else {
+                    Err(error)
+                }
+            }
+        }
+    }
+
     pub fn me(&self) -> Result<MeRecord, AppError> {
-        Ok(MeRecord(self.client.me()?))
+        Ok(MeRecord(self.client().me()?))
+    }
+
+    /// Whether the current principal has admin rights, probed with the admin-only
+    /// running configuration endpoint.
+    pub fn is_admin(&self) -> Result<bool, AppError> {
+        match self.client().admin_config() {
+            Ok(_) => Ok(true),
+            Err(ClientApiError::HttpWithBody { status, .. }) if status.as_u16() == 403 => Ok(false),
+            Err(error) => Err(error.into()),
+        }
     }
 
     pub fn me_groups(&self) -> Result<Vec<GroupRecord>, AppError> {
         Ok(self
-            .client
+            .client()
             .me_groups()?
             .into_iter()
             .map(|h| GroupRecord::from(h.resource().clone()))
@@ -19,7 +78,7 @@ impl HubuumGateway {
 
     pub fn me_tokens(&self) -> Result<Vec<PrincipalTokenRecord>, AppError> {
         Ok(self
-            .client
+            .client()
             .me_tokens()?
             .into_iter()
             .map(PrincipalTokenRecord::from)
@@ -28,7 +87,7 @@ impl HubuumGateway {
 
     pub fn me_permissions(&self) -> Result<Vec<PrincipalPermissionsRecord>, AppError> {
         Ok(self
-            .client
+            .client()
             .me_permissions()?
             .into_iter()
             .map(PrincipalPermissionsRecord::from)
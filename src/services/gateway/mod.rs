@@ -1,28 +1,45 @@
 mod admin;
+mod apply;
 mod backups;
 mod classes;
 mod collections;
 mod computed;
+mod entity_cache;
 mod events;
 mod exports;
 mod groups;
 mod identity;
 mod imports;
 mod objects;
+mod plan;
 mod relations;
 mod remote_targets;
+mod resolver;
+mod response_cache;
 mod search;
 mod service_accounts;
+mod session;
 mod settings;
 mod shared;
 mod tasks;
 mod users;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
 
 use hubuum_client::{blocking::Client as BlockingClient, Authenticated};
+use log::debug;
 
+use crate::config::get_config;
+use crate::errors::AppError;
 use crate::list_query::{FilterFieldSpec, SortFieldSpec};
+use crate::offline::{is_offline, offline_client, set_offline};
+use crate::services::BulkRunner;
+
+use entity_cache::EntityCache;
+use response_cache::ResponseCache;
+
+pub(crate) use plan::ApiCall;
+pub(crate) use session::{is_unauthorized, Relogin, Session};
 
 pub use backups::{BackupInput, RunBackupInput};
 pub use classes::{ClassUpdateInput, CreateClassInput};
@@ -48,12 +65,85 @@ pub use users::{CreateUserInput, NewTokenInput, UserFilter, UserUpdateInput};
 
 #[derive(Clone)]
 pub struct HubuumGateway {
-    pub(super) client: Arc<BlockingClient<Authenticated>>,
+    session: Arc<Session>,
+    entity_cache: Arc<Mutex<EntityCache>>,
+    response_cache: Arc<Mutex<ResponseCache>>,
+    /// Used instead of the session's client while offline; it sends nothing.
+    offline_client: Arc<OnceLock<Arc<BlockingClient<Authenticated>>>>,
 }
 
 impl HubuumGateway {
     pub fn new(client: Arc<BlockingClient<Authenticated>>) -> Self {
-        Self { client }
+        Self {
+            session: Arc::new(Session::new(client)),
+            entity_cache: Arc::default(),
+            response_cache: Arc::default(),
+            offline_client: Arc::default(),
+        }
+    }
+
+    /// The client of the current login; it changes when the session logs in again.
+    /// While offline it is a client whose requests fail without being sent.
+    pub(super) fn client(&self) -> Arc<BlockingClient<Authenticated>> {
+        if is_offline() {
+            if let Some(client) = self.offline_client.get() {
+                return client.clone();
+            }
+        }
+        self.session.client()
+    }
+
+    /// Keeps every request from the server, or lets them through again.
+    pub(crate) fn set_offline(&self, offline: bool) -> Result<(), AppError> {
+        if offline && self.offline_client.get().is_none() {
+            let client = offline_client(&self.session.client())?;
+            let _ = self.offline_client.set(Arc::new(client));
+        }
+        set_offline(offline);
+        Ok(())
+    }
+
+    /// Sets how the session logs in again when the server rejects its token.
+    pub(crate) fn set_relogin(&self, relogin: Relogin) {
+        self.session.set_relogin(relogin);
+    }
+
+    /// Identifies the current login, for [`HubuumGateway::renew_session`].
+    pub(crate) fn session_generation(&self) -> u64 {
+        self.session.generation()
+    }
+
+    /// Logs in again after the server rejected the token of login `generation`, unless
+    /// another request already did. The new token is saved like at startup.
+    pub(crate) fn renew_session(&self, generation: u64) -> Result<(), AppError> {
+        self.session.renew(generation)
+    }
+
+    /// Switches the session to another login, dropping everything cached for the old one.
+    pub(super) fn replace_client(&self, client: BlockingClient<Authenticated>, relogin: Relogin) {
+        self.session.replace(client, relogin);
+        self.invalidate_caches();
+    }
+
+    /// A bulk runner under the `bulk` limits that renews the login on a rejected token.
+    pub(crate) fn bulk_runner(&self) -> BulkRunner {
+        BulkRunner::from_config(&get_config().bulk).with_session(self.session.clone())
+    }
+
+    /// Drops cached entities and list responses, e.g. after a mutation, returning how
+    /// many list responses were dropped.
+    pub fn invalidate_caches(&self) -> usize {
+        match self.entity_cache.lock() {
+            Ok(mut cache) => cache.clear(),
+            Err(_) => debug!("Entity cache lock poisoned; skipping invalidation"),
+        }
+        match self.response_cache.lock() {
+            Ok(mut cache) => cache.clear(),
+            Err(_) => {
+                debug!("Response cache lock poisoned; skipping invalidation");
+                0
+            }
+        }
     }
 }
 
@@ -164,3 +254,40 @@ pub(crate) fn sort_specs_for_command_path(
         _ => None,
     }
 }
+
+/// The API requests behind a command, in the order it sends them.
+///
+/// Commands that only work on local files have an empty plan; `None` means no plan is
+/// recorded for the command.
+pub(crate) fn api_calls_for_command_path(command_path: &[String]) -> Option<&'static [ApiCall]> {
+    let calls = match command_path.join(" ").as_str() {
+        "class create" => classes::CLASS_CREATE_CALLS,
+        "class delete" => classes::CLASS_DELETE_CALLS,
+        "class list" => classes::CLASS_LIST_CALLS,
+        "class modify" => classes::CLASS_MODIFY_CALLS,
+        "class show" => classes::CLASS_SHOW_CALLS,
+        "collection create" => collections::COLLECTION_CREATE_CALLS,
+        "collection delete" => collections::COLLECTION_DELETE_CALLS,
+        "collection list" => collections::COLLECTION_LIST_CALLS,
+        "collection modify" => collections::COLLECTION_MODIFY_CALLS,
+        "collection show" => collections::COLLECTION_SHOW_CALLS,
+        "object create" => objects::OBJECT_CREATE_CALLS,
+        "object delete" => objects::OBJECT_DELETE_CALLS,
+        "object list" => objects::OBJECT_LIST_CALLS,
+        "object modify" => objects::OBJECT_MODIFY_CALLS,
+        "object show" => objects::OBJECT_SHOW_CALLS,
+        "group add_user" => groups::GROUP_ADD_USER_CALLS,
+        "group create" => groups::GROUP_CREATE_CALLS,
+        "group list" => groups::GROUP_LIST_CALLS,
+        "group remove_user" => groups::GROUP_REMOVE_USER_CALLS,
+        "group show" => groups::GROUP_SHOW_CALLS,
+        "user create" => users::USER_CREATE_CALLS,
+        "user delete" => users::USER_DELETE_CALLS,
+        "user list" => users::USER_LIST_CALLS,
+        "user show" => users::USER_SHOW_CALLS,
+        "audit local" | "config encrypt" | "config paths" | "config show" | "theme list"
+        | "theme preview" | "theme show" | "token list" | "token prune" => &[],
+        _ => return None,
+    };
+    Some(calls)
+}
@@ -2,23 +2,27 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 
 use hubuum_client::{FilterOperator, ObjectDataPatchDocument, ObjectPatch, ObjectPost};
+use hubuum_filter::Collation;
 use json_patch::{patch as apply_json_patch, Patch};
 use reqwest::StatusCode;
 use serde_json::Value;
 
+use crate::config::get_config;
 use crate::domain::{
-    build_related_object_tree, observed_json_pointers, ObjectDataMutationOutcome,
+    build_related_object_tree, observed_json_pointers, DesiredObject, ObjectDataMutationOutcome,
     ObjectDataMutationRecord, ObjectShowRecord, ResolvedObjectRecord,
 };
-use crate::errors::AppError;
+use crate::errors::{ApiContext, AppError};
 use crate::list_query::{
     apply_cursor_request_paging, apply_query_paging, validate_filter_clauses,
     validate_sort_clauses, FilterFieldSpec, FilterOperatorProfile, FilterValueProfile,
     FilterValueResolver, ListQuery, PagedResult, SortDirectionArg, SortFieldSpec,
     ValidatedSortClause,
 };
+use crate::safety::{guard_object_data, guard_object_data_patch};
+use crate::services::Checkpoint;
 
-use super::{shared::find_entities_by_ids, HubuumGateway, RelationTraversalOptions};
+use super::{ApiCall, HubuumGateway, RelationTraversalOptions};
 
 #[derive(Debug, Clone)]
 pub struct CreateObjectInput {
@@ -40,6 +44,13 @@ pub struct ObjectUpdateInput {
     pub data: Option<Value>,
 }
 
+/// The class and name an object id resolved to.
+#[derive(Debug, Clone)]
+pub struct ObjectLocation {
+    pub class_name: String,
+    pub object_name: String,
+}
+
 #[derive(Debug, Clone)]
 enum MissingObjectPolicy {
     Error,
@@ -94,8 +105,16 @@ impl HubuumGateway {
         &self,
         input: ObjectDataPatchInput,
     ) -> Result<ObjectDataMutationRecord, AppError> {
+        guard_object_data_patch(
+            &get_config().data,
+            &format!(
+                "patch the data of object '{}' in class '{}'",
+                input.object_name, input.class_name
+            ),
+            &input.patch,
+        )?;
         let objects = self
-            .client
+            .client()
             .class_by_name(input.class_name.clone())
             .objects();
         let object = objects.by_name(input.object_name.clone());
@@ -138,9 +157,9 @@ impl HubuumGateway {
         sample_limit: usize,
         max_depth: usize,
     ) -> Result<Vec<String>, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
+        let class = self.resolve_class(class_name)?;
         let objects = self
-            .client
+            .client()
             .objects(class.id())
             .query()
             .limit(sample_limit)
@@ -152,9 +171,9 @@ impl HubuumGateway {
     }
 
     pub fn list_object_names_for_class(&self, class_name: &str) -> Result<Vec<String>, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
+        let class = self.resolve_class(class_name)?;
         Ok(self
-            .client
+            .client()
             .objects(class.id())
             .query()
             .list()?
@@ -163,14 +182,33 @@ impl HubuumGateway {
             .collect())
     }
 
+    /// Every object in `class_name`, fetched page by page.
+    pub fn all_objects_in_class(
+        &self,
+        class_name: &str,
+    ) -> Result<Vec<ResolvedObjectRecord>, AppError> {
+        let class = self.resolve_class(class_name)?;
+        let objects = self.client().objects(class.id()).query().all()?;
+        let classmap = self.class_map_from_classes([class.resource()]);
+        let collectionmap = self.find_entities_by_ids(
+            |client| client.collections(),
+            objects.iter(),
+            |object| object.collection_id,
+        )?;
+        Ok(objects
+            .iter()
+            .map(|object| ResolvedObjectRecord::new(object, &classmap, &collectionmap))
+            .collect())
+    }
+
     pub fn list_object_names_for_class_prefix(
         &self,
         class_name: &str,
         prefix: &str,
     ) -> Result<Vec<String>, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
+        let class = self.resolve_class(class_name)?;
         Ok(self
-            .client
+            .client()
             .objects(class.id())
             .query()
             .filter(
@@ -185,20 +223,57 @@ impl HubuumGateway {
             .collect())
     }
 
+    /// Creates every object in parallel under the `bulk` limits, with one result per
+    /// object in the same order.
+    /// Creates `objects` under the `bulk` limits, skipping the ones `checkpoint` holds
+    /// from an earlier run; those have no result.
+    pub fn create_objects(
+        &self,
+        objects: &[DesiredObject],
+        checkpoint: &Checkpoint,
+    ) -> Vec<Option<Result<(), AppError>>> {
+        self.bulk_runner()
+            .run_resumable(objects, checkpoint, DesiredObject::key, |object| {
+                self.create_object(CreateObjectInput {
+                    name: object.name.clone(),
+                    class_name: object.class.clone(),
+                    collection: object.collection.clone(),
+                    description: object.description.clone(),
+                    data: object.data.clone(),
+                })
+                .map(|_| ())
+            })
+    }
+
     pub fn create_object(
         &self,
         input: CreateObjectInput,
     ) -> Result<ResolvedObjectRecord, AppError> {
-        let collection = self.client.collections().get_by_name(&input.collection)?;
-        let class = self.client.classes().get_by_name(&input.class_name)?;
-
-        let object = self.client.objects(class.id()).create_raw(ObjectPost {
-            name: input.name,
-            hubuum_class_id: Some(class.id()),
-            collection_id: Some(collection.id()),
-            description: input.description,
-            data: input.data,
-        })?;
+        if let Some(data) = &input.data {
+            guard_object_data(
+                &get_config().data,
+                &format!(
+                    "create object '{}' in class '{}'",
+                    input.name, input.class_name
+                ),
+                data,
+            )?;
+        }
+        let collection = self.resolve_collection(&input.collection)?;
+        let class = self.resolve_class(&input.class_name)?;
+
+        let name = input.name.clone();
+        let object = self
+            .client()
+            .objects(class.id())
+            .create_raw(ObjectPost {
+                name: input.name,
+                hubuum_class_id: Some(class.id()),
+                collection_id: Some(collection.id()),
+                description: input.description,
+                data: input.data,
+            })
+            .context(|| format!("creating object '{name}' in class '{}'", input.class_name))?;
 
         let classmap = HashMap::from([(class.id().into(), class.resource().clone())]);
         let collectionmap =
@@ -216,10 +291,10 @@ impl HubuumGateway {
         class_name: &str,
         object_name: &str,
     ) -> Result<ResolvedObjectRecord, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
-        let object = class.object_by_name(object_name)?;
+        let class = self.resolve_class(class_name)?;
+        let object = self.resolve_object_in(&class, object_name)?;
         let collection = self
-            .client
+            .client()
             .collections()
             .get(object.resource().collection_id)?;
 
@@ -234,6 +309,41 @@ impl HubuumGateway {
         ))
     }
 
+    /// Finds the owning class of an object id, searching only `class_name` when given.
+    ///
+    /// The API has no cross-class object lookup, so each visible class is asked in turn.
+    pub fn locate_object_by_id(
+        &self,
+        object_id: i32,
+        class_name: Option<&str>,
+    ) -> Result<ObjectLocation, AppError> {
+        let classes = match class_name {
+            Some(class_name) => vec![self.resolve_class(class_name)?.resource().clone()],
+            None => self.client().classes().query().all()?,
+        };
+        for class in classes {
+            let found = self
+                .client()
+                .objects(class.id)
+                .query()
+                .filter(
+                    "id",
+                    FilterOperator::Equals { is_negated: false },
+                    object_id,
+                )
+                .list()?;
+            if let Some(object) = found.into_iter().next() {
+                return Ok(ObjectLocation {
+                    class_name: class.name,
+                    object_name: object.name,
+                });
+            }
+        }
+        Err(AppError::EntityNotFound(format!(
+            "object with id {object_id}"
+        )))
+    }
+
     pub fn object_show_details(
         &self,
         class_name: &str,
@@ -241,10 +351,10 @@ impl HubuumGateway {
         options: &RelationTraversalOptions,
         include_computed: bool,
     ) -> Result<ObjectShowRecord, AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
-        let object = class.object_by_name(object_name)?;
+        let class = self.resolve_class(class_name)?;
+        let object = self.resolve_object_in(&class, object_name)?;
         let collection = self
-            .client
+            .client()
             .collections()
             .get(object.resource().collection_id)?;
 
@@ -254,7 +364,7 @@ impl HubuumGateway {
         let mut object_record =
             ResolvedObjectRecord::new(object.resource(), &classmap, &collectionmap);
         if include_computed {
-            let computed = self.client.computed_object(class.id(), object.id())?;
+            let computed = self.client().computed_object(class.id(), object.id())?;
             object_record = object_record.with_computed(serde_json::to_value(computed.computed)?);
         }
         let related_graph = object
@@ -293,10 +403,26 @@ impl HubuumGateway {
         })
     }
 
+    /// The collection of the object `delete_object` would delete.
+    pub fn object_collection_name(
+        &self,
+        class_name: &str,
+        object_name: &str,
+    ) -> Result<String, AppError> {
+        let (_, object) = self.resolve_object_exact(class_name, object_name)?;
+        let collection = self
+            .client()
+            .collections()
+            .get(object.resource().collection_id)?;
+        Ok(collection.resource().name.clone())
+    }
+
     pub fn delete_object(&self, class_name: &str, object_name: &str) -> Result<(), AppError> {
-        let class = self.client.classes().get_by_name(class_name)?;
-        let object = class.object_by_name(object_name)?;
-        self.client.objects(class.id()).delete(object.id())?;
+        let (class, object) = self.resolve_object_exact(class_name, object_name)?;
+        self.client()
+            .objects(class.id())
+            .delete(object.id())
+            .context(|| format!("deleting object '{object_name}' in class '{class_name}'"))?;
         Ok(())
     }
 
@@ -332,7 +458,7 @@ impl HubuumGateway {
             .iter()
             .find(|clause| clause.spec.public_name == "class")
             .ok_or_else(|| AppError::MissingOptions(vec!["class".to_string()]))?;
-        let class = self.client.classes().get_by_name(&class_filter.value)?;
+        let class = self.resolve_class(&class_filter.value)?;
 
         let filters = validated
             .iter()
@@ -342,18 +468,20 @@ impl HubuumGateway {
 
         if has_computed_sort {
             let fetched = self
-                .client
+                .client()
                 .computed_objects(class.id())
                 .filters(filters)
                 .all()?;
-            let classmap =
-                find_entities_by_ids(&self.client.classes(), fetched.iter(), |object| {
-                    object.object.hubuum_class_id
-                })?;
-            let collectionmap =
-                find_entities_by_ids(&self.client.collections(), fetched.iter(), |object| {
-                    object.object.collection_id
-                })?;
+            let classmap = self.find_entities_by_ids(
+                |client| client.classes(),
+                fetched.iter(),
+                |object| object.object.hubuum_class_id,
+            )?;
+            let collectionmap = self.find_entities_by_ids(
+                |client| client.collections(),
+                fetched.iter(),
+                |object| object.object.collection_id,
+            )?;
             let mut items = fetched
                 .into_iter()
                 .map(|object| {
@@ -363,7 +491,11 @@ impl HubuumGateway {
                     )
                 })
                 .collect::<Result<Vec<_>, AppError>>()?;
-            sort_objects_locally(&mut items, &object_sorts);
+            sort_objects_locally(
+                &mut items,
+                &object_sorts,
+                get_config().output.sort_collation.into(),
+            );
             let total_count = query.include_total.then_some(items.len() as u64);
             if let Some(limit) = query.limit {
                 items.truncate(limit);
@@ -379,7 +511,7 @@ impl HubuumGateway {
 
         if include_computed {
             let page = apply_cursor_request_paging(
-                self.client.computed_objects(class.id()).filters(filters),
+                self.client().computed_objects(class.id()).filters(filters),
                 query,
                 &validated_sorts,
             )
@@ -393,14 +525,16 @@ impl HubuumGateway {
                 });
             }
 
-            let classmap =
-                find_entities_by_ids(&self.client.classes(), page.items.iter(), |object| {
-                    object.object.hubuum_class_id
-                })?;
-            let collectionmap =
-                find_entities_by_ids(&self.client.collections(), page.items.iter(), |object| {
-                    object.object.collection_id
-                })?;
+            let classmap = self.find_entities_by_ids(
+                |client| client.classes(),
+                page.items.iter(),
+                |object| object.object.hubuum_class_id,
+            )?;
+            let collectionmap = self.find_entities_by_ids(
+                |client| client.collections(),
+                page.items.iter(),
+                |object| object.object.collection_id,
+            )?;
             let returned_count = page.items.len();
             let items = page
                 .items
@@ -421,7 +555,7 @@ impl HubuumGateway {
         }
 
         let page = apply_query_paging(
-            self.client.objects(class.id()).query().filters(filters),
+            self.client().objects(class.id()).query().filters(filters),
             query,
             &validated_sorts,
         )
@@ -435,13 +569,16 @@ impl HubuumGateway {
             });
         }
 
-        let classmap = find_entities_by_ids(&self.client.classes(), page.items.iter(), |object| {
-            object.hubuum_class_id
-        })?;
-        let collectionmap =
-            find_entities_by_ids(&self.client.collections(), page.items.iter(), |object| {
-                object.collection_id
-            })?;
+        let classmap = self.find_entities_by_ids(
+            |client| client.classes(),
+            page.items.iter(),
+            |object| object.hubuum_class_id,
+        )?;
+        let collectionmap = self.find_entities_by_ids(
+            |client| client.collections(),
+            page.items.iter(),
+            |object| object.collection_id,
+        )?;
 
         Ok(PagedResult::from_page(page, |object| {
             ResolvedObjectRecord::new(&object, &classmap, &collectionmap)
@@ -452,8 +589,18 @@ impl HubuumGateway {
         &self,
         input: ObjectUpdateInput,
     ) -> Result<ResolvedObjectRecord, AppError> {
-        let class = self.client.classes().get_by_name(&input.class_name)?;
-        let object = class.object_by_name(&input.name)?;
+        if let Some(data) = &input.data {
+            guard_object_data(
+                &get_config().data,
+                &format!(
+                    "update object '{}' in class '{}'",
+                    input.name, input.class_name
+                ),
+                data,
+            )?;
+        }
+        let class = self.resolve_class(&input.class_name)?;
+        let object = self.resolve_object_in(&class, &input.name)?;
         let mut result_class = class.resource().clone();
 
         let mut patch = ObjectPatch {
@@ -462,11 +609,11 @@ impl HubuumGateway {
         };
 
         if let Some(collection) = input.collection {
-            let collection = self.client.collections().get_by_name(&collection)?;
+            let collection = self.resolve_collection(&collection)?;
             patch.collection_id = Some(collection.id());
         }
         if let Some(reclass) = input.reclass {
-            let reclass = self.client.classes().get_by_name(&reclass)?;
+            let reclass = self.resolve_class(&reclass)?;
             patch.hubuum_class_id = Some(reclass.id());
             result_class = reclass.resource().clone();
         }
@@ -478,10 +625,16 @@ impl HubuumGateway {
         }
 
         let result = self
-            .client
+            .client()
             .objects(class.id())
-            .update_raw(object.id(), patch)?;
-        let collection = self.client.collections().get(result.collection_id)?;
+            .update_raw(object.id(), patch)
+            .context(|| {
+                format!(
+                    "updating object '{}' in class '{}'",
+                    input.name, input.class_name
+                )
+            })?;
+        let collection = self.client().collections().get(result.collection_id)?;
 
         let classmap = HashMap::from([(result_class.id.into(), result_class)]);
         let collectionmap =
@@ -592,16 +745,20 @@ fn validate_object_sort_clauses(query: &ListQuery) -> Result<Vec<ObjectSortClaus
         .collect()
 }
 
-fn sort_objects_locally(objects: &mut [ResolvedObjectRecord], sorts: &[ObjectSortClause]) {
+fn sort_objects_locally(
+    objects: &mut [ResolvedObjectRecord],
+    sorts: &[ObjectSortClause],
+    collation: Collation,
+) {
     objects.sort_by(|left, right| {
         for sort in sorts {
             let (ordering, direction) = match sort {
                 ObjectSortClause::Standard(sort) => (
-                    compare_standard_object_field(sort.spec.public_name, left, right),
+                    compare_standard_object_field(sort.spec.public_name, left, right, collation),
                     sort.direction,
                 ),
                 ObjectSortClause::Computed { field, direction } => (
-                    compare_json_sort_values(field.value(left), field.value(right)),
+                    compare_json_sort_values(field.value(left), field.value(right), collation),
                     *direction,
                 ),
             };
@@ -621,29 +778,34 @@ fn compare_standard_object_field(
     field: &str,
     left: &ResolvedObjectRecord,
     right: &ResolvedObjectRecord,
+    collation: Collation,
 ) -> Ordering {
     match field {
         "id" => left.id.cmp(&right.id),
-        "name" => left.name.cmp(&right.name),
-        "description" => left.description.cmp(&right.description),
-        "collection" => left.collection.cmp(&right.collection),
-        "class" => left.class.cmp(&right.class),
+        "name" => collation.compare(&left.name, &right.name),
+        "description" => collation.compare(&left.description, &right.description),
+        "collection" => collation.compare(&left.collection, &right.collection),
+        "class" => collation.compare(&left.class, &right.class),
         "created_at" => left.created_at.cmp(&right.created_at),
         "updated_at" => left.updated_at.cmp(&right.updated_at),
         _ => Ordering::Equal,
     }
 }
 
-fn compare_json_sort_values(left: Option<&Value>, right: Option<&Value>) -> Ordering {
+fn compare_json_sort_values(
+    left: Option<&Value>,
+    right: Option<&Value>,
+    collation: Collation,
+) -> Ordering {
     match (left, right) {
         (None, None) => Ordering::Equal,
         (None, Some(_)) => Ordering::Less,
         (Some(_), None) => Ordering::Greater,
-        (Some(left), Some(right)) => compare_json_values(left, right),
+        (Some(left), Some(right)) => compare_json_values(left, right, collation),
     }
 }
 
-fn compare_json_values(left: &Value, right: &Value) -> Ordering {
+fn compare_json_values(left: &Value, right: &Value, collation: Collation) -> Ordering {
     let rank = |value: &Value| match value {
         Value::Null => 0,
         Value::Bool(_) => 1,
@@ -664,7 +826,7 @@ fn compare_json_values(left: &Value, right: &Value) -> Ordering {
             .zip(right.as_f64())
             .map(|(left, right)| left.total_cmp(&right))
             .unwrap_or_else(|| left.to_string().cmp(&right.to_string())),
-        (Value::String(left), Value::String(right)) => left.cmp(right),
+        (Value::String(left), Value::String(right)) => collation.compare(left, right),
         (Value::Array(_), Value::Array(_)) | (Value::Object(_), Value::Object(_)) => {
             left.to_string().cmp(&right.to_string())
         }
@@ -742,6 +904,87 @@ pub(crate) const OBJECT_SORT_SPECS: &[SortFieldSpec] = &[
     SortFieldSpec::new("updated_at", "updated_at"),
 ];
 
+pub(crate) const OBJECT_CREATE_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/collections", "Look up collection '{collection}'")
+        .with_filters(&["name={collection}"]),
+    ApiCall::get("/api/v1/classes/by-name/{class}", "Look up class '{class}'"),
+    ApiCall::post("/api/v1/classes/{class_id}/", "Create object '{name}'"),
+];
+
+pub(crate) const OBJECT_DELETE_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/classes/by-name/{class}", "Look up class '{class}'"),
+    ApiCall::get("/api/v1/classes/{class_id}/", "Look up object '{name}'")
+        .with_filters(&["name={name}"]),
+    ApiCall::delete(
+        "/api/v1/classes/{class_id}/{object_id}",
+        "Delete the object",
+    ),
+];
+
+pub(crate) const OBJECT_MODIFY_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/classes/by-name/{class}", "Look up class '{class}'"),
+    ApiCall::get("/api/v1/classes/{class_id}/", "Look up object '{name}'")
+        .with_filters(&["name={name}"]),
+    ApiCall::get("/api/v1/collections", "Look up collection '{collection}'")
+        .with_filters(&["name={collection}"])
+        .when("collection"),
+    ApiCall::get(
+        "/api/v1/classes/by-name/{reclass}",
+        "Look up class '{reclass}'",
+    )
+    .when("reclass"),
+    ApiCall::patch(
+        "/api/v1/classes/{class_id}/{object_id}",
+        "Update the object",
+    ),
+    ApiCall::get(
+        "/api/v1/collections/{collection_id}",
+        "Name the collection of the object",
+    ),
+];
+
+pub(crate) const OBJECT_SHOW_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/classes/by-name/{class}", "Look up class '{class}'"),
+    ApiCall::get("/api/v1/classes/{class_id}/", "Look up object '{name}'")
+        .with_filters(&["name={name}"]),
+    ApiCall::get(
+        "/api/v1/collections/{collection_id}",
+        "Name the collection of the object",
+    ),
+    ApiCall::get(
+        "/api/v1/classes/{class_id}/{object_id}",
+        "Fetch the computed fields",
+    )
+    .when("computed"),
+    ApiCall::get(
+        "/api/v1/classes/{class_id}/objects/{object_id}/related/graph",
+        "Fetch the related objects",
+    )
+    .with_filters(&["depth__lte={max_depth}"]),
+    ApiCall::get("/api/v1/classes", "Name the classes of the related objects")
+        .with_filters(&["id__equals={class_ids}"]),
+    ApiCall::get(
+        "/api/v1/collections",
+        "Name the collections of the related objects",
+    )
+    .with_filters(&["id__equals={collection_ids}"]),
+];
+
+pub(crate) const OBJECT_LIST_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/classes/by-name/{class}", "Look up class '{class}'"),
+    ApiCall::get("/api/v1/classes/{class_id}/", "List objects").listing(&[
+        "name icontains {name}",
+        "description icontains {description}",
+    ]),
+    ApiCall::get("/api/v1/classes", "Name the classes of the listed objects")
+        .with_filters(&["id__equals={class_ids}"]),
+    ApiCall::get(
+        "/api/v1/collections",
+        "Name the collections of the listed objects",
+    )
+    .with_filters(&["id__equals={collection_ids}"]),
+];
+
 #[cfg(test)]
 mod tests {
     use std::io::{Read, Write};
@@ -754,6 +997,7 @@ mod tests {
         blocking::Client as BlockingClient, BaseUrl, ObjectDataPatchDocument,
         ObjectDataPatchOperation, Token,
     };
+    use hubuum_filter::Collation;
     use serde_json::json;
 
     use crate::domain::{ObjectDataMutationOutcome, ResolvedObjectRecord};
@@ -1063,7 +1307,7 @@ mod tests {
         })
         .expect("sort should validate");
 
-        sort_objects_locally(&mut objects, &sorts);
+        sort_objects_locally(&mut objects, &sorts, Collation::Ascii);
 
         assert_eq!(
             objects.iter().map(|object| object.id).collect::<Vec<_>>(),
@@ -1071,6 +1315,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn natural_collation_orders_names_by_their_numbers() {
+        let mut objects = ["web10", "web2", "web1"]
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| ResolvedObjectRecord {
+                name: name.to_string(),
+                ..computed_object(index as i32 + 1, json!({}))
+            })
+            .collect::<Vec<_>>();
+        let sorts = validate_object_sort_clauses(&ListQuery {
+            sorts: vec![SortClause {
+                field: "name".to_string(),
+                direction: SortDirectionArg::Asc,
+            }],
+            ..ListQuery::default()
+        })
+        .expect("sort should validate");
+
+        sort_objects_locally(&mut objects, &sorts, Collation::Natural);
+
+        assert_eq!(
+            objects
+                .iter()
+                .map(|object| object.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["web1", "web2", "web10"]
+        );
+    }
+
     fn computed_object(id: i32, computed: serde_json::Value) -> ResolvedObjectRecord {
         ResolvedObjectRecord {
             id,
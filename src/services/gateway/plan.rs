@@ -0,0 +1,92 @@
+//! The API requests behind commands, as listed by `--explain`.
+//!
+//! Plans are declared in the gateway module that sends the requests, next to the filter
+//! and sort specs, so a change to the requests and to their plan lands in one place.
+
+/// One API request a command makes.
+///
+/// `{option}` placeholders in the path and filters stand for the value given to that
+/// option; other placeholders, such as `{class_id}`, come from earlier responses.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ApiCall {
+    method: &'static str,
+    path: &'static str,
+    purpose: &'static str,
+    filters: &'static [&'static str],
+    listing: Option<&'static [&'static str]>,
+    condition: Option<&'static str>,
+}
+
+impl ApiCall {
+    const fn new(method: &'static str, path: &'static str, purpose: &'static str) -> Self {
+        Self {
+            method,
+            path,
+            purpose,
+            filters: &[],
+            listing: None,
+            condition: None,
+        }
+    }
+
+    pub(crate) const fn get(path: &'static str, purpose: &'static str) -> Self {
+        Self::new("GET", path, purpose)
+    }
+
+    pub(crate) const fn post(path: &'static str, purpose: &'static str) -> Self {
+        Self::new("POST", path, purpose)
+    }
+
+    pub(crate) const fn patch(path: &'static str, purpose: &'static str) -> Self {
+        Self::new("PATCH", path, purpose)
+    }
+
+    pub(crate) const fn delete(path: &'static str, purpose: &'static str) -> Self {
+        Self::new("DELETE", path, purpose)
+    }
+
+    /// Query parameters the request always carries, as `field=value` or `field__op=value`.
+    pub(crate) const fn with_filters(mut self, filters: &'static [&'static str]) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    /// Marks the page request that takes `--where`, `--sort`, `--limit`, and `--cursor`.
+    ///
+    /// `clauses` are `--where` clauses that options such as `--name` stand for; each is
+    /// only sent when its options are given.
+    pub(crate) const fn listing(mut self, clauses: &'static [&'static str]) -> Self {
+        self.listing = Some(clauses);
+        self
+    }
+
+    /// Only sends the request when `option` is given.
+    pub(crate) const fn when(mut self, option: &'static str) -> Self {
+        self.condition = Some(option);
+        self
+    }
+
+    pub(crate) fn method(&self) -> &'static str {
+        self.method
+    }
+
+    pub(crate) fn path(&self) -> &'static str {
+        self.path
+    }
+
+    pub(crate) fn purpose(&self) -> &'static str {
+        self.purpose
+    }
+
+    pub(crate) fn filters(&self) -> &'static [&'static str] {
+        self.filters
+    }
+
+    pub(crate) fn listing_clauses(&self) -> Option<&'static [&'static str]> {
+        self.listing
+    }
+
+    pub(crate) fn condition(&self) -> Option<&'static str> {
+        self.condition
+    }
+}
@@ -1,25 +1,25 @@
 use std::collections::HashMap;
 use std::iter::once;
-use std::mem::swap;
 use std::slice::from_ref;
 
 use hubuum_client::{
     client::sync::Handle as SyncHandle, Class, ClassRelation, ClassWithPath, FilterOperator,
-    Object, ObjectRelation, ObjectWithPath, Page,
+    Object, ObjectId, ObjectRelation, ObjectRelationPost, ObjectWithPath, Page,
 };
 
 use crate::domain::{
-    ResolvedClassRelationRecord, ResolvedObjectRelationRecord, ResolvedRelatedClassGraph,
-    ResolvedRelatedClassRecord, ResolvedRelatedObjectGraph, ResolvedRelatedObjectRecord,
+    from_to, ClassRelationMatrix, ResolvedClassRelationRecord, ResolvedObjectRelationRecord,
+    ResolvedRelatedClassGraph, ResolvedRelatedClassRecord, ResolvedRelatedObjectGraph,
+    ResolvedRelatedObjectRecord,
 };
-use crate::errors::AppError;
+use crate::errors::{ApiContext, AppError};
 use crate::list_query::{
     apply_cursor_request_paging, validate_filter_clauses, validate_sort_clauses, FilterClause,
     FilterFieldSpec, FilterOperatorProfile, FilterValueProfile, ListQuery, PagedResult,
     SortFieldSpec,
 };
 
-use super::{shared::find_entities_by_ids, HubuumGateway};
+use super::HubuumGateway;
 
 #[derive(Debug, Clone)]
 pub struct RelationTarget {
@@ -55,7 +55,7 @@ impl HubuumGateway {
     ) -> Result<PagedResult<ResolvedRelatedClassRecord>, AppError> {
         let validated = validate_filter_clauses(&query.filters, RELATED_CLASS_FILTER_SPECS)?;
         let validated_sorts = validate_sort_clauses(&query.sorts, RELATED_CLASS_SORT_SPECS)?;
-        let class = self.class_handle_by_name(root_class)?;
+        let class = self.resolve_class(root_class)?;
         let filters = validated
             .iter()
             .map(|clause| self.resolve_validated_filter(clause))
@@ -77,7 +77,7 @@ impl HubuumGateway {
     ) -> Result<PagedResult<ResolvedClassRelationRecord>, AppError> {
         let validated = validate_filter_clauses(&query.filters, CLASS_RELATION_FILTER_SPECS)?;
         let validated_sorts = validate_sort_clauses(&query.sorts, CLASS_RELATION_SORT_SPECS)?;
-        let class = self.class_handle_by_name(root_class)?;
+        let class = self.resolve_class(root_class)?;
         let filters = validated
             .iter()
             .map(|clause| self.resolve_validated_filter(clause))
@@ -98,18 +98,38 @@ impl HubuumGateway {
         }
 
         let class_map = self.class_map_from_relation_ids(&page.items)?;
+        let class_id = class.id().into();
         Ok(PagedResult::from_page(page, |relation| {
-            ResolvedClassRelationRecord::new(&relation, &class_map)
+            ResolvedClassRelationRecord::new(&relation, &class_map).seen_from(class_id)
         }))
     }
 
+    /// Every direct class relation touching `class_name`, in either direction.
+    pub fn class_relations_touching(
+        &self,
+        class_name: &str,
+    ) -> Result<Vec<ResolvedClassRelationRecord>, AppError> {
+        let class = self.resolve_class(class_name)?;
+        let relations = class.related_relations().all()?;
+        if relations.is_empty() {
+            return Ok(Vec::new());
+        }
+        let class_map = self.class_map_from_relation_ids(&relations)?;
+        Ok(relations
+            .iter()
+            .map(|relation| {
+                ResolvedClassRelationRecord::new(relation, &class_map).seen_from(class.id().into())
+            })
+            .collect())
+    }
+
     pub fn related_class_graph(
         &self,
         root_class: &str,
         filters: &[FilterClause],
     ) -> Result<ResolvedRelatedClassGraph, AppError> {
         let validated = validate_filter_clauses(filters, RELATED_CLASS_FILTER_SPECS)?;
-        let class = self.class_handle_by_name(root_class)?;
+        let class = self.resolve_class(root_class)?;
         let filters = validated
             .iter()
             .map(|clause| self.resolve_validated_filter(clause))
@@ -155,7 +175,10 @@ impl HubuumGateway {
             relations: graph
                 .relations
                 .iter()
-                .map(|relation| ResolvedClassRelationRecord::new(relation, &class_map))
+                .map(|relation| {
+                    ResolvedClassRelationRecord::new(relation, &class_map)
+                        .seen_from(class.id().into())
+                })
                 .collect(),
         })
     }
@@ -169,7 +192,55 @@ impl HubuumGateway {
         let relation =
             self.find_class_relation_between(classes.0.id.into(), classes.1.id.into())?;
         let class_map = self.class_map_from_classes([&classes.0, &classes.1]);
-        Ok(ResolvedClassRelationRecord::new(&relation, &class_map))
+        let object_relations = self.object_relation_count(relation.id.into())?;
+        Ok(ResolvedClassRelationRecord::new(&relation, &class_map)
+            .seen_from(classes.0.id.into())
+            .with_object_relations(object_relations))
+    }
+
+    /// Builds the class × class relation matrix, optionally limited to one collection.
+    ///
+    /// With `object_counts`, every class relation also gets its object relation count,
+    /// fetched in parallel under the `bulk` limits.
+    pub fn class_relation_matrix(
+        &self,
+        collection: Option<&str>,
+        object_counts: bool,
+    ) -> Result<ClassRelationMatrix, AppError> {
+        let classes = self.class_names_in_collection(collection)?;
+        let relations = self.client().class_relation().query().all()?;
+        let class_map = self.class_map_from_relation_ids(&relations)?;
+        let mut records = relations
+            .iter()
+            .map(|relation| ResolvedClassRelationRecord::new(relation, &class_map))
+            .filter(|record| classes.contains(&record.class_a) && classes.contains(&record.class_b))
+            .collect::<Vec<_>>();
+
+        if object_counts {
+            let runner = self.bulk_runner();
+            let counts = runner.run(&records, |record| self.object_relation_count(record.id));
+            for (record, count) in records.iter_mut().zip(counts) {
+                record.object_relations = count?;
+            }
+        }
+        Ok(ClassRelationMatrix::new(classes, records))
+    }
+
+    /// Counts the object relations using one class relation, asking the server only for its total.
+    fn object_relation_count(&self, class_relation_id: i32) -> Result<Option<u64>, AppError> {
+        Ok(self
+            .client()
+            .object_relation()
+            .query()
+            .filter(
+                "class_relation",
+                FilterOperator::Equals { is_negated: false },
+                class_relation_id,
+            )
+            .include_total(true)
+            .limit(1)
+            .page()?
+            .total_count)
     }
 
     pub fn delete_class_relation_by_pair(
@@ -180,8 +251,9 @@ impl HubuumGateway {
         let classes = self.class_pair(class_a, class_b)?;
         let relation =
             self.find_class_relation_between(classes.0.id.into(), classes.1.id.into())?;
-        self.class_handle_by_name(class_a)?
-            .delete_relation(relation.id)?;
+        self.resolve_class(class_a)?
+            .delete_relation(relation.id)
+            .context(|| format!("deleting relation between classes '{class_a}' and '{class_b}'"))?;
         Ok(())
     }
 
@@ -192,7 +264,7 @@ impl HubuumGateway {
     ) -> Result<PagedResult<ResolvedObjectRelationRecord>, AppError> {
         let validated = validate_filter_clauses(&query.filters, OBJECT_RELATION_FILTER_SPECS)?;
         let validated_sorts = validate_sort_clauses(&query.sorts, OBJECT_RELATION_SORT_SPECS)?;
-        let object = self.object_handle_by_name(&root.root_class, &root.root_object)?;
+        let object = self.resolve_object(&root.root_class, &root.root_object)?;
         let filters = validated
             .iter()
             .map(|clause| self.resolve_validated_filter(clause))
@@ -203,16 +275,18 @@ impl HubuumGateway {
             &validated_sorts,
         )
         .page()?;
-        self.resolve_object_relation_page(page)
+        self.resolve_object_relation_page(page, object.id().into())
     }
 
     pub fn get_object_relation_v2(
         &self,
         target: &RelationTarget,
     ) -> Result<ResolvedObjectRelationRecord, AppError> {
-        let (object_a, object_b) = self.canonical_object_relation_handles(target)?;
-        let relation = object_a.relation_to(object_b.resource().hubuum_class_id, object_b.id())?;
-        self.resolve_object_relation_record(relation.resource())
+        let (from, to, object_a_id) = self.canonical_object_relation_handles(target)?;
+        let relation = from.relation_to(to.resource().hubuum_class_id, to.id())?;
+        Ok(self
+            .resolve_object_relation_record(relation.resource())?
+            .seen_from(object_a_id))
     }
 
     pub fn create_class_relation_v2(
@@ -220,34 +294,71 @@ impl HubuumGateway {
         class_a: &str,
         class_b: &str,
     ) -> Result<ResolvedClassRelationRecord, AppError> {
-        let mut classes = (
-            self.class_handle_by_name(class_a)?,
-            self.class_handle_by_name(class_b)?,
-        );
-        let class_a_id: i32 = classes.0.id().into();
-        let class_b_id: i32 = classes.1.id().into();
-        if class_a_id > class_b_id {
-            swap(&mut classes.0, &mut classes.1);
-        }
-        let relation = classes.0.create_relation(classes.1.id())?;
+        let context = || format!("creating relation between classes '{class_a}' and '{class_b}'");
+        let class_a = self.resolve_class(class_a)?;
+        let class_b = self.resolve_class(class_b)?;
+        let class_a_id: i32 = class_a.id().into();
+        let class_b_id: i32 = class_b.id().into();
+        let (from, to) = from_to(class_a, class_b, class_a_id <= class_b_id);
+        let relation = from.create_relation(to.id()).context(context)?;
         let class_map =
             self.class_map_from_ids([relation.from_hubuum_class_id, relation.to_hubuum_class_id])?;
-        Ok(ResolvedClassRelationRecord::new(&relation, &class_map))
+        Ok(ResolvedClassRelationRecord::new(&relation, &class_map).seen_from(class_a_id))
     }
 
     pub fn create_object_relation_v2(
         &self,
         target: &RelationTarget,
     ) -> Result<ResolvedObjectRelationRecord, AppError> {
-        let (object_a, object_b) = self.canonical_object_relation_handles(target)?;
-        let relation =
-            object_a.create_relation_to(object_b.resource().hubuum_class_id, object_b.id())?;
+        let (from, to, object_a_id) = self.canonical_object_relation_handles(target)?;
+        let relation = from
+            .create_relation_to(to.resource().hubuum_class_id, to.id())
+            .context(|| format!("creating relation {}", describe_object_relation(target)))?;
+        Ok(self
+            .resolve_object_relation_record(&relation)?
+            .seen_from(object_a_id))
+    }
+
+    /// Creates an object relation from IDs the caller already resolved, skipping name lookups.
+    pub fn create_object_relation_by_ids(
+        &self,
+        class_relation_id: i32,
+        from_object_id: i32,
+        to_object_id: i32,
+    ) -> Result<ResolvedObjectRelationRecord, AppError> {
+        let relation = self
+            .client()
+            .object_relation()
+            .create_raw(ObjectRelationPost {
+                from_hubuum_object_id: ObjectId::new(from_object_id),
+                to_hubuum_object_id: ObjectId::new(to_object_id),
+                class_relation_id: class_relation_id.into(),
+            })?;
         self.resolve_object_relation_record(&relation)
     }
 
+    /// The collections of the two objects `delete_object_relation_v2` would unlink.
+    pub fn object_relation_collection_names(
+        &self,
+        target: &RelationTarget,
+    ) -> Result<Vec<String>, AppError> {
+        let (from, to, _) = self.canonical_object_relation_handles(target)?;
+        [from, to]
+            .iter()
+            .map(|object| {
+                let collection = self
+                    .client()
+                    .collections()
+                    .get(object.resource().collection_id)?;
+                Ok(collection.resource().name.clone())
+            })
+            .collect()
+    }
+
     pub fn delete_object_relation_v2(&self, target: &RelationTarget) -> Result<(), AppError> {
-        let (object_a, object_b) = self.canonical_object_relation_handles(target)?;
-        object_a.delete_relation_to(object_b.resource().hubuum_class_id, object_b.id())?;
+        let (from, to, _) = self.canonical_object_relation_handles(target)?;
+        from.delete_relation_to(to.resource().hubuum_class_id, to.id())
+            .context(|| format!("deleting relation {}", describe_object_relation(target)))?;
         Ok(())
     }
 
@@ -259,12 +370,12 @@ impl HubuumGateway {
     ) -> Result<PagedResult<ResolvedRelatedObjectRecord>, AppError> {
         let validated = validate_filter_clauses(&query.filters, RELATED_OBJECT_FILTER_SPECS)?;
         let validated_sorts = validate_sort_clauses(&query.sorts, RELATED_OBJECT_SORT_SPECS)?;
-        let object = self.object_handle_by_name(&root.root_class, &root.root_object)?;
+        let object = self.resolve_object(&root.root_class, &root.root_object)?;
         let ignore_classes = options
             .ignore_classes
             .iter()
             .map(|class_name| {
-                self.class_handle_by_name(class_name)
+                self.resolve_class(class_name)
                     .map(|class| class.id().into())
             })
             .collect::<Result<Vec<_>, _>>()?;
@@ -291,7 +402,7 @@ impl HubuumGateway {
         filters: &[FilterClause],
     ) -> Result<ResolvedRelatedObjectGraph, AppError> {
         let validated = validate_filter_clauses(filters, RELATED_OBJECT_FILTER_SPECS)?;
-        let object = self.object_handle_by_name(&root.root_class, &root.root_object)?;
+        let object = self.resolve_object(&root.root_class, &root.root_object)?;
         let filters = validated
             .iter()
             .map(|clause| self.resolve_validated_filter(clause))
@@ -317,8 +428,8 @@ impl HubuumGateway {
             .iter()
             .map(|object| Ok((i32::from(object.id), object_from_path(object)?)))
             .collect::<Result<HashMap<_, _>, AppError>>()?;
-        let class_relation_map = find_entities_by_ids(
-            &self.client.class_relation(),
+        let class_relation_map = self.find_entities_by_ids(
+            |client| client.class_relation(),
             graph.relations.iter(),
             |relation| relation.class_relation_id,
         )?;
@@ -364,7 +475,7 @@ impl HubuumGateway {
         relation: &ObjectRelation,
     ) -> Result<ResolvedObjectRelationRecord, AppError> {
         let class_relation = self
-            .client
+            .client()
             .class_relation()
             .get(relation.class_relation_id)?
             .resource()
@@ -389,6 +500,7 @@ impl HubuumGateway {
     fn resolve_object_relation_page(
         &self,
         page: Page<ObjectRelation>,
+        seen_from: i32,
     ) -> Result<PagedResult<ResolvedObjectRelationRecord>, AppError> {
         if page.items.is_empty() {
             return Ok(PagedResult {
@@ -399,8 +511,8 @@ impl HubuumGateway {
             });
         }
 
-        let class_relation_map = find_entities_by_ids(
-            &self.client.class_relation(),
+        let class_relation_map = self.find_entities_by_ids(
+            |client| client.class_relation(),
             page.items.iter(),
             |relation| relation.class_relation_id,
         )?;
@@ -418,6 +530,7 @@ impl HubuumGateway {
                 .get(&relation.class_relation_id.into())
                 .expect("class relation should be loaded");
             ResolvedObjectRelationRecord::new(&relation, class_relation, &object_map, &class_map)
+                .seen_from(seen_from)
         }))
     }
 
@@ -441,24 +554,23 @@ impl HubuumGateway {
             }
         }
 
-        let mut objects = HashMap::new();
-        for (class_id, object_ids) in grouped {
+        let grouped = grouped.into_iter().collect::<Vec<_>>();
+        let fetched = self.bulk_runner().run(&grouped, |(class_id, object_ids)| {
             let joined = object_ids
-                .into_iter()
+                .iter()
                 .map(|object_id| object_id.to_string())
                 .collect::<Vec<_>>()
                 .join(",");
-            for object in self
-                .client
-                .objects(class_id)
+            Ok(self
+                .client()
+                .objects(*class_id)
                 .query()
-                .filter(
-                    "id",
-                    FilterOperator::Equals { is_negated: false },
-                    joined.clone(),
-                )
-                .list()?
-            {
+                .filter("id", FilterOperator::Equals { is_negated: false }, joined)
+                .list()?)
+        });
+        let mut objects = HashMap::new();
+        for fetched in fetched {
+            for object in fetched? {
                 objects.insert(object.id.into(), object);
             }
         }
@@ -597,24 +709,27 @@ impl HubuumGateway {
             .collect()
     }
 
+    /// Resolves the target's objects in from → to order, along with the id of `object_a`
+    /// as the user named it.
     fn canonical_object_relation_handles(
         &self,
         target: &RelationTarget,
-    ) -> Result<(SyncHandle<Object>, SyncHandle<Object>), AppError> {
+    ) -> Result<(SyncHandle<Object>, SyncHandle<Object>, i32), AppError> {
         let (object_a_name, object_b_name) = validate_object_names(target)?;
-        let class_a = self.class_handle_by_name(&target.class_a)?;
-        let class_b = self.class_handle_by_name(&target.class_b)?;
-        let object_a = class_a.object_by_name(object_a_name)?;
-        let object_b = class_b.object_by_name(object_b_name)?;
+        let class_a = self.resolve_class(&target.class_a)?;
+        let class_b = self.resolve_class(&target.class_b)?;
+        let object_a = self.resolve_object_in(&class_a, object_a_name)?;
+        let object_b = self.resolve_object_in(&class_b, object_b_name)?;
         let class_a_id: i32 = class_a.id().into();
         let class_b_id: i32 = class_b.id().into();
         let class_relation = self.find_class_relation_between(class_a_id, class_b_id)?;
-
-        if class_relation.from_hubuum_class_id == class_a_id {
-            Ok((object_a, object_b))
-        } else {
-            Ok((object_b, object_a))
-        }
+        let object_a_id = object_a.id().into();
+        let (from, to) = from_to(
+            object_a,
+            object_b,
+            class_relation.from_hubuum_class_id == class_a_id,
+        );
+        Ok((from, to, object_a_id))
     }
 }
 
@@ -1067,6 +1182,17 @@ pub(crate) const RELATED_OBJECT_SORT_SPECS: &[SortFieldSpec] = &[
     SortFieldSpec::new("path", "path"),
 ];
 
+/// Names both ends of an object relation for error messages.
+fn describe_object_relation(target: &RelationTarget) -> String {
+    format!(
+        "between object '{}' in class '{}' and object '{}' in class '{}'",
+        target.object_a.as_deref().unwrap_or_default(),
+        target.class_a,
+        target.object_b.as_deref().unwrap_or_default(),
+        target.class_b
+    )
+}
+
 fn object_from_path(object: &ObjectWithPath) -> Result<Object, AppError> {
     Ok(serde_json::from_value(serde_json::to_value(object)?)?)
 }
@@ -5,7 +5,7 @@ use hubuum_client::{
 use serde_json::Value;
 
 use crate::domain::{RemoteTargetRecord, TaskRecord};
-use crate::errors::AppError;
+use crate::errors::{ApiContext, AppError};
 use crate::list_query::{
     apply_query_paging, validate_filter_clauses, validate_sort_clauses, FilterFieldSpec,
     FilterOperatorProfile, FilterValueProfile, ListQuery, PagedResult, SortFieldSpec,
@@ -127,7 +127,7 @@ fn build_invocation_subject(
         }
         "class" => {
             let class_id = gateway
-                .class_handle_by_name(input.class.as_deref().ok_or_else(|| {
+                .resolve_class(input.class.as_deref().ok_or_else(|| {
                     AppError::MissingOptions(vec!["class".to_string()])
                 })?)?
                 .id();
@@ -138,7 +138,7 @@ fn build_invocation_subject(
                 .class
                 .as_deref()
                 .ok_or_else(|| AppError::MissingOptions(vec!["class".to_string()]))?;
-            let object = gateway.object_handle_by_name(
+            let object = gateway.resolve_object(
                 class,
                 input
                     .object
@@ -220,6 +220,7 @@ impl HubuumGateway {
             .map(|s| parse_subject_type(s))
             .collect::<Result<Vec<_>, _>>()?;
 
+        let name = input.name.clone();
         let new_target = NewRemoteTarget {
             collection_id: self.collection_id(&input.collection)?.into(),
             name: input.name,
@@ -232,14 +233,18 @@ impl HubuumGateway {
             class_id: input
                 .class
                 .as_deref()
-                .map(|class| self.class_handle_by_name(class).map(|handle| handle.id()))
+                .map(|class| self.resolve_class(class).map(|handle| handle.id()))
                 .transpose()?,
             enabled: input.enabled,
             headers_template: input.headers_template,
             timeout_ms: input.timeout_ms,
         };
 
-        let target = self.client.remote_targets().create_raw(new_target)?;
+        let target = self
+            .client()
+            .remote_targets()
+            .create_raw(new_target)
+            .context(|| format!("creating remote target '{name}'"))?;
         Ok(RemoteTargetRecord::from(target))
     }
 
@@ -255,7 +260,7 @@ impl HubuumGateway {
             .collect::<Result<Vec<_>, _>>()?;
 
         let page = apply_query_paging(
-            self.client.remote_targets().query().filters(filters),
+            self.client().remote_targets().query().filters(filters),
             query,
             &validated_sorts,
         )
@@ -264,7 +269,7 @@ impl HubuumGateway {
     }
 
     pub fn remote_target(&self, name: &str) -> Result<RemoteTargetRecord, AppError> {
-        let target = self.client.remote_targets().get_by_name(name)?;
+        let target = self.client().remote_targets().get_by_name(name)?;
         Ok(RemoteTargetRecord::from(target.resource()))
     }
 
@@ -272,7 +277,7 @@ impl HubuumGateway {
         &self,
         input: UpdateRemoteTargetInput,
     ) -> Result<RemoteTargetRecord, AppError> {
-        let target = self.client.remote_targets().get_by_name(&input.name)?;
+        let target = self.client().remote_targets().get_by_name(&input.name)?;
 
         let method = input.method.as_ref().map(|m| parse_method(m)).transpose()?;
         let allowed_subject_types = input
@@ -304,14 +309,14 @@ impl HubuumGateway {
             class_id: input
                 .class
                 .as_deref()
-                .map(|class| self.class_handle_by_name(class).map(|handle| handle.id()))
+                .map(|class| self.resolve_class(class).map(|handle| handle.id()))
                 .transpose()?,
             enabled: input.enabled,
             timeout_ms: input.timeout_ms,
         };
 
         let updated = self
-            .client
+            .client()
             .remote_targets()
             .update(target.id())
             .params(update)
@@ -320,8 +325,11 @@ impl HubuumGateway {
     }
 
     pub fn delete_remote_target(&self, name: &str) -> Result<(), AppError> {
-        let target = self.client.remote_targets().get_by_name(name)?;
-        self.client.remote_targets().delete(target.id())?;
+        let target = self.client().remote_targets().get_by_name(name)?;
+        self.client()
+            .remote_targets()
+            .delete(target.id())
+            .context(|| format!("deleting remote target '{name}'"))?;
         Ok(())
     }
 
@@ -330,7 +338,7 @@ impl HubuumGateway {
         name: &str,
         input: InvokeRemoteTargetInput,
     ) -> Result<TaskRecord, AppError> {
-        let handle = self.client.remote_targets().get_by_name(name)?;
+        let handle = self.client().remote_targets().get_by_name(name)?;
         let subject = build_invocation_subject(self, &input)?;
         let mut req = RemoteTargetInvokeRequest::new(subject);
         if let Some(p) = input.parameters {
@@ -0,0 +1,375 @@
+//! Resolves the names commands are given to classes, collections, groups, users, and objects.
+//!
+//! Every lookup goes through the same steps: a `#<id>` reference is fetched by ID,
+//! an exact name is fetched by name, and anything else is matched under the
+//! `lookup.name_match` policy. Deletions use the `_exact` lookups, which skip that
+//! policy so a loose match never picks what gets deleted. Misses and ambiguous
+//! matches produce the same errors whatever the entity kind.
+
+use std::time::Instant;
+
+use hubuum_client::{
+    client::{sync::Handle as SyncHandle, sync::Resource, GetID},
+    ApiResource, Class, Collection, FilterOperator, Group, Object, User,
+};
+use serde::de::DeserializeOwned;
+
+use crate::config::get_config;
+use crate::errors::AppError;
+use crate::models::NameMatch;
+use crate::offline::cache_ttl;
+
+use super::{entity_cache::CacheableEntity, shared::is_missing_api_error, HubuumGateway};
+
+/// Candidates fetched per loose match; two are already enough to be ambiguous.
+const MATCH_CANDIDATES: usize = 10;
+
+/// The outcome of matching a name against a set of candidates.
+#[derive(Debug, PartialEq)]
+pub(crate) enum NameMatchResult<'a, T> {
+    One(&'a T),
+    Missing,
+    Ambiguous(Vec<&'a T>),
+}
+
+/// Picks the candidate `name` refers to under `policy`.
+///
+/// An exact match always wins. `ci` then accepts a single case-insensitive match,
+/// and `contains` additionally a single case-insensitive substring match.
+pub(crate) fn pick_by_name<'a, T>(
+    candidates: &'a [T],
+    name: &str,
+    policy: NameMatch,
+    name_of: impl Fn(&T) -> &str,
+) -> NameMatchResult<'a, T> {
+    if let Some(exact) = candidates
+        .iter()
+        .find(|candidate| name_of(candidate) == name)
+    {
+        return NameMatchResult::One(exact);
+    }
+
+    let rules: &[fn(&str, &str) -> bool] = match policy {
+        NameMatch::Exact => &[],
+        NameMatch::Ci => &[same_ignoring_case],
+        NameMatch::Contains => &[same_ignoring_case, contains_ignoring_case],
+    };
+    for rule in rules {
+        let matches = candidates
+            .iter()
+            .filter(|candidate| rule(name_of(candidate), name))
+            .collect::<Vec<_>>();
+        match matches.len() {
+            0 => continue,
+            1 => return NameMatchResult::One(matches[0]),
+            _ => return NameMatchResult::Ambiguous(matches),
+        }
+    }
+    NameMatchResult::Missing
+}
+
+fn same_ignoring_case(candidate: &str, name: &str) -> bool {
+    candidate.to_lowercase() == name.to_lowercase()
+}
+
+fn contains_ignoring_case(candidate: &str, name: &str) -> bool {
+    candidate.to_lowercase().contains(&name.to_lowercase())
+}
+
+/// The ID in a `#<id>` reference, which skips name matching altogether.
+fn id_reference(reference: &str) -> Option<i32> {
+    reference.strip_prefix('#')?.parse().ok()
+}
+
+fn described(kind: &str, name: &str) -> String {
+    format!("{kind} '{name}'")
+}
+
+impl HubuumGateway {
+    pub(super) fn resolve_class(&self, name: &str) -> Result<SyncHandle<Class>, AppError> {
+        self.resolve_class_under(name, get_config().lookup.name_match)
+    }
+
+    pub(super) fn resolve_class_exact(&self, name: &str) -> Result<SyncHandle<Class>, AppError> {
+        self.resolve_class_under(name, NameMatch::Exact)
+    }
+
+    fn resolve_class_under(
+        &self,
+        name: &str,
+        policy: NameMatch,
+    ) -> Result<SyncHandle<Class>, AppError> {
+        self.resolve_cached(self.client().classes(), name, "class", policy, |class| {
+            class.name.as_str()
+        })
+    }
+
+    pub(super) fn resolve_collection(
+        &self,
+        name: &str,
+    ) -> Result<SyncHandle<Collection>, AppError> {
+        self.resolve_collection_under(name, get_config().lookup.name_match)
+    }
+
+    pub(super) fn resolve_collection_exact(
+        &self,
+        name: &str,
+    ) -> Result<SyncHandle<Collection>, AppError> {
+        self.resolve_collection_under(name, NameMatch::Exact)
+    }
+
+    fn resolve_collection_under(
+        &self,
+        name: &str,
+        policy: NameMatch,
+    ) -> Result<SyncHandle<Collection>, AppError> {
+        self.resolve_cached(
+            self.client().collections(),
+            name,
+            "collection",
+            policy,
+            |collection| collection.name.as_str(),
+        )
+    }
+
+    pub(super) fn resolve_group(&self, name: &str) -> Result<SyncHandle<Group>, AppError> {
+        self.resolve_named(
+            self.client().groups(),
+            name,
+            &described("group", name),
+            |group| group.groupname.as_str(),
+        )
+    }
+
+    pub(super) fn resolve_user(&self, name: &str) -> Result<SyncHandle<User>, AppError> {
+        self.resolve_named(
+            self.client().users(),
+            name,
+            &described("user", name),
+            |user| user.name.as_str(),
+        )
+    }
+
+    pub(super) fn resolve_object(
+        &self,
+        class_name: &str,
+        object_name: &str,
+    ) -> Result<SyncHandle<Object>, AppError> {
+        let class = self.resolve_class(class_name)?;
+        self.resolve_object_in(&class, object_name)
+    }
+
+    pub(super) fn resolve_object_in(
+        &self,
+        class: &SyncHandle<Class>,
+        object_name: &str,
+    ) -> Result<SyncHandle<Object>, AppError> {
+        self.resolve_object_under(class, object_name, get_config().lookup.name_match)
+    }
+
+    /// Like `resolve_object`, matching both the class and the object name exactly.
+    pub(super) fn resolve_object_exact(
+        &self,
+        class_name: &str,
+        object_name: &str,
+    ) -> Result<(SyncHandle<Class>, SyncHandle<Object>), AppError> {
+        let class = self.resolve_class_exact(class_name)?;
+        let object = self.resolve_object_under(&class, object_name, NameMatch::Exact)?;
+        Ok((class, object))
+    }
+
+    fn resolve_object_under(
+        &self,
+        class: &SyncHandle<Class>,
+        object_name: &str,
+        policy: NameMatch,
+    ) -> Result<SyncHandle<Object>, AppError> {
+        self.resolve_named_under(
+            self.client().objects(class.id()),
+            object_name,
+            &format!(
+                "{} in {}",
+                described("object", object_name),
+                described("class", &class.resource().name)
+            ),
+            policy,
+            |object| object.name.as_str(),
+        )
+    }
+
+    /// Like `resolve_named`, reusing entities resolved earlier while the entity cache holds them.
+    fn resolve_cached<T>(
+        &self,
+        resource: Resource<T>,
+        reference: &str,
+        kind: &str,
+        policy: NameMatch,
+        name_of: fn(&T) -> &str,
+    ) -> Result<SyncHandle<T>, AppError>
+    where
+        T: ApiResource<GetOutput = T>
+            + CacheableEntity
+            + DeserializeOwned
+            + GetID
+            + Default
+            + 'static,
+        T::Id: Into<i32>,
+    {
+        let config = get_config();
+        let description = described(kind, reference);
+        if config.cache.disable {
+            return self.resolve_named_under(resource, reference, &description, policy, name_of);
+        }
+
+        let key = format!("{policy}:{reference}");
+        let ttl = cache_ttl(config.cache.time);
+        let cached = self
+            .entity_cache
+            .lock()
+            .map_err(|_| AppError::LockError)?
+            .lookup_named::<T>(&key, ttl, Instant::now());
+        if let Some(entity) = cached {
+            return Ok(SyncHandle::new(self.client().as_ref().clone(), entity));
+        }
+
+        let handle =
+            self.resolve_named_under(resource, reference, &description, policy, name_of)?;
+        self.entity_cache
+            .lock()
+            .map_err(|_| AppError::LockError)?
+            .store_named(&key, handle.id().into(), handle.resource(), Instant::now());
+        Ok(handle)
+    }
+
+    fn resolve_named<T>(
+        &self,
+        resource: Resource<T>,
+        reference: &str,
+        description: &str,
+        name_of: fn(&T) -> &str,
+    ) -> Result<SyncHandle<T>, AppError>
+    where
+        T: ApiResource<GetOutput = T> + DeserializeOwned + GetID + Default + Clone + 'static,
+    {
+        let policy = get_config().lookup.name_match;
+        self.resolve_named_under(resource, reference, description, policy, name_of)
+    }
+
+    fn resolve_named_under<T>(
+        &self,
+        resource: Resource<T>,
+        reference: &str,
+        description: &str,
+        policy: NameMatch,
+        name_of: fn(&T) -> &str,
+    ) -> Result<SyncHandle<T>, AppError>
+    where
+        T: ApiResource<GetOutput = T> + DeserializeOwned + GetID + Default + Clone + 'static,
+    {
+        if let Some(id) = id_reference(reference) {
+            let found = resource
+                .query()
+                .filter("id", FilterOperator::Equals { is_negated: false }, id)
+                .limit(1)
+                .list()?;
+            if let Some(entity) = found.into_iter().next() {
+                return Ok(SyncHandle::new(self.client().as_ref().clone(), entity));
+            }
+        }
+
+        match resource.get_by_name(reference) {
+            Ok(handle) => return Ok(handle),
+            Err(error) if is_missing_api_error(&error) => {}
+            Err(error) => return Err(error.into()),
+        }
+
+        let stages: &[NameMatch] = match policy {
+            NameMatch::Exact => &[],
+            NameMatch::Ci => &[NameMatch::Ci],
+            NameMatch::Contains => &[NameMatch::Ci, NameMatch::Contains],
+        };
+        for &stage in stages {
+            let operator = match stage {
+                NameMatch::Contains => FilterOperator::IContains { is_negated: false },
+                _ => FilterOperator::IEquals { is_negated: false },
+            };
+            let candidates = resource
+                .query()
+                .filter(T::NAME_FIELD, operator, reference)
+                .limit(MATCH_CANDIDATES)
+                .list()?;
+            match pick_by_name(&candidates, reference, stage, name_of) {
+                NameMatchResult::One(entity) => {
+                    return Ok(SyncHandle::new(
+                        self.client().as_ref().clone(),
+                        entity.clone(),
+                    ));
+                }
+                NameMatchResult::Missing => {}
+                NameMatchResult::Ambiguous(matches) => {
+                    let mut names = matches
+                        .into_iter()
+                        .map(|entity| name_of(entity).to_string())
+                        .collect::<Vec<_>>();
+                    if candidates.len() == MATCH_CANDIDATES {
+                        names.push("…".to_string());
+                    }
+                    return Err(AppError::MultipleEntitiesFound(format!(
+                        "{description} matches {}",
+                        names.join(", ")
+                    )));
+                }
+            }
+        }
+
+        Err(AppError::EntityNotFound(description.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(candidates: &[&'static str], name: &str, policy: NameMatch) -> Vec<&'static str> {
+        match pick_by_name(candidates, name, policy, |candidate| candidate) {
+            NameMatchResult::One(found) => vec![*found],
+            NameMatchResult::Missing => Vec::new(),
+            NameMatchResult::Ambiguous(matches) => matches.into_iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn exact_names_win_under_every_policy() {
+        let candidates = ["web", "Web", "webservers"];
+        for policy in [NameMatch::Exact, NameMatch::Ci, NameMatch::Contains] {
+            assert_eq!(names(&candidates, "web", policy), vec!["web"]);
+        }
+        assert!(names(&candidates, "WEB", NameMatch::Exact).is_empty());
+    }
+
+    #[test]
+    fn loose_policies_need_a_single_match() {
+        let candidates = ["Hosts", "hosts-archive", "routers"];
+        assert_eq!(names(&candidates, "hosts", NameMatch::Ci), vec!["Hosts"]);
+        assert_eq!(
+            names(&candidates, "hosts", NameMatch::Contains),
+            vec!["Hosts"]
+        );
+        assert_eq!(
+            names(&candidates, "ARCH", NameMatch::Contains),
+            vec!["hosts-archive"]
+        );
+        assert!(names(&candidates, "arch", NameMatch::Ci).is_empty());
+        assert_eq!(
+            names(&candidates, "s", NameMatch::Contains),
+            vec!["Hosts", "hosts-archive", "routers"]
+        );
+    }
+
+    #[test]
+    fn id_references_need_a_hash_and_digits() {
+        assert_eq!(id_reference("#42"), Some(42));
+        assert_eq!(id_reference("42"), None);
+        assert_eq!(id_reference("#web"), None);
+    }
+}
@@ -0,0 +1,180 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+struct CachedResponse {
+    fetched_at: Instant,
+    last_used: u64,
+    size: usize,
+    value: Box<dyn Any + Send>,
+}
+
+/// In-memory cache of list responses, keyed by response type and request.
+///
+/// Entries expire after the TTL given to [`ResponseCache::lookup`]. Each entry is
+/// weighed by its JSON size, and the least recently used entries are evicted once
+/// the total passes the budget given to [`ResponseCache::store`].
+#[derive(Default)]
+pub(super) struct ResponseCache {
+    entries: HashMap<(TypeId, String), CachedResponse>,
+    size: usize,
+    uses: u64,
+}
+
+impl ResponseCache {
+    /// The response stored for `key`, if it is fresher than `ttl`.
+    pub(super) fn lookup<T: Any + Clone>(
+        &mut self,
+        key: &str,
+        ttl: Duration,
+        now: Instant,
+    ) -> Option<T> {
+        let key = (TypeId::of::<T>(), key.to_string());
+        let fresh = self
+            .entries
+            .get(&key)
+            .is_some_and(|entry| now.duration_since(entry.fetched_at) < ttl);
+        if !fresh {
+            self.remove(&key);
+            return None;
+        }
+
+        self.uses += 1;
+        let entry = self.entries.get_mut(&key)?;
+        entry.last_used = self.uses;
+        entry.value.downcast_ref::<T>().cloned()
+    }
+
+    /// The response stored for `key` however old it is, with its age, for offline mode.
+    pub(super) fn lookup_stale<T: Any + Clone>(
+        &mut self,
+        key: &str,
+        now: Instant,
+    ) -> Option<(T, Duration)> {
+        let key = (TypeId::of::<T>(), key.to_string());
+        self.uses += 1;
+        let entry = self.entries.get_mut(&key)?;
+        entry.last_used = self.uses;
+        let age = now.duration_since(entry.fetched_at);
+        entry
+            .value
+            .downcast_ref::<T>()
+            .cloned()
+            .map(|value| (value, age))
+    }
+
+    /// Stores `value` for `key`, evicting the least recently used responses until
+    /// everything fits in `budget` bytes. Responses larger than the budget are not kept.
+    pub(super) fn store<T: Any + Clone + Send + Serialize>(
+        &mut self,
+        key: &str,
+        value: &T,
+        budget: usize,
+        now: Instant,
+    ) {
+        let key = (TypeId::of::<T>(), key.to_string());
+        self.remove(&key);
+        let size = serde_json::to_vec(value).map_or(usize::MAX, |json| json.len());
+        if size > budget {
+            return;
+        }
+        while self.size + size > budget {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.remove(&oldest);
+        }
+
+        self.uses += 1;
+        self.size += size;
+        self.entries.insert(
+            key,
+            CachedResponse {
+                fetched_at: now,
+                last_used: self.uses,
+                size,
+                value: Box::new(value.clone()),
+            },
+        );
+    }
+
+    /// Drops every response, returning how many there were.
+    pub(super) fn clear(&mut self) -> usize {
+        let cleared = self.entries.len();
+        self.entries.clear();
+        self.size = 0;
+        cleared
+    }
+
+    fn remove(&mut self, key: &(TypeId, String)) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.size -= entry.size;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize)]
+    struct Page(&'static str);
+
+    #[derive(Debug, Clone, PartialEq, Serialize)]
+    struct OtherPage(&'static str);
+
+    #[test]
+    fn fresh_responses_are_reused_until_they_expire() {
+        let mut cache = ResponseCache::default();
+        let start = Instant::now();
+        let ttl = Duration::from_secs(60);
+        cache.store("classes", &Page("one"), 1024, start);
+
+        assert_eq!(cache.lookup("classes", ttl, start), Some(Page("one")));
+        assert_eq!(cache.lookup::<OtherPage>("classes", ttl, start), None);
+        assert_eq!(cache.lookup::<Page>("groups", ttl, start), None);
+        assert_eq!(cache.lookup::<Page>("classes", ttl, start + ttl), None);
+        assert_eq!(cache.clear(), 0);
+    }
+
+    #[test]
+    fn expired_responses_are_offered_stale_with_their_age() {
+        let mut cache = ResponseCache::default();
+        let start = Instant::now();
+        cache.store("classes", &Page("one"), 1024, start);
+        let later = start + Duration::from_secs(3600);
+
+        assert_eq!(
+            cache.lookup_stale("classes", later),
+            Some((Page("one"), Duration::from_secs(3600)))
+        );
+        assert_eq!(cache.lookup_stale::<Page>("groups", later), None);
+    }
+
+    #[test]
+    fn least_recently_used_responses_are_evicted_to_fit_the_budget() {
+        let mut cache = ResponseCache::default();
+        let now = Instant::now();
+        let ttl = Duration::from_secs(60);
+        // Each page serializes to seven bytes, such as `"pageA"`.
+        cache.store("a", &Page("pageA"), 14, now);
+        cache.store("b", &Page("pageB"), 14, now);
+        assert_eq!(cache.lookup("a", ttl, now), Some(Page("pageA")));
+
+        cache.store("c", &Page("pageC"), 14, now);
+        assert_eq!(cache.lookup("a", ttl, now), Some(Page("pageA")));
+        assert_eq!(cache.lookup::<Page>("b", ttl, now), None);
+        assert_eq!(cache.lookup("c", ttl, now), Some(Page("pageC")));
+
+        cache.store("huge", &Page("far too large"), 14, now);
+        assert_eq!(cache.lookup::<Page>("huge", ttl, now), None);
+        assert_eq!(cache.clear(), 2);
+    }
+}
@@ -14,7 +14,7 @@ use crate::domain::{
 };
 use crate::errors::AppError;
 
-use super::{shared::find_entities_by_ids, HubuumGateway};
+use super::HubuumGateway;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumString, Display)]
 #[strum(serialize_all = "lowercase")]
@@ -78,7 +78,7 @@ impl HubuumGateway {
     }
 
     fn build_search_request(&self, input: &SearchInput) -> UnifiedSearchRequest {
-        let mut request = self.client.search(input.query.clone());
+        let mut request = self.client().search(input.query.clone());
 
         if !input.kinds.is_empty() {
             request = request.kinds(input.kinds.iter().copied().map(Into::into));
@@ -163,8 +163,8 @@ impl HubuumGateway {
             .filter(|object| !class_map.contains_key(&object.hubuum_class_id.into()))
             .count();
         if missing_class_ids > 0 {
-            class_map.extend(find_entities_by_ids(
-                &self.client.classes(),
+            class_map.extend(self.find_entities_by_ids(
+                |client| client.classes(),
                 objects.iter(),
                 |object| object.hubuum_class_id,
             )?);
@@ -175,8 +175,8 @@ impl HubuumGateway {
             .filter(|object| !collection_map.contains_key(&object.collection_id.into()))
             .count();
         if missing_collection_ids > 0 {
-            collection_map.extend(find_entities_by_ids(
-                &self.client.collections(),
+            collection_map.extend(self.find_entities_by_ids(
+                |client| client.collections(),
                 objects.iter(),
                 |object| object.collection_id,
             )?);
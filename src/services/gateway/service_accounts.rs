@@ -3,7 +3,7 @@ use hubuum_client::{HubuumDateTime, NewTokenRequest, Permissions};
 use std::str::FromStr;
 
 use crate::domain::{PrincipalTokenRecord, ServiceAccountRecord};
-use crate::errors::AppError;
+use crate::errors::{ApiContext, AppError};
 use crate::list_query::{
     apply_query_paging, validate_filter_clauses, validate_sort_clauses, FilterFieldSpec,
     FilterOperatorProfile, FilterValueProfile, ListQuery, PagedResult, SortFieldSpec,
@@ -36,7 +36,7 @@ impl HubuumGateway {
         input: CreateServiceAccountInput,
     ) -> Result<ServiceAccountRecord, AppError> {
         let mut create = self
-            .client
+            .client()
             .service_accounts()
             .create_checked()
             .name(input.name)
@@ -60,7 +60,7 @@ impl HubuumGateway {
             .map(|clause| self.resolve_validated_filter(clause))
             .collect::<Result<Vec<_>, _>>()?;
 
-        let mut query_op = self.client.service_accounts().query();
+        let mut query_op = self.client().service_accounts().query();
         for filter in filters {
             query_op = query_op.filter(&filter.key, filter.operator, &filter.value);
         }
@@ -70,13 +70,13 @@ impl HubuumGateway {
     }
 
     pub fn service_account(&self, name: &str) -> Result<ServiceAccountRecord, AppError> {
-        let sa = self.client.service_accounts().get_by_name(name)?;
+        let sa = self.client().service_accounts().get_by_name(name)?;
         Ok(ServiceAccountRecord::from(sa.resource().clone()))
     }
 
     pub fn service_account_id_by_name(&self, name: &str) -> Result<i32, AppError> {
         Ok(self
-            .client
+            .client()
             .service_accounts()
             .get_by_name(name)?
             .id()
@@ -84,13 +84,16 @@ impl HubuumGateway {
     }
 
     pub fn delete_service_account(&self, name: &str) -> Result<(), AppError> {
-        let sa = self.client.service_accounts().get_by_name(name)?;
-        self.client.service_accounts().delete(sa.id())?;
+        let sa = self.client().service_accounts().get_by_name(name)?;
+        self.client()
+            .service_accounts()
+            .delete(sa.id())
+            .context(|| format!("deleting service account '{name}'"))?;
         Ok(())
     }
 
     pub fn disable_service_account(&self, name: &str) -> Result<ServiceAccountRecord, AppError> {
-        let handle = self.client.service_accounts().get_by_name(name)?;
+        let handle = self.client().service_accounts().get_by_name(name)?;
         let disabled = handle.disable()?;
         Ok(ServiceAccountRecord::from(disabled))
     }
@@ -99,7 +102,7 @@ impl HubuumGateway {
         &self,
         name: &str,
     ) -> Result<Vec<PrincipalTokenRecord>, AppError> {
-        let handle = self.client.service_accounts().get_by_name(name)?;
+        let handle = self.client().service_accounts().get_by_name(name)?;
         let tokens = handle.tokens()?;
         Ok(tokens.into_iter().map(PrincipalTokenRecord::from).collect())
     }
@@ -109,7 +112,7 @@ impl HubuumGateway {
         name: &str,
         input: NewTokenInput,
     ) -> Result<String, AppError> {
-        let handle = self.client.service_accounts().get_by_name(name)?;
+        let handle = self.client().service_accounts().get_by_name(name)?;
         let mut req = NewTokenRequest::new();
 
         if let Some(n) = input.name {
@@ -145,7 +148,7 @@ impl HubuumGateway {
     }
 
     pub fn service_account_token_revoke(&self, name: &str, token_id: i32) -> Result<(), AppError> {
-        let handle = self.client.service_accounts().get_by_name(name)?;
+        let handle = self.client().service_accounts().get_by_name(name)?;
         handle.token_revoke(token_id)?;
         Ok(())
     }
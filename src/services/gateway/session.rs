@@ -0,0 +1,114 @@
+use std::sync::{Arc, Mutex, RwLock};
+
+use hubuum_client::{
+    blocking::Client as BlockingClient, ApiError as ClientApiError, Authenticated,
+};
+use log::debug;
+
+use crate::errors::AppError;
+
+/// Logs in again from scratch, e.g. with the stored token or the configured password.
+pub(crate) type Relogin =
+    Box<dyn Fn() -> Result<BlockingClient<Authenticated>, AppError> + Send + Sync>;
+
+/// The authenticated client shared by every gateway clone.
+///
+/// The client is replaced when its token is rejected and the session logs in again.
+/// Each replacement bumps the generation, so callers that failed with an older token
+/// can tell that someone else already renewed the login.
+pub(crate) struct Session {
+    current: RwLock<CurrentClient>,
+    relogin: Mutex<Option<Relogin>>,
+}
+
+#[derive(Clone)]
+struct CurrentClient {
+    generation: u64,
+    client: Arc<BlockingClient<Authenticated>>,
+}
+
+impl Session {
+    pub(crate) fn new(client: Arc<BlockingClient<Authenticated>>) -> Self {
+        Self {
+            current: RwLock::new(CurrentClient {
+                generation: 0,
+                client,
+            }),
+            relogin: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn client(&self) -> Arc<BlockingClient<Authenticated>> {
+        self.current().client
+    }
+
+    pub(crate) fn generation(&self) -> u64 {
+        self.current().generation
+    }
+
+    pub(crate) fn set_relogin(&self, relogin: Relogin) {
+        *self
+            .relogin
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(relogin);
+    }
+
+    /// Logs in again unless the client from `generation` was already replaced.
+    ///
+    /// Renewals are serialized: when several workers are rejected at once, the first one
+    /// logs in and the others wait for it and then reuse the new client.
+    pub(crate) fn renew(&self, generation: u64) -> Result<(), AppError> {
+        let relogin = self
+            .relogin
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if self.generation() != generation {
+            debug!("Session was already renewed by another request");
+            return Ok(());
+        }
+        let relogin = relogin.as_ref().ok_or_else(|| {
+            AppError::CommandExecutionError(
+                "The session token was rejected and this session cannot log in again".to_string(),
+            )
+        })?;
+        let client = relogin()?;
+        let mut current = self
+            .current
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        current.generation += 1;
+        current.client = Arc::new(client);
+        Ok(())
+    }
+
+    /// Makes `client` the session's client and `relogin` how it logs in again, e.g. after
+    /// switching to another user.
+    pub(crate) fn replace(&self, client: BlockingClient<Authenticated>, relogin: Relogin) {
+        let mut current_relogin = self
+            .relogin
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut current = self
+            .current
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        current.generation += 1;
+        current.client = Arc::new(client);
+        *current_relogin = Some(relogin);
+    }
+
+    fn current(&self) -> CurrentClient {
+        self.current
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+/// Whether `error` means the server no longer accepts the session token.
+pub(crate) fn is_unauthorized(error: &AppError) -> bool {
+    match error.api_error() {
+        Some(ClientApiError::HttpWithBody { status, .. }) => status.as_u16() == 401,
+        _ => false,
+    }
+}
@@ -17,7 +17,7 @@ struct StoredUserPreferences {
 
 impl HubuumGateway {
     pub fn load_user_preferences(&self) -> Result<UserPreferences, AppError> {
-        let settings = self.client.settings().get()?;
+        let settings = self.client().settings().get()?;
         let stored = settings.get(SETTINGS_NAMESPACE).ok_or_else(|| {
             AppError::EntityNotFound(format!(
                 "no settings are stored under the '{SETTINGS_NAMESPACE}' namespace"
@@ -30,7 +30,7 @@ impl HubuumGateway {
         &self,
         preferences: &UserPreferences,
     ) -> Result<UserPreferences, AppError> {
-        let mut settings = self.client.settings().get()?;
+        let mut settings = self.client().settings().get()?;
         settings.insert(
             SETTINGS_NAMESPACE,
             to_value(StoredUserPreferences {
@@ -38,7 +38,7 @@ impl HubuumGateway {
                 preferences: preferences.clone(),
             })?,
         );
-        let updated = self.client.settings().replace(&settings)?;
+        let updated = self.client().settings().replace(&settings)?;
         let stored = updated.get(SETTINGS_NAMESPACE).ok_or_else(|| {
             AppError::GeneralConfigError(
                 "server response omitted the stored Hubuum CLI settings".to_string(),
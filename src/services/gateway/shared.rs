@@ -1,17 +1,27 @@
+use std::any::Any;
 use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 use hubuum_client::{
-    client::{sync::Handle as SyncHandle, sync::Resource, GetID},
-    ApiError as ClientApiError, ApiResource, Class, ClassRelation, Collection, FilterOperator,
-    Object, ObjectRelation, QueryFilter, ResourceId,
+    blocking::Client as BlockingClient,
+    client::{sync::Resource, GetID},
+    ApiError as ClientApiError, ApiResource, Authenticated, Class, ClassRelation, Collection,
+    FilterOperator, Object, ObjectRelation, QueryFilter, ResourceId,
 };
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::to_string;
 
+use crate::config::get_config;
 use crate::errors::AppError;
 use crate::list_query::{
-    validated_clause_to_query_filter, FilterValueResolver, ValidatedFilterClause,
+    validated_clause_to_query_filter, FilterValueResolver, ListQuery, PagedResult,
+    ValidatedFilterClause,
 };
+use crate::offline::{cache_ttl, cached_listing_warning, is_offline, save_listing, saved_listing};
+use crate::output::add_warning;
 
-use super::HubuumGateway;
+use super::{entity_cache::CacheableEntity, HubuumGateway};
 
 const MAX_EQUALS_FILTER_VALUES: usize = 50;
 
@@ -22,16 +32,8 @@ impl HubuumGateway {
         class_to: &str,
     ) -> Result<(Class, Class), AppError> {
         Ok((
-            self.client
-                .classes()
-                .get_by_name(class_from)?
-                .resource()
-                .clone(),
-            self.client
-                .classes()
-                .get_by_name(class_to)?
-                .resource()
-                .clone(),
+            self.resolve_class(class_from)?.resource().clone(),
+            self.resolve_class(class_to)?.resource().clone(),
         ))
     }
 
@@ -53,15 +55,15 @@ impl HubuumGateway {
         I: IntoIterator<Item = Id>,
         Id: Into<i32>,
     {
-        fetch_entities_for_ids(&self.client.classes(), unique_ids(class_ids))
+        self.cached_entities_for_ids(|client| client.classes(), class_ids)
     }
 
     pub(super) fn class_map_from_relation_ids(
         &self,
         relations: &[ClassRelation],
     ) -> Result<HashMap<i32, Class>, AppError> {
-        fetch_entities_for_ids(
-            &self.client.classes(),
+        self.cached_entities_for_ids(
+            |client| client.classes(),
             relations
                 .iter()
                 .flat_map(|relation| [relation.from_hubuum_class_id, relation.to_hubuum_class_id]),
@@ -79,14 +81,12 @@ impl HubuumGateway {
                 [relation.from_hubuum_object_id, relation.to_hubuum_object_id]
             }));
         let mut objects = HashMap::new();
-        objects.extend(fetch_entities_for_ids(
-            &self.client.objects(from_class_id),
+        objects.extend(self.fetch_entities_for_ids(
+            |client| client.objects(from_class_id),
             object_ids.iter().copied(),
         )?);
-        objects.extend(fetch_entities_for_ids(
-            &self.client.objects(to_class_id),
-            object_ids,
-        )?);
+        objects
+            .extend(self.fetch_entities_for_ids(|client| client.objects(to_class_id), object_ids)?);
 
         Ok(objects)
     }
@@ -97,7 +97,7 @@ impl HubuumGateway {
         class_to_id: i32,
     ) -> Result<ClassRelation, AppError> {
         Ok(self
-            .client
+            .client()
             .class_relation()
             .query()
             .filter(
@@ -127,60 +127,134 @@ impl HubuumGateway {
         }
     }
 
-    pub(super) fn class_handle_by_name(
-        &self,
-        class_name: &str,
-    ) -> Result<SyncHandle<Class>, AppError> {
-        Ok(self.client.classes().get_by_name(class_name)?)
+    pub(super) fn collection_id(&self, name: &str) -> Result<i32, AppError> {
+        Ok(self.resolve_collection(name)?.id().into())
     }
 
-    pub(super) fn object_handle_by_name(
+    pub(super) fn collection_map_from_ids<I, Id>(
         &self,
-        class_name: &str,
-        object_name: &str,
-    ) -> Result<SyncHandle<Object>, AppError> {
-        let class = self.class_handle_by_name(class_name)?;
-        match class.object_by_name(object_name) {
-            Ok(object) => Ok(object),
-            Err(error) if is_missing_api_error(&error) => {
-                let matches = self
-                    .client
-                    .objects(class.id())
-                    .query()
-                    .filter(
-                        "name",
-                        FilterOperator::StartsWith { is_negated: false },
-                        object_name,
-                    )
-                    .limit(2)
-                    .list()?;
-                match matches.as_slice() {
-                    [object] => Ok(SyncHandle::new(class.client().clone(), object.clone())),
-                    [] => Err(AppError::EntityNotFound(format!(
-                        "object '{object_name}' in class '{class_name}'"
-                    ))),
-                    _ => Err(AppError::MultipleEntitiesFound(format!(
-                        "objects in class '{class_name}' starting with '{object_name}'"
-                    ))),
-                }
-            }
-            Err(error) => Err(error.into()),
-        }
+        collection_ids: I,
+    ) -> Result<HashMap<i32, Collection>, AppError>
+    where
+        I: IntoIterator<Item = Id>,
+        Id: Into<i32>,
+    {
+        self.cached_entities_for_ids(|client| client.collections(), collection_ids)
     }
 
-    pub(super) fn collection_id(&self, name: &str) -> Result<i32, AppError> {
-        Ok(self.client.collections().get_by_name(name)?.id().into())
+    /// Fetches the entities referenced by `objects`, keyed by ID.
+    ///
+    /// Entities still fresh in the entity cache (`cache.time` seconds) are reused, and
+    /// only the missing IDs are fetched, in parallel chunks. `cache.disable` turns the
+    /// cache off.
+    pub(super) fn find_entities_by_ids<T, R, I, F, Id>(
+        &self,
+        resource: R,
+        objects: I,
+        extract_id: F,
+    ) -> Result<HashMap<i32, T::GetOutput>, AppError>
+    where
+        T: ApiResource + Sync,
+        R: Fn(&BlockingClient<Authenticated>) -> Resource<T> + Sync,
+        I: IntoIterator,
+        I::Item: Copy,
+        F: Fn(I::Item) -> Id,
+        Id: Into<i32>,
+        T::GetOutput: CacheableEntity + GetID,
+    {
+        self.cached_entities_for_ids(resource, objects.into_iter().map(extract_id))
     }
 
-    pub(super) fn collection_map_from_ids<I, Id>(
+    fn cached_entities_for_ids<T, R, I, Id>(
         &self,
-        collection_ids: I,
-    ) -> Result<HashMap<i32, Collection>, AppError>
+        resource: R,
+        ids: I,
+    ) -> Result<HashMap<i32, T::GetOutput>, AppError>
     where
+        T: ApiResource + Sync,
+        R: Fn(&BlockingClient<Authenticated>) -> Resource<T> + Sync,
         I: IntoIterator<Item = Id>,
         Id: Into<i32>,
+        T::GetOutput: CacheableEntity + GetID,
     {
-        fetch_entities_for_ids(&self.client.collections(), unique_ids(collection_ids))
+        let cache_config = get_config().cache.clone();
+        if cache_config.disable {
+            return self.fetch_entities_for_ids(resource, ids);
+        }
+
+        let ttl = cache_ttl(cache_config.time);
+        let ids = unique_ids(ids);
+        let (mut entities, missing) = self
+            .entity_cache
+            .lock()
+            .map_err(|_| AppError::LockError)?
+            .lookup::<T::GetOutput>(&ids, ttl, Instant::now());
+        if missing.is_empty() {
+            return Ok(entities);
+        }
+
+        let fetched = self.fetch_entities_for_ids(resource, missing)?;
+        self.entity_cache
+            .lock()
+            .map_err(|_| AppError::LockError)?
+            .store(&fetched, Instant::now());
+        entities.extend(fetched);
+        Ok(entities)
+    }
+
+    /// Answers a list request from the response cache while the same `listing` and
+    /// `query` were fetched less than `cache.time` seconds ago, and runs `fetch`
+    /// otherwise. Cached pages share a budget of `cache.size` bytes, and
+    /// `cache.disable` turns the cache off. Fetched pages are also saved for offline
+    /// mode, which uses a cached or saved page of any age, with a warning.
+    pub(super) fn cached_list<T, F>(
+        &self,
+        listing: &str,
+        query: &ListQuery,
+        fetch: F,
+    ) -> Result<PagedResult<T>, AppError>
+    where
+        T: Any + Clone + Send + Serialize + DeserializeOwned,
+        F: FnOnce() -> Result<PagedResult<T>, AppError>,
+    {
+        let cache_config = get_config().cache.clone();
+        if cache_config.disable {
+            return fetch();
+        }
+
+        let key = format!("{listing} {}", to_string(query)?);
+        if is_offline() {
+            let cached = self
+                .response_cache
+                .lock()
+                .map_err(|_| AppError::LockError)?
+                .lookup_stale(&key, Instant::now())
+                .or_else(|| saved_listing(&key));
+            if let Some((cached, age)) = cached {
+                add_warning(cached_listing_warning(age))?;
+                return Ok(cached);
+            }
+            return fetch();
+        }
+
+        let ttl = Duration::from_secs(cache_config.time);
+        let cached = self
+            .response_cache
+            .lock()
+            .map_err(|_| AppError::LockError)?
+            .lookup(&key, ttl, Instant::now());
+        if let Some(cached) = cached {
+            return Ok(cached);
+        }
+
+        let fetched = fetch()?;
+        let budget = usize::try_from(cache_config.size).unwrap_or_default();
+        self.response_cache
+            .lock()
+            .map_err(|_| AppError::LockError)?
+            .store(&key, &fetched, budget, Instant::now());
+        save_listing(&key, &fetched, budget);
+        Ok(fetched)
     }
 
     pub(super) fn resolve_validated_filter(
@@ -198,39 +272,63 @@ impl HubuumGateway {
         resolved.value = resolved_value;
         Ok(validated_clause_to_query_filter(&resolved))
     }
+
+    fn fetch_entities_for_ids<T, R, I, Id>(
+        &self,
+        resource: R,
+        ids: I,
+    ) -> Result<HashMap<i32, T::GetOutput>, AppError>
+    where
+        T: ApiResource + Sync,
+        R: Fn(&BlockingClient<Authenticated>) -> Resource<T> + Sync,
+        I: IntoIterator<Item = Id>,
+        Id: Into<i32>,
+        T::GetOutput: GetID + Send,
+    {
+        let ids = unique_ids(ids);
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let chunks: Vec<&[i32]> = ids.chunks(MAX_EQUALS_FILTER_VALUES).collect();
+        let runner = self.bulk_runner();
+        let mut entities = HashMap::new();
+        for results in runner.run(&chunks, |chunk| {
+            let joined = chunk
+                .iter()
+                .map(i32::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            Ok(resource(&self.client())
+                .query()
+                .filter("id", FilterOperator::Equals { is_negated: false }, joined)
+                .list()?)
+        }) {
+            entities.extend(
+                results?
+                    .into_iter()
+                    .map(|entity| (entity.id().get(), entity)),
+            );
+        }
+
+        Ok(entities)
+    }
 }
 
-fn is_missing_relation_error(error: &AppError) -> bool {
+pub(super) fn is_missing_relation_error(error: &AppError) -> bool {
     matches!(
-        error,
-        AppError::ApiError(ClientApiError::HttpWithBody { status, .. })
-            if status.as_u16() == 404
-    ) || matches!(error, AppError::ApiError(ClientApiError::EmptyResult(_)))
+        error.api_error(),
+        Some(ClientApiError::HttpWithBody { status, .. }) if status.as_u16() == 404
+    ) || matches!(error.api_error(), Some(ClientApiError::EmptyResult(_)))
 }
 
-fn is_missing_api_error(error: &ClientApiError) -> bool {
+pub(super) fn is_missing_api_error(error: &ClientApiError) -> bool {
     matches!(
         error,
         ClientApiError::HttpWithBody { status, .. } if status.as_u16() == 404
     ) || matches!(error, ClientApiError::EmptyResult(_))
 }
 
-pub(super) fn find_entities_by_ids<T, I, F, Id>(
-    resource: &Resource<T>,
-    objects: I,
-    extract_id: F,
-) -> Result<HashMap<i32, T::GetOutput>, AppError>
-where
-    T: ApiResource,
-    I: IntoIterator,
-    I::Item: Copy,
-    F: Fn(I::Item) -> Id,
-    Id: Into<i32>,
-    T::GetOutput: GetID,
-{
-    fetch_entities_for_ids(resource, unique_ids(objects.into_iter().map(extract_id)))
-}
-
 fn unique_ids<I, Id>(ids: I) -> Vec<i32>
 where
     I: IntoIterator<Item = Id>,
@@ -242,39 +340,3 @@ where
         .into_iter()
         .collect()
 }
-
-fn fetch_entities_for_ids<T, I, Id>(
-    resource: &Resource<T>,
-    ids: I,
-) -> Result<HashMap<i32, T::GetOutput>, AppError>
-where
-    T: ApiResource,
-    I: IntoIterator<Item = Id>,
-    Id: Into<i32>,
-    T::GetOutput: GetID,
-{
-    let ids = unique_ids(ids);
-    if ids.is_empty() {
-        return Ok(HashMap::new());
-    }
-
-    let mut entities = HashMap::new();
-    for chunk in ids.chunks(MAX_EQUALS_FILTER_VALUES) {
-        let joined = chunk
-            .iter()
-            .map(i32::to_string)
-            .collect::<Vec<_>>()
-            .join(",");
-        let results = resource
-            .query()
-            .filter("id", FilterOperator::Equals { is_negated: false }, joined)
-            .list()?;
-        entities.extend(
-            results
-                .into_iter()
-                .map(|entity| (entity.id().get(), entity)),
-        );
-    }
-
-    Ok(entities)
-}
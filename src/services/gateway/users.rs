@@ -2,14 +2,14 @@ use chrono::{DateTime, NaiveDateTime, Utc};
 use hubuum_client::{FilterOperator, HubuumDateTime, NewTokenRequest, Permissions, UserPatch};
 use std::str::FromStr;
 
-use crate::domain::{CreatedUser, PrincipalTokenRecord, UserRecord};
-use crate::errors::AppError;
+use crate::domain::{CreatedUser, GroupRecord, PrincipalTokenRecord, UserRecord};
+use crate::errors::{ApiContext, AppError};
 use crate::list_query::{
     apply_query_paging, validate_filter_clauses, validate_sort_clauses, FilterFieldSpec,
     FilterOperatorProfile, FilterValueProfile, ListQuery, PagedResult, SortFieldSpec,
 };
 
-use super::HubuumGateway;
+use super::{ApiCall, HubuumGateway};
 
 #[derive(Debug, Clone, Default)]
 pub struct UserFilter {
@@ -57,7 +57,7 @@ impl HubuumGateway {
     pub fn create_user(&self, input: CreateUserInput) -> Result<CreatedUser, AppError> {
         // Create user with name/email/password
         let mut create = self
-            .client
+            .client()
             .users()
             .create_checked()
             .name(input.username.clone())
@@ -65,7 +65,9 @@ impl HubuumGateway {
         if let Some(email) = input.email {
             create = create.email(email);
         }
-        let user = create.send()?;
+        let user = create
+            .send()
+            .context(|| format!("creating user '{}'", input.username))?;
 
         Ok(CreatedUser {
             user: UserRecord::from(user),
@@ -74,7 +76,7 @@ impl HubuumGateway {
     }
 
     pub fn find_user(&self, filter: UserFilter) -> Result<UserRecord, AppError> {
-        let mut search = self.client.users().query();
+        let mut search = self.client().users().query();
         if let Some(username) = filter.username {
             search = search.filter(
                 "name",
@@ -111,7 +113,7 @@ impl HubuumGateway {
             .map(|clause| self.resolve_validated_filter(clause))
             .collect::<Result<Vec<_>, _>>()?;
 
-        let mut query_op = self.client.users().query();
+        let mut query_op = self.client().users().query();
         for filter in filters {
             query_op = query_op.filter(&filter.key, filter.operator, &filter.value);
         }
@@ -121,8 +123,11 @@ impl HubuumGateway {
     }
 
     pub fn delete_user(&self, username: &str) -> Result<(), AppError> {
-        let user = self.client.users().get_by_name(username)?;
-        self.client.users().delete(user.id())?;
+        let user = self.resolve_user(username)?;
+        self.client()
+            .users()
+            .delete(user.id())
+            .context(|| format!("deleting user '{username}'"))?;
         Ok(())
     }
 
@@ -136,9 +141,9 @@ impl HubuumGateway {
             ));
         }
 
-        let handle = self.client.users().get_by_name(&input.username)?;
+        let handle = self.resolve_user(&input.username)?;
         let updated = self
-            .client
+            .client()
             .users()
             .update(handle.id())
             .params(UserPatch {
@@ -150,8 +155,18 @@ impl HubuumGateway {
         Ok(UserRecord::from(updated))
     }
 
+    /// The groups `username` is a member of.
+    pub fn user_groups(&self, username: &str) -> Result<Vec<GroupRecord>, AppError> {
+        let handle = self.resolve_user(username)?;
+        Ok(handle
+            .groups()?
+            .into_iter()
+            .map(|group| GroupRecord::from(group.resource().clone()))
+            .collect())
+    }
+
     pub fn user_tokens(&self, username: &str) -> Result<Vec<PrincipalTokenRecord>, AppError> {
-        let handle = self.client.users().get_by_name(username)?;
+        let handle = self.resolve_user(username)?;
         let tokens = handle.tokens()?;
         Ok(tokens.into_iter().map(PrincipalTokenRecord::from).collect())
     }
@@ -161,7 +176,7 @@ impl HubuumGateway {
         username: &str,
         input: NewTokenInput,
     ) -> Result<String, AppError> {
-        let handle = self.client.users().get_by_name(username)?;
+        let handle = self.resolve_user(username)?;
         let mut req = NewTokenRequest::new();
 
         if let Some(n) = input.name {
@@ -197,13 +212,13 @@ impl HubuumGateway {
     }
 
     pub fn user_token_revoke(&self, username: &str, token_id: i32) -> Result<(), AppError> {
-        let handle = self.client.users().get_by_name(username)?;
+        let handle = self.resolve_user(username)?;
         handle.token_revoke(token_id)?;
         Ok(())
     }
 
     pub fn set_user_password(&self, username: &str, password: &str) -> Result<(), AppError> {
-        let handle = self.client.users().get_by_name(username)?;
+        let handle = self.resolve_user(username)?;
         handle.set_password(password)?;
         Ok(())
     }
@@ -249,3 +264,28 @@ pub(crate) const USER_SORT_SPECS: &[SortFieldSpec] = &[
     SortFieldSpec::new("created_at", "created_at"),
     SortFieldSpec::new("updated_at", "updated_at"),
 ];
+
+pub(crate) const USER_CREATE_CALLS: &[ApiCall] = &[ApiCall::post(
+    "/api/v1/iam/users",
+    "Create user '{username}'",
+)];
+
+pub(crate) const USER_DELETE_CALLS: &[ApiCall] = &[
+    ApiCall::get("/api/v1/iam/users", "Look up user '{username}'")
+        .with_filters(&["name={username}"]),
+    ApiCall::delete("/api/v1/iam/users/{user_id}", "Delete the user"),
+];
+
+pub(crate) const USER_SHOW_CALLS: &[ApiCall] =
+    &[
+        ApiCall::get("/api/v1/iam/users", "Look up user '{username}'")
+            .with_filters(&["name__equals={username}"]),
+    ];
+
+pub(crate) const USER_LIST_CALLS: &[ApiCall] = &[ApiCall::get("/api/v1/iam/users", "List users")
+    .listing(&[
+        "username icontains {username}",
+        "email icontains {email}",
+        "created_at = {created_at}",
+        "updated_at = {updated_at}",
+    ])];
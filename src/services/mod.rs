@@ -1,3 +1,4 @@
+mod bulk;
 mod completion;
 mod gateway;
 
@@ -11,10 +12,14 @@ use crate::background::BackgroundManager;
 use crate::config::{get_config, AppConfig, UserPreferences};
 use crate::errors::AppError;
 
+pub(crate) use bulk::{BulkRunner, Checkpoint};
 pub use completion::CompletionContext;
 use completion::CompletionStore;
 pub(crate) use gateway::filter_specs_for_command_path;
+pub(crate) use gateway::is_unauthorized;
 pub(crate) use gateway::sort_specs_for_command_path;
+pub(crate) use gateway::Relogin;
+pub(crate) use gateway::{api_calls_for_command_path, ApiCall};
 pub use gateway::{
     AuditListInput, AuditScope, BackupInput, ClassUpdateInput, CollectionUpdateInput,
     ComputedDefinitionInput, ComputedOperationInput, ComputedOperationKind, ComputedPatchInput,
@@ -72,6 +77,13 @@ impl AppServices {
         CompletionContext::new(self.clone(), runtime)
     }
 
+    /// Switches every service to `client`, a login as another user or on another
+    /// server, forgetting what was fetched with the previous one.
+    pub(crate) fn replace_client(&self, client: BlockingClient<Authenticated>, relogin: Relogin) {
+        self.gateway.replace_client(client, relogin);
+        self.completion.forget_login();
+    }
+
     pub fn invalidate_completion(&self) {
         self.completion.invalidate_all();
     }
@@ -0,0 +1,246 @@
+use std::io::{stderr, stdin, BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use hubuum_client::{blocking::Client as BlockingClient, BaseUrl};
+
+use crate::config::write_config_values;
+use crate::defaults::Defaults;
+use crate::errors::AppError;
+use crate::forms::Form;
+use crate::models::OutputFormat;
+
+/// Connection and output settings collected by the first-run wizard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetupAnswers {
+    pub hostname: String,
+    pub port: u16,
+    pub protocol: String,
+    pub username: String,
+    pub ssl_validation: bool,
+    pub format: String,
+}
+
+impl SetupAnswers {
+    fn server_url(&self) -> String {
+        format!("{}://{}:{}", self.protocol, self.hostname, self.port)
+    }
+
+    fn config_values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("server.hostname", self.hostname.clone()),
+            ("server.port", self.port.to_string()),
+            ("server.protocol", self.protocol.clone()),
+            ("server.username", self.username.clone()),
+            ("server.ssl_validation", self.ssl_validation.to_string()),
+            ("output.format", self.format.clone()),
+        ]
+    }
+}
+
+/// Whether the first-run wizard should be offered.
+///
+/// That is only the case for an interactive terminal when no configuration file exists
+/// and no server was given on the command line or in the environment.
+pub fn should_offer_setup(config_paths: &[&Path], server_given: bool) -> bool {
+    !server_given
+        && config_paths.iter().all(|path| !path.exists())
+        && stdin().is_terminal()
+        && stderr().is_terminal()
+}
+
+/// Offers the setup wizard on the terminal and writes the answers to `config_path`.
+///
+/// Returns `false` when the wizard is declined and nothing was written.
+pub fn run_setup_wizard(config_path: &Path) -> Result<bool, AppError> {
+    run_with(&mut Form::stdio(), config_path, check_connectivity)
+}
+
+fn run_with<R: BufRead, W: Write>(
+    form: &mut Form<R, W>,
+    config_path: &Path,
+    check: impl Fn(&SetupAnswers) -> Result<(), AppError>,
+) -> Result<bool, AppError> {
+    form.show(&format!(
+        "No Hubuum CLI configuration was found at {}.",
+        config_path.display()
+    ))?;
+    if !form.confirm("Run the setup wizard now?")? {
+        return Ok(false);
+    }
+
+    let mut answers = ask_answers(form, None)?;
+    loop {
+        form.show(&format!("Checking {}...", answers.server_url()))?;
+        match check(&answers) {
+            Ok(()) => {
+                form.show("Server is reachable.")?;
+                break;
+            }
+            Err(error) => {
+                form.show(&format!("Could not reach the server: {error}"))?;
+                if !form.confirm("Change the settings and try again?")? {
+                    break;
+                }
+            }
+        }
+        answers = ask_answers(form, Some(&answers))?;
+    }
+
+    write_config_values(config_path, &answers.config_values())?;
+    form.show(&format!(
+        "Saved configuration to {}.",
+        config_path.display()
+    ))?;
+    Ok(true)
+}
+
+/// Asks every setting, offering the previous answers (or the built-in defaults) as defaults.
+fn ask_answers<R: BufRead, W: Write>(
+    form: &mut Form<R, W>,
+    previous: Option<&SetupAnswers>,
+) -> Result<SetupAnswers, AppError> {
+    let default_format = Defaults::OUTPUT_FORMAT.to_string().to_lowercase();
+    let hostname = form.text(
+        "Server hostname",
+        Some(previous.map_or(Defaults::SERVER_HOSTNAME, |answers| &answers.hostname)),
+    )?;
+    let protocol = form.choice(
+        "Protocol",
+        &["https", "http"],
+        &previous.map_or(Defaults::PROTOCOL.to_string(), |answers| {
+            answers.protocol.clone()
+        }),
+    )?;
+    let port = parsed(
+        form,
+        "Port",
+        previous.map_or(Defaults::SERVER_PORT, |answers| answers.port),
+    )?;
+    let username = form.text(
+        "Username",
+        Some(previous.map_or(Defaults::USER_USERNAME, |answers| &answers.username)),
+    )?;
+    let ssl_validation = if protocol == "https" {
+        let default = previous.map_or(Defaults::SERVER_SSL_VALIDATION, |answers| {
+            answers.ssl_validation
+        });
+        form.choice(
+            "Validate TLS certificates",
+            &["yes", "no"],
+            if default { "yes" } else { "no" },
+        )? == "yes"
+    } else {
+        Defaults::SERVER_SSL_VALIDATION
+    };
+    let format = form.choice(
+        "Output format",
+        &[
+            &OutputFormat::Text.to_string().to_lowercase(),
+            &OutputFormat::Json.to_string().to_lowercase(),
+        ],
+        previous.map_or(default_format.as_str(), |answers| &answers.format),
+    )?;
+
+    Ok(SetupAnswers {
+        hostname,
+        port,
+        protocol,
+        username,
+        ssl_validation,
+        format,
+    })
+}
+
+fn parsed<R: BufRead, W: Write, T: FromStr + ToString>(
+    form: &mut Form<R, W>,
+    label: &str,
+    default: T,
+) -> Result<T, AppError> {
+    let default = default.to_string();
+    loop {
+        let answer = form.text(label, Some(&default))?;
+        match answer.parse() {
+            Ok(value) => return Ok(value),
+            Err(_) => form.show(&format!(
+                "  '{answer}' is not a valid {}.",
+                label.to_lowercase()
+            ))?,
+        }
+    }
+}
+
+/// Calls the unauthenticated health probe, so the check works before any login.
+fn check_connectivity(answers: &SetupAnswers) -> Result<(), AppError> {
+    let client = BlockingClient::builder(BaseUrl::from_str(&answers.server_url())?)
+        .validate_certs(answers.ssl_validation)
+        .build()?;
+    client.healthz()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::fs::read_to_string;
+    use std::io::Cursor;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn form(answers: &str) -> Form<Cursor<Vec<u8>>, Vec<u8>> {
+        Form::new(Cursor::new(answers.as_bytes().to_vec()), Vec::new())
+    }
+
+    #[test]
+    fn wizard_writes_answers_after_a_failed_check_is_retried() {
+        let directory = tempdir().expect("temporary directory should be created");
+        let path = directory.path().join("hubuum").join("config.toml");
+        // First round: hub.example, an invalid protocol then https, an invalid port then
+        // 8443, admin, no TLS validation, json; the check fails. The second round keeps
+        // everything but the port.
+        let mut prompts =
+            form("y\nhub.example\nhttps-ish\n\nx\n8443\nadmin\nno\njson\ny\n\n\n443\n\n\n\n");
+        let checks = Cell::new(0);
+
+        let saved = run_with(&mut prompts, &path, |answers| {
+            checks.set(checks.get() + 1);
+            if answers.port == 443 {
+                Ok(())
+            } else {
+                Err(AppError::CommandExecutionError("refused".to_string()))
+            }
+        })
+        .expect("wizard should complete");
+
+        assert!(saved);
+        assert_eq!(checks.get(), 2);
+        let written = read_to_string(&path).expect("config should be written");
+        for line in [
+            "hostname = \"hub.example\"",
+            "port = 443",
+            "protocol = \"https\"",
+            "username = \"admin\"",
+            "ssl_validation = false",
+            "format = \"json\"",
+        ] {
+            assert!(written.contains(line), "{line} missing from:\n{written}");
+        }
+        let transcript = String::from_utf8(prompts.into_prompt()).unwrap();
+        assert!(transcript.contains("Could not reach the server: "));
+        assert!(transcript.contains("refused"));
+        assert!(transcript.contains("'x' is not a valid port."));
+    }
+
+    #[test]
+    fn declining_the_wizard_writes_nothing() {
+        let directory = tempdir().expect("temporary directory should be created");
+        let path = directory.path().join("config.toml");
+
+        let saved = run_with(&mut form("\n"), &path, |_| Ok(())).expect("wizard should finish");
+
+        assert!(!saved);
+        assert!(!path.exists());
+    }
+}
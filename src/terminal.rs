@@ -1,6 +1,8 @@
 use std::env::var;
+use std::io::{stderr, stdin, stdout, IsTerminal};
 
 use crossterm::terminal::size;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 pub(crate) fn terminal_width() -> Option<usize> {
     size()
@@ -10,9 +12,75 @@ pub(crate) fn terminal_width() -> Option<usize> {
         .or_else(columns_env_width)
 }
 
+/// Whether stdout is a terminal rather than a pipe or a file.
+pub(crate) fn stdout_is_terminal() -> bool {
+    stdout().is_terminal()
+}
+
+/// Whether stderr, where prompts and the `-v` log go, is a terminal.
+pub(crate) fn stderr_is_terminal() -> bool {
+    stderr().is_terminal()
+}
+
+/// Whether prompts can be answered: both stdin and stderr must be terminals.
+pub(crate) fn prompts_are_interactive() -> bool {
+    stdin().is_terminal() && stderr_is_terminal()
+}
+
+/// Number of terminal columns `text` occupies; wide CJK characters and emoji count as two.
+pub(crate) fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// Left-aligns `text` in a field of `width` terminal columns. `format!("{:<n$}")` pads by
+/// character count, which misaligns columns containing wide characters.
+pub(crate) fn pad_to_width(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{text}{}", " ".repeat(padding))
+}
+
+/// Shortens `text` to at most `width` terminal columns, ending in `...` when cut.
+pub(crate) fn truncate_to_width(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    let budget = width.saturating_sub(3);
+    let mut used = 0;
+    let mut truncated = String::new();
+    for character in text.chars() {
+        used += character.width().unwrap_or_default();
+        if used > budget {
+            break;
+        }
+        truncated.push(character);
+    }
+    format!("{truncated}...")
+}
+
 fn columns_env_width() -> Option<usize> {
     var("COLUMNS")
         .ok()
         .and_then(|value| value.parse::<usize>().ok())
         .filter(|width| *width > 0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{display_width, pad_to_width, truncate_to_width};
+
+    #[test]
+    fn padding_counts_wide_characters_as_two_columns() {
+        assert_eq!(display_width("東京"), 4);
+        assert_eq!(display_width("🚀x"), 3);
+        assert_eq!(pad_to_width("東京", 6), "東京  ");
+        assert_eq!(pad_to_width("abc", 6), "abc   ");
+        assert_eq!(pad_to_width("toolong", 3), "toolong");
+    }
+
+    #[test]
+    fn truncation_respects_display_width() {
+        assert_eq!(truncate_to_width("short", 10), "short");
+        assert_eq!(truncate_to_width("東京都渋谷区", 8), "東京...");
+        assert_eq!(truncate_to_width("abcdefghij", 8), "abcde...");
+    }
+}
@@ -9,6 +9,7 @@ use hubuum_theme::{
 
 use crate::config::get_config;
 use crate::models::OutputColor;
+use crate::terminal::{stderr_is_terminal, stdout_is_terminal};
 
 pub use hubuum_theme::ThemeRole;
 
@@ -20,9 +21,40 @@ pub fn color_choice() -> ColorChoice {
     }
 }
 
+/// The stream painted text is written to, which decides `output.color = "auto"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl Stream {
+    fn is_terminal(self) -> bool {
+        match self {
+            Stream::Stdout => stdout_is_terminal(),
+            Stream::Stderr => stderr_is_terminal(),
+        }
+    }
+}
+
+/// Whether text written to `stream` is coloured under `output.color`.
+pub fn colors_on(stream: Stream) -> bool {
+    match get_config().output.color {
+        OutputColor::Never => false,
+        OutputColor::Always => true,
+        OutputColor::Auto => stream.is_terminal(),
+    }
+}
+
+/// Paints `text` for stdout, where command output is written.
 pub fn paint(role: ThemeRole, text: impl AsRef<str>) -> String {
+    paint_on(Stream::Stdout, role, text)
+}
+
+/// Paints `text` for `stream`, leaving it plain when that stream is not coloured.
+pub fn paint_on(stream: Stream, role: ThemeRole, text: impl AsRef<str>) -> String {
     let text = text.as_ref();
-    if matches!(get_config().output.color, OutputColor::Never) {
+    if !colors_on(stream) {
         return text.to_string();
     }
 
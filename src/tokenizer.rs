@@ -1,13 +1,22 @@
 use log::trace;
 
-use std::collections::HashMap;
-use std::fs::read_to_string;
+use std::collections::{BTreeSet, HashMap};
 
 use crate::commands::CliOption;
 use crate::errors::AppError;
+use crate::files::{file_url_path, read_text_file};
+use crate::formatting::human_bytes;
+use crate::output::add_warning;
 use reqwest::blocking::get;
 use shlex::split;
 
+/// Appended to the long name of a raw option: `--data-raw` takes the rest of the line as the
+/// value of `--data`, without shell-style word splitting or quote processing.
+pub(crate) const RAW_OPTION_SUFFIX: &str = "-raw";
+
+/// Inline value-source values longer than this get a warning suggesting `file://` instead.
+const LARGE_INLINE_VALUE_BYTES: usize = 4096;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OptionOccurrence {
     pub key: String,
@@ -19,6 +28,9 @@ pub struct CommandTokenizer {
     raw_tokens: Vec<String>,
     scopes: Vec<String>,
     command: String,
+    /// The full path of the command when the caller knows it, as `raw_tokens` omits the
+    /// REPL scope the line was entered in.
+    command_path: Vec<String>,
     options: HashMap<String, String>,
     option_occurrences: Vec<OptionOccurrence>,
     positionals: Vec<String>,
@@ -51,12 +63,21 @@ impl CommandTokenizer {
         option_defs: &[CliOption],
         resolve_value_sources: bool,
     ) -> Result<Self, AppError> {
-        let tokens = split(input).ok_or(AppError::InvalidInput)?;
+        let raw_names = option_defs
+            .iter()
+            .filter(|opt| opt.raw)
+            .filter_map(CliOption::long_without_dashes)
+            .collect::<BTreeSet<_>>();
+        let (head, raw_tail) = split_raw_tail(input, &raw_names);
+        let raw_option = raw_tail.map(parse_raw_tail).transpose()?;
+
+        let tokens = split(head).ok_or(AppError::InvalidInput)?;
         let option_lookup = Self::build_option_lookup(option_defs);
         let mut tokenizer = CommandTokenizer {
             raw_tokens: tokens.clone(),
             scopes: Vec::new(),
             command: String::new(),
+            command_path: Vec::new(),
             options: HashMap::new(),
             option_occurrences: Vec::new(),
             positionals: Vec::new(),
@@ -92,6 +113,20 @@ impl CommandTokenizer {
             idx += 1;
         }
 
+        if let Some((key, value)) = raw_option {
+            if tokenizer.command.is_empty() {
+                return Err(AppError::InvalidInput);
+            }
+            trace!("Raw option --{key}{RAW_OPTION_SUFFIX}: {value:?}");
+            tokenizer
+                .raw_tokens
+                .extend([format!("--{key}"), value.clone()]);
+            tokenizer.options.insert(key.clone(), value.clone());
+            tokenizer
+                .option_occurrences
+                .push(OptionOccurrence { key, value });
+        }
+
         Ok(tokenizer)
     }
 
@@ -220,6 +255,9 @@ impl CommandTokenizer {
         };
 
         let value = if parse_spec.value_source && resolve_value_sources {
+            if let Some(warning) = large_inline_value_warning(&key, &value) {
+                add_warning(warning)?;
+            }
             self.convert_file_and_http_values(&value)?
         } else {
             value
@@ -334,8 +372,7 @@ impl CommandTokenizer {
                 .trim_end()
                 .to_string()
         } else if let Some(stripped) = value.strip_prefix("file://") {
-            read_to_string(stripped)
-                .map_err(AppError::IoError)?
+            read_text_file(&file_url_path(stripped))?
                 .trim_end()
                 .to_string()
         } else {
@@ -349,6 +386,27 @@ impl CommandTokenizer {
         &self.scopes
     }
 
+    /// Records the full path of the command, e.g. `object delete`, for messages.
+    pub fn with_command_path(mut self, command_path: &[String]) -> Self {
+        self.command_path = command_path.to_vec();
+        self
+    }
+
+    /// The full command path, falling back to the scopes and command on the line.
+    pub fn command_path(&self) -> String {
+        if self.command_path.is_empty() {
+            self.scopes
+                .iter()
+                .chain([&self.command])
+                .filter(|word| !word.is_empty())
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(" ")
+        } else {
+            self.command_path.join(" ")
+        }
+    }
+
     #[allow(dead_code)]
     pub fn get_command(&self) -> Result<&str, AppError> {
         if self.command.is_empty() {
@@ -384,6 +442,115 @@ impl CommandTokenizer {
     }
 }
 
+/// Splits `line` at the first unquoted `--<name>-raw` word whose `name` is in `raw_names`.
+///
+/// Returns the text before that word, to be parsed as usual, and the verbatim tail
+/// starting at it, if there is one.
+pub(crate) fn split_raw_tail<'a>(
+    line: &'a str,
+    raw_names: &BTreeSet<String>,
+) -> (&'a str, Option<&'a str>) {
+    if raw_names.is_empty() {
+        return (line, None);
+    }
+
+    let mut quote = None;
+    let mut escaped = false;
+    let mut word_start = true;
+    for (index, ch) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        if let Some(open) = quote {
+            if ch == open {
+                quote = None;
+            } else if ch == '\\' && open == '"' {
+                escaped = true;
+            }
+            continue;
+        }
+        if ch.is_whitespace() {
+            word_start = true;
+            continue;
+        }
+        if word_start
+            && raw_option_name(&line[index..]).is_some_and(|name| raw_names.contains(name))
+        {
+            return (line[..index].trim_end(), Some(&line[index..]));
+        }
+        word_start = false;
+        match ch {
+            '\'' | '"' => quote = Some(ch),
+            '\\' => escaped = true,
+            _ => {}
+        }
+    }
+
+    (line, None)
+}
+
+/// Puts a tail split off by [`split_raw_tail`] back after `head`.
+pub(crate) fn join_raw_tail(head: &str, tail: Option<&str>) -> String {
+    match tail {
+        Some(tail) => format!("{head} {tail}"),
+        None => head.to_string(),
+    }
+}
+
+/// The option name of a `--<name>-raw` word at the start of `text`.
+fn raw_option_name(text: &str) -> Option<&str> {
+    let word = text
+        .split(|ch: char| ch.is_whitespace() || ch == '=')
+        .next()?;
+    word.strip_prefix("--")?
+        .strip_suffix(RAW_OPTION_SUFFIX)
+        .filter(|name| !name.is_empty())
+}
+
+/// Parses a raw tail into the option name and its value.
+///
+/// The value is everything after the option word (and an optional `=`), trimmed. A value
+/// wrapped in one pair of matching quotes loses them, so `--data-raw '{...}'` also works.
+fn parse_raw_tail(tail: &str) -> Result<(String, String), AppError> {
+    let name = raw_option_name(tail).ok_or(AppError::InvalidInput)?;
+    let rest = &tail[2 + name.len() + RAW_OPTION_SUFFIX.len()..];
+    let value = rest.strip_prefix('=').unwrap_or(rest).trim();
+    let value = ['\'', '"']
+        .iter()
+        .find_map(|quote| {
+            value
+                .strip_prefix(*quote)
+                .and_then(|inner| inner.strip_suffix(*quote))
+        })
+        .unwrap_or(value);
+    if value.is_empty() {
+        return Err(AppError::ParseError(format!(
+            "Option '--{name}{RAW_OPTION_SUFFIX}' requires a value"
+        )));
+    }
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// Warns about an inline value-source value that is large enough to be awkward on a
+/// command line, pointing at the `file://` form instead.
+fn large_inline_value_warning(key: &str, value: &str) -> Option<String> {
+    let inline = !["http://", "https://", "file://"]
+        .iter()
+        .any(|prefix| value.starts_with(prefix));
+    let option = if key.len() == 1 {
+        format!("-{key}")
+    } else {
+        format!("--{key}")
+    };
+    (inline && value.len() > LARGE_INLINE_VALUE_BYTES).then(|| {
+        format!(
+            "The inline value for {option} is {}; large documents are easier to pass as {option} file://PATH",
+            human_bytes(value.len() as u64)
+        )
+    })
+}
+
 fn token_key(token: &str) -> String {
     token
         .trim_start_matches('-')
@@ -398,7 +565,9 @@ mod tests {
     use std::any::TypeId;
     use std::fs::write;
 
-    use super::CommandTokenizer;
+    use super::{
+        large_inline_value_warning, split_raw_tail, CommandTokenizer, LARGE_INLINE_VALUE_BYTES,
+    };
     use crate::commands::CliOption;
     use crate::errors::AppError;
     use tempfile::tempdir;
@@ -413,6 +582,8 @@ mod tests {
             nargs: None,
             repeatable: false,
             value_source: false,
+            raw: false,
+            positional: None,
             help: String::new(),
             field_type: TypeId::of::<String>(),
             field_type_help: "string".to_string(),
@@ -665,6 +836,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn only_large_inline_values_are_warned_about() {
+        let large = format!("{{\"blob\": \"{}\"}}", "x".repeat(LARGE_INLINE_VALUE_BYTES));
+        let warning = large_inline_value_warning("data", &large).expect("large value warns");
+        assert!(warning.contains("--data file://PATH"), "{warning}");
+        assert!(large_inline_value_warning("D", &large)
+            .expect("short options warn too")
+            .contains("-D file://PATH"));
+
+        assert!(large_inline_value_warning("data", "{\"small\": true}").is_none());
+        let path = format!("file://{}", "p".repeat(LARGE_INLINE_VALUE_BYTES));
+        assert!(large_inline_value_warning("data", &path).is_none());
+    }
+
     #[test]
     fn validation_tokenizer_does_not_load_value_sources() {
         let options = vec![value_source_opt("data", None, Some("--data"))];
@@ -681,4 +866,73 @@ mod tests {
             Some(&"file:///definitely/not/read.json".to_string())
         );
     }
+
+    #[test]
+    fn raw_option_takes_the_rest_of_the_line_verbatim() {
+        let options = vec![
+            opt("name", Some("-n"), Some("--name"), false),
+            CliOption {
+                raw: true,
+                ..value_source_opt("data", Some("-D"), Some("--data"))
+            },
+        ];
+
+        let tokens = CommandTokenizer::new(
+            r#"object create -n 'web 01' --data-raw {"note": "it's \"quoted\"", "x": 'y'} | grep"#,
+            "create",
+            &options,
+        )
+        .expect("tokenization should succeed");
+        assert_eq!(tokens.get_options().get("n"), Some(&"web 01".to_string()));
+        assert_eq!(
+            tokens.get_options().get("data"),
+            Some(&r#"{"note": "it's \"quoted\"", "x": 'y'} | grep"#.to_string())
+        );
+        assert!(tokens.get_options().get("data-raw").is_none());
+
+        let tokens = CommandTokenizer::new(
+            r#"object create --data-raw='{"a": "b c"}'  "#,
+            "create",
+            &options,
+        )
+        .expect("one pair of outer quotes should be stripped");
+        assert_eq!(
+            tokens.get_options().get("data"),
+            Some(&r#"{"a": "b c"}"#.to_string())
+        );
+
+        let tokens =
+            CommandTokenizer::new("object create --data-raw file:///x", "create", &options)
+                .expect("raw values are not value sources");
+        assert_eq!(
+            tokens.get_options().get("data"),
+            Some(&"file:///x".to_string())
+        );
+
+        let err = CommandTokenizer::new("object create --data-raw ''", "create", &options)
+            .expect_err("an empty raw value should fail");
+        assert!(matches!(err, AppError::ParseError(message) if message.contains("--data-raw")));
+    }
+
+    #[test]
+    fn raw_tail_is_only_split_at_unquoted_known_options() {
+        let names = ["data".to_string()].into_iter().collect();
+
+        assert_eq!(
+            split_raw_tail("create -n x --data-raw {\"a\": 1}", &names),
+            ("create -n x", Some("--data-raw {\"a\": 1}"))
+        );
+        assert_eq!(
+            split_raw_tail("create -n 'x --data-raw y' --data z", &names),
+            ("create -n 'x --data-raw y' --data z", None)
+        );
+        assert_eq!(
+            split_raw_tail("create --schema-raw {}", &names),
+            ("create --schema-raw {}", None)
+        );
+        assert_eq!(
+            split_raw_tail("create -n x--data-raw y", &names),
+            ("create -n x--data-raw y", None)
+        );
+    }
 }
@@ -0,0 +1,42 @@
+pub const BEGIN: &str = "begin";
+pub const COMMIT: &str = "commit";
+pub const ROLLBACK: &str = "rollback";
+
+/// A validated command line held back until the transaction is committed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedCommand {
+    pub scope: Vec<String>,
+    pub line: String,
+}
+
+impl QueuedCommand {
+    pub fn display_line(&self) -> String {
+        if self.scope.is_empty() {
+            self.line.clone()
+        } else {
+            format!("{} {}", self.scope.join(" "), self.line)
+        }
+    }
+}
+
+pub fn is_transaction_command(parts: &[String]) -> bool {
+    matches!(parts, [word] if matches!(word.as_str(), BEGIN | COMMIT | ROLLBACK))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(parts: &[&str]) -> Vec<String> {
+        parts.iter().map(|part| (*part).to_string()).collect()
+    }
+
+    #[test]
+    fn display_line_includes_queued_scope() {
+        let queued = QueuedCommand {
+            scope: path(&["class"]),
+            line: "create --name Host".to_string(),
+        };
+        assert_eq!(queued.display_line(), "class create --name Host");
+    }
+}
@@ -0,0 +1,124 @@
+use serde_json::Value;
+
+use crate::errors::AppError;
+
+/// A requested value that the re-fetched entity does not show.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    pub field: String,
+    pub expected: Value,
+    pub actual: Option<Value>,
+}
+
+impl Mismatch {
+    fn describe(&self) -> String {
+        match &self.actual {
+            Some(actual) => format!("{} is {actual}, expected {}", self.field, self.expected),
+            None => format!("{} is missing, expected {}", self.field, self.expected),
+        }
+    }
+}
+
+/// Lists every value in `expected` that `actual` does not show.
+///
+/// Objects are compared as subsets, so fields the command did not touch (timestamps,
+/// other data keys) are ignored. Arrays and scalars must match exactly.
+pub fn mismatches(expected: &Value, actual: &Value) -> Vec<Mismatch> {
+    let mut found = Vec::new();
+    collect(expected, Some(actual), "", &mut found);
+    found
+}
+
+fn collect(expected: &Value, actual: Option<&Value>, path: &str, found: &mut Vec<Mismatch>) {
+    if let (Value::Object(expected), Some(Value::Object(actual))) = (expected, actual) {
+        for (key, value) in expected {
+            let path = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            collect(value, actual.get(key), &path, found);
+        }
+        return;
+    }
+    if actual != Some(expected) {
+        found.push(Mismatch {
+            field: if path.is_empty() { "value" } else { path }.to_string(),
+            expected: expected.clone(),
+            actual: actual.cloned(),
+        });
+    }
+}
+
+/// Fails, listing every mismatch, when `actual` does not show what was requested for `entity`.
+pub fn ensure_visible(entity: &str, expected: &Value, actual: &Value) -> Result<(), AppError> {
+    let found = mismatches(expected, actual);
+    if found.is_empty() {
+        return Ok(());
+    }
+    Err(AppError::CommandExecutionError(format!(
+        "Verification failed for {entity}: {}",
+        found
+            .iter()
+            .map(Mismatch::describe)
+            .collect::<Vec<_>>()
+            .join("; ")
+    )))
+}
+
+/// Turns a failed re-fetch of `entity` into a verification failure.
+pub fn ensure_found<T>(entity: &str, fetched: Result<T, AppError>) -> Result<T, AppError> {
+    fetched.map_err(|error| {
+        AppError::CommandExecutionError(format!(
+            "Verification failed for {entity}: not found after the change ({error})"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn only_requested_values_are_compared() {
+        let actual = json!({
+            "name": "web01",
+            "description": "Web server",
+            "updated_at": "2026-01-01T00:00:00",
+            "data": {"ip": "10.0.0.1", "dns": ["a", "b"], "owner": "ops"}
+        });
+        assert!(mismatches(
+            &json!({"name": "web01", "data": {"ip": "10.0.0.1", "dns": ["a", "b"]}}),
+            &actual
+        )
+        .is_empty());
+
+        let found = mismatches(
+            &json!({"name": "web02", "data": {"ip": "10.0.0.1", "dns": ["a"], "rack": 4}}),
+            &actual,
+        );
+        let fields = found
+            .iter()
+            .map(|mismatch| mismatch.field.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec!["data.dns", "data.rack", "name"]);
+        assert_eq!(found[1].actual, None);
+    }
+
+    #[test]
+    fn failures_list_every_mismatch() {
+        let error = ensure_visible(
+            "object 'web01'",
+            &json!({"name": "web01", "description": "new"}),
+            &json!({"name": "web01", "description": "old", "data": null}),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("Verification failed for object 'web01'"));
+        assert!(error.contains("description is \"old\", expected \"new\""));
+
+        assert!(ensure_visible("x", &json!({"data": {"a": 1}}), &json!({"data": null})).is_err());
+    }
+}
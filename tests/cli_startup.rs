@@ -77,6 +77,13 @@ fn direct_help_and_config_paths_do_not_require_login() {
         .assert()
         .success()
         .stdout(contains("Secrets are redacted"));
+
+    cargo_bin_cmd!("hubuum-cli")
+        .args(["help", "--json"])
+        .assert()
+        .success()
+        .stdout(contains("\"scopes\""))
+        .stdout(contains("\"required\""));
 }
 
 #[test]
@@ -201,6 +208,31 @@ fn script_applies_successful_redirects_before_a_later_failure() {
     assert!(output.contains("Available commands"));
 }
 
+#[test]
+fn piped_scripts_keep_going_past_failures_when_asked() {
+    let dir = tempdir().expect("tempdir");
+    let redirected = dir.path().join("help.txt");
+    let script = format!(
+        "# provisioning\nhelp definitely-not-a-command\n\nhelp > {}\n",
+        redirected.display()
+    );
+
+    cargo_bin_cmd!("hubuum-cli")
+        .write_stdin(script.clone())
+        .assert()
+        .failure();
+    assert!(!redirected.exists(), "the script should stop at line 2");
+
+    cargo_bin_cmd!("hubuum-cli")
+        .args(["--script", "-", "--keep-going"])
+        .write_stdin(script)
+        .assert()
+        .failure()
+        .stdout(contains("1 script command(s) failed, on line(s) 2"));
+    let output = read_to_string(redirected).expect("line 4 should still run");
+    assert!(output.contains("Available commands"));
+}
+
 #[test]
 fn hidden_command_alias_still_works() {
     cargo_bin_cmd!("hubuum-cli")
@@ -244,6 +276,30 @@ fn offline_config_show_supports_semantic_output_formats() {
         .stdout(contains("\"key\":\"output.format\""));
 }
 
+#[test]
+fn global_output_flag_and_config_set_the_default_format() {
+    cargo_bin_cmd!("hubuum-cli")
+        .args(["--output", "csv", "config", "show"])
+        .assert()
+        .success()
+        .stdout(contains("key,value,source,detail"))
+        .stdout(contains("output.format,csv,cli"));
+
+    let dir = tempdir().expect("tempdir");
+    let config = dir.path().join("config.toml");
+    write(&config, "[output]\nformat = \"tsv\"\n").expect("config should be written");
+    cargo_bin_cmd!("hubuum-cli")
+        .args([
+            "--config",
+            config.to_str().expect("UTF-8 path"),
+            "config",
+            "show",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("key\tvalue\tsource\tdetail"));
+}
+
 #[test]
 fn offline_config_show_supports_semantic_pipeline_projection() {
     cargo_bin_cmd!("hubuum-cli")
@@ -0,0 +1,1065 @@
+//! End-to-end tests of the core commands against the in-process fake server.
+
+mod support;
+
+use std::fs::write;
+
+use hubuum_fake_server::{FakeResponse, Resource, PASSWORD, USERNAME};
+use predicates::str::contains;
+use serde_json::{json, Value};
+
+use support::Harness;
+
+fn json_output(output: &[u8]) -> Value {
+    serde_json::from_slice(output).expect("command should print JSON")
+}
+
+/// Every JSON document a command printed, such as a write-ahead summary and a result.
+fn json_documents(output: &[u8]) -> Vec<Value> {
+    serde_json::Deserializer::from_slice(output)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .expect("command should print JSON")
+}
+
+fn assert_password_login(harness: &Harness) {
+    let logins = harness.server.requests_to("POST", "/api/v0/auth/login");
+    assert_eq!(logins.len(), 1);
+    assert_eq!(
+        logins[0].body(),
+        Some(&json!({ "name": USERNAME, "password": PASSWORD }))
+    );
+}
+
+#[test]
+fn logs_in_once_and_reuses_the_cached_token() {
+    let harness = Harness::new().with_fixture("inventory.json");
+
+    harness
+        .cli()
+        .args(["class", "list"])
+        .assert()
+        .success()
+        .stdout(contains("Hosts"))
+        .stdout(contains("Switches"));
+    harness
+        .cli()
+        .args(["collection", "list"])
+        .assert()
+        .success()
+        .stdout(contains("Lab equipment"));
+
+    let server = &harness.server;
+    assert_eq!(server.requests_to("POST", "/api/v0/auth/login").len(), 1);
+    assert_eq!(server.requests_to("GET", "/api/v0/auth/validate").len(), 1);
+    assert!(server
+        .requests_to("GET", "/api/v1/collections")
+        .iter()
+        .all(|request| request.token() == Some("fake-token-1")));
+}
+
+#[test]
+fn bearer_token_files_skip_the_password_login() {
+    let harness = Harness::new().with_fixture("inventory.json");
+    let token_file = harness.home().join("token");
+    write(&token_file, harness.server.issue_token()).expect("token file should be written");
+
+    harness
+        .cli_without_credentials()
+        .args(["--token-file", token_file.to_str().expect("UTF-8 path")])
+        .args(["collection", "list"])
+        .assert()
+        .success()
+        .stdout(contains("prod"));
+
+    assert!(harness
+        .server
+        .requests_to("POST", "/api/v0/auth/login")
+        .is_empty());
+}
+
+#[test]
+fn list_filters_sorts_and_limits_are_sent_to_the_server() {
+    let harness = Harness::new().with_fixture("inventory.json");
+
+    let output = harness
+        .cli()
+        .args([
+            "object",
+            "list",
+            "--class",
+            "Hosts",
+            "--where",
+            "name",
+            "icontains",
+            "WEB",
+            "--sort",
+            "name",
+            "desc",
+            "--limit",
+            "1",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let page = json_output(&output);
+    assert_eq!(page["items"][0]["name"], "web-02");
+    assert_eq!(page["returned_count"], 1);
+    assert_eq!(page["next_cursor"], "1");
+
+    let listing = harness.server.requests_to("GET", "/api/v1/classes/1/");
+    let request = listing.last().expect("objects should be listed");
+    assert_eq!(request.query_value("name__icontains"), Some("WEB"));
+    assert_eq!(request.query_value("sort"), Some("name.desc"));
+    assert_eq!(request.query_value("limit"), Some("1"));
+}
+
+#[test]
+fn objects_can_be_created_modified_shown_and_deleted() {
+    let harness = Harness::new().with_fixture("inventory.json");
+
+    harness
+        .cli()
+        .args([
+            "object",
+            "create",
+            "--class",
+            "Hosts",
+            "--name",
+            "cache-01",
+            "--collection",
+            "prod",
+            "--description",
+            "Redis",
+            "--data",
+            r#"{"os":"linux"}"#,
+        ])
+        .assert()
+        .success()
+        .stdout(contains("cache-01"));
+    harness
+        .cli()
+        .args([
+            "object",
+            "modify",
+            "--class",
+            "Hosts",
+            "--name",
+            "cache-01",
+            "--description",
+            "Valkey",
+        ])
+        .assert()
+        .success();
+
+    let output = harness
+        .cli()
+        .args(["object", "show", "--class", "Hosts", "cache-01", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let object = json_output(&output);
+    assert_eq!(object["description"], "Valkey");
+    assert_eq!(object["data"], json!({ "os": "linux" }));
+
+    harness
+        .cli()
+        .args(["object", "delete", "--class", "Hosts", "--name", "cache-01"])
+        .assert()
+        .success()
+        .stdout(contains("deleted"));
+    assert!(harness
+        .server
+        .entities(Resource::Objects)
+        .iter()
+        .all(|object| object["name"] != "cache-01"));
+}
+
+#[test]
+fn classes_and_collections_round_trip() {
+    let harness = Harness::new().with_fixture("inventory.json");
+
+    harness
+        .cli()
+        .args([
+            "collection",
+            "create",
+            "--name",
+            "edge",
+            "--description",
+            "Edge",
+        ])
+        .args(["--owner", "ops"])
+        .assert()
+        .success();
+    harness
+        .cli()
+        .args([
+            "class",
+            "create",
+            "--name",
+            "Routers",
+            "--collection",
+            "edge",
+        ])
+        .args(["--description", "Edge routers"])
+        .assert()
+        .success();
+    harness
+        .cli()
+        .args(["class", "show", "Routers"])
+        .assert()
+        .success()
+        .stdout(contains("Edge routers"))
+        .stdout(contains("edge"));
+
+    let classes = harness.server.entities(Resource::Classes);
+    let routers = classes
+        .iter()
+        .find(|class| class["name"] == "Routers")
+        .expect("the class should be stored");
+    assert_eq!(routers["collection_id"], 3);
+}
+
+#[test]
+fn group_membership_changes_reach_the_server() {
+    let harness = Harness::new().with_fixture("inventory.json");
+
+    harness
+        .cli()
+        .args([
+            "group",
+            "add_user",
+            "--groupname",
+            "ops",
+            "--username",
+            "alice",
+        ])
+        .assert()
+        .success();
+    harness
+        .cli()
+        .args(["group", "show", "--groupname", "ops"])
+        .assert()
+        .success()
+        .stdout(contains("alice"));
+    harness
+        .cli()
+        .args([
+            "group",
+            "remove_user",
+            "--groupname",
+            "ops",
+            "--username",
+            "alice",
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(
+        harness
+            .server
+            .requests_to("DELETE", "/api/v1/iam/groups/1/members/1")
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn group_lists_narrow_to_the_groups_of_a_member() {
+    let harness = Harness::new().with_fixture("inventory.json");
+    let admins = harness.server.add_group("admins");
+    harness.server.add_group("dev");
+    harness.server.add_member(1, 1);
+    harness.server.add_member(admins, 1);
+
+    let output = harness
+        .cli()
+        .args(["group", "list", "--member", "alice", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let groups = json_output(&output);
+    let names = groups
+        .as_array()
+        .expect("groups should be listed")
+        .iter()
+        .map(|group| group["groupname"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(names, [json!("ops"), json!("admins")]);
+
+    let listing = harness.server.requests_to("GET", "/api/v1/iam/groups");
+    let request = listing.last().expect("groups should be listed");
+    assert_eq!(request.query_value("id__equals"), Some("1,2"));
+}
+
+#[test]
+fn canned_server_errors_are_reported() {
+    let harness = Harness::new().with_fixture("inventory.json");
+    harness.server.respond(
+        "GET",
+        "/api/v1/collections",
+        FakeResponse::error(500, "database unavailable"),
+    );
+
+    harness
+        .cli()
+        .args(["collection", "list"])
+        .assert()
+        .failure()
+        .stdout(contains("database unavailable"));
+}
+
+#[test]
+fn conflicts_name_the_failed_operation() {
+    let harness = Harness::new().with_fixture("inventory.json");
+
+    harness
+        .cli()
+        .args(["class", "create", "--name", "Hosts", "--collection", "prod"])
+        .args(["--description", "Duplicate"])
+        .assert()
+        .failure()
+        .stdout(contains("creating class 'Hosts'"))
+        .stdout(contains("already exists"));
+}
+
+#[test]
+fn unattended_logins_take_the_password_from_the_environment() {
+    let harness = Harness::new().with_fixture("inventory.json");
+
+    harness
+        .cli_without_credentials()
+        .args(["--username", USERNAME])
+        .env("HUBUUM_CLI__SERVER__PASSWORD", PASSWORD)
+        .args(["collection", "list"])
+        .write_stdin("")
+        .assert()
+        .success()
+        .stdout(contains("prod"));
+
+    assert_password_login(&harness);
+}
+
+#[test]
+fn unattended_logins_take_the_password_from_the_config_file() {
+    let harness = Harness::new().with_fixture("inventory.json");
+    let config = harness.home().join("ci.toml");
+    write(
+        &config,
+        format!("[server]\nusername = \"{USERNAME}\"\npassword = \"{PASSWORD}\"\n"),
+    )
+    .expect("config should be written");
+
+    harness
+        .cli_without_credentials()
+        .args(["--config", config.to_str().expect("UTF-8 path")])
+        .args(["class", "list"])
+        .write_stdin("")
+        .assert()
+        .success()
+        .stdout(contains("Hosts"));
+
+    assert_password_login(&harness);
+}
+
+#[test]
+fn repeated_listings_are_answered_from_the_response_cache() {
+    let harness = Harness::new().with_fixture("inventory.json");
+
+    harness
+        .cli()
+        .args(["--script", "-"])
+        .write_stdin(
+            "class list\n\
+             class list\n\
+             cache clear\n\
+             class list\n\
+             class create --name Routers --collection lab --description Routers\n\
+             class list\n",
+        )
+        .assert()
+        .success()
+        .stdout(contains("Cleared 1 cached listing"))
+        .stdout(contains("Routers"));
+
+    assert_eq!(
+        harness.server.requests_to("GET", "/api/v1/classes").len(),
+        3
+    );
+}
+
+#[test]
+fn applies_to_a_production_profile_need_the_profile_name() {
+    let harness = Harness::new().with_fixture("inventory.json");
+    let config = harness.home().join("profiles.toml");
+    write(&config, "[profiles.live]\nproduction = true\n").expect("config should be written");
+    let desired = harness.home().join("desired.json");
+    write(
+        &desired,
+        json!({
+            "objects": [
+                { "name": "web-03", "class": "Hosts", "collection": "prod" }
+            ]
+        })
+        .to_string(),
+    )
+    .expect("desired state should be written");
+    let config = config.to_str().expect("UTF-8 path");
+    let desired = desired.to_str().expect("UTF-8 path");
+    let args = [
+        "--config",
+        config,
+        "--profile",
+        "live",
+        "apply",
+        "--yes",
+        "--file",
+        desired,
+    ];
+
+    harness
+        .cli()
+        .args(args)
+        .write_stdin("live\n")
+        .assert()
+        .failure()
+        .stdout(contains("needs an interactive terminal"));
+
+    let (applied, output) = harness.cli_on_terminal(&args, "yes\n");
+    assert!(!applied, "the wrong name should not confirm: {output}");
+    assert!(output.contains("object       1"), "{output}");
+    assert!(output.contains("Type the profile name"), "{output}");
+    assert!(harness
+        .server
+        .requests_to("POST", "/api/v1/classes/1/")
+        .is_empty());
+
+    let (applied, output) = harness.cli_on_terminal(&args, "live\n");
+    assert!(applied, "{output}");
+    assert!(output.contains("Applied: 1 to create"), "{output}");
+    assert_eq!(
+        harness
+            .server
+            .requests_to("POST", "/api/v1/classes/1/")
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn object_data_past_the_depth_limit_is_refused() {
+    let harness = Harness::new().with_fixture("inventory.json");
+    let create = [
+        "object",
+        "create",
+        "--class",
+        "Hosts",
+        "--name",
+        "cache-01",
+        "--collection",
+        "prod",
+        "--description",
+        "Redis",
+        "--data",
+        r#"{"net":{"eth0":{"ip":"10.0.0.1"}}}"#,
+    ];
+
+    harness
+        .cli()
+        .env("HUBUUM_CLI__DATA__MAX_DEPTH", "2")
+        .args(create)
+        .assert()
+        .failure()
+        .stdout(contains("nests 3 levels deep"));
+    assert!(harness
+        .server
+        .requests_to("POST", "/api/v1/classes/1/")
+        .is_empty());
+
+    harness
+        .cli()
+        .env("HUBUUM_CLI__DATA__MAX_DEPTH", "2")
+        .env("HUBUUM_CLI__DATA__LIMIT_ACTION", "warn")
+        .args(create)
+        .assert()
+        .success()
+        .stdout(contains("cache-01"));
+}
+
+#[test]
+fn deletions_match_names_exactly_and_check_the_resolved_collection() {
+    let harness = Harness::new().with_fixture("inventory.json");
+    let delete = |args: &[&str]| {
+        let mut command = harness.cli();
+        command
+            .env("HUBUUM_CLI__SAFETY__PROTECTED_COLLECTIONS", "prod")
+            .env("HUBUUM_CLI__LOOKUP__NAME_MATCH", "ci")
+            .args(args)
+            .write_stdin("");
+        command.assert()
+    };
+
+    delete(&["collection", "delete", "#1"])
+        .failure()
+        .stdout(contains(
+            "protected collection 'prod' needs an interactive terminal",
+        ));
+    delete(&["collection", "delete", "PROD"])
+        .failure()
+        .stdout(contains("collection 'PROD'"));
+    delete(&["object", "delete", "-c", "hosts", "-n", "web-01"])
+        .failure()
+        .stdout(contains("class 'hosts'"));
+    assert!(harness
+        .server
+        .requests()
+        .iter()
+        .all(|request| request.method() != "DELETE"));
+
+    harness
+        .cli()
+        .env("HUBUUM_CLI__SAFETY__PROTECTED_COLLECTIONS", "prod")
+        .args(["collection", "delete", "lab"])
+        .assert()
+        .success();
+}
+
+/// A grant of every permission to the `ops` group of the inventory fixture.
+fn ops_grant(collection_id: i64) -> Value {
+    let mut permission = json!({
+        "id": collection_id,
+        "collection_id": collection_id,
+        "group_id": 1,
+        "created_at": "2026-01-01T00:00:00Z",
+        "updated_at": "2026-01-01T00:00:00Z",
+    });
+    for kind in ["class", "object", "class_relation", "object_relation"] {
+        for verb in ["create", "read", "update", "delete"] {
+            permission[format!("has_{verb}_{kind}")] = json!(true);
+        }
+    }
+    for verb in ["read", "update", "delete", "delegate"] {
+        permission[format!("has_{verb}_collection")] = json!(true);
+    }
+    json!({
+        "group": {
+            "id": 1,
+            "groupname": "ops",
+            "description": "",
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z",
+        },
+        "permission": permission,
+    })
+}
+
+#[test]
+fn protected_collections_guard_relations_prunes_revokes_and_memberships() {
+    let harness = Harness::new().with_fixture("inventory.json");
+    let server = &harness.server;
+    server.add_member(1, 1);
+    server.respond(
+        "GET",
+        "/api/v1/collections/1/permissions",
+        FakeResponse::json(200, json!([ops_grant(1)])),
+    );
+    server.respond(
+        "GET",
+        "/api/v1/collections/2/permissions",
+        FakeResponse::json(200, json!([])),
+    );
+    let desired = harness.home().join("desired.json");
+    write(
+        &desired,
+        json!({
+            "classes": [{ "name": "Hosts", "collection": "prod" }],
+            "objects": [{ "name": "web-01", "class": "Hosts", "collection": "prod" }]
+        })
+        .to_string(),
+    )
+    .expect("desired state should be written");
+    let guarded = |args: &[&str]| {
+        let mut command = harness.cli();
+        command
+            .env("HUBUUM_CLI__SAFETY__PROTECTED_COLLECTIONS", "prod")
+            .args(args)
+            .write_stdin("prod\n");
+        command
+            .assert()
+            .failure()
+            .stdout(contains("protected collection 'prod'"));
+    };
+
+    guarded(&[
+        "relation",
+        "class",
+        "delete",
+        "--class-a",
+        "Hosts",
+        "--class-b",
+        "Switches",
+    ]);
+    guarded(&["group", "remove_user", "-g", "ops", "-u", "alice"]);
+    guarded(&[
+        "collection",
+        "permissions",
+        "set",
+        "prod",
+        "--group",
+        "ops",
+        "--ReadCollection",
+        "--exact",
+        "--yes",
+    ]);
+    guarded(&[
+        "apply",
+        "--prune",
+        "--yes",
+        "--file",
+        desired.to_str().expect("UTF-8 path"),
+    ]);
+
+    assert!(server
+        .requests()
+        .iter()
+        .all(|request| request.method() == "GET" || request.path() == "/api/v0/auth/login"));
+    assert_eq!(server.entities(Resource::Objects).len(), 4);
+}
+
+#[test]
+fn long_help_lists_fixed_choices_without_asking_the_server() {
+    let harness = Harness::new().with_fixture("inventory.json");
+
+    harness
+        .cli()
+        .args(["--script", "-"])
+        .write_stdin("class list\nhelp object list --long\n")
+        .assert()
+        .success()
+        .stdout(contains("SYNOPSIS"))
+        .stdout(contains("Choices: text, json, jsonl, csv, tsv"));
+
+    assert_eq!(
+        harness.server.requests_to("GET", "/api/v1/classes").len(),
+        1
+    );
+}
+
+#[test]
+fn offline_mode_answers_listings_from_the_cache_and_refuses_changes() {
+    let harness = Harness::new().with_fixture("inventory.json");
+
+    harness
+        .cli()
+        .args(["--keep-going", "--script", "-"])
+        .write_stdin(
+            "class list\noffline on\nclass list\nclass create --name Racks\ngroup list\n\
+             auth providers\nlogin alice\n",
+        )
+        .assert()
+        .failure()
+        .stdout(contains("Offline: showing the listing cached"))
+        .stdout(contains(
+            "'class create' changes the server and was not run",
+        ))
+        .stdout(contains("Offline: this needs the server"))
+        .stdout(contains(
+            "Offline: listing authentication providers needs the server",
+        ))
+        .stdout(contains("Offline: logging in needs the server"));
+
+    assert_eq!(
+        harness.server.requests_to("GET", "/api/v1/classes").len(),
+        1
+    );
+    assert!(harness
+        .server
+        .requests_to("GET", "/api/v0/auth/providers")
+        .is_empty());
+    assert_eq!(
+        harness
+            .server
+            .requests_to("POST", "/api/v0/auth/login")
+            .len(),
+        1
+    );
+    assert!(harness
+        .server
+        .requests_to("POST", "/api/v1/classes")
+        .is_empty());
+    assert!(harness
+        .server
+        .requests_to("GET", "/api/v1/iam/groups")
+        .is_empty());
+}
+
+#[test]
+fn starting_offline_shows_listings_saved_by_earlier_sessions() {
+    let harness = Harness::new().with_fixture("inventory.json");
+
+    harness
+        .cli()
+        .args(["--offline", "class", "list"])
+        .assert()
+        .failure()
+        .stdout(contains("Offline: this needs the server"));
+    assert!(harness.server.requests().is_empty());
+
+    harness.cli().args(["class", "list"]).assert().success();
+    let sent = harness.server.requests().len();
+
+    harness
+        .cli()
+        .args(["--offline", "class", "list"])
+        .assert()
+        .success()
+        .stdout(contains("Switches"))
+        .stdout(contains("Offline: showing the listing cached"));
+    harness
+        .cli()
+        .args(["--offline", "group", "list"])
+        .assert()
+        .failure()
+        .stdout(contains("Offline: this needs the server"));
+    assert_eq!(harness.server.requests().len(), sent);
+}
+
+#[test]
+fn object_import_creates_every_row_and_reports_the_failures() {
+    let harness = Harness::new().with_fixture("inventory.json");
+    let hosts = harness.home().join("hosts.csv");
+    write(
+        &hosts,
+        "name,description,data.os,cpus\nweb-03,New web,linux,2\nweb-01,Taken,linux,8\n",
+    )
+    .expect("import file should be written");
+    let retry_file = harness.home().join("failed.json");
+
+    let output = harness
+        .cli()
+        .args(["object", "import", "--class", "Hosts", "--output", "json"])
+        .args(["--file", hosts.to_str().expect("UTF-8 path")])
+        .args(["--retry-file", retry_file.to_str().expect("UTF-8 path")])
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let [write_ahead, summary] = &json_documents(&output)[..] else {
+        panic!("expected a write-ahead summary and a result");
+    };
+    assert_eq!(write_ahead, &json!([{ "type": "object", "create": 2 }]));
+    assert_eq!(summary["created"], 1);
+    assert_eq!(summary["failed"][0]["name"], "create object Hosts/web-01");
+    let retry: Value = serde_json::from_str(
+        &std::fs::read_to_string(&retry_file).expect("retry file should be written"),
+    )
+    .expect("retry file should be JSON");
+    assert_eq!(retry["items"][0]["payload"]["name"], "web-01");
+    assert_eq!(retry["items"][0]["payload"]["collection"], "prod");
+
+    let created = harness
+        .server
+        .requests()
+        .into_iter()
+        .filter_map(|request| request.body().cloned())
+        .find(|body| body["name"] == "web-03")
+        .expect("web-03 should be created");
+    assert_eq!(created["description"], "New web");
+    assert_eq!(created["data"], json!({"os": "linux", "cpus": "2"}));
+}
+
+#[test]
+fn object_import_resumes_after_the_objects_it_already_created() {
+    let harness = Harness::new().with_fixture("inventory.json");
+    let hosts = harness.home().join("hosts.csv");
+    let checkpoint = harness.home().join("hosts.csv.checkpoint");
+    let retry_file = harness.home().join("failed.json");
+    let import = |resume: bool| {
+        let mut command = harness.cli();
+        command
+            .args(["object", "import", "--class", "Hosts", "--output", "json"])
+            .args(["--file", hosts.to_str().expect("UTF-8 path")])
+            .args(["--retry-file", retry_file.to_str().expect("UTF-8 path")]);
+        if resume {
+            command.arg("--resume");
+        }
+        command.assert()
+    };
+    let posted = || {
+        harness
+            .server
+            .requests()
+            .into_iter()
+            .filter(|request| request.method() == "POST")
+            .filter_map(|request| request.body().cloned())
+            .filter_map(|body| body["name"].as_str().map(str::to_string))
+            .collect::<Vec<_>>()
+    };
+
+    write(&hosts, "name\nweb-03\nweb-01\nweb-04\n").expect("import file should be written");
+    import(false).failure();
+    let recorded = std::fs::read_to_string(&checkpoint).expect("checkpoint should be kept");
+    assert!(recorded.contains("Hosts/web-03") && recorded.contains("Hosts/web-04"));
+    assert!(!recorded.contains("Hosts/web-01"));
+
+    write(&hosts, "name\nweb-03\nweb-04\nweb-05\n").expect("import file should be rewritten");
+    let before = posted().len();
+    let output = import(true).success().get_output().stdout.clone();
+
+    let summary = json_documents(&output)
+        .pop()
+        .expect("summary should be printed");
+    assert_eq!(summary["created"], 1);
+    assert_eq!(summary["resumed"], 2);
+    assert_eq!(posted()[before..], ["web-05".to_string()]);
+    assert!(!checkpoint.exists());
+}
+
+#[test]
+fn object_validate_takes_the_class_and_name_positionally() {
+    let harness = Harness::new().with_fixture("inventory.json");
+    let class_id = harness.server.insert(
+        Resource::Classes,
+        json!({
+            "name": "Host",
+            "collection_id": 1,
+            "json_schema": {
+                "type": "object",
+                "properties": { "cpus": { "type": "integer" } }
+            }
+        }),
+    );
+    harness
+        .server
+        .add_object(class_id, "web1", json!({ "cpus": "eight" }));
+
+    let output = harness
+        .cli()
+        .args(["object", "validate", "Host", "web1", "--output", "json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let record = json_documents(&output)
+        .pop()
+        .expect("validation record should be printed");
+    assert_eq!(record["class"], "Host");
+    assert_eq!(record["object"], "web1");
+    assert_eq!(record["valid"], false);
+
+    harness
+        .cli()
+        .args(["object", "validate", "Hostz", "web1", "--class", "Host"])
+        .assert()
+        .failure()
+        .stdout(contains("the argument 'Hostz'"));
+}
+
+#[test]
+fn the_verbose_log_on_a_piped_stderr_has_no_colour_codes() {
+    let harness = Harness::new().with_fixture("inventory.json");
+
+    let output = harness
+        .cli()
+        .args(["-vv", "class", "list"])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+
+    let log = String::from_utf8_lossy(&output.stderr);
+    assert!(log.contains("DEBUG"), "{log}");
+    assert!(!log.contains('\x1b'), "{log}");
+}
+
+#[test]
+fn profile_doctor_reports_reachability_tokens_and_versions() {
+    let harness = Harness::new();
+    harness.server.respond(
+        "GET",
+        "/healthz",
+        FakeResponse::json(200, json!({"status": "ok"})),
+    );
+    harness.server.respond(
+        "GET",
+        "/api-doc/openapi.json",
+        FakeResponse::json(
+            200,
+            json!({"info": {"version": "0.0.3"}, "paths": {"/api/v1/classes": {}}}),
+        ),
+    );
+    harness.cli().args(["class", "list"]).assert().success();
+    let server = format!(
+        "hostname = \"{}\"\nport = {}\nprotocol = \"http\"\n",
+        harness.server.hostname(),
+        harness.server.port()
+    );
+    let config = harness.home().join("profiles.toml");
+    write(
+        &config,
+        format!(
+            "[profiles.current]\n{server}username = \"{USERNAME}\"\n\n\
+             [profiles.stranger]\n{server}username = \"bob\"\n\n\
+             [profiles.down]\nhostname = \"127.0.0.1\"\nport = 1\nprotocol = \"http\"\n"
+        ),
+    )
+    .expect("config should be written");
+    let config = config.to_str().expect("UTF-8 path");
+
+    let output = harness
+        .cli_without_credentials()
+        .args(["--config", config, "profile", "doctor", "--output", "json"])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let checks = json_documents(&output.stdout).remove(0);
+    let checks = checks.as_array().expect("one check per profile");
+    let names = checks
+        .iter()
+        .map(|check| check["profile"].as_str().unwrap_or_default())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["current", "down", "stranger"]);
+
+    assert_eq!(checks[0]["reachable"], true);
+    assert_eq!(checks[0]["token"], "valid");
+    assert_eq!(checks[0]["server_version"], "v0.0.3");
+    assert_eq!(checks[1]["reachable"], false);
+    assert_eq!(checks[1]["token"], "unchecked");
+    assert!(checks[1]["server_version"].is_null());
+    assert!(checks[1]["problem"]
+        .as_str()
+        .is_some_and(|problem| !problem.contains("retries exhausted")));
+    assert_eq!(checks[2]["reachable"], true);
+    assert_eq!(checks[2]["token"], "missing");
+
+    harness
+        .cli_without_credentials()
+        .args(["--config", config, "profile", "doctor"])
+        .assert()
+        .success()
+        .stdout(contains("Reachable"))
+        .stdout(contains("valid"))
+        .stdout(contains("missing"));
+}
+
+#[test]
+fn object_watch_field_refuses_pipelines_and_redirects() {
+    let harness = Harness::new().with_fixture("inventory.json");
+    let target = harness.home().join("watch.txt");
+    let watch = [
+        "object",
+        "watch-field",
+        "--class",
+        "Hosts",
+        "--name",
+        "web-01",
+        "--path",
+        "status",
+        "--timeout",
+        "0",
+    ];
+
+    harness
+        .cli()
+        .args(watch)
+        .args(["|", "head", "1"])
+        .assert()
+        .failure()
+        .stdout(contains("cannot be piped or redirected"));
+    harness
+        .cli()
+        .args(watch)
+        .args([">", target.to_str().expect("UTF-8 path")])
+        .assert()
+        .failure()
+        .stdout(contains("cannot be piped or redirected"));
+    assert!(!target.exists());
+}
+
+#[test]
+fn masked_keys_stay_hidden_in_every_object_output_without_reveal() {
+    let harness = Harness::new().with_fixture("inventory.json");
+    let config = harness.home().join("masked.toml");
+    write(&config, "[output]\nmask_keys = [\"password\"]\n").expect("config should be written");
+    let config = config.to_str().expect("UTF-8 path");
+    let data = r#"{"os": "linux", "password": "hunter2"}"#;
+    let printed = |args: &[&str]| {
+        let output = harness
+            .cli()
+            .args(["--config", config])
+            .args(args)
+            .assert()
+            .success()
+            .get_output()
+            .clone();
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    for (name, format) in [("vault-01", "json"), ("vault-02", "text")] {
+        let output = printed(&[
+            "object",
+            "create",
+            "--class",
+            "Hosts",
+            "--collection",
+            "prod",
+            "--name",
+            name,
+            "--description",
+            "Vault",
+            "--data",
+            data,
+            "--output",
+            format,
+        ]);
+        assert!(!output.contains("hunter2"), "{output}");
+    }
+    for args in [
+        &["class", "show", "Hosts", "--json"][..],
+        &[
+            "object",
+            "watch-field",
+            "--class",
+            "Hosts",
+            "--name",
+            "vault-01",
+            "--path",
+            "password",
+            "--timeout",
+            "0",
+        ],
+    ] {
+        let output = printed(args);
+        assert!(!output.contains("hunter2"), "{args:?}: {output}");
+        assert!(output.contains("***"), "{args:?}: {output}");
+    }
+
+    let output = printed(&[
+        "object", "show", "--class", "Hosts", "vault-01", "--json", "--reveal",
+    ]);
+    assert!(output.contains("hunter2"), "{output}");
+
+    let backup = harness.home().join("backup.json");
+    harness
+        .cli()
+        .args(["--config", config, "backup", "create", "--file"])
+        .arg(&backup)
+        .assert()
+        .failure()
+        .stdout(contains("--reveal"));
+    assert!(!backup.exists());
+}
@@ -0,0 +1,116 @@
+//! Shared setup for the end-to-end tests that drive the CLI against a fake server.
+
+use std::fs::read_to_string;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::thread::spawn;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use assert_cmd::Command;
+use hubuum_fake_server::{FakeServer, PASSWORD, USERNAME};
+use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use tempfile::TempDir;
+
+/// A fake server and a home directory of its own, so cached tokens and configuration
+/// never leak between tests or from the machine running them.
+pub struct Harness {
+    pub server: FakeServer,
+    home: TempDir,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Self {
+            server: FakeServer::start().expect("fake server should start"),
+            home: TempDir::new().expect("home directory should be created"),
+        }
+    }
+
+    /// Seeds the server from a fixture file under `tests/fixtures`.
+    pub fn with_fixture(self, name: &str) -> Self {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name);
+        let fixture = read_to_string(&path).expect("fixture should be readable");
+        self.server
+            .seed(&serde_json::from_str(&fixture).expect("fixture should be JSON"));
+        self
+    }
+
+    /// `hubuum-cli` logging in to the fake server, ready for command arguments.
+    pub fn cli(&self) -> Command {
+        let mut command = self.cli_without_credentials();
+        command.args(["--username", USERNAME, "--password", PASSWORD]);
+        command
+    }
+
+    /// `hubuum-cli` pointed at the fake server, for tests that log in another way.
+    pub fn cli_without_credentials(&self) -> Command {
+        let mut command = cargo_bin_cmd!("hubuum-cli");
+        command.envs(self.env()).args(self.server_args());
+        command
+    }
+
+    /// Runs `hubuum-cli` logged in to the fake server on a pseudo-terminal and types
+    /// `input`, for prompts that refuse to read from a pipe. Returns whether the command
+    /// succeeded and everything it printed.
+    pub fn cli_on_terminal(&self, args: &[&str], input: &str) -> (bool, String) {
+        let pty = native_pty_system()
+            .openpty(PtySize {
+                rows: 24,
+                cols: 200,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .expect("pseudo-terminal should open");
+        let mut command = CommandBuilder::new(env!("CARGO_BIN_EXE_hubuum-cli"));
+        for (key, value) in self.env() {
+            command.env(key, value);
+        }
+        command.args(self.server_args());
+        command.args(["--username", USERNAME, "--password", PASSWORD]);
+        command.args(args);
+        command.cwd(self.home());
+
+        let mut child = pty.slave.spawn_command(command).expect("CLI should start");
+        drop(pty.slave);
+        let mut reader = pty.master.try_clone_reader().expect("terminal reader");
+        let output = spawn(move || {
+            let mut output = Vec::new();
+            // Reading fails with EIO once the CLI exits and closes the terminal.
+            let _ = reader.read_to_end(&mut output);
+            String::from_utf8_lossy(&output).into_owned()
+        });
+        let mut writer = pty.master.take_writer().expect("terminal writer");
+        writer
+            .write_all(input.as_bytes())
+            .expect("input should be typed");
+        let status = child.wait().expect("CLI should exit");
+        drop(writer);
+        drop(pty.master);
+        (status.success(), output.join().expect("terminal output"))
+    }
+
+    pub fn home(&self) -> &Path {
+        self.home.path()
+    }
+
+    fn env(&self) -> [(&'static str, PathBuf); 3] {
+        [
+            ("HOME", self.home().to_path_buf()),
+            ("XDG_CONFIG_HOME", self.home().join("config")),
+            ("XDG_DATA_HOME", self.home().join("data")),
+        ]
+    }
+
+    fn server_args(&self) -> [String; 6] {
+        [
+            "--protocol".to_string(),
+            "http".to_string(),
+            "--hostname".to_string(),
+            self.server.hostname(),
+            "--port".to_string(),
+            self.server.port().to_string(),
+        ]
+    }
+}